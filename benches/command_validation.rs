@@ -0,0 +1,101 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use gitalky::security::{CommandOrigin, CommandValidator, Workspace};
+
+// Commands the validator accepts outright
+const SAFE_COMMANDS: &[&str] = &[
+    "git status",
+    "git log --oneline -10",
+    "git diff --cached",
+    "git add src/ui/app.rs",
+    "git commit -m 'Fix the refresh scheduler'",
+    "git checkout -b feature/live-suggest",
+    "git push -u origin feature/live-suggest",
+    "git stash push -m 'wip'",
+    "git show HEAD~2:src/main.rs",
+    "git log --author=alice --since=2.weeks --format='%h %s'",
+];
+
+// Accepted, but flagged for confirmation
+const DANGEROUS_COMMANDS: &[&str] = &[
+    "git push --force origin main",
+    "git reset --hard HEAD~3",
+    "git clean -fdx",
+    "git branch -D old-feature",
+    "git rebase -i HEAD~5",
+];
+
+// Rejected: shell operators, disallowed subcommands, bad flags
+const REJECTED_COMMANDS: &[&str] = &[
+    "git status; rm -rf /",
+    "git log | grep fix",
+    "git diff > changes.patch",
+    "git commit -m \"$(whoami)\"",
+    "git filter-branch --tree-filter 'rm secrets' HEAD",
+    "git -c core.pager='sh -c id' log",
+    "rm -rf .git",
+    "git clone https://example.com/repo.git /etc/repo",
+];
+
+fn corpus() -> Vec<&'static str> {
+    SAFE_COMMANDS
+        .iter()
+        .chain(DANGEROUS_COMMANDS)
+        .chain(REJECTED_COMMANDS)
+        .copied()
+        .collect()
+}
+
+fn validator() -> CommandValidator {
+    CommandValidator::new().with_workspace(Workspace::new("/home/me/src/gitalky", Vec::new()))
+}
+
+fn bench_validate_by_kind(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate");
+    let validator = validator();
+
+    for (kind, commands) in [
+        ("safe", SAFE_COMMANDS),
+        ("dangerous", DANGEROUS_COMMANDS),
+        ("rejected", REJECTED_COMMANDS),
+    ] {
+        group.bench_with_input(
+            BenchmarkId::new(kind, format!("{} commands", commands.len())),
+            commands,
+            |b, commands| {
+                b.iter(|| {
+                    for command in commands.iter() {
+                        let _ = black_box(validator.validate(black_box(command)));
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_validate_corpus(c: &mut Criterion) {
+    let mut group = c.benchmark_group("validate_corpus");
+    let validator = validator();
+    let commands = corpus();
+
+    // LLM output goes through the stricter origin checks
+    for (name, origin) in [("user", CommandOrigin::User), ("llm", CommandOrigin::Llm)] {
+        group.bench_with_input(
+            BenchmarkId::new(name, format!("{} commands", commands.len())),
+            &commands,
+            |b, commands| {
+                b.iter(|| {
+                    for command in commands {
+                        let _ = black_box(validator.validate_with_origin(black_box(command), origin));
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_validate_by_kind, bench_validate_corpus);
+criterion_main!(benches);
@@ -2,6 +2,10 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion, Benchmark
 use gitalky::git::parser::{
     parse_status_porcelain_v2, parse_log, parse_branch_list, parse_stash_list
 };
+use gitalky::git::{Repository, RepositoryState, StateSection};
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
 
 // Sample git outputs for realistic benchmarking
 const SMALL_STATUS: &str = r#"1 M. N... 100644 100644 100644 abc123 def456 README.md
@@ -95,6 +99,15 @@ fn bench_parse_status(c: &mut Criterion) {
         },
     );
 
+    let huge_status = generate_large_status(10_000);
+    group.bench_with_input(
+        BenchmarkId::new("huge", "10000 files"),
+        &huge_status,
+        |b, input| {
+            b.iter(|| parse_status_porcelain_v2(black_box(input)))
+        },
+    );
+
     group.finish();
 }
 
@@ -142,11 +155,82 @@ fn bench_parse_stash_list(c: &mut Criterion) {
     });
 }
 
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run git")
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Repository with `num_files` committed files across 50 commits, a tenth of
+/// them modified, some staged, some untracked files and a few stashes
+fn generate_large_repo(num_files: usize) -> TempDir {
+    let temp = TempDir::new().unwrap();
+    let dir = temp.path();
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "Bench"]);
+    git(dir, &["config", "user.email", "bench@example.com"]);
+
+    let batch = num_files.div_ceil(50);
+    for commit in 0..50 {
+        for i in commit * batch..((commit + 1) * batch).min(num_files) {
+            let sub = dir.join(format!("src/mod_{}", i % 40));
+            std::fs::create_dir_all(&sub).unwrap();
+            std::fs::write(sub.join(format!("file_{}.rs", i)), format!("fn f{}() {{}}\n", i)).unwrap();
+        }
+        git(dir, &["add", "-A"]);
+        git(dir, &["commit", "-q", "-m", &format!("Commit {}", commit)]);
+    }
+
+    for stash in 0..3 {
+        std::fs::write(dir.join("src/mod_0/file_0.rs"), format!("// stash {}\n", stash)).unwrap();
+        git(dir, &["stash", "-q"]);
+    }
+    for i in (0..num_files).step_by(10) {
+        let path = dir.join(format!("src/mod_{}/file_{}.rs", i % 40, i));
+        std::fs::write(path, format!("fn f{}() {{ /* changed */ }}\n", i)).unwrap();
+    }
+    git(dir, &["add", "src/mod_1"]);
+    for i in 0..num_files / 20 {
+        std::fs::write(dir.join(format!("untracked_{}.txt", i)), "new\n").unwrap();
+    }
+    temp
+}
+
+fn bench_repository_state(c: &mut Criterion) {
+    let mut group = c.benchmark_group("repository_state");
+    group.sample_size(20);
+
+    let repo_dir = generate_large_repo(5000);
+    let repo = Repository::new(repo_dir.path());
+
+    // Sections one after another, as `state()` does on a single core
+    group.bench_function(BenchmarkId::new("serial", "5000 files"), |b| {
+        b.iter(|| {
+            let mut state = RepositoryState::default();
+            for section in StateSection::ALL {
+                repo.load_section(&mut state, section).unwrap();
+            }
+            black_box(state)
+        })
+    });
+
+    group.bench_function(BenchmarkId::new("parallel", "5000 files"), |b| {
+        b.iter(|| black_box(repo.state_concurrently().unwrap()))
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_parse_status,
     bench_parse_log,
     bench_parse_branch_list,
-    bench_parse_stash_list
+    bench_parse_stash_list,
+    bench_repository_state
 );
 criterion_main!(benches);
@@ -1,5 +1,8 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use gitalky::llm::context::{ContextBuilder, RepoContext};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, BenchmarkId};
+use gitalky::git::parser::{CommitEntry, FileStatus, StashEntry, StatusEntry};
+use gitalky::git::{Repository, RepositoryState, StateCache, UpstreamInfo};
+use gitalky::llm::context::{ContextBuilder, QueryType, RepoContext};
+use std::process::Command;
 
 // Sample queries for classification benchmarking
 const QUERIES: &[&str] = &[
@@ -126,7 +129,81 @@ fn bench_context_full_string(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_escalated_context(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build_escalated_context");
+
+    // A real (empty) repository for the rename detection git calls; the
+    // state itself comes from the cache
+    let temp = tempfile::TempDir::new().unwrap();
+    Command::new("git").args(["init", "-q"]).current_dir(temp.path()).output().unwrap();
+    let repo = Repository::new(temp.path());
+    let cache = StateCache::new();
+    let builder = ContextBuilder::new(repo.clone()).with_state_cache(cache.clone());
+
+    for num_files in [100, 5000] {
+        let state = generate_large_state(num_files);
+        for query_type in [
+            QueryType::General,
+            QueryType::Commit,
+            QueryType::Branch,
+            QueryType::Diff,
+            QueryType::History,
+            QueryType::Stash,
+        ] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{:?}", query_type), format!("{} files", num_files)),
+                &query_type,
+                |b, query_type| {
+                    // Re-published per batch: cached states expire after a few seconds
+                    b.iter_batched(
+                        || cache.publish(repo.path(), state.clone()),
+                        |_| builder.build_escalated_context(black_box(query_type.clone())).unwrap(),
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
 // Helper functions to generate realistic test data
+fn generate_large_state(num_files: usize) -> RepositoryState {
+    let entry = |i: usize, status: FileStatus, staged: bool| StatusEntry {
+        status,
+        path: format!("src/module_{}/file_{}.rs", i / 50, i),
+        staged,
+        unstaged: !staged,
+    };
+    RepositoryState {
+        current_branch: Some("feature/large-change".to_string()),
+        upstream: Some(UpstreamInfo {
+            remote_branch: "origin/feature/large-change".to_string(),
+            ahead: 3,
+            behind: 1,
+        }),
+        staged_files: (0..num_files / 4).map(|i| entry(i, FileStatus::Modified, true)).collect(),
+        unstaged_files: (0..num_files / 2).map(|i| entry(i, FileStatus::Modified, false)).collect(),
+        untracked_files: (0..num_files / 4).map(|i| entry(i, FileStatus::Untracked, false)).collect(),
+        recent_commits: (0..10)
+            .map(|i| CommitEntry {
+                hash: format!("{:040x}", i),
+                message: format!("Commit message {}", i),
+                has_note: false,
+                signature: None,
+            })
+            .collect(),
+        stashes: (0..5)
+            .map(|i| StashEntry {
+                index: format!("stash@{{{}}}", i),
+                message: format!("WIP on main: {}", i),
+            })
+            .collect(),
+        ..RepositoryState::default()
+    }
+}
+
 fn generate_large_context(num_files: usize) -> String {
     let mut context = String::new();
     context.push_str("Current branch: main\n");
@@ -171,6 +248,7 @@ criterion_group!(
     benches,
     bench_query_classification,
     bench_token_estimation,
-    bench_context_full_string
+    bench_context_full_string,
+    bench_escalated_context
 );
 criterion_main!(benches);
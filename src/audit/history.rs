@@ -0,0 +1,360 @@
+use crate::audit::retention::read_all;
+use crate::security::CommandValidator;
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HistoryError {
+    #[error("Unknown option '{0}'")]
+    UnknownOption(String),
+
+    #[error("{0} needs a value")]
+    MissingValue(String),
+
+    #[error("Invalid --since value '{0}': use a date, an RFC 3339 time, or an age like 7d")]
+    InvalidSince(String),
+
+    #[error("Unknown format '{0}': use table or json")]
+    InvalidFormat(String),
+}
+
+/// How `gitalky history` prints entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HistoryFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// A command from the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HistoryEntry {
+    /// RFC 3339, as logged
+    pub timestamp: String,
+    pub user: String,
+    pub repo: PathBuf,
+    pub exit_code: i32,
+    /// Where the command came from (`user`, `llm`, ...), for entries that record it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    pub command: String,
+    pub dangerous: bool,
+}
+
+impl HistoryEntry {
+    /// Parse an executed-command line; translation timings, cancelled previews,
+    /// error kinds and validation records are skipped
+    pub fn parse(line: &str, validator: &CommandValidator) -> Option<Self> {
+        let (timestamp, rest) = bracketed(line)?;
+        let (user, rest) = bracketed(rest)?;
+        // The repo path runs up to the exit code
+        let rest = rest.strip_prefix('[')?;
+        let (repo, rest) = rest.split_once("] [exit:")?;
+        let (exit_code, message) = rest.split_once("] ")?;
+
+        if ["[translation]", "[preview-cancelled]", "[error]"]
+            .iter()
+            .any(|tag| message.starts_with(tag))
+        {
+            return None;
+        }
+        let (origin, command) = match message.strip_prefix("[origin:").and_then(|m| m.split_once("] ")) {
+            Some((origin, command)) => (Some(origin.to_string()), command),
+            None => (None, message),
+        };
+        let dangerous = command.starts_with("[lock-removed]")
+            || command.starts_with("[approval-")
+            || (!command.starts_with("[shell-session]")
+                && validator.validate(command).is_ok_and(|validated| validated.is_dangerous));
+
+        Some(Self {
+            timestamp: timestamp.to_string(),
+            user: user.to_string(),
+            repo: PathBuf::from(repo),
+            exit_code: exit_code.parse().ok()?,
+            origin,
+            command: command.to_string(),
+            dangerous,
+        })
+    }
+
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.timestamp)
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+}
+
+/// `[value] rest` → (value, rest)
+fn bracketed(text: &str) -> Option<(&str, &str)> {
+    let (value, rest) = text.strip_prefix('[')?.split_once("] ")?;
+    Some((value, rest))
+}
+
+/// Filters and output format given to `gitalky history`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistoryQuery {
+    pub repo: Option<PathBuf>,
+    pub since: Option<DateTime<Utc>>,
+    pub failed_only: bool,
+    pub dangerous_only: bool,
+    pub format: HistoryFormat,
+}
+
+impl HistoryQuery {
+    /// Parse the arguments after `history`
+    pub fn parse<I: IntoIterator<Item = String>>(args: I, now: DateTime<Utc>) -> Result<Self, HistoryError> {
+        let mut query = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| HistoryError::MissingValue(flag.clone()))
+            };
+            match flag.as_str() {
+                "--repo" => query.repo = Some(repo_filter(PathBuf::from(value()?))),
+                "--since" => query.since = Some(parse_since(&value()?, now)?),
+                "--format" => {
+                    query.format = match value()?.as_str() {
+                        "table" => HistoryFormat::Table,
+                        "json" => HistoryFormat::Json,
+                        other => return Err(HistoryError::InvalidFormat(other.to_string())),
+                    }
+                }
+                "--failed-only" => query.failed_only = true,
+                "--dangerous-only" => query.dangerous_only = true,
+                _ => return Err(HistoryError::UnknownOption(arg)),
+            }
+        }
+        Ok(query)
+    }
+
+    pub fn matches(&self, entry: &HistoryEntry) -> bool {
+        self.repo.as_ref().is_none_or(|repo| entry.repo.starts_with(repo))
+            && self.since.is_none_or(|since| entry.time().is_some_and(|time| time >= since))
+            && (!self.failed_only || entry.exit_code != 0)
+            && (!self.dangerous_only || entry.dangerous)
+    }
+
+    /// Matching entries from the audit log at `path` and its rotated backups, oldest first
+    pub fn run(&self, path: &Path) -> std::io::Result<Vec<HistoryEntry>> {
+        let text = match read_all(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let validator = CommandValidator::new();
+        Ok(text
+            .lines()
+            .filter_map(|line| HistoryEntry::parse(line, &validator))
+            .filter(|entry| self.matches(entry))
+            .collect())
+    }
+}
+
+/// `path` as the audit log records repositories: canonical, when it exists
+pub fn repo_filter(path: PathBuf) -> PathBuf {
+    fs::canonicalize(&path).unwrap_or(path)
+}
+
+/// A date, an RFC 3339 time, or an age counted back from `now`
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, HistoryError> {
+    let invalid = || HistoryError::InvalidSince(value.to_string());
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(invalid)?;
+        return Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+            .ok_or_else(invalid);
+    }
+
+    let unit_at = value.len().checked_sub(1).ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(unit_at);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let age = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => None,
+    };
+    age.and_then(|age| now.checked_sub_signed(age)).ok_or_else(invalid)
+}
+
+/// Aligned columns: time, exit code, repository, command
+pub fn render_table(entries: &[HistoryEntry]) -> String {
+    let rows: Vec<[String; 4]> = entries
+        .iter()
+        .map(|entry| {
+            let time = entry
+                .time()
+                .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| entry.timestamp.clone());
+            let command = if entry.dangerous {
+                format!("{} (dangerous)", entry.command)
+            } else {
+                entry.command.clone()
+            };
+            [time, entry.exit_code.to_string(), entry.repo.display().to_string(), command]
+        })
+        .collect();
+
+    let header = ["TIME", "EXIT", "REPO", "COMMAND"].map(str::to_string);
+    let widths: Vec<usize> = (0..3)
+        .map(|col| {
+            rows.iter()
+                .chain(std::iter::once(&header))
+                .map(|row| row[col].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut out = String::new();
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        out.push_str(&format!(
+            "{:<w0$}  {:>w1$}  {:<w2$}  {}\n",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        ));
+    }
+    out
+}
+
+pub fn render_json(entries: &[HistoryEntry]) -> String {
+    serde_json::to_string_pretty(entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+[2026-01-01T10:00:00Z] [me] [/work/app] [exit:0] [translation] 800ms
+[2026-01-01T10:00:01Z] [me] [/work/app] [exit:0] [origin:llm] git status
+[2026-01-02T10:00:00Z] [me] [/work/app] [exit:0] [preview-cancelled] [origin:llm] git push --force
+[2026-01-03T10:00:00Z] [me] [/work/app] [exit:128] [origin:user] git push --force
+[2026-01-03T10:00:01Z] [me] [/work/app] [exit:128] [error] No upstream branch
+[2026-01-04T10:00:00Z] [me] [/home/me/notes] [exit:0] [shell-session] /bin/zsh (12s)
+[2026-01-05T10:00:00Z] [me] [/home/me/notes] [exit:1] git commit -m 'wip'
+[2026-01-05T10:00:01Z] [me] [/home/me/notes] [VALIDATION-REJECTED] query=\"x\" llm_output=\"rm\" reason=\"y\"
+";
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-06T10:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn query(args: &[&str]) -> HistoryQuery {
+        HistoryQuery::parse(args.iter().map(|arg| arg.to_string()), now()).unwrap()
+    }
+
+    fn commands(query: &HistoryQuery) -> Vec<String> {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("history.log");
+        fs::write(&path, LOG).unwrap();
+        query.run(&path).unwrap().into_iter().map(|entry| entry.command).collect()
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let validator = CommandValidator::new();
+        let entry = HistoryEntry::parse(
+            "[2026-01-03T10:00:00Z] [me] [/work/my app] [exit:128] [origin:user] git push --force",
+            &validator,
+        )
+        .unwrap();
+        assert_eq!(entry.repo, PathBuf::from("/work/my app"));
+        assert_eq!(entry.exit_code, 128);
+        assert_eq!(entry.origin.as_deref(), Some("user"));
+        assert_eq!(entry.command, "git push --force");
+        assert!(entry.dangerous);
+
+        let entry = HistoryEntry::parse("[t] [me] [/r] [exit:0] [lock-removed] /r/.git/index.lock (900s old)", &validator)
+            .unwrap();
+        assert!(entry.dangerous);
+        let entry = HistoryEntry::parse("[t] [me] [/r] [exit:1] [approval-denied] [approver:alice] git gc", &validator)
+            .unwrap();
+        assert!(entry.dangerous);
+
+        assert!(HistoryEntry::parse("[t] [me] [/r] [exit:0] [translation] 5ms", &validator).is_none());
+        assert!(HistoryEntry::parse("not a log line", &validator).is_none());
+    }
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(
+            commands(&query(&[])),
+            vec!["git status", "git push --force", "[shell-session] /bin/zsh (12s)", "git commit -m 'wip'"]
+        );
+        assert_eq!(commands(&query(&["--repo", "/work"])), vec!["git status", "git push --force"]);
+        assert_eq!(commands(&query(&["--failed-only"])), vec!["git push --force", "git commit -m 'wip'"]);
+        assert_eq!(commands(&query(&["--dangerous-only"])), vec!["git push --force"]);
+        assert_eq!(
+            commands(&query(&["--since=3d", "--failed-only"])),
+            vec!["git push --force", "git commit -m 'wip'"]
+        );
+        assert_eq!(commands(&query(&["--since", "2026-01-05T00:00:00Z"])), vec!["git commit -m 'wip'"]);
+    }
+
+    #[test]
+    fn test_parse_arguments() {
+        assert_eq!(query(&["--format", "json"]).format, HistoryFormat::Json);
+        assert_eq!(
+            HistoryQuery::parse(vec!["--format".to_string(), "csv".to_string()], now()),
+            Err(HistoryError::InvalidFormat("csv".to_string()))
+        );
+        assert_eq!(
+            HistoryQuery::parse(vec!["--since".to_string()], now()),
+            Err(HistoryError::MissingValue("--since".to_string()))
+        );
+        assert_eq!(
+            HistoryQuery::parse(vec!["--all".to_string()], now()),
+            Err(HistoryError::UnknownOption("--all".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(parse_since("2d", now()).unwrap(), now() - Duration::days(2));
+        assert_eq!(parse_since("90m", now()).unwrap(), now() - Duration::minutes(90));
+        assert!(parse_since("2026-01-01", now()).unwrap() < now());
+        assert!(parse_since("yesterday", now()).is_err());
+        assert!(parse_since("", now()).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        let entries = query(&["--repo", "/home/me/notes"]).run(Path::new("/nonexistent/history.log")).unwrap();
+        assert!(entries.is_empty());
+
+        let validator = CommandValidator::new();
+        let entry = HistoryEntry::parse("[2026-01-03T10:00:00Z] [me] [/r] [exit:0] git reset --hard", &validator).unwrap();
+        let table = render_table(std::slice::from_ref(&entry));
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("TIME"));
+        assert!(lines[1].ends_with("  0  /r    git reset --hard (dangerous)"));
+
+        let json: serde_json::Value = serde_json::from_str(&render_json(&[entry])).unwrap();
+        assert_eq!(json[0]["command"], "git reset --hard");
+        assert_eq!(json[0]["dangerous"], true);
+        assert!(json[0].get("origin").is_none());
+    }
+}
@@ -1,12 +1,19 @@
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use chrono::Utc;
+use crate::audit::retention::RetentionPolicy;
+use crate::audit::sink::{AuditRecord, AuditSink, open_sinks};
+use crate::config::Config;
+use crate::hooks::ApprovalDecision;
+use crate::security::CommandOrigin;
 
 const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024; // 10MB
 
 pub struct AuditLogger {
     log_path: PathBuf,
+    retention: RetentionPolicy,
+    /// Where every record is copied after it is written to the log
+    sinks: Vec<Box<dyn AuditSink>>,
 }
 
 impl AuditLogger {
@@ -19,7 +26,11 @@ impl AuditLogger {
             fs::create_dir_all(parent)?;
         }
 
-        Ok(Self { log_path })
+        Ok(Self {
+            log_path,
+            retention: RetentionPolicy::default(),
+            sinks: Vec::new(),
+        })
     }
 
     /// Create an AuditLogger with a custom log path
@@ -31,7 +42,30 @@ impl AuditLogger {
             fs::create_dir_all(parent)?;
         }
 
-        Ok(Self { log_path })
+        Ok(Self {
+            log_path,
+            retention: RetentionPolicy::default(),
+            sinks: Vec::new(),
+        })
+    }
+
+    /// Keep rotated logs as `policy` says instead of the default
+    pub fn with_retention(mut self, policy: RetentionPolicy) -> Self {
+        self.retention = policy;
+        self
+    }
+
+    /// Also send every record to `sinks`
+    pub fn with_sinks(mut self, sinks: Vec<Box<dyn AuditSink>>) -> Self {
+        self.sinks = sinks;
+        self
+    }
+
+    /// The default log with the retention policy and sinks from `config`
+    pub fn for_config(config: &Config) -> std::io::Result<Self> {
+        let logger = Self::new()?.with_retention(config.audit.clone());
+        let sinks = open_sinks(&config.audit_sinks, &config.network, &logger.log_path);
+        Ok(logger.with_sinks(sinks))
     }
 
     /// Get the default log path: ~/.config/gitalky/history.log
@@ -55,27 +89,107 @@ impl AuditLogger {
         repo_path: &Path,
         exit_code: i32,
     ) -> std::io::Result<()> {
-        // Check and rotate log if needed
-        self.rotate_if_needed()?;
+        self.write(AuditRecord::new(repo_path, Some(exit_code), command.to_string()))
+    }
 
-        let timestamp = Utc::now().to_rfc3339();
-        let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-        let repo_path_str = repo_path.display();
+    /// Log a command execution tagged with where the command came from
+    pub fn log_command_with_origin(
+        &self,
+        command: &str,
+        repo_path: &Path,
+        exit_code: i32,
+        origin: CommandOrigin,
+    ) -> std::io::Result<()> {
+        self.log_command(&format!("[origin:{}] {}", origin, command), repo_path, exit_code)
+    }
 
-        let log_entry = format!(
-            "[{}] [{}] [{}] [exit:{}] {}\n",
-            timestamp, user, repo_path_str, exit_code, command
-        );
+    /// Log that the user suspended the TUI for an interactive shell
+    ///
+    /// Commands run inside the shell are not visible to gitalky; the entry
+    /// marks the window in which the repository may have changed externally.
+    pub fn log_shell_session(
+        &self,
+        shell: &str,
+        repo_path: &Path,
+        exit_code: i32,
+        duration: std::time::Duration,
+    ) -> std::io::Result<()> {
+        self.log_command(
+            &format!("[shell-session] {} ({}s)", shell, duration.as_secs()),
+            repo_path,
+            exit_code,
+        )
+    }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.log_path)?;
+    /// Log deleting a stale `index.lock`, which gitalky does outside git
+    pub fn log_lock_removal(
+        &self,
+        lock_path: &Path,
+        repo_path: &Path,
+        age: std::time::Duration,
+        removed: bool,
+    ) -> std::io::Result<()> {
+        self.log_command(
+            &format!("[lock-removed] {} ({}s old)", lock_path.display(), age.as_secs()),
+            repo_path,
+            if removed { 0 } else { 1 },
+        )
+    }
 
-        file.write_all(log_entry.as_bytes())?;
-        file.flush()?;
+    /// Log the approval endpoint's decision on a risky command, with who
+    /// decided and why
+    pub fn log_approval(
+        &self,
+        command: &str,
+        repo_path: &Path,
+        decision: &ApprovalDecision,
+    ) -> std::io::Result<()> {
+        let mut entry = if decision.allowed { "[approval-granted]".to_string() } else { "[approval-denied]".to_string() };
+        if let Some(ref approver) = decision.approver {
+            entry.push_str(&format!(" [approver:{}]", approver));
+        }
+        if let Some(ref reason) = decision.reason {
+            entry.push_str(&format!(" reason=\"{}\"", reason));
+        }
+        self.log_command(&format!("{} {}", entry, command), repo_path, if decision.allowed { 0 } else { 1 })
+    }
 
-        Ok(())
+    /// Log a dangerous command run past its `[rate_limits]` entry by typing the override
+    pub fn log_rate_limit_override(&self, command: &str, repo_path: &Path) -> std::io::Result<()> {
+        self.log_command(&format!("[rate-limit-override] {}", command), repo_path, 0)
+    }
+
+    /// Log how long an LLM translation took, for the local stats view
+    pub fn log_translation(
+        &self,
+        repo_path: &Path,
+        latency: std::time::Duration,
+        success: bool,
+    ) -> std::io::Result<()> {
+        self.log_command(
+            &format!("[translation] {}ms", latency.as_millis()),
+            repo_path,
+            if success { 0 } else { 1 },
+        )
+    }
+
+    /// Log a previewed command that was dismissed without running
+    pub fn log_preview_cancelled(
+        &self,
+        command: &str,
+        repo_path: &Path,
+        origin: CommandOrigin,
+    ) -> std::io::Result<()> {
+        self.log_command(
+            &format!("[preview-cancelled] [origin:{}] {}", origin, command),
+            repo_path,
+            0,
+        )
+    }
+
+    /// Log what kind of error a failed command ran into, for the local stats view
+    pub fn log_error_kind(&self, kind: &str, repo_path: &Path, exit_code: i32) -> std::io::Result<()> {
+        self.log_command(&format!("[error] {}", kind), repo_path, exit_code)
     }
 
     /// Log a validation failure for forensics
@@ -89,24 +203,47 @@ impl AuditLogger {
         reason: &str,
         repo_path: &Path,
     ) -> std::io::Result<()> {
-        // Check and rotate log if needed
-        self.rotate_if_needed()?;
+        let message = format!(
+            "[VALIDATION-REJECTED] query=\"{}\" llm_output=\"{}\" reason=\"{}\"",
+            query, llm_output, reason
+        );
+        self.write(AuditRecord::new(repo_path, None, message))
+    }
 
-        let timestamp = Utc::now().to_rfc3339();
-        let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-        let repo_path_str = repo_path.display();
+    /// Log a repair round-trip sent after the LLM's command was rejected
+    pub fn log_repair_attempt(
+        &self,
+        query: &str,
+        attempt: u32,
+        reason: &str,
+        repo_path: &Path,
+    ) -> std::io::Result<()> {
+        let message = format!("[REPAIR-ATTEMPT {}] query=\"{}\" reason=\"{}\"", attempt, query, reason);
+        self.write(AuditRecord::new(repo_path, None, message))
+    }
 
-        let log_entry = format!(
-            "[{}] [{}] [{}] [VALIDATION-REJECTED] query=\"{}\" llm_output=\"{}\" reason=\"{}\"\n",
-            timestamp, user, repo_path_str, query, llm_output, reason
-        );
+    /// Append `record` to the log, then hand it to each sink; a failing sink
+    /// is only warned about, and never keeps the others or the log from it
+    fn write(&self, record: AuditRecord) -> std::io::Result<()> {
+        let written = self.append(&record);
+        for sink in &self.sinks {
+            if let Err(e) = sink.record(&record) {
+                tracing::warn!(sink = sink.name(), error = %e, "audit sink failed");
+            }
+        }
+        written
+    }
+
+    fn append(&self, record: &AuditRecord) -> std::io::Result<()> {
+        // Check and rotate log if needed
+        self.rotate_if_needed()?;
 
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.log_path)?;
 
-        file.write_all(log_entry.as_bytes())?;
+        file.write_all(format!("{}\n", record.to_line()).as_bytes())?;
         file.flush()?;
 
         Ok(())
@@ -120,14 +257,18 @@ impl AuditLogger {
 
         let metadata = fs::metadata(&self.log_path)?;
         if metadata.len() > MAX_LOG_SIZE {
-            // Rotate: history.log -> history.log.1
-            let backup_path = self.log_path.with_extension("log.1");
-            fs::rename(&self.log_path, backup_path)?;
+            // Rotate: history.log -> history.log.1.gz, older backups shift up
+            self.retention.rotate(&self.log_path)?;
         }
 
         Ok(())
     }
 
+    /// Drop rotated logs the retention policy no longer keeps, e.g. by age
+    pub fn cleanup(&self) -> std::io::Result<()> {
+        self.retention.cleanup(&self.log_path)
+    }
+
     /// Get the path to the log file
     pub fn log_path(&self) -> &Path {
         &self.log_path
@@ -174,6 +315,151 @@ mod tests {
         assert!(content.contains("exit:0"));
     }
 
+    #[test]
+    fn test_log_command_with_origin() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        logger
+            .log_command_with_origin("git push", Path::new("/test/repo"), 0, CommandOrigin::LlmEdited)
+            .unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("[exit:0] [origin:llm-edited] git push"));
+    }
+
+    #[test]
+    fn test_log_shell_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        logger
+            .log_shell_session("/bin/zsh", Path::new("/test/repo"), 0, std::time::Duration::from_secs(42))
+            .unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("[/test/repo] [exit:0] [shell-session] /bin/zsh (42s)"));
+    }
+
+    #[test]
+    fn test_log_lock_removal() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        logger
+            .log_lock_removal(
+                Path::new("/test/repo/.git/index.lock"),
+                Path::new("/test/repo"),
+                std::time::Duration::from_secs(900),
+                true,
+            )
+            .unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("[exit:0] [lock-removed] /test/repo/.git/index.lock (900s old)"));
+    }
+
+    struct Recorder(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl AuditSink for Recorder {
+        fn name(&self) -> &'static str {
+            "recorder"
+        }
+
+        fn record(&self, record: &AuditRecord) -> Result<(), crate::audit::SinkError> {
+            self.0.lock().unwrap().push(record.to_line());
+            Ok(())
+        }
+    }
+
+    struct Broken;
+
+    impl AuditSink for Broken {
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn record(&self, _record: &AuditRecord) -> Result<(), crate::audit::SinkError> {
+            Err(crate::audit::SinkError::Unavailable("down".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_sinks_get_every_record_despite_broken_ones() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+        let recorded = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let logger = AuditLogger::with_path(&log_path)
+            .unwrap()
+            .with_sinks(vec![Box::new(Broken), Box::new(Recorder(recorded.clone()))]);
+        logger.log_command("git status", Path::new("/test/repo"), 0).unwrap();
+        logger.log_validation_failure("q", "rm -rf", "not git", Path::new("/test/repo")).unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        let recorded = recorded.lock().unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(content, format!("{}\n{}\n", recorded[0], recorded[1]));
+        assert!(recorded[1].ends_with("[/test/repo] [VALIDATION-REJECTED] query=\"q\" llm_output=\"rm -rf\" reason=\"not git\""));
+    }
+
+    #[test]
+    fn test_log_approval() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        let denied = ApprovalDecision {
+            allowed: false,
+            approver: Some("alice".to_string()),
+            reason: Some("release freeze".to_string()),
+        };
+        logger.log_approval("git push --force", Path::new("/test/repo"), &denied).unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains(
+            "[exit:1] [approval-denied] [approver:alice] reason=\"release freeze\" git push --force"
+        ));
+    }
+
+    #[test]
+    fn test_log_repair_attempt() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        logger
+            .log_repair_attempt("show log", 1, "contains pipe", Path::new("/test/repo"))
+            .unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("[/test/repo] [REPAIR-ATTEMPT 1] query=\"show log\" reason=\"contains pipe\""));
+    }
+
+    #[test]
+    fn test_log_usage_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("test.log");
+        let repo = Path::new("/test/repo");
+
+        let logger = AuditLogger::with_path(&log_path).unwrap();
+        logger
+            .log_translation(repo, std::time::Duration::from_millis(850), true)
+            .unwrap();
+        logger
+            .log_preview_cancelled("git push --force", repo, CommandOrigin::Llm)
+            .unwrap();
+        logger.log_error_kind("No upstream branch", repo, 128).unwrap();
+
+        let content = fs::read_to_string(&log_path).unwrap();
+        assert!(content.contains("[exit:0] [translation] 850ms"));
+        assert!(content.contains("[exit:0] [preview-cancelled] [origin:llm] git push --force"));
+        assert!(content.contains("[exit:128] [error] No upstream branch"));
+    }
+
     #[test]
     fn test_multiple_log_entries() {
         let temp_dir = TempDir::new().unwrap();
@@ -209,8 +495,8 @@ mod tests {
         // Write another entry - should trigger rotation
         logger.log_command("git status", repo_path, 0).unwrap();
 
-        // Check backup file exists
-        let backup_path = log_path.with_extension("log.1");
+        // Check compressed backup file exists
+        let backup_path = log_path.with_extension("log.1.gz");
         assert!(backup_path.exists());
 
         // New log should exist and be smaller
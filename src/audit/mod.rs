@@ -1,3 +1,13 @@
+pub mod history;
 pub mod logger;
+pub mod queries;
+pub mod retention;
+pub mod sink;
+pub mod stats;
 
+pub use history::{HistoryEntry, HistoryError, HistoryFormat, HistoryQuery};
 pub use logger::AuditLogger;
+pub use queries::{QueryHistory, QueryHistoryError, QueryOutcome};
+pub use retention::RetentionPolicy;
+pub use sink::{AuditRecord, AuditSink, AuditSinkConfig, SinkError, SqliteSink, SyslogSink, WebhookSink, open_sinks};
+pub use stats::UsageStats;
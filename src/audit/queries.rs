@@ -0,0 +1,277 @@
+use crate::audit::stats::UsageStats;
+use crate::config::settings::{Config, ConfigError};
+use crate::security::CommandOrigin;
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// File under the config dir holding the query history
+const QUERIES_FILE: &str = "queries.db";
+
+/// Rows kept; older ones are dropped as new ones come in
+const MAX_QUERIES: i64 = 10_000;
+
+/// Queries shown in the stats screen's "most repeated" chart
+const TOP_QUERIES: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum QueryHistoryError {
+    #[error("{0}")]
+    Config(#[from] ConfigError),
+
+    #[error("Query history: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What became of the command proposed for a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryOutcome {
+    /// Ran, with this exit code
+    Executed(i32),
+    /// Dismissed from the preview
+    Cancelled,
+    /// Refused by the validator
+    Rejected,
+}
+
+impl QueryOutcome {
+    fn name(&self) -> &'static str {
+        match self {
+            QueryOutcome::Executed(_) => "executed",
+            QueryOutcome::Cancelled => "cancelled",
+            QueryOutcome::Rejected => "rejected",
+        }
+    }
+
+    fn exit_code(&self) -> Option<i32> {
+        match self {
+            QueryOutcome::Executed(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Queries typed at the prompt and what became of their commands, in a
+/// local SQLite database with a full-text index
+///
+/// Stored in `~/.config/gitalky/queries.db` when `behavior.query_history`
+/// is on; recalled with Up at the prompt and summarized on `:stats`.
+pub struct QueryHistory {
+    connection: Connection,
+}
+
+impl QueryHistory {
+    /// Open `~/.config/gitalky/queries.db`, creating it if needed
+    pub fn open_default() -> Result<Self, QueryHistoryError> {
+        Self::open(&Config::config_dir()?.join(QUERIES_FILE))
+    }
+
+    pub fn open(path: &Path) -> Result<Self, QueryHistoryError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path)?;
+        // Another instance writing at the same moment shouldn't stall this one
+        connection.busy_timeout(Duration::from_millis(200))?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS queries (
+                 id INTEGER PRIMARY KEY,
+                 timestamp TEXT NOT NULL,
+                 repo TEXT NOT NULL,
+                 query TEXT NOT NULL,
+                 command TEXT NOT NULL,
+                 origin TEXT NOT NULL,
+                 outcome TEXT NOT NULL,
+                 exit_code INTEGER
+             );
+             CREATE INDEX IF NOT EXISTS queries_repo ON queries (repo);
+             CREATE VIRTUAL TABLE IF NOT EXISTS queries_fts
+                 USING fts5(query, command, content = 'queries', content_rowid = 'id');
+             CREATE TRIGGER IF NOT EXISTS queries_insert AFTER INSERT ON queries BEGIN
+                 INSERT INTO queries_fts (rowid, query, command) VALUES (new.id, new.query, new.command);
+             END;
+             CREATE TRIGGER IF NOT EXISTS queries_delete AFTER DELETE ON queries BEGIN
+                 INSERT INTO queries_fts (queries_fts, rowid, query, command)
+                     VALUES ('delete', old.id, old.query, old.command);
+             END;",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// Remember `query`, the command proposed for it and what became of it
+    pub fn record(
+        &self,
+        repo: &Path,
+        query: &str,
+        command: &str,
+        origin: CommandOrigin,
+        outcome: QueryOutcome,
+    ) -> Result<(), QueryHistoryError> {
+        self.connection.execute(
+            "INSERT INTO queries (timestamp, repo, query, command, origin, outcome, exit_code)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                Utc::now().to_rfc3339(),
+                repo.display().to_string(),
+                query,
+                command,
+                origin.to_string(),
+                outcome.name(),
+                outcome.exit_code()
+            ],
+        )?;
+        self.connection.execute(
+            "DELETE FROM queries WHERE id <= (SELECT max(id) FROM queries) - ?1",
+            params![MAX_QUERIES],
+        )?;
+        Ok(())
+    }
+
+    /// Distinct queries, most recent first, whose query or command contains
+    /// words starting with each word of `search`; all of them when it's empty
+    pub fn recall(&self, search: &str, repo: Option<&Path>, limit: usize) -> Result<Vec<String>, QueryHistoryError> {
+        let repo = repo.map(|repo| repo.display().to_string());
+        let pattern = fts_pattern(search);
+        let mut statement = self.connection.prepare(
+            "SELECT query, max(id) AS last FROM queries
+             WHERE (?1 IS NULL OR repo = ?1)
+               AND (?2 IS NULL OR id IN (SELECT rowid FROM queries_fts WHERE queries_fts MATCH ?2))
+             GROUP BY query ORDER BY last DESC LIMIT ?3",
+        )?;
+        let queries = statement
+            .query_map(params![repo, pattern, limit as i64], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        Ok(queries)
+    }
+
+    /// The command last run for exactly `query` in `repo`, if it succeeded
+    pub fn last_success(&self, query: &str, repo: &Path) -> Result<Option<String>, QueryHistoryError> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT command FROM queries
+                 WHERE repo = ?1 AND query = ?2 AND outcome = 'executed' AND exit_code = 0
+                 ORDER BY id DESC LIMIT 1",
+                params![repo.display().to_string(), query],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Fill in what the log can't tell: acceptance of LLM commands including
+    /// rejections, and the queries typed most often
+    pub fn add_to(&self, stats: &mut UsageStats) -> Result<(), QueryHistoryError> {
+        let mut statement = self.connection.prepare(
+            "SELECT outcome, count(*) FROM queries WHERE origin IN ('llm', 'llm-edited') GROUP BY outcome",
+        )?;
+        let counts = statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize)))?;
+        for count in counts {
+            let (outcome, count) = count?;
+            match outcome.as_str() {
+                "executed" => stats.translations_executed = count,
+                "cancelled" => stats.translations_cancelled = count,
+                "rejected" => stats.translations_rejected = count,
+                _ => {}
+            }
+        }
+
+        let mut statement = self.connection.prepare(
+            "SELECT query, count(*) AS times FROM queries WHERE outcome = 'executed'
+             GROUP BY query HAVING times > 1 ORDER BY times DESC, query LIMIT ?1",
+        )?;
+        stats.top_queries = statement
+            .query_map(params![TOP_QUERIES as i64], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+}
+
+/// `"word"*` per word of `search`, so FTS syntax typed by the user is taken
+/// literally and partial words match; `None` when there are no words
+fn fts_pattern(search: &str) -> Option<String> {
+    let words: Vec<String> = search
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "")))
+        .filter(|word| word != "\"\"*")
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn history() -> (TempDir, QueryHistory) {
+        let temp = TempDir::new().unwrap();
+        let history = QueryHistory::open(&temp.path().join("queries.db")).unwrap();
+        (temp, history)
+    }
+
+    #[test]
+    fn test_fts_pattern() {
+        assert_eq!(fts_pattern("undo last"), Some("\"undo\"* \"last\"*".to_string()));
+        assert_eq!(fts_pattern("say \"hi\" OR"), Some("\"say\"* \"hi\"* \"OR\"*".to_string()));
+        assert_eq!(fts_pattern("  \" "), None);
+    }
+
+    #[test]
+    fn test_recall_searches_queries_and_commands() {
+        let (_temp, history) = history();
+        let app = Path::new("/work/app");
+        let record = |query: &str, command: &str, repo: &Path| {
+            history
+                .record(repo, query, command, CommandOrigin::Llm, QueryOutcome::Executed(0))
+                .unwrap();
+        };
+        record("undo the last commit", "git reset --soft HEAD~1", app);
+        record("show what changed", "git diff", app);
+        record("undo the last commit", "git reset --soft HEAD~1", app);
+        record("push my branch", "git push", Path::new("/work/other"));
+
+        assert_eq!(
+            history.recall("", Some(app), 10).unwrap(),
+            ["undo the last commit", "show what changed"]
+        );
+        assert_eq!(history.recall("und", Some(app), 10).unwrap(), ["undo the last commit"]);
+        // Commands are searched too
+        assert_eq!(history.recall("diff", Some(app), 10).unwrap(), ["show what changed"]);
+        assert_eq!(history.recall("push", None, 10).unwrap(), ["push my branch"]);
+        assert!(history.recall("push", Some(app), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_last_success_and_stats() {
+        let (_temp, history) = history();
+        let repo = Path::new("/work/app");
+        history
+            .record(repo, "sync", "git pull", CommandOrigin::Llm, QueryOutcome::Executed(1))
+            .unwrap();
+        assert_eq!(history.last_success("sync", repo).unwrap(), None);
+        history
+            .record(repo, "sync", "git pull --rebase", CommandOrigin::LlmEdited, QueryOutcome::Executed(0))
+            .unwrap();
+        history
+            .record(repo, "nuke it", "git clean -fdx", CommandOrigin::Llm, QueryOutcome::Cancelled)
+            .unwrap();
+        history
+            .record(repo, "delete all", "rm -rf .", CommandOrigin::Llm, QueryOutcome::Rejected)
+            .unwrap();
+        history
+            .record(repo, "git status", "git status", CommandOrigin::User, QueryOutcome::Executed(0))
+            .unwrap();
+        assert_eq!(history.last_success("sync", repo).unwrap().as_deref(), Some("git pull --rebase"));
+
+        let mut stats = UsageStats::default();
+        history.add_to(&mut stats).unwrap();
+        assert_eq!(stats.translations_executed, 2);
+        assert_eq!(stats.translations_cancelled, 1);
+        assert_eq!(stats.translations_rejected, 1);
+        assert_eq!(stats.top_queries, [("sync".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,267 @@
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// `[audit]`: how many rotated audit logs are kept, and for how long
+///
+/// Backups are named `history.log.1` (newest) to `history.log.N`, with a
+/// `.gz` suffix when compressed.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RetentionPolicy {
+    /// Rotated files kept next to the current log
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Rotated files are dropped, oldest first, beyond this total size
+    #[serde(default = "default_max_total_mb")]
+    pub max_total_mb: u64,
+    /// Rotated files older than this are dropped; unset keeps them regardless of age
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Gzip rotated files
+    #[serde(default = "default_compress")]
+    pub compress: bool,
+}
+
+fn default_max_files() -> usize {
+    5
+}
+
+fn default_max_total_mb() -> u64 {
+    50
+}
+
+fn default_compress() -> bool {
+    true
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_files: default_max_files(),
+            max_total_mb: default_max_total_mb(),
+            max_age_days: None,
+            compress: default_compress(),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Move the current log to backup 1, shifting older backups up, then
+    /// drop whatever the policy no longer keeps
+    pub fn rotate(&self, log_path: &Path) -> io::Result<()> {
+        let backups = rotated_files(log_path);
+        for (index, path) in backups.iter().rev() {
+            fs::rename(path, backup_path(log_path, index + 1, is_gz(path)))?;
+        }
+
+        if self.compress {
+            let target = backup_path(log_path, 1, true);
+            let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+            io::copy(&mut File::open(log_path)?, &mut encoder)?;
+            encoder.finish()?.sync_all()?;
+            fs::remove_file(log_path)?;
+        } else {
+            fs::rename(log_path, backup_path(log_path, 1, false))?;
+        }
+
+        self.cleanup(log_path)
+    }
+
+    /// Delete backups beyond `max_files`, over `max_total_mb`, or older than `max_age_days`
+    pub fn cleanup(&self, log_path: &Path) -> io::Result<()> {
+        let max_total = self.max_total_mb.saturating_mul(1024 * 1024);
+        let max_age = self.max_age_days.map(|days| Duration::from_secs(days * 24 * 60 * 60));
+        let now = SystemTime::now();
+
+        let mut total = 0;
+        for (position, (_, path)) in rotated_files(log_path).into_iter().enumerate() {
+            let metadata = fs::metadata(&path)?;
+            total += metadata.len();
+            let too_old = max_age.is_some_and(|max_age| {
+                metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| now.duration_since(modified).ok())
+                    .is_some_and(|age| age > max_age)
+            });
+            if position >= self.max_files || total > max_total || too_old {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rotated backups of `log_path` as (index, path), newest first
+pub fn rotated_files(log_path: &Path) -> Vec<(usize, PathBuf)> {
+    let (Some(dir), Some(name)) = (log_path.parent(), log_path.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(if dir.as_os_str().is_empty() { Path::new(".") } else { dir }) else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<(usize, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let suffix = file_name.strip_prefix(&prefix)?;
+            let index = suffix.strip_suffix(".gz").unwrap_or(suffix).parse().ok()?;
+            Some((index, dir.join(file_name)))
+        })
+        .collect();
+    backups.sort_by_key(|(index, _)| *index);
+    backups
+}
+
+/// Backups and the current log as one text, oldest entries first
+pub fn read_all(log_path: &Path) -> io::Result<String> {
+    let mut text = String::new();
+    for (_, path) in rotated_files(log_path).into_iter().rev() {
+        // A damaged backup shouldn't hide the rest of the history
+        let _ = read_backup(&path, &mut text);
+    }
+    match fs::read_to_string(log_path) {
+        Ok(current) => text.push_str(&current),
+        Err(e) if e.kind() == io::ErrorKind::NotFound && !text.is_empty() => {}
+        Err(e) => return Err(e),
+    }
+    Ok(text)
+}
+
+fn read_backup(path: &Path, text: &mut String) -> io::Result<()> {
+    let file = File::open(path)?;
+    if is_gz(path) {
+        GzDecoder::new(file).read_to_string(text)?;
+    } else {
+        io::BufReader::new(file).read_to_string(text)?;
+    }
+    Ok(())
+}
+
+fn backup_path(log_path: &Path, index: usize, gz: bool) -> PathBuf {
+    let mut name = log_path.as_os_str().to_owned();
+    name.push(format!(".{}{}", index, if gz { ".gz" } else { "" }));
+    PathBuf::from(name)
+}
+
+fn is_gz(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// Write `text` as a new gzip file; used by tests to fake old backups
+#[cfg(test)]
+fn write_gz(path: &Path, text: &str) -> io::Result<()> {
+    use std::io::Write;
+    let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+    encoder.write_all(text.as_bytes())?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn names(log_path: &Path) -> Vec<String> {
+        rotated_files(log_path)
+            .into_iter()
+            .map(|(_, path)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_shifts_and_compresses() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("history.log");
+        let policy = RetentionPolicy::default();
+
+        fs::write(&log, "first\n").unwrap();
+        policy.rotate(&log).unwrap();
+        fs::write(&log, "second\n").unwrap();
+        policy.rotate(&log).unwrap();
+        fs::write(&log, "third\n").unwrap();
+
+        assert_eq!(names(&log), vec!["history.log.1.gz", "history.log.2.gz"]);
+        assert_eq!(read_all(&log).unwrap(), "first\nsecond\nthird\n");
+    }
+
+    #[test]
+    fn test_max_files() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("history.log");
+        let policy = RetentionPolicy {
+            max_files: 2,
+            compress: false,
+            ..RetentionPolicy::default()
+        };
+
+        for text in ["a\n", "b\n", "c\n", "d\n"] {
+            fs::write(&log, text).unwrap();
+            policy.rotate(&log).unwrap();
+        }
+        assert_eq!(names(&log), vec!["history.log.1", "history.log.2"]);
+        assert_eq!(read_all(&log).unwrap(), "c\nd\n");
+    }
+
+    #[test]
+    fn test_max_total_size() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("history.log");
+        let megabyte = "x".repeat(1024 * 1024);
+        fs::write(backup_path(&log, 1, false), &megabyte).unwrap();
+        fs::write(backup_path(&log, 2, false), &megabyte).unwrap();
+
+        let policy = RetentionPolicy {
+            max_total_mb: 1,
+            ..RetentionPolicy::default()
+        };
+        policy.cleanup(&log).unwrap();
+        assert_eq!(names(&log), vec!["history.log.1"]);
+    }
+
+    #[test]
+    fn test_max_age() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("history.log");
+        let old = backup_path(&log, 2, true);
+        write_gz(&old, "old\n").unwrap();
+        write_gz(&backup_path(&log, 1, true), "recent\n").unwrap();
+        File::options()
+            .write(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(40 * 24 * 60 * 60))
+            .unwrap();
+
+        let policy = RetentionPolicy {
+            max_age_days: Some(30),
+            ..RetentionPolicy::default()
+        };
+        policy.cleanup(&log).unwrap();
+        assert_eq!(names(&log), vec!["history.log.1.gz"]);
+        assert_eq!(read_all(&log).unwrap(), "recent\n");
+    }
+
+    #[test]
+    fn test_read_all_legacy_backup() {
+        let temp = TempDir::new().unwrap();
+        let log = temp.path().join("history.log");
+        assert!(read_all(&log).is_err());
+
+        fs::write(temp.path().join("history.log.1"), "old\n").unwrap();
+        fs::write(temp.path().join("history.log.bak"), "ignored\n").unwrap();
+        assert_eq!(read_all(&log).unwrap(), "old\n");
+    }
+}
@@ -0,0 +1,577 @@
+use crate::audit::history::{HistoryEntry, HistoryQuery};
+use crate::config::NetworkConfig;
+use crate::security::CommandValidator;
+use chrono::{SecondsFormat, Utc};
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// How long a request to the webhook may take before the batch is dropped
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a write waits on a SQLite database another process has locked
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("SQLite: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("{0}")]
+    Unavailable(String),
+}
+
+/// One audit log entry, as written to `history.log` and handed to every sink
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    /// RFC 3339
+    pub timestamp: String,
+    pub user: String,
+    pub repo: PathBuf,
+    /// `None` for validation and repair records, which have no exit code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub message: String,
+}
+
+impl AuditRecord {
+    pub fn new(repo: &Path, exit_code: Option<i32>, message: String) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            user: std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()),
+            repo: repo.to_path_buf(),
+            exit_code,
+            message,
+        }
+    }
+
+    /// `[timestamp] [user] [repo] [exit:N] message`
+    pub fn to_line(&self) -> String {
+        match self.exit_code {
+            Some(exit_code) => format!(
+                "[{}] [{}] [{}] [exit:{}] {}",
+                self.timestamp,
+                self.user,
+                self.repo.display(),
+                exit_code,
+                self.message
+            ),
+            None => format!("[{}] [{}] [{}] {}", self.timestamp, self.user, self.repo.display(), self.message),
+        }
+    }
+}
+
+/// Somewhere audit records go besides `history.log`
+///
+/// Sinks must not block: `AuditLogger` calls them inline while commands run,
+/// and only logs their errors.
+pub trait AuditSink: Send + Sync {
+    /// Shown in warnings about the sink
+    fn name(&self) -> &'static str;
+
+    fn record(&self, record: &AuditRecord) -> Result<(), SinkError>;
+}
+
+/// `[[audit_sinks]]`: where audit records are copied to
+///
+/// ```toml
+/// [[audit_sinks]]
+/// type = "webhook"
+/// url = "https://audit.example.com/gitalky"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AuditSinkConfig {
+    /// The local syslog daemon
+    Syslog {
+        /// Datagram socket of the daemon; `/dev/log`, or `/var/run/syslog` on macOS
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        socket: Option<PathBuf>,
+        #[serde(default = "default_ident")]
+        ident: String,
+    },
+    /// Records POSTed as `{"records": [...]}`, in batches
+    Webhook {
+        url: String,
+        /// Records per request
+        #[serde(default = "default_batch_size")]
+        batch_size: usize,
+        /// Seconds a partial batch waits before it is sent anyway
+        #[serde(default = "default_flush_secs")]
+        flush_secs: u64,
+        /// Environment variable holding a bearer token for the endpoint
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token_env: Option<String>,
+    },
+    /// A SQLite database `gitalky history` queries instead of the log
+    Sqlite {
+        /// Defaults to `history.db` next to `history.log`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<PathBuf>,
+    },
+}
+
+fn default_ident() -> String {
+    "gitalky".to_string()
+}
+
+fn default_batch_size() -> usize {
+    20
+}
+
+fn default_flush_secs() -> u64 {
+    5
+}
+
+impl AuditSinkConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            AuditSinkConfig::Webhook { url, .. } if !url.starts_with("https://") && !url.starts_with("http://") => {
+                Err(format!("audit_sinks: webhook url must be an http(s) URL, got '{}'", url))
+            }
+            AuditSinkConfig::Webhook { batch_size: 0, .. } => {
+                Err("audit_sinks: webhook batch_size must be greater than 0".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Open the sink; `log_path` is where `history.log` lives
+    pub fn open(&self, network: &NetworkConfig, log_path: &Path) -> Result<Box<dyn AuditSink>, SinkError> {
+        Ok(match self {
+            AuditSinkConfig::Syslog { socket, ident } => Box::new(SyslogSink::connect(socket.as_deref(), ident)?),
+            AuditSinkConfig::Webhook { url, batch_size, flush_secs, token_env } => Box::new(WebhookSink::start(
+                url,
+                *batch_size,
+                Duration::from_secs(*flush_secs),
+                token_env.as_deref().and_then(|name| std::env::var(name).ok()),
+                network,
+            )?),
+            AuditSinkConfig::Sqlite { path } => Box::new(SqliteSink::open(&sqlite_path(path.as_deref(), log_path))?),
+        })
+    }
+
+    /// Database of a `sqlite` sink
+    pub fn database(&self, log_path: &Path) -> Option<PathBuf> {
+        match self {
+            AuditSinkConfig::Sqlite { path } => Some(sqlite_path(path.as_deref(), log_path)),
+            _ => None,
+        }
+    }
+}
+
+fn sqlite_path(path: Option<&Path>, log_path: &Path) -> PathBuf {
+    path.map_or_else(|| log_path.with_file_name("history.db"), Path::to_path_buf)
+}
+
+/// Open every configured sink; one that can't be opened is left out with a
+/// warning rather than keeping commands from being logged
+pub fn open_sinks(configs: &[AuditSinkConfig], network: &NetworkConfig, log_path: &Path) -> Vec<Box<dyn AuditSink>> {
+    configs
+        .iter()
+        .filter_map(|config| match config.open(network, log_path) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to open audit sink");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Records sent to the syslog daemon over its datagram socket
+pub struct SyslogSink {
+    #[cfg(unix)]
+    socket: std::os::unix::net::UnixDatagram,
+    ident: String,
+}
+
+impl SyslogSink {
+    #[cfg(unix)]
+    pub fn connect(socket: Option<&Path>, ident: &str) -> Result<Self, SinkError> {
+        let default = if cfg!(target_os = "macos") { "/var/run/syslog" } else { "/dev/log" };
+        let path = socket.unwrap_or(Path::new(default));
+        let datagram = std::os::unix::net::UnixDatagram::unbound()?;
+        datagram
+            .connect(path)
+            .map_err(|e| SinkError::Unavailable(format!("syslog socket {}: {}", path.display(), e)))?;
+        // A stalled daemon drops records instead of stalling commands
+        datagram.set_nonblocking(true)?;
+        Ok(Self { socket: datagram, ident: ident.to_string() })
+    }
+
+    #[cfg(not(unix))]
+    pub fn connect(_socket: Option<&Path>, _ident: &str) -> Result<Self, SinkError> {
+        Err(SinkError::Unavailable("syslog is only supported on Unix".to_string()))
+    }
+
+    /// `<PRI>ident[pid]: user repo exit=N message`, facility user, at
+    /// warning severity unless the command succeeded
+    fn format(&self, record: &AuditRecord) -> String {
+        let severity = if record.exit_code == Some(0) { 6 } else { 4 };
+        let exit = record.exit_code.map_or("-".to_string(), |code| code.to_string());
+        format!(
+            "<{}>{}[{}]: {} {} exit={} {}",
+            8 + severity,
+            self.ident,
+            std::process::id(),
+            record.user,
+            record.repo.display(),
+            exit,
+            record.message
+        )
+    }
+}
+
+impl AuditSink for SyslogSink {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    #[cfg(unix)]
+    fn record(&self, record: &AuditRecord) -> Result<(), SinkError> {
+        self.socket.send(self.format(record).as_bytes())?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn record(&self, _record: &AuditRecord) -> Result<(), SinkError> {
+        Ok(())
+    }
+}
+
+/// Records queued for a background thread that POSTs them in batches
+pub struct WebhookSink {
+    sender: Option<Sender<AuditRecord>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl WebhookSink {
+    pub fn start(
+        url: &str,
+        batch_size: usize,
+        flush_after: Duration,
+        token: Option<String>,
+        network: &NetworkConfig,
+    ) -> Result<Self, SinkError> {
+        let client = network
+            .apply(reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT))
+            .map_err(|e| SinkError::Unavailable(e.to_string()))?
+            .build()
+            .map_err(|e| SinkError::Unavailable(e.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let (sender, receiver) = mpsc::channel::<AuditRecord>();
+        let url = url.to_string();
+
+        let worker = std::thread::spawn(move || {
+            let post = |batch: &mut Vec<AuditRecord>| {
+                let mut request = client.post(&url).json(&serde_json::json!({ "records": batch }));
+                if let Some(ref token) = token {
+                    request = request.bearer_auth(token);
+                }
+                // `send` needs the runtime already, for its timeout
+                match runtime.block_on(async { request.send().await }).map(|response| response.error_for_status()) {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) | Err(e) => {
+                        tracing::warn!(error = %e, records = batch.len(), "audit webhook dropped a batch");
+                    }
+                }
+                batch.clear();
+            };
+
+            let mut batch = Vec::new();
+            let mut oldest = Instant::now();
+            loop {
+                let wait = flush_after.saturating_sub(oldest.elapsed());
+                match receiver.recv_timeout(if batch.is_empty() { flush_after } else { wait }) {
+                    Ok(record) => {
+                        if batch.is_empty() {
+                            oldest = Instant::now();
+                        }
+                        batch.push(record);
+                        if batch.len() >= batch_size {
+                            post(&mut batch);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) if !batch.is_empty() => post(&mut batch),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        if !batch.is_empty() {
+                            post(&mut batch);
+                        }
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Self { sender: Some(sender), worker: Some(worker) })
+    }
+}
+
+impl AuditSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn record(&self, record: &AuditRecord) -> Result<(), SinkError> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(record.clone()).ok())
+            .ok_or_else(|| SinkError::Unavailable("webhook worker stopped".to_string()))
+    }
+}
+
+impl Drop for WebhookSink {
+    /// Send what is still queued before gitalky exits
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Records in a SQLite database, indexed for `gitalky history`
+pub struct SqliteSink {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteSink {
+    pub fn open(path: &Path) -> Result<Self, SinkError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS audit (
+                 id INTEGER PRIMARY KEY,
+                 timestamp TEXT NOT NULL,
+                 user TEXT NOT NULL,
+                 repo TEXT NOT NULL,
+                 exit_code INTEGER,
+                 message TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS audit_timestamp ON audit (timestamp);
+             CREATE INDEX IF NOT EXISTS audit_repo ON audit (repo);",
+        )?;
+        Ok(Self { connection: Mutex::new(connection) })
+    }
+
+    /// Entries matching `query`, oldest first; time and exit code are
+    /// filtered by SQLite, the rest as for the text log
+    pub fn query(&self, query: &HistoryQuery) -> Result<Vec<HistoryEntry>, SinkError> {
+        let connection = self.lock()?;
+        // Logged timestamps are UTC RFC 3339, so they sort as text
+        let since = query.since.map(|since| since.to_rfc3339_opts(SecondsFormat::AutoSi, false));
+        let mut statement = connection.prepare(
+            "SELECT timestamp, user, repo, exit_code, message FROM audit
+             WHERE (?1 IS NULL OR timestamp >= ?1) AND (?2 = 0 OR exit_code != 0)
+             ORDER BY id",
+        )?;
+        let records = statement.query_map(params![since, query.failed_only], |row| {
+            Ok(AuditRecord {
+                timestamp: row.get(0)?,
+                user: row.get(1)?,
+                repo: PathBuf::from(row.get::<_, String>(2)?),
+                exit_code: row.get(3)?,
+                message: row.get(4)?,
+            })
+        })?;
+
+        let validator = CommandValidator::new();
+        let mut entries = Vec::new();
+        for record in records {
+            if let Some(entry) = HistoryEntry::parse(&record?.to_line(), &validator)
+                && query.matches(&entry)
+            {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, SinkError> {
+        self.connection
+            .lock()
+            .map_err(|_| SinkError::Unavailable("SQLite connection poisoned".to_string()))
+    }
+}
+
+impl AuditSink for SqliteSink {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn record(&self, record: &AuditRecord) -> Result<(), SinkError> {
+        self.lock()?.execute(
+            "INSERT INTO audit (timestamp, user, repo, exit_code, message) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                record.timestamp,
+                record.user,
+                record.repo.display().to_string(),
+                record.exit_code,
+                record.message
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use tempfile::TempDir;
+
+    fn record(exit_code: Option<i32>, message: &str) -> AuditRecord {
+        AuditRecord {
+            timestamp: "2026-01-01T10:00:00+00:00".to_string(),
+            user: "me".to_string(),
+            repo: PathBuf::from("/work/app"),
+            exit_code,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_record_lines() {
+        assert_eq!(
+            record(Some(0), "git status").to_line(),
+            "[2026-01-01T10:00:00+00:00] [me] [/work/app] [exit:0] git status"
+        );
+        assert_eq!(
+            record(None, "[VALIDATION-REJECTED] reason=\"x\"").to_line(),
+            "[2026-01-01T10:00:00+00:00] [me] [/work/app] [VALIDATION-REJECTED] reason=\"x\""
+        );
+    }
+
+    #[test]
+    fn test_parse_sink_config() {
+        let configs: Vec<AuditSinkConfig> = toml::from_str::<toml::Table>(
+            r#"
+            [[sinks]]
+            type = "syslog"
+
+            [[sinks]]
+            type = "webhook"
+            url = "https://audit.example.com"
+            batch_size = 50
+
+            [[sinks]]
+            type = "sqlite"
+        "#,
+        )
+        .unwrap()["sinks"]
+            .clone()
+            .try_into()
+            .unwrap();
+        assert_eq!(
+            configs[0],
+            AuditSinkConfig::Syslog { socket: None, ident: "gitalky".to_string() }
+        );
+        assert!(matches!(configs[1], AuditSinkConfig::Webhook { batch_size: 50, flush_secs: 5, .. }));
+        assert!(configs.iter().all(|config| config.validate().is_ok()));
+        assert_eq!(configs[2].database(Path::new("/h/history.log")), Some(PathBuf::from("/h/history.db")));
+
+        let bad = AuditSinkConfig::Webhook { url: "audit".to_string(), batch_size: 1, flush_secs: 1, token_env: None };
+        assert!(bad.validate().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_syslog_sink() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("log.sock");
+        let daemon = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        let sink = SyslogSink::connect(Some(&path), "gitalky").unwrap();
+        sink.record(&record(Some(1), "git push")).unwrap();
+        let mut buffer = [0u8; 512];
+        let len = daemon.recv(&mut buffer).unwrap();
+        let message = String::from_utf8_lossy(&buffer[..len]);
+        assert!(message.starts_with("<12>gitalky["), "{}", message);
+        assert!(message.ends_with("]: me /work/app exit=1 git push"), "{}", message);
+
+        assert!(SyslogSink::connect(Some(&temp.path().join("missing.sock")), "gitalky").is_err());
+    }
+
+    #[test]
+    fn test_webhook_sink_batches_and_flushes_on_drop() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                // Read until the whole body announced by Content-Length is in
+                loop {
+                    let n = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                            .and_then(|value| value.trim().parse::<usize>().ok())
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            bodies.push(body.to_string());
+                            break;
+                        }
+                    }
+                }
+                stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n").unwrap();
+            }
+            bodies
+        });
+
+        let sink = WebhookSink::start(&url, 2, Duration::from_secs(60), None, &NetworkConfig::default()).unwrap();
+        for command in ["git status", "git log", "git push"] {
+            sink.record(&record(Some(0), command)).unwrap();
+        }
+        drop(sink);
+
+        let bodies = server.join().unwrap();
+        let batches: Vec<serde_json::Value> = bodies.iter().map(|body| serde_json::from_str(body).unwrap()).collect();
+        assert_eq!(batches[0]["records"].as_array().unwrap().len(), 2);
+        assert_eq!(batches[0]["records"][1]["message"], "git log");
+        assert_eq!(batches[1]["records"][0]["message"], "git push");
+    }
+
+    #[test]
+    fn test_sqlite_sink_queries() {
+        let temp = TempDir::new().unwrap();
+        let sink = SqliteSink::open(&temp.path().join("history.db")).unwrap();
+        sink.record(&record(Some(0), "[origin:llm] git status")).unwrap();
+        sink.record(&AuditRecord { timestamp: "2026-01-03T10:00:00+00:00".to_string(), ..record(Some(1), "git push --force") })
+            .unwrap();
+        sink.record(&record(None, "[VALIDATION-REJECTED] reason=\"x\"")).unwrap();
+        sink.record(&record(Some(0), "[translation] 800ms")).unwrap();
+
+        let all = sink.query(&HistoryQuery::default()).unwrap();
+        assert_eq!(all.iter().map(|entry| entry.command.as_str()).collect::<Vec<_>>(), ["git status", "git push --force"]);
+        assert_eq!(all[0].origin.as_deref(), Some("llm"));
+
+        let since = chrono::DateTime::parse_from_rfc3339("2026-01-02T00:00:00Z").unwrap().with_timezone(&Utc);
+        let recent = sink.query(&HistoryQuery { since: Some(since), ..HistoryQuery::default() }).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert!(recent[0].dangerous);
+        let failed = sink.query(&HistoryQuery { failed_only: true, ..HistoryQuery::default() }).unwrap();
+        assert_eq!(failed[0].exit_code, 1);
+    }
+}
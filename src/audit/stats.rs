@@ -0,0 +1,199 @@
+use crate::audit::retention::read_all;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Usage summary built from the local audit log
+///
+/// Everything is derived from `history.log` (and its rotated backups);
+/// translation timings, cancelled previews and error kinds are only present
+/// when `behavior.local_stats` is enabled.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageStats {
+    /// Executed commands by git subcommand, most used first
+    pub commands: Vec<(String, usize)>,
+    /// LLM-proposed commands that were run (edited or not)
+    pub translations_executed: usize,
+    /// LLM-proposed commands dismissed from the preview
+    pub translations_cancelled: usize,
+    /// LLM-proposed commands the validator refused; only known from the query history
+    pub translations_rejected: usize,
+    /// Queries run more than once, most repeated first; only known from the query history
+    pub top_queries: Vec<(String, usize)>,
+    /// Failures by kind, most frequent first
+    pub errors: Vec<(String, usize)>,
+    /// LLM translation latencies in milliseconds
+    pub latencies_ms: Vec<u64>,
+}
+
+impl UsageStats {
+    /// Read the audit log at `path` and its rotated backups, if any
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Ok(Self::from_log(&read_all(path)?))
+    }
+
+    pub fn from_log(text: &str) -> Self {
+        let mut commands: HashMap<String, usize> = HashMap::new();
+        let mut errors: HashMap<String, usize> = HashMap::new();
+        let mut stats = Self::default();
+
+        for line in text.lines() {
+            if line.contains("[VALIDATION-REJECTED]") {
+                *errors.entry("validation rejected".to_string()).or_default() += 1;
+                continue;
+            }
+            let Some(message) = entry_message(line) else {
+                continue;
+            };
+
+            if let Some(latency) = message.strip_prefix("[translation] ") {
+                if let Ok(ms) = latency.trim_end_matches("ms").parse() {
+                    stats.latencies_ms.push(ms);
+                }
+            } else if let Some(rest) = message.strip_prefix("[preview-cancelled] ") {
+                if is_llm(split_origin(rest).0) {
+                    stats.translations_cancelled += 1;
+                }
+            } else if let Some(kind) = message.strip_prefix("[error] ") {
+                *errors.entry(kind.to_string()).or_default() += 1;
+            } else if !["[shell-session]", "[lock-removed]", "[approval-"].iter().any(|tag| message.starts_with(tag)) {
+                let (origin, command) = split_origin(message);
+                if is_llm(origin) {
+                    stats.translations_executed += 1;
+                }
+                if let Some(subcommand) = subcommand(command) {
+                    *commands.entry(subcommand.to_string()).or_default() += 1;
+                }
+            }
+        }
+
+        stats.commands = ranked(commands);
+        stats.errors = ranked(errors);
+        stats
+    }
+
+    /// Share of LLM-proposed commands that were run, if any were proposed
+    pub fn acceptance_rate(&self) -> Option<f64> {
+        let total = self.translations_executed + self.translations_cancelled;
+        (total > 0).then(|| self.translations_executed as f64 / total as f64)
+    }
+
+    pub fn average_latency_ms(&self) -> Option<u64> {
+        let count = self.latencies_ms.len() as u64;
+        (count > 0).then(|| self.latencies_ms.iter().sum::<u64>() / count)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+            && self.errors.is_empty()
+            && self.latencies_ms.is_empty()
+            && self.translations_cancelled == 0
+            && self.top_queries.is_empty()
+    }
+}
+
+/// Message part of `[ts] [user] [repo] [exit:N] message`
+fn entry_message(line: &str) -> Option<&str> {
+    let start = line.find("[exit:")?;
+    let rest = &line[start..];
+    let end = rest.find("] ")?;
+    Some(&rest[end + 2..])
+}
+
+/// Split a leading `[origin:x] ` tag from a logged command
+fn split_origin(message: &str) -> (Option<&str>, &str) {
+    message
+        .strip_prefix("[origin:")
+        .and_then(|rest| rest.split_once("] "))
+        .map_or((None, message), |(origin, command)| (Some(origin), command))
+}
+
+fn is_llm(origin: Option<&str>) -> bool {
+    matches!(origin, Some("llm" | "llm-edited"))
+}
+
+fn subcommand(command: &str) -> Option<&str> {
+    command
+        .split_whitespace()
+        .find(|word| *word != "git" && !word.starts_with('-'))
+}
+
+fn ranked(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut ranked: Vec<_> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const LOG: &str = "\
+[2026-01-01T10:00:00Z] [me] [/r] [exit:0] [translation] 800ms
+[2026-01-01T10:00:01Z] [me] [/r] [exit:0] [origin:llm] git status
+[2026-01-01T10:00:02Z] [me] [/r] [exit:0] [translation] 1200ms
+[2026-01-01T10:00:03Z] [me] [/r] [exit:0] [preview-cancelled] [origin:llm] git push --force
+[2026-01-01T10:00:04Z] [me] [/r] [exit:0] [origin:user] git status -s
+[2026-01-01T10:00:05Z] [me] [/r] [exit:128] [origin:llm-edited] git push
+[2026-01-01T10:00:05Z] [me] [/r] [exit:128] [error] No upstream branch
+[2026-01-01T10:00:06Z] [me] [/r] [exit:0] [preview-cancelled] [origin:user] git gc
+[2026-01-01T10:00:07Z] [me] [/r] [exit:0] [shell-session] /bin/zsh (12s)
+[2026-01-01T10:00:07Z] [me] [/r] [exit:0] [lock-removed] /r/.git/index.lock (900s old)
+[2026-01-01T10:00:07Z] [me] [/r] [exit:1] [approval-denied] [approver:alice] git gc --prune=now
+[2026-01-01T10:00:08Z] [me] [/r] [exit:0] git log
+[2026-01-01T10:00:09Z] [me] [/r] [exit:1] [VALIDATION-REJECTED] query=\"x\" llm_output=\"rm\" reason=\"y\"
+";
+
+    #[test]
+    fn test_counts_commands_by_subcommand() {
+        let stats = UsageStats::from_log(LOG);
+        assert_eq!(
+            stats.commands,
+            vec![
+                ("status".to_string(), 2),
+                ("log".to_string(), 1),
+                ("push".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_acceptance_and_latency() {
+        let stats = UsageStats::from_log(LOG);
+        assert_eq!(stats.translations_executed, 2);
+        assert_eq!(stats.translations_cancelled, 1);
+        assert_eq!(stats.acceptance_rate(), Some(2.0 / 3.0));
+        assert_eq!(stats.average_latency_ms(), Some(1000));
+    }
+
+    #[test]
+    fn test_counts_errors_by_kind() {
+        let stats = UsageStats::from_log(LOG);
+        assert_eq!(
+            stats.errors,
+            vec![
+                ("No upstream branch".to_string(), 1),
+                ("validation rejected".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_log() {
+        let stats = UsageStats::from_log("");
+        assert!(stats.is_empty());
+        assert_eq!(stats.acceptance_rate(), None);
+        assert_eq!(stats.average_latency_ms(), None);
+    }
+
+    #[test]
+    fn test_load_includes_rotated_log() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("history.log");
+        fs::write(path.with_extension("log.1"), "[t] [u] [/r] [exit:0] git fetch\n").unwrap();
+        fs::write(&path, "[t] [u] [/r] [exit:0] git fetch\n").unwrap();
+
+        let stats = UsageStats::load(&path).unwrap();
+        assert_eq!(stats.commands, vec![("fetch".to_string(), 2)]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::audit::history::{parse_since, repo_filter};
+use crate::audit::{HistoryError, HistoryFormat, HistoryQuery};
+use crate::cli::run::{RunArgs, RunError};
+use chrono::{DateTime, Utc};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Name completions and the man page are generated for
+const BIN_NAME: &str = "gitalky";
+
+/// Talk to git in plain language
+///
+/// Without a subcommand, opens the interactive interface in the current repository.
+#[derive(Debug, Parser)]
+#[command(name = BIN_NAME, version)]
+pub struct Cli {
+    /// Write more to the diagnostics log (-vv for everything)
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Translate, validate and run one command, then exit
+    #[command(after_help = "Exit codes: 0 success, 2 rejected or untrusted, 3 dangerous operation refused or not approved, \
+                            4 LLM failure, 5 git failure")]
+    Run(RunCommand),
+    /// Show commands from the audit log
+    History(HistoryCommand),
+    /// Check the setup and say how to fix what's wrong
+    Doctor,
+    /// Answer JSON-RPC and MCP requests from an editor on stdin
+    Serve,
+    /// Print a shell completion script
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print the man page (roff)
+    Man,
+}
+
+/// `gitalky run [options] <query | git command>`
+#[derive(Debug, Args)]
+pub struct RunCommand {
+    /// Only the command (or the error) on stderr; git's output on stdout
+    #[arg(short, long)]
+    pub quiet: bool,
+    /// Allow dangerous operations; ignored unless stdin and stdout are a terminal
+    #[arg(short, long)]
+    pub yes: bool,
+    /// Print the command to stdout instead of running it
+    #[arg(short, long)]
+    pub print: bool,
+    /// Natural-language query, or a command starting with `git`
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true, value_name = "QUERY")]
+    pub input: Vec<String>,
+}
+
+impl RunCommand {
+    pub fn into_run_args(self) -> Result<RunArgs, RunError> {
+        RunArgs {
+            quiet: self.quiet,
+            yes: self.yes,
+            print: self.print,
+            ..RunArgs::default()
+        }
+        .with_input(&self.input)
+    }
+}
+
+/// `gitalky history [filters]`
+#[derive(Debug, Args)]
+pub struct HistoryCommand {
+    /// Only commands run in repositories at or under PATH
+    #[arg(long, value_name = "PATH", value_hint = clap::ValueHint::DirPath)]
+    pub repo: Option<PathBuf>,
+    /// A date (2026-01-31), an RFC 3339 time, or an age such as 30m, 12h, 7d, 2w
+    #[arg(long, value_name = "WHEN")]
+    pub since: Option<String>,
+    /// Only commands that exited non-zero
+    #[arg(long)]
+    pub failed_only: bool,
+    /// Only commands the validator treats as dangerous
+    #[arg(long)]
+    pub dangerous_only: bool,
+    /// How to print the entries
+    #[arg(long, value_enum, default_value_t = HistoryFormat::Table)]
+    pub format: HistoryFormat,
+}
+
+impl HistoryCommand {
+    pub fn into_query(self, now: DateTime<Utc>) -> Result<HistoryQuery, HistoryError> {
+        Ok(HistoryQuery {
+            repo: self.repo.map(repo_filter),
+            since: self.since.map(|since| parse_since(&since, now)).transpose()?,
+            failed_only: self.failed_only,
+            dangerous_only: self.dangerous_only,
+            format: self.format,
+        })
+    }
+}
+
+/// Write the completion script for `shell`
+pub fn write_completions(shell: Shell, out: &mut dyn Write) -> io::Result<()> {
+    // The generator panics on write errors; a closed pipe shouldn't
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), BIN_NAME, &mut script);
+    out.write_all(&script)
+}
+
+/// Write the man page, in roff
+pub fn write_man_page(out: &mut dyn Write) -> io::Result<()> {
+    clap_mangen::Man::new(Cli::command()).render(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once(BIN_NAME).chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let cli = parse(&["history", "-v", "--since", "7d", "--format", "json", "--verbose"]);
+        assert_eq!(cli.verbose, 2);
+        let Some(CliCommand::History(history)) = cli.command else { panic!("not history") };
+        let query = history.into_query(Utc::now()).unwrap();
+        assert!(query.since.is_some());
+        assert_eq!(query.format, HistoryFormat::Json);
+
+        // Options after the input belong to the git command
+        let Some(CliCommand::Run(run)) = parse(&["run", "-qy", "git", "log", "-n", "3"]).command else {
+            panic!("not run")
+        };
+        let args = run.into_run_args().unwrap();
+        assert_eq!((args.input.as_str(), args.quiet, args.yes, args.print), ("git log -n 3", true, true, false));
+
+        assert_eq!(parse(&["-vv"]).verbose, 2);
+        assert!(parse(&[]).command.is_none());
+        assert!(Cli::try_parse_from([BIN_NAME, "run"]).is_err());
+        assert!(Cli::try_parse_from([BIN_NAME, "history", "--format", "csv"]).is_err());
+        assert!(Cli::try_parse_from([BIN_NAME, "completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn test_generated_completions_and_man_page() {
+        let mut bash = Vec::new();
+        write_completions(Shell::Bash, &mut bash).unwrap();
+        let bash = String::from_utf8(bash).unwrap();
+        assert!(bash.contains("completions"));
+        assert!(bash.contains("--dangerous-only"));
+
+        let mut man = Vec::new();
+        write_man_page(&mut man).unwrap();
+        let man = String::from_utf8(man).unwrap();
+        assert!(man.starts_with(".ie"));
+        assert!(man.contains(".TH gitalky"));
+        assert!(man.contains("history"));
+    }
+}
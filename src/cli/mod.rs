@@ -0,0 +1,5 @@
+pub mod args;
+pub mod run;
+
+pub use args::{Cli, CliCommand, HistoryCommand, RunCommand, write_completions, write_man_page};
+pub use run::{CliRunner, RunArgs, RunError, RunOutcome, exit_codes, interactive};
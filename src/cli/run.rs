@@ -0,0 +1,475 @@
+use crate::audit::AuditLogger;
+use crate::config::{Config, DangerLedger, TrustStore};
+use crate::error::GitError;
+use crate::git::{CommandOutput, Repository};
+use crate::hooks::{
+    ApprovalClient, ApprovalRequest, HookEvent, HookPayload, HookRunner, HookValidation,
+};
+use crate::llm::{GitCommand, Translator};
+use crate::security::{CommandOrigin, CommandValidator, DangerousOp, Workspace, is_read_only_command};
+use chrono::Utc;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Process exit codes of `gitalky run`, stable for scripts and CI jobs
+pub mod exit_codes {
+    pub const SUCCESS: i32 = 0;
+    /// Bad arguments or config, the validator or a hook rejected the command, or the
+    /// repository isn't trusted to run it
+    pub const VALIDATION_REJECTED: i32 = 2;
+    /// Dangerous operation without `--yes`, past or unable to check its rate
+    /// limit, or the approval endpoint said no
+    pub const DANGEROUS_REFUSED: i32 = 3;
+    /// No LLM configured, or the translation failed
+    pub const LLM_FAILURE: i32 = 4;
+    /// Not a repository, git missing, or the git command failed
+    pub const GIT_FAILURE: i32 = 5;
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RunError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("~/.config/gitalky/config.toml: {0}")]
+    Config(String),
+    #[error("Command rejected: {0}")]
+    Rejected(String),
+    #[error(
+        "Untrusted repository: only commands that don't modify it can run; \
+         open it in gitalky and type :trust to trust it"
+    )]
+    Untrusted,
+    #[error("Dangerous operation ({0}) refused; pass --yes to run it")]
+    Dangerous(String),
+    #[error("Dangerous operation ({0}) refused: not run from a terminal, where --yes is ignored")]
+    SafeMode(String),
+    #[error("{0}; only the interactive confirmation can override it")]
+    RateLimited(String),
+    #[error("Can't check the rate limit, so the command didn't run: {0}")]
+    Ledger(String),
+    #[error("Command not approved: {0}")]
+    NotApproved(String),
+    #[error("Translation failed: {0}")]
+    Llm(String),
+    #[error("{message}")]
+    Git { message: String, stdout: String },
+}
+
+impl RunError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RunError::Usage(_) | RunError::Config(_) | RunError::Rejected(_) | RunError::Untrusted => {
+                exit_codes::VALIDATION_REJECTED
+            }
+            RunError::Dangerous(_)
+            | RunError::SafeMode(_)
+            | RunError::RateLimited(_)
+            | RunError::Ledger(_)
+            | RunError::NotApproved(_) => exit_codes::DANGEROUS_REFUSED,
+            RunError::Llm(_) => exit_codes::LLM_FAILURE,
+            RunError::Git { .. } => exit_codes::GIT_FAILURE,
+        }
+    }
+
+    /// Failure to reach git or the repository at all
+    pub fn git(error: impl ToString) -> Self {
+        RunError::Git { message: error.to_string(), stdout: String::new() }
+    }
+}
+
+/// Whether both stdin and stdout are terminals; when either isn't, gitalky is
+/// probably in a CI job or a script with nobody watching
+pub fn interactive() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Parsed `gitalky run` arguments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunArgs {
+    /// Natural-language query, or a command starting with `git `
+    pub input: String,
+    pub quiet: bool,
+    pub yes: bool,
+    pub print: bool,
+}
+
+impl RunArgs {
+    pub fn parse(args: &[String]) -> Result<Self, RunError> {
+        let mut parsed = RunArgs::default();
+        let mut words = Vec::new();
+        let mut rest_is_input = false;
+        for arg in args {
+            match arg.as_str() {
+                _ if rest_is_input => words.push(arg.as_str()),
+                "--" => rest_is_input = true,
+                "-q" | "--quiet" => parsed.quiet = true,
+                "-y" | "--yes" => parsed.yes = true,
+                "-p" | "--print" => parsed.print = true,
+                flag if flag.starts_with('-') && words.is_empty() => {
+                    return Err(RunError::Usage(format!("Unknown option: {}", flag)));
+                }
+                _ => words.push(arg.as_str()),
+            }
+        }
+        parsed.with_input(&words)
+    }
+
+    /// These options with `words` joined as the input
+    pub fn with_input<S: AsRef<str>>(mut self, words: &[S]) -> Result<Self, RunError> {
+        let words: Vec<&str> = words.iter().map(AsRef::as_ref).collect();
+        self.input = words.join(" ").trim().to_string();
+        if self.input.is_empty() {
+            return Err(RunError::Usage("Nothing to run".to_string()));
+        }
+        Ok(self)
+    }
+
+    /// Whether the input is already a git command rather than a query
+    fn is_git_command(&self) -> bool {
+        self.input.starts_with("git ")
+    }
+}
+
+/// What `gitalky run` did
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    pub command: String,
+    pub explanation: Option<String>,
+    /// `None` with `--print`
+    pub output: Option<CommandOutput>,
+    /// The run couldn't be counted against `[rate_limits]`
+    pub warning: Option<String>,
+}
+
+/// One-shot translate → validate → execute, with the same validator, hooks
+/// and audit log as the TUI and `gitalky serve`
+pub struct CliRunner {
+    config: Config,
+    audit_logger: Option<AuditLogger>,
+    hooks: HookRunner,
+    /// Refuse dangerous operations even with `--yes`
+    safe_mode: bool,
+    /// Rate-limit ledger checked before a dangerous run and appended to after it
+    danger_ledger: Option<PathBuf>,
+    /// Trust decisions consulted when `behavior.repo_trust` is on
+    trust_file: Option<PathBuf>,
+}
+
+impl CliRunner {
+    pub fn new(config: Config) -> Self {
+        let audit_logger = if config.behavior.log_commands {
+            AuditLogger::for_config(&config).ok()
+        } else {
+            None
+        };
+        let hooks = HookRunner::new(config.hooks.clone());
+        let danger_ledger = DangerLedger::file_path().ok();
+        let trust_file = TrustStore::file_path().ok();
+        Self { config, audit_logger, hooks, safe_mode: false, danger_ledger, trust_file }
+    }
+
+    /// Never run dangerous operations, for when nobody is at a terminal
+    pub fn with_safe_mode(mut self) -> Self {
+        self.safe_mode = true;
+        self
+    }
+
+    pub async fn run(&self, repo: &Repository, args: &RunArgs) -> Result<RunOutcome, RunError> {
+        let (command, explanation, origin) = if args.is_git_command() {
+            (args.input.clone(), None, CommandOrigin::User)
+        } else {
+            let translated = self.translate(repo, &args.input).await?;
+            (translated.command, translated.explanation, CommandOrigin::Llm)
+        };
+
+        let validator = CommandValidator::new()
+            .with_workspace(Workspace::new(repo.path(), self.config.git.projects_dirs.clone()))
+            .with_allowed_hosts(self.config.git.allowed_hosts.clone());
+        let validated = match validator.validate_with_origin(&command, origin) {
+            Ok(validated) => validated,
+            Err(e) => {
+                self.log(&command, repo, 1, origin);
+                return Err(RunError::Rejected(e.to_string()));
+            }
+        };
+        let danger_type = validated.danger_type.as_ref().map(|op| format!("{:?}", op));
+        if validated.is_dangerous {
+            let op = danger_type.clone().unwrap_or_else(|| "unknown".to_string());
+            if self.safe_mode {
+                return Err(RunError::SafeMode(op));
+            }
+            if !args.yes {
+                return Err(RunError::Dangerous(op));
+            }
+        }
+        if args.print {
+            return Ok(RunOutcome { command, explanation, output: None, warning: None });
+        }
+        if !self.trusts(repo) && !is_read_only_command(&command) {
+            self.log(&command, repo, 1, origin);
+            return Err(RunError::Untrusted);
+        }
+        let ledger = self.danger_ledger.as_deref();
+        if let Some(ref op) = validated.danger_type {
+            match DangerLedger::check_saved(ledger, op, &self.config.rate_limits) {
+                Ok(Some(hit)) => return Err(RunError::RateLimited(hit.describe(Utc::now()))),
+                Ok(None) => {}
+                Err(e) => return Err(RunError::Ledger(format!("{}: {}", DangerLedger::display_path(ledger), e))),
+            }
+        }
+
+        let payload = HookPayload::new(HookEvent::PreExecute, repo.path())
+            .with_command(&command)
+            .with_validation(HookValidation { is_dangerous: validated.is_dangerous, danger_type });
+        if let Some(reason) = self.hooks.run(&payload).await.veto {
+            self.log(&command, repo, 1, origin);
+            return Err(RunError::Rejected(format!("blocked by hook: {}", reason)));
+        }
+        if let Some(ref op) = validated.danger_type {
+            self.approve(repo, &command, op, origin).await?;
+        }
+
+        // Executor adds the "git " prefix itself
+        let result = repo.executor().execute_for_user(command.strip_prefix("git ").unwrap_or(&command));
+        let exit_code = match result {
+            Ok(ref output) => output.exit_code,
+            Err(ref e) => e.exit_code().unwrap_or(1),
+        };
+        self.log(&command, repo, exit_code, origin);
+        let warning = validated.danger_type.as_ref().and_then(|op| {
+            DangerLedger::record_saved(ledger, op, &self.config.rate_limits).err().map(|e| {
+                format!("run not counted against [rate_limits] in {}: {}", DangerLedger::display_path(ledger), e)
+            })
+        });
+        let payload = HookPayload::new(HookEvent::PostExecute, repo.path())
+            .with_command(&command)
+            .with_exit_code(exit_code);
+        self.hooks.run(&payload).await;
+
+        match result {
+            Ok(output) => Ok(RunOutcome { command, explanation, output: Some(output), warning }),
+            Err(GitError::CommandExited { stdout, stderr, exit_code, .. }) => {
+                let mut message = match stderr.trim() {
+                    "" => format!("{} exited with status {}", command, exit_code),
+                    stderr => stderr.to_string(),
+                };
+                if let Some(warning) = warning {
+                    message.push_str(&format!("\nWarning: {}", warning));
+                }
+                Err(RunError::Git { message, stdout })
+            }
+            Err(e) => Err(RunError::git(e)),
+        }
+    }
+
+    /// Ask the `[approval]` endpoint when the operation is risky enough
+    async fn approve(
+        &self,
+        repo: &Repository,
+        command: &str,
+        op: &DangerousOp,
+        origin: CommandOrigin,
+    ) -> Result<(), RunError> {
+        let Some(ref approval) = self.config.approval else {
+            return Ok(());
+        };
+        let branch = repo.current_branch().unwrap_or(None);
+        let risk = self.config.confirmation.risk(op, command, branch.as_deref());
+        if !approval.applies_to(risk) {
+            return Ok(());
+        }
+
+        let request = ApprovalRequest::new(command, repo.path(), format!("{:?}", op), risk, origin)
+            .with_branch(branch.as_deref());
+        let decision = ApprovalClient::ask(approval, &self.config.network, &request).await;
+        if let Some(ref logger) = self.audit_logger {
+            let _ = logger.log_approval(command, repo.path(), &decision);
+        }
+        if decision.allowed { Ok(()) } else { Err(RunError::NotApproved(decision.describe())) }
+    }
+
+    /// Whether `repo` may run commands that modify it; nobody is asked here,
+    /// so a repository never trusted in the TUI isn't
+    fn trusts(&self, repo: &Repository) -> bool {
+        !self.config.behavior.repo_trust
+            || self.trust_file.as_deref().is_some_and(|file| TrustStore::trusts_saved(file, repo.path()))
+    }
+
+    async fn translate(&self, repo: &Repository, query: &str) -> Result<GitCommand, RunError> {
+        if !self.config.llm.features.translate {
+            return Err(RunError::Llm("translation is turned off in [llm.features]".to_string()));
+        }
+        let translator = Translator::from_config(repo, &self.config)
+            .ok_or_else(|| RunError::Llm("no LLM API key configured".to_string()))?;
+        translator.translate(query).await.map_err(|e| RunError::Llm(e.to_string()))
+    }
+
+    fn log(&self, command: &str, repo: &Repository, exit_code: i32, origin: CommandOrigin) {
+        if let Some(ref logger) = self.audit_logger {
+            let _ = logger.log_command_with_origin(command, repo.path(), exit_code, origin);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use tempfile::TempDir;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    fn test_runner() -> CliRunner {
+        let mut config = Config::default_config();
+        config.behavior.log_commands = false;
+        config.behavior.repo_trust = false;
+        config.llm.api_key_env = "NONEXISTENT_API_KEY".to_string();
+        config.llm.api_key = None;
+        CliRunner::new(config)
+    }
+
+    fn test_repo() -> (TempDir, Repository) {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let repo = Repository::new(temp.path());
+        (temp, repo)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = RunArgs::parse(&args(&["-q", "--yes", "show", "my", "changes"])).unwrap();
+        assert_eq!(parsed.input, "show my changes");
+        assert!(parsed.quiet && parsed.yes && !parsed.print);
+
+        // Flags after the input belong to the git command
+        let parsed = RunArgs::parse(&args(&["git", "log", "-n", "3"])).unwrap();
+        assert_eq!(parsed.input, "git log -n 3");
+        assert!(parsed.is_git_command());
+        assert_eq!(RunArgs::parse(&args(&["--", "-q"])).unwrap().input, "-q");
+
+        assert_eq!(RunArgs::parse(&args(&["--quiet"])).unwrap_err().exit_code(), exit_codes::VALIDATION_REJECTED);
+        assert!(matches!(RunArgs::parse(&args(&["--force", "x"])), Err(RunError::Usage(_))));
+    }
+
+    #[tokio::test]
+    async fn test_exit_codes() {
+        let (_temp, repo) = test_repo();
+        let runner = test_runner();
+        let code = |result: Result<RunOutcome, RunError>| result.map_or_else(|e| e.exit_code(), |_| exit_codes::SUCCESS);
+        let run = |input: &str, yes: bool| RunArgs { input: input.to_string(), yes, ..RunArgs::default() };
+
+        assert_eq!(code(runner.run(&repo, &run("git status", false)).await), exit_codes::SUCCESS);
+        assert_eq!(code(runner.run(&repo, &run("git rm -rf .", false)).await), exit_codes::VALIDATION_REJECTED);
+        assert_eq!(code(runner.run(&repo, &run("git clean -fd", false)).await), exit_codes::DANGEROUS_REFUSED);
+        assert_eq!(code(runner.run(&repo, &run("git clean -fd", true)).await), exit_codes::SUCCESS);
+        assert_eq!(code(runner.run(&repo, &run("show my changes", false)).await), exit_codes::LLM_FAILURE);
+
+        // No commits yet, so git log fails
+        let err = runner.run(&repo, &run("git log", false)).await.unwrap_err();
+        assert_eq!(err.exit_code(), exit_codes::GIT_FAILURE);
+        assert!(err.to_string().contains("does not have any commits"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_safe_mode_ignores_yes() {
+        let (temp, repo) = test_repo();
+        std::fs::write(temp.path().join("scratch.txt"), "x").unwrap();
+        let runner = test_runner().with_safe_mode();
+        let run = |input: &str| RunArgs { input: input.to_string(), yes: true, ..RunArgs::default() };
+
+        let err = runner.run(&repo, &run("git clean -f")).await.unwrap_err();
+        assert!(matches!(err, RunError::SafeMode(_)), "{}", err);
+        assert_eq!(err.exit_code(), exit_codes::DANGEROUS_REFUSED);
+        assert!(temp.path().join("scratch.txt").exists());
+        assert!(runner.run(&repo, &run("git status")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_command_does_not_run() {
+        let (temp, repo) = test_repo();
+        let mut runner = test_runner();
+        runner.danger_ledger = Some(temp.path().join(".git").join("danger_runs.toml"));
+        runner.config.rate_limits.insert(DangerousOp::Clean, crate::config::RateLimit { max: 1, window_minutes: 10 });
+        let clean = RunArgs { input: "git clean -f".to_string(), yes: true, ..RunArgs::default() };
+
+        std::fs::write(temp.path().join("first.txt"), "x").unwrap();
+        runner.run(&repo, &clean).await.unwrap();
+        assert!(!temp.path().join("first.txt").exists());
+
+        std::fs::write(temp.path().join("second.txt"), "x").unwrap();
+        let err = runner.run(&repo, &clean).await.unwrap_err();
+        assert!(matches!(err, RunError::RateLimited(_)), "{}", err);
+        assert_eq!(err.exit_code(), exit_codes::DANGEROUS_REFUSED);
+        assert!(temp.path().join("second.txt").exists());
+
+        // An unreadable ledger refuses instead of forgetting the runs
+        std::fs::write(runner.danger_ledger.as_ref().unwrap(), "runs = [").unwrap();
+        let err = runner.run(&repo, &clean).await.unwrap_err();
+        assert!(matches!(err, RunError::Ledger(_)), "{}", err);
+        assert_eq!(err.exit_code(), exit_codes::DANGEROUS_REFUSED);
+        assert!(temp.path().join("second.txt").exists());
+
+        // Nowhere to keep the ledger refuses too
+        runner.danger_ledger = None;
+        let err = runner.run(&repo, &clean).await.unwrap_err();
+        assert!(matches!(err, RunError::Ledger(_)), "{}", err);
+        assert!(temp.path().join("second.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_repository_only_reads() {
+        let (temp, repo) = test_repo();
+        std::fs::write(temp.path().join("a.txt"), "a").unwrap();
+        let mut runner = test_runner();
+        runner.config.behavior.repo_trust = true;
+        runner.trust_file = Some(temp.path().join(".git").join("trust.toml"));
+        let run = |input: &str| RunArgs { input: input.to_string(), yes: true, ..RunArgs::default() };
+
+        assert!(runner.run(&repo, &run("git status")).await.is_ok());
+        for command in ["git add a.txt", "git diff --output=/tmp/x", "git clean -f"] {
+            let err = runner.run(&repo, &run(command)).await.unwrap_err();
+            assert_eq!(err, RunError::Untrusted, "{}", command);
+            assert_eq!(err.exit_code(), exit_codes::VALIDATION_REJECTED);
+        }
+        assert!(temp.path().join("a.txt").exists());
+
+        let mut store = TrustStore::default();
+        store.set(repo.path(), crate::config::TrustLevel::Trusted);
+        store.save_to(runner.trust_file.as_ref().unwrap()).unwrap();
+        assert!(runner.run(&repo, &run("git add a.txt")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_unapproved_command_does_not_run() {
+        let (temp, repo) = test_repo();
+        std::fs::write(temp.path().join("scratch.txt"), "x").unwrap();
+        let mut runner = test_runner();
+        runner.config.approval = Some(crate::hooks::ApprovalConfig {
+            url: "http://127.0.0.1:1".to_string(),
+            min_risk: crate::security::RiskLevel::Medium,
+            timeout_secs: 5,
+            poll_interval_secs: 1,
+            token_env: None,
+        });
+
+        let args = RunArgs { input: "git clean -f".to_string(), yes: true, ..RunArgs::default() };
+        let err = runner.run(&repo, &args).await.unwrap_err();
+        assert!(matches!(err, RunError::NotApproved(_)), "{}", err);
+        assert_eq!(err.exit_code(), exit_codes::DANGEROUS_REFUSED);
+        assert!(temp.path().join("scratch.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_print_does_not_run() {
+        let (temp, repo) = test_repo();
+        std::fs::write(temp.path().join("a.txt"), "a").unwrap();
+        let args = RunArgs { input: "git add a.txt".to_string(), print: true, ..RunArgs::default() };
+        let outcome = test_runner().run(&repo, &args).await.unwrap();
+        assert_eq!(outcome.command, "git add a.txt");
+        assert!(outcome.output.is_none());
+        assert!(repo.state().unwrap().staged_files.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+use crate::config::settings::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Corrections kept per repository; older ones are dropped first
+pub const MAX_CORRECTIONS_PER_REPO: usize = 20;
+
+/// Most recent corrections sent to the LLM as examples
+pub const PROMPT_CORRECTIONS: usize = 5;
+
+/// Input that wipes every stored correction
+pub const FORGET_CORRECTIONS_COMMAND: &str = ":forget-corrections";
+
+/// File under the config dir holding the corrections
+const CORRECTIONS_FILE: &str = "corrections.toml";
+
+/// A translated command the user edited before running it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Correction {
+    pub repo: PathBuf,
+    pub query: String,
+    /// What the LLM proposed
+    pub proposed: String,
+    /// What the user ran instead
+    pub executed: String,
+}
+
+/// Edited translations, fed back into the prompt as examples
+///
+/// Only recorded when `llm.learn_from_edits` is enabled. Stored locally in
+/// `~/.config/gitalky/corrections.toml`, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Corrections {
+    #[serde(default, rename = "correction")]
+    entries: Vec<Correction>,
+}
+
+impl Corrections {
+    /// Path of the corrections file
+    pub fn file_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join(CORRECTIONS_FILE))
+    }
+
+    /// Load from `~/.config/gitalky/corrections.toml`; a missing file is empty
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::file_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&Self::file_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Remember a correction, replacing an identical earlier one
+    ///
+    /// Keeps at most [`MAX_CORRECTIONS_PER_REPO`] per repository.
+    pub fn record(&mut self, correction: Correction) {
+        if correction.proposed.trim() == correction.executed.trim() {
+            return;
+        }
+        self.entries.retain(|c| {
+            !(c.repo == correction.repo
+                && c.proposed == correction.proposed
+                && c.executed == correction.executed)
+        });
+        let repo = correction.repo.clone();
+        self.entries.push(correction);
+
+        let excess = self.for_repo(&repo).count().saturating_sub(MAX_CORRECTIONS_PER_REPO);
+        let mut dropped = 0;
+        self.entries.retain(|c| {
+            let drop = dropped < excess && c.repo == repo;
+            dropped += usize::from(drop);
+            !drop
+        });
+    }
+
+    /// Corrections made in `repo`, oldest first
+    pub fn for_repo<'a>(&'a self, repo: &'a Path) -> impl Iterator<Item = &'a Correction> + 'a {
+        self.entries.iter().filter(move |c| c.repo == repo)
+    }
+
+    /// The most recent corrections for `repo`, oldest first
+    pub fn recent(&self, repo: &Path) -> Vec<Correction> {
+        let all: Vec<_> = self.for_repo(repo).cloned().collect();
+        all[all.len().saturating_sub(PROMPT_CORRECTIONS)..].to_vec()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn correction(repo: &str, n: usize) -> Correction {
+        Correction {
+            repo: PathBuf::from(repo),
+            query: format!("query {}", n),
+            proposed: format!("git log -{}", n),
+            executed: format!("git log --oneline -{}", n),
+        }
+    }
+
+    #[test]
+    fn test_record_caps_per_repo() {
+        let mut corrections = Corrections::default();
+        corrections.record(correction("/other", 0));
+        for n in 0..MAX_CORRECTIONS_PER_REPO + 3 {
+            corrections.record(correction("/repo", n));
+        }
+
+        let repo: Vec<_> = corrections.for_repo(Path::new("/repo")).collect();
+        assert_eq!(repo.len(), MAX_CORRECTIONS_PER_REPO);
+        assert_eq!(repo[0].query, "query 3");
+        // Other repositories keep their corrections
+        assert_eq!(corrections.for_repo(Path::new("/other")).count(), 1);
+    }
+
+    #[test]
+    fn test_record_skips_unchanged_and_dedups() {
+        let mut corrections = Corrections::default();
+        let mut same = correction("/repo", 1);
+        same.executed = same.proposed.clone();
+        corrections.record(same);
+        assert!(corrections.is_empty());
+
+        corrections.record(correction("/repo", 1));
+        corrections.record(correction("/repo", 2));
+        corrections.record(correction("/repo", 1));
+        let queries: Vec<_> = corrections.for_repo(Path::new("/repo")).map(|c| c.query.as_str()).collect();
+        assert_eq!(queries, ["query 2", "query 1"]);
+    }
+
+    #[test]
+    fn test_recent_keeps_latest() {
+        let mut corrections = Corrections::default();
+        for n in 0..PROMPT_CORRECTIONS + 2 {
+            corrections.record(correction("/repo", n));
+        }
+        let recent = corrections.recent(Path::new("/repo"));
+        assert_eq!(recent.len(), PROMPT_CORRECTIONS);
+        assert_eq!(recent.last().unwrap().query, format!("query {}", PROMPT_CORRECTIONS + 1));
+        assert!(corrections.recent(Path::new("/none")).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("gitalky").join(CORRECTIONS_FILE);
+        assert_eq!(Corrections::load_from(&file).unwrap(), Corrections::default());
+
+        let mut corrections = Corrections::default();
+        corrections.record(correction("/repo", 1));
+        corrections.save_to(&file).unwrap();
+        assert_eq!(Corrections::load_from(&file).unwrap(), corrections);
+    }
+}
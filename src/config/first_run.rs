@@ -1,4 +1,6 @@
+use super::network::{NetworkConfig, describe_network_error};
 use super::settings::{Config, ConfigError};
+use crate::llm::{AnthropicClient, KNOWN_MODELS, ModelCheck, check_model};
 use std::io::{self, Write};
 use std::time::Duration;
 use thiserror::Error;
@@ -24,6 +26,7 @@ pub enum SetupStep {
     SelectProvider,
     SelectKeySource,
     EnterAPIKey,
+    SelectModel,
     ValidateAPI,
     Complete,
 }
@@ -64,20 +67,19 @@ impl FirstRunWizard {
         // Select key source (env var or direct input)
         let use_env = wizard.select_key_source()?;
 
-        if use_env {
-            // Use environment variable
-            wizard.step = SetupStep::ValidateAPI;
-            wizard.validate_api().await?;
-        } else {
+        if !use_env {
             // Enter API key directly
             wizard.step = SetupStep::EnterAPIKey;
             let api_key = wizard.enter_api_key()?;
             wizard.config.llm.api_key = Some(api_key);
-
-            wizard.step = SetupStep::ValidateAPI;
-            wizard.validate_api().await?;
         }
 
+        wizard.step = SetupStep::SelectModel;
+        wizard.select_model().await?;
+
+        wizard.step = SetupStep::ValidateAPI;
+        wizard.validate_api().await?;
+
         wizard.step = SetupStep::Complete;
         wizard.show_complete(true)?;
 
@@ -201,7 +203,97 @@ impl FirstRunWizard {
         Ok(api_key)
     }
 
-    async fn validate_api(&self) -> Result<(), SetupError> {
+    async fn select_model(&mut self) -> Result<(), SetupError> {
+        println!("\n{}", "-".repeat(70));
+        println!("Model Selection");
+        println!("{}", "-".repeat(70));
+
+        // Prefer what this key can actually use; fall back to the built-in list
+        let listed = match self.config.get_api_key() {
+            Some(api_key) => Self::fetch_models(api_key, &self.config.network).await,
+            None => None,
+        };
+        if listed.is_none() {
+            println!("\n(Couldn't fetch the model list from the API; showing known models.)");
+        }
+        let choices = Self::model_choices(listed);
+
+        println!("\nSelect the Claude model to use:");
+        for (i, (id, description)) in choices.iter().enumerate() {
+            let current = if *id == self.config.llm.model { " (current)" } else { "" };
+            println!("  [{}] {} - {}{}", i + 1, id, description, current);
+        }
+        print!(
+            "\nEnter a number or a model id [Enter keeps {}]: ",
+            self.config.llm.model
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match Self::parse_model_choice(input.trim(), &choices) {
+            Some(model) => {
+                if let ModelCheck::Unknown { .. } = check_model(&model, &self.config.llm.extra_models) {
+                    // Typed ids are trusted; remember them so startup doesn't warn
+                    self.config.llm.extra_models.push(model.clone());
+                }
+                println!("\n✓ Using {}.", model);
+                self.config.llm.model = model;
+                Ok(())
+            }
+            None if input.trim().is_empty() => {
+                println!("\n✓ Using {}.", self.config.llm.model);
+                Ok(())
+            }
+            None => {
+                println!("\n⚠️  Invalid choice. Enter a number from the list or a model id.");
+                Box::pin(self.select_model()).await
+            }
+        }
+    }
+
+    async fn fetch_models(api_key: String, network: &NetworkConfig) -> Option<Vec<String>> {
+        let client = AnthropicClient::new(api_key)
+            .with_network(Duration::from_secs(5), Duration::from_secs(5), network)
+            .ok()?;
+        client.list_models().await.ok().filter(|models| !models.is_empty())
+    }
+
+    /// Pick-list entries: the API's models when listed, described where known
+    fn model_choices(listed: Option<Vec<String>>) -> Vec<(String, String)> {
+        let describe = |id: &str| {
+            KNOWN_MODELS
+                .iter()
+                .find(|(known, _)| *known == id)
+                .map(|(_, description)| description.to_string())
+                .unwrap_or_default()
+        };
+        match listed {
+            Some(models) => models
+                .into_iter()
+                .filter(|id| id.starts_with("claude-"))
+                .map(|id| {
+                    let description = describe(&id);
+                    (id, description)
+                })
+                .collect(),
+            None => KNOWN_MODELS
+                .iter()
+                .map(|(id, description)| (id.to_string(), description.to_string()))
+                .collect(),
+        }
+    }
+
+    /// A list number or a `claude-` model id; `None` for anything else
+    fn parse_model_choice(input: &str, choices: &[(String, String)]) -> Option<String> {
+        if let Ok(n) = input.parse::<usize>() {
+            return n.checked_sub(1).and_then(|i| choices.get(i)).map(|(id, _)| id.clone());
+        }
+        input.starts_with("claude-").then(|| input.to_string())
+    }
+
+    async fn validate_api(&mut self) -> Result<(), SetupError> {
         println!("\n{}", "-".repeat(70));
         println!("Validating API Connection");
         println!("{}", "-".repeat(70));
@@ -235,7 +327,7 @@ impl FirstRunWizard {
 
         // Perform actual validation using a simple API test
         let api_key = api_key.unwrap();
-        match Self::test_api_connection(&api_key, &self.config.llm.model).await {
+        match Self::test_api_connection(&api_key, &self.config.llm.model, &self.config.network).await {
             Ok(_) => {
                 println!("\n✓ API connection successful!");
                 Ok(())
@@ -245,7 +337,8 @@ impl FirstRunWizard {
                 println!("\nWould you like to:");
                 println!("  [1] Try again with a different key");
                 println!("  [2] Skip validation and continue");
-                print!("\nEnter your choice [1-2]: ");
+                println!("  [3] Configure a proxy or CA bundle and retry");
+                print!("\nEnter your choice [1-3]: ");
                 io::stdout().flush()?;
 
                 let mut input = String::new();
@@ -258,15 +351,51 @@ impl FirstRunWizard {
                         println!("\n⚠️  Skipping validation. The app may not work correctly.");
                         Ok(())
                     }
+                    "3" => {
+                        self.enter_network_settings()?;
+                        Box::pin(self.validate_api()).await
+                    }
                     _ => Box::pin(self.validate_api()).await,
                 }
             }
         }
     }
 
-    async fn test_api_connection(api_key: &str, model: &str) -> Result<(), String> {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(5))
+    /// Ask for `[network]` settings (enterprise proxies and TLS inspection)
+    fn enter_network_settings(&mut self) -> Result<(), SetupError> {
+        println!("\n{}", "-".repeat(70));
+        println!("Network Settings");
+        println!("{}", "-".repeat(70));
+        println!("\nLeave a field empty to skip it.");
+
+        let network = NetworkConfig {
+            proxy: Self::prompt_optional("Proxy URL (e.g. http://proxy.corp:3128): ")?,
+            ca_bundle: Self::prompt_optional("CA bundle to trust (PEM file path): ")?.map(Into::into),
+            no_proxy: Self::prompt_optional("Hosts that bypass the proxy (comma-separated): ")?,
+        };
+
+        match network.validate() {
+            Ok(()) => self.config.network = network,
+            Err(e) => println!("\n⚠️  {}; keeping the previous network settings.", e),
+        }
+        Ok(())
+    }
+
+    fn prompt_optional(prompt: &str) -> Result<Option<String>, SetupError> {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let value = input.trim();
+        Ok((!value.is_empty()).then(|| value.to_string()))
+    }
+
+    async fn test_api_connection(api_key: &str, model: &str, network: &NetworkConfig) -> Result<(), String> {
+        let builder = reqwest::Client::builder().timeout(Duration::from_secs(5));
+        let client = network
+            .apply(builder)
+            .map_err(|e| e.to_string())?
             .build()
             .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
@@ -287,7 +416,7 @@ impl FirstRunWizard {
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+            .map_err(|e| format!("Request failed: {}", describe_network_error(&e)))?;
 
         if response.status().is_success() {
             Ok(())
@@ -328,6 +457,12 @@ impl FirstRunWizard {
     }
 }
 
+impl Default for FirstRunWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -338,6 +473,38 @@ mod tests {
         assert_eq!(wizard.step, SetupStep::Welcome);
     }
 
+    #[test]
+    fn test_model_choices() {
+        let fallback = FirstRunWizard::model_choices(None);
+        assert_eq!(fallback.len(), KNOWN_MODELS.len());
+
+        let listed = FirstRunWizard::model_choices(Some(vec![
+            "claude-sonnet-4-20250514".to_string(),
+            "claude-future-9".to_string(),
+            "embedding-model".to_string(),
+        ]));
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].1, "Claude Sonnet 4");
+        assert_eq!(listed[1], ("claude-future-9".to_string(), String::new()));
+    }
+
+    #[test]
+    fn test_parse_model_choice() {
+        let choices = FirstRunWizard::model_choices(None);
+        assert_eq!(
+            FirstRunWizard::parse_model_choice("1", &choices),
+            Some(KNOWN_MODELS[0].0.to_string())
+        );
+        assert_eq!(FirstRunWizard::parse_model_choice("0", &choices), None);
+        assert_eq!(FirstRunWizard::parse_model_choice("99", &choices), None);
+        assert_eq!(
+            FirstRunWizard::parse_model_choice("claude-custom-1", &choices),
+            Some("claude-custom-1".to_string())
+        );
+        assert_eq!(FirstRunWizard::parse_model_choice("gpt-4", &choices), None);
+        assert_eq!(FirstRunWizard::parse_model_choice("", &choices), None);
+    }
+
     #[test]
     fn test_setup_steps() {
         assert_ne!(SetupStep::Welcome, SetupStep::Complete);
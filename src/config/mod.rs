@@ -1,5 +1,19 @@
 pub mod settings;
+pub mod corrections;
 pub mod first_run;
+pub mod identity;
+pub mod network;
+pub mod rate_limit;
+pub mod recent;
+pub mod startup;
+pub mod trust;
 
-pub use settings::{Config, LLMConfig, UIConfig, BehaviorConfig, GitConfig};
+pub use settings::{Config, LLMConfig, LlmFeatures, UIConfig, BehaviorConfig, GitConfig};
+pub use corrections::{Correction, Corrections, FORGET_CORRECTIONS_COMMAND};
+pub use identity::{IDENTITY_COMMAND, IdentityProfile, IdentityRule};
+pub use network::{NetworkConfig, describe_network_error};
 pub use first_run::{FirstRunWizard, SetupStep};
+pub use rate_limit::{DangerLedger, RateLimit, RateLimitHit};
+pub use recent::RecentRepos;
+pub use startup::{StartupChoice, StartupWizard};
+pub use trust::{TRUST_COMMAND, TrustLevel, TrustStore};
@@ -0,0 +1,325 @@
+use crate::config::settings::{Config, ConfigError};
+use crate::security::DangerousOp;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration as StdDuration, SystemTime};
+
+/// File under the config dir holding when rate-limited operations ran
+const DANGER_RUNS_FILE: &str = "danger_runs.toml";
+
+/// How long `record_saved` waits for another process to finish updating the ledger
+const LOCK_TIMEOUT: StdDuration = StdDuration::from_secs(2);
+
+/// A lockfile older than this was left behind by a process that died holding it
+const STALE_LOCK_AGE: StdDuration = StdDuration::from_secs(30);
+
+/// `[rate_limits.<operation>]`: at most `max` runs within `window_minutes`
+///
+/// ```toml
+/// [rate_limits.force_push]
+/// max = 2
+/// window_minutes = 10
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RateLimit {
+    pub max: u32,
+    pub window_minutes: u32,
+}
+
+impl RateLimit {
+    fn window(&self) -> Duration {
+        Duration::minutes(i64::from(self.window_minutes))
+    }
+}
+
+/// A dangerous operation that used up its limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitHit {
+    pub op: DangerousOp,
+    pub limit: RateLimit,
+    /// Runs within the window, overrides included
+    pub count: usize,
+    /// When enough runs leave the window for another; never with `max = 0`
+    pub frees_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimitHit {
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let again = match self.frees_at {
+            Some(at) => format!("allowed again in {} min", ((at - now).num_seconds().max(1) as u64).div_ceil(60)),
+            None => "never allowed without an override".to_string(),
+        };
+        format!(
+            "Rate limit: {:?} ran {} time(s) in the last {} min (max {}); {}",
+            self.op, self.count, self.limit.window_minutes, self.limit.max, again
+        )
+    }
+
+    /// Text to type to run it anyway, e.g. `override force_push`
+    pub fn override_phrase(&self) -> String {
+        let key = serde_json::to_value(&self.op)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_else(|| format!("{:?}", self.op));
+        format!("override {}", key)
+    }
+}
+
+/// When each rate-limited operation last ran, so `[rate_limits]` hold across
+/// sessions, `gitalky run` calls and `gitalky serve`
+///
+/// Only operations with a limit are recorded, and only runs still inside
+/// their window are kept. Stored in `~/.config/gitalky/danger_runs.toml` as
+/// Unix timestamps.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DangerLedger {
+    #[serde(default)]
+    runs: BTreeMap<DangerousOp, Vec<i64>>,
+}
+
+impl DangerLedger {
+    /// Path of the ledger file
+    pub fn file_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join(DANGER_RUNS_FILE))
+    }
+
+    /// Load from `path`; a missing file has no runs
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Write to `path` through a temporary file, so readers never see half a ledger
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = sibling(path, "tmp");
+        fs::write(&temp, toml::to_string(self)?)?;
+        fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// The limit `op` has used up at `now`, if any
+    pub fn check(
+        &self,
+        op: &DangerousOp,
+        limits: &BTreeMap<DangerousOp, RateLimit>,
+        now: DateTime<Utc>,
+    ) -> Option<RateLimitHit> {
+        let limit = *limits.get(op)?;
+        let start = (now - limit.window()).timestamp();
+        let mut recent: Vec<i64> = self
+            .runs
+            .get(op)
+            .map(|runs| runs.iter().copied().filter(|at| *at > start).collect())
+            .unwrap_or_default();
+        if recent.len() < limit.max as usize {
+            return None;
+        }
+        recent.sort_unstable();
+        // The run whose leaving the window brings the count under the limit
+        let frees_at = match limit.max as usize {
+            0 => None,
+            max => DateTime::from_timestamp(recent[recent.len() - max], 0).map(|at| at + limit.window()),
+        };
+        Some(RateLimitHit { op: op.clone(), limit, count: recent.len(), frees_at })
+    }
+
+    /// Note that `op` ran at `now`, when it has a limit
+    pub fn record(&mut self, op: &DangerousOp, limits: &BTreeMap<DangerousOp, RateLimit>, now: DateTime<Utc>) {
+        let Some(limit) = limits.get(op) else {
+            return;
+        };
+        let start = (now - limit.window()).timestamp();
+        let runs = self.runs.entry(op.clone()).or_default();
+        runs.retain(|at| *at > start);
+        runs.push(now.timestamp());
+    }
+
+    /// The ledger path for messages, its file name when there's no config dir
+    pub fn display_path(path: Option<&Path>) -> String {
+        path.map_or_else(|| DANGER_RUNS_FILE.to_string(), |path| path.display().to_string())
+    }
+
+    /// Check `op` against the ledger saved at `path`
+    ///
+    /// A ledger that can't be read, or can't be found for want of a config
+    /// dir, is an error rather than an empty one, so it doesn't lift every limit.
+    pub fn check_saved(
+        path: Option<&Path>,
+        op: &DangerousOp,
+        limits: &BTreeMap<DangerousOp, RateLimit>,
+    ) -> Result<Option<RateLimitHit>, ConfigError> {
+        if !limits.contains_key(op) {
+            return Ok(None);
+        }
+        let path = path.ok_or(ConfigError::DirectoryNotFound)?;
+        Ok(Self::load_from(path)?.check(op, limits, Utc::now()))
+    }
+
+    /// Record a run of `op` in the ledger saved at `path`
+    ///
+    /// Holds `<path>.lock` while reading and rewriting the file, so runs
+    /// recorded at once by the TUI, `gitalky run` and `gitalky serve` all count.
+    pub fn record_saved(
+        path: Option<&Path>,
+        op: &DangerousOp,
+        limits: &BTreeMap<DangerousOp, RateLimit>,
+    ) -> Result<(), ConfigError> {
+        if !limits.contains_key(op) {
+            return Ok(());
+        }
+        let path = path.ok_or(ConfigError::DirectoryNotFound)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let _lock = LedgerLock::acquire(&sibling(path, "lock"))?;
+        let mut ledger = Self::load_from(path)?;
+        ledger.record(op, limits, Utc::now());
+        ledger.save_to(path)
+    }
+}
+
+/// `path` with `.extension` appended: `danger_runs.toml.lock`
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Exclusive lock on the ledger, held by creating its lockfile; removed on drop
+struct LedgerLock {
+    path: PathBuf,
+}
+
+impl LedgerLock {
+    fn acquire(path: &Path) -> io::Result<Self> {
+        let deadline = SystemTime::now() + LOCK_TIMEOUT;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(Self { path: path.to_path_buf() }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(path)
+                        .and_then(|meta| meta.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                    if stale {
+                        let _ = fs::remove_file(path);
+                    } else if SystemTime::now() >= deadline {
+                        return Err(io::Error::new(
+                            ErrorKind::WouldBlock,
+                            format!("{} is held by another process", path.display()),
+                        ));
+                    } else {
+                        std::thread::sleep(StdDuration::from_millis(10));
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for LedgerLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn limits() -> BTreeMap<DangerousOp, RateLimit> {
+        BTreeMap::from([(DangerousOp::ForcePush, RateLimit { max: 2, window_minutes: 10 })])
+    }
+
+    #[test]
+    fn test_limit_per_window() {
+        let limits = limits();
+        let start = Utc::now();
+        let mut ledger = DangerLedger::default();
+        ledger.record(&DangerousOp::ForcePush, &limits, start);
+        assert_eq!(ledger.check(&DangerousOp::ForcePush, &limits, start), None);
+        ledger.record(&DangerousOp::ForcePush, &limits, start + Duration::minutes(4));
+
+        let now = start + Duration::minutes(5);
+        let hit = ledger.check(&DangerousOp::ForcePush, &limits, now).unwrap();
+        assert_eq!(hit.count, 2);
+        assert_eq!(hit.frees_at.map(|at| at.timestamp()), Some((start + Duration::minutes(10)).timestamp()));
+        assert!(hit.describe(now).ends_with("allowed again in 5 min"), "{}", hit.describe(now));
+        assert_eq!(hit.override_phrase(), "override force_push");
+
+        // The first run leaves the window; other operations aren't limited or recorded
+        assert_eq!(ledger.check(&DangerousOp::ForcePush, &limits, start + Duration::minutes(11)), None);
+        ledger.record(&DangerousOp::HardReset, &limits, now);
+        assert_eq!(ledger.check(&DangerousOp::HardReset, &limits, now), None);
+        assert!(!ledger.runs.contains_key(&DangerousOp::HardReset));
+    }
+
+    #[test]
+    fn test_zero_max_never_frees() {
+        let limits = BTreeMap::from([(DangerousOp::GcPruneNow, RateLimit { max: 0, window_minutes: 60 })]);
+        let hit = DangerLedger::default().check(&DangerousOp::GcPruneNow, &limits, Utc::now()).unwrap();
+        assert_eq!((hit.count, hit.frees_at), (0, None));
+    }
+
+    #[test]
+    fn test_saved_ledger() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("gitalky").join(DANGER_RUNS_FILE);
+        let limits = limits();
+        for _ in 0..2 {
+            assert_eq!(DangerLedger::check_saved(Some(&path), &DangerousOp::ForcePush, &limits).unwrap(), None);
+            DangerLedger::record_saved(Some(&path), &DangerousOp::ForcePush, &limits).unwrap();
+        }
+        assert!(DangerLedger::check_saved(Some(&path), &DangerousOp::ForcePush, &limits).unwrap().is_some());
+        assert!(fs::read_to_string(&path).unwrap().contains("force_push"));
+        assert!(!sibling(&path, "lock").exists());
+
+        // A corrupt ledger fails closed instead of counting as empty
+        fs::write(&path, "runs = [").unwrap();
+        assert!(DangerLedger::check_saved(Some(&path), &DangerousOp::ForcePush, &limits).is_err());
+        assert!(DangerLedger::record_saved(Some(&path), &DangerousOp::ForcePush, &limits).is_err());
+
+        // So does having nowhere to keep it, for limited operations only
+        assert!(DangerLedger::check_saved(None, &DangerousOp::ForcePush, &limits).is_err());
+        assert!(DangerLedger::record_saved(None, &DangerousOp::ForcePush, &limits).is_err());
+        assert_eq!(DangerLedger::check_saved(None, &DangerousOp::Clean, &limits).unwrap(), None);
+    }
+
+    #[test]
+    fn test_concurrent_records_all_count() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(DANGER_RUNS_FILE);
+        let limits = BTreeMap::from([(DangerousOp::ForcePush, RateLimit { max: 100, window_minutes: 10 })]);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| DangerLedger::record_saved(Some(&path), &DangerousOp::ForcePush, &limits).unwrap());
+            }
+        });
+        let ledger = DangerLedger::load_from(&path).unwrap();
+        assert_eq!(ledger.runs[&DangerousOp::ForcePush].len(), 8);
+    }
+
+    #[test]
+    fn test_stale_lock_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(DANGER_RUNS_FILE);
+        let lock = sibling(&path, "lock");
+        let file = fs::File::create(&lock).unwrap();
+        file.set_modified(SystemTime::now() - STALE_LOCK_AGE * 2).unwrap();
+        DangerLedger::record_saved(Some(&path), &DangerousOp::ForcePush, &limits()).unwrap();
+        assert!(!lock.exists());
+    }
+}
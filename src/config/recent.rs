@@ -0,0 +1,139 @@
+use crate::config::settings::{Config, ConfigError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many repositories the startup screen remembers
+pub const MAX_RECENT_REPOS: usize = 10;
+
+/// File under the config dir holding one repository path per line
+const RECENT_REPOS_FILE: &str = "recent_repos";
+
+/// Repositories gitalky was recently opened in, most recent first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentRepos {
+    paths: Vec<PathBuf>,
+}
+
+impl RecentRepos {
+    /// Path of the recent repositories file
+    pub fn file_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join(RECENT_REPOS_FILE))
+    }
+
+    /// Load the list from `~/.config/gitalky/recent_repos`
+    ///
+    /// A missing file is an empty list.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::file_path()?)
+    }
+
+    /// Load the list from a specific file
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let paths = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .take(MAX_RECENT_REPOS)
+            .collect();
+        Ok(Self { paths })
+    }
+
+    /// Save the list to `~/.config/gitalky/recent_repos`
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&Self::file_path()?)
+    }
+
+    /// Save the list to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut content = String::new();
+        for repo in &self.paths {
+            content.push_str(&repo.display().to_string());
+            content.push('\n');
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Move a repository to the front of the list
+    pub fn record(&mut self, repo: &Path) {
+        self.paths.retain(|p| p != repo);
+        self.paths.insert(0, repo.to_path_buf());
+        self.paths.truncate(MAX_RECENT_REPOS);
+    }
+
+    /// Recent repositories that still exist on disk
+    pub fn existing(&self) -> Vec<&Path> {
+        self.paths
+            .iter()
+            .filter(|p| p.join(".git").exists())
+            .map(PathBuf::as_path)
+            .collect()
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_moves_to_front_and_dedups() {
+        let mut recent = RecentRepos::default();
+        recent.record(Path::new("/a"));
+        recent.record(Path::new("/b"));
+        recent.record(Path::new("/a"));
+
+        assert_eq!(recent.paths(), &[PathBuf::from("/a"), PathBuf::from("/b")]);
+    }
+
+    #[test]
+    fn test_record_is_capped() {
+        let mut recent = RecentRepos::default();
+        for i in 0..MAX_RECENT_REPOS + 5 {
+            recent.record(&PathBuf::from(format!("/repo{}", i)));
+        }
+
+        assert_eq!(recent.paths().len(), MAX_RECENT_REPOS);
+        assert_eq!(recent.paths()[0], PathBuf::from(format!("/repo{}", MAX_RECENT_REPOS + 4)));
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("gitalky").join(RECENT_REPOS_FILE);
+
+        assert_eq!(RecentRepos::load_from(&file).unwrap(), RecentRepos::default());
+
+        let mut recent = RecentRepos::default();
+        recent.record(Path::new("/home/me/project"));
+        recent.record(Path::new("/home/me/dotfiles"));
+        recent.save_to(&file).unwrap();
+
+        assert_eq!(RecentRepos::load_from(&file).unwrap(), recent);
+    }
+
+    #[test]
+    fn test_existing_skips_missing_repos() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+
+        let mut recent = RecentRepos::default();
+        recent.record(&temp.path().join("deleted"));
+        recent.record(&repo);
+
+        assert_eq!(recent.existing(), vec![repo.as_path()]);
+    }
+}
@@ -1,4 +1,16 @@
+use crate::config::identity::{
+    IdentityProfile, IdentityRule, validate_identities, validate_identity_rules,
+};
+use crate::audit::{AuditSinkConfig, RetentionPolicy};
+use crate::config::network::NetworkConfig;
+use crate::config::rate_limit::RateLimit;
+use crate::git::{BranchPolicy, DEFAULT_PARSE_LOCALE, DEFAULT_RENAME_THRESHOLD};
+use crate::hooks::{ApprovalConfig, HookConfig};
+use crate::llm::mcp::McpConfig;
+use crate::notify::TerminalNotifications;
+use crate::security::{ALLOWED_GIT_SUBCOMMANDS, ConfirmationPolicy, DangerousOp};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -27,6 +39,36 @@ pub struct Config {
     pub ui: UIConfig,
     pub behavior: BehaviorConfig,
     pub git: GitConfig,
+    /// External executables run around the command lifecycle
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookConfig>,
+    /// External MCP servers that contribute translation context
+    #[serde(default, skip_serializing_if = "McpConfig::is_empty")]
+    pub mcp: McpConfig,
+    /// Proxy and CA settings for requests to the LLM API
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_empty")]
+    pub network: NetworkConfig,
+    /// How dangerous operations are confirmed, per operation
+    #[serde(default, skip_serializing_if = "ConfirmationPolicy::is_default")]
+    pub confirmation: ConfirmationPolicy,
+    /// How often each dangerous operation may run before it takes a typed override
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rate_limits: BTreeMap<DangerousOp, RateLimit>,
+    /// Author identities to switch between with `:identity <label>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identities: Vec<IdentityProfile>,
+    /// Emails allowed per remote host, checked before each commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identity_rules: Vec<IdentityRule>,
+    /// How many rotated audit logs are kept, compressed or not
+    #[serde(default, skip_serializing_if = "RetentionPolicy::is_default")]
+    pub audit: RetentionPolicy,
+    /// Syslog, webhook or SQLite destinations for audit records besides the log
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audit_sinks: Vec<AuditSinkConfig>,
+    /// Endpoint that must approve risky commands before they run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<ApprovalConfig>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -36,6 +78,132 @@ pub struct LLMConfig {
     pub api_key_env: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
+    /// Remember edits to translated commands and show recent ones to the LLM
+    /// as examples for this repository
+    #[serde(default)]
+    pub learn_from_edits: bool,
+    /// Times to send a rejected command back to the LLM with the reason
+    #[serde(default = "default_repair_attempts")]
+    pub repair_attempts: u32,
+    /// Seconds allowed to connect to the LLM API
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+    /// Seconds allowed for a whole LLM request, connection included
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Seconds of translating before offering to keep waiting, cancel or go offline
+    #[serde(default = "default_soft_budget_secs")]
+    pub soft_budget_secs: u64,
+    /// Upper bound on tokens in each LLM response
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32,
+    /// Sampling temperature (0.0-1.0); low values keep commands consistent
+    #[serde(default = "default_temperature", skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Nucleus sampling cutoff (0.0-1.0); usually left unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Replaces the built-in translation instructions sent as the system prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Model ids to accept besides the built-in list, e.g. newly released ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_models: Vec<String>,
+    /// Show a translation beneath the input after a pause in typing (Tab accepts)
+    #[serde(default)]
+    pub live_suggest: bool,
+    /// Cheaper model used for live suggestions
+    #[serde(default = "default_live_suggest_model")]
+    pub live_suggest_model: String,
+    /// Milliseconds without typing before a live suggestion is requested
+    #[serde(default = "default_live_suggest_delay_ms")]
+    pub live_suggest_delay_ms: u64,
+    /// Prebuild translation context in the background after each refresh,
+    /// so a query only waits for the API
+    #[serde(default)]
+    pub warmup_context: bool,
+    /// Offer two commands side by side for vague queries like "clean this up"
+    #[serde(default)]
+    pub alternatives: bool,
+    /// Which features may call the LLM; the others work as when offline
+    #[serde(default, skip_serializing_if = "LlmFeatures::all_enabled")]
+    pub features: LlmFeatures,
+}
+
+/// LLM-powered features that can be switched off one by one, e.g. keeping
+/// queries away from the API for compliance while explanations still use it
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct LlmFeatures {
+    /// Natural-language queries, multi-step plans and live suggestions
+    #[serde(default = "default_feature_enabled")]
+    pub translate: bool,
+    /// `x` explanations of previewed commands; the built-in flag table otherwise
+    #[serde(default = "default_feature_enabled")]
+    pub explain: bool,
+}
+
+fn default_feature_enabled() -> bool {
+    true
+}
+
+impl Default for LlmFeatures {
+    fn default() -> Self {
+        Self {
+            translate: true,
+            explain: true,
+        }
+    }
+}
+
+impl LlmFeatures {
+    pub fn all_enabled(&self) -> bool {
+        self.translate && self.explain
+    }
+
+    /// Whether anything needs an LLM connection at all
+    pub fn any_enabled(&self) -> bool {
+        self.translate || self.explain
+    }
+
+    /// Names of the enabled features, for the title when only some are
+    pub fn enabled_names(&self) -> Vec<&'static str> {
+        [(self.translate, "translate"), (self.explain, "explain")]
+            .into_iter()
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect()
+    }
+}
+
+fn default_live_suggest_model() -> String {
+    "claude-3-5-haiku-latest".to_string()
+}
+
+fn default_live_suggest_delay_ms() -> u64 {
+    800
+}
+
+fn default_repair_attempts() -> u32 {
+    1
+}
+
+fn default_max_tokens() -> u32 {
+    1024
+}
+
+fn default_temperature() -> Option<f32> {
+    Some(0.0)
+}
+
+fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_request_timeout_secs() -> u64 {
+    30
+}
+
+fn default_soft_budget_secs() -> u64 {
+    8
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -44,6 +212,9 @@ pub struct UIConfig {
     pub max_commits_display: usize,
     pub max_stashes_display: usize,
     pub show_line_numbers: bool,
+    /// Editor for "open in editor"; falls back to `$VISUAL`, then `$EDITOR`
+    #[serde(default)]
+    pub editor: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -51,11 +222,121 @@ pub struct BehaviorConfig {
     pub auto_refresh: bool,
     pub confirm_dangerous_ops: bool,
     pub log_commands: bool,
+    /// Explain directly typed git commands in the preview before running them
+    #[serde(default)]
+    pub explain_direct_commands: bool,
+    /// Desktop notification when push/fetch/pull/clone finishes while unfocused
+    #[serde(default)]
+    pub notify_long_commands: bool,
+    /// Seconds a remote command must run before it triggers a notification
+    #[serde(default = "default_notify_after_secs")]
+    pub notify_after_secs: u64,
+    /// Scan what a commit will contain for likely secrets before running it
+    #[serde(default)]
+    pub scan_secrets: bool,
+    /// Staged files larger than this (KB) are flagged in the commit preview
+    #[serde(default = "default_large_file_kb")]
+    pub large_file_kb: u64,
+    /// Record translation timings, cancelled previews and error kinds in the
+    /// audit log for the local `:stats` screen; nothing leaves the machine
+    #[serde(default)]
+    pub local_stats: bool,
+    /// Show "gitalky — <repo> (<branch>)" as the terminal title
+    #[serde(default = "default_terminal_title")]
+    pub terminal_title: bool,
+    /// Announce long commands with OSC 9/777 escapes instead of the desktop notifier
+    #[serde(default)]
+    pub terminal_notifications: TerminalNotifications,
+    /// Try dangerous commands on a throwaway copy of the repository before
+    /// asking to confirm them
+    #[serde(default)]
+    pub dry_run_dangerous: bool,
+    /// Run translations of `instant_commands` straight away, without a preview
+    #[serde(default)]
+    pub auto_execute_safe: bool,
+    /// Subcommands that skip the preview when `auto_execute_safe` is on;
+    /// commands that could modify the repository are previewed regardless
+    #[serde(default = "default_instant_commands")]
+    pub instant_commands: Vec<String>,
+    /// Ask whether to trust each new repository; untrusted ones only run
+    /// read-only commands
+    #[serde(default = "default_repo_trust")]
+    pub repo_trust: bool,
+    /// Keep queries and what became of their commands in a local SQLite
+    /// database, for recall with Up and the `:stats` screen
+    #[serde(default = "default_query_history")]
+    pub query_history: bool,
+}
+
+fn default_query_history() -> bool {
+    true
+}
+
+fn default_repo_trust() -> bool {
+    true
+}
+
+fn default_instant_commands() -> Vec<String> {
+    ["status", "log", "diff", "show"].iter().map(|s| s.to_string()).collect()
+}
+
+fn default_notify_after_secs() -> u64 {
+    10
+}
+
+fn default_terminal_title() -> bool {
+    true
+}
+
+fn default_large_file_kb() -> u64 {
+    1024
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct GitConfig {
     pub timeout_seconds: u64,
+    /// Extra directories `clone`/`init` may create repositories in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects_dirs: Vec<PathBuf>,
+    /// Similarity (percent) for rename and copy detection in previews and LLM context
+    #[serde(default = "default_rename_threshold")]
+    pub rename_threshold: u8,
+    /// Locale for git commands whose output is parsed; empty keeps the user's
+    #[serde(default = "default_parse_locale")]
+    pub parse_locale: String,
+    /// Git to run instead of `git` from PATH, e.g. `/opt/homebrew/bin/git`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_path: Option<PathBuf>,
+    /// Remote branch to show ahead/behind against next to the upstream,
+    /// e.g. `upstream/main` when `origin` is your fork
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub second_remote: Option<String>,
+    /// Hosts remote URLs may point at (`git.corp.example`, `*.github.com`);
+    /// empty allows any
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    /// Regex new branch names must match, e.g. `^(feat|fix|chore)/[a-z0-9-]+$`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_pattern: Option<String>,
+    /// Badge recent commits with their signature status; runs gpg for each
+    /// signed one on every refresh
+    #[serde(default)]
+    pub verify_signatures: bool,
+}
+
+fn default_parse_locale() -> String {
+    DEFAULT_PARSE_LOCALE.to_string()
+}
+
+impl GitConfig {
+    /// The locale to pin parsed commands to, if any
+    pub fn parse_locale(&self) -> Option<String> {
+        Some(self.parse_locale.clone()).filter(|locale| !locale.is_empty())
+    }
+}
+
+fn default_rename_threshold() -> u8 {
+    DEFAULT_RENAME_THRESHOLD
 }
 
 impl Config {
@@ -93,6 +374,17 @@ impl Config {
         Ok(config)
     }
 
+    /// `loaded` (from [`Config::load`]), or the defaults when there is no
+    /// config file to load; one that exists but doesn't load stays an error,
+    /// so its protections aren't silently dropped
+    pub fn or_default_when_missing(loaded: Result<Self, ConfigError>) -> Result<Self, ConfigError> {
+        match loaded {
+            Err(ConfigError::DirectoryNotFound) => Ok(Self::default_config()),
+            Err(ConfigError::ReadError(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default_config()),
+            loaded => loaded,
+        }
+    }
+
     /// Save configuration to file
     pub fn save(&self) -> Result<(), ConfigError> {
         // Validate before saving
@@ -126,21 +418,69 @@ impl Config {
                 model: "claude-sonnet-4-20250514".to_string(),
                 api_key_env: "ANTHROPIC_API_KEY".to_string(),
                 api_key: None,
+                learn_from_edits: false,
+                repair_attempts: default_repair_attempts(),
+                connect_timeout_secs: default_connect_timeout_secs(),
+                request_timeout_secs: default_request_timeout_secs(),
+                soft_budget_secs: default_soft_budget_secs(),
+                max_tokens: default_max_tokens(),
+                temperature: default_temperature(),
+                top_p: None,
+                system_prompt: None,
+                extra_models: Vec::new(),
+                live_suggest: false,
+                live_suggest_model: default_live_suggest_model(),
+                live_suggest_delay_ms: default_live_suggest_delay_ms(),
+                warmup_context: false,
+                alternatives: false,
+                features: LlmFeatures::default(),
             },
             ui: UIConfig {
                 refresh_interval_ms: 100,
                 max_commits_display: 5,
                 max_stashes_display: 5,
                 show_line_numbers: false,
+                editor: None,
             },
             behavior: BehaviorConfig {
                 auto_refresh: true,
                 confirm_dangerous_ops: true,
                 log_commands: true,
+                explain_direct_commands: false,
+                notify_long_commands: false,
+                notify_after_secs: default_notify_after_secs(),
+                scan_secrets: false,
+                large_file_kb: default_large_file_kb(),
+                local_stats: false,
+                terminal_title: default_terminal_title(),
+                terminal_notifications: TerminalNotifications::Off,
+                dry_run_dangerous: false,
+                auto_execute_safe: false,
+                instant_commands: default_instant_commands(),
+                repo_trust: default_repo_trust(),
+                query_history: default_query_history(),
             },
             git: GitConfig {
                 timeout_seconds: 30,
+                projects_dirs: Vec::new(),
+                rename_threshold: default_rename_threshold(),
+                parse_locale: default_parse_locale(),
+                binary_path: None,
+                second_remote: None,
+                allowed_hosts: Vec::new(),
+                branch_pattern: None,
+                verify_signatures: false,
             },
+            hooks: Vec::new(),
+            mcp: McpConfig::default(),
+            network: NetworkConfig::default(),
+            confirmation: ConfirmationPolicy::default(),
+            rate_limits: BTreeMap::new(),
+            identities: Vec::new(),
+            identity_rules: Vec::new(),
+            audit: RetentionPolicy::default(),
+            audit_sinks: Vec::new(),
+            approval: None,
         }
     }
 
@@ -161,6 +501,29 @@ impl Config {
             ));
         }
 
+        if self.llm.live_suggest && !self.llm.live_suggest_model.starts_with("claude-") {
+            return Err(ConfigError::InvalidValue(
+                format!("Invalid live_suggest_model: {}. Must be a Claude model", self.llm.live_suggest_model)
+            ));
+        }
+
+        // Validate generation parameters
+        if self.llm.max_tokens == 0 {
+            return Err(ConfigError::InvalidValue(
+                "max_tokens must be greater than 0".to_string()
+            ));
+        }
+
+        for (name, value) in [("temperature", self.llm.temperature), ("top_p", self.llm.top_p)] {
+            if let Some(value) = value
+                && !(0.0..=1.0).contains(&value)
+            {
+                return Err(ConfigError::InvalidValue(
+                    format!("{} must be between 0.0 and 1.0, got {}", name, value)
+                ));
+            }
+        }
+
         // Validate UI settings
         if self.ui.refresh_interval_ms == 0 {
             return Err(ConfigError::InvalidValue(
@@ -181,16 +544,71 @@ impl Config {
             ));
         }
 
+        if !(1..=100).contains(&self.git.rename_threshold) {
+            return Err(ConfigError::InvalidValue(format!(
+                "rename_threshold must be between 1 and 100, got {}",
+                self.git.rename_threshold
+            )));
+        }
+
+        if !self.git.parse_locale.chars().all(|c| c.is_ascii_alphanumeric() || "._-@".contains(c)) {
+            return Err(ConfigError::InvalidValue(format!(
+                "parse_locale is not a locale name: {:?}",
+                self.git.parse_locale
+            )));
+        }
+
+        // Whether it runs is checked at startup, where `gitalky doctor` can explain
+        if self.git.binary_path.as_ref().is_some_and(|path| path.as_os_str().is_empty()) {
+            return Err(ConfigError::InvalidValue(
+                "binary_path is empty; remove it to use git from PATH".to_string(),
+            ));
+        }
+
+        if let Err(e) = BranchPolicy::new(self.git.branch_pattern.as_deref()) {
+            return Err(ConfigError::InvalidValue(format!("branch_pattern is not a valid regex: {}", e)));
+        }
+
+        if let Some(unknown) = self
+            .behavior
+            .instant_commands
+            .iter()
+            .find(|name| !ALLOWED_GIT_SUBCOMMANDS.contains(&name.as_str()))
+        {
+            return Err(ConfigError::InvalidValue(format!(
+                "instant_commands: '{}' is not a git subcommand gitalky can run",
+                unknown
+            )));
+        }
+
+        if self.audit.max_files == 0 {
+            return Err(ConfigError::InvalidValue(
+                "audit.max_files must be greater than 0".to_string()
+            ));
+        }
+
+        for sink in &self.audit_sinks {
+            sink.validate().map_err(ConfigError::InvalidValue)?;
+        }
+
+        if let Some(ref approval) = self.approval {
+            approval.validate().map_err(ConfigError::InvalidValue)?;
+        }
+
+        self.network.validate()?;
+        validate_identities(&self.identities)?;
+        validate_identity_rules(&self.identity_rules)?;
+
         Ok(())
     }
 
     /// Get API key from environment variable or config
     pub fn get_api_key(&self) -> Option<String> {
         // First try environment variable
-        if let Ok(key) = std::env::var(&self.llm.api_key_env) {
-            if !key.is_empty() {
-                return Some(key);
-            }
+        if let Ok(key) = std::env::var(&self.llm.api_key_env)
+            && !key.is_empty()
+        {
+            return Some(key);
         }
 
         // Fall back to config file if present
@@ -216,6 +634,18 @@ mod tests {
         assert!(config.behavior.confirm_dangerous_ops);
     }
 
+    #[test]
+    fn test_default_only_when_missing() {
+        let missing = ConfigError::ReadError(std::io::Error::new(std::io::ErrorKind::NotFound, "Config file not found"));
+        assert!(Config::or_default_when_missing(Err(missing)).is_ok());
+        assert!(Config::or_default_when_missing(Err(ConfigError::DirectoryNotFound)).is_ok());
+
+        let broken = toml::from_str::<Config>("[llm").unwrap_err();
+        assert!(Config::or_default_when_missing(Err(ConfigError::ParseError(broken))).is_err());
+        let invalid = ConfigError::InvalidValue("llm.provider".to_string());
+        assert!(Config::or_default_when_missing(Err(invalid)).is_err());
+    }
+
     #[test]
     fn test_validate_valid_config() {
         let config = Config::default_config();
@@ -236,6 +666,77 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_validate_generation_params() {
+        let mut config = Config::default_config();
+        config.llm.temperature = Some(1.5);
+        assert!(config.validate().unwrap_err().to_string().contains("temperature"));
+
+        let mut config = Config::default_config();
+        config.llm.top_p = Some(-0.1);
+        assert!(config.validate().unwrap_err().to_string().contains("top_p"));
+
+        let mut config = Config::default_config();
+        config.llm.max_tokens = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_generation_params() {
+        let toml = toml::to_string(&Config::default_config())
+            .unwrap()
+            .replace("max_tokens = 1024\n", "max_tokens = 256\ntop_p = 0.9\nsystem_prompt = \"Only git.\"\n");
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.llm.max_tokens, 256);
+        assert_eq!(config.llm.temperature, Some(0.0));
+        assert_eq!(config.llm.top_p, Some(0.9));
+        assert_eq!(config.llm.system_prompt.as_deref(), Some("Only git."));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rename_threshold() {
+        let mut config = Config::default_config();
+        assert_eq!(config.git.rename_threshold, 50);
+        config.git.rename_threshold = 0;
+        assert!(config.validate().is_err());
+        config.git.rename_threshold = 101;
+        assert!(config.validate().is_err());
+        config.git.rename_threshold = 90;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_parse_locale() {
+        let mut config = Config::default_config();
+        assert_eq!(config.git.parse_locale, "C");
+        config.git.parse_locale = "en_US.UTF-8".to_string();
+        assert!(config.validate().is_ok());
+        config.git.parse_locale = String::new();
+        assert!(config.validate().is_ok());
+        config.git.parse_locale = "C; rm".to_string();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_branch_pattern() {
+        let mut config = Config::default_config();
+        config.git.branch_pattern = Some("^(feat|fix)/[a-z0-9-]+$".to_string());
+        assert!(config.validate().is_ok());
+        config.git.branch_pattern = Some("^(feat".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_binary_path() {
+        let mut config = Config::default_config();
+        assert!(!toml::to_string(&config).unwrap().contains("binary_path"));
+        config.git.binary_path = Some(PathBuf::from("/opt/homebrew/bin/git"));
+        assert!(config.validate().is_ok());
+        config.git.binary_path = Some(PathBuf::new());
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_validate_zero_refresh_interval() {
         let mut config = Config::default_config();
@@ -269,6 +770,244 @@ mod tests {
         assert!(config.has_api_key());
     }
 
+    #[test]
+    fn test_missing_optional_behavior_fields_default() {
+        let toml = r#"
+            [llm]
+            provider = "anthropic"
+            model = "claude-sonnet-4-20250514"
+            api_key_env = "ANTHROPIC_API_KEY"
+
+            [ui]
+            refresh_interval_ms = 100
+            max_commits_display = 5
+            max_stashes_display = 5
+            show_line_numbers = false
+
+            [behavior]
+            auto_refresh = true
+            confirm_dangerous_ops = true
+            log_commands = true
+
+            [git]
+            timeout_seconds = 30
+        "#;
+        let config: Config = toml::from_str(toml).unwrap();
+        assert!(!config.behavior.explain_direct_commands);
+        assert!(!config.behavior.notify_long_commands);
+        assert_eq!(config.behavior.notify_after_secs, 10);
+        assert!(config.behavior.terminal_title);
+        assert_eq!(config.behavior.terminal_notifications, TerminalNotifications::Off);
+        assert!(config.hooks.is_empty());
+        assert!(config.mcp.is_empty());
+    }
+
+    #[test]
+    fn test_parse_hooks() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        toml.push_str(
+            r#"
+            [[hooks]]
+            event = "pre-execute"
+            command = "/usr/local/bin/policy-check"
+            args = ["--strict"]
+            required = true
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.hooks.len(), 1);
+        assert_eq!(config.hooks[0].event, crate::hooks::HookEvent::PreExecute);
+        assert_eq!(config.hooks[0].args, vec!["--strict".to_string()]);
+        assert!(config.hooks[0].required);
+    }
+
+    #[test]
+    fn test_parse_llm_features() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("[llm.features]"));
+        toml.push_str(
+            r#"
+            [llm.features]
+            translate = false
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert!(!config.llm.features.translate);
+        assert!(config.llm.features.explain);
+        assert!(config.llm.features.any_enabled());
+        assert_eq!(config.llm.features.enabled_names(), ["explain"]);
+        assert!(toml::to_string(&config).unwrap().contains("[llm.features]"));
+    }
+
+    #[test]
+    fn test_parse_network() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("[network]"));
+        toml.push_str(
+            r#"
+            [network]
+            proxy = "http://proxy.corp.example:3128"
+            no_proxy = "localhost,.corp.example"
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.network.proxy.as_deref(), Some("http://proxy.corp.example:3128"));
+        assert_eq!(config.network.ca_bundle, None);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_confirmation() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("[confirmation]"));
+        toml.push_str(
+            r#"
+            [confirmation.levels]
+            hard_reset = "high"
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(
+            config.confirmation.levels.get(&crate::security::DangerousOp::HardReset),
+            Some(&crate::security::RiskLevel::High)
+        );
+        assert_eq!(config.confirmation.protected_branches, vec!["main", "master"]);
+    }
+
+    #[test]
+    fn test_parse_rate_limits() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("rate_limits"));
+        toml.push_str(
+            r#"
+            [rate_limits.force_push]
+            max = 2
+            window_minutes = 10
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(
+            config.rate_limits.get(&crate::security::DangerousOp::ForcePush),
+            Some(&RateLimit { max: 2, window_minutes: 10 })
+        );
+    }
+
+    #[test]
+    fn test_parse_identities() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("identities"));
+        toml.push_str(
+            r#"
+            [[identities]]
+            label = "work"
+            name = "Jane Doe"
+            email = "jane@corp.example"
+            signing_key = "ABCD1234"
+
+            [[identities]]
+            label = "oss"
+            name = "Jane Doe"
+            email = "jane@users.example"
+
+            [[identity_rules]]
+            host = "github.company.com"
+            emails = ["*@corp.example"]
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.identities.len(), 2);
+        assert_eq!(config.identities[0].signing_key.as_deref(), Some("ABCD1234"));
+        assert_eq!(config.identities[1].signing_key, None);
+        assert!(config.identity_rules[0].allows("jane@corp.example"));
+    }
+
+    #[test]
+    fn test_parse_audit_retention() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("[audit]"));
+        toml.push_str(
+            r#"
+            [audit]
+            max_files = 20
+            max_age_days = 365
+        "#,
+        );
+
+        let mut config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.audit.max_files, 20);
+        assert_eq!(config.audit.max_age_days, Some(365));
+        assert_eq!(config.audit.max_total_mb, 50);
+        assert!(config.audit.compress);
+        assert!(config.validate().is_ok());
+
+        config.audit.max_files = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_audit_sinks() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("audit_sinks"));
+        toml.push_str(
+            r#"
+            [[audit_sinks]]
+            type = "sqlite"
+            path = "/var/lib/gitalky/audit.db"
+
+            [[audit_sinks]]
+            type = "webhook"
+            url = "siem.example.com"
+        "#,
+        );
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(
+            config.audit_sinks[0],
+            AuditSinkConfig::Sqlite { path: Some(PathBuf::from("/var/lib/gitalky/audit.db")) }
+        );
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_approval() {
+        let mut toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("[approval]"));
+        toml.push_str(
+            r#"
+            [approval]
+            url = "https://approvals.example.com/gitalky"
+            min_risk = "medium"
+        "#,
+        );
+
+        let mut config: Config = toml::from_str(&toml).unwrap();
+        let approval = config.approval.clone().unwrap();
+        assert_eq!(approval.min_risk, crate::security::RiskLevel::Medium);
+        assert_eq!(approval.timeout_secs, 300);
+        assert_eq!(approval.token_env, None);
+        assert!(config.validate().is_ok());
+
+        config.approval = Some(ApprovalConfig { url: "approvals.example.com".to_string(), ..approval });
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_second_remote() {
+        let toml = toml::to_string(&Config::default_config()).unwrap();
+        assert!(!toml.contains("second_remote"));
+        let toml = toml.replace("[git]\n", "[git]\nsecond_remote = \"upstream/main\"\n");
+
+        let config: Config = toml::from_str(&toml).unwrap();
+        assert_eq!(config.git.second_remote.as_deref(), Some("upstream/main"));
+    }
+
     #[test]
     fn test_serialize_deserialize() {
         let config = Config::default_config();
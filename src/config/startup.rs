@@ -0,0 +1,304 @@
+use crate::audit::AuditLogger;
+use crate::config::first_run::SetupError;
+use crate::config::recent::RecentRepos;
+use crate::config::settings::Config;
+use crate::git::{GitExecutor, Repository, quote_arg};
+use crate::security::paths::default_clone_dir;
+use crate::security::{CommandValidator, Workspace};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// What the user picked on the startup screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupChoice {
+    /// Open a recently used repository
+    Recent(PathBuf),
+    /// Clone a repository into the current directory
+    Clone,
+    /// Run `git init` in the current directory
+    Init,
+    Quit,
+}
+
+/// Parse a startup menu selection ("1".."n" for recent repos, c/i/q)
+pub fn parse_choice(input: &str, recent: &[&Path]) -> Option<StartupChoice> {
+    let input = input.trim().to_lowercase();
+    match input.as_str() {
+        "c" | "clone" => Some(StartupChoice::Clone),
+        "i" | "init" => Some(StartupChoice::Init),
+        "q" | "quit" => Some(StartupChoice::Quit),
+        _ => {
+            let index: usize = input.parse().ok()?;
+            let path = recent.get(index.checked_sub(1)?)?;
+            Some(StartupChoice::Recent(path.to_path_buf()))
+        }
+    }
+}
+
+/// Check that a clone source looks like a URL, scp-style address or local path
+pub fn validate_clone_url(url: &str) -> Result<(), String> {
+    if url.is_empty() {
+        return Err("URL cannot be empty".to_string());
+    }
+    if url.chars().any(char::is_whitespace) {
+        return Err("URL cannot contain whitespace".to_string());
+    }
+    if url.starts_with('-') {
+        return Err("URL cannot start with '-'".to_string());
+    }
+
+    let is_url = ["https://", "http://", "ssh://", "git://", "file://"]
+        .iter()
+        .any(|scheme| url.starts_with(scheme));
+    // user@host:path (scp-like syntax used by ssh remotes)
+    let is_scp = url
+        .split_once(':')
+        .map(|(host, path)| !host.is_empty() && !host.contains('/') && !path.is_empty())
+        .unwrap_or(false);
+    let is_local = Path::new(url).exists();
+
+    if is_url || is_scp || is_local {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{}' is not a URL (https://, ssh://, git@host:path) or an existing path",
+            url
+        ))
+    }
+}
+
+/// Build the `clone` command for a source and optional destination directory
+pub fn clone_command(url: &str, dest: Option<&str>) -> String {
+    match dest {
+        Some(dest) => format!("clone --progress {} {}", quote_arg(url), quote_arg(dest)),
+        None => format!("clone --progress {}", quote_arg(url)),
+    }
+}
+
+/// Directory a clone ends up in, relative to `cwd`
+pub fn clone_destination(cwd: &Path, url: &str, dest: Option<&str>) -> PathBuf {
+    match dest {
+        Some(dest) => cwd.join(dest),
+        None => cwd.join(default_clone_dir(url)),
+    }
+}
+
+/// Startup screen shown when gitalky is launched outside a git repository
+///
+/// Offers recent repositories, cloning, or `git init` in the current
+/// directory. Clone and init commands go through `CommandValidator` with the
+/// current directory as workspace, just like commands typed in the TUI.
+pub struct StartupWizard {
+    cwd: PathBuf,
+    validator: CommandValidator,
+    audit_logger: Option<AuditLogger>,
+}
+
+impl StartupWizard {
+    pub fn new(cwd: PathBuf, config: &Config) -> Self {
+        let validator = CommandValidator::new()
+            .with_workspace(Workspace::new(&cwd, config.git.projects_dirs.clone()))
+            .with_allowed_hosts(config.git.allowed_hosts.clone());
+        let audit_logger = if config.behavior.log_commands {
+            AuditLogger::for_config(config).ok()
+        } else {
+            None
+        };
+
+        Self {
+            cwd,
+            validator,
+            audit_logger,
+        }
+    }
+
+    /// Show the startup menu until the user picks or creates a repository
+    pub fn run(&self, recent: &RecentRepos) -> Result<Repository, SetupError> {
+        let recent = recent.existing();
+
+        println!("\n{}", "=".repeat(70));
+        println!("No git repository found in {}", self.cwd.display());
+        println!("{}", "=".repeat(70));
+
+        loop {
+            if !recent.is_empty() {
+                println!("\nRecent repositories:");
+                for (i, path) in recent.iter().enumerate() {
+                    println!("  [{}] {}", i + 1, path.display());
+                }
+            }
+            println!("\n  [c] Clone a repository");
+            println!("  [i] Init a new repository here");
+            println!("  [q] Quit");
+            print!("\nEnter your choice: ");
+            io::stdout().flush()?;
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input)? == 0 {
+                return Err(SetupError::Cancelled);
+            }
+
+            let repo = match parse_choice(&input, &recent) {
+                Some(StartupChoice::Recent(path)) => Some(Repository::new(path)),
+                Some(StartupChoice::Clone) => self.clone_repository()?,
+                Some(StartupChoice::Init) => self.init_repository(),
+                Some(StartupChoice::Quit) => return Err(SetupError::Cancelled),
+                None => {
+                    println!("\n⚠️  Invalid choice.");
+                    None
+                }
+            };
+
+            if let Some(repo) = repo {
+                return Ok(repo);
+            }
+        }
+    }
+
+    /// Ask for a URL and destination, then clone with git's progress output
+    fn clone_repository(&self) -> Result<Option<Repository>, SetupError> {
+        println!("\n{}", "-".repeat(70));
+        println!("Clone a Repository");
+        println!("{}", "-".repeat(70));
+
+        let url = loop {
+            print!("\nRepository URL (empty to go back): ");
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let url = input.trim().to_string();
+
+            if url.is_empty() {
+                return Ok(None);
+            }
+            match validate_clone_url(&url) {
+                Ok(()) => break url,
+                Err(e) => println!("\n⚠️  {}", e),
+            }
+        };
+
+        let default_dest = default_clone_dir(&url);
+        print!("Directory [{}]: ", default_dest);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let dest = Some(input.trim()).filter(|d| !d.is_empty());
+
+        let command = clone_command(&url, dest);
+        if !self.run_command(&command) {
+            return Ok(None);
+        }
+
+        let path = clone_destination(&self.cwd, &url, dest);
+        println!("\n✓ Cloned into {}", path.display());
+        Ok(Some(Repository::new(path)))
+    }
+
+    /// Run `git init` in the current directory
+    fn init_repository(&self) -> Option<Repository> {
+        if !self.run_command("init") {
+            return None;
+        }
+        println!("\n✓ Initialized a new repository in {}", self.cwd.display());
+        Some(Repository::new(&self.cwd))
+    }
+
+    /// Validate and run a command, printing any failure; returns whether it succeeded
+    fn run_command(&self, command: &str) -> bool {
+        let validated = match self.validator.validate(command) {
+            Ok(validated) => validated,
+            Err(e) => {
+                println!("\n⚠️  Command rejected: {}", e);
+                return false;
+            }
+        };
+
+        println!("\n$ git {}", validated.command);
+        let result = GitExecutor::new(&self.cwd).execute_with_progress(&validated.command);
+        let exit_code = match &result {
+            Ok(()) => 0,
+            Err(e) => e.exit_code().unwrap_or(-1),
+        };
+        if let Some(ref logger) = self.audit_logger {
+            let _ = logger.log_command(&validated.command, &self.cwd, exit_code);
+        }
+
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                println!("\n⚠️  {}", e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_choice() {
+        let recent = [Path::new("/a"), Path::new("/b")];
+
+        assert_eq!(parse_choice("c\n", &recent), Some(StartupChoice::Clone));
+        assert_eq!(parse_choice(" I ", &recent), Some(StartupChoice::Init));
+        assert_eq!(parse_choice("q", &recent), Some(StartupChoice::Quit));
+        assert_eq!(
+            parse_choice("2", &recent),
+            Some(StartupChoice::Recent(PathBuf::from("/b")))
+        );
+        assert_eq!(parse_choice("0", &recent), None);
+        assert_eq!(parse_choice("3", &recent), None);
+        assert_eq!(parse_choice("x", &recent), None);
+    }
+
+    #[test]
+    fn test_validate_clone_url() {
+        assert!(validate_clone_url("https://github.com/org/app.git").is_ok());
+        assert!(validate_clone_url("ssh://git@host/org/app.git").is_ok());
+        assert!(validate_clone_url("git@github.com:org/app.git").is_ok());
+        assert!(validate_clone_url(".").is_ok());
+
+        assert!(validate_clone_url("").is_err());
+        assert!(validate_clone_url("--upload-pack=evil").is_err());
+        assert!(validate_clone_url("https://host/a b").is_err());
+        assert!(validate_clone_url("not-a-url").is_err());
+    }
+
+    #[test]
+    fn test_clone_command_and_destination() {
+        let cwd = Path::new("/work");
+
+        assert_eq!(
+            clone_command("https://host/org/app.git", None),
+            "clone --progress https://host/org/app.git"
+        );
+        assert_eq!(
+            clone_destination(cwd, "https://host/org/app.git", None),
+            PathBuf::from("/work/app")
+        );
+
+        assert_eq!(
+            clone_command("git@host:org/app.git", Some("my app")),
+            "clone --progress git@host:org/app.git 'my app'"
+        );
+        assert_eq!(
+            clone_destination(cwd, "git@host:org/app.git", Some("my app")),
+            PathBuf::from("/work/my app")
+        );
+    }
+
+    #[test]
+    fn test_clone_command_passes_validator() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let validator = CommandValidator::new()
+            .with_workspace(Workspace::new(temp.path(), Vec::new()));
+
+        let command = clone_command("https://host/org/app.git", None);
+        assert!(validator.validate(&command).is_ok());
+
+        let command = clone_command("https://host/org/app.git", Some("/etc/evil"));
+        assert!(validator.validate(&command).is_err());
+    }
+}
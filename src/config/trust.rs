@@ -0,0 +1,108 @@
+use crate::config::settings::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Input that marks the current repository as trusted
+pub const TRUST_COMMAND: &str = ":trust";
+
+/// File under the config dir holding each repository's trust decision
+const TRUST_FILE: &str = "trust.toml";
+
+/// Whether a repository may do more than read-only commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustLevel {
+    Trusted,
+    /// Only commands that don't modify the repository run
+    Untrusted,
+}
+
+/// Trust decisions per repository, like an editor's workspace trust
+///
+/// Untrusted repositories only run read-only commands, in the TUI,
+/// `gitalky run` and `gitalky serve` alike. Nothing else is gated: the
+/// commands that do run still honour the repository's own config, such as
+/// `core.fsmonitor`. Stored in `~/.config/gitalky/trust.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    repos: BTreeMap<PathBuf, TrustLevel>,
+}
+
+impl TrustStore {
+    /// Path of the trust file
+    pub fn file_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join(TRUST_FILE))
+    }
+
+    /// Load from `~/.config/gitalky/trust.toml`; a missing file trusts nothing
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::file_path()?)
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&Self::file_path()?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// The decision for `repo`, or None if the user was never asked
+    pub fn level(&self, repo: &Path) -> Option<TrustLevel> {
+        self.repos.get(repo).copied()
+    }
+
+    pub fn set(&mut self, repo: &Path, level: TrustLevel) {
+        self.repos.insert(repo.to_path_buf(), level);
+    }
+
+    /// Whether the store saved at `path` trusts `repo`; an unreadable store trusts nothing
+    pub fn trusts_saved(path: &Path, repo: &Path) -> bool {
+        Self::load_from(path).is_ok_and(|store| store.level(repo) == Some(TrustLevel::Trusted))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("gitalky").join(TRUST_FILE);
+
+        let mut store = TrustStore::load_from(&file).unwrap();
+        assert_eq!(store.level(Path::new("/home/me/project")), None);
+
+        store.set(Path::new("/home/me/project"), TrustLevel::Trusted);
+        store.set(Path::new("/tmp/downloaded"), TrustLevel::Untrusted);
+        store.set(Path::new("/tmp/downloaded"), TrustLevel::Trusted);
+        store.set(Path::new("/tmp/other"), TrustLevel::Untrusted);
+        store.save_to(&file).unwrap();
+
+        let loaded = TrustStore::load_from(&file).unwrap();
+        assert_eq!(loaded, store);
+        assert_eq!(loaded.level(Path::new("/tmp/downloaded")), Some(TrustLevel::Trusted));
+        assert_eq!(loaded.level(Path::new("/tmp/other")), Some(TrustLevel::Untrusted));
+        assert!(TrustStore::trusts_saved(&file, Path::new("/tmp/downloaded")));
+        assert!(!TrustStore::trusts_saved(&file, Path::new("/tmp/other")));
+
+        fs::write(&file, "not toml [").unwrap();
+        assert!(!TrustStore::trusts_saved(&file, Path::new("/tmp/downloaded")));
+    }
+}
@@ -17,6 +17,15 @@ pub enum GitError {
     #[error("Git command failed: {0}")]
     CommandFailed(String),
 
+    #[error("Command 'git {command}' failed with exit code {exit_code}: {}", stderr.trim())]
+    CommandExited {
+        command: String,
+        args: Vec<String>,
+        exit_code: i32,
+        stdout: String,
+        stderr: String,
+    },
+
     #[error("Failed to parse git output: {0}")]
     ParseError(String),
 
@@ -30,6 +39,24 @@ pub enum GitError {
     IoError(#[from] io::Error),
 }
 
+impl GitError {
+    /// Exit code of the git process, if the error came from a finished command
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            GitError::CommandExited { exit_code, .. } => Some(*exit_code),
+            _ => None,
+        }
+    }
+
+    /// Captured stderr of the git process, if the error came from a finished command
+    pub fn stderr(&self) -> Option<&str> {
+        match self {
+            GitError::CommandExited { stderr, .. } => Some(stderr),
+            _ => None,
+        }
+    }
+}
+
 /// Top-level application error that wraps all module-specific errors
 ///
 /// This provides a unified error type for application-level code while preserving
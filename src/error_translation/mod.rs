@@ -1,3 +1,5 @@
+pub mod rules;
 pub mod translator;
 
+pub use rules::{ErrorRule, ErrorRules, RuleMatch};
 pub use translator::{ErrorTranslator, UserFriendlyError};
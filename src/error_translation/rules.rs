@@ -0,0 +1,223 @@
+use crate::config::settings::{Config, ConfigError};
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A single user-defined error rule as written in the rules file
+///
+/// `message` and `suggestion` may reference capture groups from `pattern`
+/// using `$1` or `${name}` syntax.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ErrorRule {
+    pub pattern: String,
+    pub message: String,
+    #[serde(default)]
+    pub suggestion: Option<String>,
+    /// Candidate fix commands offered in the output view (may use `{branch}`/`{remote}`)
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+/// On-disk layout of the rules file
+///
+/// ```toml
+/// [[rule]]
+/// pattern = "(?i)pre-receive hook declined"
+/// message = "The server rejected the push (company policy hook)."
+/// suggestion = "Check the CI policy page or ask #git-help."
+/// commands = ["git fetch origin"]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct RulesFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<ErrorRule>,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    regex: Regex,
+    message: String,
+    suggestion: Option<String>,
+    commands: Vec<String>,
+}
+
+/// Result of matching an error against a rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleMatch {
+    pub message: String,
+    pub suggestion: Option<String>,
+    pub commands: Vec<String>,
+}
+
+/// Custom error translation rules, checked before the built-in patterns
+#[derive(Debug, Clone, Default)]
+pub struct ErrorRules {
+    rules: Vec<CompiledRule>,
+}
+
+impl ErrorRules {
+    /// An empty rule set (built-in patterns only)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the default rules path: ~/.config/gitalky/error_rules.toml
+    pub fn default_path() -> Result<PathBuf, ConfigError> {
+        Ok(Config::config_dir()?.join("error_rules.toml"))
+    }
+
+    /// Load rules from the default path, returning an empty set if the file doesn't exist
+    pub fn load_default() -> Result<Self, ConfigError> {
+        let path = Self::default_path()?;
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        Self::load(&path)
+    }
+
+    /// Load rules from a TOML file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path)?;
+        Self::from_toml(&contents)
+    }
+
+    /// Parse and compile rules from TOML text
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        let file: RulesFile = toml::from_str(contents)?;
+        Self::from_rules(file.rules)
+    }
+
+    /// Compile a list of rules, rejecting invalid regular expressions
+    pub fn from_rules(rules: Vec<ErrorRule>) -> Result<Self, ConfigError> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern).map_err(|e| {
+                    ConfigError::InvalidValue(format!(
+                        "Invalid error rule pattern '{}': {}",
+                        rule.pattern, e
+                    ))
+                })?;
+                Ok(CompiledRule {
+                    regex,
+                    message: rule.message,
+                    suggestion: rule.suggestion,
+                    commands: rule.commands,
+                })
+            })
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Number of loaded rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Check if no custom rules are loaded
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Find the first rule matching `error_text` and expand its templates
+    pub fn match_error(&self, error_text: &str) -> Option<RuleMatch> {
+        self.rules.iter().find_map(|rule| {
+            let caps = rule.regex.captures(error_text)?;
+            let expand = |template: &str| {
+                let mut expanded = String::new();
+                caps.expand(template, &mut expanded);
+                expanded
+            };
+
+            Some(RuleMatch {
+                message: expand(&rule.message),
+                suggestion: rule.suggestion.as_deref().map(expand),
+                commands: rule.commands.iter().map(|c| expand(c)).collect(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules_file() {
+        let rules = ErrorRules::from_toml(
+            r#"
+            [[rule]]
+            pattern = "(?i)pre-receive hook declined"
+            message = "The server rejected the push."
+            suggestion = "Check the policy page."
+
+            [[rule]]
+            pattern = "proxy"
+            message = "Proxy problem."
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(rules.len(), 2);
+    }
+
+    #[test]
+    fn test_empty_rules_file() {
+        let rules = ErrorRules::from_toml("").unwrap();
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        let result = ErrorRules::from_toml(
+            r#"
+            [[rule]]
+            pattern = "(unclosed"
+            message = "never"
+            "#,
+        );
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_match_expands_captures() {
+        let rules = ErrorRules::from_rules(vec![ErrorRule {
+            pattern: r"hook '(?P<hook>[\w-]+)' failed".to_string(),
+            message: "The ${hook} hook rejected this.".to_string(),
+            suggestion: Some("See docs for $hook.".to_string()),
+            commands: vec!["git config --get hooks.${hook}".to_string()],
+        }])
+        .unwrap();
+
+        let matched = rules
+            .match_error("error: hook 'commit-lint' failed")
+            .unwrap();
+        assert_eq!(matched.message, "The commit-lint hook rejected this.");
+        assert_eq!(matched.suggestion.as_deref(), Some("See docs for commit-lint."));
+        assert_eq!(matched.commands, vec!["git config --get hooks.commit-lint"]);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = ErrorRules::from_rules(vec![
+            ErrorRule {
+                pattern: "denied".to_string(),
+                message: "first".to_string(),
+                suggestion: None,
+                commands: Vec::new(),
+            },
+            ErrorRule {
+                pattern: "denied".to_string(),
+                message: "second".to_string(),
+                suggestion: None,
+                commands: Vec::new(),
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(rules.match_error("access denied").unwrap().message, "first");
+        assert!(rules.match_error("all good").is_none());
+    }
+}
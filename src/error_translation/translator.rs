@@ -1,10 +1,48 @@
 use crate::error::{AppError, GitError};
+use crate::error_translation::rules::{ErrorRules, RuleMatch};
+use crate::git::quote_arg;
 
 #[derive(Debug, Clone)]
 pub struct UserFriendlyError {
     pub simple_message: String,
     pub suggestion: Option<String>,
     pub raw_error: String,
+    /// Exit code of the failed git process, when known
+    pub exit_code: Option<i32>,
+    /// Candidate git commands that may fix the problem
+    pub recovery_commands: Vec<String>,
+}
+
+impl UserFriendlyError {
+    /// Fill `{branch}` placeholders in recovery commands with the current branch
+    ///
+    /// Commands that need a branch are dropped when there is none (detached HEAD).
+    pub fn resolve_branch(&mut self, branch: Option<&str>) {
+        self.recovery_commands = std::mem::take(&mut self.recovery_commands)
+            .into_iter()
+            .filter_map(|command| {
+                if !command.contains("{branch}") {
+                    return Some(command);
+                }
+                branch.map(|name| command.replace("{branch}", &quote_arg(name)))
+            })
+            .collect();
+    }
+
+    /// Fill `{remote}` placeholders in recovery commands with the repository's remote
+    ///
+    /// Commands that need a remote are dropped when none could be chosen.
+    pub fn resolve_remote(&mut self, remote: Option<&str>) {
+        self.recovery_commands = std::mem::take(&mut self.recovery_commands)
+            .into_iter()
+            .filter_map(|command| {
+                if !command.contains("{remote}") {
+                    return Some(command);
+                }
+                remote.map(|name| command.replace("{remote}", &quote_arg(name)))
+            })
+            .collect();
+    }
 }
 
 pub struct ErrorTranslator;
@@ -19,175 +57,268 @@ impl ErrorTranslator {
                 simple_message: "Configuration error occurred.".to_string(),
                 suggestion: Some("Check your config file at ~/.config/gitalky/config.toml".to_string()),
                 raw_error: config_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
             AppError::Llm(llm_err) => UserFriendlyError {
                 simple_message: "Error communicating with LLM.".to_string(),
                 suggestion: Some("Check your API key and network connection".to_string()),
                 raw_error: llm_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
             AppError::Translation(trans_err) => UserFriendlyError {
                 simple_message: "Error translating your query.".to_string(),
                 suggestion: Some("Try rephrasing your question or check connection".to_string()),
                 raw_error: trans_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
             AppError::Security(sec_err) => UserFriendlyError {
                 simple_message: "Command validation failed for security reasons.".to_string(),
                 suggestion: None,
                 raw_error: sec_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
             AppError::Setup(setup_err) => UserFriendlyError {
                 simple_message: "Setup error occurred.".to_string(),
                 suggestion: Some("Try running the first-run wizard again".to_string()),
                 raw_error: setup_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
             AppError::Io(io_err) => UserFriendlyError {
                 simple_message: "I/O error occurred.".to_string(),
                 suggestion: Some("Check file permissions and disk space".to_string()),
                 raw_error: io_err.to_string(),
+                exit_code: None,
+                recovery_commands: Vec::new(),
             },
         }
     }
 
     /// Translate a GitError into a user-friendly error message
     pub fn translate(error: &GitError) -> UserFriendlyError {
+        Self::translate_with_rules(error, &ErrorRules::new())
+    }
+
+    /// Translate a GitError, checking custom rules before the built-in patterns
+    pub fn translate_with_rules(error: &GitError, rules: &ErrorRules) -> UserFriendlyError {
         let raw_error = error.to_string();
 
-        // Match common error patterns
-        let (simple_message, suggestion) = Self::match_error_patterns(&raw_error);
+        // Prefer the captured process output over the formatted message: git
+        // reports some conditions (e.g. "nothing to commit") on stdout
+        let (pattern_text, fallback) = match error {
+            GitError::CommandExited { stdout, stderr, .. } => {
+                let fallback = if stderr.trim().is_empty() {
+                    raw_error.clone()
+                } else {
+                    stderr.trim().to_string()
+                };
+                (format!("{}\n{}", stderr, stdout), fallback)
+            }
+            _ => (raw_error.clone(), raw_error.clone()),
+        };
+
+        // Custom rules take precedence, then common patterns; unknown errors pass through as-is
+        let matched = rules
+            .match_error(&pattern_text)
+            .or_else(|| Self::match_error_patterns(&pattern_text))
+            .unwrap_or(RuleMatch {
+                message: fallback,
+                suggestion: None,
+                commands: Vec::new(),
+            });
 
         UserFriendlyError {
-            simple_message,
-            suggestion,
+            simple_message: matched.message,
+            suggestion: matched.suggestion,
             raw_error,
+            exit_code: error.exit_code(),
+            recovery_commands: matched.commands,
         }
     }
 
     /// Match common git error patterns and provide user-friendly messages
-    fn match_error_patterns(error_text: &str) -> (String, Option<String>) {
+    ///
+    /// Commands may contain `{branch}` and `{remote}` placeholders, filled in
+    /// later by [`UserFriendlyError::resolve_branch`] and
+    /// [`UserFriendlyError::resolve_remote`].
+    fn match_error_patterns(error_text: &str) -> Option<RuleMatch> {
         let lower = error_text.to_lowercase();
 
+        // Another git process holds the index lock
+        if lower.contains("index.lock") {
+            return Some(RuleMatch {
+                message: "Another git process is using this repository (index.lock exists).".to_string(),
+                suggestion: Some("Wait for it to finish, then run the command again.".to_string()),
+                commands: Vec::new(),
+            });
+        }
+
         // No upstream branch
         if lower.contains("no upstream") || lower.contains("does not have an upstream") {
-            return (
-                "No remote branch is configured for tracking.".to_string(),
-                Some("Try: git push -u origin <branch-name>".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "No remote branch is configured for tracking.".to_string(),
+                suggestion: Some("Publish the branch and track it: git push -u <remote> <branch-name>".to_string()),
+                commands: vec!["git push -u {remote} {branch}".to_string()],
+            });
+        }
+
+        // Rebase or cherry-pick in progress; checked before the generic
+        // conflict rule, since their conflicts mention `--continue`
+        if lower.contains("rebase in progress") || lower.contains("rebase --continue") {
+            return Some(RuleMatch {
+                message: "A rebase operation is currently in progress.".to_string(),
+                suggestion: Some("Continue with: git rebase --continue, or abort: git rebase --abort".to_string()),
+                commands: vec![
+                    "git rebase --continue".to_string(),
+                    "git rebase --abort".to_string(),
+                ],
+            });
+        }
+        if lower.contains("cherry-pick is already in progress") || lower.contains("cherry-pick --continue") {
+            return Some(RuleMatch {
+                message: "A cherry-pick is currently in progress.".to_string(),
+                suggestion: Some("Continue with: git cherry-pick --continue, or abort: git cherry-pick --abort".to_string()),
+                commands: vec![
+                    "git cherry-pick --continue".to_string(),
+                    "git cherry-pick --abort".to_string(),
+                ],
+            });
         }
 
         // Merge conflicts
         if lower.contains("merge conflict") || lower.contains("conflict") {
-            return (
-                "Merge has conflicts that need to be resolved.".to_string(),
-                Some("Fix conflicts in the listed files, then git add and git commit.".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Merge has conflicts that need to be resolved.".to_string(),
+                suggestion: Some("Fix conflicts in the listed files, then git add and git commit.".to_string()),
+                commands: vec![
+                    "git status".to_string(),
+                    "git merge --abort".to_string(),
+                ],
+            });
         }
 
         // Detached HEAD
         if lower.contains("detached head") {
-            return (
-                "Not currently on any branch (detached HEAD state).".to_string(),
-                Some("Create a new branch: git checkout -b <branch-name>".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Not currently on any branch (detached HEAD state).".to_string(),
+                suggestion: Some("Create a new branch: git checkout -b <branch-name>".to_string()),
+                commands: Vec::new(),
+            });
         }
 
         // Nothing to commit
         if lower.contains("nothing to commit") || lower.contains("working tree clean") {
-            return (
-                "No changes to commit - working directory is clean.".to_string(),
-                None,
-            );
+            return Some(RuleMatch {
+                message: "No changes to commit - working directory is clean.".to_string(),
+                suggestion: None,
+                commands: Vec::new(),
+            });
         }
 
         // Pathspec did not match
         if lower.contains("pathspec") && lower.contains("did not match") {
-            return (
-                "File path not found in the repository.".to_string(),
-                Some("Check the file path and try again. Use 'git status' to see available files.".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "File path not found in the repository.".to_string(),
+                suggestion: Some("Check the file path and try again. Use 'git status' to see available files.".to_string()),
+                commands: vec!["git status".to_string()],
+            });
         }
 
         // Branch already exists
         if lower.contains("already exists") && (lower.contains("branch") || lower.contains("ref")) {
-            return (
-                "A branch with that name already exists.".to_string(),
-                Some("Use a different name or delete the existing branch first.".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "A branch with that name already exists.".to_string(),
+                suggestion: Some("Use a different name or delete the existing branch first.".to_string()),
+                commands: Vec::new(),
+            });
         }
 
         // Not a git repository
         if lower.contains("not a git repository") {
-            return (
-                "Current directory is not a git repository.".to_string(),
-                Some("Initialize with: git init".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Current directory is not a git repository.".to_string(),
+                suggestion: Some("Initialize with: git init".to_string()),
+                commands: Vec::new(),
+            });
         }
 
         // Remote not found
         if lower.contains("remote") && (lower.contains("not found") || lower.contains("does not appear")) {
-            return (
-                "Remote repository not found.".to_string(),
-                Some("Check the remote URL with: git remote -v".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Remote repository not found.".to_string(),
+                suggestion: Some("Check the remote URL with: git remote -v".to_string()),
+                commands: vec!["git remote -v".to_string()],
+            });
         }
 
         // Authentication failed
         if lower.contains("authentication failed") || lower.contains("permission denied") {
-            return (
-                "Authentication failed - check your credentials.".to_string(),
-                Some("Verify your SSH keys or personal access token.".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Authentication failed - check your credentials.".to_string(),
+                suggestion: Some("Verify your SSH keys or personal access token.".to_string()),
+                commands: Vec::new(),
+            });
         }
 
         // Uncommitted changes
         if lower.contains("uncommitted changes") || lower.contains("would be overwritten") {
-            return (
-                "Operation would overwrite uncommitted changes.".to_string(),
-                Some("Commit or stash your changes first: git stash".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Operation would overwrite uncommitted changes.".to_string(),
+                suggestion: Some("Commit or stash your changes first: git stash".to_string()),
+                commands: vec![
+                    "git stash".to_string(),
+                    "git status".to_string(),
+                ],
+            });
         }
 
         // Divergent branches
         if lower.contains("diverged") || (lower.contains("rejected") && lower.contains("non-fast-forward")) {
-            return (
-                "Local and remote branches have diverged.".to_string(),
-                Some("Pull changes first: git pull, or force push: git push --force (dangerous!)".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Local and remote branches have diverged.".to_string(),
+                suggestion: Some("Pull changes first: git pull, or force push: git push --force (dangerous!)".to_string()),
+                commands: vec![
+                    "git pull --rebase".to_string(),
+                    "git pull".to_string(),
+                ],
+            });
         }
 
         // Untracked files would be overwritten
         if lower.contains("untracked working tree files would be overwritten") {
-            return (
-                "Untracked files would be overwritten by this operation.".to_string(),
-                Some("Move or remove the conflicting files, or commit them first.".to_string()),
-            );
-        }
-
-        // Rebase in progress
-        if lower.contains("rebase in progress") {
-            return (
-                "A rebase operation is currently in progress.".to_string(),
-                Some("Continue with: git rebase --continue, or abort: git rebase --abort".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "Untracked files would be overwritten by this operation.".to_string(),
+                suggestion: Some("Move or remove the conflicting files, or commit them first.".to_string()),
+                commands: Vec::new(),
+            });
         }
 
         // Merge in progress
         if lower.contains("merge in progress") {
-            return (
-                "A merge operation is currently in progress.".to_string(),
-                Some("Complete the merge and commit, or abort: git merge --abort".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "A merge operation is currently in progress.".to_string(),
+                suggestion: Some("Complete the merge and commit, or abort: git merge --abort".to_string()),
+                commands: vec!["git merge --abort".to_string()],
+            });
         }
 
         // No changes staged
         if lower.contains("no changes added to commit") {
-            return (
-                "No files staged for commit.".to_string(),
-                Some("Stage files with: git add <file>".to_string()),
-            );
+            return Some(RuleMatch {
+                message: "No files staged for commit.".to_string(),
+                suggestion: Some("Stage files with: git add <file>".to_string()),
+                commands: vec![
+                    "git add -A".to_string(),
+                    "git status".to_string(),
+                ],
+            });
         }
 
-        // Default: return the error as-is with no suggestion
-        (error_text.to_string(), None)
+        None
     }
 }
 
@@ -214,6 +345,25 @@ mod tests {
         assert!(translated.suggestion.is_some());
     }
 
+    #[test]
+    fn test_translate_index_lock() {
+        let error = GitError::CommandExited {
+            command: "add .".to_string(),
+            args: vec!["add".to_string(), ".".to_string()],
+            exit_code: 128,
+            stdout: String::new(),
+            stderr: "fatal: Unable to create '/src/app/.git/index.lock': File exists.\n\n\
+                     Another git process seems to be running in this repository, e.g.\n\
+                     an editor opened by 'git commit'. Please make sure all processes\n\
+                     are terminated then try again."
+                .to_string(),
+        };
+        let translated = ErrorTranslator::translate(&error);
+
+        assert!(translated.simple_message.contains("index.lock"));
+        assert!(translated.recovery_commands.is_empty());
+    }
+
     #[test]
     fn test_translate_pathspec_not_found() {
         let error = GitError::CommandFailed("fatal: pathspec 'input.rs' did not match any files".to_string());
@@ -276,6 +426,149 @@ mod tests {
         assert!(translated.suggestion.is_none());
     }
 
+    #[test]
+    fn test_translate_structured_error_uses_stderr() {
+        let error = GitError::CommandExited {
+            command: "push".to_string(),
+            args: vec!["push".to_string()],
+            exit_code: 128,
+            stdout: String::new(),
+            stderr: "fatal: The current branch feature has no upstream branch.\n".to_string(),
+        };
+        let translated = ErrorTranslator::translate(&error);
+
+        assert!(translated.simple_message.contains("No remote branch"));
+        assert_eq!(translated.exit_code, Some(128));
+    }
+
+    #[test]
+    fn test_translate_structured_error_matches_stdout() {
+        let error = GitError::CommandExited {
+            command: "commit -m 'x'".to_string(),
+            args: vec!["commit".to_string(), "-m".to_string(), "x".to_string()],
+            exit_code: 1,
+            stdout: "On branch main\nnothing to commit, working tree clean\n".to_string(),
+            stderr: String::new(),
+        };
+        let translated = ErrorTranslator::translate(&error);
+
+        assert!(translated.simple_message.contains("No changes to commit"));
+    }
+
+    #[test]
+    fn test_translate_structured_unknown_error_falls_back_to_stderr() {
+        let error = GitError::CommandExited {
+            command: "frobnicate".to_string(),
+            args: vec!["frobnicate".to_string()],
+            exit_code: 1,
+            stdout: String::new(),
+            stderr: "  something odd happened  \n".to_string(),
+        };
+        let translated = ErrorTranslator::translate(&error);
+
+        assert_eq!(translated.simple_message, "something odd happened");
+        assert!(translated.suggestion.is_none());
+        assert!(translated.raw_error.contains("exit code 1"));
+    }
+
+    #[test]
+    fn test_custom_rules_take_precedence() {
+        use crate::error_translation::rules::ErrorRule;
+
+        let rules = ErrorRules::from_rules(vec![ErrorRule {
+            pattern: "(?i)authentication failed".to_string(),
+            message: "SSO session expired.".to_string(),
+            suggestion: Some("Run the company login helper.".to_string()),
+            commands: Vec::new(),
+        }])
+        .unwrap();
+
+        let error = GitError::CommandFailed("fatal: Authentication failed".to_string());
+        let translated = ErrorTranslator::translate_with_rules(&error, &rules);
+        assert_eq!(translated.simple_message, "SSO session expired.");
+
+        // Built-ins still apply when no custom rule matches
+        let error = GitError::CommandFailed("nothing to commit, working tree clean".to_string());
+        let translated = ErrorTranslator::translate_with_rules(&error, &rules);
+        assert!(translated.simple_message.contains("No changes to commit"));
+    }
+
+    #[test]
+    fn test_no_upstream_offers_push_with_branch() {
+        let error = GitError::CommandFailed("fatal: The current branch has no upstream branch".to_string());
+        let mut translated = ErrorTranslator::translate(&error);
+        assert_eq!(translated.recovery_commands, vec!["git push -u {remote} {branch}"]);
+
+        translated.resolve_branch(Some("feature/login"));
+        translated.resolve_remote(Some("origin"));
+        assert_eq!(translated.recovery_commands, vec!["git push -u origin feature/login"]);
+    }
+
+    #[test]
+    fn test_resolve_remote_drops_commands_without_remote() {
+        let error = GitError::CommandFailed("fatal: The current branch has no upstream branch".to_string());
+        let mut translated = ErrorTranslator::translate(&error);
+
+        translated.resolve_branch(Some("main"));
+        translated.resolve_remote(None);
+        assert!(translated.recovery_commands.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_branch_drops_commands_without_branch() {
+        let error = GitError::CommandFailed("fatal: The current branch has no upstream branch".to_string());
+        let mut translated = ErrorTranslator::translate(&error);
+
+        translated.resolve_branch(None);
+        assert!(translated.recovery_commands.is_empty());
+    }
+
+    #[test]
+    fn test_rebase_in_progress_offers_continue_and_abort() {
+        let error = GitError::CommandFailed("fatal: It seems that there is already a rebase in progress".to_string());
+        let translated = ErrorTranslator::translate(&error);
+
+        assert_eq!(
+            translated.recovery_commands,
+            vec!["git rebase --continue", "git rebase --abort"]
+        );
+    }
+
+    #[test]
+    fn test_conflicts_during_rebase_and_cherry_pick() {
+        let error = GitError::CommandFailed(
+            "CONFLICT (content): Merge conflict in src/lib.rs\nerror: could not apply 1a2b3c4... Add parser\n\
+             hint: Resolve all conflicts manually, mark them as resolved with\n\
+             hint: \"git add/rm <conflicted_files>\", then run \"git rebase --continue\"."
+                .to_string(),
+        );
+        assert_eq!(
+            ErrorTranslator::translate(&error).recovery_commands,
+            vec!["git rebase --continue", "git rebase --abort"]
+        );
+
+        let error = GitError::CommandFailed(
+            "error: could not apply 1a2b3c4... Add parser\n\
+             hint: After resolving the conflicts, mark them with\n\
+             hint: \"git add/rm <pathspec>\", then run\n\
+             hint: \"git cherry-pick --continue\".\n\
+             CONFLICT (content): Merge conflict in src/lib.rs"
+                .to_string(),
+        );
+        assert_eq!(
+            ErrorTranslator::translate(&error).recovery_commands,
+            vec!["git cherry-pick --continue", "git cherry-pick --abort"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_error_has_no_recovery_commands() {
+        let error = GitError::CommandFailed("Some unknown error message".to_string());
+        let translated = ErrorTranslator::translate(&error);
+
+        assert!(translated.recovery_commands.is_empty());
+    }
+
     #[test]
     fn test_raw_error_preserved() {
         let error = GitError::CommandFailed("fatal: pathspec 'test.rs' did not match any files".to_string());
@@ -0,0 +1,243 @@
+use crate::git::executor::quote_arg;
+use regex::Regex;
+
+/// Longest slug suggested from a description, cut at a word boundary
+const MAX_SLUG_LEN: usize = 40;
+
+/// Words a description may use for a conventional branch prefix
+const PREFIX_HINTS: &[(&str, &[&str])] = &[
+    ("feat", &["feature", "add", "new", "implement", "support"]),
+    ("fix", &["bug", "bugfix", "hotfix", "repair", "broken", "crash"]),
+    ("chore", &["bump", "upgrade", "update", "cleanup", "deps", "dependencies"]),
+    ("docs", &["doc", "readme", "document", "documentation"]),
+    ("refactor", &["rename", "restructure", "simplify", "extract"]),
+    ("test", &["tests", "testing", "coverage"]),
+];
+
+/// Words left out of suggested slugs
+const STOP_WORDS: &[&str] = &["a", "an", "the", "for", "to", "of", "on", "in", "and", "branch", "my", "some"];
+
+/// Phrases that ask to create a branch
+const NEW_BRANCH_PHRASES: &[&str] = &[
+    "new branch",
+    "create a branch",
+    "create branch",
+    "make a branch",
+    "start a branch",
+    "open a branch",
+    "branch off",
+];
+
+/// Words after which a new-branch query describes the branch
+const DESCRIPTION_MARKERS: &[&str] = &["for", "to", "called", "named", "about"];
+
+/// Check whether a natural language query asks to create a branch
+///
+/// Matched queries open the branch dialog, which names the branch by
+/// `git.branch_pattern` instead of leaving it to the LLM.
+pub fn is_new_branch_query(query: &str) -> bool {
+    let normalized = query.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ");
+    NEW_BRANCH_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// What the branch is for: "new branch for the login timeout" gives "the login timeout"
+pub fn branch_description(query: &str) -> String {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    words
+        .iter()
+        .position(|word| DESCRIPTION_MARKERS.contains(&word.to_lowercase().as_str()))
+        .map(|index| words[index + 1..].join(" "))
+        .unwrap_or_default()
+}
+
+/// Lowercase words of `description` joined by `-`, without filler words
+pub fn slugify(description: &str) -> String {
+    let lower = description.to_lowercase();
+    let mut slug = String::new();
+    for word in lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(word))
+    {
+        if !slug.is_empty() && slug.len() + 1 + word.len() > MAX_SLUG_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(word);
+    }
+    slug.truncate(MAX_SLUG_LEN);
+    slug
+}
+
+/// Why git would refuse `name` as a branch name (`git check-ref-format --branch`)
+pub fn ref_format_problem(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        Some("empty name")
+    } else if name.starts_with('-') {
+        Some("starts with '-'")
+    } else if name.chars().any(|c| c.is_whitespace() || c.is_control() || "~^:?*[\\".contains(c)) {
+        Some("contains a space or one of ~ ^ : ? * [ \\")
+    } else if name.contains("..") || name.contains("@{") || name.contains("//") {
+        Some("contains '..', '@{' or '//'")
+    } else if name.starts_with('/') || name.ends_with('/') || name.ends_with('.') || name.ends_with(".lock") {
+        Some("starts or ends with '/', or ends with '.' or '.lock'")
+    } else if name.split('/').any(|part| part.starts_with('.')) {
+        Some("a part starts with '.'")
+    } else if name == "@" {
+        Some("'@' alone")
+    } else {
+        None
+    }
+}
+
+/// `git switch -c <name>`, from `base` when given
+pub fn create_branch_command(name: &str, base: Option<&str>) -> String {
+    match base {
+        Some(base) => format!("git switch -c {} {}", quote_arg(name), quote_arg(base)),
+        None => format!("git switch -c {}", quote_arg(name)),
+    }
+}
+
+/// Naming convention new branches must follow, from `git.branch_pattern`
+#[derive(Debug, Clone, Default)]
+pub struct BranchPolicy {
+    pattern: Option<Regex>,
+}
+
+impl BranchPolicy {
+    /// A policy requiring names to match `pattern`; without one any valid name goes
+    pub fn new(pattern: Option<&str>) -> Result<Self, regex::Error> {
+        let pattern = pattern.filter(|p| !p.is_empty()).map(Regex::new).transpose()?;
+        Ok(Self { pattern })
+    }
+
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_ref().map(Regex::as_str)
+    }
+
+    /// Why `name` can't be used, if it can't
+    pub fn check(&self, name: &str) -> Result<(), String> {
+        if let Some(problem) = ref_format_problem(name) {
+            return Err(format!("not a valid branch name: {}", problem));
+        }
+        match self.pattern {
+            Some(ref pattern) if !pattern.is_match(name) => Err(format!("doesn't match {}", pattern.as_str())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Prefixes the pattern starts with: `^(feat|fix)/...` gives feat and fix,
+    /// `^feature/...` gives feature
+    pub fn prefixes(&self) -> Vec<String> {
+        let Some(pattern) = self.pattern() else {
+            return Vec::new();
+        };
+        let rest = pattern.strip_prefix('^').unwrap_or(pattern);
+        let Some((head, _)) = rest.split_once('/') else {
+            return Vec::new();
+        };
+        let head = head
+            .strip_prefix("(?:")
+            .or_else(|| head.strip_prefix('('))
+            .and_then(|group| group.strip_suffix(')'))
+            .unwrap_or(head);
+        let literal = |word: &&str| !word.is_empty() && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        let words: Vec<&str> = head.split('|').collect();
+        if words.iter().all(literal) {
+            words.into_iter().map(String::from).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// A name for a branch described by `description` that follows the policy
+    /// when it can: the slug, behind the prefix the description hints at
+    pub fn suggest(&self, description: &str) -> String {
+        let slug = slugify(description);
+        let words: Vec<String> = slug.split('-').map(String::from).collect();
+        let hinted = |prefix: &str| {
+            let hints = PREFIX_HINTS.iter().find(|(name, _)| *name == prefix).map_or(&[][..], |(_, hints)| *hints);
+            words.iter().any(|word| word == prefix || hints.contains(&word.as_str()))
+        };
+        let mut prefixes = self.prefixes();
+        // Hinted prefixes first, in pattern order otherwise
+        prefixes.sort_by_key(|prefix| !hinted(prefix));
+
+        let candidates = prefixes
+            .iter()
+            .map(|prefix| {
+                // "fix login timeout" names fix/login-timeout, not fix/fix-login-timeout
+                let rest = slug.strip_prefix(&format!("{}-", prefix)).unwrap_or(&slug);
+                format!("{}/{}", prefix, rest)
+            })
+            .chain(std::iter::once(slug.clone()));
+        let candidates: Vec<String> = candidates.collect();
+        candidates
+            .iter()
+            .find(|name| self.check(name).is_ok())
+            .unwrap_or(&candidates[0])
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONVENTIONAL: &str = "^(feat|fix|chore)/[a-z0-9-]+$";
+
+    #[test]
+    fn test_queries() {
+        assert!(is_new_branch_query("Create a branch for the login timeout"));
+        assert!(is_new_branch_query("new branch"));
+        assert!(!is_new_branch_query("delete the old branches"));
+        assert_eq!(branch_description("create a new branch for the login timeout"), "the login timeout");
+        assert_eq!(branch_description("new branch"), "");
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Fix the login timeout!"), "fix-login-timeout");
+        assert_eq!(slugify("Add OAuth2 support for GitHub & GitLab"), "add-oauth2-support-github-gitlab");
+        let long = slugify("one two three four five six seven eight nine ten eleven");
+        assert!(long.len() <= MAX_SLUG_LEN && !long.ends_with('-'), "{}", long);
+    }
+
+    #[test]
+    fn test_ref_format() {
+        assert!(ref_format_problem("feat/login").is_none());
+        for bad in ["", "-x", "a b", "a..b", "a/", "x.lock", "a:b", "a/.b", "@"] {
+            assert!(ref_format_problem(bad).is_some(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_policy_check_and_prefixes() {
+        let policy = BranchPolicy::new(Some(CONVENTIONAL)).unwrap();
+        assert!(policy.check("fix/login-timeout").is_ok());
+        assert!(policy.check("Fix/Login").unwrap_err().contains("doesn't match"));
+        assert!(policy.check("fix/a b").unwrap_err().contains("not a valid branch name"));
+        assert_eq!(policy.prefixes(), ["feat", "fix", "chore"]);
+        assert_eq!(BranchPolicy::new(Some("^feature/.+")).unwrap().prefixes(), ["feature"]);
+        assert!(BranchPolicy::new(Some("^[a-z]+/.+")).unwrap().prefixes().is_empty());
+        assert!(BranchPolicy::new(Some("(")).is_err());
+        assert!(BranchPolicy::new(None).unwrap().check("anything-goes").is_ok());
+    }
+
+    #[test]
+    fn test_suggest() {
+        let policy = BranchPolicy::new(Some(CONVENTIONAL)).unwrap();
+        assert_eq!(policy.suggest("Fix the login timeout"), "fix/login-timeout");
+        assert_eq!(policy.suggest("bump serde to 1.0.200"), "chore/bump-serde-1-0-200");
+        // No hint: the first prefix
+        assert_eq!(policy.suggest("dark mode"), "feat/dark-mode");
+        assert_eq!(BranchPolicy::new(None).unwrap().suggest("Dark mode"), "dark-mode");
+    }
+
+    #[test]
+    fn test_create_branch_command() {
+        assert_eq!(create_branch_command("feat/x", None), "git switch -c feat/x");
+        assert_eq!(create_branch_command("feat/x", Some("develop")), "git switch -c feat/x develop");
+    }
+}
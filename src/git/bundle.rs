@@ -0,0 +1,236 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::quote_arg;
+use crate::git::repository::Repository;
+use chrono::{DateTime, Local};
+
+/// Remote-tracking namespace the branches of an imported bundle land in
+pub const BUNDLE_REMOTE: &str = "bundle";
+
+/// Refs listed in the import preview before the rest are counted
+const PREVIEW_REFS: usize = 8;
+
+/// Phrases that ask to write the repository to a bundle file
+const BACKUP_PHRASES: &[&str] = &[
+    "to a bundle",
+    "into a bundle",
+    "as a bundle",
+    "create a bundle",
+    "make a bundle",
+    "bundle backup",
+    "bundle this repo",
+    "bundle the repo",
+];
+
+/// Phrases that ask to bring commits in from a bundle file
+const IMPORT_PHRASES: &[&str] = &[
+    "import this bundle",
+    "import the bundle",
+    "import a bundle",
+    "import bundle",
+    "from this bundle",
+    "from the bundle",
+    "from a bundle",
+    "unbundle",
+];
+
+fn normalize(query: &str) -> String {
+    query.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Check whether a natural language query asks to back the repository up to a bundle
+pub fn is_bundle_backup_query(query: &str) -> bool {
+    let normalized = normalize(query);
+    !is_bundle_import_query(query) && BACKUP_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// Check whether a natural language query asks to import a bundle
+pub fn is_bundle_import_query(query: &str) -> bool {
+    let normalized = normalize(query);
+    IMPORT_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// The `.bundle` file a query names, if any
+pub fn bundle_path_in(query: &str) -> Option<String> {
+    query
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c| matches!(c, '"' | '\'' | ',' | '?' | '!')))
+        .find(|word| word.len() > ".bundle".len() && word.ends_with(".bundle"))
+        .map(str::to_string)
+}
+
+/// `path` with a leading `~/` expanded; git gets no shell to do it
+pub fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home.trim_end_matches('/'), rest),
+        _ => path.to_string(),
+    }
+}
+
+/// Backup file next to the repository: `../project-20240131.bundle`
+pub fn default_bundle_path(repo: &Repository, now: DateTime<Local>) -> String {
+    let name = repo
+        .path()
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repository".to_string());
+    format!("../{}-{}.bundle", name, now.format("%Y%m%d"))
+}
+
+/// Write every branch and tag, with the commits they reach, to `path`
+pub fn backup_command(path: &str) -> String {
+    format!("git bundle create {} --all", quote_arg(path))
+}
+
+/// A ref a bundle carries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleRef {
+    /// `refs/heads/main`, `refs/tags/v1.0` or `HEAD`
+    pub name: String,
+    pub oid: String,
+}
+
+impl BundleRef {
+    pub fn is_branch(&self) -> bool {
+        self.name.starts_with("refs/heads/")
+    }
+
+    pub fn is_tag(&self) -> bool {
+        self.name.starts_with("refs/tags/")
+    }
+}
+
+/// Parse `git bundle list-heads` output: `<oid> <ref>` per line
+pub fn parse_heads(output: &str) -> Vec<BundleRef> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().split_once(' '))
+        .map(|(oid, name)| BundleRef {
+            name: name.trim().to_string(),
+            oid: oid.to_string(),
+        })
+        .collect()
+}
+
+/// Refs in the bundle at `path`, relative to the repository
+pub fn list_heads(repo: &Repository, path: &str) -> GitResult<Vec<BundleRef>> {
+    let output = repo.executor().execute(&format!("bundle list-heads {}", quote_arg(path)))?;
+    Ok(parse_heads(&output.stdout))
+}
+
+/// Check that the bundle at `path` is readable and that this repository has
+/// the commits it was cut from; the error carries git's explanation
+pub fn verify_bundle(repo: &Repository, path: &str) -> GitResult<()> {
+    match repo.executor().execute(&format!("bundle verify -q {}", quote_arg(path))) {
+        Ok(_) => Ok(()),
+        Err(GitError::CommandExited { stderr, .. }) => {
+            let reason = stderr
+                .lines()
+                .map(|line| line.trim().trim_start_matches("error: "))
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Err(GitError::CommandFailed(format!("{} can't be imported: {}", path, reason)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Verify the bundle, then fetch its branches to `bundle/*` (and its tags,
+/// when it has any) without touching local branches
+pub fn import_steps(path: &str, refs: &[BundleRef]) -> Vec<(String, String)> {
+    let path = quote_arg(path);
+    let mut fetch = format!(
+        "git fetch {} {}",
+        path,
+        quote_arg(&format!("+refs/heads/*:refs/remotes/{}/*", BUNDLE_REMOTE))
+    );
+    if refs.iter().any(BundleRef::is_tag) {
+        fetch.push_str(" 'refs/tags/*:refs/tags/*'");
+    }
+    vec![
+        ("Check the bundle is complete".to_string(), format!("git bundle verify {}", path)),
+        (format!("Fetch its branches as {}/<branch>", BUNDLE_REMOTE), fetch),
+    ]
+}
+
+/// Lines listing what a bundle holds, shown before anything is fetched
+pub fn describe_heads(path: &str, refs: &[BundleRef]) -> String {
+    let mut lines = vec![format!("{} holds {} ref(s):", path, refs.len())];
+    for head in refs.iter().take(PREVIEW_REFS) {
+        let short = head.oid.get(..7).unwrap_or(&head.oid);
+        let name = head.name.strip_prefix("refs/").unwrap_or(&head.name);
+        lines.push(format!("  {} {}", short, name));
+    }
+    if refs.len() > PREVIEW_REFS {
+        lines.push(format!("  ... and {} more", refs.len() - PREVIEW_REFS));
+    }
+    if !refs.iter().any(BundleRef::is_branch) {
+        lines.push("No branches: nothing lands in bundle/*".to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_queries() {
+        assert!(is_bundle_backup_query("back up this repo to a bundle"));
+        assert!(is_bundle_backup_query("Create a bundle of everything"));
+        assert!(!is_bundle_backup_query("import this bundle"));
+        assert!(is_bundle_import_query("import this bundle ~/usb/app.bundle"));
+        assert!(is_bundle_import_query("fetch the commits from the bundle"));
+        assert!(!is_bundle_import_query("show me the log"));
+        assert_eq!(bundle_path_in("import 'usb/app.bundle'").as_deref(), Some("usb/app.bundle"));
+        assert_eq!(bundle_path_in("import this bundle"), None);
+        assert_eq!(expand_home("../a.bundle"), "../a.bundle");
+    }
+
+    #[test]
+    fn test_parse_and_describe_heads() {
+        let refs = parse_heads(
+            "1a2b3c4d5e6f7a8b9c0d1a2b3c4d5e6f7a8b9c0d refs/heads/main\n\
+             0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f refs/tags/v1.0\n",
+        );
+        assert_eq!(refs.len(), 2);
+        assert!(refs[0].is_branch() && refs[1].is_tag());
+        assert_eq!(
+            describe_heads("app.bundle", &refs),
+            "app.bundle holds 2 ref(s):\n  1a2b3c4 heads/main\n  0f0f0f0 tags/v1.0"
+        );
+
+        let commands: Vec<String> = import_steps("my app.bundle", &refs).into_iter().map(|(_, c)| c).collect();
+        assert_eq!(
+            commands,
+            [
+                "git bundle verify 'my app.bundle'",
+                "git fetch 'my app.bundle' '+refs/heads/*:refs/remotes/bundle/*' 'refs/tags/*:refs/tags/*'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_and_list_real_bundle() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        git(temp.path(), &["commit", "-q", "--allow-empty", "-m", "One"]);
+        git(temp.path(), &["commit", "-q", "--allow-empty", "-m", "Two"]);
+        git(temp.path(), &["bundle", "create", "-q", "full.bundle", "--all"]);
+        // Only the last commit: needs "One" to be present already
+        git(temp.path(), &["bundle", "create", "-q", "thin.bundle", "HEAD~1..main"]);
+
+        let repo = Repository::new(temp.path());
+        let refs = list_heads(&repo, "full.bundle").unwrap();
+        assert!(refs.iter().any(|head| head.name == "refs/heads/main"));
+        assert!(verify_bundle(&repo, "full.bundle").is_ok());
+
+        let fresh = TempDir::new().unwrap();
+        init_repo(fresh.path());
+        let thin = temp.path().join("thin.bundle").display().to_string();
+        let error = verify_bundle(&Repository::new(fresh.path()), &thin).unwrap_err().to_string();
+        assert!(error.contains("can't be imported"), "{}", error);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::{GitExecutor, quote_arg};
+use crate::git::repository::Repository;
+use chrono::{DateTime, Local, TimeZone};
+use std::collections::BTreeMap;
+
+/// Namespace of the refs holding checkpoints
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/gitalky/checkpoints/";
+
+/// Characters the executor refuses in a command line, so also in names
+const FORBIDDEN_NAME_CHARS: &[char] = &['$', '`', '|', ';', '&'];
+
+/// What HEAD was on when a checkpoint was taken
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointHead {
+    Branch(String),
+    Detached,
+}
+
+/// HEAD and the position of every local branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoSnapshot {
+    pub head: CheckpointHead,
+    /// Full commit id of HEAD; `None` before the first commit
+    pub head_commit: Option<String>,
+    /// Branch names mapped to full commit ids
+    pub branches: BTreeMap<String, String>,
+}
+
+impl RepoSnapshot {
+    pub fn read(executor: &GitExecutor) -> GitResult<Self> {
+        let head = match executor.execute("symbolic-ref --short -q HEAD") {
+            Ok(output) => CheckpointHead::Branch(output.stdout.trim().to_string()),
+            Err(_) => CheckpointHead::Detached,
+        };
+        let head_commit = executor
+            .execute("rev-parse --verify -q HEAD")
+            .ok()
+            .map(|output| output.stdout.trim().to_string());
+        let branches = executor
+            .execute("for-each-ref '--format=%(refname:short) %(objectname)' refs/heads")?
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(name, commit)| (name.to_string(), commit.to_string()))
+            .collect();
+        Ok(Self {
+            head,
+            head_commit,
+            branches,
+        })
+    }
+}
+
+/// A named save point of the whole repository
+///
+/// Stored as a commit under [`CHECKPOINT_REF_PREFIX`] whose parents are HEAD,
+/// a `git stash create` of the index and tracked changes, and every branch
+/// tip, so none of them can be garbage collected while the checkpoint exists.
+/// The message records which parent is which.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub ref_name: String,
+    pub name: String,
+    pub created: DateTime<Local>,
+    pub snapshot: RepoSnapshot,
+    /// Stash commit of the index and tracked changes; `None` when clean
+    pub stash: Option<String>,
+}
+
+impl Checkpoint {
+    /// Record the current HEAD, branches, index and tracked changes as `name`
+    ///
+    /// Nothing in the working tree or index changes. Untracked files are not
+    /// part of the checkpoint.
+    pub fn create(repo: &Repository, name: &str) -> GitResult<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(GitError::CommandFailed("Give the checkpoint a name".to_string()));
+        }
+        if name.contains(FORBIDDEN_NAME_CHARS) || name.contains(char::is_control) {
+            return Err(GitError::CommandFailed(
+                "Checkpoint names can't contain $ ` | ; & or line breaks".to_string(),
+            ));
+        }
+
+        let executor = repo.executor();
+        let snapshot = RepoSnapshot::read(executor)?;
+        let Some(ref head_commit) = snapshot.head_commit else {
+            return Err(GitError::CommandFailed(
+                "Make a first commit before creating a checkpoint".to_string(),
+            ));
+        };
+        let stash = Some(executor.execute("stash create")?.stdout.trim().to_string()).filter(|s| !s.is_empty());
+
+        let mut parents: Vec<&str> = vec![head_commit];
+        parents.extend(stash.as_deref());
+        parents.extend(snapshot.branches.values().map(String::as_str));
+        let mut seen = std::collections::HashSet::new();
+        parents.retain(|commit| seen.insert(*commit));
+
+        let tree = executor.execute("rev-parse HEAD^{tree}")?.stdout.trim().to_string();
+        let parent_args: String = parents.iter().map(|commit| format!(" -p {}", commit)).collect();
+        // Fixed identity: the user's may be unset, and this is not their history
+        let commit = executor
+            .execute(&format!(
+                "-c user.name=gitalky -c user.email=gitalky@localhost commit-tree --no-gpg-sign{} -m {} {}",
+                parent_args,
+                quote_arg(&format_message(name, &snapshot, stash.as_deref())),
+                tree
+            ))?
+            .stdout
+            .trim()
+            .to_string();
+
+        let created = Local::now();
+        let ref_name = format!("{}{}-{}", CHECKPOINT_REF_PREFIX, created.timestamp(), slug(name));
+        executor.execute(&format!("update-ref {} {}", ref_name, commit))?;
+
+        Ok(Checkpoint {
+            ref_name,
+            name: name.to_string(),
+            created,
+            snapshot,
+            stash,
+        })
+    }
+
+    /// All checkpoints, newest first; refs that don't parse are skipped
+    pub fn list(repo: &Repository) -> GitResult<Vec<Self>> {
+        let executor = repo.executor();
+        let refs = executor.execute(&format!(
+            "for-each-ref --sort=-committerdate --format=%(refname) {}",
+            CHECKPOINT_REF_PREFIX.trim_end_matches('/')
+        ))?;
+        Ok(refs
+            .stdout
+            .lines()
+            .filter_map(|ref_name| {
+                let commit = executor.execute(&format!("cat-file commit {}", ref_name)).ok()?;
+                Self::parse(ref_name, &commit.stdout).ok()
+            })
+            .collect())
+    }
+
+    /// Parse a checkpoint commit as printed by `git cat-file commit`
+    fn parse(ref_name: &str, commit: &str) -> GitResult<Self> {
+        let invalid = || GitError::ParseError(format!("{} is not a gitalky checkpoint", ref_name));
+        let (headers, message) = commit.split_once("\n\n").ok_or_else(invalid)?;
+        let created = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("committer "))
+            .and_then(|committer| committer.rsplit(' ').nth(1))
+            .and_then(|secs| secs.parse().ok())
+            .and_then(|secs| Local.timestamp_opt(secs, 0).single())
+            .ok_or_else(invalid)?;
+
+        let mut lines = message.lines();
+        let name = lines.next().ok_or_else(invalid)?.to_string();
+        let mut snapshot = RepoSnapshot {
+            head: CheckpointHead::Detached,
+            head_commit: None,
+            branches: BTreeMap::new(),
+        };
+        let mut stash = None;
+        for line in lines {
+            if let Some(branch) = line.strip_prefix("head: branch ") {
+                snapshot.head = CheckpointHead::Branch(branch.to_string());
+            } else if let Some(commit) = line.strip_prefix("head-commit: ") {
+                snapshot.head_commit = Some(commit.to_string());
+            } else if let Some(commit) = line.strip_prefix("stash: ") {
+                stash = Some(commit.to_string());
+            } else if let Some((branch, commit)) = line.strip_prefix("branch ").and_then(|rest| rest.rsplit_once(' ')) {
+                snapshot.branches.insert(branch.to_string(), commit.to_string());
+            }
+        }
+        if snapshot.head_commit.is_none() {
+            return Err(invalid());
+        }
+
+        Ok(Checkpoint {
+            ref_name: ref_name.to_string(),
+            name,
+            created,
+            snapshot,
+            stash,
+        })
+    }
+
+    /// Delete the checkpoint; its commits stay reachable from wherever else they are
+    pub fn delete(&self, repo: &Repository) -> GitResult<()> {
+        repo.executor().execute(&format!("update-ref -d {}", self.ref_name))?;
+        Ok(())
+    }
+
+    /// Steps, as (intent, command), that bring the repository from `current`
+    /// back to this checkpoint
+    ///
+    /// Uncommitted changes are stashed first so nothing is lost. Branches
+    /// created after the checkpoint are left alone.
+    pub fn restore_steps(&self, current: &RepoSnapshot, dirty: bool) -> Vec<(String, String)> {
+        let mut steps = Vec::new();
+        let Some(ref head_commit) = self.snapshot.head_commit else {
+            return steps;
+        };
+        let short = |commit: &str| commit.chars().take(7).collect::<String>();
+
+        if dirty {
+            steps.push((
+                "set your current changes aside".to_string(),
+                format!(
+                    "git stash push --include-untracked -m {}",
+                    quote_arg(&format!("gitalky: before restoring checkpoint {}", self.name))
+                ),
+            ));
+        }
+
+        let head_branch = match self.snapshot.head {
+            CheckpointHead::Branch(ref branch) => {
+                let at_checkpoint = current.head == self.snapshot.head
+                    && current.head_commit.as_deref() == Some(head_commit.as_str());
+                if !at_checkpoint {
+                    steps.push((
+                        format!("check out {} at {}", branch, short(head_commit)),
+                        format!("git checkout -B {} {}", quote_arg(branch), head_commit),
+                    ));
+                }
+                Some(branch.as_str())
+            }
+            CheckpointHead::Detached => {
+                if current.head != CheckpointHead::Detached || current.head_commit.as_ref() != Some(head_commit) {
+                    steps.push((
+                        format!("detach HEAD at {}", short(head_commit)),
+                        format!("git checkout --detach {}", head_commit),
+                    ));
+                }
+                None
+            }
+        };
+
+        for (branch, commit) in &self.snapshot.branches {
+            if Some(branch.as_str()) == head_branch || current.branches.get(branch) == Some(commit) {
+                continue;
+            }
+            steps.push((
+                format!("move {} back to {}", branch, short(commit)),
+                format!("git branch -f {} {}", quote_arg(branch), commit),
+            ));
+        }
+
+        if let Some(ref stash) = self.stash {
+            steps.push((
+                "bring back the checkpoint's staged and unstaged changes".to_string(),
+                format!("git stash apply --index {}", stash),
+            ));
+        }
+        steps
+    }
+}
+
+fn format_message(name: &str, snapshot: &RepoSnapshot, stash: Option<&str>) -> String {
+    let mut message = format!("{}\n\n", name);
+    match snapshot.head {
+        CheckpointHead::Branch(ref branch) => message.push_str(&format!("head: branch {}\n", branch)),
+        CheckpointHead::Detached => message.push_str("head: detached\n"),
+    }
+    if let Some(ref commit) = snapshot.head_commit {
+        message.push_str(&format!("head-commit: {}\n", commit));
+    }
+    if let Some(stash) = stash {
+        message.push_str(&format!("stash: {}\n", stash));
+    }
+    for (branch, commit) in &snapshot.branches {
+        message.push_str(&format!("branch {} {}\n", branch, commit));
+    }
+    message
+}
+
+/// `Before Rebase!` → `before-rebase`
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    if slug.is_empty() { "checkpoint".to_string() } else { slug }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+
+    fn repo_with_history() -> (TempDir, Repository) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        for name in ["a.txt", "b.txt"] {
+            fs::write(dir.join(name), name).unwrap();
+            git(dir, &["add", name]);
+            git(dir, &["commit", "-q", "-m", name]);
+        }
+        git(dir, &["branch", "feature", "HEAD~1"]);
+        let repo = Repository::new(dir);
+        (temp, repo)
+    }
+
+    /// Run the restore steps the way the plan would
+    fn restore(repo: &Repository, checkpoint: &Checkpoint) {
+        let current = RepoSnapshot::read(repo.executor()).unwrap();
+        let dirty = !git(repo.path(), &["status", "--porcelain"]).is_empty();
+        for (_, command) in checkpoint.restore_steps(&current, dirty) {
+            repo.executor().execute(command.strip_prefix("git ").unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Before Rebase!"), "before-rebase");
+        assert_eq!(slug("  --  "), "checkpoint");
+    }
+
+    #[test]
+    fn test_create_and_list() {
+        let (_temp, repo) = repo_with_history();
+        fs::write(repo.path().join("a.txt"), "edited").unwrap();
+
+        let checkpoint = Checkpoint::create(&repo, "before rebase").unwrap();
+        assert!(checkpoint.ref_name.starts_with(CHECKPOINT_REF_PREFIX));
+        assert!(checkpoint.ref_name.ends_with("-before-rebase"));
+        assert!(checkpoint.stash.is_some());
+        // Creating a checkpoint leaves the working tree alone
+        assert_eq!(fs::read_to_string(repo.path().join("a.txt")).unwrap(), "edited");
+        assert_eq!(git(repo.path(), &["stash", "list"]), "");
+
+        let listed = Checkpoint::list(&repo).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "before rebase");
+        assert_eq!(listed[0].snapshot, checkpoint.snapshot);
+        assert_eq!(listed[0].stash, checkpoint.stash);
+        assert_eq!(listed[0].created.timestamp(), checkpoint.created.timestamp());
+
+        listed[0].delete(&repo).unwrap();
+        assert!(Checkpoint::list(&repo).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        let (_temp, repo) = repo_with_history();
+        assert!(Checkpoint::create(&repo, "  ").is_err());
+        assert!(Checkpoint::create(&repo, "a & b").is_err());
+        assert!(Checkpoint::create(&repo, "it's fine").is_ok());
+    }
+
+    #[test]
+    fn test_restore_round_trip() {
+        let (_temp, repo) = repo_with_history();
+        fs::write(repo.path().join("a.txt"), "staged").unwrap();
+        git(repo.path(), &["add", "a.txt"]);
+        fs::write(repo.path().join("b.txt"), "unstaged").unwrap();
+        let main = git(repo.path(), &["rev-parse", "main"]);
+        let feature = git(repo.path(), &["rev-parse", "feature"]);
+        let checkpoint = Checkpoint::create(&repo, "safe").unwrap();
+
+        // Wreck things: move both branches and switch away
+        git(repo.path(), &["reset", "-q", "--hard", "HEAD~1"]);
+        git(repo.path(), &["checkout", "-q", "feature"]);
+        fs::write(repo.path().join("c.txt"), "c").unwrap();
+        git(repo.path(), &["add", "c.txt"]);
+        git(repo.path(), &["commit", "-q", "-m", "c"]);
+
+        restore(&repo, &checkpoint);
+        assert_eq!(git(repo.path(), &["symbolic-ref", "--short", "HEAD"]), "main");
+        assert_eq!(git(repo.path(), &["rev-parse", "main"]), main);
+        assert_eq!(git(repo.path(), &["rev-parse", "feature"]), feature);
+        assert_eq!(git(repo.path(), &["status", "--porcelain"]), "M  a.txt\n M b.txt");
+        assert_eq!(git(repo.path(), &["diff", "--cached", "--name-only"]), "a.txt");
+        assert_eq!(fs::read_to_string(repo.path().join("b.txt")).unwrap(), "unstaged");
+
+        // Already there apart from the changes: only set them aside and reapply
+        let current = RepoSnapshot::read(repo.executor()).unwrap();
+        let steps = checkpoint.restore_steps(&current, true);
+        assert_eq!(steps.len(), 2, "{:?}", steps);
+        assert!(steps[0].1.starts_with("git stash push"));
+    }
+
+    #[test]
+    fn test_restore_steps_for_detached_head() {
+        let (_temp, repo) = repo_with_history();
+        git(repo.path(), &["checkout", "-q", "--detach", "feature"]);
+        let checkpoint = Checkpoint::create(&repo, "detached").unwrap();
+        git(repo.path(), &["checkout", "-q", "main"]);
+
+        let current = RepoSnapshot::read(repo.executor()).unwrap();
+        let steps = checkpoint.restore_steps(&current, false);
+        let commands: Vec<&str> = steps.iter().map(|(_, command)| command.as_str()).collect();
+        let feature = git(repo.path(), &["rev-parse", "feature"]);
+        assert_eq!(commands, vec![format!("git checkout --detach {}", feature)]);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::error::GitError;
+use crate::git::repository::Repository;
+use std::fmt;
+
+/// Something worth a second look in what a commit will contain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitWarning {
+    /// Trailing whitespace, space before tab, blank line at EOF, ...
+    Whitespace { file: String, line: usize, message: String },
+    /// `<<<<<<<`, `=======` or `>>>>>>>` left in a file
+    ConflictMarker { file: String, line: usize },
+    /// Added or modified file larger than the configured limit
+    LargeFile { file: String, bytes: u64 },
+    /// `user.email` isn't allowed by the identity rule for a remote's host;
+    /// `profile` is a configured identity that is
+    WrongIdentity { email: Option<String>, host: String, profile: Option<String> },
+}
+
+impl fmt::Display for CommitWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitWarning::Whitespace { file, line, message } => {
+                write!(f, "{}:{}: {}", file, line, message)
+            }
+            CommitWarning::ConflictMarker { file, line } => {
+                write!(f, "{}:{}: leftover conflict marker", file, line)
+            }
+            CommitWarning::LargeFile { file, bytes } => {
+                write!(f, "{}: large file ({})", file, format_size(*bytes))
+            }
+            CommitWarning::WrongIdentity { email, host, profile } => {
+                match email {
+                    Some(email) => write!(f, "{} isn't allowed for {} remotes", email, host)?,
+                    None => write!(f, "user.email is not set; {} remotes need an allowed email", host)?,
+                }
+                match profile {
+                    Some(label) => write!(f, " (i: switch to '{}' and commit)", label),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Run `git diff --check` and size checks against what a commit will contain
+///
+/// `diff_target` is `--cached` for a plain commit or `HEAD` for `commit -a`.
+/// Failures to run the checks yield no warnings rather than blocking the commit.
+pub fn check_commit(repo: &Repository, diff_target: &str, max_file_bytes: u64) -> Vec<CommitWarning> {
+    let mut warnings = diff_check(repo, diff_target);
+    warnings.extend(large_files(repo, diff_target, max_file_bytes));
+    warnings
+}
+
+/// Whitespace errors and conflict markers reported by `git diff --check`
+fn diff_check(repo: &Repository, diff_target: &str) -> Vec<CommitWarning> {
+    // --check exits with 2 when it finds problems; the report is on stdout either way
+    let output = match repo
+        .executor()
+        .execute(&format!("diff {} --check --no-color", diff_target))
+    {
+        Ok(output) => output.stdout,
+        Err(GitError::CommandExited { stdout, .. }) => stdout,
+        Err(_) => return Vec::new(),
+    };
+    parse_diff_check(&output)
+}
+
+/// Parse `git diff --check` output ("file:line: message" followed by the offending line)
+pub fn parse_diff_check(output: &str) -> Vec<CommitWarning> {
+    output
+        .lines()
+        .filter(|line| !line.starts_with('+') && !line.starts_with('-'))
+        .filter_map(|line| {
+            let (location, message) = line.split_once(": ")?;
+            let (file, line_no) = location.rsplit_once(':')?;
+            let line_no: usize = line_no.parse().ok()?;
+            let file = file.to_string();
+            Some(if message.contains("conflict marker") {
+                CommitWarning::ConflictMarker { file, line: line_no }
+            } else {
+                CommitWarning::Whitespace {
+                    file,
+                    line: line_no,
+                    message: message.trim_end_matches('.').to_string(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Added/modified files whose size exceeds `max_file_bytes`
+fn large_files(repo: &Repository, diff_target: &str, max_file_bytes: u64) -> Vec<CommitWarning> {
+    let Ok(output) = repo
+        .executor()
+        .execute(&format!("diff {} --name-only --diff-filter=AM", diff_target))
+    else {
+        return Vec::new();
+    };
+
+    output
+        .stdout
+        .lines()
+        .filter(|path| !path.is_empty())
+        .filter_map(|path| {
+            let bytes = std::fs::metadata(repo.path().join(path)).ok()?.len();
+            (bytes > max_file_bytes).then(|| CommitWarning::LargeFile {
+                file: path.to_string(),
+                bytes,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const MB: u64 = 1024 * 1024;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_diff_check() {
+        let output = "f.txt:1: trailing whitespace.\n+a  \nsrc/x.rs:12: leftover conflict marker\n";
+        assert_eq!(
+            parse_diff_check(output),
+            vec![
+                CommitWarning::Whitespace {
+                    file: "f.txt".to_string(),
+                    line: 1,
+                    message: "trailing whitespace".to_string(),
+                },
+                CommitWarning::ConflictMarker {
+                    file: "src/x.rs".to_string(),
+                    line: 12,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(1), "1 KB");
+        assert_eq!(format_size(2048), "2 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 + 512 * 1024), "3.5 MB");
+    }
+
+    #[test]
+    fn test_check_staged_changes() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        fs::write(dir.join("notes.txt"), "fine\ntrailing  \n<<<<<<< HEAD\n").unwrap();
+        fs::write(dir.join("blob.bin"), vec![0u8; 4096]).unwrap();
+        fs::write(dir.join("small.txt"), "ok\n").unwrap();
+        git(dir, &["add", "."]);
+
+        let warnings = check_commit(&Repository::new(dir), "--cached", 1024);
+        let text: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+
+        assert!(text.contains(&"notes.txt:2: trailing whitespace".to_string()));
+        assert!(text.contains(&"notes.txt:3: leftover conflict marker".to_string()));
+        assert!(text.contains(&"blob.bin: large file (4 KB)".to_string()));
+        assert!(!text.iter().any(|t| t.starts_with("small.txt")));
+    }
+}
@@ -0,0 +1,313 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::{git_command, quote_arg};
+use crate::git::repository::Repository;
+use std::fs;
+
+/// One `<<<<<<<` ... `>>>>>>>` block of a conflicted file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    pub ours: Vec<String>,
+    /// What the merge base had; `None` when neither the markers nor the
+    /// index stages tell
+    pub base: Option<Vec<String>>,
+    pub theirs: Vec<String>,
+    /// Labels after the markers: "HEAD", "feature", "1a2b3c4 (Fix the parser)"
+    pub ours_label: String,
+    pub theirs_label: String,
+    /// Line of the `<<<<<<<` marker, counting from 1
+    pub line: usize,
+}
+
+/// A run of lines outside or inside conflict markers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictSegment {
+    Clean(Vec<String>),
+    Conflict(ConflictHunk),
+}
+
+/// Which side of a hunk to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HunkChoice {
+    Ours,
+    Theirs,
+    /// Ours, then theirs
+    Both,
+}
+
+/// A conflicted file split into its hunks, with a choice per hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictFile {
+    /// Path relative to the repository root
+    pub path: String,
+    pub segments: Vec<ConflictSegment>,
+    choices: Vec<Option<HunkChoice>>,
+    trailing_newline: bool,
+}
+
+/// Split `text` at its conflict markers; an unterminated block stays as plain lines
+pub fn parse_conflicts(text: &str) -> Vec<ConflictSegment> {
+    let lines: Vec<&str> = text.split_terminator('\n').collect();
+    let mut segments = Vec::new();
+    let mut clean: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        match lines[index].strip_prefix("<<<<<<<").and_then(|rest| parse_hunk(&lines, index, rest)) {
+            Some((hunk, end)) => {
+                if !clean.is_empty() {
+                    segments.push(ConflictSegment::Clean(std::mem::take(&mut clean)));
+                }
+                segments.push(ConflictSegment::Conflict(hunk));
+                index = end;
+            }
+            None => {
+                clean.push(lines[index].to_string());
+                index += 1;
+            }
+        }
+    }
+    if !clean.is_empty() {
+        segments.push(ConflictSegment::Clean(clean));
+    }
+    segments
+}
+
+/// The hunk opened at `start`, and the index after its `>>>>>>>` line
+fn parse_hunk(lines: &[&str], start: usize, ours_label: &str) -> Option<(ConflictHunk, usize)> {
+    let mut ours = Vec::new();
+    let mut base: Option<Vec<String>> = None;
+    let mut theirs: Option<Vec<String>> = None;
+    for (index, line) in lines.iter().enumerate().skip(start + 1) {
+        let marker = line.trim_end_matches('\r');
+        if theirs.is_none() && marker.starts_with("|||||||") {
+            base = Some(Vec::new());
+        } else if theirs.is_none() && marker == "=======" {
+            theirs = Some(Vec::new());
+        } else if let Some(label) = marker.strip_prefix(">>>>>>>")
+            && let Some(theirs) = theirs.take()
+        {
+            let hunk = ConflictHunk {
+                ours,
+                base,
+                theirs,
+                ours_label: ours_label.trim().to_string(),
+                theirs_label: label.trim().to_string(),
+                line: start + 1,
+            };
+            return Some((hunk, index + 1));
+        } else if marker.starts_with("<<<<<<<") {
+            // Nested markers: leave it all to the editor
+            return None;
+        } else {
+            let side = match (&mut theirs, &mut base) {
+                (Some(theirs), _) => theirs,
+                (None, Some(base)) => base,
+                (None, None) => &mut ours,
+            };
+            side.push(line.to_string());
+        }
+    }
+    None
+}
+
+impl ConflictFile {
+    pub fn parse(path: &str, text: &str) -> Self {
+        let segments = parse_conflicts(text);
+        let hunks = segments
+            .iter()
+            .filter(|segment| matches!(segment, ConflictSegment::Conflict(_)))
+            .count();
+        Self {
+            path: path.to_string(),
+            segments,
+            choices: vec![None; hunks],
+            trailing_newline: text.ends_with('\n'),
+        }
+    }
+
+    /// Read `path` from the working tree; hunks whose markers lack the base
+    /// (the default `merge` conflict style) get it from the index stages
+    pub fn load(repo: &Repository, path: &str) -> GitResult<Self> {
+        let bytes = fs::read(repo.path().join(path))
+            .map_err(|e| GitError::CommandFailed(format!("Couldn't read {}: {}", path, e)))?;
+        let mut file = Self::parse(path, &String::from_utf8_lossy(&bytes));
+        if file.hunks().any(|hunk| hunk.base.is_none())
+            && let Some(bases) = stage_bases(repo, path)
+            // Only trustworthy while the hunks still line up with git's
+            && bases.len() == file.hunk_count()
+        {
+            let hunks = file.segments.iter_mut().filter_map(|segment| match segment {
+                ConflictSegment::Conflict(hunk) => Some(hunk),
+                ConflictSegment::Clean(_) => None,
+            });
+            for (hunk, base) in hunks.zip(bases) {
+                hunk.base.get_or_insert(base);
+            }
+        }
+        Ok(file)
+    }
+
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|segment| match segment {
+            ConflictSegment::Conflict(hunk) => Some(hunk),
+            ConflictSegment::Clean(_) => None,
+        })
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.choices.len()
+    }
+
+    pub fn choice(&self, index: usize) -> Option<HunkChoice> {
+        self.choices.get(index).copied().flatten()
+    }
+
+    /// Keep a side of hunk `index`, or undo the choice with `None`
+    pub fn choose(&mut self, index: usize, choice: Option<HunkChoice>) {
+        if let Some(slot) = self.choices.get_mut(index) {
+            *slot = choice;
+        }
+    }
+
+    /// Hunks without a choice yet
+    pub fn undecided(&self) -> usize {
+        self.choices.iter().filter(|choice| choice.is_none()).count()
+    }
+
+    /// The file with every hunk replaced by its chosen side; `None` while
+    /// some are undecided
+    pub fn resolved_text(&self) -> Option<String> {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut choices = self.choices.iter();
+        for segment in &self.segments {
+            match segment {
+                ConflictSegment::Clean(clean) => lines.extend(clean.iter().map(String::as_str)),
+                ConflictSegment::Conflict(hunk) => match (*choices.next()?)? {
+                    HunkChoice::Ours => lines.extend(hunk.ours.iter().map(String::as_str)),
+                    HunkChoice::Theirs => lines.extend(hunk.theirs.iter().map(String::as_str)),
+                    HunkChoice::Both => {
+                        lines.extend(hunk.ours.iter().map(String::as_str));
+                        lines.extend(hunk.theirs.iter().map(String::as_str));
+                    }
+                },
+            }
+        }
+        let mut text = lines.join("\n");
+        if self.trailing_newline && !text.is_empty() {
+            text.push('\n');
+        }
+        Some(text)
+    }
+}
+
+/// Base side of each hunk, from re-merging the stage blobs (`:1:` base,
+/// `:2:` ours, `:3:` theirs) with diff3 markers; `None` when a stage is
+/// missing or git fails
+fn stage_bases(repo: &Repository, path: &str) -> Option<Vec<Vec<String>>> {
+    let stage = |number: u8| {
+        repo.executor()
+            .execute(&format!("show {}", quote_arg(&format!(":{}:{}", number, path))))
+            .ok()
+            .map(|output| output.stdout)
+    };
+    // No base stage when both sides added the file
+    let base = stage(1).unwrap_or_default();
+    let (ours, theirs) = (stage(2)?, stage(3)?);
+
+    // merge-file reads files; they live in the git dir only while it runs
+    let dir = repo.git_dir().join("gitalky-conflict");
+    fs::create_dir_all(&dir).ok()?;
+    let names = ["ours", "base", "theirs"];
+    for (name, contents) in names.iter().zip([&ours, &base, &theirs]) {
+        fs::write(dir.join(name), contents).ok()?;
+    }
+    let output = git_command()
+        .args(["merge-file", "-p", "--diff3"])
+        .args(names.iter().map(|name| dir.join(name)))
+        .current_dir(repo.path())
+        .output();
+    let _ = fs::remove_dir_all(&dir);
+
+    // The exit code is the number of conflicts; negative on errors
+    let output = output.ok().filter(|output| output.status.code().is_some_and(|code| code >= 0))?;
+    let merged = String::from_utf8_lossy(&output.stdout);
+    Some(
+        parse_conflicts(&merged)
+            .into_iter()
+            .filter_map(|segment| match segment {
+                ConflictSegment::Conflict(hunk) => hunk.base,
+                ConflictSegment::Clean(_) => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo, try_git};
+    use tempfile::TempDir;
+
+    const DIFF3: &str = "fn main() {\n<<<<<<< HEAD\n    run(1);\n||||||| base\n    run();\n=======\n    run(2);\n>>>>>>> feature\n}\n";
+
+    #[test]
+    fn test_parse_diff3_markers() {
+        let file = ConflictFile::parse("src/main.rs", DIFF3);
+        assert_eq!(file.hunk_count(), 1);
+        let hunk = file.hunks().next().unwrap();
+        assert_eq!(hunk.ours, ["    run(1);"]);
+        assert_eq!(hunk.base.as_deref(), Some(&["    run();".to_string()][..]));
+        assert_eq!(hunk.theirs, ["    run(2);"]);
+        assert_eq!((hunk.ours_label.as_str(), hunk.theirs_label.as_str(), hunk.line), ("HEAD", "feature", 2));
+    }
+
+    #[test]
+    fn test_unterminated_markers_stay_text() {
+        let text = "a\n<<<<<<< HEAD\nb\n=======\nc\n";
+        assert_eq!(
+            parse_conflicts(text),
+            [ConflictSegment::Clean(vec!["a", "<<<<<<< HEAD", "b", "=======", "c"].into_iter().map(String::from).collect())]
+        );
+    }
+
+    #[test]
+    fn test_resolved_text_per_hunk() {
+        let text = format!("{}<<<<<<< HEAD\nx\n=======\ny\n>>>>>>> feature\n", DIFF3);
+        let mut file = ConflictFile::parse("src/main.rs", &text);
+        assert_eq!(file.hunk_count(), 2);
+        file.choose(0, Some(HunkChoice::Theirs));
+        assert_eq!(file.resolved_text(), None);
+        assert_eq!(file.undecided(), 1);
+        file.choose(1, Some(HunkChoice::Both));
+        assert_eq!(file.resolved_text().unwrap(), "fn main() {\n    run(2);\n}\nx\ny\n");
+        file.choose(1, Some(HunkChoice::Ours));
+        assert_eq!(file.resolved_text().unwrap(), "fn main() {\n    run(2);\n}\nx\n");
+    }
+
+    #[test]
+    fn test_load_takes_base_from_stages() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let commit = |contents: &str, message: &str| {
+            fs::write(dir.join("a.txt"), contents).unwrap();
+            git(dir, &["commit", "-q", "-am", message]);
+        };
+        init_repo(dir);
+        git(dir, &["config", "merge.conflictstyle", "merge"]);
+        fs::write(dir.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        git(dir, &["add", "a.txt"]);
+        git(dir, &["commit", "-q", "-m", "Base"]);
+        git(dir, &["checkout", "-q", "-b", "feature"]);
+        commit("one\nTWO\nthree\n", "Shout");
+        git(dir, &["checkout", "-q", "main"]);
+        commit("one\n2\nthree\n", "Number");
+        assert!(!try_git(dir, &["merge", "feature"]));
+
+        let repo = Repository::new(dir);
+        let file = ConflictFile::load(&repo, "a.txt").unwrap();
+        let hunk = file.hunks().next().unwrap();
+        assert_eq!(hunk.ours, ["2"]);
+        assert_eq!(hunk.theirs, ["TWO"]);
+        assert_eq!(hunk.base.as_deref(), Some(&["two".to_string()][..]));
+        assert!(!repo.git_dir().join("gitalky-conflict").exists());
+    }
+}
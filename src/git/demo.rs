@@ -0,0 +1,125 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::quote_arg;
+use crate::git::repository::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File the tutorial edits to produce a change
+pub const DEMO_CHANGED_FILE: &str = "notes.txt";
+
+/// Files and commit messages of the demo history, oldest first
+const DEMO_HISTORY: &[(&str, &str, &str)] = &[
+    ("README.md", "# Demo project\n\nA throwaway repository for the gitalky tutorial.\n", "Add README"),
+    ("notes.txt", "Things to try:\n- stage a change\n", "Add notes"),
+    ("src/main.rs", "fn main() {\n    println!(\"hello\");\n}\n", "Add hello world"),
+];
+
+/// A throwaway repository with a small history, deleted on drop
+///
+/// Used by the tutorial so new users can try commands (including destructive
+/// ones) without touching a real repository.
+#[derive(Debug)]
+pub struct DemoRepo {
+    path: PathBuf,
+}
+
+impl DemoRepo {
+    /// Create a demo repository in the system temp directory
+    pub fn create() -> GitResult<Self> {
+        Self::create_in(&std::env::temp_dir())
+    }
+
+    /// Create a demo repository in a new directory under `parent`
+    pub fn create_in(parent: &Path) -> GitResult<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let path = parent.join(format!("gitalky-tutorial-{}-{}", std::process::id(), nanos));
+        fs::create_dir_all(&path).map_err(io_error)?;
+
+        // Dropped (and the directory removed) if any step below fails
+        let demo = Self { path };
+        let repo = demo.repository();
+        let executor = repo.executor();
+        executor.execute("init -q")?;
+        // Local identity and no signing, so commits work on any machine
+        executor.execute("config user.name 'Gitalky Tutorial'")?;
+        executor.execute("config user.email tutorial@example.invalid")?;
+        executor.execute("config commit.gpgsign false")?;
+
+        for (file, contents, message) in DEMO_HISTORY {
+            demo.write(file, contents)?;
+            executor.execute(&format!("add -- {}", quote_arg(file)))?;
+            executor.execute(&format!("commit -q -m {}", quote_arg(message)))?;
+        }
+        Ok(demo)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn repository(&self) -> Repository {
+        Repository::new(&self.path)
+    }
+
+    /// Edit [`DEMO_CHANGED_FILE`] as a user would in their editor
+    pub fn make_change(&self) -> GitResult<()> {
+        let path = self.path.join(DEMO_CHANGED_FILE);
+        let mut contents = fs::read_to_string(&path).map_err(io_error)?;
+        contents.push_str("- commit it\n- undo a mistake\n");
+        fs::write(path, contents).map_err(io_error)
+    }
+
+    fn write(&self, file: &str, contents: &str) -> GitResult<()> {
+        let path = self.path.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        fs::write(path, contents).map_err(io_error)
+    }
+}
+
+impl Drop for DemoRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn io_error(e: std::io::Error) -> GitError {
+    GitError::CommandFailed(format!("Failed to set up demo repository: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_demo_repo_has_history() {
+        let temp = TempDir::new().unwrap();
+        let demo = DemoRepo::create_in(temp.path()).unwrap();
+        let repo = demo.repository();
+
+        let state = repo.state().unwrap();
+        assert!(state.is_clean());
+        assert_eq!(state.recent_commits.len(), DEMO_HISTORY.len());
+
+        demo.make_change().unwrap();
+        let state = repo.state().unwrap();
+        assert_eq!(state.unstaged_files[0].path, DEMO_CHANGED_FILE);
+    }
+
+    #[test]
+    fn test_demo_repo_removed_on_drop() {
+        let temp = TempDir::new().unwrap();
+        let demo = DemoRepo::create_in(temp.path()).unwrap();
+        let path = demo.path().to_path_buf();
+        assert!(path.join(".git").exists());
+
+        drop(demo);
+        assert!(!path.exists());
+    }
+}
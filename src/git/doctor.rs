@@ -0,0 +1,141 @@
+use crate::error::GitError;
+use crate::git::version::GitVersion;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// One finding of `gitalky doctor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+    /// What to do about a failed check
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    pub fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            ok: true,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    pub fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            ok: false,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Where `binary` resolves to: itself when it names a path, else the first
+/// match on PATH
+pub fn resolve_binary(binary: &Path) -> Option<PathBuf> {
+    if binary.components().count() > 1 {
+        return binary.is_file().then(|| binary.to_path_buf());
+    }
+    let names: Vec<PathBuf> = if cfg!(windows) {
+        vec![binary.with_extension("exe"), binary.to_path_buf()]
+    } else {
+        vec![binary.to_path_buf()]
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Whether the git at `binary` can be found and is recent enough;
+/// `configured` when it came from `git.binary_path`
+pub fn check_git(binary: &Path, configured: bool) -> Vec<DoctorCheck> {
+    let fix = if configured {
+        "Fix git.binary_path in ~/.config/gitalky/config.toml, or remove it to use git from PATH"
+    } else {
+        "Install git, or set git.binary_path in ~/.config/gitalky/config.toml to its full path"
+    };
+    let source = if configured { "git.binary_path" } else { "PATH" };
+
+    let Some(resolved) = resolve_binary(binary) else {
+        return vec![DoctorCheck::fail(
+            "git binary",
+            format!("{} not found (from {})", binary.display(), source),
+            fix,
+        )];
+    };
+    let binary_check = DoctorCheck::pass("git binary", format!("{} (from {})", resolved.display(), source));
+
+    let version_check = match GitVersion::validate_at(&resolved) {
+        Ok(version) => DoctorCheck::pass(
+            "git version",
+            format!("{} ({} or newer required)", version, GitVersion::minimum()),
+        ),
+        Err(GitError::GitVersionTooOld(_)) => {
+            let found = GitVersion::detect_at(&resolved).map(|v| v.to_string()).unwrap_or_default();
+            DoctorCheck::fail(
+                "git version",
+                format!("{} is older than {}", found, GitVersion::minimum()),
+                format!("Upgrade git, or point git.binary_path at a {} or newer one", GitVersion::minimum()),
+            )
+        }
+        Err(e) => DoctorCheck::fail("git version", e.to_string(), fix),
+    };
+    vec![binary_check, version_check]
+}
+
+/// Checks as printed by `gitalky doctor`, one per line with hints below failures
+pub fn render_checks(checks: &[DoctorCheck]) -> String {
+    let width = checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for check in checks {
+        let mark = if check.ok { "✓" } else { "✗" };
+        out.push_str(&format!("{} {:<width$}  {}\n", mark, check.name, check.detail, width = width));
+        if let Some(ref hint) = check.hint {
+            out.push_str(&format!("  {:<width$}  → {}\n", "", hint, width = width));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_git_from_path() {
+        let checks = check_git(Path::new("git"), false);
+        assert!(checks.iter().all(|check| check.ok), "{:?}", checks);
+        assert!(checks[0].detail.ends_with("(from PATH)"));
+        assert!(checks[1].detail.contains("or newer required"));
+    }
+
+    #[test]
+    fn test_check_missing_configured_binary() {
+        let checks = check_git(Path::new("/nonexistent/bin/git"), true);
+        assert_eq!(checks.len(), 1);
+        assert!(!checks[0].ok);
+        assert_eq!(checks[0].detail, "/nonexistent/bin/git not found (from git.binary_path)");
+
+        let rendered = render_checks(&checks);
+        assert!(rendered.starts_with("✗ git binary  /nonexistent/bin/git not found"));
+        assert!(rendered.contains("→ Fix git.binary_path"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_old_git() {
+        use std::os::unix::fs::PermissionsExt;
+        let temp = tempfile::TempDir::new().unwrap();
+        let fake = temp.path().join("git");
+        std::fs::write(&fake, "#!/bin/sh\necho 'git version 2.1.4'\n").unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let checks = check_git(&fake, true);
+        assert!(checks[0].ok);
+        assert!(!checks[1].ok);
+        assert_eq!(checks[1].detail, "2.1.4 is older than 2.20");
+    }
+}
@@ -1,6 +1,8 @@
 use crate::error::{GitError, GitResult};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 /// Result of executing a git command
@@ -10,6 +12,90 @@ pub struct CommandOutput {
     pub stderr: String,
     pub exit_code: i32,
     pub success: bool,
+    /// The raw bytes when stdout was binary or not valid UTF-8; `stdout`
+    /// then holds a lossy conversion
+    pub raw_stdout: Option<Vec<u8>>,
+}
+
+impl CommandOutput {
+    /// Whether stdout is binary data rather than (possibly mis-encoded) text
+    pub fn is_binary(&self) -> bool {
+        self.raw_stdout.as_deref().is_some_and(is_binary)
+    }
+}
+
+/// Bytes git itself would treat as binary: a NUL in the first 8000 bytes
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.iter().take(8000).any(|&b| b == 0)
+}
+
+/// Locale for commands whose output gitalky parses, so parsers and error
+/// patterns see git's untranslated messages
+pub const DEFAULT_PARSE_LOCALE: &str = "C";
+
+/// Config pinned for parsed commands, in `GIT_CONFIG_PARAMETERS` form:
+/// user settings that would add colour codes or signature lines to output
+const PARSE_CONFIG_PARAMETERS: &str = "'color.ui=never' 'log.showsignature=false'";
+
+static PARSE_LOCALE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Git binary used when `git.binary_path` is unset: whichever `git` is on PATH
+pub const DEFAULT_GIT_BINARY: &str = "git";
+
+static GIT_BINARY: OnceLock<PathBuf> = OnceLock::new();
+
+/// Run `path` for every git command instead of `git` from PATH
+///
+/// Call once at startup, before running any git command; later calls are ignored.
+pub fn set_git_binary(path: Option<PathBuf>) {
+    let _ = GIT_BINARY.set(path.unwrap_or_else(|| PathBuf::from(DEFAULT_GIT_BINARY)));
+}
+
+/// The git binary commands run with
+pub fn git_binary() -> &'static Path {
+    GIT_BINARY.get_or_init(|| PathBuf::from(DEFAULT_GIT_BINARY))
+}
+
+/// A command for the configured git binary, with nothing else set up
+pub fn git_command() -> Command {
+    Command::new(git_binary())
+}
+
+/// Set the locale parsed commands run in; `None` leaves them in the user's
+///
+/// Call once at startup, before running any git command; later calls are ignored.
+pub fn set_parse_locale(locale: Option<String>) {
+    let _ = PARSE_LOCALE.set(locale);
+}
+
+fn parse_locale() -> Option<&'static str> {
+    PARSE_LOCALE
+        .get_or_init(|| Some(DEFAULT_PARSE_LOCALE.to_string()))
+        .as_deref()
+}
+
+/// Git subprocesses spawned by any executor that haven't exited yet
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of git commands currently running in this process
+pub fn running_commands() -> usize {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+/// Counts a git subprocess in [`running_commands`] until dropped
+struct RunningGuard;
+
+impl RunningGuard {
+    fn start() -> Self {
+        RUNNING.fetch_add(1, Ordering::SeqCst);
+        RunningGuard
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// Executes git commands within a repository
@@ -26,7 +112,11 @@ impl GitExecutor {
         }
     }
 
-    /// Execute a git command and return the output
+    /// Execute a git command whose output will be parsed and return the output
+    ///
+    /// Runs in the parse locale (see [`set_parse_locale`]) with colour and
+    /// signature output turned off. Commands the user asked for should go
+    /// through [`execute_for_user`](Self::execute_for_user) instead.
     ///
     /// The command string should not include "git" prefix
     /// Example: executor.execute("status --porcelain")
@@ -35,7 +125,26 @@ impl GitExecutor {
     }
 
     /// Execute a git command with a custom timeout
+    #[tracing::instrument(name = "execution", level = "debug", skip(self, _timeout))]
     pub fn execute_with_timeout(&self, command: &str, _timeout: Duration) -> GitResult<CommandOutput> {
+        self.run(command, true, None)
+    }
+
+    /// Execute a command the user asked for, in their own locale and config
+    pub fn execute_for_user(&self, command: &str) -> GitResult<CommandOutput> {
+        self.run(command, false, None)
+    }
+
+    /// Execute a command the user asked for with `GIT_EDITOR` set to `editor`
+    ///
+    /// Output is captured, so the editor can't be interactive: `true` accepts
+    /// the message git would have opened, as `git rebase --continue` does after
+    /// conflicts.
+    pub fn execute_for_user_with_editor(&self, command: &str, editor: &str) -> GitResult<CommandOutput> {
+        self.run(command, false, Some(editor))
+    }
+
+    fn run(&self, command: &str, for_parsing: bool, editor: Option<&str>) -> GitResult<CommandOutput> {
         // Basic input sanitization - no shell interpolation
         if command.contains('$') || command.contains('`') {
             return Err(GitError::CommandFailed(
@@ -56,6 +165,82 @@ impl GitExecutor {
             return Err(GitError::CommandFailed("Empty command".to_string()));
         }
 
+        let mut cmd = self.sanitized_command(&args);
+        if for_parsing {
+            pin_output_format(&mut cmd);
+        }
+        if let Some(editor) = editor {
+            cmd.env("GIT_EDITOR", editor);
+        }
+
+        // Execute git command
+        let started = std::time::Instant::now();
+        let running = RunningGuard::start();
+        let output = cmd
+            .output()
+            .map_err(|e| GitError::CommandFailed(format!("Failed to execute git: {}", e)))?;
+        drop(running);
+        tracing::debug!(
+            exit_code = ?output.status.code(),
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "git finished"
+        );
+
+        self.process_output(output, command, args)
+    }
+
+    /// Execute a git command with its output streamed to the terminal
+    ///
+    /// Used outside the TUI (e.g. cloning from the startup screen) so git's
+    /// own progress display is visible. Stdout/stderr are not captured, and
+    /// the command runs in the user's locale.
+    pub fn execute_with_progress(&self, command: &str) -> GitResult<()> {
+        self.run_attached(command, None)
+    }
+
+    /// Execute a git command attached to the terminal, with `editor` as
+    /// `GIT_EDITOR`, for commands that open an editor (`rebase --edit-todo`)
+    ///
+    /// The caller must have suspended the TUI.
+    pub fn execute_interactive(&self, command: &str, editor: &str) -> GitResult<()> {
+        self.run_attached(command, Some(editor))
+    }
+
+    fn run_attached(&self, command: &str, editor: Option<&str>) -> GitResult<()> {
+        if command.contains('$') || command.contains('`') {
+            return Err(GitError::CommandFailed(
+                "Command contains potentially unsafe characters".to_string(),
+            ));
+        }
+
+        let args = self.parse_command(command)?;
+        if args.is_empty() {
+            return Err(GitError::CommandFailed("Empty command".to_string()));
+        }
+
+        let _running = RunningGuard::start();
+        let mut cmd = self.sanitized_command(&args);
+        if let Some(editor) = editor {
+            cmd.env("GIT_EDITOR", editor);
+        }
+        let status = cmd
+            .status()
+            .map_err(|e| GitError::CommandFailed(format!("Failed to execute git: {}", e)))?;
+
+        if !status.success() {
+            return Err(GitError::CommandExited {
+                command: command.to_string(),
+                args,
+                exit_code: status.code().unwrap_or(-1),
+                stdout: String::new(),
+                stderr: String::new(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Build a git command running in the repository with a sanitized environment
+    fn sanitized_command(&self, args: &[String]) -> Command {
         // Sanitize environment: remove dangerous git environment variables
         // These can be used to execute arbitrary code via git hooks/editors/etc
         let safe_env_vars = [
@@ -70,9 +255,8 @@ impl GitExecutor {
             "TMPDIR",
         ];
 
-        // Build command with sanitized environment
-        let mut cmd = Command::new("git");
-        cmd.args(&args)
+        let mut cmd = git_command();
+        cmd.args(args)
             .current_dir(&self.repo_path)
             .env_clear(); // Start with clean environment
 
@@ -82,108 +266,49 @@ impl GitExecutor {
                 cmd.env(var, value);
             }
         }
-
-        // Execute git command
-        let output = cmd
-            .output()
-            .map_err(|e| GitError::CommandFailed(format!("Failed to execute git: {}", e)))?;
-
-        self.process_output(output, command)
+        cmd
     }
 
-    /// Parse command string respecting single and double quotes
+    /// Parse command string into arguments using shell-words rules
     ///
-    /// # Limitations
-    ///
-    /// This parser does NOT support:
-    /// - Escape sequences (`\"` or `\'`) - quotes must be balanced, not escaped
-    /// - Nested quotes of the same type
-    /// - ANSI-C quoting (`$'...'`)
-    /// - Unicode escape sequences
-    ///
-    /// These limitations are acceptable because:
-    /// 1. Git commands rarely need escaped quotes
-    /// 2. The validator blocks complex inputs before they reach the parser
-    /// 3. Security is prioritized over expressiveness
-    ///
-    /// # Examples
-    ///
-    /// ```text
-    /// Supported:
-    ///   commit -m "test message"      → ["commit", "-m", "test message"]
-    ///   commit -m 'it works'          → ["commit", "-m", "it works"]
-    ///   commit -m "It's working"      → ["commit", "-m", "It's working"]
-    ///
-    /// NOT Supported (will fail or behave unexpectedly):
-    ///   commit -m "He said \"hi\""    → Error or unexpected parsing
-    ///   commit -m 'can\'t'            → Error (unclosed quote)
-    /// ```
+    /// See [`split_command`] for the supported syntax.
     fn parse_command(&self, command: &str) -> GitResult<Vec<String>> {
-        let mut args = Vec::new();
-        let mut current_arg = String::new();
-        let mut in_single_quote = false;
-        let mut in_double_quote = false;
-
-        for ch in command.chars() {
-            match ch {
-                '\'' if !in_double_quote => {
-                    in_single_quote = !in_single_quote;
-                }
-                '"' if !in_single_quote => {
-                    in_double_quote = !in_double_quote;
-                }
-                ' ' | '\t' if !in_single_quote && !in_double_quote => {
-                    if !current_arg.is_empty() {
-                        args.push(current_arg.clone());
-                        current_arg.clear();
-                    }
-                }
-                _ => {
-                    current_arg.push(ch);
-                }
-            }
-        }
-
-        // Push final argument if any
-        if !current_arg.is_empty() {
-            args.push(current_arg);
-        }
-
-        // Check for unclosed quotes
-        if in_single_quote || in_double_quote {
-            return Err(GitError::CommandFailed(
-                "Unclosed quote in command".to_string(),
-            ));
-        }
-
-        Ok(args)
+        split_command(command)
     }
 
     /// Process command output into CommandOutput struct
-    fn process_output(&self, output: Output, command: &str) -> GitResult<CommandOutput> {
+    fn process_output(
+        &self,
+        output: Output,
+        command: &str,
+        args: Vec<String>,
+    ) -> GitResult<CommandOutput> {
+        // Keep the bytes only when the text form loses something
+        let raw_stdout = (is_binary(&output.stdout) || std::str::from_utf8(&output.stdout).is_err())
+            .then(|| output.stdout.clone());
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let exit_code = output.status.code().unwrap_or(-1);
         let success = output.status.success();
 
-        let cmd_output = CommandOutput {
-            stdout,
-            stderr: stderr.clone(),
-            exit_code,
-            success,
-        };
-
-        // Return error for failed commands
+        // Return structured error for failed commands so callers keep the details
         if !success {
-            return Err(GitError::CommandFailed(format!(
-                "Command 'git {}' failed with exit code {}: {}",
-                command,
+            return Err(GitError::CommandExited {
+                command: command.to_string(),
+                args,
                 exit_code,
-                stderr.trim()
-            )));
+                stdout,
+                stderr,
+            });
         }
 
-        Ok(cmd_output)
+        Ok(CommandOutput {
+            stdout,
+            stderr,
+            exit_code,
+            success,
+            raw_stdout,
+        })
     }
 
     /// Get the repository path
@@ -192,9 +317,140 @@ impl GitExecutor {
     }
 }
 
+/// Make git's output predictable for parsing: untranslated messages and no
+/// colour or signatures, whatever the user's locale and config
+fn pin_output_format(cmd: &mut Command) {
+    if let Some(locale) = parse_locale() {
+        cmd.env("LC_ALL", locale).env_remove("LANGUAGE");
+    }
+    cmd.env("GIT_CONFIG_PARAMETERS", PARSE_CONFIG_PARAMETERS);
+}
+
+/// Split a command string into arguments using shell-words style rules
+///
+/// Supported syntax:
+/// - Whitespace separates arguments unless quoted or escaped
+/// - Single quotes preserve everything literally until the closing quote
+/// - Double quotes allow `\"` and `\\` escapes; other backslashes are literal
+/// - Outside quotes, a backslash escapes the following character
+/// - Adjacent quoted and unquoted segments join into one argument
+///   (`--pretty=format:"%h %s"` → `--pretty=format:%h %s`)
+/// - Quoted empty strings produce an empty argument (`commit -m ""`)
+///
+/// Shell expansion (`$VAR`, globbing, ANSI-C `$'...'`) is intentionally
+/// not supported; the executor rejects `$` and backticks before parsing.
+///
+/// # Examples
+///
+/// ```text
+///   commit -m "test message"      → ["commit", "-m", "test message"]
+///   commit -m 'can'\''t'         → ["commit", "-m", "can't"]
+///   commit -m "He said \"hi\""    → ["commit", "-m", "He said \"hi\""]
+///   log --grep=foo\ bar           → ["log", "--grep=foo bar"]
+/// ```
+pub fn split_command(command: &str) -> GitResult<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current_arg = String::new();
+    // Distinguishes an empty quoted argument from no argument at all
+    let mut in_arg = false;
+    let mut in_single_quote = false;
+    let mut in_double_quote = false;
+    let mut chars = command.chars();
+
+    while let Some(ch) = chars.next() {
+        if in_single_quote {
+            if ch == '\'' {
+                in_single_quote = false;
+            } else {
+                current_arg.push(ch);
+            }
+            continue;
+        }
+
+        if in_double_quote {
+            match ch {
+                '"' => in_double_quote = false,
+                '\\' => match chars.next() {
+                    Some(next @ ('"' | '\\')) => current_arg.push(next),
+                    Some(next) => {
+                        current_arg.push('\\');
+                        current_arg.push(next);
+                    }
+                    None => current_arg.push('\\'),
+                },
+                _ => current_arg.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '\'' => {
+                in_single_quote = true;
+                in_arg = true;
+            }
+            '"' => {
+                in_double_quote = true;
+                in_arg = true;
+            }
+            '\\' => match chars.next() {
+                Some(next) => {
+                    current_arg.push(next);
+                    in_arg = true;
+                }
+                None => {
+                    return Err(GitError::CommandFailed(
+                        "Trailing backslash in command".to_string(),
+                    ));
+                }
+            },
+            ' ' | '\t' | '\n' => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current_arg));
+                    in_arg = false;
+                }
+            }
+            _ => {
+                current_arg.push(ch);
+                in_arg = true;
+            }
+        }
+    }
+
+    // Check for unclosed quotes
+    if in_single_quote || in_double_quote {
+        return Err(GitError::CommandFailed(
+            "Unclosed quote in command".to_string(),
+        ));
+    }
+
+    // Push final argument if any
+    if in_arg {
+        args.push(current_arg);
+    }
+
+    Ok(args)
+}
+
+/// Quote a single argument so that [`split_command`] yields it back unchanged
+///
+/// Use this whenever gitalky builds a command string that embeds user or
+/// repository supplied text (branch names, paths, messages). Arguments made
+/// only of shell-safe characters are returned as-is for readability.
+pub fn quote_arg(arg: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=@%+,^~{}".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(is_safe) {
+        return arg.to_string();
+    }
+
+    // Close the quote, emit an escaped quote, and reopen: 'it'\''s'
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::git;
     use tempfile::TempDir;
 
     fn create_test_repo() -> (TempDir, PathBuf) {
@@ -202,20 +458,20 @@ mod tests {
         let repo_path = temp_dir.path().to_path_buf();
 
         // Initialize git repo
-        Command::new("git")
+        git_command()
             .args(["init"])
             .current_dir(&repo_path)
             .output()
             .unwrap();
 
         // Configure git
-        Command::new("git")
+        git_command()
             .args(["config", "user.name", "Test User"])
             .current_dir(&repo_path)
             .output()
             .unwrap();
 
-        Command::new("git")
+        git_command()
             .args(["config", "user.email", "test@example.com"])
             .current_dir(&repo_path)
             .output()
@@ -237,6 +493,41 @@ mod tests {
         assert_eq!(output.exit_code, 0);
     }
 
+    #[test]
+    fn test_binary_and_invalid_utf8_stdout() {
+        let (_temp, repo_path) = create_test_repo();
+        std::fs::write(repo_path.join("image.bin"), [0x89, b'P', b'N', b'G', 0, 0, 1, 2]).unwrap();
+        std::fs::write(repo_path.join("latin1.txt"), b"caf\xe9\n").unwrap();
+        std::fs::write(repo_path.join("plain.txt"), "café\n").unwrap();
+        git(&repo_path, &["add", "."]);
+        let executor = GitExecutor::new(&repo_path);
+
+        let binary = executor.execute("show :image.bin").unwrap();
+        assert!(binary.is_binary());
+        assert_eq!(binary.raw_stdout.as_deref().map(<[u8]>::len), Some(8));
+
+        let latin1 = executor.execute("show :latin1.txt").unwrap();
+        assert!(!latin1.is_binary());
+        assert_eq!(latin1.raw_stdout.as_deref(), Some(&b"caf\xe9\n"[..]));
+        assert_eq!(latin1.stdout, "caf\u{fffd}\n");
+
+        let plain = executor.execute("show :plain.txt").unwrap();
+        assert_eq!(plain.raw_stdout, None);
+        assert_eq!(plain.stdout, "café\n");
+    }
+
+    #[test]
+    fn test_parsed_commands_ignore_user_output_config() {
+        let (_temp, repo_path) = create_test_repo();
+        git(&repo_path, &["config", "color.ui", "always"]);
+        let executor = GitExecutor::new(&repo_path);
+
+        let parsed = executor.execute("config --get color.ui").unwrap();
+        assert_eq!(parsed.stdout.trim(), "never");
+        let user = executor.execute_for_user("config --get color.ui").unwrap();
+        assert_eq!(user.stdout.trim(), "always");
+    }
+
     #[test]
     fn test_execute_log_empty_repo() {
         let (_temp, repo_path) = create_test_repo();
@@ -247,6 +538,29 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_failed_command_captures_details() {
+        let (_temp, repo_path) = create_test_repo();
+        let executor = GitExecutor::new(&repo_path);
+
+        let err = executor.execute("log --oneline").unwrap_err();
+        match err {
+            GitError::CommandExited {
+                command,
+                args,
+                exit_code,
+                stderr,
+                ..
+            } => {
+                assert_eq!(command, "log --oneline");
+                assert_eq!(args, vec!["log", "--oneline"]);
+                assert_ne!(exit_code, 0);
+                assert!(!stderr.is_empty());
+            }
+            other => panic!("Expected CommandExited, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_sanitization_dollar_sign() {
         let (_temp, repo_path) = create_test_repo();
@@ -388,4 +702,70 @@ mod tests {
         let result = executor.execute("status && ls");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_command_escaped_double_quote() {
+        let args = split_command(r#"log --pretty=format:"a\"b""#).unwrap();
+        assert_eq!(args, vec!["log", "--pretty=format:a\"b"]);
+    }
+
+    #[test]
+    fn test_parse_command_backslash_outside_quotes() {
+        let args = split_command(r"log --grep=foo\ bar").unwrap();
+        assert_eq!(args, vec!["log", "--grep=foo bar"]);
+    }
+
+    #[test]
+    fn test_parse_command_backslash_literal_in_single_quotes() {
+        let args = split_command(r"log --grep='a\b'").unwrap();
+        assert_eq!(args, vec!["log", r"--grep=a\b"]);
+    }
+
+    #[test]
+    fn test_parse_command_empty_quoted_string() {
+        let args = split_command(r#"commit --allow-empty -m """#).unwrap();
+        assert_eq!(args, vec!["commit", "--allow-empty", "-m", ""]);
+
+        let args = split_command("commit -m ''").unwrap();
+        assert_eq!(args, vec!["commit", "-m", ""]);
+    }
+
+    #[test]
+    fn test_parse_command_adjacent_segments() {
+        let args = split_command(r#"commit -m 'it'"'"'s'"#).unwrap();
+        assert_eq!(args, vec!["commit", "-m", "it's"]);
+    }
+
+    #[test]
+    fn test_parse_command_trailing_backslash() {
+        assert!(split_command(r"status \").is_err());
+    }
+
+    #[test]
+    fn test_quote_arg_round_trip() {
+        let samples = [
+            "main",
+            "feature/login",
+            "",
+            "two words",
+            "it's",
+            r#"say "hi""#,
+            r"back\slash",
+            "tab\tseparated",
+            "'leading quote",
+        ];
+
+        for sample in samples {
+            let command = format!("commit -m {}", quote_arg(sample));
+            let args = split_command(&command).unwrap();
+            assert_eq!(args, vec!["commit", "-m", sample], "round trip failed for {:?}", sample);
+        }
+    }
+
+    #[test]
+    fn test_quote_arg_leaves_safe_args_bare() {
+        assert_eq!(quote_arg("origin/main"), "origin/main");
+        assert_eq!(quote_arg("HEAD~1"), "HEAD~1");
+        assert_eq!(quote_arg("two words"), "'two words'");
+    }
 }
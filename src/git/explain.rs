@@ -0,0 +1,328 @@
+use crate::git::executor::split_command;
+
+/// One-line descriptions of git subcommands, used for offline explanations
+const SUBCOMMANDS: &[(&str, &str)] = &[
+    ("status", "Show the working tree status"),
+    ("log", "Show commit history"),
+    ("show", "Show a commit (or other object) and its changes"),
+    ("verify-commit", "Check the GPG or SSH signature of commits"),
+    ("diff", "Show changes between commits, the index, and the working tree"),
+    ("branch", "List, create, or delete branches"),
+    ("tag", "List, create, or delete tags"),
+    ("remote", "Manage the set of tracked remote repositories"),
+    ("reflog", "Show where branch tips and HEAD have pointed recently"),
+    ("blame", "Show which commit last modified each line of a file"),
+    ("describe", "Name a commit using the nearest tag"),
+    ("ls-files", "List the files git tracks, optionally with their index flags"),
+    ("add", "Stage file contents for the next commit"),
+    ("commit", "Record staged changes as a new commit"),
+    ("checkout", "Switch branches or restore working tree files"),
+    ("switch", "Switch to another branch"),
+    ("restore", "Restore working tree or staged files"),
+    ("reset", "Move the current branch tip and optionally reset the index/working tree"),
+    ("revert", "Create a new commit that undoes an earlier commit"),
+    ("merge", "Join another branch's history into the current branch"),
+    ("rebase", "Replay commits on top of another base commit"),
+    ("cherry-pick", "Apply the changes from existing commits"),
+    ("stash", "Set aside uncommitted changes to restore later"),
+    ("clean", "Delete untracked files from the working tree"),
+    ("update-index", "Set or clear the bits that make git ignore changes to tracked files"),
+    ("push", "Upload local commits to a remote repository"),
+    ("pull", "Fetch from a remote and integrate into the current branch"),
+    ("fetch", "Download objects and refs from a remote without merging"),
+    ("clone", "Copy a repository into a new directory"),
+    ("init", "Create an empty repository or reinitialize an existing one"),
+    ("config", "Get or set repository configuration"),
+    ("filter-branch", "Rewrite branch history (deprecated; prefer git filter-repo)"),
+    ("gc", "Compress the object database and prune unreachable objects"),
+    ("maintenance", "Run or schedule background repository optimization tasks"),
+    ("notes", "Add, show or remove notes attached to commits without changing them"),
+    ("bundle", "Pack branches and their history into one file, or check and list one"),
+    ("difftool", "Show changes in the external diff tool set by diff.tool"),
+    ("mergetool", "Resolve conflicts in the external merge tool set by merge.tool"),
+];
+
+/// Flag descriptions keyed by subcommand; `*` applies to any subcommand
+const FLAGS: &[(&str, &str, &str)] = &[
+    // Subcommand-specific meanings first so they win over generic ones
+    ("add", "-A", "Stage all changes, including deletions and new files"),
+    ("add", "--all", "Stage all changes, including deletions and new files"),
+    ("add", "-p", "Interactively choose hunks to stage"),
+    ("add", "-u", "Stage changes to already-tracked files only"),
+    ("add", "-N", "Record that a new file will be added later (intent to add)"),
+    ("commit", "-m", "Use the following text as the commit message"),
+    ("commit", "-a", "Automatically stage modified and deleted tracked files"),
+    ("commit", "--amend", "Replace the last commit instead of creating a new one"),
+    ("commit", "--no-edit", "Keep the existing commit message"),
+    ("commit", "--allow-empty", "Allow a commit with no changes"),
+    ("checkout", "-b", "Create a new branch and switch to it"),
+    ("checkout", "-f", "Discard local changes when switching (destructive)"),
+    ("checkout", "--force", "Discard local changes when switching (destructive)"),
+    ("switch", "-c", "Create a new branch and switch to it"),
+    ("switch", "--create", "Create a new branch and switch to it"),
+    ("switch", "--discard-changes", "Throw away local changes when switching (destructive)"),
+    ("switch", "--detach", "Switch to a commit without a branch (detached HEAD)"),
+    ("restore", "--staged", "Unstage files (restore the index from HEAD)"),
+    ("restore", "-S", "Unstage files (restore the index from HEAD)"),
+    ("restore", "--worktree", "Restore working tree files (discards edits)"),
+    ("restore", "-W", "Restore working tree files (discards edits)"),
+    ("restore", "--source", "Restore content from the given commit"),
+    ("reset", "--hard", "Discard all staged and working tree changes (destructive)"),
+    ("reset", "--soft", "Move the branch tip but keep changes staged"),
+    ("reset", "--mixed", "Move the branch tip and unstage changes (default)"),
+    ("branch", "-d", "Delete a fully merged branch"),
+    ("branch", "-D", "Force-delete a branch even if unmerged (destructive)"),
+    ("branch", "-m", "Rename a branch"),
+    ("branch", "-a", "List both local and remote-tracking branches"),
+    ("branch", "-r", "List remote-tracking branches"),
+    ("branch", "-v", "Show the latest commit on each branch"),
+    ("branch", "-vv", "Show latest commit and upstream tracking info"),
+    ("branch", "-u", "Set the upstream for a branch"),
+    ("log", "--oneline", "Show each commit on a single line"),
+    ("log", "--graph", "Draw the branch structure as ASCII art"),
+    ("log", "--show-signature", "Check each commit's signature and print the result"),
+    ("show", "--show-signature", "Check the commit's signature and print the result"),
+    ("verify-commit", "-v", "Also print the commit's contents"),
+    ("log", "-n", "Limit the number of commits shown"),
+    ("log", "-p", "Show the patch (diff) of each commit"),
+    ("log", "--stat", "Show files changed per commit"),
+    ("log", "--author", "Only show commits by a matching author"),
+    ("log", "--since", "Only show commits newer than a date"),
+    ("log", "--grep", "Only show commits whose message matches"),
+    ("diff", "--staged", "Compare staged changes with the last commit"),
+    ("diff", "--cached", "Compare staged changes with the last commit"),
+    ("diff", "--stat", "Show a summary of changed files"),
+    ("diff", "--name-only", "Show only the names of changed files"),
+    ("push", "-u", "Set the pushed branch as the upstream for future pulls"),
+    ("push", "--set-upstream", "Set the pushed branch as the upstream for future pulls"),
+    ("push", "-f", "Overwrite the remote branch (destructive)"),
+    ("push", "--force", "Overwrite the remote branch (destructive)"),
+    ("push", "--force-with-lease", "Overwrite the remote only if it hasn't moved since your last fetch"),
+    ("push", "--tags", "Push all tags"),
+    ("push", "--delete", "Delete the named remote branch or tag"),
+    ("pull", "--rebase", "Rebase local commits on top of the fetched ones instead of merging"),
+    ("pull", "--ff-only", "Only update if no merge commit is needed"),
+    ("fetch", "--all", "Fetch from every configured remote"),
+    ("fetch", "--prune", "Remove remote-tracking branches that no longer exist"),
+    ("fetch", "-p", "Remove remote-tracking branches that no longer exist"),
+    ("merge", "--no-ff", "Always create a merge commit"),
+    ("merge", "--ff-only", "Refuse to merge unless it is a fast-forward"),
+    ("merge", "--squash", "Combine the changes into the index without committing"),
+    ("rebase", "-i", "Interactively edit, reorder, or squash commits"),
+    ("rebase", "--interactive", "Interactively edit, reorder, or squash commits"),
+    ("rebase", "--onto", "Rebase onto a different base than the upstream"),
+    ("stash", "-u", "Also stash untracked files"),
+    ("stash", "--include-untracked", "Also stash untracked files"),
+    ("stash", "-m", "Use the following text as the stash message"),
+    ("reflog", "--expire", "Drop reflog entries older than this time (\"now\" drops all)"),
+    ("reflog", "--expire-unreachable", "Drop entries for unreachable commits older than this time"),
+    ("reflog", "--all", "Process the reflogs of all references"),
+    ("gc", "--prune", "Delete unreachable objects older than this time (\"now\" deletes all)"),
+    ("gc", "--aggressive", "Optimize the repository more thoroughly, at the cost of time"),
+    ("maintenance", "--schedule", "Run the tasks due on this schedule (hourly, daily or weekly)"),
+    ("maintenance", "--scheduler", "Which system scheduler runs the background tasks"),
+    ("maintenance", "--task", "Run only this maintenance task"),
+    ("notes", "-m", "Use the following text as the note (each -m adds a paragraph)"),
+    ("notes", "-f", "Replace the commit's existing note"),
+    ("notes", "--ref", "Use this notes ref instead of refs/notes/commits"),
+    ("bundle", "--all", "Include every branch and tag"),
+    ("difftool", "--no-prompt", "Open each file without asking first"),
+    ("mergetool", "--no-prompt", "Open each file without asking first"),
+    ("ls-files", "-v", "Tag files: lowercase letters are assume-unchanged, S is skip-worktree"),
+    ("update-index", "--assume-unchanged", "Promise the file won't change, so git stops checking it"),
+    ("update-index", "--no-assume-unchanged", "Check the file for changes again"),
+    ("update-index", "--skip-worktree", "Keep local edits to the file out of status, diff and checkouts"),
+    ("update-index", "--no-skip-worktree", "Treat the file's working copy normally again"),
+    ("clean", "-f", "Actually delete files (required by default)"),
+    ("clean", "-d", "Also remove untracked directories"),
+    ("clean", "-n", "Dry run: only show what would be deleted"),
+    ("clean", "-x", "Also remove ignored files"),
+    ("cherry-pick", "-x", "Record the original commit id in the message"),
+    ("cherry-pick", "-n", "Apply changes without committing"),
+    ("revert", "--no-commit", "Apply the inverse changes without committing"),
+    ("tag", "-a", "Create an annotated tag"),
+    ("tag", "-d", "Delete a tag"),
+    ("remote", "-v", "Show remote URLs"),
+    ("config", "--global", "Use the user-wide config file"),
+    ("config", "--local", "Use the repository config file"),
+    ("config", "--list", "List all configuration values"),
+    // Generic meanings
+    ("*", "--continue", "Resume the operation after resolving conflicts"),
+    ("*", "--abort", "Cancel the operation and restore the previous state"),
+    ("*", "--skip", "Skip the current commit and continue"),
+    ("*", "-q", "Suppress informational output"),
+    ("*", "--quiet", "Suppress informational output"),
+    ("*", "-v", "Show more detailed output"),
+    ("*", "--verbose", "Show more detailed output"),
+    ("*", "--dry-run", "Show what would happen without doing it"),
+    ("*", "--", "Everything after this is a file path, not an option"),
+];
+
+/// One-line description of a subcommand
+pub fn subcommand_summary(subcommand: &str) -> Option<&'static str> {
+    SUBCOMMANDS.iter().find(|(name, _)| *name == subcommand).map(|(_, desc)| *desc)
+}
+
+/// Flags described for `subcommand`, in table order, without the generic ones
+pub fn subcommand_flags(subcommand: &str) -> Vec<(&'static str, &'static str)> {
+    FLAGS
+        .iter()
+        .filter(|(sub, _, _)| *sub == subcommand)
+        .map(|(_, flag, desc)| (*flag, *desc))
+        .collect()
+}
+
+/// Explanation of a single flag in a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlagExplanation {
+    pub flag: String,
+    pub description: Option<String>,
+}
+
+/// Offline explanation of a git command built from the lookup tables
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandExplanation {
+    pub subcommand: String,
+    pub summary: Option<String>,
+    pub flags: Vec<FlagExplanation>,
+}
+
+impl CommandExplanation {
+    /// Explain a git command using the built-in tables
+    ///
+    /// Returns `None` if the command can't be parsed or has no subcommand.
+    pub fn offline(command: &str) -> Option<Self> {
+        let args = split_command(command).ok()?;
+        let mut args = args.iter().map(String::as_str).peekable();
+        if args.peek() == Some(&"git") {
+            args.next();
+        }
+
+        let subcommand = args.find(|arg| !arg.starts_with('-'))?.to_string();
+        let summary = subcommand_summary(&subcommand).map(str::to_string);
+
+        let mut flags = Vec::new();
+        for arg in args.filter(|arg| arg.starts_with('-')) {
+            // --flag=value is described by its flag part
+            let flag = arg.split_once('=').map_or(arg, |(flag, _)| flag);
+
+            match Self::describe_flag(&subcommand, flag) {
+                Some(description) => flags.push(FlagExplanation {
+                    flag: flag.to_string(),
+                    description: Some(description.to_string()),
+                }),
+                // Combined short flags like -fd: explain each letter
+                None if Self::is_combined_short(flag) => {
+                    for letter in flag.chars().skip(1) {
+                        let single = format!("-{}", letter);
+                        let description = Self::describe_flag(&subcommand, &single);
+                        flags.push(FlagExplanation {
+                            flag: single,
+                            description: description.map(str::to_string),
+                        });
+                    }
+                }
+                None => flags.push(FlagExplanation {
+                    flag: flag.to_string(),
+                    description: None,
+                }),
+            }
+        }
+
+        Some(Self {
+            subcommand,
+            summary,
+            flags,
+        })
+    }
+
+    fn describe_flag(subcommand: &str, flag: &str) -> Option<&'static str> {
+        FLAGS
+            .iter()
+            .find(|(sub, name, _)| *sub == subcommand && *name == flag)
+            .or_else(|| FLAGS.iter().find(|(sub, name, _)| *sub == "*" && *name == flag))
+            .map(|(_, _, desc)| *desc)
+    }
+
+    fn is_combined_short(flag: &str) -> bool {
+        flag.len() > 2
+            && flag.starts_with('-')
+            && !flag.starts_with("--")
+            && flag.chars().skip(1).all(|c| c.is_ascii_alphabetic())
+    }
+
+    /// Render as multi-line text for the command preview
+    pub fn to_text(&self) -> String {
+        let mut text = match self.summary {
+            Some(ref summary) => format!("git {}: {}", self.subcommand, summary),
+            None => format!("git {}: (no offline description)", self.subcommand),
+        };
+
+        for flag in &self.flags {
+            let description = flag
+                .description
+                .as_deref()
+                .unwrap_or("(unknown flag - check `git help` before running)");
+            text.push_str(&format!("\n  {}  {}", flag.flag, description));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_subcommand() {
+        let explanation = CommandExplanation::offline("git status").unwrap();
+        assert_eq!(explanation.subcommand, "status");
+        assert!(explanation.summary.unwrap().contains("working tree status"));
+        assert!(explanation.flags.is_empty());
+    }
+
+    #[test]
+    fn test_subcommand_specific_flag_meaning() {
+        let branch = CommandExplanation::offline("git branch -d old").unwrap();
+        assert!(branch.flags[0].description.as_ref().unwrap().contains("Delete"));
+
+        let clean = CommandExplanation::offline("git clean -d -n").unwrap();
+        assert!(clean.flags[0].description.as_ref().unwrap().contains("directories"));
+    }
+
+    #[test]
+    fn test_flag_with_value() {
+        let explanation = CommandExplanation::offline("git log --author=alice").unwrap();
+        assert_eq!(explanation.flags[0].flag, "--author");
+        assert!(explanation.flags[0].description.is_some());
+    }
+
+    #[test]
+    fn test_combined_short_flags() {
+        let explanation = CommandExplanation::offline("git clean -fd").unwrap();
+        let flags: Vec<_> = explanation.flags.iter().map(|f| f.flag.as_str()).collect();
+        assert_eq!(flags, vec!["-f", "-d"]);
+        assert!(explanation.flags.iter().all(|f| f.description.is_some()));
+    }
+
+    #[test]
+    fn test_unknown_flag_is_reported() {
+        let explanation = CommandExplanation::offline("git status --frobnicate").unwrap();
+        assert_eq!(explanation.flags[0].description, None);
+        assert!(explanation.to_text().contains("unknown flag"));
+    }
+
+    #[test]
+    fn test_without_git_prefix() {
+        let explanation = CommandExplanation::offline("commit -m 'msg'").unwrap();
+        assert_eq!(explanation.subcommand, "commit");
+        assert_eq!(explanation.flags[0].flag, "-m");
+    }
+
+    #[test]
+    fn test_unparseable_command() {
+        assert!(CommandExplanation::offline("git commit -m 'unclosed").is_none());
+        assert!(CommandExplanation::offline("").is_none());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::repository::Repository;
+use crate::security::validator::abbreviated_option;
+
+/// Option that makes git run a command of the caller's choosing instead of a tool
+const EXTCMD_OPTION: &str = "--extcmd";
+
+/// Short spelling of `--extcmd`
+const EXTCMD_SHORT_OPTION: char = 'x';
+
+/// Short difftool and mergetool options that take the rest of a flag group as their value
+const SHORT_OPTIONS_WITH_VALUE: &[char] = &['t', 'O'];
+
+/// Options that stop git asking before it opens each file
+const NO_PROMPT_OPTIONS: &[&str] = &["--no-prompt", "-y"];
+
+/// Options that make git ask before each file, from inside the suspended TUI
+const PROMPT_OPTION: &str = "--prompt";
+
+/// A kind of external tool git opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    /// `git difftool`, set by `diff.tool`
+    Diff,
+    /// `git mergetool`, set by `merge.tool`
+    Merge,
+}
+
+impl ToolKind {
+    pub fn subcommand(self) -> &'static str {
+        match self {
+            ToolKind::Diff => "difftool",
+            ToolKind::Merge => "mergetool",
+        }
+    }
+
+    /// Which tool `command` opens, if it runs difftool or mergetool
+    pub fn of_command(command: &str) -> Option<Self> {
+        let words = split_command(command).ok()?;
+        match words.iter().find(|word| *word != "git" && !word.starts_with('-'))?.as_str() {
+            "difftool" => Some(ToolKind::Diff),
+            "mergetool" => Some(ToolKind::Merge),
+            _ => None,
+        }
+    }
+}
+
+/// The tool git would open: `diff.tool`, which falls back to `merge.tool` as
+/// difftool does, or `merge.tool`
+pub fn configured_tool(repo: &Repository, kind: ToolKind) -> Option<String> {
+    let get = |key: &str| {
+        repo.executor()
+            .execute(&format!("config --get {}", key))
+            .ok()
+            .map(|output| output.stdout.trim().to_string())
+            .filter(|tool| !tool.is_empty())
+    };
+    match kind {
+        ToolKind::Diff => get("diff.tool").or_else(|| get("merge.tool")),
+        ToolKind::Merge => get("merge.tool"),
+    }
+}
+
+/// Open `paths` in the difftool, their staged changes when `staged`
+pub fn difftool_command(paths: &[&str], staged: bool) -> String {
+    let quoted: Vec<String> = paths.iter().map(|path| quote_arg(path)).collect();
+    format!(
+        "git difftool --no-prompt{} -- {}",
+        if staged { " --cached" } else { "" },
+        quoted.join(" ")
+    )
+}
+
+/// Resolve the conflicts in `path` with the mergetool
+pub fn mergetool_command(path: &str) -> String {
+    format!("git mergetool --no-prompt -- {}", quote_arg(path))
+}
+
+/// Why a difftool or mergetool in `args` (the words after the subcommand)
+/// can't run: an `--extcmd` runs any program, and `--prompt` stops to ask
+/// before every file
+pub fn tool_args_problem(subcommand: &str, args: &[String]) -> Option<String> {
+    let options = || args.iter().take_while(|arg| *arg != "--");
+    if let Some(arg) = options().find(|arg| is_extcmd(arg)) {
+        return Some(format!("{} runs an arbitrary command", arg));
+    }
+    if options().any(|arg| abbreviated_option(arg, &[PROMPT_OPTION]).is_some()) {
+        return Some(format!("{} --prompt; it runs with --no-prompt", subcommand));
+    }
+    None
+}
+
+/// Whether `arg` is `--extcmd` however git accepts it spelled: abbreviated
+/// (`--extc=`), with the command attached (`-xcmd`) or grouped (`-yx cmd`)
+fn is_extcmd(arg: &str) -> bool {
+    if let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.starts_with('-')) {
+        return flags
+            .chars()
+            .take_while(|flag| !SHORT_OPTIONS_WITH_VALUE.contains(flag))
+            .any(|flag| flag == EXTCMD_SHORT_OPTION);
+    }
+    abbreviated_option(arg, &[EXTCMD_OPTION]).is_some()
+}
+
+/// `command` with `--no-prompt` after its difftool/mergetool unless it has it
+pub fn with_no_prompt(command: &str) -> String {
+    let words = split_command(command).unwrap_or_default();
+    if words.iter().any(|word| NO_PROMPT_OPTIONS.contains(&word.as_str())) {
+        return command.to_string();
+    }
+    let mut end = 0;
+    for word in command.split_whitespace() {
+        end += command[end..].find(word).unwrap_or(0) + word.len();
+        if matches!(word, "difftool" | "mergetool") {
+            return format!("{} --no-prompt{}", &command[..end], &command[end..]);
+        }
+    }
+    command.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(difftool_command(&["src/a.rs", "my file"], false), "git difftool --no-prompt -- src/a.rs 'my file'");
+        assert_eq!(difftool_command(&["a"], true), "git difftool --no-prompt --cached -- a");
+        assert_eq!(mergetool_command("a.txt"), "git mergetool --no-prompt -- a.txt");
+        assert_eq!(ToolKind::of_command("git difftool -y"), Some(ToolKind::Diff));
+        assert_eq!(ToolKind::of_command("mergetool"), Some(ToolKind::Merge));
+        assert_eq!(ToolKind::of_command("git log --grep difftool"), None);
+        assert_eq!(with_no_prompt("difftool HEAD~1 -- a"), "difftool --no-prompt HEAD~1 -- a");
+        assert_eq!(with_no_prompt("git mergetool"), "git mergetool --no-prompt");
+        assert_eq!(with_no_prompt("difftool -y"), "difftool -y");
+    }
+
+    #[test]
+    fn test_tool_args_problem() {
+        assert_eq!(tool_args_problem("difftool", &args(&["--no-prompt", "--cached"])), None);
+        assert_eq!(tool_args_problem("mergetool", &args(&["-y", "--tool=meld", "a"])), None);
+        assert_eq!(tool_args_problem("difftool", &args(&["HEAD~1"])), None);
+        assert_eq!(
+            tool_args_problem("mergetool", &args(&["--prompt"])).as_deref(),
+            Some("mergetool --prompt; it runs with --no-prompt")
+        );
+        for extcmd in [
+            &["-y", "-x", "sh"][..],
+            &["-y", "--extcmd=sh -c id"],
+            &["-xtouch", "--no-prompt"],
+            &["--extc=touch"],
+            &["--ext", "touch"],
+            &["-yxtouch"],
+        ] {
+            assert!(tool_args_problem("difftool", &args(extcmd)).unwrap().contains("arbitrary command"), "{:?}", extcmd);
+        }
+        assert!(tool_args_problem("mergetool", &args(&["--prom"])).unwrap().contains("--no-prompt"));
+        // A tool named with x in it, and paths after `--`, aren't options
+        assert_eq!(tool_args_problem("difftool", &args(&["-ytxxdiff", "--", "-xfile"])), None);
+    }
+
+    #[test]
+    fn test_configured_tool() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        // Keep the user's global settings out of it
+        git(temp.path(), &["config", "diff.tool", ""]);
+        git(temp.path(), &["config", "merge.tool", ""]);
+        let repo = Repository::new(temp.path());
+        assert_eq!(configured_tool(&repo, ToolKind::Diff), None);
+
+        git(temp.path(), &["config", "merge.tool", "meld"]);
+        assert_eq!(configured_tool(&repo, ToolKind::Diff).as_deref(), Some("meld"));
+        git(temp.path(), &["config", "diff.tool", "vimdiff"]);
+        assert_eq!(configured_tool(&repo, ToolKind::Diff).as_deref(), Some("vimdiff"));
+        assert_eq!(configured_tool(&repo, ToolKind::Merge).as_deref(), Some("meld"));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::error::GitResult;
+use crate::git::executor::{git_command, quote_arg, split_command};
+use crate::git::repository::Repository;
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
+
+/// Directory under the git dir holding the bundles written before a filter-branch
+pub const BACKUP_DIR: &str = "gitalky-backups";
+
+/// What git prints before running filter-branch, minus the pause
+pub const FILTER_BRANCH_ADVICE: &str = "git-filter-branch has a glut of gotchas generating mangled history \
+     rewrites; git recommends an alternative filtering tool such as git filter-repo instead.";
+
+/// Whether `command` runs `git filter-branch`
+pub fn is_filter_branch(command: &str) -> bool {
+    split_command(command)
+        .ok()
+        .and_then(|words| words.into_iter().find(|word| word != "git" && !word.starts_with('-')))
+        .is_some_and(|subcommand| subcommand == "filter-branch")
+}
+
+/// Whether `git filter-repo` is on the PATH
+pub fn filter_repo_installed() -> bool {
+    git_command()
+        .args(["filter-repo", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// The `git filter-repo` command doing the same as a common filter-branch:
+/// removing paths from history, or keeping one subdirectory
+pub fn filter_repo_equivalent(command: &str) -> Option<String> {
+    let words = split_command(command).ok()?;
+    let value_of = |flag: &str| {
+        words
+            .iter()
+            .position(|word| word == flag)
+            .and_then(|index| words.get(index + 1))
+    };
+
+    if let Some(dir) = value_of("--subdirectory-filter") {
+        return Some(format!("git filter-repo --subdirectory-filter {}", quote_arg(dir)));
+    }
+    let script = value_of("--index-filter").or_else(|| value_of("--tree-filter"))?;
+    let script = split_command(script).ok()?;
+    let rest = match script.as_slice() {
+        [git, rm, rest @ ..] if git == "git" && rm == "rm" => rest,
+        [rm, rest @ ..] if rm == "rm" => rest,
+        _ => return None,
+    };
+    let paths: Vec<String> = rest
+        .iter()
+        .filter(|word| !word.starts_with('-'))
+        .map(|path| format!("--path {}", quote_arg(path)))
+        .collect();
+    (!paths.is_empty()).then(|| format!("git filter-repo --invert-paths {}", paths.join(" ")))
+}
+
+/// Where the backup for a filter-branch started at `now` goes
+pub fn backup_path(repo: &Repository, now: DateTime<Local>) -> PathBuf {
+    repo.git_dir()
+        .join(BACKUP_DIR)
+        .join(format!("filter-branch-{}.bundle", now.format("%Y%m%d-%H%M%S")))
+}
+
+/// Write every ref and the commits they reach to a bundle at `path`
+pub fn create_backup(repo: &Repository, path: &Path) -> GitResult<()> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    repo.executor()
+        .execute(&format!("bundle create {} --all", quote_arg(&path.display().to_string())))?;
+    Ok(())
+}
+
+/// Explanation shown before a filter-branch may run
+pub fn filter_branch_warning(command: &str, filter_repo: bool, backup: &str) -> String {
+    let alternative = match (filter_repo, filter_repo_equivalent(command)) {
+        (true, Some(equivalent)) => format!(
+            "git filter-repo is installed; this does the same: {}\n(run it from a shell: ! on an empty prompt)",
+            equivalent
+        ),
+        (true, None) => "git filter-repo is installed; see git filter-repo --help for the equivalent.".to_string(),
+        (false, _) => "git filter-repo isn't installed (pip install git-filter-repo).".to_string(),
+    };
+    format!(
+        "⚠ {}\n{}\nEnter first writes a backup of every ref to {}, then runs this.",
+        FILTER_BRANCH_ADVICE, alternative, backup
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_filter_branch() {
+        assert!(is_filter_branch("git filter-branch --tree-filter 'rm -f a' HEAD"));
+        assert!(is_filter_branch("filter-branch HEAD"));
+        assert!(!is_filter_branch("git log --grep filter-branch"));
+    }
+
+    #[test]
+    fn test_filter_repo_equivalent() {
+        assert_eq!(
+            filter_repo_equivalent("git filter-branch --index-filter 'git rm --cached --ignore-unmatch secrets.txt' HEAD")
+                .as_deref(),
+            Some("git filter-repo --invert-paths --path secrets.txt")
+        );
+        assert_eq!(
+            filter_repo_equivalent("git filter-branch --tree-filter 'rm -rf build dist' -- --all").as_deref(),
+            Some("git filter-repo --invert-paths --path build --path dist")
+        );
+        assert_eq!(
+            filter_repo_equivalent("git filter-branch --subdirectory-filter lib HEAD").as_deref(),
+            Some("git filter-repo --subdirectory-filter lib")
+        );
+        assert_eq!(filter_repo_equivalent("git filter-branch --msg-filter 'sed s/a/b/' HEAD"), None);
+    }
+
+    #[test]
+    fn test_warning_mentions_alternative_and_backup() {
+        let warning = filter_branch_warning(
+            "git filter-branch --subdirectory-filter lib HEAD",
+            true,
+            ".git/gitalky-backups/x.bundle",
+        );
+        assert!(warning.contains("git filter-repo --subdirectory-filter lib"));
+        assert!(warning.ends_with("backup of every ref to .git/gitalky-backups/x.bundle, then runs this."));
+        assert!(filter_branch_warning("git filter-branch HEAD", false, "b").contains("isn't installed"));
+    }
+
+    #[test]
+    fn test_create_backup_bundles_all_refs() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        git(temp.path(), &["commit", "-q", "--allow-empty", "-m", "One"]);
+        let repo = Repository::new(temp.path());
+        let path = backup_path(&repo, Local::now());
+        create_backup(&repo, &path).unwrap();
+        let verify = repo
+            .executor()
+            .execute(&format!("bundle verify {}", quote_arg(&path.display().to_string())));
+        assert!(verify.is_ok());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::error::GitResult;
+use crate::git::repository::Repository;
+use std::collections::BTreeSet;
+
+/// Paths named in a change description before the rest are counted
+const MAX_NAMED_PATHS: usize = 3;
+
+/// The parts of the repository state a translated command is most likely to
+/// depend on, captured when it was translated
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateFingerprint {
+    pub branch: Option<String>,
+    /// Commit HEAD points at; `None` before the first commit
+    pub head: Option<String>,
+    pub staged: BTreeSet<String>,
+}
+
+impl StateFingerprint {
+    /// Ask git directly; the panel's state may be a refresh behind
+    pub fn capture(repo: &Repository) -> GitResult<Self> {
+        let executor = repo.executor();
+        let branch = executor
+            .execute("branch --show-current")
+            .ok()
+            .map(|output| output.stdout.trim().to_string())
+            .filter(|branch| !branch.is_empty());
+        let head = executor.execute("rev-parse --verify -q HEAD").ok().map(|output| output.stdout.trim().to_string());
+        let staged = executor
+            .execute("diff --cached --name-only -z")?
+            .stdout
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .collect();
+        Ok(Self { branch, head, staged })
+    }
+
+    /// What changed between this fingerprint and `now`, one line per change;
+    /// empty when nothing did
+    pub fn changes(&self, now: &StateFingerprint) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.branch != now.branch {
+            let name = |branch: &Option<String>| branch.clone().unwrap_or_else(|| "detached HEAD".to_string());
+            changes.push(format!("Switched from {} to {}", name(&self.branch), name(&now.branch)));
+        } else if self.head != now.head {
+            let short = |head: &Option<String>| {
+                head.as_deref().map_or("no commits".to_string(), |hash| hash.chars().take(7).collect())
+            };
+            changes.push(format!("HEAD moved from {} to {}", short(&self.head), short(&now.head)));
+        }
+
+        let added: Vec<&String> = now.staged.difference(&self.staged).collect();
+        let removed: Vec<&String> = self.staged.difference(&now.staged).collect();
+        if !added.is_empty() {
+            changes.push(format!("Newly staged: {}", name_paths(&added)));
+        }
+        if !removed.is_empty() {
+            changes.push(format!("No longer staged: {}", name_paths(&removed)));
+        }
+        changes
+    }
+}
+
+fn name_paths(paths: &[&String]) -> String {
+    let mut named = paths.iter().take(MAX_NAMED_PATHS).map(|path| path.as_str()).collect::<Vec<_>>().join(", ");
+    if paths.len() > MAX_NAMED_PATHS {
+        named.push_str(&format!(" and {} more", paths.len() - MAX_NAMED_PATHS));
+    }
+    named
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_capture_and_changes() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let repo = Repository::new(temp.path());
+
+        let empty = StateFingerprint::capture(&repo).unwrap();
+        assert_eq!(empty.branch.as_deref(), Some("main"));
+        assert_eq!(empty.head, None);
+        assert!(empty.changes(&StateFingerprint::capture(&repo).unwrap()).is_empty());
+
+        fs::write(temp.path().join("a.txt"), "a").unwrap();
+        git(temp.path(), &["add", "a.txt"]);
+        let staged = StateFingerprint::capture(&repo).unwrap();
+        assert_eq!(empty.changes(&staged), ["Newly staged: a.txt"]);
+
+        git(temp.path(), &["commit", "-q", "-m", "a"]);
+        let committed = StateFingerprint::capture(&repo).unwrap();
+        let changes = staged.changes(&committed);
+        assert!(changes[0].starts_with("HEAD moved from no commits to "));
+        assert_eq!(changes[1], "No longer staged: a.txt");
+    }
+
+    #[test]
+    fn test_branch_switch_and_long_path_lists() {
+        let before = StateFingerprint {
+            branch: Some("main".to_string()),
+            head: Some("1111111111".to_string()),
+            staged: BTreeSet::new(),
+        };
+        let after = StateFingerprint {
+            branch: Some("feature".to_string()),
+            head: Some("2222222222".to_string()),
+            staged: ["a", "b", "c", "d", "e"].into_iter().map(String::from).collect(),
+        };
+        assert_eq!(
+            before.changes(&after),
+            ["Switched from main to feature", "Newly staged: a, b, c and 2 more"]
+        );
+    }
+}
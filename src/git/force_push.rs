@@ -0,0 +1,357 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::repository::Repository;
+
+/// Phrases that ask to push a rewritten branch
+const FORCE_PUSH_PHRASES: &[&str] = &[
+    "force push",
+    "force-push",
+    "push --force",
+    "push -f",
+    "push after rebase",
+    "push after the rebase",
+    "push the rebased",
+    "push my rebased",
+    "push rebased",
+];
+
+/// Rebase options that only continue or end a rebase already in progress
+const REBASE_IN_PROGRESS_FLAGS: &[&str] =
+    &["--continue", "--abort", "--skip", "--quit", "--edit-todo", "--show-current-patch"];
+
+/// Rebase options whose value is the next word
+const REBASE_VALUE_FLAGS: &[&str] = &["--onto", "-s", "--strategy", "-X", "--strategy-option", "-x", "--exec"];
+
+/// Check whether a natural language query asks to force push
+///
+/// Matched queries are answered locally by [`ForcePushCheck`], which knows
+/// what the remote held when the rebase started.
+pub fn is_force_push_query(query: &str) -> bool {
+    let normalized = query
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    FORCE_PUSH_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// The branch a `git rebase` command starts rewriting; `None` for other
+/// commands and for `--continue`, `--abort` and friends
+pub fn rebased_branch(command: &str, current_branch: Option<&str>) -> Option<String> {
+    let words = split_command(command).ok()?;
+    let mut words = words.iter().map(String::as_str).skip_while(|w| *w == "git");
+    if words.next()? != "rebase" {
+        return None;
+    }
+
+    let mut positional = Vec::new();
+    let mut skip_value = false;
+    for word in words {
+        if skip_value {
+            skip_value = false;
+        } else if REBASE_IN_PROGRESS_FLAGS.contains(&word) {
+            return None;
+        } else if REBASE_VALUE_FLAGS.contains(&word) {
+            skip_value = true;
+        } else if !word.starts_with('-') {
+            positional.push(word);
+        }
+    }
+    // git rebase [<upstream> [<branch>]]
+    match positional.get(1) {
+        Some(branch) => Some(branch.to_string()),
+        None => current_branch.map(str::to_string),
+    }
+}
+
+/// Commit the upstream of `branch` points at locally, e.g. `origin/feature`
+pub fn upstream_commit(repo: &Repository, branch: &str) -> Option<String> {
+    let output = repo
+        .executor()
+        .execute(&format!("rev-parse --verify -q {}", quote_arg(&format!("{}@{{upstream}}", branch))))
+        .ok()?;
+    let commit = output.stdout.trim();
+    (!commit.is_empty()).then(|| commit.to_string())
+}
+
+/// Commits only on `branch` and only on its upstream, when both exist
+pub fn upstream_divergence(repo: &Repository, branch: &str) -> Option<(usize, usize)> {
+    let output = repo
+        .executor()
+        .execute(&format!(
+            "rev-list --left-right --count {}",
+            quote_arg(&format!("{}...{}@{{upstream}}", branch, branch))
+        ))
+        .ok()?;
+    let mut counts = output.stdout.split_whitespace().map(|n| n.parse().ok());
+    Some((counts.next()??, counts.next()??))
+}
+
+/// What pushing a branch would replace on its remote, checked against the
+/// remote itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForcePushCheck {
+    pub branch: String,
+    pub remote: String,
+    /// Branch name on the remote
+    pub remote_branch: String,
+    /// Remote tip the rebase started from: the lease value
+    pub expected: String,
+    /// Remote tip right now, from `git ls-remote`; `None` once it's deleted
+    pub actual: Option<String>,
+    /// Whether the branch ever contained `expected` (per its reflog), i.e.
+    /// the rebase really started from it
+    pub integrated: bool,
+    /// Local commits not in `expected`
+    pub ahead: usize,
+    /// Commits in `expected` the push would drop
+    pub behind: usize,
+}
+
+impl ForcePushCheck {
+    /// Compare `branch` with its upstream on the remote
+    ///
+    /// `rebase_start` is the upstream commit recorded when the rebase began;
+    /// without it the last fetched position stands in, checked against the
+    /// branch's reflog like `--force-if-includes`. Asks the remote with
+    /// `git ls-remote` so a stale remote-tracking branch can't hide commits
+    /// pushed in the meantime. `None` when the branch doesn't track a remote
+    /// branch.
+    pub fn run(repo: &Repository, branch: &str, rebase_start: Option<&str>) -> GitResult<Option<Self>> {
+        let executor = repo.executor();
+        let config = |key: &str| {
+            executor
+                .execute(&format!("config --get {}", quote_arg(&format!("branch.{}.{}", branch, key))))
+                .ok()
+                .map(|output| output.stdout.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let (Some(remote), Some(merge)) = (config("remote"), config("merge")) else {
+            return Ok(None);
+        };
+        // A local branch as upstream (`.`) has nothing to push to
+        if remote == "." {
+            return Ok(None);
+        }
+        let Some(expected) = rebase_start.map(str::to_string).or_else(|| upstream_commit(repo, branch)) else {
+            return Ok(None);
+        };
+
+        let actual = match executor.execute(&format!("ls-remote --exit-code {} {}", quote_arg(&remote), quote_arg(&merge))) {
+            Ok(output) => output.stdout.split_whitespace().next().map(str::to_string),
+            // --exit-code: the remote answered but has no such branch
+            Err(GitError::CommandExited { exit_code: 2, .. }) => None,
+            Err(e) => return Err(e),
+        };
+
+        let integrated = rebase_start.is_some() || reflog_includes(repo, branch, &expected);
+        let counts = executor.execute(&format!("rev-list --left-right --count {}...{}", quote_arg(branch), expected))?;
+        let mut counts = counts.stdout.split_whitespace().map(|n| n.parse().unwrap_or(0));
+        let (ahead, behind) = (counts.next().unwrap_or(0), counts.next().unwrap_or(0));
+
+        Ok(Some(Self {
+            branch: branch.to_string(),
+            remote,
+            remote_branch: merge.strip_prefix("refs/heads/").unwrap_or(&merge).to_string(),
+            expected,
+            actual,
+            integrated,
+            ahead,
+            behind,
+        }))
+    }
+
+    /// Whether the remote branch changed since the rebase started
+    pub fn remote_moved(&self) -> bool {
+        self.actual.as_deref() != Some(self.expected.as_str())
+    }
+
+    /// The safest next command, with what it does and why
+    pub fn proposal(&self) -> (String, String) {
+        let tracking = format!("{}/{}", self.remote, self.remote_branch);
+        let destination = if self.remote_branch == self.branch {
+            quote_arg(&self.branch)
+        } else {
+            quote_arg(&format!("{}:{}", self.branch, self.remote_branch))
+        };
+
+        let Some(ref actual) = self.actual else {
+            return (
+                format!("git push {} {}", quote_arg(&self.remote), destination),
+                format!("{} no longer exists on the remote, so a normal push recreates it; no force needed", tracking),
+            );
+        };
+        if self.remote_moved() {
+            return (
+                format!("git fetch {}", quote_arg(&self.remote)),
+                format!(
+                    "{} moved from {} to {} since your rebase started, so a force push would delete \
+                     commits someone else pushed. Fetch them, bring them into {} (e.g. git cherry-pick), \
+                     then push again.",
+                    tracking,
+                    short(&self.expected),
+                    short(actual),
+                    self.branch
+                ),
+            );
+        }
+        if !self.integrated {
+            return (
+                format!(
+                    "git log --oneline --cherry-pick --right-only {}",
+                    quote_arg(&format!("{}...{}", self.branch, tracking))
+                ),
+                format!(
+                    "{} has commits {} never contained; they were probably fetched after your \
+                     rebase and a force push would delete them. Review them and bring them into \
+                     {} before pushing.",
+                    tracking, self.branch, self.branch
+                ),
+            );
+        }
+        if self.behind == 0 {
+            return (
+                format!("git push {} {}", quote_arg(&self.remote), destination),
+                format!("{} only adds commits on top of {}; a normal push is enough", self.branch, tracking),
+            );
+        }
+
+        (
+            format!(
+                "git push {} {} {}",
+                quote_arg(&format!("--force-with-lease=refs/heads/{}:{}", self.remote_branch, self.expected)),
+                quote_arg(&self.remote),
+                destination
+            ),
+            format!(
+                "Replace {} ({} commit(s) only there) with your rebased {} ({} new commit(s)). \
+                 The remote still points at {}, where your rebase started, and the lease makes \
+                 the push fail if it doesn't anymore, so commits pushed by someone else in the \
+                 meantime are never overwritten. Unlike a bare --force-with-lease, it doesn't \
+                 trust {} as last fetched, which a background fetch can move without you seeing \
+                 the new commits.",
+                tracking,
+                self.behind,
+                self.branch,
+                self.ahead,
+                short(&self.expected),
+                tracking
+            ),
+        )
+    }
+}
+
+/// Whether `commit` is part of any recent position of `branch`
+fn reflog_includes(repo: &Repository, branch: &str, commit: &str) -> bool {
+    let executor = repo.executor();
+    let Ok(reflog) = executor.execute(&format!("reflog show --format=%H {}", quote_arg(&format!("refs/heads/{}", branch))))
+    else {
+        return false;
+    };
+    let mut seen = std::collections::HashSet::new();
+    reflog.stdout.lines().filter(|entry| seen.insert(*entry)).take(100).any(|entry| {
+        entry == commit || executor.execute(&format!("merge-base --is-ancestor {} {}", commit, entry)).is_ok()
+    })
+}
+
+fn short(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo, commit_file};
+    use tempfile::TempDir;
+
+
+
+    /// A clone with `feature` pushed to a bare remote, then rebased onto a new main commit
+    fn rebased_clone() -> (TempDir, TempDir) {
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "-q", "--bare", "-b", "main"]);
+        let clone = TempDir::new().unwrap();
+        let dir = clone.path();
+        init_repo(dir);
+        git(dir, &["remote", "add", "origin", remote.path().to_str().unwrap()]);
+        commit_file(dir, "base.txt");
+        git(dir, &["push", "-q", "-u", "origin", "main"]);
+        git(dir, &["checkout", "-q", "-b", "feature"]);
+        commit_file(dir, "f1.txt");
+        git(dir, &["push", "-q", "-u", "origin", "feature"]);
+        git(dir, &["checkout", "-q", "main"]);
+        commit_file(dir, "main2.txt");
+        git(dir, &["checkout", "-q", "feature"]);
+        git(dir, &["rebase", "-q", "main"]);
+        (remote, clone)
+    }
+
+    #[test]
+    fn test_force_push_queries() {
+        assert!(is_force_push_query("force push my branch"));
+        assert!(is_force_push_query("Push after the rebase"));
+        assert!(is_force_push_query("git push --force please"));
+        assert!(!is_force_push_query("push my changes"));
+    }
+
+    #[test]
+    fn test_rebased_branch() {
+        assert_eq!(rebased_branch("git rebase main", Some("feature")).as_deref(), Some("feature"));
+        assert_eq!(rebased_branch("git rebase -i HEAD~3", Some("feature")).as_deref(), Some("feature"));
+        assert_eq!(
+            rebased_branch("git rebase --onto main abc123 topic", Some("feature")).as_deref(),
+            Some("topic")
+        );
+        assert_eq!(rebased_branch("git rebase -X theirs main topic", None).as_deref(), Some("topic"));
+        assert_eq!(rebased_branch("git rebase --continue", Some("feature")), None);
+        assert_eq!(rebased_branch("git push --force", Some("feature")), None);
+    }
+
+    #[test]
+    fn test_lease_after_rebase() {
+        let (_remote, clone) = rebased_clone();
+        let repo = Repository::new(clone.path());
+        assert_eq!(upstream_divergence(&repo, "feature"), Some((2, 1)));
+
+        let check = ForcePushCheck::run(&repo, "feature", None).unwrap().unwrap();
+        let pushed = git(clone.path(), &["rev-parse", "origin/feature"]);
+        assert_eq!(check.expected, pushed);
+        assert!(!check.remote_moved());
+        assert_eq!((check.ahead, check.behind), (2, 1));
+
+        let (command, explanation) = check.proposal();
+        assert_eq!(command, format!("git push --force-with-lease=refs/heads/feature:{} origin feature", pushed));
+        assert!(explanation.contains("where your rebase started"));
+
+        git(clone.path(), &["checkout", "-q", "main"]);
+        assert_eq!(ForcePushCheck::run(&repo, "main", None).unwrap().unwrap().behind, 0);
+    }
+
+    #[test]
+    fn test_remote_moved_since_rebase() {
+        let (remote, clone) = rebased_clone();
+        let rebase_start = git(clone.path(), &["rev-parse", "origin/feature"]);
+
+        // Someone else pushes to feature, and a fetch moves origin/feature
+        let other = TempDir::new().unwrap();
+        git(other.path(), &["clone", "-q", "-b", "feature", remote.path().to_str().unwrap(), "."]);
+        git(other.path(), &["config", "user.name", "Other User"]);
+        git(other.path(), &["config", "user.email", "other@example.com"]);
+        commit_file(other.path(), "theirs.txt");
+        git(other.path(), &["push", "-q"]);
+        git(clone.path(), &["fetch", "-q"]);
+
+        let repo = Repository::new(clone.path());
+        let check = ForcePushCheck::run(&repo, "feature", Some(&rebase_start)).unwrap().unwrap();
+        assert!(check.remote_moved());
+        let (command, explanation) = check.proposal();
+        assert_eq!(command, "git fetch origin");
+        assert!(explanation.contains("since your rebase started"));
+
+        // Without the recorded start, the reflog shows the fetched commit was never integrated
+        let unrecorded = ForcePushCheck::run(&repo, "feature", None).unwrap().unwrap();
+        assert!(!unrecorded.remote_moved());
+        assert!(!unrecorded.integrated);
+        assert!(unrecorded.proposal().0.starts_with("git log --oneline --cherry-pick --right-only"));
+    }
+}
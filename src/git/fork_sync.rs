@@ -0,0 +1,209 @@
+use crate::git::executor::quote_arg;
+
+/// Name given to the original project's remote when gitalky adds it
+pub const UPSTREAM_REMOTE: &str = "upstream";
+
+/// Phrases that ask to bring a fork up to date with the project it came from
+const FORK_SYNC_PHRASES: &[&str] = &[
+    "sync my fork",
+    "sync fork",
+    "sync the fork",
+    "sync this fork",
+    "update my fork",
+    "update the fork",
+    "sync with upstream",
+    "sync from upstream",
+    "catch up with upstream",
+    "pull from upstream",
+    "pull upstream changes",
+];
+
+/// Check whether a natural language query asks to sync a fork
+///
+/// Matched queries are answered locally with a [`ForkSync`] plan, built from
+/// the remotes this repository has.
+pub fn is_fork_sync_query(query: &str) -> bool {
+    let normalized = query
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    FORK_SYNC_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// `(your fork, the original project)` among `remotes`
+///
+/// The original is the remote of `second_remote` (`upstream/main`) when it
+/// exists, else one named `upstream`; your fork is `origin`, or the only other
+/// remote.
+pub fn fork_remotes(remotes: &[String], second_remote: Option<&str>) -> (Option<String>, Option<String>) {
+    let upstream = second_remote
+        .and_then(|name| name.split_once('/'))
+        .map(|(remote, _)| remote)
+        .filter(|remote| remotes.iter().any(|r| r == remote))
+        .or_else(|| remotes.iter().find(|r| *r == UPSTREAM_REMOTE).map(String::as_str))
+        .map(str::to_string);
+    let others: Vec<&String> = remotes.iter().filter(|r| Some(*r) != upstream.as_ref()).collect();
+    let origin = match others.as_slice() {
+        [only] => Some((*only).clone()),
+        _ => others.iter().find(|r| r.as_str() == "origin").map(|r| (*r).clone()),
+    };
+    (origin, upstream)
+}
+
+/// The steps that bring a branch of a fork up to date with the original project:
+/// fetch it, merge or rebase onto it, push the result to the fork
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkSync {
+    pub branch: String,
+    pub upstream_remote: String,
+    /// Added as `upstream_remote` first, when the repository has no such remote yet
+    pub upstream_url: Option<String>,
+    /// What to sync with: `upstream/main`
+    pub upstream_branch: String,
+    /// Your fork; no push step without one
+    pub origin_remote: Option<String>,
+    /// Replay local commits instead of merging (`pull.rebase`)
+    pub rebase: bool,
+    /// Local commits the original project doesn't have, when known
+    pub ahead: usize,
+}
+
+impl ForkSync {
+    /// Sync `branch` with the same branch of `upstream_remote`, or with
+    /// `second_remote` when that is on the same remote
+    pub fn new(branch: &str, upstream_remote: &str, origin_remote: Option<String>, second_remote: Option<&str>) -> Self {
+        let upstream_branch = second_remote
+            .filter(|name| name.split_once('/').is_some_and(|(remote, _)| remote == upstream_remote))
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}/{}", upstream_remote, branch));
+        Self {
+            branch: branch.to_string(),
+            upstream_remote: upstream_remote.to_string(),
+            upstream_url: None,
+            upstream_branch,
+            origin_remote,
+            rebase: false,
+            ahead: 0,
+        }
+    }
+
+    /// Add the original project from `url` before fetching it
+    pub fn with_upstream_url(mut self, url: String) -> Self {
+        self.upstream_url = Some(url);
+        self
+    }
+
+    pub fn with_rebase(mut self, rebase: bool, ahead: usize) -> Self {
+        self.rebase = rebase;
+        self.ahead = ahead;
+        self
+    }
+
+    /// `(intent, command)` per step, in order
+    pub fn steps(&self) -> Vec<(String, String)> {
+        let remote = quote_arg(&self.upstream_remote);
+        let target = quote_arg(&self.upstream_branch);
+        let mut steps = Vec::new();
+        if let Some(ref url) = self.upstream_url {
+            steps.push((
+                format!("add the original project as '{}'", self.upstream_remote),
+                format!("git remote add {} {}", remote, quote_arg(url)),
+            ));
+        }
+        steps.push((format!("fetch {}", self.upstream_remote), format!("git fetch {}", remote)));
+        if self.rebase {
+            steps.push((format!("replay your commits onto {}", self.upstream_branch), format!("git rebase {}", target)));
+        } else {
+            steps.push((format!("merge {}", self.upstream_branch), format!("git merge {}", target)));
+        }
+        if let Some(ref origin) = self.origin_remote {
+            // Rebased commits replace the ones the fork has
+            let force = if self.rebase && self.ahead > 0 { "--force-with-lease " } else { "" };
+            steps.push((
+                format!("push {} to your fork", self.branch),
+                format!("git push {}{} {}", force, quote_arg(origin), quote_arg(&self.branch)),
+            ));
+        }
+        steps
+    }
+
+    /// What the plan does to history, shown above its steps
+    pub fn warning(&self) -> String {
+        let push = match self.origin_remote {
+            Some(ref origin) => format!("then pushes {} to {}", self.branch, origin),
+            None => "no fork remote was found to push to".to_string(),
+        };
+        if self.rebase && self.ahead > 0 {
+            format!(
+                "Rebases your {} commit(s) onto {} (pull.rebase), {} with --force-with-lease.",
+                self.ahead, self.upstream_branch, push
+            )
+        } else if self.rebase {
+            format!("Rebases onto {} (pull.rebase), {}.", self.upstream_branch, push)
+        } else {
+            format!("Merges {} into {} (fast-forward when possible), {}.", self.upstream_branch, self.branch, push)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remotes(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_fork_sync_queries() {
+        assert!(is_fork_sync_query("sync my fork"));
+        assert!(is_fork_sync_query("Please  sync with upstream"));
+        assert!(!is_fork_sync_query("sync the tags"));
+        assert!(!is_fork_sync_query("set upstream"));
+    }
+
+    #[test]
+    fn test_fork_remotes() {
+        let some = |name: &str| Some(name.to_string());
+        assert_eq!(fork_remotes(&remotes(&["origin", "upstream"]), None), (some("origin"), some("upstream")));
+        assert_eq!(
+            fork_remotes(&remotes(&["mine", "project"]), Some("project/main")),
+            (some("mine"), some("project"))
+        );
+        assert_eq!(fork_remotes(&remotes(&["origin"]), None), (some("origin"), None));
+        // The configured remote must exist
+        assert_eq!(fork_remotes(&remotes(&["origin"]), Some("project/main")), (some("origin"), None));
+        assert_eq!(fork_remotes(&remotes(&["a", "b", "upstream"]), None), (None, some("upstream")));
+    }
+
+    #[test]
+    fn test_merge_steps() {
+        let sync = ForkSync::new("main", "upstream", Some("origin".to_string()), None);
+        let commands: Vec<String> = sync.steps().into_iter().map(|(_, command)| command).collect();
+        assert_eq!(commands, ["git fetch upstream", "git merge upstream/main", "git push origin main"]);
+        assert!(sync.warning().starts_with("Merges upstream/main into main"));
+    }
+
+    #[test]
+    fn test_rebase_steps_with_new_remote() {
+        let sync = ForkSync::new("feature", "upstream", Some("origin".to_string()), Some("upstream/trunk"))
+            .with_upstream_url("https://example.com/project.git".to_string())
+            .with_rebase(true, 2);
+        let commands: Vec<String> = sync.steps().into_iter().map(|(_, command)| command).collect();
+        assert_eq!(
+            commands,
+            [
+                "git remote add upstream https://example.com/project.git",
+                "git fetch upstream",
+                "git rebase upstream/trunk",
+                "git push --force-with-lease origin feature",
+            ]
+        );
+
+        let sync = ForkSync::new("main", "upstream", None, Some("other/main")).with_rebase(true, 0);
+        let commands: Vec<String> = sync.steps().into_iter().map(|(_, command)| command).collect();
+        assert_eq!(commands, ["git fetch upstream", "git rebase upstream/main"]);
+        assert!(sync.warning().ends_with("no fork remote was found to push to."));
+    }
+}
@@ -0,0 +1,297 @@
+use crate::error::{GitError, GitResult};
+use crate::git::maintenance::MaintenanceStatus;
+use crate::git::repository::Repository;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Branches without a commit for this many days are listed as stale
+pub const STALE_BRANCH_DAYS: i64 = 90;
+
+/// Largest blobs in history that are listed
+pub const MAX_LARGE_BLOBS: usize = 5;
+
+/// Loose objects beyond which `git gc` is suggested
+const LOOSE_OBJECT_LIMIT: u64 = 1000;
+
+/// Days after the last repack before `git gc` is suggested
+const GC_INTERVAL_DAYS: i64 = 30;
+
+/// Stale branches offered for deletion
+const MAX_BRANCH_SUGGESTIONS: usize = 3;
+
+/// Object store figures from `git count-objects -v`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ObjectCounts {
+    pub loose_objects: u64,
+    pub loose_bytes: u64,
+    pub packs: u64,
+    pub pack_bytes: u64,
+    /// Files in the object directory git doesn't recognize
+    pub garbage: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleBranch {
+    pub name: String,
+    pub days: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeBlob {
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// Maintenance overview of a repository, for "why is my repo slow"
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoHealth {
+    pub objects: ObjectCounts,
+    /// Local branches other than the current one, oldest first
+    pub stale_branches: Vec<StaleBranch>,
+    /// Largest blobs reachable from any ref, largest first
+    pub large_blobs: Vec<LargeBlob>,
+    pub dangling_commits: usize,
+    /// Days since the newest pack was written, which approximates the last
+    /// gc; `None` when there are no packs
+    pub days_since_repack: Option<i64>,
+    pub maintenance: MaintenanceStatus,
+}
+
+impl RepoHealth {
+    /// Run the read-only git commands behind the summary
+    pub fn collect(repo: &Repository, current_branch: Option<&str>) -> GitResult<Self> {
+        let executor = repo.executor();
+        let now = chrono::Utc::now().timestamp();
+
+        let objects = parse_count_objects(&executor.execute("count-objects -v")?.stdout);
+
+        let branches = executor.execute(
+            "for-each-ref --format=%(refname:short)%09%(committerdate:unix) refs/heads",
+        )?;
+        let stale_branches = parse_stale_branches(&branches.stdout, now, current_branch);
+
+        let sizes = executor.execute("cat-file --batch-all-objects --batch-check")?;
+        let paths = executor.execute("rev-list --objects --all")?;
+        let large_blobs = parse_large_blobs(&sizes.stdout, &paths.stdout);
+
+        // fsck exits non-zero when it finds problems; the report is on stdout either way
+        let dangling_commits = match executor.execute("fsck --no-progress --dangling") {
+            Ok(output) => count_dangling_commits(&output.stdout),
+            Err(GitError::CommandExited { stdout, .. }) => count_dangling_commits(&stdout),
+            Err(_) => 0,
+        };
+
+        let days_since_repack = executor
+            .execute("rev-parse --git-path objects/pack")
+            .ok()
+            .and_then(|output| newest_pack_age_days(repo.path().join(output.stdout.trim())));
+
+        Ok(Self {
+            objects,
+            stale_branches,
+            large_blobs,
+            dangling_commits,
+            days_since_repack,
+            maintenance: MaintenanceStatus::detect(repo),
+        })
+    }
+
+    /// Loose and packed objects together
+    pub fn size_bytes(&self) -> u64 {
+        self.objects.loose_bytes + self.objects.pack_bytes
+    }
+
+    /// Maintenance commands worth running; they go through the preview and
+    /// validation like any other command
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut commands = Vec::new();
+        let overdue = self.days_since_repack.is_none_or(|days| days > GC_INTERVAL_DAYS);
+        if self.objects.loose_objects > LOOSE_OBJECT_LIMIT
+            || (overdue && self.objects.loose_objects > 0)
+        {
+            commands.push("git gc".to_string());
+        }
+        commands.extend(self.maintenance.suggestion());
+        // `-d` refuses to delete unmerged branches, so nothing is lost by trying
+        for branch in self.stale_branches.iter().take(MAX_BRANCH_SUGGESTIONS) {
+            commands.push(format!("git branch -d {}", crate::git::quote_arg(&branch.name)));
+        }
+        commands
+    }
+}
+
+/// Parse `git count-objects -v` ("count: 12", "size: 48", ... sizes in KiB)
+pub fn parse_count_objects(output: &str) -> ObjectCounts {
+    let values: HashMap<&str, u64> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim(), value.trim().parse().ok()?))
+        })
+        .collect();
+    let get = |key: &str| values.get(key).copied().unwrap_or(0);
+
+    ObjectCounts {
+        loose_objects: get("count"),
+        loose_bytes: get("size") * 1024,
+        packs: get("packs"),
+        pack_bytes: get("size-pack") * 1024,
+        garbage: get("garbage"),
+    }
+}
+
+/// Branches whose last commit is older than [`STALE_BRANCH_DAYS`], oldest first
+///
+/// `output` is "name<TAB>committer unix time" per line.
+pub fn parse_stale_branches(output: &str, now: i64, current_branch: Option<&str>) -> Vec<StaleBranch> {
+    let mut branches: Vec<StaleBranch> = output
+        .lines()
+        .filter_map(|line| {
+            let (name, time) = line.split_once('\t')?;
+            let days = (now - time.trim().parse::<i64>().ok()?) / 86_400;
+            (days >= STALE_BRANCH_DAYS && Some(name) != current_branch).then(|| StaleBranch {
+                name: name.to_string(),
+                days,
+            })
+        })
+        .collect();
+    branches.sort_by(|a, b| b.days.cmp(&a.days).then_with(|| a.name.cmp(&b.name)));
+    branches
+}
+
+/// The largest blobs in `batch_check` that have a path in `rev_list`
+///
+/// `batch_check` is "sha type size" for every object; `rev_list` is
+/// `rev-list --objects` output ("sha path"). Unreachable blobs have no path
+/// and are left out.
+pub fn parse_large_blobs(batch_check: &str, rev_list: &str) -> Vec<LargeBlob> {
+    let paths: HashMap<&str, &str> = rev_list
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .collect();
+
+    let mut blobs: Vec<LargeBlob> = batch_check
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let sha = fields.next()?;
+            if fields.next()? != "blob" {
+                return None;
+            }
+            let bytes = fields.next()?.parse().ok()?;
+            Some(LargeBlob {
+                path: paths.get(sha)?.to_string(),
+                bytes,
+            })
+        })
+        .collect();
+    blobs.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    blobs.truncate(MAX_LARGE_BLOBS);
+    blobs
+}
+
+fn count_dangling_commits(fsck: &str) -> usize {
+    fsck.lines().filter(|line| line.starts_with("dangling commit ")).count()
+}
+
+/// Age in days of the newest `.pack` file in `pack_dir`
+fn newest_pack_age_days(pack_dir: PathBuf) -> Option<i64> {
+    let newest = std::fs::read_dir(pack_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "pack"))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()?;
+    let age = SystemTime::now().duration_since(newest).unwrap_or_default();
+    Some((age.as_secs() / 86_400) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_count_objects() {
+        let output = "count: 1200\nsize: 48\nin-pack: 30\npacks: 2\nsize-pack: 1024\nprune-packable: 0\ngarbage: 1\nsize-garbage: 4\n";
+        let counts = parse_count_objects(output);
+        assert_eq!(counts.loose_objects, 1200);
+        assert_eq!(counts.loose_bytes, 48 * 1024);
+        assert_eq!(counts.packs, 2);
+        assert_eq!(counts.pack_bytes, 1024 * 1024);
+        assert_eq!(counts.garbage, 1);
+    }
+
+    #[test]
+    fn test_parse_stale_branches() {
+        let now = 1_000 * 86_400;
+        let output = format!(
+            "main\t{}\nold\t{}\nolder\t{}\nfresh\t{}\n",
+            now - 400 * 86_400,
+            now - 100 * 86_400,
+            now - 200 * 86_400,
+            now - 86_400
+        );
+        let stale = parse_stale_branches(&output, now, Some("main"));
+        assert_eq!(
+            stale,
+            vec![
+                StaleBranch { name: "older".to_string(), days: 200 },
+                StaleBranch { name: "old".to_string(), days: 100 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_large_blobs() {
+        let batch = "aaa commit 200\nbbb blob 5000\nccc blob 90\nddd blob 99999\neee tree 30\n";
+        let rev_list = "aaa\nbbb assets/logo.png\nccc README.md\neee \n";
+        let blobs = parse_large_blobs(batch, rev_list);
+        assert_eq!(
+            blobs,
+            vec![
+                LargeBlob { path: "assets/logo.png".to_string(), bytes: 5000 },
+                LargeBlob { path: "README.md".to_string(), bytes: 90 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut health = RepoHealth {
+            objects: ObjectCounts { loose_objects: 5, ..Default::default() },
+            days_since_repack: Some(2),
+            ..Default::default()
+        };
+        assert!(health.suggestions().is_empty());
+
+        health.objects.loose_objects = 5000;
+        health.stale_branches.push(StaleBranch { name: "old".to_string(), days: 120 });
+        assert_eq!(health.suggestions(), vec!["git gc", "git branch -d old"]);
+
+        health.maintenance = MaintenanceStatus { supported: true, ..Default::default() };
+        assert_eq!(
+            health.suggestions(),
+            vec!["git gc", "git maintenance start", "git branch -d old"]
+        );
+    }
+
+    #[test]
+    fn test_collect() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        std::fs::write(dir.join("big.bin"), vec![7u8; 10_000]).unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Add blob"]);
+
+        let health = RepoHealth::collect(&Repository::new(dir), Some("main")).unwrap();
+        assert!(health.objects.loose_objects >= 3);
+        assert_eq!(health.large_blobs[0].path, "big.bin");
+        assert_eq!(health.large_blobs[0].bytes, 10_000);
+        assert!(health.stale_branches.is_empty());
+        assert_eq!(health.days_since_repack, None);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::git::executor::quote_arg;
+use serde::Serialize;
+
+/// A tracked file whose working tree changes git has been told to ignore, so
+/// `git status` and `git diff` stay quiet about it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HiddenFile {
+    pub path: String,
+    /// `git update-index --assume-unchanged`: a performance promise, lost on reset
+    pub assume_unchanged: bool,
+    /// `git update-index --skip-worktree`: keep local edits, survives checkouts
+    pub skip_worktree: bool,
+}
+
+impl HiddenFile {
+    /// Which bits are set, as shown in the panel and LLM context
+    pub fn label(&self) -> &'static str {
+        match (self.assume_unchanged, self.skip_worktree) {
+            (true, true) => "assume-unchanged, skip-worktree",
+            (true, false) => "assume-unchanged",
+            _ => "skip-worktree",
+        }
+    }
+}
+
+/// Files with the assume-unchanged or skip-worktree bit from `git ls-files -v -z`
+///
+/// A lowercase tag means assume-unchanged, `S`/`s` skip-worktree.
+pub fn parse_hidden_files(output: &str) -> Vec<HiddenFile> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let (tag, path) = record.split_once(' ')?;
+            let tag = tag.chars().next()?;
+            let assume_unchanged = tag.is_ascii_lowercase();
+            let skip_worktree = tag.eq_ignore_ascii_case(&'s');
+            (assume_unchanged || skip_worktree).then(|| HiddenFile {
+                path: path.to_string(),
+                assume_unchanged,
+                skip_worktree,
+            })
+        })
+        .collect()
+}
+
+/// `git update-index` clearing every bit set on `files`, so their changes show again
+pub fn unhide_command(files: &[&HiddenFile]) -> String {
+    let mut flags = Vec::new();
+    if files.iter().any(|file| file.assume_unchanged) {
+        flags.push("--no-assume-unchanged");
+    }
+    if files.iter().any(|file| file.skip_worktree) {
+        flags.push("--no-skip-worktree");
+    }
+    let paths: Vec<String> = files.iter().map(|file| quote_arg(&file.path)).collect();
+    format!("git update-index {} -- {}", flags.join(" "), paths.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hidden_files() {
+        let output = "H src/main.rs\0h config/local.toml\0S .env\0s my notes.md\0";
+        let hidden = parse_hidden_files(output);
+        assert_eq!(hidden.len(), 3);
+        assert_eq!(hidden[0].path, "config/local.toml");
+        assert_eq!(hidden[0].label(), "assume-unchanged");
+        assert_eq!(hidden[1].label(), "skip-worktree");
+        assert_eq!(hidden[2].path, "my notes.md");
+        assert_eq!(hidden[2].label(), "assume-unchanged, skip-worktree");
+    }
+
+    #[test]
+    fn test_unhide_command() {
+        let hidden = parse_hidden_files("h a.toml\0S my notes.md\0");
+        assert_eq!(unhide_command(&[&hidden[0]]), "git update-index --no-assume-unchanged -- a.toml");
+        assert_eq!(
+            unhide_command(&hidden.iter().collect::<Vec<_>>()),
+            "git update-index --no-assume-unchanged --no-skip-worktree -- a.toml 'my notes.md'"
+        );
+    }
+}
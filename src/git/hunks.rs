@@ -0,0 +1,204 @@
+use crate::error::{GitError, GitResult};
+use crate::git::checkpoint::Checkpoint;
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::repository::Repository;
+use std::fs;
+use std::path::Path;
+
+/// Patch file `git apply -R` reads a discarded hunk from, in the git directory
+const DISCARD_PATCH_FILE: &str = "gitalky-discard.patch";
+
+/// One hunk of `git diff` output with the file header it needs to apply alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub path: String,
+    /// `diff --git` through `+++` lines
+    header: String,
+    /// `@@` line and body
+    body: String,
+}
+
+impl DiffHunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub fn range(&self) -> &str {
+        self.body.lines().next().unwrap_or_default()
+    }
+
+    /// Body lines after the `@@` line
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.body.lines().skip(1)
+    }
+
+    /// Lines added and removed
+    pub fn counts(&self) -> (usize, usize) {
+        let count = |prefix: char| self.lines().filter(|line| line.starts_with(prefix)).count();
+        (count('+'), count('-'))
+    }
+
+    /// A patch holding only this hunk
+    pub fn patch(&self) -> String {
+        format!("{}{}", self.header, self.body)
+    }
+}
+
+/// Split `git diff` output into hunks; binary files and mode changes have none
+pub fn parse_hunks(diff: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    let mut header = String::new();
+    let mut path: Option<String> = None;
+    let mut in_body = false;
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            header = line.to_string();
+            path = None;
+            in_body = false;
+        } else if line.starts_with("@@ ") && let Some(ref path) = path {
+            hunks.push(DiffHunk {
+                path: path.clone(),
+                header: header.clone(),
+                body: line.to_string(),
+            });
+            in_body = true;
+        } else if in_body && let Some(hunk) = hunks.last_mut() {
+            hunk.body.push_str(line);
+        } else {
+            if let Some(new) = line.strip_prefix("+++ ") {
+                path = new.trim_end().strip_prefix("b/").map(str::to_string);
+            }
+            header.push_str(line);
+        }
+    }
+    hunks
+}
+
+/// Paths an unstaged `git diff` command shows, empty for all of them
+///
+/// `None` for staged diffs and diffs against a revision: their hunks aren't
+/// in the working tree alone. Arguments naming files under `root` are paths.
+pub fn unstaged_diff_paths(command: &str, root: &Path) -> Option<Vec<String>> {
+    let words = split_command(command).ok()?;
+    let mut words = words.into_iter().skip_while(|word| word == "git");
+    if words.next()? != "diff" {
+        return None;
+    }
+    let mut paths = Vec::new();
+    let mut after_separator = false;
+    for word in words {
+        if after_separator {
+            paths.push(word);
+        } else if word == "--" {
+            after_separator = true;
+        } else if matches!(word.as_str(), "--cached" | "--staged" | "--no-index") {
+            return None;
+        } else if !word.starts_with('-') {
+            if !root.join(&word).exists() {
+                return None;
+            }
+            paths.push(word);
+        }
+    }
+    Some(paths)
+}
+
+/// Unstaged hunks of `paths` (all files when empty)
+pub fn unstaged_hunks(repo: &Repository, paths: &[String]) -> GitResult<Vec<DiffHunk>> {
+    let mut command = "diff --no-color --no-ext-diff --src-prefix=a/ --dst-prefix=b/".to_string();
+    if !paths.is_empty() {
+        let quoted: Vec<String> = paths.iter().map(|path| quote_arg(path)).collect();
+        command.push_str(&format!(" -- {}", quoted.join(" ")));
+    }
+    Ok(parse_hunks(&repo.executor().execute(&command)?.stdout))
+}
+
+/// Remove `hunk` from the working tree with `git apply -R`
+///
+/// A checkpoint of the uncommitted changes is saved first, so the hunk can
+/// be restored from `:checkpoint`. Nothing changes when the hunk no longer
+/// applies or the checkpoint can't be saved.
+pub fn discard_hunk(repo: &Repository, hunk: &DiffHunk) -> GitResult<Checkpoint> {
+    let patch_path = repo.git_dir().join(DISCARD_PATCH_FILE);
+    fs::write(&patch_path, hunk.patch())?;
+    let result = apply_reversed(repo, hunk, &quote_arg(&patch_path.to_string_lossy()));
+    let _ = fs::remove_file(&patch_path);
+    result
+}
+
+fn apply_reversed(repo: &Repository, hunk: &DiffHunk, patch: &str) -> GitResult<Checkpoint> {
+    let executor = repo.executor();
+    executor
+        .execute(&format!("apply -R --check {}", patch))
+        .map_err(|_| GitError::CommandFailed(format!("The hunk no longer matches {}; reload the diff", hunk.path)))?;
+    let name: String = format!("before discarding a hunk of {}", hunk.path)
+        .chars()
+        .filter(|c| !"$`|;&".contains(*c) && !c.is_control())
+        .collect();
+    let checkpoint = Checkpoint::create(repo, &name)?;
+    executor.execute(&format!("apply -R {}", patch))?;
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    const DIFF: &str = "diff --git a/a.txt b/a.txt\nindex 1..2 100644\n--- a/a.txt\n+++ b/a.txt\n\
+                        @@ -1,2 +1,2 @@\n-one\n+ONE\n two\n@@ -9 +9,2 @@\n nine\n+ten\n\
+                        diff --git a/img.png b/img.png\nBinary files a/img.png and b/img.png differ\n\
+                        diff --git a/gone.txt b/gone.txt\ndeleted file mode 100644\n--- a/gone.txt\n+++ /dev/null\n\
+                        @@ -1 +0,0 @@\n-x\n";
+
+    #[test]
+    fn test_parse_hunks() {
+        let hunks = parse_hunks(DIFF);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].path, "a.txt");
+        assert_eq!(hunks[0].range(), "@@ -1,2 +1,2 @@");
+        assert_eq!(hunks[0].counts(), (1, 1));
+        assert_eq!(
+            hunks[1].patch(),
+            "diff --git a/a.txt b/a.txt\nindex 1..2 100644\n--- a/a.txt\n+++ b/a.txt\n@@ -9 +9,2 @@\n nine\n+ten\n"
+        );
+    }
+
+    #[test]
+    fn test_unstaged_diff_paths() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.txt"), "").unwrap();
+        let paths = |command: &str| unstaged_diff_paths(command, temp.path());
+        assert_eq!(paths("git diff"), Some(vec![]));
+        assert_eq!(paths("git diff --stat a.txt"), Some(vec!["a.txt".to_string()]));
+        assert_eq!(paths("git diff -- gone.txt"), Some(vec!["gone.txt".to_string()]));
+        assert_eq!(paths("git diff --cached"), None);
+        assert_eq!(paths("git diff HEAD~1"), None);
+        assert_eq!(paths("git show"), None);
+    }
+
+    #[test]
+    fn test_discard_one_hunk() {
+        let temp = TempDir::new().unwrap();
+        let original: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        init_repo(temp.path());
+        fs::write(temp.path().join("a.txt"), &original).unwrap();
+        git(temp.path(), &["add", "a.txt"]);
+        git(temp.path(), &["commit", "-q", "-m", "a"]);
+        let edited = original.replace("line 2\n", "line two\n").replace("line 19\n", "line nineteen\n");
+        fs::write(temp.path().join("a.txt"), &edited).unwrap();
+
+        let repo = Repository::new(temp.path());
+        let hunks = unstaged_hunks(&repo, &[]).unwrap();
+        assert_eq!(hunks.len(), 2);
+        let checkpoint = discard_hunk(&repo, &hunks[1]).unwrap();
+        assert!(checkpoint.stash.is_some());
+        assert_eq!(
+            fs::read_to_string(temp.path().join("a.txt")).unwrap(),
+            original.replace("line 2\n", "line two\n")
+        );
+        assert!(!repo.git_dir().join(DISCARD_PATCH_FILE).exists());
+
+        // Already discarded: refused, nothing saved
+        assert!(discard_hunk(&repo, &hunks[1]).unwrap_err().to_string().contains("no longer matches"));
+        assert_eq!(Checkpoint::list(&repo).unwrap().len(), 1);
+    }
+}
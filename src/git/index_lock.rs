@@ -0,0 +1,202 @@
+use crate::git::executor::running_commands;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Locks at least this old are offered for removal when no git process runs
+pub const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Why a lock was not removed
+#[derive(Debug, Error)]
+pub enum LockRemovalError {
+    #[error("index.lock is only {} old; another git process may still be using it", format_age(*.0))]
+    TooRecent(Duration),
+
+    #[error("A git process is running and may still be using index.lock")]
+    GitRunning,
+
+    #[error("Failed to remove index.lock: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// The `index.lock` another git process created, and how long ago
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexLock {
+    path: PathBuf,
+    age: Duration,
+}
+
+impl IndexLock {
+    /// The lock in `git_dir`, if there is one
+    pub fn find(git_dir: &Path) -> Option<Self> {
+        let path = git_dir.join("index.lock");
+        let modified = fs::metadata(&path).ok()?.modified().ok();
+        let age = modified
+            .and_then(|time| SystemTime::now().duration_since(time).ok())
+            .unwrap_or_default();
+        Some(IndexLock { path, age })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.age >= STALE_LOCK_AGE
+    }
+
+    /// Who probably holds the lock, judged by its age
+    pub fn likely_holder(&self) -> String {
+        let age = format_age(self.age);
+        if self.is_stale() {
+            format!("The lock was created {} ago, probably by a git process that crashed or was killed.", age)
+        } else if self.age < Duration::from_secs(60) {
+            format!(
+                "The lock was created {} ago; another git process (an IDE, a shell or a GUI client) is probably still running.",
+                age
+            )
+        } else {
+            format!(
+                "The lock was created {} ago, perhaps by an editor still open for git commit or a paused rebase.",
+                age
+            )
+        }
+    }
+
+    /// Delete the lock, refusing unless it is stale and no git process is running
+    pub fn remove_stale(&self, git_running: bool) -> Result<(), LockRemovalError> {
+        if git_running {
+            return Err(LockRemovalError::GitRunning);
+        }
+        // The lock may have been released and taken again since it was found
+        let Some(current) = self.path.parent().and_then(IndexLock::find) else {
+            return Ok(());
+        };
+        if !current.is_stale() {
+            return Err(LockRemovalError::TooRecent(current.age));
+        }
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result.map_err(LockRemovalError::from),
+        }
+    }
+}
+
+/// Whether git's error output is about `index.lock`
+pub fn is_index_lock_error(error_text: &str) -> bool {
+    error_text.contains("index.lock")
+}
+
+/// `45 s`, `12 min`, `3 h`, `2 days`
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => format!("{} s", secs),
+        60..3600 => format!("{} min", secs / 60),
+        3600..86400 => format!("{} h", secs / 3600),
+        _ => format!("{} days", secs / 86400),
+    }
+}
+
+/// Whether any git process is running on this machine, as far as can be told;
+/// assumes one is when the process list can't be read
+pub fn git_processes_running() -> bool {
+    if running_commands() > 0 {
+        return true;
+    }
+    if let Ok(entries) = fs::read_dir("/proc") {
+        return entries
+            .flatten()
+            .any(|entry| fs::read_to_string(entry.path().join("comm")).is_ok_and(|comm| is_git_process(comm.trim())));
+    }
+    Command::new("ps")
+        .args(["-A", "-o", "comm="])
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+                let name = Path::new(line.trim()).file_name().and_then(|name| name.to_str());
+                name.is_some_and(is_git_process)
+            })
+        })
+        .unwrap_or(true)
+}
+
+/// `git` itself or one of its helpers, e.g. `git-remote-https`
+fn is_git_process(name: &str) -> bool {
+    name == "git" || name.starts_with("git-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lock_aged(dir: &Path, age: Duration) -> IndexLock {
+        let file = fs::File::create(dir.join("index.lock")).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+        IndexLock::find(dir).unwrap()
+    }
+
+    #[test]
+    fn test_find() {
+        let temp = TempDir::new().unwrap();
+        assert!(IndexLock::find(temp.path()).is_none());
+
+        let lock = lock_aged(temp.path(), Duration::from_secs(120));
+        assert_eq!(lock.path(), temp.path().join("index.lock"));
+        assert!(lock.age() >= Duration::from_secs(120));
+        assert!(!lock.is_stale());
+        assert!(lock.likely_holder().contains("2 min ago"));
+    }
+
+    #[test]
+    fn test_remove_stale() {
+        let temp = TempDir::new().unwrap();
+
+        let fresh = lock_aged(temp.path(), Duration::from_secs(5));
+        assert!(matches!(fresh.remove_stale(false), Err(LockRemovalError::TooRecent(_))));
+        assert!(fresh.path().exists());
+
+        let stale = lock_aged(temp.path(), STALE_LOCK_AGE + Duration::from_secs(60));
+        assert!(stale.likely_holder().contains("crashed"));
+        assert!(matches!(stale.remove_stale(true), Err(LockRemovalError::GitRunning)));
+        assert!(stale.path().exists());
+
+        stale.remove_stale(false).unwrap();
+        assert!(!stale.path().exists());
+        // Already gone is fine
+        stale.remove_stale(false).unwrap();
+    }
+
+    #[test]
+    fn test_stale_check_uses_current_lock() {
+        let temp = TempDir::new().unwrap();
+        let stale = lock_aged(temp.path(), STALE_LOCK_AGE * 2);
+        // Released and taken again by a new process
+        lock_aged(temp.path(), Duration::ZERO);
+        assert!(matches!(stale.remove_stale(false), Err(LockRemovalError::TooRecent(_))));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(45)), "45 s");
+        assert_eq!(format_age(Duration::from_secs(12 * 60 + 5)), "12 min");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600)), "3 h");
+        assert_eq!(format_age(Duration::from_secs(2 * 86400 + 1)), "2 days");
+    }
+
+    #[test]
+    fn test_is_git_process() {
+        assert!(is_git_process("git"));
+        assert!(is_git_process("git-remote-https"));
+        assert!(!is_git_process("gitalky"));
+        assert!(!is_git_process("bash"));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::{GitError, GitResult};
+use crate::git::repository::Repository;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Name of the lockfile inside the repository's git directory
+pub const LOCK_FILE_NAME: &str = "gitalky.lock";
+
+/// Information about the gitalky instance that owns a repository lock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub started_at: String,
+}
+
+/// Result of trying to take the per-repo session lock
+#[derive(Debug)]
+pub enum LockStatus {
+    /// This process now owns the lock
+    Acquired(SessionLock),
+    /// Another live gitalky instance owns the lock
+    HeldBy(LockOwner),
+}
+
+/// Advisory per-repository lock held for the lifetime of a gitalky session
+///
+/// The lockfile records the owning PID so a lock left behind by a crashed
+/// instance can be detected as stale and taken over. The file is removed
+/// when the lock is dropped.
+#[derive(Debug)]
+pub struct SessionLock {
+    path: PathBuf,
+}
+
+impl SessionLock {
+    /// Try to acquire the session lock for a repository
+    pub fn acquire(repo: &Repository) -> GitResult<LockStatus> {
+        let git_dir = Self::git_dir(repo)?;
+        Self::acquire_at(git_dir.join(LOCK_FILE_NAME))
+    }
+
+    /// Try to acquire a session lock at an explicit lockfile path
+    pub fn acquire_at<P: AsRef<Path>>(path: P) -> GitResult<LockStatus> {
+        let path = path.as_ref().to_path_buf();
+
+        // Two attempts: the second one runs after removing a stale lock
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}", std::process::id())?;
+                    writeln!(file, "{}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"))?;
+                    return Ok(LockStatus::Acquired(SessionLock { path }));
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    match Self::read_owner(&path) {
+                        Some(owner) if owner.pid != std::process::id() && process_alive(owner.pid) => {
+                            return Ok(LockStatus::HeldBy(owner));
+                        }
+                        // Stale, unreadable, or left behind by this process
+                        _ => match fs::remove_file(&path) {
+                            Ok(()) => continue,
+                            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                            Err(e) => return Err(GitError::IoError(e)),
+                        },
+                    }
+                }
+                Err(e) => return Err(GitError::IoError(e)),
+            }
+        }
+
+        // Lost the race to another instance that started at the same moment
+        match Self::read_owner(&path) {
+            Some(owner) => Ok(LockStatus::HeldBy(owner)),
+            None => Err(GitError::CommandFailed(format!(
+                "Could not acquire session lock at {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Path of the lockfile
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the owner recorded in a lockfile
+    fn read_owner(path: &Path) -> Option<LockOwner> {
+        let contents = fs::read_to_string(path).ok()?;
+        let mut lines = contents.lines();
+        let pid = lines.next()?.trim().parse().ok()?;
+        let started_at = lines.next().unwrap_or("unknown").trim().to_string();
+        Some(LockOwner { pid, started_at })
+    }
+
+    /// Resolve the git directory (handles worktrees where `.git` is a file)
+    fn git_dir(repo: &Repository) -> GitResult<PathBuf> {
+        let output = repo.executor().execute("rev-parse --git-dir")?;
+        let git_dir = PathBuf::from(output.stdout.trim());
+        if git_dir.is_absolute() {
+            Ok(git_dir)
+        } else {
+            Ok(repo.path().join(git_dir))
+        }
+    }
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Check whether a process with the given PID is still running
+#[cfg(target_os = "linux")]
+fn process_alive(pid: u32) -> bool {
+    Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+/// Check whether a process with the given PID is still running
+#[cfg(all(unix, not(target_os = "linux")))]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(true)
+}
+
+/// Check whether a process with the given PID is still running
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    // No cheap liveness check; assume the owner is alive
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn write_lock(path: &Path, pid: u32) {
+        fs::write(path, format!("{}\n2025-01-01 00:00:00\n", pid)).unwrap();
+    }
+
+    #[test]
+    fn test_acquire_and_release() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE_NAME);
+
+        let status = SessionLock::acquire_at(&path).unwrap();
+        let lock = match status {
+            LockStatus::Acquired(lock) => lock,
+            LockStatus::HeldBy(owner) => panic!("unexpected owner {:?}", owner),
+        };
+        assert!(path.exists());
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().next().unwrap(), std::process::id().to_string());
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_live_owner_is_reported() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE_NAME);
+
+        let mut child = Command::new("sleep").arg("5").spawn().unwrap();
+        write_lock(&path, child.id());
+
+        let status = SessionLock::acquire_at(&path).unwrap();
+        let _ = child.kill();
+        let _ = child.wait();
+
+        match status {
+            LockStatus::HeldBy(owner) => {
+                assert_eq!(owner.pid, child.id());
+                assert_eq!(owner.started_at, "2025-01-01 00:00:00");
+            }
+            LockStatus::Acquired(_) => panic!("lock should be held by the child process"),
+        }
+        // The other instance's lockfile must be left alone
+        assert!(path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE_NAME);
+
+        let mut child = Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+        write_lock(&path, dead_pid);
+
+        let status = SessionLock::acquire_at(&path).unwrap();
+        assert!(matches!(status, LockStatus::Acquired(_)));
+    }
+
+    #[test]
+    fn test_garbage_lock_is_taken_over() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(LOCK_FILE_NAME);
+        fs::write(&path, "not a pid").unwrap();
+
+        let status = SessionLock::acquire_at(&path).unwrap();
+        assert!(matches!(status, LockStatus::Acquired(_)));
+    }
+
+    #[test]
+    fn test_acquire_in_repository() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let repo = Repository::new(temp.path());
+
+        let status = SessionLock::acquire(&repo).unwrap();
+        match status {
+            LockStatus::Acquired(lock) => {
+                assert!(lock.path().ends_with(".git/gitalky.lock"));
+                assert!(lock.path().exists());
+            }
+            LockStatus::HeldBy(owner) => panic!("unexpected owner {:?}", owner),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::git::repository::Repository;
+use crate::git::version::GitVersion;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// First git release with `git maintenance start`
+const MIN_MAINTENANCE_VERSION: (u32, u32) = (2, 30);
+
+/// Hours since the last maintenance output before a manual run is suggested
+const STALE_MAINTENANCE_HOURS: u64 = 48;
+
+/// Files maintenance tasks rewrite: commit-graph, incremental-repack and the
+/// lock held while a run is in progress
+const MAINTENANCE_OUTPUTS: &[&str] = &[
+    "objects/info/commit-graph",
+    "objects/info/commit-graphs/commit-graph-chain",
+    "objects/pack/multi-pack-index",
+];
+const MAINTENANCE_LOCK: &str = "objects/maintenance.lock";
+
+/// Whether background `git maintenance` runs for a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaintenanceStatus {
+    /// The installed git has `git maintenance start`
+    pub supported: bool,
+    /// Listed in the global `maintenance.repo` config
+    pub registered: bool,
+    /// `maintenance.strategy`, e.g. "incremental"
+    pub strategy: Option<String>,
+    /// Hours since a maintenance task last wrote its output; fetches and gc
+    /// write some of the same files, so this is an approximation
+    pub hours_since_run: Option<u64>,
+    /// A maintenance run holds the lock right now
+    pub running: bool,
+}
+
+impl MaintenanceStatus {
+    pub fn detect(repo: &Repository) -> Self {
+        let supported = GitVersion::detect()
+            .is_ok_and(|v| (v.major, v.minor) >= MIN_MAINTENANCE_VERSION);
+        if !supported {
+            return Self::default();
+        }
+
+        let executor = repo.executor();
+        // Exits 1 when the key is unset
+        let registered_repos = executor
+            .execute("config --global --get-all maintenance.repo")
+            .map(|output| output.stdout)
+            .unwrap_or_default();
+        let registered = is_registered(&registered_repos, repo.path());
+        let strategy = executor
+            .execute("config --get maintenance.strategy")
+            .ok()
+            .map(|output| output.stdout.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        let paths = git_paths(repo, MAINTENANCE_OUTPUTS);
+        let hours_since_run = paths
+            .iter()
+            .filter_map(|path| path.metadata().ok()?.modified().ok())
+            .max()
+            .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default().as_secs() / 3600);
+        let running = git_paths(repo, &[MAINTENANCE_LOCK]).iter().any(|path| path.exists());
+
+        Self {
+            supported,
+            registered,
+            strategy,
+            hours_since_run,
+            running,
+        }
+    }
+
+    /// Command that would get maintenance going, if it isn't
+    pub fn suggestion(&self) -> Option<String> {
+        if !self.supported || self.running {
+            return None;
+        }
+        if !self.registered {
+            // Registers the repo and schedules the hourly, daily and weekly tasks
+            return Some("git maintenance start".to_string());
+        }
+        let stale = self.hours_since_run.is_none_or(|hours| hours > STALE_MAINTENANCE_HOURS);
+        stale.then(|| "git maintenance run --schedule=daily".to_string())
+    }
+}
+
+/// Whether `repo` is among the `maintenance.repo` entries
+pub fn is_registered(maintenance_repos: &str, repo: &Path) -> bool {
+    let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let repo = canonical(repo);
+    maintenance_repos
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .any(|line| canonical(Path::new(line)) == repo)
+}
+
+/// Resolve paths inside the git directory, as `rev-parse --git-path` does
+fn git_paths(repo: &Repository, names: &[&str]) -> Vec<PathBuf> {
+    let args: Vec<String> = names.iter().map(|name| format!("--git-path {}", name)).collect();
+    let Ok(output) = repo.executor().execute(&format!("rev-parse {}", args.join(" "))) else {
+        return Vec::new();
+    };
+    output.stdout.lines().map(|line| repo.path().join(line.trim())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_registered() {
+        let temp = TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+
+        let listed = format!("/elsewhere/other\n{}\n", repo.display());
+        assert!(is_registered(&listed, &repo));
+        assert!(!is_registered("/elsewhere/other\n", &repo));
+        assert!(!is_registered("", &repo));
+    }
+
+    #[test]
+    fn test_suggestion() {
+        let mut status = MaintenanceStatus {
+            supported: true,
+            ..Default::default()
+        };
+        assert_eq!(status.suggestion().as_deref(), Some("git maintenance start"));
+
+        status.registered = true;
+        status.hours_since_run = Some(3);
+        assert_eq!(status.suggestion(), None);
+        status.hours_since_run = Some(100);
+        assert_eq!(status.suggestion().as_deref(), Some("git maintenance run --schedule=daily"));
+
+        status.running = true;
+        assert_eq!(status.suggestion(), None);
+        assert_eq!(MaintenanceStatus::default().suggestion(), None);
+    }
+
+    #[test]
+    fn test_detect_unregistered_repo() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let status = MaintenanceStatus::detect(&Repository::new(temp.path()));
+        assert!(!status.registered);
+        assert!(!status.running);
+        assert_eq!(status.hours_since_run, None);
+    }
+}
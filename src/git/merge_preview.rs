@@ -0,0 +1,173 @@
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::refs::ref_candidates;
+use crate::git::repository::Repository;
+
+/// Most incoming commits listed in the preview
+pub const MAX_MERGE_COMMITS: usize = 5;
+
+/// What merging a branch into HEAD would bring in, worked out before the merge runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergePreview {
+    pub branch: String,
+    /// Commits on `branch` that HEAD lacks
+    pub commit_count: usize,
+    /// `log --oneline` of the newest of them
+    pub commits: Vec<String>,
+    /// Last line of `diff --stat`: "3 files changed, 10 insertions(+), 2 deletions(-)"
+    pub stat: Option<String>,
+    /// HEAD is an ancestor of `branch`, so the merge can just move it forward
+    pub fast_forward: bool,
+    /// How the command's flags treat a fast-forward
+    pub ff_mode: FastForwardMode,
+}
+
+/// `--ff` (the default), `--no-ff` or `--ff-only`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastForwardMode {
+    Allow,
+    Never,
+    Only,
+}
+
+/// The single branch a `git merge` command merges in; `None` for other
+/// commands, `--abort`/`--continue` and octopus merges
+pub fn merged_branch(command: &str) -> Option<String> {
+    let words = split_command(command).ok()?;
+    if words.iter().find(|word| *word != "git").map(String::as_str) != Some("merge") {
+        return None;
+    }
+    match ref_candidates(command).as_slice() {
+        [branch] => Some(branch.clone()),
+        _ => None,
+    }
+}
+
+fn ff_mode(command: &str) -> FastForwardMode {
+    let words = split_command(command).unwrap_or_default();
+    // The last of the flags wins, as in git
+    match words.iter().rev().find(|word| matches!(word.as_str(), "--ff" | "--no-ff" | "--ff-only")) {
+        Some(word) if word == "--no-ff" => FastForwardMode::Never,
+        Some(word) if word == "--ff-only" => FastForwardMode::Only,
+        _ => FastForwardMode::Allow,
+    }
+}
+
+impl MergePreview {
+    /// Preview the merge `command` would do; `None` when it isn't a merge of
+    /// one branch or git can't resolve it
+    pub fn load(repo: &Repository, command: &str) -> Option<Self> {
+        let branch = merged_branch(command)?;
+        let executor = repo.executor();
+        let quoted = quote_arg(&branch);
+
+        let commit_count = executor
+            .execute(&format!("rev-list --count HEAD..{} --", quoted))
+            .ok()?
+            .stdout
+            .trim()
+            .parse()
+            .ok()?;
+        let commits = executor
+            .execute(&format!("log --oneline -n {} HEAD..{} --", MAX_MERGE_COMMITS, quoted))
+            .map(|output| output.stdout.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        // Three dots: what changed on the branch since it forked, as the merge sees it
+        let stat = executor
+            .execute(&format!("diff --stat HEAD...{} --", quoted))
+            .ok()
+            .and_then(|output| output.stdout.lines().last().map(|line| line.trim().to_string()))
+            .filter(|line| !line.is_empty());
+        let fast_forward = executor.execute(&format!("merge-base --is-ancestor HEAD {}", quoted)).is_ok();
+
+        Some(Self {
+            branch,
+            commit_count,
+            commits,
+            stat,
+            fast_forward,
+            ff_mode: ff_mode(command),
+        })
+    }
+
+    /// Lines for the preview's impact section
+    pub fn impact_lines(&self) -> Vec<String> {
+        if self.commit_count == 0 {
+            return vec![format!("Already up to date: HEAD has every commit of {}", self.branch)];
+        }
+
+        let outcome = match (self.fast_forward, self.ff_mode) {
+            (true, FastForwardMode::Never) => "merge commit forced by --no-ff",
+            (true, _) => "fast-forward, no merge commit",
+            (false, FastForwardMode::Only) => "will refuse: the branches have diverged and --ff-only is set",
+            (false, _) => "needs a merge commit; conflicts are possible",
+        };
+        let mut lines = vec![format!(
+            "Brings in {} commit{} from {} ({})",
+            self.commit_count,
+            if self.commit_count == 1 { "" } else { "s" },
+            self.branch,
+            outcome
+        )];
+        lines.extend(self.commits.iter().map(|commit| format!("  {}", commit)));
+        if self.commit_count > self.commits.len() {
+            lines.push(format!("  ... and {} more", self.commit_count - self.commits.len()));
+        }
+        if let Some(ref stat) = self.stat {
+            lines.push(stat.clone());
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merged_branch_and_ff_mode() {
+        assert_eq!(merged_branch("git merge feature").as_deref(), Some("feature"));
+        assert_eq!(merged_branch("git merge --no-ff -m 'Merge it' origin/main").as_deref(), Some("origin/main"));
+        assert_eq!(merged_branch("git merge --abort"), None);
+        assert_eq!(merged_branch("git merge a b"), None);
+        assert_eq!(merged_branch("git rebase main"), None);
+
+        assert_eq!(ff_mode("git merge feature"), FastForwardMode::Allow);
+        assert_eq!(ff_mode("git merge --no-ff feature"), FastForwardMode::Never);
+        assert_eq!(ff_mode("git merge --no-ff --ff-only feature"), FastForwardMode::Only);
+    }
+
+    #[test]
+    fn test_load_previews_fast_forward_and_merge() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        git(dir, &["commit", "-q", "--allow-empty", "-m", "Initial commit"]);
+        git(dir, &["checkout", "-q", "-b", "feature"]);
+        for name in ["a.txt", "b.txt"] {
+            std::fs::write(dir.join(name), "text\n").unwrap();
+            git(dir, &["add", name]);
+            git(dir, &["commit", "-q", "-m", &format!("Add {}", name)]);
+        }
+        git(dir, &["checkout", "-q", "main"]);
+        let repo = Repository::new(dir);
+
+        let preview = MergePreview::load(&repo, "git merge feature").unwrap();
+        assert_eq!(preview.commit_count, 2);
+        assert!(preview.fast_forward);
+        let lines = preview.impact_lines();
+        assert_eq!(lines[0], "Brings in 2 commits from feature (fast-forward, no merge commit)");
+        assert!(lines[1].ends_with("Add b.txt"));
+        assert_eq!(lines[3], "2 files changed, 2 insertions(+)");
+
+        git(dir, &["commit", "-q", "--allow-empty", "-m", "Diverge"]);
+        let preview = MergePreview::load(&repo, "git merge --ff-only feature").unwrap();
+        assert!(!preview.fast_forward);
+        assert!(preview.impact_lines()[0].contains("will refuse"));
+
+        let preview = MergePreview::load(&repo, "git merge main").unwrap();
+        assert_eq!(preview.impact_lines(), ["Already up to date: HEAD has every commit of main"]);
+        assert_eq!(MergePreview::load(&repo, "git merge no-such-branch"), None);
+    }
+}
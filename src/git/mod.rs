@@ -1,13 +1,97 @@
+pub mod branch_name;
+pub mod bundle;
+pub mod checkpoint;
+pub mod commit_checks;
+pub mod conflict;
+pub mod demo;
+pub mod doctor;
 pub mod executor;
+pub mod explain;
+pub mod external_tool;
+pub mod fingerprint;
+pub mod filter_branch;
+pub mod force_push;
+pub mod fork_sync;
+pub mod health;
+pub mod hidden;
+pub mod hunks;
+pub mod index_lock;
+pub mod lock;
+pub mod maintenance;
+pub mod merge_preview;
+pub mod next_actions;
 pub mod parser;
+pub mod pull;
+pub mod rebase;
+pub mod refresh;
+pub mod reference;
+pub mod refs;
+pub mod renames;
 pub mod repository;
+pub mod sandbox;
+pub mod shallow;
+pub mod signature;
+pub mod split;
+pub mod stack;
+pub mod stash_wrap;
+pub mod state_cache;
+pub mod state_loader;
+pub mod upstream;
 pub mod version;
 
 // Re-export commonly used types
-pub use executor::{CommandOutput, GitExecutor};
+pub use branch_name::{
+    BranchPolicy, branch_description, create_branch_command, is_new_branch_query, ref_format_problem, slugify,
+};
+pub use bundle::{
+    BUNDLE_REMOTE, BundleRef, backup_command, bundle_path_in, default_bundle_path, describe_heads, expand_home,
+    import_steps,
+    is_bundle_backup_query, is_bundle_import_query, list_heads, parse_heads, verify_bundle,
+};
+pub use checkpoint::{CHECKPOINT_REF_PREFIX, Checkpoint, CheckpointHead, RepoSnapshot};
+pub use commit_checks::{CommitWarning, check_commit};
+pub use conflict::{ConflictFile, ConflictHunk, ConflictSegment, HunkChoice, parse_conflicts};
+pub use demo::{DEMO_CHANGED_FILE, DemoRepo};
+pub use doctor::{DoctorCheck, check_git, render_checks};
+pub use executor::{
+    CommandOutput, DEFAULT_GIT_BINARY, DEFAULT_PARSE_LOCALE, GitExecutor, git_binary, git_command, quote_arg,
+    running_commands, set_git_binary, set_parse_locale, split_command,
+};
+pub use explain::CommandExplanation;
+pub use external_tool::{ToolKind, configured_tool, difftool_command, mergetool_command, tool_args_problem, with_no_prompt};
+pub use fingerprint::StateFingerprint;
+pub use filter_branch::{
+    BACKUP_DIR, FILTER_BRANCH_ADVICE, backup_path, create_backup, filter_branch_warning, filter_repo_equivalent,
+    filter_repo_installed, is_filter_branch,
+};
+pub use fork_sync::{ForkSync, UPSTREAM_REMOTE, fork_remotes, is_fork_sync_query};
+pub use force_push::{ForcePushCheck, is_force_push_query, rebased_branch, upstream_commit, upstream_divergence};
+pub use health::RepoHealth;
+pub use hidden::{HiddenFile, parse_hidden_files, unhide_command};
+pub use hunks::{DiffHunk, discard_hunk, parse_hunks, unstaged_diff_paths, unstaged_hunks};
+pub use index_lock::{IndexLock, LockRemovalError, STALE_LOCK_AGE, git_processes_running, is_index_lock_error};
+pub use lock::{LockOwner, LockStatus, SessionLock};
+pub use maintenance::MaintenanceStatus;
+pub use merge_preview::{FastForwardMode, MAX_MERGE_COMMITS, MergePreview, merged_branch};
+pub use next_actions::{MAX_NEXT_ACTIONS, NextAction, next_actions};
 pub use parser::{
     BranchEntry, CommitEntry, FileStatus, StashEntry, StatusEntry,
     parse_branch_list, parse_log, parse_stash_list, parse_status_porcelain_v2,
 };
-pub use repository::{Repository, RepositoryState, UpstreamInfo};
+pub use pull::{PullStrategy, is_pull_without_strategy, with_pull_strategy};
+pub use rebase::{RebaseAction, RebaseProgress, RebaseStop};
+pub use refresh::{REFRESH_DEBOUNCE, RefreshDeferral, RefreshScheduler};
+pub use reference::{REFERENCE, ReferenceEntry, search_reference};
+pub use refs::{ResolvedRef, resolve_refs};
+pub use renames::{DEFAULT_RENAME_THRESHOLD, Rename, RenameKind, detect_renames, diff_target_args, format_renames};
+pub use repository::{Repository, RepositoryState, StateSection, UpstreamInfo};
+pub use sandbox::{RefChange, Sandbox, SandboxReport, dry_run, sandbox_refusal};
+pub use shallow::{UNSHALLOW_COMMAND, full_history_subcommand, unshallow_warning, with_unshallow};
+pub use signature::{SignatureCheck, SignatureStatus, signature_statuses, verified_revision};
+pub use split::{CommitSplit, SPLIT_BACKUP_REF, is_split_commit_query};
+pub use stack::{BranchStack, MAX_STACK_BRANCHES, StackBranch};
+pub use stash_wrap::{blocked_by_local_changes, stash_wrapped};
+pub use state_cache::{StateCache, StateSnapshot};
+pub use state_loader::StateLoader;
+pub use upstream::{is_upstream_setup_query, push_upstream_command};
 pub use version::GitVersion;
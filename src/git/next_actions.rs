@@ -0,0 +1,251 @@
+use crate::git::parser::FileStatus;
+use crate::git::rebase::RebaseProgress;
+use crate::git::repository::RepositoryState;
+
+/// Most actions suggested at once
+pub const MAX_NEXT_ACTIONS: usize = 3;
+
+/// A likely next step, worked out from the repository state alone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextAction {
+    /// What in the state prompted it ("3 staged files")
+    pub reason: String,
+    /// Short name of the step ("commit")
+    pub label: String,
+    pub command: String,
+}
+
+impl NextAction {
+    fn new(reason: impl Into<String>, label: &str, command: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            label: label.to_string(),
+            command: command.into(),
+        }
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    format!("{} {}{}", count, noun, if count == 1 { "" } else { "s" })
+}
+
+/// Suggested next steps for `state`, most pressing first
+///
+/// No LLM involved: these come from what's staged, how the branch compares
+/// with its upstream and whether a merge or rebase is under way, so they
+/// work offline too.
+pub fn next_actions(state: &RepositoryState) -> Vec<NextAction> {
+    let mut actions = Vec::new();
+    let conflicts = state
+        .unstaged_files
+        .iter()
+        .filter(|file| file.status == FileStatus::Conflicted)
+        .count();
+
+    // An operation under way comes first; it blocks most other commands
+    for (in_progress, operation) in [(state.in_merge, "merge"), (state.in_rebase, "rebase")] {
+        if !in_progress {
+            continue;
+        }
+        if operation == "rebase"
+            && let Some(ref rebase) = state.rebase
+        {
+            actions.extend(rebase_actions(rebase, conflicts));
+            continue;
+        }
+        let reason = format!("{} in progress", operation);
+        if conflicts == 0 {
+            actions.push(NextAction::new(&reason, "continue", format!("git {} --continue", operation)));
+        }
+        actions.push(NextAction::new(&reason, "abort", format!("git {} --abort", operation)));
+    }
+
+    if !state.in_merge && !state.in_rebase {
+        let staged = state.staged_files.len();
+        // Name a lone file in the message, unless quoting it would take escapes
+        let plain = |path: &str| path.chars().all(|c| c.is_ascii_alphanumeric() || "-_./ ".contains(c));
+        if let [file] = state.staged_files.as_slice()
+            && plain(&file.path)
+        {
+            actions.push(NextAction::new(
+                "1 staged file",
+                "commit",
+                format!("git commit -m \"Update {}\"", file.path),
+            ));
+        } else if staged > 0 {
+            actions.push(NextAction::new(
+                plural(staged, "staged file"),
+                "commit",
+                format!("git commit -m \"Update {}\"", plural(staged, "file")),
+            ));
+        }
+
+        let changed = state.unstaged_files.len() + state.untracked_files.len();
+        if staged == 0 && changed > 0 {
+            actions.push(NextAction::new(plural(changed, "changed file"), "stage all", "git add -A"));
+        }
+    }
+
+    if let Some(ref upstream) = state.upstream {
+        if upstream.behind > 0 {
+            actions.push(NextAction::new(
+                format!("{} behind {}", upstream.behind, upstream.remote_branch),
+                "pull --rebase",
+                "git pull --rebase",
+            ));
+        } else if upstream.ahead > 0 {
+            actions.push(NextAction::new(
+                format!("{} ahead of {}", plural(upstream.ahead, "commit"), upstream.remote_branch),
+                "push",
+                "git push",
+            ));
+        }
+    }
+
+    let clean = state.staged_files.is_empty() && state.unstaged_files.is_empty();
+    if clean && !state.in_merge && !state.in_rebase && !state.stashes.is_empty() {
+        actions.push(NextAction::new(plural(state.stashes.len(), "stash"), "pop latest", "git stash pop"));
+    }
+
+    actions.truncate(MAX_NEXT_ACTIONS);
+    actions
+}
+
+/// Ways on from the step a rebase stopped at: continue once nothing is
+/// conflicted, else skip the commit; abort; edit what is left of the todo
+fn rebase_actions(rebase: &RebaseProgress, conflicts: usize) -> Vec<NextAction> {
+    let reason = format!("rebase {}/{}, {}", rebase.step, rebase.total, rebase.stop(conflicts).describe());
+    let mut actions = Vec::new();
+    if conflicts == 0 {
+        actions.push(NextAction::new(&reason, "continue", "git rebase --continue"));
+    } else {
+        actions.push(NextAction::new(&reason, "skip this commit", "git rebase --skip"));
+    }
+    actions.push(NextAction::new(&reason, "abort", "git rebase --abort"));
+    if rebase.interactive && rebase.remaining > 0 {
+        actions.push(NextAction::new(&reason, "edit todo", "git rebase --edit-todo"));
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parser::{StashEntry, StatusEntry};
+    use crate::git::repository::UpstreamInfo;
+
+    fn file(path: &str, status: FileStatus) -> StatusEntry {
+        StatusEntry {
+            status,
+            path: path.to_string(),
+            staged: false,
+            unstaged: true,
+        }
+    }
+
+    fn commands(state: &RepositoryState) -> Vec<String> {
+        next_actions(state).into_iter().map(|action| action.command).collect()
+    }
+
+    #[test]
+    fn test_clean_repository_has_no_actions() {
+        assert!(next_actions(&RepositoryState::default()).is_empty());
+    }
+
+    #[test]
+    fn test_staged_files_and_upstream() {
+        let state = RepositoryState {
+            staged_files: vec![file("a.rs", FileStatus::Modified), file("b.rs", FileStatus::Added)],
+            unstaged_files: vec![file("c.rs", FileStatus::Modified)],
+            upstream: Some(UpstreamInfo {
+                remote_branch: "origin/main".to_string(),
+                ahead: 1,
+                behind: 2,
+            }),
+            ..RepositoryState::default()
+        };
+        let actions = next_actions(&state);
+        assert_eq!(actions[0].reason, "2 staged files");
+        assert_eq!(actions[0].command, "git commit -m \"Update 2 files\"");
+        assert_eq!(actions[1].reason, "2 behind origin/main");
+        assert_eq!(actions[1].command, "git pull --rebase");
+        assert_eq!(actions.len(), 2);
+
+        let state = RepositoryState {
+            staged_files: vec![file("src/lib.rs", FileStatus::Modified)],
+            upstream: Some(UpstreamInfo {
+                remote_branch: "origin/main".to_string(),
+                ahead: 1,
+                behind: 0,
+            }),
+            ..RepositoryState::default()
+        };
+        assert_eq!(commands(&state), ["git commit -m \"Update src/lib.rs\"", "git push"]);
+
+        let state = RepositoryState {
+            staged_files: vec![file("$(touch pwned).rs", FileStatus::Added)],
+            ..RepositoryState::default()
+        };
+        assert_eq!(commands(&state), ["git commit -m \"Update 1 file\""]);
+    }
+
+    #[test]
+    fn test_merge_in_progress() {
+        let mut state = RepositoryState {
+            in_merge: true,
+            staged_files: vec![file("a.rs", FileStatus::Modified)],
+            unstaged_files: vec![file("b.rs", FileStatus::Conflicted)],
+            ..RepositoryState::default()
+        };
+        assert_eq!(commands(&state), ["git merge --abort"]);
+
+        state.unstaged_files.clear();
+        assert_eq!(commands(&state), ["git merge --continue", "git merge --abort"]);
+    }
+
+    #[test]
+    fn test_rebase_in_progress() {
+        let mut state = RepositoryState {
+            in_rebase: true,
+            unstaged_files: vec![file("b.rs", FileStatus::Conflicted)],
+            ..RepositoryState::default()
+        };
+        assert_eq!(commands(&state), ["git rebase --abort"]);
+
+        state.rebase = Some(RebaseProgress {
+            step: 3,
+            total: 7,
+            action: Some("pick".to_string()),
+            subject: Some("Fix the parser".to_string()),
+            branch: Some("feature".to_string()),
+            remaining: 4,
+            interactive: true,
+        });
+        let actions = next_actions(&state);
+        assert_eq!(actions[0].reason, "rebase 3/7, 1 conflicted file");
+        assert_eq!(
+            commands(&state),
+            ["git rebase --skip", "git rebase --abort", "git rebase --edit-todo"]
+        );
+
+        state.unstaged_files.clear();
+        assert_eq!(next_actions(&state)[0].reason, "rebase 3/7, ready to continue");
+        assert_eq!(next_actions(&state)[0].command, "git rebase --continue");
+    }
+
+    #[test]
+    fn test_unstaged_changes_and_stashes() {
+        let state = RepositoryState {
+            untracked_files: vec![file("new.rs", FileStatus::Untracked)],
+            stashes: vec![StashEntry {
+                index: "stash@{0}".to_string(),
+                message: "WIP on main".to_string(),
+            }],
+            ..RepositoryState::default()
+        };
+        let actions = next_actions(&state);
+        assert_eq!(actions[0].reason, "1 changed file");
+        assert_eq!(actions[0].command, "git add -A");
+        assert_eq!(actions[1].command, "git stash pop");
+    }
+}
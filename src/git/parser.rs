@@ -1,4 +1,6 @@
 use crate::error::GitResult;
+use crate::git::signature::SignatureStatus;
+use serde::Serialize;
 
 /// Parse git status --porcelain=v2 output
 pub fn parse_status_porcelain_v2(output: &str) -> GitResult<Vec<StatusEntry>> {
@@ -15,45 +17,50 @@ pub fn parse_status_porcelain_v2(output: &str) -> GitResult<Vec<StatusEntry>> {
         }
 
         match parts[0] {
-            "1" | "2" => {
-                // Tracked entry format: 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
-                if parts.len() >= 9 {
-                    let xy = parts[1];
-                    let path = parts[8..].join(" ");
-
-                    let status = match xy {
-                        "M." => FileStatus::Modified,
-                        ".M" => FileStatus::Modified,
-                        "MM" => FileStatus::Modified,
-                        "A." => FileStatus::Added,
-                        ".A" => FileStatus::Added,
-                        "D." => FileStatus::Deleted,
-                        ".D" => FileStatus::Deleted,
-                        _ => FileStatus::Unknown,
-                    };
-
-                    let staged = !xy.starts_with('.');
-                    let unstaged = xy.chars().nth(1).is_some_and(|c| c != '.');
-
-                    entries.push(StatusEntry {
-                        status,
-                        path,
-                        staged,
-                        unstaged,
-                    });
-                }
+            // Tracked entry format: 1 <XY> <sub> <mH> <mI> <mW> <hH> <hI> <path>
+            "1" | "2" if parts.len() >= 9 => {
+                let xy = parts[1];
+                let path = parts[8..].join(" ");
+
+                let status = match xy {
+                    "M." => FileStatus::Modified,
+                    ".M" => FileStatus::Modified,
+                    "MM" => FileStatus::Modified,
+                    "A." => FileStatus::Added,
+                    ".A" => FileStatus::Added,
+                    "D." => FileStatus::Deleted,
+                    ".D" => FileStatus::Deleted,
+                    _ => FileStatus::Unknown,
+                };
+
+                let staged = !xy.starts_with('.');
+                let unstaged = xy.chars().nth(1).is_some_and(|c| c != '.');
+
+                entries.push(StatusEntry {
+                    status,
+                    path,
+                    staged,
+                    unstaged,
+                });
             }
-            "?" => {
-                // Untracked file: ? <path>
-                if parts.len() >= 2 {
-                    let path = parts[1..].join(" ");
-                    entries.push(StatusEntry {
-                        status: FileStatus::Untracked,
-                        path,
-                        staged: false,
-                        unstaged: false,
-                    });
-                }
+            // Unmerged entry: u <XY> <sub> <m1> <m2> <m3> <mW> <h1> <h2> <h3> <path>
+            "u" if parts.len() >= 11 => {
+                entries.push(StatusEntry {
+                    status: FileStatus::Conflicted,
+                    path: parts[10..].join(" "),
+                    staged: false,
+                    unstaged: true,
+                });
+            }
+            // Untracked file: ? <path>
+            "?" if parts.len() >= 2 => {
+                let path = parts[1..].join(" ");
+                entries.push(StatusEntry {
+                    status: FileStatus::Untracked,
+                    path,
+                    staged: false,
+                    unstaged: false,
+                });
             }
             _ => {}
         }
@@ -76,12 +83,16 @@ pub fn parse_log(output: &str) -> GitResult<Vec<CommitEntry>> {
             commits.push(CommitEntry {
                 hash: parts[0].to_string(),
                 message: parts[1].to_string(),
+                has_note: false,
+                signature: None,
             });
         } else if parts.len() == 1 {
             // Handle case where there's no message
             commits.push(CommitEntry {
                 hash: parts[0].to_string(),
                 message: String::new(),
+                has_note: false,
+                signature: None,
             });
         }
     }
@@ -141,7 +152,7 @@ pub fn parse_stash_list(output: &str) -> GitResult<Vec<StashEntry>> {
 }
 
 /// Represents a file status entry from git status
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct StatusEntry {
     pub status: FileStatus,
     pub path: String,
@@ -149,31 +160,47 @@ pub struct StatusEntry {
     pub unstaged: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl StatusEntry {
+    /// Recorded with `git add -N`: tracked, but none of its content is staged yet
+    pub fn is_intent_to_add(&self) -> bool {
+        self.status == FileStatus::Added && !self.staged && self.unstaged
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FileStatus {
     Modified,
     Added,
     Deleted,
     Untracked,
+    /// Unmerged path with conflicts to resolve
+    Conflicted,
     Unknown,
 }
 
 /// Represents a commit from git log
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct CommitEntry {
     pub hash: String,
     pub message: String,
+    /// A note is attached in `refs/notes/commits`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub has_note: bool,
+    /// Set when signatures are checked (`git.verify_signatures`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SignatureStatus>,
 }
 
 /// Represents a branch from git branch
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct BranchEntry {
     pub name: String,
     pub is_current: bool,
 }
 
 /// Represents a stash entry
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct StashEntry {
     pub index: String,
     pub message: String,
@@ -216,6 +243,27 @@ mod tests {
         assert_eq!(entries[0].status, FileStatus::Untracked);
     }
 
+    #[test]
+    fn test_parse_status_intent_to_add() {
+        let output = "1 .A N... 000000 000000 100644 0000000 0000000 new.rs\n1 A. N... 000000 100644 100644 0000000 abc123 staged.rs";
+        let entries = parse_status_porcelain_v2(output).unwrap();
+
+        assert!(entries[0].is_intent_to_add());
+        assert!(!entries[1].is_intent_to_add());
+    }
+
+    #[test]
+    fn test_parse_status_conflicted() {
+        let output = "u UU N... 100644 100644 100644 100644 aaa111 bbb222 ccc333 src/lib.rs";
+        let entries = parse_status_porcelain_v2(output).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "src/lib.rs");
+        assert_eq!(entries[0].status, FileStatus::Conflicted);
+        assert!(!entries[0].staged);
+        assert!(entries[0].unstaged);
+    }
+
     #[test]
     fn test_parse_log() {
         let output = "abc123\0Initial commit\ndef456\0Add README";
@@ -0,0 +1,111 @@
+use crate::git::executor::{quote_arg, split_command};
+
+/// Flags that already tell `git pull` how to reconcile diverged branches
+const STRATEGY_FLAGS: &[&str] = &["--rebase", "-r", "--no-rebase", "--ff-only", "--ff", "--no-ff"];
+
+/// How `git pull` combines local and upstream commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullStrategy {
+    Merge,
+    Rebase,
+    FastForwardOnly,
+}
+
+impl PullStrategy {
+    pub const ALL: [PullStrategy; 3] = [PullStrategy::Merge, PullStrategy::Rebase, PullStrategy::FastForwardOnly];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PullStrategy::Merge => "Merge",
+            PullStrategy::Rebase => "Rebase",
+            PullStrategy::FastForwardOnly => "Fast-forward only",
+        }
+    }
+
+    /// The `git pull` flag selecting this strategy
+    pub fn flag(self) -> &'static str {
+        match self {
+            PullStrategy::Merge => "--no-rebase",
+            PullStrategy::Rebase => "--rebase",
+            PullStrategy::FastForwardOnly => "--ff-only",
+        }
+    }
+
+    /// What the strategy does to history
+    pub fn effect(self) -> &'static str {
+        match self {
+            PullStrategy::Merge => "Keeps both lines of history and joins them with a merge commit",
+            PullStrategy::Rebase => {
+                "Replays your local commits on top of the upstream: linear history, but they get new ids"
+            }
+            PullStrategy::FastForwardOnly => {
+                "Only moves the branch if it hasn't diverged, so this pull stops without changing anything"
+            }
+        }
+    }
+
+    /// The `git config` command that makes this the repository's default
+    pub fn config_command(self) -> &'static str {
+        match self {
+            PullStrategy::Merge => "git config pull.rebase false",
+            PullStrategy::Rebase => "git config pull.rebase true",
+            PullStrategy::FastForwardOnly => "git config pull.ff only",
+        }
+    }
+}
+
+/// Whether `command` is a single `git pull` that leaves the strategy to git
+pub fn is_pull_without_strategy(command: &str) -> bool {
+    if command.contains("&&") {
+        return false;
+    }
+    let Ok(words) = split_command(command) else {
+        return false;
+    };
+    let mut rest = words.iter().skip_while(|word| *word == "git");
+    if rest.find(|word| !word.starts_with('-')).is_none_or(|sub| sub != "pull") {
+        return false;
+    }
+    !rest.any(|word| STRATEGY_FLAGS.contains(&word.as_str()) || word.starts_with("--rebase="))
+}
+
+/// `command` with the strategy's flag added after `pull`
+pub fn with_pull_strategy(command: &str, strategy: PullStrategy) -> String {
+    let Ok(mut words) = split_command(command) else {
+        return command.to_string();
+    };
+    if let Some(index) = words.iter().position(|word| word == "pull") {
+        words.insert(index + 1, strategy.flag().to_string());
+    }
+    words.iter().map(|word| quote_arg(word)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pull_without_strategy() {
+        assert!(is_pull_without_strategy("git pull"));
+        assert!(is_pull_without_strategy("git pull origin main"));
+        assert!(is_pull_without_strategy("pull --tags"));
+        assert!(!is_pull_without_strategy("git pull --rebase"));
+        assert!(!is_pull_without_strategy("git pull --rebase=merges origin"));
+        assert!(!is_pull_without_strategy("git pull --ff-only"));
+        assert!(!is_pull_without_strategy("git push"));
+        assert!(!is_pull_without_strategy("git fetch && git pull"));
+    }
+
+    #[test]
+    fn test_with_pull_strategy() {
+        assert_eq!(with_pull_strategy("git pull", PullStrategy::Rebase), "git pull --rebase");
+        assert_eq!(
+            with_pull_strategy("git pull origin main", PullStrategy::Merge),
+            "git pull --no-rebase origin main"
+        );
+        assert_eq!(
+            with_pull_strategy("git pull origin 'my branch'", PullStrategy::FastForwardOnly),
+            "git pull --ff-only origin 'my branch'"
+        );
+    }
+}
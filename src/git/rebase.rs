@@ -0,0 +1,293 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// How far a rebase has got, read from the files git keeps under
+/// `rebase-merge` (or `rebase-apply` for the apply backend) in the git dir
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RebaseProgress {
+    /// Step being applied, counting from 1
+    pub step: usize,
+    pub total: usize,
+    /// Todo command of that step ("pick", "edit", ...) when known
+    pub action: Option<String>,
+    /// Subject of the commit being applied
+    pub subject: Option<String>,
+    /// Branch being rebased; `None` when rebasing a detached HEAD
+    pub branch: Option<String>,
+    /// Steps left in the todo list after this one
+    pub remaining: usize,
+    /// Driven by a todo list that `--edit-todo` can change: `-i`, and
+    /// every rebase with the merge backend since git 2.26
+    pub interactive: bool,
+}
+
+/// Why the rebase is waiting for the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseStop {
+    /// The step hit this many conflicted files
+    Conflicts(usize),
+    /// An `edit` step: amend the commit, then continue
+    Edit,
+    /// A `break` in the todo list
+    Break,
+    /// Nothing left to resolve; continue to apply the next step
+    Ready,
+}
+
+impl RebaseStop {
+    pub fn describe(&self) -> String {
+        match self {
+            RebaseStop::Conflicts(1) => "1 conflicted file".to_string(),
+            RebaseStop::Conflicts(count) => format!("{} conflicted files", count),
+            RebaseStop::Edit => "stopped to edit".to_string(),
+            RebaseStop::Break => "paused at a break".to_string(),
+            RebaseStop::Ready => "ready to continue".to_string(),
+        }
+    }
+}
+
+/// What a command does to a rebase in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseAction {
+    Continue,
+    Skip,
+    Abort,
+    /// Opens the remaining todo list in an editor
+    EditTodo,
+}
+
+impl RebaseAction {
+    /// `git rebase --continue` and friends; `None` for anything else
+    pub fn parse(command: &str) -> Option<Self> {
+        let words: Vec<&str> = command.split_whitespace().collect();
+        let words = words.strip_prefix(&["git"]).unwrap_or(&words);
+        match words {
+            ["rebase", "--continue"] => Some(RebaseAction::Continue),
+            ["rebase", "--skip"] => Some(RebaseAction::Skip),
+            ["rebase", "--abort"] => Some(RebaseAction::Abort),
+            ["rebase", "--edit-todo"] => Some(RebaseAction::EditTodo),
+            _ => None,
+        }
+    }
+}
+
+impl RebaseProgress {
+    /// The rebase under way in `git_dir`, if any
+    pub fn read(git_dir: &Path) -> Option<Self> {
+        let merge = git_dir.join("rebase-merge");
+        if merge.is_dir() {
+            return Self::read_merge(&merge);
+        }
+        let apply = git_dir.join("rebase-apply");
+        // rebase-apply is shared with `git am`, which leaves `applying` behind
+        if apply.is_dir() && !apply.join("applying").exists() {
+            return Self::read_apply(&apply);
+        }
+        None
+    }
+
+    fn read_merge(dir: &Path) -> Option<Self> {
+        let current = read(dir, "done")
+            .and_then(|done| done.lines().rev().find_map(parse_todo_line));
+        let remaining = read(dir, "git-rebase-todo")
+            .map_or(0, |todo| todo.lines().filter_map(parse_todo_line).count());
+        let (action, subject) = current.map_or((None, None), |(action, subject)| (Some(action), subject));
+        Some(Self {
+            step: read_number(dir, "msgnum")?,
+            total: read_number(dir, "end")?,
+            action,
+            subject,
+            branch: read_branch(dir),
+            remaining,
+            interactive: dir.join("interactive").exists(),
+        })
+    }
+
+    fn read_apply(dir: &Path) -> Option<Self> {
+        let step = read_number(dir, "next")?;
+        let total = read_number(dir, "last")?;
+        let subject = read(dir, "info").and_then(|info| {
+            info.lines()
+                .find_map(|line| line.strip_prefix("Subject: "))
+                .map(str::to_string)
+        });
+        Some(Self {
+            step,
+            total,
+            action: None,
+            subject,
+            branch: read_branch(dir),
+            remaining: total.saturating_sub(step),
+            interactive: false,
+        })
+    }
+
+    /// "applying 3/7: Fix the parser"
+    pub fn summary(&self) -> String {
+        match self.subject {
+            Some(ref subject) => format!("applying {}/{}: {}", self.step, self.total, subject),
+            None => format!("applying {}/{}", self.step, self.total),
+        }
+    }
+
+    /// Why the rebase stopped at this step, given the conflicted files now
+    pub fn stop(&self, conflicts: usize) -> RebaseStop {
+        if conflicts > 0 {
+            return RebaseStop::Conflicts(conflicts);
+        }
+        match self.action.as_deref() {
+            Some("edit") => RebaseStop::Edit,
+            Some("break") => RebaseStop::Break,
+            _ => RebaseStop::Ready,
+        }
+    }
+}
+
+fn read(dir: &Path, file: &str) -> Option<String> {
+    fs::read_to_string(dir.join(file)).ok()
+}
+
+fn read_number(dir: &Path, file: &str) -> Option<usize> {
+    read(dir, file)?.trim().parse().ok()
+}
+
+/// `head-name` holds `refs/heads/<branch>`, or `detached HEAD`
+fn read_branch(dir: &Path) -> Option<String> {
+    read(dir, "head-name")?
+        .trim()
+        .strip_prefix("refs/heads/")
+        .map(str::to_string)
+}
+
+/// Todo commands with a commit and its subject after the command
+const COMMIT_ACTIONS: [&str; 6] = ["pick", "reword", "edit", "squash", "fixup", "drop"];
+
+/// `(command, what it applies)` for a line of the todo list, the short
+/// forms spelled out; `None` for blank lines and comments
+fn parse_todo_line(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (action, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let action = match action {
+        "p" => "pick",
+        "r" => "reword",
+        "e" => "edit",
+        "s" => "squash",
+        "f" => "fixup",
+        "x" => "exec",
+        "b" => "break",
+        "d" => "drop",
+        "l" => "label",
+        "t" => "reset",
+        "m" => "merge",
+        "u" => "update-ref",
+        other => other,
+    };
+
+    let subject = if COMMIT_ACTIONS.contains(&action) {
+        // `fixup -C <commit> <subject>`: skip options, then the commit
+        let mut words = rest.split(' ').skip_while(|word| word.starts_with('-'));
+        words.next();
+        words.collect::<Vec<_>>().join(" ")
+    } else if let Some((_, subject)) = rest.split_once("# ") {
+        // `merge -C <commit> <label> # <subject>`
+        subject.to_string()
+    } else {
+        rest.to_string()
+    };
+    let subject = subject.trim();
+    Some((action.to_string(), (!subject.is_empty()).then(|| subject.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &Path, files: &[(&str, &str)]) {
+        fs::create_dir_all(dir).unwrap();
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_todo_line() {
+        assert_eq!(
+            parse_todo_line("pick 1a2b3c4 Fix the parser"),
+            Some(("pick".to_string(), Some("Fix the parser".to_string())))
+        );
+        assert_eq!(
+            parse_todo_line("f -C 1a2b3c4 Tidy up"),
+            Some(("fixup".to_string(), Some("Tidy up".to_string())))
+        );
+        assert_eq!(
+            parse_todo_line("exec cargo test"),
+            Some(("exec".to_string(), Some("cargo test".to_string())))
+        );
+        assert_eq!(
+            parse_todo_line("merge -C 1a2b3c4 topic # Merge topic"),
+            Some(("merge".to_string(), Some("Merge topic".to_string())))
+        );
+        assert_eq!(parse_todo_line("b"), Some(("break".to_string(), None)));
+        assert_eq!(parse_todo_line("# Rebase 1a2b..5d6e onto 5d6e"), None);
+        assert_eq!(parse_todo_line("   "), None);
+    }
+
+    #[test]
+    fn test_reads_interactive_rebase() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(RebaseProgress::read(temp.path()), None);
+
+        let dir = temp.path().join("rebase-merge");
+        write(
+            &dir,
+            &[
+                ("msgnum", "3\n"),
+                ("end", "7\n"),
+                ("head-name", "refs/heads/feature\n"),
+                ("interactive", ""),
+                ("done", "pick aaa First\npick bbb Second\nedit ccc Third step\n"),
+                ("git-rebase-todo", "pick ddd Fourth\n\n# Commands:\n# p, pick <commit>\nexec make\n"),
+            ],
+        );
+        let progress = RebaseProgress::read(temp.path()).unwrap();
+        assert_eq!(progress.summary(), "applying 3/7: Third step");
+        assert_eq!(progress.branch.as_deref(), Some("feature"));
+        assert_eq!(progress.remaining, 2);
+        assert!(progress.interactive);
+        assert_eq!(progress.stop(0), RebaseStop::Edit);
+        assert_eq!(progress.stop(2), RebaseStop::Conflicts(2));
+        assert_eq!(progress.stop(2).describe(), "2 conflicted files");
+    }
+
+    #[test]
+    fn test_reads_apply_rebase_but_not_am() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("rebase-apply");
+        write(
+            &dir,
+            &[("next", "2"), ("last", "4"), ("head-name", "detached HEAD"), ("info", "Author: A\nSubject: Add docs\n")],
+        );
+        let progress = RebaseProgress::read(temp.path()).unwrap();
+        assert_eq!(progress.summary(), "applying 2/4: Add docs");
+        assert_eq!(progress.branch, None);
+        assert_eq!(progress.remaining, 2);
+        assert_eq!(progress.stop(0), RebaseStop::Ready);
+
+        write(&dir, &[("applying", "")]);
+        assert_eq!(RebaseProgress::read(temp.path()), None);
+    }
+
+    #[test]
+    fn test_rebase_action() {
+        assert_eq!(RebaseAction::parse("git rebase --continue"), Some(RebaseAction::Continue));
+        assert_eq!(RebaseAction::parse("rebase --edit-todo"), Some(RebaseAction::EditTodo));
+        assert_eq!(RebaseAction::parse("git rebase --skip"), Some(RebaseAction::Skip));
+        assert_eq!(RebaseAction::parse("git rebase main"), None);
+        assert_eq!(RebaseAction::parse("git merge --abort"), None);
+    }
+}
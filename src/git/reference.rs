@@ -0,0 +1,411 @@
+use crate::git::explain::{subcommand_flags, subcommand_summary};
+
+/// Offline reference page for one git subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReferenceEntry {
+    pub name: &'static str,
+    pub synopsis: &'static str,
+    /// Example commands with what they do
+    pub examples: &'static [(&'static str, &'static str)],
+    /// What Gitalky checks or refuses for this subcommand, if anything
+    pub caution: Option<&'static str>,
+}
+
+impl ReferenceEntry {
+    pub fn summary(&self) -> &'static str {
+        subcommand_summary(self.name).unwrap_or_default()
+    }
+
+    /// Common flags, from the same table the offline explanations use
+    pub fn flags(&self) -> Vec<(&'static str, &'static str)> {
+        subcommand_flags(self.name)
+    }
+
+    /// Whether every word of `query` appears in the page
+    fn matches(&self, words: &[String]) -> bool {
+        let mut text = format!("{} {} {}", self.name, self.synopsis, self.summary());
+        for (flag, description) in self.flags() {
+            text.push_str(&format!(" {} {}", flag, description));
+        }
+        for (example, description) in self.examples {
+            text.push_str(&format!(" {} {}", example, description));
+        }
+        if let Some(caution) = self.caution {
+            text.push(' ');
+            text.push_str(caution);
+        }
+        let text = text.to_lowercase();
+        words.iter().all(|word| text.contains(word.as_str()))
+    }
+}
+
+/// Reference pages for every allowlisted subcommand, roughly by how often
+/// they are needed
+pub const REFERENCE: &[ReferenceEntry] = &[
+    ReferenceEntry {
+        name: "status",
+        synopsis: "git status [-s] [-b] [-- <path>...]",
+        examples: &[
+            ("git status", "What is staged, modified and untracked"),
+            ("git status -sb", "Short format with the branch and its upstream"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "add",
+        synopsis: "git add [-A | -u | -p] [--] <path>...",
+        examples: &[
+            ("git add src/main.rs", "Stage one file"),
+            ("git add -A", "Stage every change, new and deleted files included"),
+            ("git add -p", "Pick the hunks to stage one by one"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "commit",
+        synopsis: "git commit [-a] [--amend] [-m <message>]",
+        examples: &[
+            ("git commit -m 'Fix login'", "Commit the staged changes"),
+            ("git commit --amend --no-edit", "Add the staged changes to the last commit"),
+        ],
+        caution: Some("Amending a pushed commit rewrites history; pushing it again needs a force push."),
+    },
+    ReferenceEntry {
+        name: "diff",
+        synopsis: "git diff [--cached] [<commit> [<commit>]] [-- <path>...]",
+        examples: &[
+            ("git diff", "Unstaged changes"),
+            ("git diff --cached", "Staged changes, as they will be committed"),
+            ("git diff main...feature", "What feature changed since it left main"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "difftool",
+        synopsis: "git difftool --no-prompt [--cached] [<commit>] [-- <path>...]",
+        examples: &[
+            ("git difftool --no-prompt", "Unstaged changes, file by file, in diff.tool"),
+            ("git difftool --no-prompt --cached -- src/app.rs", "One file's staged changes"),
+        ],
+        caution: Some("Opens the tool set by diff.tool (or merge.tool); --extcmd is refused."),
+    },
+    ReferenceEntry {
+        name: "log",
+        synopsis: "git log [--oneline] [--graph] [-n <count>] [<revision range>] [-- <path>]",
+        examples: &[
+            ("git log --oneline -n 10", "The last ten commits, one per line"),
+            ("git log --graph --oneline --all", "Every branch as a graph"),
+            ("git log -p -- src/lib.rs", "Changes to one file over time"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "show",
+        synopsis: "git show [<commit>] [-- <path>]",
+        examples: &[
+            ("git show", "The last commit and its changes"),
+            ("git show HEAD~2:src/lib.rs", "A file as it was two commits ago"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "verify-commit",
+        synopsis: "git verify-commit [-v] <commit>...",
+        examples: &[
+            ("git verify-commit HEAD", "Is the last commit signed, and by whom?"),
+            ("git log --show-signature -3", "Signatures of the last three commits"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "branch",
+        synopsis: "git branch [-a | -r | -vv] | <name> [<start>] | -d <name> | -m <old> <new>",
+        examples: &[
+            ("git branch -vv", "Local branches with their upstream and last commit"),
+            ("git branch feature main", "Create feature from main without switching"),
+            ("git branch -d feature", "Delete a merged branch"),
+        ],
+        caution: Some("-D deletes unmerged branches; their commits stay reachable only from the reflog."),
+    },
+    ReferenceEntry {
+        name: "switch",
+        synopsis: "git switch [-c <new-branch>] <branch>",
+        examples: &[
+            ("git switch main", "Switch to main"),
+            ("git switch -c feature", "Create feature here and switch to it"),
+            ("git switch -", "Back to the previous branch"),
+        ],
+        caution: Some("--discard-changes throws away local edits and needs confirmation."),
+    },
+    ReferenceEntry {
+        name: "checkout",
+        synopsis: "git checkout [-b <new-branch>] <branch> | git checkout [<commit>] -- <path>",
+        examples: &[
+            ("git checkout -b feature", "Create feature here and switch to it"),
+            ("git checkout main -- config.toml", "Take one file from main"),
+        ],
+        caution: Some("Checking out paths overwrites their local edits; -f needs confirmation."),
+    },
+    ReferenceEntry {
+        name: "restore",
+        synopsis: "git restore [--staged] [--worktree] [--source <commit>] <path>...",
+        examples: &[
+            ("git restore --staged a.txt", "Unstage a file, keeping the edits"),
+            ("git restore a.txt", "Discard unstaged edits to a file"),
+        ],
+        caution: Some("Restoring the working tree discards edits for good and needs confirmation."),
+    },
+    ReferenceEntry {
+        name: "reset",
+        synopsis: "git reset [--soft | --mixed | --hard] [<commit>]",
+        examples: &[
+            ("git reset HEAD~1", "Undo the last commit, keeping its changes unstaged"),
+            ("git reset --soft HEAD~1", "Undo the last commit, keeping its changes staged"),
+        ],
+        caution: Some("--hard discards all uncommitted changes and needs confirmation."),
+    },
+    ReferenceEntry {
+        name: "revert",
+        synopsis: "git revert [--no-commit] <commit>...",
+        examples: &[("git revert abc1234", "New commit that undoes abc1234; safe on shared branches")],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "stash",
+        synopsis: "git stash [push [-u] [-m <message>] | list | show | pop | apply | drop] [<stash>]",
+        examples: &[
+            ("git stash -u -m 'wip'", "Set aside all changes, untracked files included"),
+            ("git stash list", "Saved stashes"),
+            ("git stash pop", "Reapply the latest stash and drop it"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "merge",
+        synopsis: "git merge [--no-ff | --ff-only | --squash] <branch> | --abort | --continue",
+        examples: &[
+            ("git merge feature", "Bring feature into the current branch"),
+            ("git merge --abort", "Give up a conflicted merge"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "mergetool",
+        synopsis: "git mergetool --no-prompt [--tool=<tool>] [-- <path>...]",
+        examples: &[
+            ("git mergetool --no-prompt", "Resolve each conflicted file in merge.tool"),
+            ("git mergetool --no-prompt -- src/app.rs", "Just this file"),
+        ],
+        caution: Some("Files the tool saves are staged as resolved; the originals are kept as *.orig."),
+    },
+    ReferenceEntry {
+        name: "rebase",
+        synopsis: "git rebase [-i] [--onto <newbase>] [<upstream> [<branch>]] | --continue | --abort",
+        examples: &[
+            ("git rebase main", "Replay the current branch on top of main"),
+            ("git rebase -i HEAD~3", "Reorder, squash or reword the last three commits"),
+            ("git rebase --onto main old-base feature", "Move only feature's own commits onto main"),
+        ],
+        caution: Some("Rewrites commits: needs confirmation, and pushed branches then need a force push (Ctrl+F)."),
+    },
+    ReferenceEntry {
+        name: "cherry-pick",
+        synopsis: "git cherry-pick [-x] [-n] <commit>... | --continue | --abort",
+        examples: &[("git cherry-pick -x abc1234", "Copy one commit here, noting where it came from")],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "fetch",
+        synopsis: "git fetch [--all] [--prune] [<remote> [<refspec>]]",
+        examples: &[
+            ("git fetch", "Download new commits without touching your branches"),
+            ("git fetch --all --prune", "Every remote, dropping deleted branches"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "pull",
+        synopsis: "git pull [--rebase | --ff-only] [<remote> [<branch>]]",
+        examples: &[
+            ("git pull --ff-only", "Update only when no merge is needed"),
+            ("git pull --rebase", "Replay your commits on top of the fetched ones"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "push",
+        synopsis: "git push [-u] [--force-with-lease] [<remote> [<refspec>]]",
+        examples: &[
+            ("git push -u origin feature", "Publish feature and track it"),
+            ("git push --force-with-lease", "Replace the remote branch unless it moved"),
+            ("git push origin --delete feature", "Delete a remote branch"),
+        ],
+        caution: Some("--force and --force-with-lease overwrite remote history and need confirmation."),
+    },
+    ReferenceEntry {
+        name: "remote",
+        synopsis: "git remote [-v] | add <name> <url> | remove <name> | rename <old> <new>",
+        examples: &[
+            ("git remote -v", "Remotes and their URLs"),
+            ("git remote add upstream <url>", "Track another repository"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "tag",
+        synopsis: "git tag [-a <name> -m <message>] [<commit>] | -d <name> | -l [<pattern>]",
+        examples: &[
+            ("git tag -a v1.2.0 -m 'Release 1.2.0'", "Annotated tag on HEAD"),
+            ("git push origin v1.2.0", "Publish the tag"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "reflog",
+        synopsis: "git reflog [show] [<ref>]",
+        examples: &[
+            ("git reflog", "Every recent position of HEAD"),
+            ("git reset --hard HEAD@{1}", "Go back to where HEAD was one step ago"),
+        ],
+        caution: Some("reflog expire and delete drop recovery points and need confirmation."),
+    },
+    ReferenceEntry {
+        name: "blame",
+        synopsis: "git blame [-L <start>,<end>] <file>",
+        examples: &[("git blame -L 10,20 src/lib.rs", "Who last changed lines 10 to 20")],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "describe",
+        synopsis: "git describe [--tags] [<commit>]",
+        examples: &[("git describe --tags", "Nearest tag plus commits since, e.g. v1.2.0-3-gabc1234")],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "ls-files",
+        synopsis: "git ls-files [-v] [-m] [--others] [-- <path>...]",
+        examples: &[
+            ("git ls-files", "Every tracked file"),
+            ("git ls-files -v", "Tracked files tagged h (assume-unchanged) or S (skip-worktree) when hidden"),
+        ],
+        caution: None,
+    },
+    ReferenceEntry {
+        name: "update-index",
+        synopsis: "git update-index [--[no-]assume-unchanged | --[no-]skip-worktree] [--] <path>...",
+        examples: &[
+            ("git update-index --no-assume-unchanged config.toml", "Show changes to config.toml again"),
+            ("git update-index --skip-worktree .env", "Keep local edits to .env out of git status"),
+        ],
+        caution: Some("Only the assume-unchanged and skip-worktree options are allowed."),
+    },
+    ReferenceEntry {
+        name: "notes",
+        synopsis: "git notes [list | show <commit> | add -m <text> <commit> | remove <commit>]",
+        examples: &[
+            ("git notes add -m 'Deployed' abc1234", "Annotate a commit without rewriting it"),
+            ("git log --notes", "History with notes shown"),
+        ],
+        caution: Some("Notes need -m text here; edit and merge, which open an editor, are refused."),
+    },
+    ReferenceEntry {
+        name: "bundle",
+        synopsis: "git bundle [create <file> <revs> | verify <file> | list-heads <file>]",
+        examples: &[
+            ("git bundle create ../app.bundle --all", "Every branch and tag in one file, to copy or keep"),
+            ("git fetch ../app.bundle 'refs/heads/*:refs/remotes/bundle/*'", "Bring its branches in as bundle/*"),
+        ],
+        caution: Some("New bundles go inside the repository's parent directory and never overwrite a file."),
+    },
+    ReferenceEntry {
+        name: "clean",
+        synopsis: "git clean [-n | -f] [-d] [-x] [-- <path>...]",
+        examples: &[
+            ("git clean -n -d", "List untracked files and directories that would go"),
+            ("git clean -f -d", "Delete them"),
+        ],
+        caution: Some("Deleted files can't be recovered; -f needs confirmation after a dry run."),
+    },
+    ReferenceEntry {
+        name: "config",
+        synopsis: "git config [--local] [--list | --get <key> | <key> <value>]",
+        examples: &[
+            ("git config user.email me@example.com", "Author email for this repository"),
+            ("git config --list", "Every setting in effect"),
+        ],
+        caution: Some("Only repository settings can be changed; --global and --system are refused."),
+    },
+    ReferenceEntry {
+        name: "clone",
+        synopsis: "git clone <url> [<directory>]",
+        examples: &[("git clone https://example.com/repo.git", "Copy a repository into ./repo")],
+        caution: Some("The destination path is validated."),
+    },
+    ReferenceEntry {
+        name: "init",
+        synopsis: "git init [-b <branch>] [<directory>]",
+        examples: &[("git init -b main", "Start a repository here with main as the first branch")],
+        caution: Some("The destination path is validated."),
+    },
+    ReferenceEntry {
+        name: "gc",
+        synopsis: "git gc [--aggressive] [--prune=<date>]",
+        examples: &[("git gc", "Pack loose objects and remove old unreachable ones")],
+        caution: Some("--prune=now deletes unreachable commits immediately and needs confirmation."),
+    },
+    ReferenceEntry {
+        name: "maintenance",
+        synopsis: "git maintenance [run [--task <task>] | start | stop | register | unregister]",
+        examples: &[
+            ("git maintenance start", "Schedule hourly, daily and weekly optimization"),
+            ("git maintenance run --task gc", "Run one task now"),
+        ],
+        caution: Some("--config-file and unknown options are refused."),
+    },
+    ReferenceEntry {
+        name: "filter-branch",
+        synopsis: "git filter-branch [--tree-filter <command>] [<rev-list options>]",
+        examples: &[("git filter-branch --tree-filter 'rm -f secrets.txt' HEAD", "Rewrite every commit")],
+        caution: Some("Rewrites all history and needs confirmation; git filter-repo is the modern tool."),
+    },
+];
+
+/// Pages matching every word of `query`, all of them for an empty query
+pub fn search_reference(query: &str) -> Vec<&'static ReferenceEntry> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    // Pages whose name starts with a one-word query come first
+    let mut entries: Vec<&ReferenceEntry> = REFERENCE.iter().filter(|entry| entry.matches(&words)).collect();
+    entries.sort_by_key(|entry| !(words.len() == 1 && entry.name.starts_with(words[0].as_str())));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::ALLOWED_GIT_SUBCOMMANDS;
+
+    #[test]
+    fn test_every_allowed_subcommand_has_a_page() {
+        for subcommand in ALLOWED_GIT_SUBCOMMANDS {
+            let entry = REFERENCE.iter().find(|entry| entry.name == *subcommand);
+            let entry = entry.unwrap_or_else(|| panic!("no reference page for {}", subcommand));
+            assert!(!entry.summary().is_empty(), "no summary for {}", subcommand);
+            assert!(entry.synopsis.starts_with(&format!("git {}", subcommand)));
+            assert!(!entry.examples.is_empty());
+        }
+        assert_eq!(REFERENCE.len(), ALLOWED_GIT_SUBCOMMANDS.len());
+    }
+
+    #[test]
+    fn test_search_reference() {
+        assert_eq!(search_reference("").len(), REFERENCE.len());
+        assert_eq!(search_reference("stash")[0].name, "stash");
+
+        // Flags and descriptions are searched too, every word must match
+        let names: Vec<&str> = search_reference("untracked").iter().map(|entry| entry.name).collect();
+        assert!(names.contains(&"clean") && names.contains(&"stash"));
+        let names: Vec<&str> = search_reference("UNDO last commit").iter().map(|entry| entry.name).collect();
+        assert_eq!(names, vec!["reset"]);
+        assert!(search_reference("no such words").is_empty());
+    }
+}
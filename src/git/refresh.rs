@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+/// Shortest gap between two refreshes; triggers arriving sooner are
+/// coalesced into the next one
+pub const REFRESH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Why a refresh that was due has been held back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshDeferral {
+    /// A git command started by gitalky is still running
+    GitRunning,
+    /// Another git process holds `index.lock`
+    IndexLocked,
+}
+
+/// Decides when a due repository refresh may actually spawn git
+///
+/// A refresh runs five git subprocesses, so it waits for running commands
+/// and foreign `index.lock` holders instead of contending with them.
+#[derive(Debug, Clone)]
+pub struct RefreshScheduler {
+    debounce: Duration,
+    last_run: Option<Instant>,
+    deferred: Option<RefreshDeferral>,
+}
+
+impl RefreshScheduler {
+    pub fn new(debounce: Duration) -> Self {
+        RefreshScheduler {
+            debounce,
+            last_run: None,
+            deferred: None,
+        }
+    }
+
+    /// Whether a due refresh should run at `now`; callers keep the refresh
+    /// pending when this returns false and ask again on the next tick
+    pub fn should_run(&mut self, now: Instant, git_running: bool, index_locked: bool) -> bool {
+        self.deferred = if git_running {
+            Some(RefreshDeferral::GitRunning)
+        } else if index_locked {
+            Some(RefreshDeferral::IndexLocked)
+        } else {
+            None
+        };
+        if let Some(reason) = self.deferred {
+            tracing::trace!(?reason, "refresh deferred");
+            return false;
+        }
+        self.last_run
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.debounce)
+    }
+
+    /// Record that a refresh ran at `now`
+    pub fn mark_run(&mut self, now: Instant) {
+        self.last_run = Some(now);
+    }
+
+    /// Why the last due refresh was held back, if it was
+    pub fn deferred(&self) -> Option<RefreshDeferral> {
+        self.deferred
+    }
+}
+
+impl Default for RefreshScheduler {
+    fn default() -> Self {
+        Self::new(REFRESH_DEBOUNCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce() {
+        let start = Instant::now();
+        let mut scheduler = RefreshScheduler::new(Duration::from_millis(500));
+        assert!(scheduler.should_run(start, false, false));
+        scheduler.mark_run(start);
+
+        assert!(!scheduler.should_run(start + Duration::from_millis(100), false, false));
+        assert!(!scheduler.should_run(start + Duration::from_millis(499), false, false));
+        assert!(scheduler.should_run(start + Duration::from_millis(500), false, false));
+        assert_eq!(scheduler.deferred(), None);
+    }
+
+    #[test]
+    fn test_defers_while_git_busy() {
+        let start = Instant::now();
+        let mut scheduler = RefreshScheduler::default();
+
+        assert!(!scheduler.should_run(start, true, true));
+        assert_eq!(scheduler.deferred(), Some(RefreshDeferral::GitRunning));
+        assert!(!scheduler.should_run(start, false, true));
+        assert_eq!(scheduler.deferred(), Some(RefreshDeferral::IndexLocked));
+
+        assert!(scheduler.should_run(start, false, false));
+        assert_eq!(scheduler.deferred(), None);
+    }
+}
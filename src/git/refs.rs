@@ -0,0 +1,144 @@
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::repository::Repository;
+use std::fmt;
+
+/// Most refs resolved for one command
+pub const MAX_RESOLVED_REFS: usize = 4;
+
+/// Subcommands whose arguments name the commits they move to or act on
+const REF_SUBCOMMANDS: &[&str] = &[
+    "reset", "checkout", "switch", "restore", "rebase", "merge", "cherry-pick", "revert",
+    "branch", "tag",
+];
+
+/// Flags whose value is text rather than a commit
+const VALUE_FLAGS: &[&str] = &[
+    "-m", "--message", "-F", "--file", "-b", "-B", "-c", "-C", "--create", "--force-create",
+    "-X", "--strategy-option", "-s", "--strategy",
+];
+
+/// Flags whose `=` value is a commit
+const REF_VALUE_FLAGS: &[&str] = &["--source", "--onto"];
+
+/// A ref named in a command and the commit it points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedRef {
+    pub name: String,
+    pub short_sha: String,
+    pub subject: String,
+}
+
+impl fmt::Display for ResolvedRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {} '{}'", self.name, self.short_sha, self.subject)
+    }
+}
+
+/// Resolve the refs a command mentions (`HEAD~3`, branches, tags) to commits
+///
+/// Arguments that aren't commits, such as paths or new branch names, are
+/// skipped, as is everything when git can't be run.
+pub fn resolve_refs(repo: &Repository, command: &str) -> Vec<ResolvedRef> {
+    ref_candidates(command)
+        .into_iter()
+        .filter_map(|name| resolve(repo, &name))
+        .take(MAX_RESOLVED_REFS)
+        .collect()
+}
+
+fn resolve(repo: &Repository, name: &str) -> Option<ResolvedRef> {
+    // The trailing `--` makes git treat the name as a revision only
+    let output = repo
+        .executor()
+        .execute(&format!("log -1 --no-walk --format=%h%x09%s {} --", quote_arg(name)))
+        .ok()?;
+    let (short_sha, subject) = output.stdout.trim_end().split_once('\t')?;
+    Some(ResolvedRef {
+        name: name.to_string(),
+        short_sha: short_sha.to_string(),
+        subject: subject.to_string(),
+    })
+}
+
+/// Arguments of a command that may name commits, in order and without repeats
+pub fn ref_candidates(command: &str) -> Vec<String> {
+    let Ok(words) = split_command(command) else {
+        return Vec::new();
+    };
+    let mut words = words.into_iter().skip_while(|w| w == "git");
+    if !words.next().is_some_and(|sub| REF_SUBCOMMANDS.contains(&sub.as_str())) {
+        return Vec::new();
+    }
+
+    let mut candidates: Vec<String> = Vec::new();
+    let mut skip_value = false;
+    for word in words {
+        if skip_value {
+            skip_value = false;
+            continue;
+        }
+        if word == "--" {
+            break;
+        }
+
+        let names: Vec<&str> = if let Some((flag, value)) = word.split_once('=')
+            && word.starts_with("--")
+        {
+            if REF_VALUE_FLAGS.contains(&flag) { vec![value] } else { Vec::new() }
+        } else if word.starts_with('-') {
+            skip_value = VALUE_FLAGS.contains(&word.as_str());
+            Vec::new()
+        } else {
+            // Both ends of a range such as `main..feature`
+            word.split("...").flat_map(|part| part.split("..")).collect()
+        };
+
+        for name in names {
+            if !name.is_empty() && !candidates.iter().any(|c| c == name) {
+                candidates.push(name.to_string());
+            }
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ref_candidates() {
+        assert_eq!(ref_candidates("git reset --hard HEAD~3"), vec!["HEAD~3"]);
+        assert_eq!(ref_candidates("git rebase --onto=main old feature"), vec!["main", "old", "feature"]);
+        assert_eq!(ref_candidates("git checkout -b topic origin/main"), vec!["origin/main"]);
+        assert_eq!(ref_candidates("git restore --source=HEAD~1 -- src/lib.rs"), vec!["HEAD~1"]);
+        assert_eq!(ref_candidates("git cherry-pick main..feature"), vec!["main", "feature"]);
+        assert_eq!(ref_candidates("git merge -m 'main' dev"), vec!["dev"]);
+        assert!(ref_candidates("git status").is_empty());
+        assert!(ref_candidates("git commit -m main").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_refs() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        git(dir, &["commit", "-q", "--allow-empty", "-m", "Initial commit"]);
+        git(dir, &["commit", "-q", "--allow-empty", "-m", "Fix login"]);
+        git(dir, &["tag", "v1"]);
+
+        let repo = Repository::new(dir);
+        let resolved = resolve_refs(&repo, "git reset --hard HEAD~1 -- notes.txt");
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].subject, "Initial commit");
+        assert!(resolved[0].to_string().starts_with("HEAD~1 = "));
+
+        // New branch names and paths don't resolve
+        let resolved = resolve_refs(&repo, "git branch topic v1");
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].name, "v1");
+        assert!(resolved[0].to_string().ends_with(" 'Fix login'"));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::git::executor::{quote_arg, split_command};
+use crate::git::repository::Repository;
+use std::fmt;
+
+/// Git's own default similarity index for `-M` and `-C`
+pub const DEFAULT_RENAME_THRESHOLD: u8 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameKind {
+    Renamed,
+    Copied,
+}
+
+/// A path git matched to another by content similarity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub kind: RenameKind,
+    pub from: String,
+    pub to: String,
+    /// Similarity index in percent
+    pub similarity: u8,
+}
+
+impl fmt::Display for Rename {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            RenameKind::Renamed => "renamed",
+            RenameKind::Copied => "copied",
+        };
+        write!(f, "{}: {} → {} ({}%)", kind, self.from, self.to, self.similarity)
+    }
+}
+
+/// Renames and copies in `git diff <target>`, detected with `-M -C` at
+/// `threshold` percent similarity
+///
+/// `target` is e.g. `--cached`, `HEAD` or the revisions of a `git diff`
+/// command. Failures (no commits yet, bad revision) yield no renames.
+pub fn detect_renames(repo: &Repository, target: &str, threshold: u8) -> Vec<Rename> {
+    let threshold = threshold.clamp(1, 100);
+    repo.executor()
+        .execute(&format!(
+            "diff {} -M{t}% -C{t}% --name-status -z --no-color --no-ext-diff",
+            target,
+            t = threshold
+        ))
+        .map(|output| parse_name_status(&output.stdout))
+        .unwrap_or_default()
+}
+
+/// Parse `git diff --name-status -z`, keeping only renames and copies
+pub fn parse_name_status(output: &str) -> Vec<Rename> {
+    let mut renames = Vec::new();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let Some(status) = fields.next() {
+        let kind = match status.chars().next() {
+            Some('R') => RenameKind::Renamed,
+            Some('C') => RenameKind::Copied,
+            // Every other status has a single path
+            _ => {
+                fields.next();
+                continue;
+            }
+        };
+        let (Some(from), Some(to)) = (fields.next(), fields.next()) else {
+            break;
+        };
+        renames.push(Rename {
+            kind,
+            from: from.to_string(),
+            to: to.to_string(),
+            similarity: status[1..].parse().unwrap_or(0),
+        });
+    }
+    renames
+}
+
+/// The revisions, `--cached` and paths of a `git diff` command, to compare
+/// the same things with rename detection; `None` for other commands
+pub fn diff_target_args(command: &str) -> Option<String> {
+    let words = split_command(command).ok()?;
+    let mut words = words.iter().map(String::as_str).skip_while(|w| *w == "git");
+    if words.next()? != "diff" {
+        return None;
+    }
+
+    let mut args = Vec::new();
+    let mut paths = false;
+    for word in words {
+        if paths || word == "--" {
+            paths = true;
+        } else if word.starts_with('-') && !matches!(word, "--cached" | "--staged") {
+            // Output options such as --stat or -p don't change what is compared
+            continue;
+        }
+        args.push(quote_arg(word));
+    }
+    Some(args.join(" "))
+}
+
+/// Renames for LLM context, one per line, at most `limit`
+pub fn format_renames(renames: &[Rename], limit: usize) -> String {
+    let mut text: String = renames.iter().take(limit).map(|rename| format!("{}\n", rename)).collect();
+    if renames.len() > limit {
+        text.push_str(&format!("... and {} more\n", renames.len() - limit));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\0a.txt\0R092\0old.rs\0new.rs\0C075\0lib.rs\0lib_copy.rs\0A\0b.txt\0";
+        let renames = parse_name_status(output);
+        assert_eq!(renames.len(), 2);
+        assert_eq!(renames[0].to_string(), "renamed: old.rs → new.rs (92%)");
+        assert_eq!(renames[1].to_string(), "copied: lib.rs → lib_copy.rs (75%)");
+    }
+
+    #[test]
+    fn test_diff_target_args() {
+        assert_eq!(diff_target_args("git diff").as_deref(), Some(""));
+        assert_eq!(diff_target_args("git diff --stat --cached").as_deref(), Some("--cached"));
+        assert_eq!(
+            diff_target_args("git diff -M main feature -- 'src/my file.rs'").as_deref(),
+            Some("main feature -- 'src/my file.rs'")
+        );
+        assert_eq!(diff_target_args("git commit -m diff"), None);
+    }
+
+    #[test]
+    fn test_detect_renames_with_threshold() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        let content: String = (0..10).map(|i| format!("line {}\n", i)).collect();
+        std::fs::write(temp.path().join("old.txt"), &content).unwrap();
+        git(temp.path(), &["add", "old.txt"]);
+        git(temp.path(), &["commit", "-q", "-m", "old"]);
+
+        // 8 of 10 lines kept
+        git(temp.path(), &["mv", "old.txt", "new.txt"]);
+        let edited = content.replace("line 3\n", "changed\n").replace("line 7\n", "changed\n");
+        std::fs::write(temp.path().join("new.txt"), edited).unwrap();
+        git(temp.path(), &["add", "new.txt"]);
+
+        let repo = Repository::new(temp.path());
+        let renames = detect_renames(&repo, "--cached", DEFAULT_RENAME_THRESHOLD);
+        assert_eq!(renames.len(), 1);
+        assert_eq!(renames[0].kind, RenameKind::Renamed);
+        assert_eq!((renames[0].from.as_str(), renames[0].to.as_str()), ("old.txt", "new.txt"));
+        assert!(renames[0].similarity < 100);
+
+        assert!(detect_renames(&repo, "--cached", 100).is_empty());
+    }
+
+    #[test]
+    fn test_format_renames() {
+        let rename = |n: usize| Rename {
+            kind: RenameKind::Renamed,
+            from: format!("a{}", n),
+            to: format!("b{}", n),
+            similarity: 100,
+        };
+        let renames: Vec<Rename> = (0..3).map(rename).collect();
+        assert_eq!(
+            format_renames(&renames, 2),
+            "renamed: a0 → b0 (100%)\nrenamed: a1 → b1 (100%)\n... and 1 more\n"
+        );
+    }
+}
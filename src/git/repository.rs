@@ -1,8 +1,14 @@
 use crate::error::{GitError, GitResult};
-use crate::git::executor::GitExecutor;
+use crate::git::executor::{GitExecutor, quote_arg};
+use crate::git::hidden::{HiddenFile, parse_hidden_files};
 use crate::git::parser::{self, CommitEntry, StashEntry, StatusEntry};
+use crate::git::rebase::RebaseProgress;
+use crate::git::signature::signature_statuses;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::thread;
 
 /// Represents a git repository and provides access to its state
 #[derive(Debug)]
@@ -10,6 +16,10 @@ use std::path::{Path, PathBuf};
 pub struct Repository {
     path: PathBuf,
     executor: GitExecutor,
+    /// Remote branch to count ahead/behind against besides the upstream
+    second_remote: Option<String>,
+    /// Check the signatures of recent commits
+    verify_signatures: bool,
 }
 
 impl Repository {
@@ -44,7 +54,26 @@ impl Repository {
         let path = path.as_ref().to_path_buf();
         let executor = GitExecutor::new(&path);
 
-        Self { path, executor }
+        Self {
+            path,
+            executor,
+            second_remote: None,
+            verify_signatures: false,
+        }
+    }
+
+    /// Also count ahead/behind against `remote_branch`, such as `upstream/main`
+    /// in a fork whose `origin` is your own copy
+    pub fn with_second_remote(mut self, remote_branch: Option<String>) -> Self {
+        self.second_remote = remote_branch.filter(|name| !name.trim().is_empty());
+        self
+    }
+
+    /// Also check the signature of each recent commit, which runs gpg for
+    /// the signed ones
+    pub fn with_signature_check(mut self, verify: bool) -> Self {
+        self.verify_signatures = verify;
+        self
     }
 
     /// Get the repository path
@@ -52,51 +81,121 @@ impl Repository {
         &self.path
     }
 
+    /// Whether the repository is still there: deleting or moving the
+    /// directory, or removing `.git`, makes every git command fail
+    pub fn is_available(&self) -> bool {
+        self.path.join(".git").exists()
+    }
+
+    /// The git directory; linked worktrees and submodules have a `.git`
+    /// file pointing elsewhere
+    pub fn git_dir(&self) -> PathBuf {
+        let dot_git = self.path.join(".git");
+        if let Ok(contents) = std::fs::read_to_string(&dot_git)
+            && let Some(dir) = contents.strip_prefix("gitdir:")
+        {
+            return self.path.join(dir.trim());
+        }
+        dot_git
+    }
+
+    /// Whether another git process holds `index.lock`; commands that write
+    /// the index fail until it is released
+    pub fn index_locked(&self) -> bool {
+        self.git_dir().join("index.lock").exists()
+    }
+
     /// Query the current repository state
+    #[tracing::instrument(name = "refresh", level = "debug", skip(self), fields(repo = %self.path.display()), err(Display, level = "debug"))]
     pub fn state(&self) -> GitResult<RepositoryState> {
-        let current_branch = self.current_branch()?;
-        let upstream = self.upstream_info(&current_branch)?;
-        let status_entries = self.status()?;
-        let commits = self.recent_commits(10)?;
-        let stashes = self.stash_list()?;
-
-        // Categorize status entries
-        let mut staged = Vec::new();
-        let mut unstaged = Vec::new();
-        let mut untracked = Vec::new();
-
-        for entry in status_entries {
-            if entry.staged {
-                staged.push(entry.clone());
+        // With one core the threads would only add overhead
+        if thread::available_parallelism().map_or(true, |cores| cores.get() < 2) {
+            let mut state = RepositoryState::default();
+            for section in StateSection::ALL {
+                self.load_section(&mut state, section)?;
+            }
+            return Ok(state);
+        }
+        self.state_concurrently()
+    }
+
+    /// Load every section on its own thread and assemble the result
+    ///
+    /// Sections run independent git commands and fill disjoint fields;
+    /// status and log dominate on slow filesystems.
+    pub fn state_concurrently(&self) -> GitResult<RepositoryState> {
+        let parts = thread::scope(|scope| {
+            let handles: Vec<_> = StateSection::ALL
+                .into_iter()
+                .map(|section| {
+                    scope.spawn(move || {
+                        let mut part = RepositoryState::default();
+                        self.load_section(&mut part, section).map(|_| (section, part))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("state section loader panicked"))
+                .collect::<GitResult<Vec<_>>>()
+        })?;
+
+        let mut state = RepositoryState::default();
+        for (section, part) in parts {
+            state.take_section(part, section);
+        }
+        Ok(state)
+    }
+
+    /// Fill in one section of `state`, leaving the others as they are
+    pub fn load_section(&self, state: &mut RepositoryState, section: StateSection) -> GitResult<()> {
+        match section {
+            StateSection::Head => {
+                state.current_branch = self.current_branch()?;
+                state.upstream = self.upstream_info(&state.current_branch)?;
+                state.second_upstream = self.second_upstream_info(state.upstream.as_ref());
+
+                // Detect special states
+                state.in_merge = self.path.join(".git/MERGE_HEAD").exists();
+                state.in_rebase = self.path.join(".git/rebase-merge").exists()
+                    || self.path.join(".git/rebase-apply").exists();
+                state.rebase = RebaseProgress::read(&self.git_dir());
+                state.user_name = self.config_value("user.name");
+                state.user_email = self.config_value("user.email");
             }
-            if entry.unstaged {
-                unstaged.push(entry.clone());
+            StateSection::Files => {
+                // Categorize status entries
+                let mut staged = Vec::new();
+                let mut unstaged = Vec::new();
+                let mut untracked = Vec::new();
+
+                for entry in self.status()? {
+                    if entry.staged {
+                        staged.push(entry.clone());
+                    }
+                    if entry.unstaged {
+                        unstaged.push(entry.clone());
+                    }
+                    if entry.status == parser::FileStatus::Untracked {
+                        untracked.push(entry);
+                    }
+                }
+                state.staged_files = staged;
+                state.unstaged_files = unstaged;
+                state.untracked_files = untracked;
+                state.hidden_files = self.hidden_files();
             }
-            if entry.status == parser::FileStatus::Untracked {
-                untracked.push(entry);
+            StateSection::History => {
+                state.recent_commits = self.recent_commits(10)?;
+                state.stashes = self.stash_list()?;
+                state.shallow_depth = self.shallow_depth();
             }
         }
-
-        // Detect special states
-        let in_merge = self.path.join(".git/MERGE_HEAD").exists();
-        let in_rebase = self.path.join(".git/rebase-merge").exists()
-            || self.path.join(".git/rebase-apply").exists();
-
-        Ok(RepositoryState {
-            current_branch,
-            upstream,
-            staged_files: staged,
-            unstaged_files: unstaged,
-            untracked_files: untracked,
-            recent_commits: commits,
-            stashes,
-            in_merge,
-            in_rebase,
-        })
+        Ok(())
     }
 
     /// Get the current branch name
-    fn current_branch(&self) -> GitResult<Option<String>> {
+    pub fn current_branch(&self) -> GitResult<Option<String>> {
         match self.executor.execute("branch --show-current") {
             Ok(output) => {
                 let branch = output.stdout.trim();
@@ -120,8 +219,8 @@ impl Repository {
 
         // Get upstream branch name
         let cmd = format!(
-            "for-each-ref --format=%(upstream:short) refs/heads/{}",
-            branch_name
+            "for-each-ref --format=%(upstream:short) {}",
+            quote_arg(&format!("refs/heads/{}", branch_name))
         );
         let upstream_branch = match self.executor.execute(&cmd) {
             Ok(output) => {
@@ -135,40 +234,102 @@ impl Repository {
         };
 
         // Get ahead/behind counts
-        let cmd = format!("rev-list --left-right --count {}...{}", branch_name, upstream_branch);
-        match self.executor.execute(&cmd) {
-            Ok(output) => {
-                let parts: Vec<&str> = output.stdout.split_whitespace().collect();
-                if parts.len() == 2 {
-                    let ahead = parts[0].parse::<usize>().unwrap_or(0);
-                    let behind = parts[1].parse::<usize>().unwrap_or(0);
-
-                    Ok(Some(UpstreamInfo {
-                        remote_branch: upstream_branch,
-                        ahead,
-                        behind,
-                    }))
-                } else {
-                    Ok(None)
-                }
-            }
-            Err(_) => Ok(None),
+        Ok(self
+            .ahead_behind(branch_name, &upstream_branch)
+            .map(|(ahead, behind)| UpstreamInfo {
+                remote_branch: upstream_branch,
+                ahead,
+                behind,
+            }))
+    }
+
+    /// Ahead/behind of HEAD against the configured second remote branch,
+    /// unless that is the upstream already or doesn't exist
+    fn second_upstream_info(&self, upstream: Option<&UpstreamInfo>) -> Option<UpstreamInfo> {
+        let remote_branch = self.second_remote.as_ref()?;
+        if upstream.is_some_and(|upstream| &upstream.remote_branch == remote_branch) {
+            return None;
+        }
+        let (ahead, behind) = self.ahead_behind("HEAD", remote_branch)?;
+        Some(UpstreamInfo {
+            remote_branch: remote_branch.clone(),
+            ahead,
+            behind,
+        })
+    }
+
+    /// Commits only on `local` and only on `remote`
+    fn ahead_behind(&self, local: &str, remote: &str) -> Option<(usize, usize)> {
+        let cmd = format!(
+            "rev-list --left-right --count {} --",
+            quote_arg(&format!("{}...{}", local, remote))
+        );
+        let output = self.executor.execute(&cmd).ok()?;
+        match output.stdout.split_whitespace().collect::<Vec<_>>()[..] {
+            [ahead, behind] => Some((ahead.parse().unwrap_or(0), behind.parse().unwrap_or(0))),
+            _ => None,
         }
     }
 
     /// Get status entries
-    fn status(&self) -> GitResult<Vec<StatusEntry>> {
+    pub fn status(&self) -> GitResult<Vec<StatusEntry>> {
         let output = self.executor.execute("status --porcelain=v2")?;
         parser::parse_status_porcelain_v2(&output.stdout)
     }
 
-    /// Get recent commits
+    /// Tracked files marked assume-unchanged or skip-worktree
+    ///
+    /// Skip-worktree files missing from disk are left out: sparse checkout
+    /// marks everything outside the cone that way.
+    fn hidden_files(&self) -> Vec<HiddenFile> {
+        let Ok(output) = self.executor.execute("ls-files -v -z") else {
+            return Vec::new();
+        };
+        parse_hidden_files(&output.stdout)
+            .into_iter()
+            .filter(|file| file.assume_unchanged || self.path.join(&file.path).exists())
+            .collect()
+    }
+
+    /// Get recent commits, marking the ones with notes
     fn recent_commits(&self, count: usize) -> GitResult<Vec<CommitEntry>> {
         let cmd = format!("log -n {} --format=%H%x00%s", count);
-        match self.executor.execute(&cmd) {
-            Ok(output) => parser::parse_log(&output.stdout),
-            Err(_) => Ok(Vec::new()), // Empty repo has no commits
+        let mut commits = match self.executor.execute(&cmd) {
+            Ok(output) => parser::parse_log(&output.stdout)?,
+            Err(_) => return Ok(Vec::new()), // Empty repo has no commits
+        };
+
+        // "<note blob> <annotated object>" per note; none without a notes ref
+        if let Ok(output) = self.executor.execute("notes list") {
+            let annotated: HashSet<&str> = output.stdout.lines().filter_map(|line| line.split_whitespace().nth(1)).collect();
+            for commit in &mut commits {
+                commit.has_note = annotated.contains(commit.hash.as_str());
+            }
+        }
+        if self.verify_signatures
+            && let Ok(statuses) = signature_statuses(&self.executor, count)
+        {
+            for commit in &mut commits {
+                commit.signature = statuses.get(&commit.hash).copied();
+            }
         }
+        Ok(commits)
+    }
+
+    /// Commits reachable from HEAD in a shallow clone, about the depth it was
+    /// cloned with; `None` for a full clone
+    fn shallow_depth(&self) -> Option<usize> {
+        if !self.git_dir().join("shallow").exists() {
+            return None;
+        }
+        let output = self.executor.execute("rev-list --count HEAD").ok();
+        Some(output.and_then(|output| output.stdout.trim().parse().ok()).unwrap_or(0))
+    }
+
+    /// The note attached to `commit`, if any
+    pub fn note(&self, commit: &str) -> Option<String> {
+        let output = self.executor.execute(&format!("notes show {}", quote_arg(commit))).ok()?;
+        Some(output.stdout.trim_end().to_string()).filter(|note| !note.is_empty())
     }
 
     /// Get stash list
@@ -179,6 +340,74 @@ impl Repository {
         }
     }
 
+    /// Effective value of a config key; `None` when unset
+    fn config_value(&self, key: &str) -> Option<String> {
+        let output = self.executor.execute(&format!("config --get {}", key)).ok()?;
+        let value = output.stdout.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
+    /// Whether `pull.rebase` or `pull.ff` tells `git pull` how to handle
+    /// diverged branches
+    pub fn pull_strategy_configured(&self) -> bool {
+        self.config_value("pull.rebase").is_some() || self.config_value("pull.ff").is_some()
+    }
+
+    /// Whether `pull.rebase` asks to rebase rather than merge
+    pub fn pull_rebases(&self) -> bool {
+        self.config_value("pull.rebase").is_some_and(|value| value != "false")
+    }
+
+    /// Commits on HEAD that `remote_branch` doesn't have; `None` when it can't be resolved
+    pub fn commits_ahead_of(&self, remote_branch: &str) -> Option<usize> {
+        self.ahead_behind("HEAD", remote_branch).map(|(ahead, _)| ahead)
+    }
+
+    /// Names of the configured remotes
+    pub fn remotes(&self) -> GitResult<Vec<String>> {
+        let output = self.executor.execute("remote")?;
+        Ok(output
+            .stdout
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Local branch names, most recently committed to first
+    pub fn local_branches(&self) -> GitResult<Vec<String>> {
+        let output = self
+            .executor
+            .execute("for-each-ref --sort=-committerdate --format=%(refname:short) refs/heads")?;
+        Ok(output.stdout.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect())
+    }
+
+    /// Fetch and push URLs of all remotes, without repeats
+    pub fn remote_urls(&self) -> GitResult<Vec<String>> {
+        let output = self.executor.execute("remote -v")?;
+        let mut urls: Vec<String> = Vec::new();
+        // "origin<TAB>url (fetch)"
+        for url in output.stdout.lines().filter_map(|line| line.split_whitespace().nth(1)) {
+            if !urls.iter().any(|u| u == url) {
+                urls.push(url.to_string());
+            }
+        }
+        Ok(urls)
+    }
+
+    /// Remote new branches are published to: `origin` if present, else the only remote
+    pub fn default_remote(&self) -> GitResult<Option<String>> {
+        let remotes = self.remotes()?;
+        if remotes.iter().any(|r| r == "origin") {
+            return Ok(Some("origin".to_string()));
+        }
+        Ok(match remotes.as_slice() {
+            [only] => Some(only.clone()),
+            _ => None,
+        })
+    }
+
     /// Get the git executor for this repository
     pub fn executor(&self) -> &GitExecutor {
         &self.executor
@@ -186,18 +415,35 @@ impl Repository {
 }
 
 /// Upstream tracking information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UpstreamInfo {
     pub remote_branch: String,
     pub ahead: usize,
     pub behind: usize,
 }
 
+/// Parts of [`RepositoryState`] that can be loaded separately, cheapest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateSection {
+    /// Branch, upstream, merge/rebase and identity
+    Head,
+    /// Staged, unstaged and untracked files; slowest on large repositories
+    Files,
+    /// Recent commits and stashes
+    History,
+}
+
+impl StateSection {
+    pub const ALL: [StateSection; 3] = [StateSection::Head, StateSection::Files, StateSection::History];
+}
+
 /// Represents the current state of a git repository
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
 pub struct RepositoryState {
     pub current_branch: Option<String>,
     pub upstream: Option<UpstreamInfo>,
+    /// HEAD against `git.second_remote` (`upstream/main` in a fork), when set
+    pub second_upstream: Option<UpstreamInfo>,
     pub staged_files: Vec<StatusEntry>,
     pub unstaged_files: Vec<StatusEntry>,
     pub untracked_files: Vec<StatusEntry>,
@@ -205,9 +451,45 @@ pub struct RepositoryState {
     pub stashes: Vec<StashEntry>,
     pub in_merge: bool,
     pub in_rebase: bool,
+    /// Step, subject and todo of the rebase under way
+    pub rebase: Option<RebaseProgress>,
+    /// Effective `user.name` and `user.email`, local config first
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    /// Set in a shallow clone: the commits it has, roughly its depth
+    pub shallow_depth: Option<usize>,
+    /// Files whose changes git status won't show
+    pub hidden_files: Vec<HiddenFile>,
 }
 
 impl RepositoryState {
+    /// Move the fields `section` loads from `part` into this state
+    fn take_section(&mut self, part: RepositoryState, section: StateSection) {
+        match section {
+            StateSection::Head => {
+                self.current_branch = part.current_branch;
+                self.upstream = part.upstream;
+                self.second_upstream = part.second_upstream;
+                self.in_merge = part.in_merge;
+                self.in_rebase = part.in_rebase;
+                self.rebase = part.rebase;
+                self.user_name = part.user_name;
+                self.user_email = part.user_email;
+            }
+            StateSection::Files => {
+                self.staged_files = part.staged_files;
+                self.unstaged_files = part.unstaged_files;
+                self.untracked_files = part.untracked_files;
+                self.hidden_files = part.hidden_files;
+            }
+            StateSection::History => {
+                self.recent_commits = part.recent_commits;
+                self.stashes = part.stashes;
+                self.shallow_depth = part.shallow_depth;
+            }
+        }
+    }
+
     /// Check if the repository is in a clean state (no changes)
     pub fn is_clean(&self) -> bool {
         self.staged_files.is_empty()
@@ -219,13 +501,25 @@ impl RepositoryState {
     pub fn is_detached(&self) -> bool {
         self.current_branch.is_none()
     }
+
+    /// Check if the current branch has no upstream tracking branch
+    pub fn needs_upstream(&self) -> bool {
+        self.current_branch.is_some() && self.upstream.is_none()
+    }
+
+    /// Check if the current branch and its upstream both have commits the
+    /// other lacks, as after rebasing a pushed branch
+    pub fn has_diverged(&self) -> bool {
+        !self.in_rebase && self.upstream.as_ref().is_some_and(|u| u.ahead > 0 && u.behind > 0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::executor::git_command;
     use std::fs;
-    use std::process::Command;
+    use crate::test_support::git;
     use tempfile::TempDir;
 
     fn create_test_repo() -> (TempDir, PathBuf) {
@@ -233,20 +527,20 @@ mod tests {
         let repo_path = temp_dir.path().to_path_buf();
 
         // Initialize git repo
-        Command::new("git")
+        git_command()
             .args(["init"])
             .current_dir(&repo_path)
             .output()
             .unwrap();
 
         // Configure git
-        Command::new("git")
+        git_command()
             .args(["config", "user.name", "Test User"])
             .current_dir(&repo_path)
             .output()
             .unwrap();
 
-        Command::new("git")
+        git_command()
             .args(["config", "user.email", "test@example.com"])
             .current_dir(&repo_path)
             .output()
@@ -255,6 +549,22 @@ mod tests {
         (temp_dir, repo_path)
     }
 
+    #[test]
+    fn test_index_locked() {
+        let (_temp, repo_path) = create_test_repo();
+        let repo = Repository::new(&repo_path);
+        assert!(!repo.index_locked());
+
+        fs::write(repo_path.join(".git/index.lock"), "").unwrap();
+        assert!(repo.index_locked());
+
+        // A linked worktree's lock lives in the directory its .git file names
+        let worktree = repo_path.join("wt");
+        fs::create_dir(&worktree).unwrap();
+        fs::write(worktree.join(".git"), "gitdir: ../.git\n").unwrap();
+        assert!(Repository::new(&worktree).index_locked());
+    }
+
     #[test]
     fn test_discover_repo() {
         let (_temp, repo_path) = create_test_repo();
@@ -307,6 +617,8 @@ mod tests {
         assert!(!state.is_detached());
         assert_eq!(state.recent_commits.len(), 0);
         assert_eq!(state.stashes.len(), 0);
+        assert_eq!(state.user_name.as_deref(), Some("Test User"));
+        assert_eq!(state.user_email.as_deref(), Some("test@example.com"));
     }
 
     #[test]
@@ -333,14 +645,153 @@ mod tests {
         let test_file = repo_path.join("staged.txt");
         fs::write(&test_file, "staged content").unwrap();
 
-        Command::new("git")
-            .args(["add", "staged.txt"])
-            .current_dir(&repo_path)
-            .output()
-            .unwrap();
+        git(&repo_path, &["add", "staged.txt"]);
 
         let state = repo.state().unwrap();
         assert!(!state.is_clean());
         assert_eq!(state.staged_files.len(), 1);
     }
+
+    #[test]
+    fn test_parallel_state_matches_sections_loaded_in_turn() {
+        let (_temp, repo_path) = create_test_repo();
+        let repo = Repository::new(&repo_path);
+
+        fs::write(repo_path.join("a.txt"), "a").unwrap();
+        git(&repo_path, &["add", "a.txt"]);
+        git(&repo_path, &["commit", "-m", "first"]);
+        fs::write(repo_path.join("a.txt"), "stashed").unwrap();
+        git(&repo_path, &["stash"]);
+        fs::write(repo_path.join("a.txt"), "changed").unwrap();
+        fs::write(repo_path.join("b.txt"), "b").unwrap();
+        git(&repo_path, &["add", "b.txt"]);
+        fs::write(repo_path.join("c.txt"), "c").unwrap();
+
+        let mut serial = RepositoryState::default();
+        for section in StateSection::ALL {
+            repo.load_section(&mut serial, section).unwrap();
+        }
+        let state = repo.state_concurrently().unwrap();
+        assert_eq!(state, serial);
+        assert_eq!(repo.state().unwrap(), serial);
+        assert_eq!(state.recent_commits.len(), 1);
+        assert_eq!(state.stashes.len(), 1);
+        assert_eq!(
+            (state.staged_files.len(), state.unstaged_files.len(), state.untracked_files.len()),
+            (1, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_commit_notes() {
+        let (_temp, repo_path) = create_test_repo();
+        let repo = Repository::new(&repo_path);
+        for name in ["a.txt", "b.txt"] {
+            fs::write(repo_path.join(name), name).unwrap();
+            git(&repo_path, &["add", name]);
+            git(&repo_path, &["commit", "-q", "-m", name]);
+        }
+        let state = repo.state().unwrap();
+        assert!(state.recent_commits.iter().all(|commit| !commit.has_note));
+        assert_eq!(repo.note("HEAD"), None);
+
+        git(&repo_path, &["notes", "add", "-m", "Deployed to staging", "HEAD~1"]);
+        let state = repo.state().unwrap();
+        let flags: Vec<bool> = state.recent_commits.iter().map(|commit| commit.has_note).collect();
+        assert_eq!(flags, [false, true]);
+        assert_eq!(repo.note("HEAD~1").as_deref(), Some("Deployed to staging"));
+    }
+
+    #[test]
+    fn test_hidden_files() {
+        let (_temp, repo_path) = create_test_repo();
+        for name in ["local.toml", ".env", "gone.txt"] {
+            fs::write(repo_path.join(name), name).unwrap();
+        }
+        git(&repo_path, &["add", "."]);
+        git(&repo_path, &["commit", "-q", "-m", "files"]);
+        git(&repo_path, &["update-index", "--assume-unchanged", "local.toml"]);
+        git(&repo_path, &["update-index", "--skip-worktree", ".env", "gone.txt"]);
+        fs::remove_file(repo_path.join("gone.txt")).unwrap();
+        fs::write(repo_path.join("local.toml"), "changed").unwrap();
+
+        let state = Repository::new(&repo_path).state().unwrap();
+        let hidden: Vec<(&str, &str)> = state.hidden_files.iter().map(|f| (f.path.as_str(), f.label())).collect();
+        assert_eq!(hidden, [(".env", "skip-worktree"), ("local.toml", "assume-unchanged")]);
+        assert!(state.is_clean());
+    }
+
+    #[test]
+    fn test_shallow_depth() {
+        let (_temp, repo_path) = create_test_repo();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(repo_path.join(name), name).unwrap();
+            git(&repo_path, &["add", name]);
+            git(&repo_path, &["commit", "-q", "-m", name]);
+        }
+        assert_eq!(Repository::new(&repo_path).state().unwrap().shallow_depth, None);
+
+        let clone_dir = TempDir::new().unwrap();
+        let url = format!("file://{}", repo_path.display());
+        git(clone_dir.path(), &["clone", "-q", "--depth", "2", &url, "clone"]);
+        let state = Repository::new(clone_dir.path().join("clone")).state().unwrap();
+        assert_eq!(state.shallow_depth, Some(2));
+    }
+
+    #[test]
+    fn test_second_upstream() {
+        let (_temp, repo_path) = create_test_repo();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(repo_path.join(name), name).unwrap();
+            git(&repo_path, &["add", name]);
+            git(&repo_path, &["commit", "-q", "-m", name]);
+        }
+        // The forked-from project is one commit behind
+        git(&repo_path, &["update-ref", "refs/remotes/upstream/main", "HEAD~1"]);
+
+        let repo = Repository::new(&repo_path);
+        assert_eq!(repo.state().unwrap().second_upstream, None);
+
+        let repo = repo.with_second_remote(Some("upstream/main".to_string()));
+        let second = repo.state().unwrap().second_upstream.unwrap();
+        assert_eq!((second.remote_branch.as_str(), second.ahead, second.behind), ("upstream/main", 1, 0));
+
+        let repo = repo.with_second_remote(Some("upstream/gone".to_string()));
+        assert_eq!(repo.state().unwrap().second_upstream, None);
+    }
+
+    #[test]
+    fn test_default_remote() {
+        let (_temp, repo_path) = create_test_repo();
+        let repo = Repository::new(&repo_path);
+        assert_eq!(repo.default_remote().unwrap(), None);
+
+        let add_remote = |name: &str| {
+            git(&repo_path, &["remote", "add", name, "https://example.com/repo.git"]);
+        };
+
+        add_remote("upstream");
+        assert_eq!(repo.default_remote().unwrap(), Some("upstream".to_string()));
+
+        add_remote("fork");
+        assert_eq!(repo.default_remote().unwrap(), None);
+
+        add_remote("origin");
+        assert_eq!(repo.default_remote().unwrap(), Some("origin".to_string()));
+    }
+
+    #[test]
+    fn test_needs_upstream() {
+        let (_temp, repo_path) = create_test_repo();
+        let state = Repository::new(&repo_path).state().unwrap();
+
+        // A fresh branch has never been pushed
+        assert!(state.needs_upstream());
+
+        let detached = RepositoryState {
+            current_branch: None,
+            ..state
+        };
+        assert!(!detached.needs_upstream());
+    }
 }
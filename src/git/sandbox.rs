@@ -0,0 +1,377 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::{GitExecutor, quote_arg, split_command};
+use crate::git::repository::Repository;
+use crate::security::DangerousOp;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Subcommands that talk to a remote, which a sandbox doesn't have
+const NETWORK_SUBCOMMANDS: &[&str] = &["push", "pull", "fetch", "clone", "ls-remote", "submodule"];
+
+/// Why `command` can't be tried in a sandbox, or `None` when it can
+pub fn sandbox_refusal(command: &str, danger: Option<&DangerousOp>) -> Option<&'static str> {
+    let words = split_command(command).ok()?;
+    let subcommand = words.iter().map(String::as_str).find(|w| *w != "git")?;
+    if NETWORK_SUBCOMMANDS.contains(&subcommand) || danger == Some(&DangerousOp::ForcePush) {
+        return Some("it talks to a remote, which the sandbox copy has no access to");
+    }
+    match danger {
+        Some(
+            DangerousOp::StashDrop
+            | DangerousOp::StashClear
+            | DangerousOp::ReflogExpire
+            | DangerousOp::Gc
+            | DangerousOp::GcPruneNow,
+        ) => Some("it works on reflogs and stored objects, which the sandbox copy doesn't have its own of"),
+        Some(DangerousOp::RemoveIndexLock) => Some("it isn't a git command"),
+        _ => None,
+    }
+}
+
+/// HEAD, branch and tag positions and working tree status at one moment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxSnapshot {
+    /// Branch and tag names mapped to abbreviated commits
+    pub refs: BTreeMap<String, String>,
+    /// The checked-out branch, or the abbreviated commit when detached
+    pub head: String,
+    /// Full commit id of HEAD; `None` before the first commit
+    head_commit: Option<String>,
+    /// `git status --porcelain` lines
+    pub status: Vec<String>,
+}
+
+impl SandboxSnapshot {
+    fn capture(executor: &GitExecutor) -> GitResult<Self> {
+        let refs = executor
+            .execute("for-each-ref '--format=%(refname:short) %(objectname:short)' refs/heads refs/tags")?
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .map(|(name, commit)| (name.to_string(), commit.to_string()))
+            .collect();
+        let head_commit = executor
+            .execute("rev-parse HEAD")
+            .ok()
+            .map(|output| output.stdout.trim().to_string());
+        let head = match executor.execute("symbolic-ref --short -q HEAD") {
+            Ok(output) => output.stdout.trim().to_string(),
+            Err(_) => head_commit
+                .as_deref()
+                .map(|commit| commit.chars().take(7).collect())
+                .unwrap_or_default(),
+        };
+        let status = executor
+            .execute("status --porcelain --untracked-files=all")?
+            .stdout
+            .lines()
+            .map(str::to_string)
+            .collect();
+        Ok(Self {
+            refs,
+            head,
+            head_commit,
+            status,
+        })
+    }
+}
+
+/// A branch or tag the command created, moved or deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefChange {
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// What a command did to the sandbox copy
+#[derive(Debug, Clone)]
+pub struct SandboxReport {
+    pub command: String,
+    pub exit_code: i32,
+    /// stdout followed by stderr
+    pub output: String,
+    pub head_before: String,
+    pub head_after: String,
+    pub ref_changes: Vec<RefChange>,
+    /// `git diff --stat` from the old HEAD commit to the new one
+    pub commit_diff: Vec<String>,
+    /// Working tree status lines that went away
+    pub status_removed: Vec<String>,
+    /// Working tree status lines that appeared
+    pub status_added: Vec<String>,
+}
+
+impl SandboxReport {
+    fn compare(command: &str, exit_code: i32, output: String, before: &SandboxSnapshot, after: &SandboxSnapshot) -> Self {
+        let names: std::collections::BTreeSet<&String> = before.refs.keys().chain(after.refs.keys()).collect();
+        let ref_changes = names
+            .into_iter()
+            .filter(|name| before.refs.get(*name) != after.refs.get(*name))
+            .map(|name| RefChange {
+                name: name.clone(),
+                before: before.refs.get(name).cloned(),
+                after: after.refs.get(name).cloned(),
+            })
+            .collect();
+        let status_removed = before.status.iter().filter(|line| !after.status.contains(line)).cloned().collect();
+        let status_added = after.status.iter().filter(|line| !before.status.contains(line)).cloned().collect();
+
+        SandboxReport {
+            command: command.to_string(),
+            exit_code,
+            output,
+            head_before: before.head.clone(),
+            head_after: after.head.clone(),
+            ref_changes,
+            commit_diff: Vec::new(),
+            status_removed,
+            status_added,
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == 0
+    }
+
+    /// Whether the command left refs, HEAD and the working tree as they were
+    pub fn is_unchanged(&self) -> bool {
+        self.head_before == self.head_after
+            && self.ref_changes.is_empty()
+            && self.commit_diff.is_empty()
+            && self.status_removed.is_empty()
+            && self.status_added.is_empty()
+    }
+}
+
+/// A throwaway copy of a repository to try a command in before running it for real
+///
+/// The copy is a `--shared` local clone, borrowing the original's objects
+/// instead of copying them, with its branches, tags, remote-tracking refs,
+/// HEAD, index and uncommitted changes. The clone's `origin` is removed so
+/// nothing run in it can write back to the original. Ignored files, hooks,
+/// reflogs and stashes are not copied. The copy is deleted on drop.
+#[derive(Debug)]
+pub struct Sandbox {
+    dir: PathBuf,
+    executor: GitExecutor,
+}
+
+impl Sandbox {
+    /// Copy `repo` into a new temporary directory
+    pub fn create(repo: &Repository) -> GitResult<Self> {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+        let dir = std::env::temp_dir().join(format!("gitalky-sandbox-{}-{}", std::process::id(), nanos));
+        // Built first so a failed copy is still cleaned up on drop
+        let sandbox = Sandbox {
+            executor: GitExecutor::new(&dir),
+            dir,
+        };
+
+        let source = repo.executor();
+        let source_path = quote_arg(&repo.path().to_string_lossy());
+        source.execute(&format!(
+            "clone --quiet --shared --no-checkout -- {} {}",
+            source_path,
+            quote_arg(&sandbox.dir.to_string_lossy())
+        ))?;
+
+        let copy = &sandbox.executor;
+        copy.execute("remote remove origin")?;
+        copy.execute(&format!(
+            "fetch --quiet --no-tags --update-head-ok {} +refs/heads/*:refs/heads/* +refs/tags/*:refs/tags/* +refs/remotes/*:refs/remotes/*",
+            source_path
+        ))?;
+        match source.execute("symbolic-ref -q HEAD") {
+            Ok(branch) => copy.execute(&format!("symbolic-ref HEAD {}", quote_arg(branch.stdout.trim())))?,
+            Err(_) => {
+                let commit = source.execute("rev-parse HEAD")?;
+                copy.execute(&format!("update-ref --no-deref HEAD {}", commit.stdout.trim()))?
+            }
+        };
+
+        // Staged changes, then whatever differs in the working tree on top
+        let index = repo.git_dir().join("index");
+        if index.exists() {
+            fs::copy(&index, sandbox.dir.join(".git").join("index")).map_err(GitError::IoError)?;
+            copy.execute("checkout-index --all --force")?;
+        }
+        let status = source.execute("status --porcelain -z --untracked-files=all")?;
+        sandbox.copy_worktree_changes(repo.path(), &status.stdout)?;
+
+        Ok(sandbox)
+    }
+
+    /// Bring unstaged and untracked files over from `source`, given
+    /// `status --porcelain -z` output
+    fn copy_worktree_changes(&self, source: &Path, status: &str) -> GitResult<()> {
+        let mut entries = status.split('\0');
+        while let Some(entry) = entries.next() {
+            let Some((code, path)) = entry.get(..2).zip(entry.get(3..)) else {
+                continue;
+            };
+            // Renames and copies are followed by their original path
+            if code.starts_with(['R', 'C']) {
+                entries.next();
+            }
+            let (staged, worktree) = (code.as_bytes()[0], code.as_bytes()[1]);
+            let target = self.dir.join(path);
+            if worktree == b'D' {
+                let _ = fs::remove_file(&target);
+            } else if worktree != b' ' || staged == b'?' {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(GitError::IoError)?;
+                }
+                fs::copy(source.join(path), &target).map_err(GitError::IoError)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Run `command` in the copy and report what it changed
+    pub fn run(&self, command: &str) -> GitResult<SandboxReport> {
+        let before = SandboxSnapshot::capture(&self.executor)?;
+        let (exit_code, output) = match self.executor.execute_for_user(command.strip_prefix("git ").unwrap_or(command)) {
+            Ok(output) => (output.exit_code, output.stdout + &output.stderr),
+            Err(GitError::CommandExited {
+                exit_code,
+                stdout,
+                stderr,
+                ..
+            }) => (exit_code, stdout + &stderr),
+            Err(e) => return Err(e),
+        };
+        let after = SandboxSnapshot::capture(&self.executor)?;
+
+        let mut report = SandboxReport::compare(command, exit_code, output, &before, &after);
+        if let (Some(old), Some(new)) = (&before.head_commit, &after.head_commit)
+            && old != new
+        {
+            report.commit_diff = self
+                .executor
+                .execute(&format!("diff --stat {} {}", old, new))?
+                .stdout
+                .lines()
+                .map(str::to_string)
+                .collect();
+        }
+        Ok(report)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Try `command` on a throwaway copy of `repo`
+pub fn dry_run(repo: &Repository, command: &str) -> GitResult<SandboxReport> {
+    Sandbox::create(repo)?.run(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+
+    fn repo_with_history() -> (TempDir, Repository) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        for name in ["a.txt", "b.txt"] {
+            fs::write(dir.join(name), name).unwrap();
+            git(dir, &["add", name]);
+            git(dir, &["commit", "-q", "-m", name]);
+        }
+        git(dir, &["branch", "feature"]);
+        let repo = Repository::new(dir);
+        (temp, repo)
+    }
+
+    fn head(repo: &Repository) -> String {
+        repo.executor().execute("rev-parse HEAD").unwrap().stdout
+    }
+
+    #[test]
+    fn test_refusal() {
+        assert!(sandbox_refusal("git push --force", Some(&DangerousOp::ForcePush)).is_some());
+        assert!(sandbox_refusal("git fetch origin", None).is_some());
+        assert!(sandbox_refusal("git stash clear", Some(&DangerousOp::StashClear)).is_some());
+        assert!(sandbox_refusal("git reset --hard HEAD~1", Some(&DangerousOp::HardReset)).is_none());
+        assert!(sandbox_refusal("git rebase main", Some(&DangerousOp::Rebase)).is_none());
+    }
+
+    #[test]
+    fn test_copy_matches_original() {
+        let (_temp, repo) = repo_with_history();
+        fs::write(repo.path().join("a.txt"), "edited").unwrap();
+        fs::write(repo.path().join("staged.txt"), "staged").unwrap();
+        git(repo.path(), &["add", "staged.txt"]);
+        fs::remove_file(repo.path().join("b.txt")).unwrap();
+        fs::create_dir(repo.path().join("new")).unwrap();
+        fs::write(repo.path().join("new/untracked.txt"), "new").unwrap();
+
+        let sandbox = Sandbox::create(&repo).unwrap();
+        let copy = GitExecutor::new(sandbox.path());
+        assert_eq!(
+            SandboxSnapshot::capture(&copy).unwrap(),
+            SandboxSnapshot::capture(repo.executor()).unwrap()
+        );
+        assert!(copy.execute("remote").unwrap().stdout.is_empty());
+
+        let path = sandbox.path().to_path_buf();
+        drop(sandbox);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_hard_reset_report() {
+        let (_temp, repo) = repo_with_history();
+        fs::write(repo.path().join("a.txt"), "edited").unwrap();
+        let original_head = head(&repo);
+
+        let report = dry_run(&repo, "git reset --hard HEAD~1").unwrap();
+        assert!(report.succeeded());
+        assert_eq!(report.ref_changes.len(), 1);
+        assert_eq!(report.ref_changes[0].name, "main");
+        assert_ne!(report.ref_changes[0].before, report.ref_changes[0].after);
+        assert!(report.commit_diff.iter().any(|line| line.contains("b.txt")));
+        assert_eq!(report.status_removed, vec![" M a.txt"]);
+        assert!(report.status_added.is_empty());
+
+        // The original is untouched
+        assert_eq!(head(&repo), original_head);
+        assert_eq!(fs::read_to_string(repo.path().join("a.txt")).unwrap(), "edited");
+    }
+
+    #[test]
+    fn test_failed_command_report() {
+        let (_temp, repo) = repo_with_history();
+        let report = dry_run(&repo, "git branch -D main").unwrap();
+        assert!(!report.succeeded());
+        assert!(report.is_unchanged());
+        assert!(report.output.contains("main"));
+    }
+
+    #[test]
+    fn test_detached_head() {
+        let (_temp, repo) = repo_with_history();
+        git(repo.path(), &["checkout", "-q", "--detach", "HEAD~1"]);
+
+        let report = dry_run(&repo, "git branch -D feature").unwrap();
+        assert!(report.succeeded());
+        assert_eq!(report.head_before, report.head_after);
+        assert_eq!(report.head_before.len(), 7);
+        assert_eq!(report.ref_changes[0].after, None);
+        assert!(repo.executor().execute("rev-parse --verify feature").is_ok());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::git::commit_checks::format_size;
+use crate::git::executor::split_command;
+
+/// Fetches the history a shallow clone left out
+pub const UNSHALLOW_COMMAND: &str = "git fetch --unshallow";
+
+/// Subcommands whose answers are wrong or missing without the full history:
+/// bisect can't reach the good commit, describe the tag, merge-base the fork point
+const FULL_HISTORY_SUBCOMMANDS: &[&str] = &["bisect", "describe", "merge-base"];
+
+/// The first subcommand in `command` that needs the full history, if any
+pub fn full_history_subcommand(command: &str) -> Option<&'static str> {
+    if command.contains("--unshallow") {
+        return None;
+    }
+    command.split("&&").find_map(|part| {
+        let words = split_command(part).ok()?;
+        let sub = words.iter().skip_while(|word| *word == "git").find(|word| !word.starts_with('-'))?;
+        FULL_HISTORY_SUBCOMMANDS.iter().copied().find(|name| name == sub)
+    })
+}
+
+/// `command` run after fetching the full history
+pub fn with_unshallow(command: &str) -> String {
+    format!("{} && {}", UNSHALLOW_COMMAND, command)
+}
+
+/// What unshallowing costs, for a clone of `depth` commits taking `local_bytes`
+pub fn unshallow_warning(depth: usize, local_bytes: u64) -> String {
+    format!(
+        "Downloads every commit the shallow clone left out. This clone has {} commit(s) in {}; \
+         the full history is often many times larger.",
+        depth,
+        format_size(local_bytes)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_history_subcommand() {
+        assert_eq!(full_history_subcommand("git describe --tags"), Some("describe"));
+        assert_eq!(full_history_subcommand("git bisect start"), Some("bisect"));
+        assert_eq!(full_history_subcommand("git status && git merge-base main HEAD"), Some("merge-base"));
+        assert_eq!(full_history_subcommand("git log --oneline"), None);
+        assert_eq!(full_history_subcommand("git commit -m 'describe it'"), None);
+        assert_eq!(full_history_subcommand(&with_unshallow("git describe")), None);
+    }
+
+    #[test]
+    fn test_unshallow_warning() {
+        assert_eq!(
+            unshallow_warning(1, 2048),
+            "Downloads every commit the shallow clone left out. This clone has 1 commit(s) in 2 KB; \
+             the full history is often many times larger."
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::GitResult;
+use crate::git::executor::{GitExecutor, quote_arg, split_command};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// What git made of a commit's signature, from `%G?`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    /// `G`: good, from a trusted key
+    Good,
+    /// `U`: good, but nothing says the key belongs to the signer
+    Untrusted,
+    /// `X`/`Y`: good, but the signature or the key has expired
+    Expired,
+    /// `R`: made with a revoked key
+    Revoked,
+    /// `B`: doesn't match the commit
+    Bad,
+    /// `E`: couldn't be checked, usually for want of the public key
+    Unverifiable,
+    /// `N`: not signed
+    Unsigned,
+}
+
+impl SignatureStatus {
+    pub fn from_code(code: &str) -> Self {
+        match code.trim() {
+            "G" => SignatureStatus::Good,
+            "U" => SignatureStatus::Untrusted,
+            "X" | "Y" => SignatureStatus::Expired,
+            "R" => SignatureStatus::Revoked,
+            "B" => SignatureStatus::Bad,
+            "E" => SignatureStatus::Unverifiable,
+            _ => SignatureStatus::Unsigned,
+        }
+    }
+
+    /// One-character mark for commit lists
+    pub fn badge(self) -> &'static str {
+        match self {
+            SignatureStatus::Good | SignatureStatus::Untrusted => "✓",
+            SignatureStatus::Expired => "!",
+            SignatureStatus::Revoked | SignatureStatus::Bad => "✗",
+            SignatureStatus::Unverifiable => "?",
+            SignatureStatus::Unsigned => "·",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SignatureStatus::Good => "good signature",
+            SignatureStatus::Untrusted => "good signature, untrusted key",
+            SignatureStatus::Expired => "expired signature or key",
+            SignatureStatus::Revoked => "revoked key",
+            SignatureStatus::Bad => "BAD signature",
+            SignatureStatus::Unverifiable => "signature can't be checked",
+            SignatureStatus::Unsigned => "unsigned",
+        }
+    }
+}
+
+/// Signature status of the last `count` commits from HEAD, by full hash
+pub fn signature_statuses(executor: &GitExecutor, count: usize) -> GitResult<HashMap<String, SignatureStatus>> {
+    let output = executor.execute(&format!("log -n {} --format=%H%x00%G?", count))?;
+    Ok(output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('\0'))
+        .map(|(hash, code)| (hash.to_string(), SignatureStatus::from_code(code)))
+        .collect())
+}
+
+/// A commit's signature with who made it and gpg's own report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureCheck {
+    pub status: SignatureStatus,
+    /// Signer's name as the key has it (`%GS`)
+    pub signer: String,
+    /// Signing key id (`%GK`)
+    pub key: String,
+    /// What gpg (or ssh-keygen) printed (`%GG`)
+    pub report: String,
+}
+
+impl SignatureCheck {
+    /// Check the signature of `revision`, which must name a commit
+    pub fn read(executor: &GitExecutor, revision: &str) -> GitResult<Self> {
+        let output = executor.execute(&format!(
+            "show -s --format=%G?%x00%GS%x00%GK%x00%GG {}",
+            quote_arg(&format!("{}^{{commit}}", revision))
+        ))?;
+        let mut fields = output.stdout.splitn(4, '\0');
+        let mut next = || fields.next().unwrap_or_default().trim().to_string();
+        Ok(SignatureCheck {
+            status: SignatureStatus::from_code(&next()),
+            signer: next(),
+            key: next(),
+            report: next(),
+        })
+    }
+
+    /// What the status means and how to deal with the usual trust problems
+    pub fn explanation(&self) -> String {
+        let signer = if self.signer.is_empty() { "an unknown signer".to_string() } else { self.signer.clone() };
+        let key = if self.key.is_empty() { "<key id>".to_string() } else { self.key.clone() };
+        let ssh = self.report.contains("ssh") || self.report.contains("allowedSignersFile");
+        match self.status {
+            SignatureStatus::Good => format!("✓ Good signature from {} (key {}).", signer, key),
+            SignatureStatus::Untrusted => format!(
+                "✓ Good signature from {}, but the key {} isn't trusted: nothing in your keyring \
+                 vouches that it belongs to them.\n\n💡 Once you've checked the fingerprint with them, \
+                 `gpg --lsign-key {}` marks it as theirs.",
+                signer, key, key
+            ),
+            SignatureStatus::Expired => format!(
+                "! The signature by {} or its key {} has expired.\n\n💡 `gpg --refresh-keys {}` picks up \
+                 a renewed key; if it was really renewed the status turns good.",
+                signer, key, key
+            ),
+            SignatureStatus::Revoked => format!(
+                "✗ Signed with key {}, which its owner revoked. Treat the commit as unsigned and ask \
+                 {} how it should be re-signed.",
+                key, signer
+            ),
+            SignatureStatus::Bad => "✗ BAD signature: the commit doesn't match what was signed. It was \
+                 changed after signing or the signature is forged; don't build on it before finding out why."
+                .to_string(),
+            SignatureStatus::Unverifiable if ssh => "? The SSH signature can't be checked: git doesn't know \
+                 which keys belong to whom.\n\n💡 Point `gpg.ssh.allowedSignersFile` at a file listing \
+                 `<email> <public key>` for each signer."
+                .to_string(),
+            SignatureStatus::Unverifiable if self.report.contains("No public key") => format!(
+                "? The commit is signed with key {}, which isn't in your keyring.\n\n💡 `gpg --recv-keys {}` \
+                 fetches it from a keyserver, or import the key the signer publishes.",
+                key, key
+            ),
+            SignatureStatus::Unverifiable => "? The signature can't be checked. Is gpg installed, and is \
+                 `gpg.program` right?"
+                .to_string(),
+            SignatureStatus::Unsigned => "· Not signed. Branches that require signed commits will refuse \
+                 it.\n\n💡 `git commit --amend -S` signs the last commit; `commit.gpgsign` signs every new one."
+                .to_string(),
+        }
+    }
+}
+
+/// The commit a `git show` or `git verify-commit` command looks at: its
+/// first revision, HEAD when it names none
+pub fn verified_revision(command: &str) -> Option<String> {
+    let words = split_command(command).ok()?;
+    let mut words = words.into_iter().skip_while(|word| word == "git");
+    if !matches!(words.next()?.as_str(), "show" | "verify-commit") {
+        return None;
+    }
+    let revision = words.take_while(|word| word != "--").find(|word| !word.starts_with('-'));
+    Some(revision.unwrap_or_else(|| "HEAD".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_status_codes() {
+        assert_eq!(SignatureStatus::from_code("G"), SignatureStatus::Good);
+        assert_eq!(SignatureStatus::from_code("Y\n"), SignatureStatus::Expired);
+        assert_eq!(SignatureStatus::from_code("E"), SignatureStatus::Unverifiable);
+        assert_eq!(SignatureStatus::from_code("N"), SignatureStatus::Unsigned);
+        assert_eq!(SignatureStatus::Untrusted.badge(), "✓");
+    }
+
+    #[test]
+    fn test_explanations() {
+        let check = |status, report: &str| SignatureCheck {
+            status,
+            signer: "Ada <ada@example.com>".to_string(),
+            key: "ABCD1234".to_string(),
+            report: report.to_string(),
+        };
+        assert!(check(SignatureStatus::Untrusted, "").explanation().contains("gpg --lsign-key ABCD1234"));
+        let missing = check(SignatureStatus::Unverifiable, "gpg: Can't check signature: No public key");
+        assert!(missing.explanation().contains("gpg --recv-keys ABCD1234"));
+        let ssh = check(SignatureStatus::Unverifiable, "error: gpg.ssh.allowedSignersFile needs to be configured");
+        assert!(ssh.explanation().contains("gpg.ssh.allowedSignersFile"));
+        assert!(check(SignatureStatus::Bad, "").explanation().starts_with("✗ BAD"));
+    }
+
+    #[test]
+    fn test_verified_revision() {
+        assert_eq!(verified_revision("git show").as_deref(), Some("HEAD"));
+        assert_eq!(verified_revision("git show --stat abc123 -- src").as_deref(), Some("abc123"));
+        assert_eq!(verified_revision("git verify-commit -v v1.0").as_deref(), Some("v1.0"));
+        assert_eq!(verified_revision("git log -1"), None);
+    }
+
+    #[test]
+    fn test_unsigned_commits() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        git(temp.path(), &["config", "commit.gpgsign", "false"]);
+        git(temp.path(), &["commit", "-q", "--allow-empty", "-m", "one"]);
+        let executor = GitExecutor::new(temp.path());
+        let statuses = signature_statuses(&executor, 10).unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses.values().all(|status| *status == SignatureStatus::Unsigned));
+        let check = SignatureCheck::read(&executor, "HEAD").unwrap();
+        assert_eq!(check.status, SignatureStatus::Unsigned);
+        assert!(check.signer.is_empty());
+    }
+}
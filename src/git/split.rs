@@ -0,0 +1,341 @@
+use crate::error::{GitError, GitResult};
+use crate::git::executor::quote_arg;
+use crate::git::repository::Repository;
+
+/// Ref holding the original commit while a split is in progress
+///
+/// Written before anything is reset, so an interrupted split (crash, closed
+/// terminal) can still be restored or resumed on the next start.
+pub const SPLIT_BACKUP_REF: &str = "refs/gitalky/split-backup";
+
+/// Phrases that ask to split the last commit
+const SPLIT_PHRASES: &[&str] = &[
+    "split my last commit",
+    "split the last commit",
+    "split last commit",
+    "split the previous commit",
+    "split head",
+    "split commit",
+    "break up the last commit",
+    "break up my last commit",
+];
+
+/// Check whether a natural language query asks to split the last commit
+pub fn is_split_commit_query(query: &str) -> bool {
+    let normalized = query
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    SPLIT_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// A guided split of the last commit into several smaller commits
+///
+/// `start` snapshots HEAD to [`SPLIT_BACKUP_REF`], soft-resets the commit and
+/// unstages its changes. The user then stages subsets and commits them one at
+/// a time until the tree matches the original commit again. `abort` restores
+/// the original commit from the snapshot.
+#[derive(Debug, Clone)]
+pub struct CommitSplit {
+    original_head: String,
+    original_message: String,
+    commits_made: usize,
+    /// Commands run since the last [`CommitSplit::drain_executed`], for the audit log
+    executed: Vec<String>,
+}
+
+impl CommitSplit {
+    /// Snapshot HEAD and undo the last commit, leaving its changes unstaged
+    pub fn start(repo: &Repository) -> GitResult<Self> {
+        if Self::backup_exists(repo) {
+            return Err(GitError::CommandFailed(
+                "A commit split is already in progress".to_string(),
+            ));
+        }
+
+        let state = repo.state()?;
+        if state.in_merge || state.in_rebase {
+            return Err(GitError::CommandFailed(
+                "Finish the merge or rebase in progress before splitting a commit".to_string(),
+            ));
+        }
+        if !state.is_clean() {
+            return Err(GitError::CommandFailed(
+                "Commit or stash your changes before splitting a commit".to_string(),
+            ));
+        }
+
+        let executor = repo.executor();
+        let parents = executor.execute("rev-list --parents -n 1 HEAD")?;
+        match parents.stdout.split_whitespace().count() {
+            2 => {}
+            1 => {
+                return Err(GitError::CommandFailed(
+                    "The first commit of a repository can't be split".to_string(),
+                ));
+            }
+            _ => {
+                return Err(GitError::CommandFailed(
+                    "Merge commits can't be split".to_string(),
+                ));
+            }
+        }
+
+        let original_head = executor.execute("rev-parse HEAD")?.stdout.trim().to_string();
+        let original_message = executor.execute("log -1 --format=%B HEAD")?.stdout.trim().to_string();
+
+        let mut split = Self {
+            original_head,
+            original_message,
+            commits_made: 0,
+            executed: Vec::new(),
+        };
+
+        split.run(repo, &format!("update-ref {} {}", SPLIT_BACKUP_REF, split.original_head))?;
+        split.run(repo, "reset --soft HEAD~1")?;
+        split.run(repo, "reset -q")?;
+        Ok(split)
+    }
+
+    /// Pick up a split left behind by a previous session
+    pub fn resume(repo: &Repository) -> GitResult<Option<Self>> {
+        if !Self::backup_exists(repo) {
+            return Ok(None);
+        }
+
+        let executor = repo.executor();
+        let original_head = executor
+            .execute(&format!("rev-parse {}", SPLIT_BACKUP_REF))?
+            .stdout
+            .trim()
+            .to_string();
+        let original_message = executor
+            .execute(&format!("log -1 --format=%B {}", original_head))?
+            .stdout
+            .trim()
+            .to_string();
+        let commits_made = executor
+            .execute(&format!("rev-list --count {}~1..HEAD", original_head))?
+            .stdout
+            .trim()
+            .parse()
+            .unwrap_or(0);
+
+        Ok(Some(Self {
+            original_head,
+            original_message,
+            commits_made,
+            executed: Vec::new(),
+        }))
+    }
+
+    fn backup_exists(repo: &Repository) -> bool {
+        repo.executor()
+            .execute(&format!("rev-parse --verify --quiet {}", SPLIT_BACKUP_REF))
+            .is_ok()
+    }
+
+    /// Stage a file for the next commit
+    pub fn stage(&mut self, repo: &Repository, path: &str) -> GitResult<()> {
+        self.run(repo, &format!("add -- {}", quote_arg(path)))
+    }
+
+    /// Remove a file from the next commit
+    pub fn unstage(&mut self, repo: &Repository, path: &str) -> GitResult<()> {
+        self.run(repo, &format!("restore --staged -- {}", quote_arg(path)))
+    }
+
+    /// Stage everything that is left
+    pub fn stage_all(&mut self, repo: &Repository) -> GitResult<()> {
+        self.run(repo, "add -A")
+    }
+
+    /// Commit the staged subset as the next piece of the split
+    pub fn commit(&mut self, repo: &Repository, message: &str) -> GitResult<()> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(GitError::CommandFailed("Commit message cannot be empty".to_string()));
+        }
+        self.run(repo, &format!("commit -m {}", quote_arg(message)))?;
+        self.commits_made += 1;
+        Ok(())
+    }
+
+    /// Check whether the new commits add up to the original commit
+    ///
+    /// True once HEAD has the original commit's tree and nothing is left over.
+    pub fn is_complete(&self, repo: &Repository) -> GitResult<bool> {
+        let head_matches = repo
+            .executor()
+            .execute(&format!("diff --quiet HEAD {} --", self.original_head))
+            .is_ok();
+        Ok(head_matches && repo.state()?.is_clean())
+    }
+
+    /// Drop the snapshot once every change has been committed again
+    pub fn finish(&mut self, repo: &Repository) -> GitResult<usize> {
+        if !self.is_complete(repo)? {
+            return Err(GitError::CommandFailed(
+                "Some changes from the original commit are not committed yet".to_string(),
+            ));
+        }
+        self.run(repo, &format!("update-ref -d {}", SPLIT_BACKUP_REF))?;
+        Ok(self.commits_made)
+    }
+
+    /// Throw away the partial split and restore the original commit
+    ///
+    /// The working tree was clean when the split started, so resetting to the
+    /// snapshot restores exactly the state before the split.
+    pub fn abort(&mut self, repo: &Repository) -> GitResult<()> {
+        self.run(repo, &format!("reset --hard {}", self.original_head))?;
+        self.run(repo, &format!("update-ref -d {}", SPLIT_BACKUP_REF))
+    }
+
+    /// Commands run since the last call, for the audit log
+    pub fn drain_executed(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.executed)
+    }
+
+    pub fn original_head(&self) -> &str {
+        &self.original_head
+    }
+
+    pub fn original_message(&self) -> &str {
+        &self.original_message
+    }
+
+    /// Subject line of the commit being split
+    pub fn original_subject(&self) -> &str {
+        self.original_message.lines().next().unwrap_or("")
+    }
+
+    pub fn commits_made(&self) -> usize {
+        self.commits_made
+    }
+
+    fn run(&mut self, repo: &Repository, command: &str) -> GitResult<()> {
+        self.executed.push(command.to_string());
+        repo.executor().execute(command).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use std::fs;
+    use tempfile::TempDir;
+
+
+    /// Repo with a base commit and a second commit touching two files
+    fn repo_with_commit_to_split() -> (TempDir, Repository) {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+
+        fs::write(dir.join("base.txt"), "base").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Base"]);
+
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::write(dir.join("b.txt"), "b").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-q", "-m", "Add a and b"]);
+
+        let repo = Repository::new(dir);
+        (temp, repo)
+    }
+
+    #[test]
+    fn test_split_queries() {
+        assert!(is_split_commit_query("split my last commit"));
+        assert!(is_split_commit_query("Please split  the last commit into two"));
+        assert!(!is_split_commit_query("show the last commit"));
+    }
+
+    #[test]
+    fn test_split_into_two_commits() {
+        let (_temp, repo) = repo_with_commit_to_split();
+
+        let mut split = CommitSplit::start(&repo).unwrap();
+        assert_eq!(split.original_subject(), "Add a and b");
+        assert!(!split.is_complete(&repo).unwrap());
+
+        let state = repo.state().unwrap();
+        assert_eq!(state.untracked_files.len(), 2);
+
+        split.stage(&repo, "a.txt").unwrap();
+        split.commit(&repo, "Add a").unwrap();
+        assert!(!split.is_complete(&repo).unwrap());
+
+        split.stage_all(&repo).unwrap();
+        split.commit(&repo, "Add b").unwrap();
+        assert!(split.is_complete(&repo).unwrap());
+
+        assert_eq!(split.finish(&repo).unwrap(), 2);
+        assert!(CommitSplit::resume(&repo).unwrap().is_none());
+
+        let log = repo.executor().execute("log --format=%s").unwrap().stdout;
+        assert_eq!(log.lines().collect::<Vec<_>>(), vec!["Add b", "Add a", "Base"]);
+    }
+
+    #[test]
+    fn test_abort_restores_original_commit() {
+        let (_temp, repo) = repo_with_commit_to_split();
+        let head = repo.executor().execute("rev-parse HEAD").unwrap().stdout;
+
+        let mut split = CommitSplit::start(&repo).unwrap();
+        split.stage(&repo, "a.txt").unwrap();
+        split.commit(&repo, "Add a").unwrap();
+        split.abort(&repo).unwrap();
+
+        assert_eq!(repo.executor().execute("rev-parse HEAD").unwrap().stdout, head);
+        assert!(repo.state().unwrap().is_clean());
+        assert!(CommitSplit::resume(&repo).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resume_interrupted_split() {
+        let (_temp, repo) = repo_with_commit_to_split();
+
+        let mut split = CommitSplit::start(&repo).unwrap();
+        split.stage(&repo, "b.txt").unwrap();
+        split.commit(&repo, "Add b").unwrap();
+        drop(split);
+
+        let resumed = CommitSplit::resume(&repo).unwrap().unwrap();
+        assert_eq!(resumed.original_subject(), "Add a and b");
+        assert_eq!(resumed.commits_made(), 1);
+
+        // Starting again is refused while the snapshot exists
+        assert!(CommitSplit::start(&repo).is_err());
+    }
+
+    #[test]
+    fn test_start_refuses_dirty_tree_and_root_commit() {
+        let (temp, repo) = repo_with_commit_to_split();
+        fs::write(temp.path().join("a.txt"), "changed").unwrap();
+        assert!(CommitSplit::start(&repo).is_err());
+
+        let root = TempDir::new().unwrap();
+        init_repo(root.path());
+        fs::write(root.path().join("f.txt"), "f").unwrap();
+        git(root.path(), &["add", "."]);
+        git(root.path(), &["commit", "-q", "-m", "Root"]);
+        assert!(CommitSplit::start(&Repository::new(root.path())).is_err());
+    }
+
+    #[test]
+    fn test_finish_refuses_incomplete_split() {
+        let (_temp, repo) = repo_with_commit_to_split();
+
+        let mut split = CommitSplit::start(&repo).unwrap();
+        split.stage(&repo, "a.txt").unwrap();
+        split.commit(&repo, "Add a").unwrap();
+
+        assert!(split.clone().finish(&repo).is_err());
+        assert!(split.commit(&repo, "  ").is_err());
+    }
+}
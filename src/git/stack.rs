@@ -0,0 +1,438 @@
+use crate::error::GitResult;
+use crate::git::executor::{GitExecutor, quote_arg};
+use crate::git::repository::Repository;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Local branches looked at, the most recently committed first
+pub const MAX_STACK_BRANCHES: usize = 20;
+
+/// A local branch and the branch it is stacked on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackBranch {
+    pub name: String,
+    /// Branch or trunk ref this one was started from; `None` when it
+    /// shares no work with any of them
+    pub parent: Option<String>,
+    /// Levels below the trunk (or below a root branch)
+    pub depth: usize,
+    /// Commit the branch forked from its parent at
+    pub base: Option<String>,
+    /// Commits on the branch since `base`
+    pub ahead: usize,
+    /// Commits the parent gained since `base`; non-zero means the branch
+    /// no longer sits on top of its parent
+    pub parent_moved: usize,
+    /// Local copy of a remote trunk, brought up to date by pulling
+    pub follows_trunk: bool,
+}
+
+impl StackBranch {
+    pub fn needs_restack(&self) -> bool {
+        self.parent_moved > 0 && self.ahead > 0 && !self.follows_trunk
+    }
+}
+
+/// Parent relationships among the local branches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchStack {
+    /// Branch everything is ultimately based on, e.g. `origin/main`
+    pub trunk: Option<String>,
+    /// Parents before their children, siblings by name
+    pub branches: Vec<StackBranch>,
+}
+
+impl BranchStack {
+    /// Work out which branch each local branch is stacked on
+    ///
+    /// A branch whose upstream is another local branch (`git branch -u`)
+    /// is stacked on it. Otherwise the parent is the branch or trunk it has
+    /// the fewest commits of its own over, counted from the fork point so
+    /// that a parent which was rebased since is still recognised; a parent
+    /// it sits exactly on top of wins ties. A branch that was created at or
+    /// after the fork point (per its reflog) is a sibling, not a parent.
+    pub fn read(repo: &Repository) -> GitResult<Self> {
+        let executor = repo.executor();
+        let listed = executor.execute(&format!(
+            "for-each-ref --sort=-committerdate --count={} '--format=%(refname:short) %(upstream:short)' refs/heads",
+            MAX_STACK_BRANCHES
+        ))?;
+        let mut upstreams: BTreeMap<String, Option<String>> = BTreeMap::new();
+        for line in listed.stdout.lines() {
+            let mut words = line.split_whitespace();
+            if let Some(name) = words.next() {
+                upstreams.insert(name.to_string(), words.next().map(str::to_string));
+            }
+        }
+
+        let trunk = trunk_ref(repo, &upstreams);
+        // The local `main` of a remote `origin/main` trunk
+        let trunk_twin = trunk
+            .as_deref()
+            .filter(|trunk| !upstreams.contains_key(*trunk))
+            .and_then(|trunk| trunk.split_once('/'))
+            .map(|(_, branch)| branch.to_string())
+            .filter(|branch| upstreams.contains_key(branch));
+
+        let mut graph = Graph {
+            executor,
+            divergence: HashMap::new(),
+        };
+        let names: Vec<String> = upstreams.keys().filter(|name| Some(*name) != trunk.as_ref()).cloned().collect();
+        let mut parents: HashMap<String, String> = HashMap::new();
+        for name in &names {
+            let candidates: Vec<String> = if Some(name) == trunk_twin.as_ref() {
+                trunk.iter().cloned().collect()
+            } else {
+                let explicit = upstreams[name].iter().filter(|upstream| upstreams.contains_key(*upstream));
+                explicit.cloned().chain(graph.ranked_candidates(name, &upstreams, trunk.as_deref())).collect()
+            };
+            // Skip candidates that would make a branch its own ancestor
+            let parent = candidates.into_iter().find(|candidate| {
+                let mut current = Some(candidate);
+                while let Some(ancestor) = current {
+                    if ancestor == name {
+                        return false;
+                    }
+                    current = parents.get(ancestor);
+                }
+                true
+            });
+            if let Some(parent) = parent {
+                parents.insert(name.clone(), parent);
+            }
+        }
+
+        // A merged branch with nothing of its own still belongs on the trunk
+        if let Some(ref trunk) = trunk {
+            for name in &names {
+                if !parents.contains_key(name) && graph.divergence(name, trunk).0 == 0 {
+                    parents.insert(name.clone(), trunk.clone());
+                }
+            }
+        }
+
+        let mut children: BTreeMap<Option<&str>, Vec<&str>> = BTreeMap::new();
+        for name in &names {
+            let parent = parents.get(name).map(String::as_str);
+            let parent = parent.filter(|parent| Some(*parent) != trunk.as_deref());
+            children.entry(parent).or_default().push(name);
+        }
+
+        let mut branches = Vec::new();
+        let mut pending: Vec<(&str, usize)> = children.get(&None).into_iter().flatten().rev().map(|n| (*n, 0)).collect();
+        while let Some((name, depth)) = pending.pop() {
+            let parent = parents.get(name).cloned();
+            let (base, ahead, parent_moved) = match parent {
+                Some(ref parent) => {
+                    let base = graph.fork_point(parent, name).or_else(|| graph.merge_base(parent, name));
+                    let ahead = match base {
+                        Some(ref base) => graph.count(base, name),
+                        None => 0,
+                    };
+                    (base, ahead, graph.divergence(name, parent).1)
+                }
+                None => (None, 0, 0),
+            };
+            branches.push(StackBranch {
+                name: name.to_string(),
+                follows_trunk: Some(name) == trunk_twin.as_deref(),
+                parent,
+                depth,
+                base,
+                ahead,
+                parent_moved,
+            });
+            pending.extend(children.get(&Some(name)).into_iter().flatten().rev().map(|child| (*child, depth + 1)));
+        }
+
+        Ok(Self { trunk, branches })
+    }
+
+    /// Rebases that put every branch back on top of its parent
+    ///
+    /// A branch is restacked when its parent moved on, or when the parent is
+    /// restacked earlier in the plan. Each step replays only the branch's own
+    /// commits (`--onto <parent> <fork point>`); `current` is checked out again
+    /// at the end, since each rebase leaves its branch checked out.
+    pub fn restack_steps(&self, current: Option<&str>) -> Vec<(String, String)> {
+        let mut steps = Vec::new();
+        let mut restacked: HashSet<&str> = HashSet::new();
+        for branch in &self.branches {
+            let (Some(parent), Some(base)) = (&branch.parent, &branch.base) else {
+                continue;
+            };
+            if branch.follows_trunk || branch.ahead == 0 {
+                continue;
+            }
+            if branch.parent_moved == 0 && !restacked.contains(parent.as_str()) {
+                continue;
+            }
+            let commits = if branch.ahead == 1 { "commit" } else { "commits" };
+            steps.push((
+                format!("restack {} ({} {}) onto {}", branch.name, branch.ahead, commits, parent),
+                format!("git rebase --onto {} {} {}", quote_arg(parent), base, quote_arg(&branch.name)),
+            ));
+            restacked.insert(&branch.name);
+        }
+
+        if !steps.is_empty()
+            && let Some(current) = current
+        {
+            steps.push((format!("return to {}", current), format!("git checkout {}", quote_arg(current))));
+        }
+        steps
+    }
+}
+
+/// `origin/HEAD` (or `origin/main`, `origin/master`), else a local `main`
+/// or `master`
+fn trunk_ref(repo: &Repository, branches: &BTreeMap<String, Option<String>>) -> Option<String> {
+    let executor = repo.executor();
+    if let Ok(Some(remote)) = repo.default_remote() {
+        if let Ok(output) = executor.execute(&format!("symbolic-ref --quiet --short refs/remotes/{}/HEAD", remote)) {
+            let head = output.stdout.trim();
+            if !head.is_empty() {
+                return Some(head.to_string());
+            }
+        }
+        for branch in ["main", "master"] {
+            let candidate = format!("{}/{}", remote, branch);
+            if executor.execute(&format!("rev-parse --verify --quiet refs/remotes/{}", candidate)).is_ok() {
+                return Some(candidate);
+            }
+        }
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| branches.contains_key(*branch))
+        .map(str::to_string)
+}
+
+/// Commit graph queries, with divergence counts cached per pair
+struct Graph<'a> {
+    executor: &'a GitExecutor,
+    divergence: HashMap<(String, String), (usize, usize)>,
+}
+
+impl Graph<'_> {
+    /// Commits only on `a` and only on `b`
+    fn divergence(&mut self, a: &str, b: &str) -> (usize, usize) {
+        if let Some((only_b, only_a)) = self.divergence.get(&(b.to_string(), a.to_string())) {
+            return (*only_a, *only_b);
+        }
+        let executor = self.executor;
+        *self.divergence.entry((a.to_string(), b.to_string())).or_insert_with(|| {
+            executor
+                .execute(&format!("rev-list --left-right --count {}...{}", quote_arg(a), quote_arg(b)))
+                .ok()
+                .and_then(|output| {
+                    let mut counts = output.stdout.split_whitespace().map(|n| n.parse().ok());
+                    Some((counts.next()??, counts.next()??))
+                })
+                .unwrap_or((0, 0))
+        })
+    }
+
+    /// Commits in `to` but not in `from`
+    fn count(&self, from: &str, to: &str) -> usize {
+        self.executor
+            .execute(&format!("rev-list --count {}..{}", from, quote_arg(to)))
+            .ok()
+            .and_then(|output| output.stdout.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn merge_base(&self, a: &str, b: &str) -> Option<String> {
+        self.commit(&format!("merge-base {} {}", quote_arg(a), quote_arg(b)))
+    }
+
+    /// Where `branch` forked from `parent`, using the parent's reflog to see
+    /// through rebases of the parent
+    fn fork_point(&self, parent: &str, branch: &str) -> Option<String> {
+        self.commit(&format!("merge-base --fork-point {} {}", quote_arg(parent), quote_arg(branch)))
+    }
+
+    /// Whether `branch` was created on a strict ancestor of `commit`; true
+    /// when the branch has no reflog
+    fn created_before(&self, branch: &str, commit: &str) -> bool {
+        let Some(created) = self
+            .executor
+            .execute(&format!("reflog show --format=%H {}", quote_arg(&format!("refs/heads/{}", branch))))
+            .ok()
+            .and_then(|output| output.stdout.lines().last().map(str::to_string))
+        else {
+            return true;
+        };
+        created != commit
+            && self
+                .executor
+                .execute(&format!("merge-base --is-ancestor {} {}", created, commit))
+                .is_ok()
+    }
+
+    fn commit(&self, command: &str) -> Option<String> {
+        let output = self.executor.execute(command).ok()?;
+        let commit = output.stdout.trim();
+        (!commit.is_empty()).then(|| commit.to_string())
+    }
+
+    /// Possible parents of `name`, best first
+    fn ranked_candidates(
+        &mut self,
+        name: &str,
+        branches: &BTreeMap<String, Option<String>>,
+        trunk: Option<&str>,
+    ) -> Vec<String> {
+        let others = branches.keys().map(String::as_str).filter(|other| *other != name);
+        let candidates: Vec<&str> = others.chain(trunk.filter(|trunk| !branches.contains_key(*trunk))).collect();
+
+        let mut ranked = Vec::new();
+        for candidate in candidates {
+            let (mut ahead, moved) = self.divergence(name, candidate);
+            if ahead == 0 {
+                // Everything on `name` is already in `candidate`
+                continue;
+            }
+            if moved > 0 && Some(candidate) != trunk {
+                let Some(fork_point) = self.fork_point(candidate, name).or_else(|| self.merge_base(candidate, name))
+                else {
+                    continue;
+                };
+                if !self.created_before(candidate, &fork_point) {
+                    continue;
+                }
+                ahead = ahead.min(self.count(&fork_point, name));
+            }
+            ranked.push(((ahead, moved > 0, moved, Some(candidate) != trunk), candidate.to_string()));
+        }
+        ranked.sort();
+        ranked.into_iter().map(|(_, candidate)| candidate).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo, commit_file};
+    use tempfile::TempDir;
+
+
+
+    /// main ← feature-a ← feature-b, plus feature-c on main
+    fn stacked_repo() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        init_repo(dir);
+        commit_file(dir, "base.txt");
+        git(dir, &["checkout", "-q", "-b", "feature-a"]);
+        commit_file(dir, "a1.txt");
+        commit_file(dir, "a2.txt");
+        git(dir, &["checkout", "-q", "-b", "feature-b"]);
+        commit_file(dir, "b1.txt");
+        git(dir, &["checkout", "-q", "-b", "feature-c", "main"]);
+        commit_file(dir, "c1.txt");
+        temp
+    }
+
+    fn parents(stack: &BranchStack) -> Vec<(String, Option<String>, usize)> {
+        stack
+            .branches
+            .iter()
+            .map(|branch| (branch.name.clone(), branch.parent.clone(), branch.depth))
+            .collect()
+    }
+
+    #[test]
+    fn test_read_stack() {
+        let temp = stacked_repo();
+        let stack = BranchStack::read(&Repository::new(temp.path())).unwrap();
+
+        assert_eq!(stack.trunk.as_deref(), Some("main"));
+        assert_eq!(
+            parents(&stack),
+            vec![
+                ("feature-a".to_string(), Some("main".to_string()), 0),
+                ("feature-b".to_string(), Some("feature-a".to_string()), 1),
+                ("feature-c".to_string(), Some("main".to_string()), 0),
+            ]
+        );
+        assert_eq!(stack.branches[0].ahead, 2);
+        assert!(stack.branches.iter().all(|branch| !branch.needs_restack()));
+        assert!(stack.restack_steps(Some("feature-c")).is_empty());
+    }
+
+    #[test]
+    fn test_restack_after_base_moves() {
+        let temp = stacked_repo();
+        let dir = temp.path();
+        git(dir, &["checkout", "-q", "main"]);
+        commit_file(dir, "main2.txt");
+        git(dir, &["checkout", "-q", "feature-a"]);
+        commit_file(dir, "a3.txt");
+        git(dir, &["checkout", "-q", "feature-c"]);
+
+        let stack = BranchStack::read(&Repository::new(dir)).unwrap();
+        let a = &stack.branches[0];
+        assert_eq!((a.name.as_str(), a.parent_moved, a.needs_restack()), ("feature-a", 1, true));
+        let b = &stack.branches[1];
+        assert_eq!((b.parent.as_deref(), b.ahead, b.parent_moved), (Some("feature-a"), 1, 1));
+
+        let steps = stack.restack_steps(Some("feature-c"));
+        let intents: Vec<&str> = steps.iter().map(|(intent, _)| intent.as_str()).collect();
+        assert_eq!(
+            intents,
+            vec![
+                "restack feature-a (3 commits) onto main",
+                "restack feature-b (1 commit) onto feature-a",
+                "restack feature-c (1 commit) onto main",
+                "return to feature-c",
+            ]
+        );
+        assert!(steps[1].1.starts_with("git rebase --onto feature-a "));
+        assert!(steps[1].1.ends_with(" feature-b"));
+
+        for (_, command) in &steps {
+            let args = crate::git::split_command(command).unwrap();
+            git(dir, &args[1..].iter().map(String::as_str).collect::<Vec<_>>());
+        }
+        let stack = BranchStack::read(&Repository::new(dir)).unwrap();
+        assert!(stack.branches.iter().all(|branch| !branch.needs_restack()));
+        assert_eq!(stack.branches[1].ahead, 1);
+    }
+
+    #[test]
+    fn test_stack_after_parent_rebased() {
+        let temp = stacked_repo();
+        let dir = temp.path();
+        git(dir, &["checkout", "-q", "main"]);
+        commit_file(dir, "main2.txt");
+        git(dir, &["checkout", "-q", "feature-a"]);
+        git(dir, &["rebase", "-q", "main"]);
+
+        // feature-b still holds the old copies of feature-a's commits
+        let stack = BranchStack::read(&Repository::new(dir)).unwrap();
+        let b = stack.branches.iter().find(|branch| branch.name == "feature-b").unwrap();
+        assert_eq!((b.parent.as_deref(), b.ahead), (Some("feature-a"), 1));
+        assert!(b.needs_restack());
+
+        let intents: Vec<String> = stack.restack_steps(None).into_iter().map(|(intent, _)| intent).collect();
+        assert_eq!(
+            intents,
+            vec!["restack feature-b (1 commit) onto feature-a", "restack feature-c (1 commit) onto main"]
+        );
+    }
+
+    #[test]
+    fn test_upstream_marks_parent() {
+        let temp = stacked_repo();
+        let dir = temp.path();
+        // History alone would put feature-d on feature-b
+        git(dir, &["checkout", "-q", "-b", "feature-d", "feature-b"]);
+        git(dir, &["branch", "-q", "--set-upstream-to", "feature-a"]);
+        commit_file(dir, "d1.txt");
+
+        let stack = BranchStack::read(&Repository::new(dir)).unwrap();
+        let d = stack.branches.iter().find(|branch| branch.name == "feature-d").unwrap();
+        assert_eq!((d.parent.as_deref(), d.depth), (Some("feature-a"), 1));
+    }
+}
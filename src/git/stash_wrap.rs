@@ -0,0 +1,57 @@
+use crate::git::executor::split_command;
+
+/// Sets tracked and untracked changes aside before the retried command
+pub const STASH_PUSH_COMMAND: &str = "git stash push --include-untracked";
+
+/// Reapplies the changes once the retried command succeeded
+pub const STASH_POP_COMMAND: &str = "git stash pop";
+
+/// Whether git refused an operation because it would overwrite local changes
+///
+/// Covers "Your local changes to the following files would be overwritten"
+/// and the untracked-files variant.
+pub fn blocked_by_local_changes(stderr: &str) -> bool {
+    stderr.to_lowercase().contains("would be overwritten by")
+}
+
+/// `command` wrapped in a stash push and pop, as (intent, command) steps
+///
+/// `None` for stash commands themselves, which wrapping can't help.
+pub fn stash_wrapped(command: &str) -> Option<Vec<(String, String)>> {
+    let words = split_command(command).ok()?;
+    let subcommand = words.iter().find(|w| *w != "git")?;
+    if subcommand == "stash" {
+        return None;
+    }
+
+    Some(vec![
+        ("set local changes aside".to_string(), STASH_PUSH_COMMAND.to_string()),
+        ("retry the blocked command".to_string(), command.to_string()),
+        ("reapply local changes".to_string(), STASH_POP_COMMAND.to_string()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocked_by_local_changes() {
+        assert!(blocked_by_local_changes(
+            "error: Your local changes to the following files would be overwritten by checkout:\n\tsrc/main.rs"
+        ));
+        assert!(blocked_by_local_changes(
+            "error: The following untracked working tree files would be overwritten by merge:"
+        ));
+        assert!(!blocked_by_local_changes("fatal: not a git repository"));
+    }
+
+    #[test]
+    fn test_stash_wrapped() {
+        let steps = stash_wrapped("git checkout main").unwrap();
+        let commands: Vec<&str> = steps.iter().map(|(_, c)| c.as_str()).collect();
+        assert_eq!(commands, vec![STASH_PUSH_COMMAND, "git checkout main", STASH_POP_COMMAND]);
+
+        assert!(stash_wrapped("git stash pop").is_none());
+    }
+}
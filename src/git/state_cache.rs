@@ -0,0 +1,158 @@
+use crate::error::GitResult;
+use crate::git::repository::{Repository, RepositoryState};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Latest `RepositoryState`, shared between the UI and context building
+///
+/// The UI publishes every state it loads for the repository panel; the LLM
+/// context reuses it while it is recent instead of running the same git
+/// commands again. Clones share the same slot.
+#[derive(Debug, Clone, Default)]
+pub struct StateCache {
+    inner: Arc<Mutex<Slot>>,
+}
+
+#[derive(Debug, Default)]
+struct Slot {
+    /// Bumped on every publish and invalidation
+    generation: u64,
+    entry: Option<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    repo: PathBuf,
+    state: Arc<RepositoryState>,
+    captured: Instant,
+}
+
+/// A cached state and the generation it was published in
+#[derive(Debug, Clone)]
+pub struct StateSnapshot {
+    pub state: Arc<RepositoryState>,
+    pub generation: u64,
+}
+
+impl StateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a freshly loaded state for `repo`
+    pub fn publish(&self, repo: &Path, state: RepositoryState) -> StateSnapshot {
+        let mut slot = self.inner.lock().unwrap();
+        slot.generation += 1;
+        let state = Arc::new(state);
+        slot.entry = Some(Entry {
+            repo: repo.to_path_buf(),
+            state: Arc::clone(&state),
+            captured: Instant::now(),
+        });
+        StateSnapshot {
+            state,
+            generation: slot.generation,
+        }
+    }
+
+    /// Forget the cached state, e.g. after running a command that changes it
+    pub fn invalidate(&self) {
+        let mut slot = self.inner.lock().unwrap();
+        slot.generation += 1;
+        slot.entry = None;
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.inner.lock().unwrap().generation
+    }
+
+    /// The cached state for `repo` if it is younger than `max_age`
+    pub fn fresh(&self, repo: &Path, max_age: Duration) -> Option<StateSnapshot> {
+        let slot = self.inner.lock().unwrap();
+        let entry = slot.entry.as_ref()?;
+        (entry.repo == repo && entry.captured.elapsed() < max_age).then(|| StateSnapshot {
+            state: Arc::clone(&entry.state),
+            generation: slot.generation,
+        })
+    }
+
+    /// The fresh cached state, or a newly loaded (and published) one
+    pub fn get_or_load(&self, repo: &Repository, max_age: Duration) -> GitResult<StateSnapshot> {
+        if let Some(snapshot) = self.fresh(repo.path(), max_age) {
+            return Ok(snapshot);
+        }
+        Ok(self.publish(repo.path(), repo.state()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+
+    fn state(branch: &str) -> RepositoryState {
+        RepositoryState {
+            current_branch: Some(branch.to_string()),
+            upstream: None,
+            staged_files: Vec::new(),
+            unstaged_files: Vec::new(),
+            untracked_files: Vec::new(),
+            recent_commits: Vec::new(),
+            stashes: Vec::new(),
+            in_merge: false,
+            in_rebase: false,
+            rebase: None,
+            second_upstream: None,
+            user_name: None,
+            user_email: None,
+            shallow_depth: None,
+            hidden_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_publish_and_reuse() {
+        let cache = StateCache::new();
+        let repo = Path::new("/work/repo");
+        assert!(cache.fresh(repo, Duration::from_secs(5)).is_none());
+
+        let published = cache.publish(repo, state("main"));
+        let shared = cache.clone();
+        let snapshot = shared.fresh(repo, Duration::from_secs(5)).unwrap();
+        assert_eq!(snapshot.generation, published.generation);
+        assert_eq!(snapshot.state.current_branch.as_deref(), Some("main"));
+
+        // Another repository's state is never handed out
+        assert!(cache.fresh(Path::new("/work/other"), Duration::from_secs(5)).is_none());
+    }
+
+    #[test]
+    fn test_stale_and_invalidated_states() {
+        let cache = StateCache::new();
+        let repo = Path::new("/work/repo");
+        cache.publish(repo, state("main"));
+        assert!(cache.fresh(repo, Duration::ZERO).is_none());
+
+        let generation = cache.generation();
+        cache.invalidate();
+        assert!(cache.generation() > generation);
+        assert!(cache.fresh(repo, Duration::from_secs(5)).is_none());
+    }
+
+    #[test]
+    fn test_get_or_load_queries_git_once() {
+        let temp = tempfile::TempDir::new().unwrap();
+        init_repo(temp.path());
+        let repo = Repository::new(temp.path());
+        let cache = StateCache::new();
+
+        let first = cache.get_or_load(&repo, Duration::from_secs(5)).unwrap();
+        let second = cache.get_or_load(&repo, Duration::from_secs(5)).unwrap();
+        assert_eq!(first.generation, second.generation);
+        assert!(Arc::ptr_eq(&first.state, &second.state));
+
+        let reloaded = cache.get_or_load(&repo, Duration::ZERO).unwrap();
+        assert!(reloaded.generation > first.generation);
+    }
+}
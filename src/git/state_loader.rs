@@ -0,0 +1,120 @@
+use crate::error::GitResult;
+use crate::git::repository::{Repository, RepositoryState, StateSection};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+/// Loads a repository's state on a background thread, one section at a time
+///
+/// Lets the UI draw its first frame straight away and fill in the panel as
+/// sections arrive, instead of blocking on `git status` in a large repository.
+pub struct StateLoader {
+    receiver: Receiver<GitResult<(StateSection, RepositoryState)>>,
+    /// Sections not received yet, in loading order
+    pending: Vec<StateSection>,
+}
+
+impl StateLoader {
+    pub fn spawn(repo: Repository) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut state = RepositoryState::default();
+            for section in StateSection::ALL {
+                let loaded = repo.load_section(&mut state, section).map(|_| (section, state.clone()));
+                let failed = loaded.is_err();
+                // The app may have quit or moved on to another repository
+                if sender.send(loaded).is_err() || failed {
+                    return;
+                }
+            }
+        });
+        Self {
+            receiver,
+            pending: StateSection::ALL.to_vec(),
+        }
+    }
+
+    /// The state with every section received so far, if any arrived since
+    /// the last call, or the error that stopped loading
+    pub fn poll(&mut self) -> Option<GitResult<RepositoryState>> {
+        let mut latest = None;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(Ok((section, state))) => {
+                    self.pending.retain(|pending| *pending != section);
+                    latest = Some(Ok(state));
+                }
+                Ok(Err(e)) => {
+                    self.pending.clear();
+                    return Some(Err(e));
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.pending.clear();
+                    break;
+                }
+            }
+        }
+        latest
+    }
+
+    /// Sections still loading
+    pub fn pending(&self) -> &[StateSection] {
+        &self.pending
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::git;
+    use std::time::{Duration, Instant};
+
+
+    #[test]
+    fn test_loads_every_section() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let dir = temp.path();
+        git(dir, &["init", "-q", "-b", "main"]);
+        git(dir, &["config", "user.email", "test@example.com"]);
+        git(dir, &["config", "user.name", "Test"]);
+        std::fs::write(dir.join("a.txt"), "a").unwrap();
+        git(dir, &["add", "a.txt"]);
+        git(dir, &["commit", "-q", "-m", "first"]);
+        std::fs::write(dir.join("b.txt"), "b").unwrap();
+
+        let repo = Repository::new(dir);
+        let mut loader = StateLoader::spawn(repo.clone());
+        assert_eq!(loader.pending(), StateSection::ALL);
+
+        let started = Instant::now();
+        let mut state = None;
+        while !loader.is_done() {
+            assert!(started.elapsed() < Duration::from_secs(10), "loading never finished");
+            if let Some(loaded) = loader.poll() {
+                state = Some(loaded.unwrap());
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(state.unwrap(), repo.state().unwrap());
+    }
+
+    #[test]
+    fn test_stops_at_first_error() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let mut loader = StateLoader::spawn(Repository::new(temp.path().join("missing")));
+
+        // The head section tolerates a missing repository; status does not
+        let started = Instant::now();
+        let mut failed = false;
+        while !loader.is_done() {
+            assert!(started.elapsed() < Duration::from_secs(10), "loading never finished");
+            failed |= matches!(loader.poll(), Some(Err(_)));
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(failed);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::git::executor::quote_arg;
+
+/// Phrases that ask to publish the current branch or set its upstream
+const UPSTREAM_PHRASES: &[&str] = &[
+    "set upstream",
+    "set the upstream",
+    "set-upstream",
+    "setup upstream",
+    "set up upstream",
+    "track remote",
+    "track the remote",
+    "publish branch",
+    "publish this branch",
+    "publish my branch",
+    "push new branch",
+    "push this new branch",
+    "push branch for the first time",
+    "no upstream",
+];
+
+/// Command that pushes a branch and sets it as the upstream
+pub fn push_upstream_command(remote: &str, branch: &str) -> String {
+    format!("git push -u {} {}", quote_arg(remote), quote_arg(branch))
+}
+
+/// Check whether a natural language query asks to set up the upstream branch
+///
+/// Matched queries are answered locally with [`push_upstream_command`], so the
+/// current branch and remote are filled in without a round trip to the LLM.
+pub fn is_upstream_setup_query(query: &str) -> bool {
+    let normalized = query
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    UPSTREAM_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_upstream_command() {
+        assert_eq!(
+            push_upstream_command("origin", "feature/login"),
+            "git push -u origin feature/login"
+        );
+        assert_eq!(
+            push_upstream_command("origin", "it's-mine"),
+            r"git push -u origin 'it'\''s-mine'"
+        );
+    }
+
+    #[test]
+    fn test_upstream_setup_queries() {
+        assert!(is_upstream_setup_query("set upstream for this branch"));
+        assert!(is_upstream_setup_query("Publish  this branch"));
+        assert!(is_upstream_setup_query("push new branch to the remote"));
+        assert!(is_upstream_setup_query("fix no upstream error"));
+
+        assert!(!is_upstream_setup_query("push my changes"));
+        assert!(!is_upstream_setup_query("show the upstream diff"));
+    }
+}
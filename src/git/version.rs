@@ -1,4 +1,6 @@
 use crate::error::{GitError, GitResult};
+use crate::git::executor::git_binary;
+use std::path::Path;
 use std::process::Command;
 
 /// Minimum required git version
@@ -13,17 +15,22 @@ pub struct GitVersion {
 }
 
 impl GitVersion {
-    /// Detect the installed git version
+    /// Detect the version of the configured git binary
     pub fn detect() -> GitResult<Self> {
-        let output = Command::new("git")
-            .arg("--version")
-            .output()
-            .map_err(|e| GitError::GitVersionDetectionFailed(format!("Failed to execute git: {}", e)))?;
+        Self::detect_at(git_binary())
+    }
+
+    /// Detect the version of the git at `binary`
+    pub fn detect_at(binary: &Path) -> GitResult<Self> {
+        let output = Command::new(binary).arg("--version").output().map_err(|e| {
+            GitError::GitVersionDetectionFailed(format!("Failed to execute {}: {}", binary.display(), e))
+        })?;
 
         if !output.status.success() {
-            return Err(GitError::GitVersionDetectionFailed(
-                "git --version command failed".to_string(),
-            ));
+            return Err(GitError::GitVersionDetectionFailed(format!(
+                "{} --version command failed",
+                binary.display()
+            )));
         }
 
         let version_string = String::from_utf8_lossy(&output.stdout);
@@ -83,14 +90,21 @@ impl GitVersion {
 
     /// Validate that git version is sufficient
     pub fn validate() -> GitResult<Self> {
-        let version = Self::detect()?;
+        Self::validate_at(git_binary())
+    }
+
+    /// Validate that the git at `binary` runs and is recent enough
+    pub fn validate_at(binary: &Path) -> GitResult<Self> {
+        let version = Self::detect_at(binary)?;
 
         if !version.is_supported() {
             return Err(GitError::GitVersionTooOld(format!(
-                "{}.{}.{}\n\nPlease upgrade git to version {}.{} or higher.\nVisit: https://git-scm.com/downloads",
+                "{}.{}.{} ({})\n\nPlease upgrade git to version {}.{} or higher, or point git.binary_path \
+                 at a newer one.\nVisit: https://git-scm.com/downloads",
                 version.major,
                 version.minor,
                 version.patch,
+                binary.display(),
                 MIN_GIT_VERSION.0,
                 MIN_GIT_VERSION.1
             )));
@@ -98,6 +112,11 @@ impl GitVersion {
 
         Ok(version)
     }
+
+    /// The oldest version gitalky supports, as "2.20"
+    pub fn minimum() -> String {
+        format!("{}.{}", MIN_GIT_VERSION.0, MIN_GIT_VERSION.1)
+    }
 }
 
 impl std::fmt::Display for GitVersion {
@@ -0,0 +1,342 @@
+use crate::config::NetworkConfig;
+use crate::security::{CommandOrigin, RiskLevel};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ApprovalError {
+    #[error("Approval endpoint unreachable: {0}")]
+    Unreachable(String),
+
+    #[error("Approval endpoint returned HTTP {0}")]
+    Status(u16),
+
+    #[error("Approval endpoint returned an invalid response: {0}")]
+    InvalidResponse(String),
+
+    #[error("No decision within {0}s")]
+    Timeout(u64),
+}
+
+/// `[approval]`: ask a team endpoint before running risky commands
+///
+/// ```toml
+/// [approval]
+/// url = "https://approvals.example.com/gitalky"
+/// min_risk = "high"
+/// token_env = "GITALKY_APPROVAL_TOKEN"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApprovalConfig {
+    /// Endpoint the approval request is POSTed to
+    pub url: String,
+    /// Dangerous operations at or above this risk need approval
+    #[serde(default = "default_min_risk")]
+    pub min_risk: RiskLevel,
+    /// Seconds to wait for a decision before giving up, which denies
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Seconds between polls while the request is pending
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Environment variable holding a bearer token for the endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_env: Option<String>,
+}
+
+fn default_min_risk() -> RiskLevel {
+    RiskLevel::High
+}
+
+fn default_timeout_secs() -> u64 {
+    300
+}
+
+fn default_poll_interval_secs() -> u64 {
+    5
+}
+
+impl ApprovalConfig {
+    pub fn applies_to(&self, risk: RiskLevel) -> bool {
+        risk >= self.min_risk
+    }
+
+    /// Reject settings the client can't work with
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("https://") && !self.url.starts_with("http://") {
+            return Err(format!("approval.url must be an http(s) URL, got '{}'", self.url));
+        }
+        if self.timeout_secs == 0 || self.poll_interval_secs == 0 {
+            return Err("approval.timeout_secs and poll_interval_secs must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// JSON document POSTed to the approval endpoint
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApprovalRequest {
+    pub command: String,
+    pub repo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub danger_type: String,
+    pub risk: RiskLevel,
+    pub origin: CommandOrigin,
+    pub user: String,
+}
+
+impl ApprovalRequest {
+    pub fn new(command: &str, repo: &Path, danger_type: String, risk: RiskLevel, origin: CommandOrigin) -> Self {
+        Self {
+            command: command.to_string(),
+            repo: repo.display().to_string(),
+            branch: None,
+            danger_type,
+            risk,
+            origin,
+            user: std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()),
+        }
+    }
+
+    pub fn with_branch(mut self, branch: Option<&str>) -> Self {
+        self.branch = branch.map(str::to_string);
+        self
+    }
+}
+
+/// What the endpoint answers, to the POST and to each poll
+///
+/// `pending` must come with a `poll_url`, which is fetched with GET until
+/// the decision is in; the endpoint may hold that request open (long-poll).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct ApprovalResponse {
+    decision: Decision,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    approver: Option<String>,
+    #[serde(default)]
+    poll_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Decision {
+    Allow,
+    Deny,
+    Pending,
+}
+
+/// Final answer of the approval endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalDecision {
+    pub allowed: bool,
+    pub approver: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl ApprovalDecision {
+    /// "Denied by alice: not during the release freeze"
+    pub fn describe(&self) -> String {
+        let mut text = if self.allowed { "Approved".to_string() } else { "Denied".to_string() };
+        if let Some(ref approver) = self.approver {
+            text.push_str(&format!(" by {}", approver));
+        }
+        if let Some(ref reason) = self.reason {
+            text.push_str(&format!(": {}", reason));
+        }
+        text
+    }
+}
+
+/// Sends approval requests and waits for the decision
+pub struct ApprovalClient {
+    config: ApprovalConfig,
+    http: reqwest::Client,
+}
+
+impl ApprovalClient {
+    pub fn new(config: &ApprovalConfig, network: &NetworkConfig) -> Result<Self, ApprovalError> {
+        let http = network
+            .apply(reqwest::Client::builder())
+            .map_err(|e| ApprovalError::Unreachable(e.to_string()))?
+            .build()
+            .map_err(|e| ApprovalError::Unreachable(e.to_string()))?;
+        Ok(Self { config: config.clone(), http })
+    }
+
+    /// POST the request, then poll until allowed, denied or `timeout_secs` pass
+    pub async fn request(&self, request: &ApprovalRequest) -> Result<ApprovalDecision, ApprovalError> {
+        let deadline = Instant::now() + Duration::from_secs(self.config.timeout_secs);
+        let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
+
+        let post = self.http.post(&self.config.url).json(request);
+        let mut response = self.send(post, deadline).await?;
+        loop {
+            let poll_url = match response.decision {
+                Decision::Allow | Decision::Deny => {
+                    return Ok(ApprovalDecision {
+                        allowed: response.decision == Decision::Allow,
+                        approver: response.approver,
+                        reason: response.reason,
+                    });
+                }
+                Decision::Pending => response.poll_url.ok_or_else(|| {
+                    ApprovalError::InvalidResponse("pending without a poll_url".to_string())
+                })?,
+            };
+
+            let started = Instant::now();
+            response = self.send(self.http.get(&poll_url), deadline).await?;
+            // A long-polling endpoint already waited; a quick one is asked again later
+            if response.decision == Decision::Pending {
+                let wait = poll_interval.saturating_sub(started.elapsed());
+                if Instant::now() + wait >= deadline {
+                    return Err(ApprovalError::Timeout(self.config.timeout_secs));
+                }
+                tokio::time::sleep(wait).await;
+            }
+        }
+    }
+
+    /// Ask the endpoint, failing closed: an unreachable, broken or silent
+    /// endpoint denies, with the error as the reason
+    pub async fn ask(config: &ApprovalConfig, network: &NetworkConfig, request: &ApprovalRequest) -> ApprovalDecision {
+        let result = match Self::new(config, network) {
+            Ok(client) => client.request(request).await,
+            Err(e) => Err(e),
+        };
+        result.unwrap_or_else(|e| ApprovalDecision {
+            allowed: false,
+            approver: None,
+            reason: Some(e.to_string()),
+        })
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, deadline: Instant) -> Result<ApprovalResponse, ApprovalError> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(ApprovalError::Timeout(self.config.timeout_secs));
+        }
+        let mut request = request.timeout(remaining);
+        if let Some(token) = self.config.token_env.as_deref().and_then(|name| std::env::var(name).ok()) {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                ApprovalError::Timeout(self.config.timeout_secs)
+            } else {
+                ApprovalError::Unreachable(crate::config::network::describe_network_error(&e))
+            }
+        })?;
+        if !response.status().is_success() {
+            return Err(ApprovalError::Status(response.status().as_u16()));
+        }
+        let body = response.text().await.map_err(|e| ApprovalError::Unreachable(e.to_string()))?;
+        serde_json::from_str(&body).map_err(|e| ApprovalError::InvalidResponse(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one canned JSON body per connection, in order; `bodies` gets
+    /// the server's URL so pending answers can poll it again
+    fn serve(bodies: impl FnOnce(&str) -> Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let bodies = bodies(&url);
+        std::thread::spawn(move || {
+            for body in bodies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0u8; 4096];
+                let _ = stream.read(&mut buffer);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+
+    fn config(url: &str) -> ApprovalConfig {
+        ApprovalConfig {
+            url: url.to_string(),
+            min_risk: RiskLevel::High,
+            timeout_secs: 5,
+            poll_interval_secs: 1,
+            token_env: None,
+        }
+    }
+
+    fn request() -> ApprovalRequest {
+        ApprovalRequest::new(
+            "git push --force",
+            Path::new("/repo"),
+            "ForcePush".to_string(),
+            RiskLevel::High,
+            CommandOrigin::User,
+        )
+        .with_branch(Some("main"))
+    }
+
+    #[test]
+    fn test_applies_to_and_validate() {
+        let config = config("https://approvals.example.com");
+        assert!(config.applies_to(RiskLevel::High));
+        assert!(!config.applies_to(RiskLevel::Medium));
+        assert!(config.validate().is_ok());
+        assert!(ApprovalConfig { url: "approvals".to_string(), ..config.clone() }.validate().is_err());
+        assert!(ApprovalConfig { poll_interval_secs: 0, ..config }.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pending_then_denied() {
+        let url = serve(|url| {
+            let pending = format!(r#"{{"decision": "pending", "poll_url": "{}/requests/1"}}"#, url);
+            vec![
+                pending.clone(),
+                pending,
+                r#"{"decision": "deny", "approver": "alice", "reason": "release freeze"}"#.to_string(),
+            ]
+        });
+        let client = ApprovalClient::new(&config(&url), &NetworkConfig::default()).unwrap();
+        let decision = client.request(&request()).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.describe(), "Denied by alice: release freeze");
+    }
+
+    #[tokio::test]
+    async fn test_allowed_and_failures() {
+        let url = serve(|_| vec![r#"{"decision": "allow"}"#.to_string(), r#"{"decision": "pending"}"#.to_string()]);
+        let client = ApprovalClient::new(&config(&url), &NetworkConfig::default()).unwrap();
+        assert_eq!(client.request(&request()).await.unwrap().describe(), "Approved");
+        assert!(matches!(client.request(&request()).await, Err(ApprovalError::InvalidResponse(_))));
+
+        // Nothing listens on port 1
+        let unreachable = config("http://127.0.0.1:1");
+        let client = ApprovalClient::new(&unreachable, &NetworkConfig::default()).unwrap();
+        assert!(matches!(client.request(&request()).await, Err(ApprovalError::Unreachable(_))));
+        let decision = ApprovalClient::ask(&unreachable, &NetworkConfig::default(), &request()).await;
+        assert!(!decision.allowed);
+        assert!(decision.describe().starts_with("Denied: Approval endpoint unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_times_out_while_pending() {
+        let url = serve(|url| vec![format!(r#"{{"decision": "pending", "poll_url": "{}"}}"#, url); 3]);
+        let config = ApprovalConfig { timeout_secs: 1, poll_interval_secs: 1, ..config(&url) };
+        let client = ApprovalClient::new(&config, &NetworkConfig::default()).unwrap();
+        assert!(matches!(client.request(&request()).await, Err(ApprovalError::Timeout(1))));
+    }
+}
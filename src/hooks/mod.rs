@@ -0,0 +1,9 @@
+pub mod approval;
+pub mod runner;
+
+pub use approval::{ApprovalClient, ApprovalConfig, ApprovalDecision, ApprovalError, ApprovalRequest};
+
+pub use runner::{
+    HookConfig, HookError, HookEvent, HookOutcome, HookPayload, HookResponse, HookRunner,
+    HookValidation,
+};
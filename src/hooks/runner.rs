@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Failed to run hook '{0}': {1}")]
+    SpawnFailed(String, std::io::Error),
+
+    #[error("Hook '{0}' timed out after {1}ms")]
+    Timeout(String, u64),
+
+    #[error("Hook '{0}' returned invalid JSON: {1}")]
+    InvalidResponse(String, serde_json::Error),
+}
+
+/// Points in the command lifecycle where hooks run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// Before a natural language query is sent to the LLM
+    PreTranslate,
+    /// After the LLM proposed a command, before it is previewed
+    PostTranslate,
+    /// After validation, before the command runs
+    PreExecute,
+    /// After the command finished
+    PostExecute,
+}
+
+/// A user-configured hook executable (`[[hooks]]` in config.toml)
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Veto the command when the hook itself fails (crash, timeout, bad output)
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_timeout_ms() -> u64 {
+    2000
+}
+
+/// Validation result passed to pre-execute hooks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HookValidation {
+    pub is_dangerous: bool,
+    pub danger_type: Option<String>,
+}
+
+/// JSON document written to a hook's stdin
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HookPayload {
+    pub event: HookEvent,
+    pub repo: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<HookValidation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+}
+
+impl HookPayload {
+    pub fn new(event: HookEvent, repo: &Path) -> Self {
+        Self {
+            event,
+            repo: repo.display().to_string(),
+            query: None,
+            command: None,
+            validation: None,
+            exit_code: None,
+        }
+    }
+
+    pub fn with_query(mut self, query: &str) -> Self {
+        self.query = Some(query.to_string());
+        self
+    }
+
+    pub fn with_command(mut self, command: &str) -> Self {
+        self.command = Some(command.to_string());
+        self
+    }
+
+    pub fn with_validation(mut self, validation: HookValidation) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
+    pub fn with_exit_code(mut self, exit_code: i32) -> Self {
+        self.exit_code = Some(exit_code);
+        self
+    }
+}
+
+/// Optional JSON document a hook prints to stdout
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HookResponse {
+    #[serde(default)]
+    pub veto: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub annotation: Option<String>,
+}
+
+/// Combined result of all hooks for one event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookOutcome {
+    /// Reason the command was vetoed, if any hook vetoed it
+    pub veto: Option<String>,
+    /// Notes from hooks to show the user
+    pub annotations: Vec<String>,
+}
+
+impl HookOutcome {
+    pub fn is_vetoed(&self) -> bool {
+        self.veto.is_some()
+    }
+}
+
+/// Runs configured hook executables at lifecycle events
+///
+/// Each hook receives a [`HookPayload`] as JSON on stdin. A hook vetoes by
+/// exiting non-zero or printing `{"veto": true, "reason": "..."}`; it may add
+/// an `annotation` shown to the user. Empty output means "allow".
+#[derive(Debug, Clone, Default)]
+pub struct HookRunner {
+    hooks: Vec<HookConfig>,
+}
+
+impl HookRunner {
+    pub fn new(hooks: Vec<HookConfig>) -> Self {
+        Self { hooks }
+    }
+
+    /// Check if any hooks are configured for an event
+    pub fn has_hooks(&self, event: HookEvent) -> bool {
+        self.hooks.iter().any(|h| h.event == event)
+    }
+
+    /// Run every hook for the payload's event, stopping at the first veto
+    pub async fn run(&self, payload: &HookPayload) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+
+        for hook in self.hooks.iter().filter(|h| h.event == payload.event) {
+            match Self::run_hook(hook, payload).await {
+                Ok(response) => {
+                    if let Some(annotation) = response.annotation {
+                        outcome.annotations.push(annotation);
+                    }
+                    if response.veto {
+                        outcome.veto = Some(
+                            response
+                                .reason
+                                .unwrap_or_else(|| format!("Vetoed by hook '{}'", hook.command)),
+                        );
+                        break;
+                    }
+                }
+                Err(e) if hook.required => {
+                    outcome.veto = Some(e.to_string());
+                    break;
+                }
+                Err(e) => outcome.annotations.push(e.to_string()),
+            }
+        }
+
+        outcome
+    }
+
+    async fn run_hook(hook: &HookConfig, payload: &HookPayload) -> Result<HookResponse, HookError> {
+        let input = serde_json::to_vec(payload)
+            .map_err(|e| HookError::InvalidResponse(hook.command.clone(), e))?;
+
+        let mut child = Command::new(&hook.command)
+            .args(&hook.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| HookError::SpawnFailed(hook.command.clone(), e))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its input may close stdin early; that's fine
+            let _ = stdin.write_all(&input).await;
+        }
+
+        let output = tokio::time::timeout(
+            Duration::from_millis(hook.timeout_ms),
+            child.wait_with_output(),
+        )
+        .await
+        .map_err(|_| HookError::Timeout(hook.command.clone(), hook.timeout_ms))?
+        .map_err(|e| HookError::SpawnFailed(hook.command.clone(), e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut response = if stdout.trim().is_empty() {
+            HookResponse::default()
+        } else {
+            serde_json::from_str(stdout.trim())
+                .map_err(|e| HookError::InvalidResponse(hook.command.clone(), e))?
+        };
+
+        // Non-zero exit always vetoes, with stderr as the reason if none was given
+        if !output.status.success() {
+            response.veto = true;
+            if response.reason.is_none() {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                if !stderr.is_empty() {
+                    response.reason = Some(stderr);
+                }
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh_hook(event: HookEvent, script: &str) -> HookConfig {
+        HookConfig {
+            event,
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_ms: default_timeout_ms(),
+            required: false,
+        }
+    }
+
+    fn payload(event: HookEvent) -> HookPayload {
+        HookPayload::new(event, Path::new("/tmp/repo")).with_command("git push origin main")
+    }
+
+    #[test]
+    fn test_parse_hook_config() {
+        let toml = r#"
+            event = "pre-execute"
+            command = "/usr/local/bin/policy"
+        "#;
+        let hook: HookConfig = toml::from_str(toml).unwrap();
+        assert_eq!(hook.event, HookEvent::PreExecute);
+        assert_eq!(hook.timeout_ms, 2000);
+        assert!(hook.args.is_empty());
+        assert!(!hook.required);
+    }
+
+    #[test]
+    fn test_payload_json() {
+        let payload = payload(HookEvent::PreExecute).with_validation(HookValidation {
+            is_dangerous: false,
+            danger_type: None,
+        });
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["event"], "pre-execute");
+        assert_eq!(json["command"], "git push origin main");
+        assert_eq!(json["validation"]["is_dangerous"], false);
+        assert!(json.get("query").is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_empty_output_allows() {
+        let runner = HookRunner::new(vec![sh_hook(HookEvent::PreExecute, "cat > /dev/null")]);
+        let outcome = runner.run(&payload(HookEvent::PreExecute)).await;
+        assert_eq!(outcome, HookOutcome::default());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_json_veto_and_annotation() {
+        let runner = HookRunner::new(vec![sh_hook(
+            HookEvent::PreExecute,
+            r#"echo '{"veto": true, "reason": "no pushes on Friday", "annotation": "policy"}'"#,
+        )]);
+        let outcome = runner.run(&payload(HookEvent::PreExecute)).await;
+        assert_eq!(outcome.veto.as_deref(), Some("no pushes on Friday"));
+        assert_eq!(outcome.annotations, vec!["policy".to_string()]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_nonzero_exit_vetoes_with_stderr() {
+        let runner = HookRunner::new(vec![sh_hook(
+            HookEvent::PreExecute,
+            "echo 'blocked by policy' >&2; exit 1",
+        )]);
+        let outcome = runner.run(&payload(HookEvent::PreExecute)).await;
+        assert_eq!(outcome.veto.as_deref(), Some("blocked by policy"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hook_receives_payload() {
+        let runner = HookRunner::new(vec![sh_hook(
+            HookEvent::PreExecute,
+            r#"grep -q '"command":"git push origin main"' || exit 1"#,
+        )]);
+        let outcome = runner.run(&payload(HookEvent::PreExecute)).await;
+        assert!(!outcome.is_vetoed());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_only_matching_event_runs() {
+        let runner = HookRunner::new(vec![sh_hook(HookEvent::PostExecute, "exit 1")]);
+        assert!(runner.has_hooks(HookEvent::PostExecute));
+        assert!(!runner.has_hooks(HookEvent::PreExecute));
+
+        let outcome = runner.run(&payload(HookEvent::PreExecute)).await;
+        assert!(!outcome.is_vetoed());
+    }
+
+    #[tokio::test]
+    async fn test_failed_hook_only_vetoes_when_required() {
+        let mut hook = HookConfig {
+            event: HookEvent::PreTranslate,
+            command: "/nonexistent/gitalky-hook".to_string(),
+            args: Vec::new(),
+            timeout_ms: default_timeout_ms(),
+            required: false,
+        };
+        let payload = HookPayload::new(HookEvent::PreTranslate, Path::new("/tmp")).with_query("q");
+
+        let outcome = HookRunner::new(vec![hook.clone()]).run(&payload).await;
+        assert!(!outcome.is_vetoed());
+        assert_eq!(outcome.annotations.len(), 1);
+
+        hook.required = true;
+        let outcome = HookRunner::new(vec![hook]).run(&payload).await;
+        assert!(outcome.is_vetoed());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_timeout() {
+        let mut hook = sh_hook(HookEvent::PreExecute, "sleep 5");
+        hook.timeout_ms = 100;
+        hook.required = true;
+        let outcome = HookRunner::new(vec![hook]).run(&payload(HookEvent::PreExecute)).await;
+        assert!(outcome.veto.unwrap().contains("timed out"));
+    }
+}
@@ -1,15 +1,24 @@
 pub mod audit;
+pub mod cli;
 pub mod config;
 pub mod error;
 pub mod error_translation;
 pub mod git;
+pub mod hooks;
 pub mod llm;
+pub mod notify;
 pub mod security;
+pub mod server;
+pub mod telemetry;
 pub mod ui;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-export commonly used types for convenience
 pub use audit::AuditLogger;
 pub use error::{AppError, AppResult, GitError, GitResult};
-pub use error_translation::{ErrorTranslator, UserFriendlyError};
+pub use error_translation::{ErrorRules, ErrorTranslator, UserFriendlyError};
 pub use git::{GitVersion, Repository, RepositoryState};
-pub use security::{CommandValidator, DangerousOp, ValidatedCommand, ValidationError};
+pub use hooks::{HookEvent, HookRunner};
+pub use security::{CommandOrigin, CommandValidator, DangerousOp, ValidatedCommand, ValidationError};
@@ -0,0 +1,92 @@
+use crate::llm::client::GitCommand;
+
+/// Candidate commands offered for a vague query
+pub const MAX_ALTERNATIVES: usize = 2;
+
+/// Queries longer than this are specific enough for one answer
+const MAX_VAGUE_WORDS: usize = 5;
+
+/// Phrases that read several ways: "clean this up" may mean discarding
+/// changes, deleting untracked files or pruning merged branches
+const VAGUE_PHRASES: &[&str] = &[
+    "clean up",
+    "clean this",
+    "clean it",
+    "cleanup",
+    "tidy",
+    "fix this",
+    "fix it",
+    "fix that",
+    "undo",
+    "start over",
+    "start fresh",
+    "get rid of",
+    "sort out",
+    "mess",
+    "go back",
+];
+
+/// Appended to the translation instructions when asking for alternatives
+pub const ALTERNATIVES_INSTRUCTIONS: &str = "ALTERNATIVES (this replaces the one-line format above):
+- The query can be read more than one way; answer with the two most likely readings
+- The two commands must do different things
+- Use exactly this format, nothing else:
+COMMAND: <first git command>
+WHY: <one short sentence: what it does and when to pick it>
+COMMAND: <second git command>
+WHY: <one short sentence: what it does and when to pick it>";
+
+/// Check whether a natural language query is vague enough to offer alternatives for
+pub fn is_vague_query(query: &str) -> bool {
+    let normalized = query.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" ");
+    normalized.split(' ').count() <= MAX_VAGUE_WORDS && VAGUE_PHRASES.iter().any(|phrase| normalized.contains(phrase))
+}
+
+/// Parse `COMMAND:`/`WHY:` pairs; lines that aren't git commands are dropped
+pub fn parse_alternatives(response: &str) -> Vec<GitCommand> {
+    let mut options: Vec<GitCommand> = Vec::new();
+    for line in response.lines().map(str::trim) {
+        if let Some(command) = line.strip_prefix("COMMAND:") {
+            let command = command.trim().trim_matches('`').trim();
+            if command.starts_with("git ") {
+                options.push(GitCommand {
+                    command: command.to_string(),
+                    explanation: None,
+                });
+            }
+        } else if let Some(why) = line.strip_prefix("WHY:")
+            && let Some(last) = options.last_mut()
+            && last.explanation.is_none()
+        {
+            last.explanation = Some(why.trim().to_string());
+        }
+    }
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_vague_query() {
+        assert!(is_vague_query("clean this up"));
+        assert!(is_vague_query("Undo that"));
+        assert!(is_vague_query("fix this mess"));
+        assert!(!is_vague_query("show me the log"));
+        assert!(!is_vague_query("undo the last commit but keep the changes staged"));
+    }
+
+    #[test]
+    fn test_parse_alternatives() {
+        let options = parse_alternatives(
+            "COMMAND: git clean -n\nWHY: Lists untracked files that would be deleted.\n\n\
+             COMMAND: `git restore .`\nWHY: Discards unstaged edits to tracked files.\nCOMMAND: rm -rf x\n",
+        );
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].command, "git clean -n");
+        assert_eq!(options[0].explanation.as_deref(), Some("Lists untracked files that would be deleted."));
+        assert_eq!(options[1].command, "git restore .");
+        assert!(parse_alternatives("git status").is_empty());
+    }
+}
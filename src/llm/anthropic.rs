@@ -1,33 +1,87 @@
+use crate::llm::alternatives::{ALTERNATIVES_INSTRUCTIONS, parse_alternatives};
 use crate::llm::client::{GitCommand, LLMClient, LLMError};
 use crate::llm::context::RepoContext;
+use crate::llm::models::{ModelCheck, check_model};
 use async_trait::async_trait;
-use reqwest::Client;
+use crate::config::NetworkConfig;
+use crate::config::settings::ConfigError;
+use reqwest::{Client, ClientBuilder};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_MODELS_URL: &str = "https://api.anthropic.com/v1/models";
 const DEFAULT_MODEL: &str = "claude-sonnet-4-5-20250929";
 const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 1000;
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 // Rate limiting: 10 requests per minute
 const RATE_LIMIT_REQUESTS: usize = 10;
 const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+/// Requests in each window that live suggestions leave for real translations
+const SUGGEST_RESERVED_REQUESTS: usize = 3;
+/// Suggestions are one short line
+const SUGGEST_MAX_TOKENS: u32 = 64;
 
 #[derive(Serialize)]
 struct AnthropicRequest {
     model: String,
     max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
     messages: Vec<Message>,
 }
 
+/// Sampling settings sent with every request
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationParams {
+    pub max_tokens: u32,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    /// Replaces the built-in translation instructions
+    pub system_prompt: Option<String>,
+}
+
+impl Default for GenerationParams {
+    fn default() -> Self {
+        Self {
+            max_tokens: 1024,
+            temperature: None,
+            top_p: None,
+            system_prompt: None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 struct Message {
     role: String,
     content: String,
 }
 
+impl Message {
+    fn user(content: String) -> Self {
+        Self {
+            role: "user".to_string(),
+            content,
+        }
+    }
+
+    fn assistant(content: String) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct AnthropicResponse {
     content: Vec<ContentBlock>,
@@ -38,12 +92,23 @@ struct ContentBlock {
     text: String,
 }
 
+#[derive(Deserialize)]
+struct ModelsResponse {
+    data: Vec<ModelInfo>,
+}
+
+#[derive(Deserialize)]
+struct ModelInfo {
+    id: String,
+}
+
 pub struct AnthropicClient {
     api_key: String,
     model: String,
+    generation: GenerationParams,
     http_client: Client,
-    // Rate limiting: track request timestamps
-    request_times: Mutex<Vec<Instant>>,
+    // Rate limiting: track request timestamps, shared with the suggestion client
+    request_times: Arc<Mutex<Vec<Instant>>>,
 }
 
 impl AnthropicClient {
@@ -52,22 +117,114 @@ impl AnthropicClient {
     }
 
     pub fn with_model(api_key: String, model: String) -> Self {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
         Self {
             api_key,
             model,
-            http_client,
-            request_times: Mutex::new(Vec::new()),
+            generation: GenerationParams::default(),
+            http_client: Self::client_builder(DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+            request_times: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// A client for live suggestions on a cheaper `model`
+    ///
+    /// Shares the HTTP client and the rate limiter with this one, so
+    /// suggestions and translations count against the same budget.
+    pub fn suggestion_client(&self, model: String) -> Self {
+        Self {
+            api_key: self.api_key.clone(),
+            model,
+            generation: GenerationParams {
+                max_tokens: SUGGEST_MAX_TOKENS,
+                temperature: Some(0.0),
+                top_p: None,
+                system_prompt: None,
+            },
+            http_client: self.http_client.clone(),
+            request_times: Arc::clone(&self.request_times),
         }
     }
 
+    /// Limit connecting and whole requests separately, and apply the
+    /// `[network]` proxy and CA settings
+    pub fn with_network(
+        mut self,
+        connect: Duration,
+        request: Duration,
+        network: &NetworkConfig,
+    ) -> Result<Self, ConfigError> {
+        self.http_client = network
+            .apply(Self::client_builder(connect, request))?
+            .build()
+            .map_err(|e| ConfigError::InvalidValue(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(self)
+    }
+
+    pub fn with_generation(mut self, generation: GenerationParams) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    fn client_builder(connect: Duration, request: Duration) -> ClientBuilder {
+        Client::builder()
+            .connect_timeout(connect.min(request))
+            .timeout(request)
+    }
+
+    /// Model ids available to this API key, newest first
+    pub async fn list_models(&self) -> Result<Vec<String>, LLMError> {
+        let response = self
+            .http_client
+            .get(ANTHROPIC_MODELS_URL)
+            .query(&[("limit", "100")])
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(LLMError::ApiError(format!(
+                "API returned status {}: {}",
+                status, error_text
+            )));
+        }
+
+        let models: ModelsResponse = response.json().await?;
+        Ok(models.data.into_iter().map(|model| model.id).collect())
+    }
+
+    /// Error for a failed request, naming the model when the API rejected it
+    fn api_error(&self, status: reqwest::StatusCode, error_text: &str) -> LLMError {
+        let model_rejected = matches!(status.as_u16(), 400 | 404)
+            && error_text.to_lowercase().contains("model");
+        if model_rejected {
+            let hint = match check_model(&self.model, &[]) {
+                ModelCheck::Unknown { suggestion: Some(id) } => {
+                    format!("did you mean '{}'?", id)
+                }
+                _ => "check llm.model in config.toml".to_string(),
+            };
+            return LLMError::ApiError(format!(
+                "The API rejected model '{}'; {} (status {}: {})",
+                self.model, hint, status, error_text
+            ));
+        }
+        LLMError::ApiError(format!("API returned status {}: {}", status, error_text))
+    }
+
     /// Check and enforce rate limiting
     /// Returns Ok(()) if request is allowed, Err with wait time if rate limited
     fn check_rate_limit(&self) -> Result<(), LLMError> {
+        self.check_rate_limit_reserving(0)
+    }
+
+    /// Like `check_rate_limit`, but refuse while fewer than `reserve`
+    /// requests would be left in the window afterwards
+    fn check_rate_limit_reserving(&self, reserve: usize) -> Result<(), LLMError> {
         let now = Instant::now();
         let mut times = self.request_times.lock().unwrap();
 
@@ -75,7 +232,7 @@ impl AnthropicClient {
         times.retain(|&time| now.duration_since(time) < RATE_LIMIT_WINDOW);
 
         // Check if we've exceeded the rate limit
-        if times.len() >= RATE_LIMIT_REQUESTS {
+        if times.len() + reserve >= RATE_LIMIT_REQUESTS {
             let oldest = times[0];
             let wait_time = RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(oldest));
             return Err(LLMError::RateLimitExceeded(wait_time.as_secs()));
@@ -86,14 +243,12 @@ impl AnthropicClient {
         Ok(())
     }
 
-    async fn call_api(&self, prompt: &str, context: &str) -> Result<String, LLMError> {
-        let full_prompt = format!(
-            "You are a git command expert. Translate the user's natural language query into a git command.
-
-Repository Context:
-{}
-
-User Query: {}
+    /// Instructions for translating a query; `system_prompt` overrides them
+    fn translation_system(&self) -> String {
+        if let Some(ref system) = self.generation.system_prompt {
+            return system.clone();
+        }
+        "You are a git command expert. Translate the user's natural language query into a git command.
 
 CRITICAL INSTRUCTIONS:
 - Respond with ONLY the git command itself
@@ -104,6 +259,11 @@ CRITICAL INSTRUCTIONS:
 - Example good response: git status
 - Example bad response: ```bash\\ngit status\\n```
 
+MISSING INFORMATION:
+- If the query leaves out a value the command needs (a branch name, a commit message, a remote), don't guess it
+- Put a placeholder in its place instead: a short name in angle brackets, like <branch-name> or <message>
+- Example: User: \"make a new branch\" → git checkout -b <branch-name>
+
 FILE PATH MATCHING:
 - When the user mentions a file name, look at the repository files in the context
 - Use fuzzy matching to find the correct file path
@@ -113,20 +273,90 @@ FILE PATH MATCHING:
 - Examples:
   * User: \"add input.rs\" → git add src/ui/input.rs (if that's the only input.rs)
   * User: \"stage app.rs\" → git add src/ui/app.rs (if that's in the file list)
-  * User: \"add main\" → git add src/main.rs (if that's in the file list)
+  * User: \"add main\" → git add src/main.rs (if that's in the file list)"
+            .to_string()
+    }
+
+    fn translation_prompt(prompt: &str, context: &str) -> String {
+        format!(
+            "Repository Context:
+{}
+
+User Query: {}
 
 Your response:",
             context, prompt
-        );
+        )
+    }
+
+    async fn call_api(&self, prompt: &str, context: &str) -> Result<String, LLMError> {
+        self.send_messages(
+            self.translation_system(),
+            vec![Message::user(Self::translation_prompt(prompt, context))],
+        )
+        .await
+    }
 
-        let request_body = AnthropicRequest {
+    /// Follow-up message telling the model why its command was rejected
+    fn repair_prompt(reason: &str) -> String {
+        format!(
+            "That command was rejected: {}
+
+Respond again with a single plain git command on one line: no shell operators, \
+pipes, redirects, markdown or explanation.
+
+Your response:",
+            reason
+        )
+    }
+
+    async fn explain_command(&self, command: &str) -> Result<String, LLMError> {
+        let system = "You are a git teacher. Explain git commands for a beginner who \
+copied them from the internet and wants to know what they do before running them.
+
+INSTRUCTIONS:
+- First line: one sentence describing what the command does overall
+- Then one line per flag or argument in the form: <flag>  <what it does>
+- Mention clearly if anything is destructive or hard to undo
+- Plain text only, no markdown, at most 10 lines";
+        let prompt = format!("Command: {}\n\nYour explanation:", command);
+
+        let response = self
+            .send_messages(system.to_string(), vec![Message::user(prompt)])
+            .await?;
+        Ok(response.trim().to_string())
+    }
+
+    /// Short instructions for live suggestions; a full translation follows on Enter
+    fn suggestion_system() -> String {
+        "Translate the user's request into a single git command. \
+Respond with only the command on one line, no markdown or explanation."
+            .to_string()
+    }
+
+    fn request(&self, system: String, messages: Vec<Message>) -> AnthropicRequest {
+        AnthropicRequest {
             model: self.model.clone(),
-            max_tokens: 1024,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: full_prompt,
-            }],
-        };
+            max_tokens: self.generation.max_tokens,
+            system: Some(system),
+            temperature: self.generation.temperature,
+            top_p: self.generation.top_p,
+            messages,
+        }
+    }
+
+    /// Send a conversation to the API with retry on rate limiting
+    async fn send_messages(&self, system: String, messages: Vec<Message>) -> Result<String, LLMError> {
+        self.send_messages_with_attempts(system, messages, MAX_RETRIES).await
+    }
+
+    async fn send_messages_with_attempts(
+        &self,
+        system: String,
+        messages: Vec<Message>,
+        max_attempts: u32,
+    ) -> Result<String, LLMError> {
+        let request_body = self.request(system, messages);
 
         let mut attempt = 0;
         let mut backoff_ms = INITIAL_BACKOFF_MS;
@@ -165,26 +395,20 @@ Your response:",
                     .and_then(|v| v.parse::<u64>().ok())
                     .unwrap_or(60);
 
-                if attempt >= MAX_RETRIES {
+                if attempt >= max_attempts {
                     return Err(LLMError::RateLimitExceeded(retry_after));
                 }
 
                 // Exponential backoff with retry-after
                 let wait_ms = retry_after.saturating_mul(1000).max(backoff_ms);
-                eprintln!(
-                    "Rate limited, retrying in {}ms (attempt {}/{})",
-                    wait_ms, attempt, MAX_RETRIES
-                );
+                tracing::warn!(wait_ms, attempt, max = MAX_RETRIES, "rate limited, retrying");
 
                 tokio::time::sleep(Duration::from_millis(wait_ms)).await;
                 backoff_ms *= 2;
                 continue;
             } else {
                 let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-                return Err(LLMError::ApiError(format!(
-                    "API returned status {}: {}",
-                    status, error_text
-                )));
+                return Err(self.api_error(status, &error_text));
             }
         }
     }
@@ -198,9 +422,64 @@ impl LLMClient for AnthropicClient {
 
         let context_str = context.get_full_context();
         let response = self.call_api(query, &context_str).await?;
+        Self::parse_command(&response)
+    }
+
+    async fn repair(
+        &self,
+        query: &str,
+        context: &RepoContext,
+        rejected: &str,
+        reason: &str,
+    ) -> Result<GitCommand, LLMError> {
+        self.check_rate_limit()?;
+
+        // Replay the first turn so the model sees its own rejected answer
+        let messages = vec![
+            Message::user(Self::translation_prompt(query, &context.get_full_context())),
+            Message::assistant(rejected.to_string()),
+            Message::user(Self::repair_prompt(reason)),
+        ];
+        let response = self.send_messages(self.translation_system(), messages).await?;
+        Self::parse_command(&response)
+    }
+
+    async fn alternatives(&self, query: &str, context: &RepoContext) -> Result<Vec<GitCommand>, LLMError> {
+        self.check_rate_limit()?;
+
+        // One request for both readings, not one per candidate
+        let system = format!("{}\n\n{}", self.translation_system(), ALTERNATIVES_INSTRUCTIONS);
+        let prompt = Self::translation_prompt(query, &context.get_full_context());
+        let response = self.send_messages(system, vec![Message::user(prompt)]).await?;
+        let options = parse_alternatives(&response);
+        if options.is_empty() {
+            return Err(LLMError::InvalidResponse(format!("No alternatives in response: {}", response.trim())));
+        }
+        Ok(options)
+    }
 
+    async fn explain(&self, command: &str) -> Result<String, LLMError> {
+        self.check_rate_limit()?;
+        self.explain_command(command).await
+    }
+
+    async fn suggest(&self, query: &str, context: &RepoContext) -> Result<GitCommand, LLMError> {
+        self.check_rate_limit_reserving(SUGGEST_RESERVED_REQUESTS)?;
+
+        // Default context only and no retries: a stale suggestion is worthless
+        let prompt = Self::translation_prompt(query, &context.default_info);
+        let response = self
+            .send_messages_with_attempts(Self::suggestion_system(), vec![Message::user(prompt)], 1)
+            .await?;
+        Self::parse_command(&response)
+    }
+}
+
+impl AnthropicClient {
+    /// Extract the git command from a raw response
+    fn parse_command(response: &str) -> Result<GitCommand, LLMError> {
         // Clean up response - strip markdown, extra whitespace, etc.
-        let command = Self::clean_response(&response);
+        let command = Self::clean_response(response);
 
         // Basic validation: should start with "git" or be a git subcommand
         if !command.starts_with("git ") && !Self::is_git_subcommand(&command) {
@@ -215,9 +494,7 @@ impl LLMClient for AnthropicClient {
             explanation: None,
         })
     }
-}
 
-impl AnthropicClient {
     /// Clean up LLM response to extract just the git command
     fn clean_response(response: &str) -> String {
         let mut cleaned = response.trim();
@@ -327,6 +604,74 @@ mod tests {
         assert!(matches!(result.unwrap_err(), LLMError::RateLimitExceeded(_)));
     }
 
+    #[test]
+    fn test_request_uses_system_and_sampling_params() {
+        let client = AnthropicClient::new("test-key".to_string()).with_generation(GenerationParams {
+            max_tokens: 200,
+            temperature: Some(0.0),
+            top_p: None,
+            system_prompt: None,
+        });
+        let prompt = AnthropicClient::translation_prompt("show changes", "Branch: main");
+        let request = client.request(client.translation_system(), vec![Message::user(prompt)]);
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["max_tokens"], 200);
+        assert_eq!(json["temperature"], 0.0);
+        assert!(json.get("top_p").is_none());
+        assert!(json["system"].as_str().unwrap().starts_with("You are a git command expert"));
+        let user = json["messages"][0]["content"].as_str().unwrap();
+        assert!(user.contains("User Query: show changes"));
+        assert!(!user.contains("CRITICAL INSTRUCTIONS"));
+    }
+
+    #[test]
+    fn test_system_prompt_override() {
+        let client = AnthropicClient::new("test-key".to_string()).with_generation(GenerationParams {
+            system_prompt: Some("Reply with one git command.".to_string()),
+            ..GenerationParams::default()
+        });
+        assert_eq!(client.translation_system(), "Reply with one git command.");
+    }
+
+    #[test]
+    fn test_api_error_names_rejected_model() {
+        let client = AnthropicClient::with_model("test-key".to_string(), "claude-sonet-4-20250514".to_string());
+        let body = r#"{"type":"error","error":{"type":"not_found_error","message":"model: claude-sonet-4-20250514"}}"#;
+        let err = client.api_error(reqwest::StatusCode::NOT_FOUND, body).to_string();
+        assert!(err.contains("rejected model 'claude-sonet-4-20250514'"));
+        assert!(err.contains("did you mean 'claude-sonnet-4-20250514'?"));
+
+        let err = client
+            .api_error(reqwest::StatusCode::INTERNAL_SERVER_ERROR, "overloaded")
+            .to_string();
+        assert!(err.contains("API returned status 500"));
+    }
+
+    #[test]
+    fn test_suggestions_leave_requests_for_translations() {
+        let client = AnthropicClient::new("test-key".to_string());
+        let suggester = client.suggestion_client("claude-3-5-haiku-latest".to_string());
+
+        for _ in 0..RATE_LIMIT_REQUESTS - SUGGEST_RESERVED_REQUESTS {
+            suggester.check_rate_limit_reserving(SUGGEST_RESERVED_REQUESTS).unwrap();
+        }
+        assert!(matches!(
+            suggester.check_rate_limit_reserving(SUGGEST_RESERVED_REQUESTS),
+            Err(LLMError::RateLimitExceeded(_))
+        ));
+
+        // The shared limiter still lets translations use the reserve
+        for _ in 0..SUGGEST_RESERVED_REQUESTS {
+            client.check_rate_limit().unwrap();
+        }
+        assert!(client.check_rate_limit().is_err());
+
+        let request = suggester.request(AnthropicClient::suggestion_system(), Vec::new());
+        assert_eq!(request.model, "claude-3-5-haiku-latest");
+        assert_eq!(request.max_tokens, SUGGEST_MAX_TOKENS);
+    }
+
     #[test]
     fn test_rate_limiting_basic_flow() {
         use std::thread;
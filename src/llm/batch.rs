@@ -0,0 +1,110 @@
+/// Most intents translated from one input; longer checklists are refused
+pub const MAX_BATCH_INTENTS: usize = 8;
+
+/// Split a pasted checklist into separate intents
+///
+/// Splits on newlines and on semicolons outside quotes, drops empty entries
+/// and list markers such as `-`, `*`, `1.` or `[ ]`. A single intent means
+/// the input is an ordinary query.
+pub fn split_intents(query: &str) -> Vec<String> {
+    let mut intents = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+
+    for c in query.chars() {
+        // Quotes open only at a word start, so "what's" isn't one
+        let word_start = current.chars().last().is_none_or(|p| p.is_whitespace() || p == '=');
+        match (c, quote) {
+            ('\'' | '"', None) if word_start => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (_, Some(open)) if c == open => {
+                quote = None;
+                current.push(c);
+            }
+            (';' | '\n', None) => intents.push(std::mem::take(&mut current)),
+            // A line break also ends an unbalanced quote
+            ('\n', Some(_)) => {
+                quote = None;
+                intents.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    intents.push(current);
+
+    intents
+        .iter()
+        .map(|intent| strip_list_marker(intent.trim()).trim().to_string())
+        .filter(|intent| !intent.is_empty())
+        .collect()
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let line = line
+        .strip_prefix("[ ]")
+        .or_else(|| line.strip_prefix("[x]"))
+        .unwrap_or(line)
+        .trim_start();
+    if let Some(rest) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+        return rest;
+    }
+    // Numbered items: "1." or "1)"
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0
+        && let Some(rest) = line[digits..]
+            .strip_prefix(". ")
+            .or_else(|| line[digits..].strip_prefix(") "))
+    {
+        return rest;
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_query_is_one_intent() {
+        assert_eq!(split_intents("show me what changed"), vec!["show me what changed"]);
+        assert!(split_intents("  ;\n ").is_empty());
+    }
+
+    #[test]
+    fn test_split_on_semicolons_and_newlines() {
+        assert_eq!(
+            split_intents("stage everything; commit as 'wip'; push"),
+            vec!["stage everything", "commit as 'wip'", "push"]
+        );
+        assert_eq!(
+            split_intents("- stage everything\n- commit as wip\n\n3. push"),
+            vec!["stage everything", "commit as wip", "push"]
+        );
+    }
+
+    #[test]
+    fn test_quoted_semicolons_are_kept() {
+        assert_eq!(
+            split_intents("commit with message \"fix; cleanup\"; push"),
+            vec!["commit with message \"fix; cleanup\"", "push"]
+        );
+    }
+
+    #[test]
+    fn test_apostrophes_are_not_quotes() {
+        assert_eq!(
+            split_intents("show what's changed; push"),
+            vec!["show what's changed", "push"]
+        );
+    }
+
+    #[test]
+    fn test_list_markers() {
+        assert_eq!(strip_list_marker("[ ] push"), "push");
+        assert_eq!(strip_list_marker("2) pull"), "pull");
+        assert_eq!(strip_list_marker("* fetch"), "fetch");
+        assert_eq!(strip_list_marker("2024 release notes"), "2024 release notes");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::config::describe_network_error;
 use crate::llm::context::RepoContext;
 use async_trait::async_trait;
 use thiserror::Error;
@@ -17,7 +18,7 @@ pub enum LLMError {
     #[error("Invalid API response: {0}")]
     InvalidResponse(String),
 
-    #[error("Network error: {0}")]
+    #[error("Network error: {}", describe_network_error(.0))]
     NetworkError(#[from] reqwest::Error),
 
     #[error("JSON parsing error: {0}")]
@@ -36,4 +37,49 @@ pub struct GitCommand {
 pub trait LLMClient: Send + Sync {
     /// Translate a natural language query into a git command
     async fn translate(&self, query: &str, context: &RepoContext) -> Result<GitCommand, LLMError>;
+
+    /// Ask again after `rejected` (a previous answer to `query`) failed validation
+    ///
+    /// Clients without multi-turn support re-translate with the rejection
+    /// reason appended to the query.
+    async fn repair(
+        &self,
+        query: &str,
+        context: &RepoContext,
+        rejected: &str,
+        reason: &str,
+    ) -> Result<GitCommand, LLMError> {
+        let query = format!(
+            "{}\n\n(Your previous answer \"{}\" was rejected: {}. Respond with a single plain git command.)",
+            query, rejected, reason
+        );
+        self.translate(&query, context).await
+    }
+
+    /// Differing commands a vague query could mean, each explaining when to pick it
+    ///
+    /// Clients that can't offer alternatives translate once.
+    async fn alternatives(&self, query: &str, context: &RepoContext) -> Result<Vec<GitCommand>, LLMError> {
+        Ok(vec![self.translate(query, context).await?])
+    }
+
+    /// Explain what a git command and each of its flags does
+    ///
+    /// Clients that don't support explanations return an error so callers can
+    /// fall back to the offline lookup table.
+    async fn explain(&self, _command: &str) -> Result<String, LLMError> {
+        Err(LLMError::ApiError(
+            "Command explanation not supported by this client".to_string(),
+        ))
+    }
+
+    /// Cheap, best-effort translation shown while the user is still typing
+    ///
+    /// Implementations should refuse rather than use up the requests left
+    /// for real translations.
+    async fn suggest(&self, _query: &str, _context: &RepoContext) -> Result<GitCommand, LLMError> {
+        Err(LLMError::ApiError(
+            "Live suggestions not supported by this client".to_string(),
+        ))
+    }
 }
@@ -1,5 +1,19 @@
 use crate::error::GitResult;
-use crate::git::Repository;
+use crate::git::{
+    DEFAULT_RENAME_THRESHOLD, HiddenFile, Repository, RepositoryState, StateCache, StateSnapshot, detect_renames,
+    format_renames,
+};
+use crate::llm::file_summary::summarize_files;
+use crate::llm::mcp::McpContextSource;
+use crate::llm::warmup::{ContextWarmup, WarmContext};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How old a state published by the UI may be and still be used for context
+pub const CONTEXT_STATE_MAX_AGE: Duration = Duration::from_secs(3);
+
+/// `git diff` targets whose renames commit (`--cached`) and diff (`HEAD`) context list
+pub(crate) const RENAME_TARGETS: [&str; 2] = ["--cached", "HEAD"];
 
 /// Types of queries that require different context
 #[derive(Debug, Clone, PartialEq)]
@@ -21,13 +35,62 @@ pub struct RepoContext {
 }
 
 /// Builds context for LLM queries with token budget enforcement
+///
+/// Clones share the state cache and warm context.
+#[derive(Clone)]
 pub struct ContextBuilder {
     repo: Repository,
+    mcp: Option<McpContextSource>,
+    state_cache: StateCache,
+    rename_threshold: u8,
+    /// Context prebuilt by [`ContextWarmup`], when warmup is on
+    warm: Option<Arc<Mutex<WarmContext>>>,
 }
 
 impl ContextBuilder {
     pub fn new(repo: Repository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            mcp: None,
+            state_cache: StateCache::new(),
+            rename_threshold: DEFAULT_RENAME_THRESHOLD,
+            warm: None,
+        }
+    }
+
+    /// Reuse repository states published by the UI instead of re-querying git
+    pub fn with_state_cache(mut self, state_cache: StateCache) -> Self {
+        self.state_cache = state_cache;
+        self
+    }
+
+    /// Similarity (percent) for the renames listed in commit and diff context
+    pub fn with_rename_threshold(mut self, threshold: u8) -> Self {
+        self.rename_threshold = threshold;
+        self
+    }
+
+    /// Enrich escalated context with content from external MCP servers
+    pub fn with_mcp(mut self, mcp: McpContextSource) -> Self {
+        self.mcp = Some(mcp);
+        self
+    }
+
+    /// Use context prebuilt in the background, see [`ContextBuilder::warmup`]
+    pub fn with_warmup(mut self) -> Self {
+        self.warm = Some(Arc::default());
+        self
+    }
+
+    /// Handle that prebuilds this builder's context, when warmup is on
+    pub fn warmup(&self) -> Option<ContextWarmup> {
+        let warm = self.warm.as_ref()?;
+        Some(ContextWarmup::new(
+            self.repo.clone(),
+            self.state_cache.clone(),
+            self.rename_threshold,
+            Arc::clone(warm),
+        ))
     }
 
     /// Get the repository path
@@ -35,9 +98,34 @@ impl ContextBuilder {
         self.repo.path()
     }
 
+    /// The repository state, from the cache while it is recent; with warmup
+    /// on, the last published one however old, as the UI's refresh renews it
+    fn current_state(&self) -> GitResult<StateSnapshot> {
+        if self.warm.is_some()
+            && let Some(snapshot) = self.state_cache.fresh(self.repo.path(), Duration::MAX)
+        {
+            return Ok(snapshot);
+        }
+        self.state_cache.get_or_load(&self.repo, CONTEXT_STATE_MAX_AGE)
+    }
+
+    /// What `read` gives from the warm context built for `generation`, if any
+    fn warm<T>(&self, generation: u64, read: impl FnOnce(&WarmContext) -> Option<T>) -> Option<T> {
+        let warm = self.warm.as_ref()?.lock().unwrap();
+        (warm.generation == generation).then(|| read(&warm)).flatten()
+    }
+
     /// Build default context (~500 tokens)
     pub fn build_default_context(&self) -> GitResult<RepoContext> {
-        let state = self.repo.state()?;
+        let snapshot = self.current_state()?;
+        if let Some(context) = self.warm(snapshot.generation, |warm| warm.default.clone()) {
+            return Ok(context);
+        }
+        Ok(Self::default_context(&snapshot.state, None))
+    }
+
+    /// `query`, when given, picks the files to name in summarized file lists
+    pub(crate) fn default_context(state: &RepositoryState, query: Option<&str>) -> RepoContext {
         let mut context = String::new();
 
         // Current branch and upstream
@@ -50,33 +138,21 @@ impl ContextBuilder {
                     upstream.remote_branch, upstream.ahead, upstream.behind
                 ));
             }
+            if let Some(ref second) = state.second_upstream {
+                context.push_str(&format!(
+                    "Also compared with: {} (ahead: {}, behind: {})\n",
+                    second.remote_branch, second.ahead, second.behind
+                ));
+            }
         } else {
             context.push_str("Detached HEAD state\n");
         }
 
-        // File lists with paths - critical for fuzzy matching
+        // File lists with paths - critical for fuzzy matching; big sets are summarized
         context.push_str("\n=== Repository Files ===\n");
-
-        if !state.staged_files.is_empty() {
-            context.push_str("\nStaged files:\n");
-            for file in state.staged_files.iter().take(50) {
-                context.push_str(&format!("  {}\n", file.path));
-            }
-        }
-
-        if !state.unstaged_files.is_empty() {
-            context.push_str("\nUnstaged files:\n");
-            for file in state.unstaged_files.iter().take(50) {
-                context.push_str(&format!("  {}\n", file.path));
-            }
-        }
-
-        if !state.untracked_files.is_empty() {
-            context.push_str("\nUntracked files:\n");
-            for file in state.untracked_files.iter().take(50) {
-                context.push_str(&format!("  {}\n", file.path));
-            }
-        }
+        context.push_str(&summarize_files("Staged files", &state.staged_files, query));
+        context.push_str(&summarize_files("Unstaged files", &state.unstaged_files, query));
+        context.push_str(&summarize_files("Untracked files", &state.untracked_files, query));
 
         // Recent commits (just count for default)
         context.push_str(&format!("\nRecent commits: {}\n", state.recent_commits.len()));
@@ -90,25 +166,69 @@ impl ContextBuilder {
         if state.in_merge {
             context.push_str("\nMerge in progress\n");
         }
-        if state.in_rebase {
+        if let Some(ref rebase) = state.rebase {
+            context.push_str(&format!(
+                "\nRebase of {} in progress, {} ({} more to go)\n",
+                rebase.branch.as_deref().unwrap_or("a detached HEAD"),
+                rebase.summary(),
+                rebase.remaining
+            ));
+        } else if state.in_rebase {
             context.push_str("\nRebase in progress\n");
         }
+        if let Some(depth) = state.shallow_depth {
+            context.push_str(&format!(
+                "\nShallow clone (depth≈{}): older history is missing until `git fetch --unshallow`\n",
+                depth
+            ));
+        }
+        if !state.hidden_files.is_empty() {
+            context.push_str(&Self::hidden_files_line(&state.hidden_files));
+        }
 
         let estimated_tokens = Self::estimate_tokens(&context);
 
-        Ok(RepoContext {
+        RepoContext {
             default_info: context,
             escalated_info: None,
             estimated_tokens,
-        })
+        }
+    }
+
+    /// Files whose changes git status won't show, so "why isn't my change
+    /// showing" translates to unhiding them
+    fn hidden_files_line(files: &[HiddenFile]) -> String {
+        const MAX_NAMED: usize = 10;
+        let mut named: Vec<String> =
+            files.iter().take(MAX_NAMED).map(|file| format!("{} ({})", file.path, file.label())).collect();
+        if files.len() > MAX_NAMED {
+            named.push(format!("and {} more", files.len() - MAX_NAMED));
+        }
+        format!(
+            "\nHidden from git status (changes not shown; `git update-index --no-assume-unchanged` or \
+             `--no-skip-worktree` shows them again): {}\n",
+            named.join(", ")
+        )
+    }
+
+    /// Build the context for a query: escalated by its type, with the files
+    /// it mentions kept visible in large change sets
+    pub fn build_query_context(&self, query: &str) -> GitResult<RepoContext> {
+        self.escalated_context(Self::classify_query(query), Some(query))
     }
 
     /// Build escalated context based on query type
     pub fn build_escalated_context(&self, query_type: QueryType) -> GitResult<RepoContext> {
-        let mut ctx = self.build_default_context()?;
-        let state = self.repo.state()?;
+        self.escalated_context(query_type, None)
+    }
 
-        let escalated = match query_type {
+    fn escalated_context(&self, query_type: QueryType, query: Option<&str>) -> GitResult<RepoContext> {
+        // One state for both parts, so git runs at most once per translation
+        let StateSnapshot { state, generation } = self.current_state()?;
+        let mut ctx = Self::default_context(&state, query);
+
+        let mcp_query_type = query_type.clone();
+        let mut escalated = match query_type {
             QueryType::Commit => {
                 // Add staged/unstaged file details
                 let mut info = String::from("\n=== Files to Commit ===\n");
@@ -127,6 +247,7 @@ impl ContextBuilder {
                     }
                 }
 
+                info.push_str(&self.renames_section("--cached", generation));
                 Some(info)
             }
 
@@ -137,6 +258,14 @@ impl ContextBuilder {
                     info.push_str(&format!("Tracking: {}\n", upstream.remote_branch));
                     info.push_str(&format!("Status: {} ahead, {} behind\n", upstream.ahead, upstream.behind));
                 }
+                // A fork's own remote and the project it was forked from
+                if let Some(ref second) = state.second_upstream {
+                    let remote = second.remote_branch.split('/').next().unwrap_or(&second.remote_branch);
+                    info.push_str(&format!(
+                        "Compared with {}: {} ahead, {} behind (sync a fork: fetch {}, then merge or rebase onto {})\n",
+                        second.remote_branch, second.ahead, second.behind, remote, second.remote_branch
+                    ));
+                }
                 Some(info)
             }
 
@@ -168,12 +297,23 @@ impl ContextBuilder {
                 for file in state.unstaged_files.iter().take(15) {
                     info.push_str(&format!("{:?}: {}\n", file.status, file.path));
                 }
+                // Staged and unstaged changes together, so `git mv` plus edits is matched
+                info.push_str(&self.renames_section("HEAD", generation));
                 Some(info)
             }
 
             QueryType::General => None,
         };
 
+        // MCP content only gets the budget left over after the repo's own context
+        if let Some(ref mcp) = self.mcp {
+            let used = Self::estimate_tokens(&ctx.default_info)
+                + escalated.as_deref().map(Self::estimate_tokens).unwrap_or(0);
+            if let Some(extra) = mcp.gather(&mcp_query_type, 5000usize.saturating_sub(used)) {
+                escalated.get_or_insert_with(String::new).push_str(&extra);
+            }
+        }
+
         if let Some(ref escalated_info) = escalated {
             ctx.escalated_info = Some(escalated_info.clone());
             ctx.estimated_tokens = Self::estimate_tokens(&ctx.get_full_context());
@@ -187,15 +327,29 @@ impl ContextBuilder {
         Ok(ctx)
     }
 
+    /// The renames section for `target`, prebuilt for `generation` when warm
+    fn renames_section(&self, target: &str, generation: u64) -> String {
+        self.warm(generation, |warm| warm.renames.get(target).cloned())
+            .unwrap_or_else(|| renames_section(&self.repo, target, self.rename_threshold))
+    }
+
     /// Classify query based on keywords
     pub fn classify_query(query: &str) -> QueryType {
         let query_lower = query.to_lowercase();
 
         if query_lower.contains("commit") || query_lower.contains("stage") {
             QueryType::Commit
-        } else if query_lower.contains("branch") || query_lower.contains("checkout") {
+        } else if query_lower.contains("branch")
+            || query_lower.contains("checkout")
+            || query_lower.contains("fork")
+            || query_lower.contains("upstream")
+        {
             QueryType::Branch
-        } else if query_lower.contains("diff") || query_lower.contains("change") {
+        } else if query_lower.contains("diff")
+            || query_lower.contains("change")
+            // "moved", "renamed"; not "removed"
+            || query_lower.split_whitespace().any(|word| word.starts_with("move") || word.starts_with("renam"))
+        {
             QueryType::Diff
         } else if query_lower.contains("log") || query_lower.contains("history") {
             QueryType::History
@@ -217,9 +371,10 @@ impl ContextBuilder {
             return;
         }
 
-        eprintln!(
-            "Warning: Context exceeds token budget ({} > {}), truncating...",
-            context.estimated_tokens, max_tokens
+        tracing::warn!(
+            tokens = context.estimated_tokens,
+            budget = max_tokens,
+            "context exceeds token budget, truncating"
         );
 
         // Strategy: Keep default info, truncate escalated info
@@ -255,18 +410,47 @@ impl RepoContext {
     }
 }
 
+/// Renames and copies in `git diff <target>`, or nothing when there are none
+pub(crate) fn renames_section(repo: &Repository, target: &str, threshold: u8) -> String {
+    let renames = detect_renames(repo, target, threshold);
+    if renames.is_empty() {
+        return String::new();
+    }
+    format!("\n=== Renames ===\n{}", format_renames(&renames, 20))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{git, init_repo};
 
     #[test]
     fn test_query_classification() {
         assert_eq!(ContextBuilder::classify_query("commit all changes"), QueryType::Commit);
         assert_eq!(ContextBuilder::classify_query("create a new branch"), QueryType::Branch);
+        assert_eq!(ContextBuilder::classify_query("sync my fork"), QueryType::Branch);
         assert_eq!(ContextBuilder::classify_query("show me the diff"), QueryType::Diff);
         assert_eq!(ContextBuilder::classify_query("view log history"), QueryType::History);
         assert_eq!(ContextBuilder::classify_query("stash my work"), QueryType::Stash);
         assert_eq!(ContextBuilder::classify_query("what's the status?"), QueryType::General);
+        assert_eq!(ContextBuilder::classify_query("where was config.rs moved to?"), QueryType::Diff);
+        assert_eq!(ContextBuilder::classify_query("which files did I rename"), QueryType::Diff);
+        assert_eq!(ContextBuilder::classify_query("undo removed files"), QueryType::General);
+    }
+
+    #[test]
+    fn test_diff_context_lists_renames() {
+        let temp = tempfile::TempDir::new().unwrap();
+        init_repo(temp.path());
+        std::fs::write(temp.path().join("config.rs"), "fn main() {}\n".repeat(5)).unwrap();
+        git(temp.path(), &["add", "config.rs"]);
+        git(temp.path(), &["commit", "-q", "-m", "config"]);
+        git(temp.path(), &["mv", "config.rs", "settings.rs"]);
+
+        let builder = ContextBuilder::new(Repository::new(temp.path())).with_rename_threshold(90);
+        let ctx = builder.build_query_context("where was config.rs moved?").unwrap();
+        let full = ctx.get_full_context();
+        assert!(full.contains("=== Renames ===\nrenamed: config.rs → settings.rs (100%)"));
     }
 
     #[test]
@@ -286,4 +470,63 @@ mod tests {
 
         assert_eq!(ctx.get_full_context(), "defaultescalated");
     }
+
+    #[test]
+    fn test_context_uses_published_state() {
+        let temp = tempfile::TempDir::new().unwrap();
+        init_repo(temp.path());
+        let repo = Repository::new(temp.path());
+
+        // A state published by the UI wins over asking git again
+        let cache = StateCache::new();
+        let mut state = repo.state().unwrap();
+        state.current_branch = Some("published-by-ui".to_string());
+        state.shallow_depth = Some(5);
+        state.hidden_files = crate::git::parse_hidden_files("h config/local.toml\0");
+        cache.publish(repo.path(), state);
+
+        let builder = ContextBuilder::new(repo).with_state_cache(cache.clone());
+        let generation = cache.generation();
+        let ctx = builder.build_escalated_context(QueryType::Branch).unwrap();
+        assert!(ctx.default_info.contains("Current branch: published-by-ui"));
+        assert!(ctx.default_info.contains("Shallow clone (depth≈5)"));
+        assert!(ctx.default_info.contains("shows them again): config/local.toml (assume-unchanged)\n"));
+        assert_eq!(cache.generation(), generation);
+
+        cache.invalidate();
+        let ctx = builder.build_default_context().unwrap();
+        assert!(!ctx.default_info.contains("published-by-ui"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_escalated_context_includes_mcp() {
+        use crate::llm::mcp::{McpConfig, McpServerConfig};
+
+        let temp = tempfile::TempDir::new().unwrap();
+        init_repo(temp.path());
+
+        let script = r#"
+            read l; echo '{"jsonrpc":"2.0","id":1,"result":{}}'
+            read l
+            read l; echo '{"jsonrpc":"2.0","id":2,"result":{"content":[{"type":"text","text":"Issue #7: fix login"}]}}'
+            read l
+        "#;
+        let mcp = McpContextSource::new(McpConfig {
+            servers: vec![McpServerConfig {
+                name: "tracker".to_string(),
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                resources: Vec::new(),
+                tool: Some("open_issues".to_string()),
+            }],
+            ..McpConfig::default()
+        });
+
+        let builder = ContextBuilder::new(Repository::new(temp.path())).with_mcp(mcp);
+        let ctx = builder.build_escalated_context(QueryType::General).unwrap();
+        let full = ctx.get_full_context();
+        assert!(full.contains("=== Context from tracker ==="));
+        assert!(full.contains("Issue #7: fix login"));
+    }
 }
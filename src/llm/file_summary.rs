@@ -0,0 +1,256 @@
+use crate::git::{FileStatus, StatusEntry};
+use std::collections::BTreeMap;
+
+/// Sections with at most this many files are listed in full
+pub const MAX_LISTED_FILES: usize = 50;
+
+/// Files named in the query that are listed even in a summarized section
+const MAX_MENTIONED_FILES: usize = 20;
+
+/// Directory lines in a summary before grouping at a shallower depth
+const MAX_DIRECTORY_LINES: usize = 25;
+
+/// Extensions shown in a summary's histogram
+const MAX_EXTENSIONS: usize = 8;
+
+/// Describe one file section of the context, e.g. "Unstaged files"
+///
+/// Small sections list every path. Large ones list the files the query
+/// mentions, then counts per directory and per extension, so the LLM still
+/// sees where the changes are without thousands of paths.
+pub fn summarize_files(heading: &str, entries: &[StatusEntry], query: Option<&str>) -> String {
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::new();
+    if entries.len() <= MAX_LISTED_FILES {
+        section.push_str(&format!("\n{}:\n", heading));
+        for entry in entries {
+            section.push_str(&format!("  {}\n", entry.path));
+        }
+        return section;
+    }
+
+    section.push_str(&format!("\n{}: {} in total, summarized\n", heading, entries.len()));
+
+    let mentioned = query.map(|q| mentioned_files(q, entries)).unwrap_or_default();
+    if !mentioned.is_empty() {
+        section.push_str("  Matching the query:\n");
+        for entry in &mentioned {
+            section.push_str(&format!("    {}\n", entry.path));
+        }
+    }
+
+    section.push_str("  By directory:\n");
+    for (directory, counts) in directory_groups(entries) {
+        section.push_str(&format!("    {}: {}\n", directory, counts));
+    }
+
+    section.push_str(&format!("  By extension: {}\n", extension_histogram(entries)));
+    section
+}
+
+/// Entries the query names by path, file name, stem or directory
+fn mentioned_files<'a>(query: &str, entries: &'a [StatusEntry]) -> Vec<&'a StatusEntry> {
+    let words: Vec<String> = query
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '/')))
+        .map(|w| w.trim_matches(|c: char| c == '.' || c == '/').to_lowercase())
+        .filter(|w| w.len() >= 2)
+        .collect();
+
+    entries
+        .iter()
+        .filter(|entry| {
+            let path = entry.path.to_lowercase();
+            let name = path.rsplit('/').next().unwrap_or(&path);
+            let stem = name.split('.').next().unwrap_or(name);
+            let directories: Vec<&str> = path.split('/').collect();
+            let directories = &directories[..directories.len() - 1];
+            words.iter().any(|word| {
+                name == word
+                    || stem == word
+                    || (word.contains('/') && path.contains(word.as_str()))
+                    || directories.contains(&word.as_str())
+            })
+        })
+        .take(MAX_MENTIONED_FILES)
+        .collect()
+}
+
+/// Counts per directory and status, grouped shallower while there are too many
+fn directory_groups(entries: &[StatusEntry]) -> Vec<(String, String)> {
+    let deepest = entries
+        .iter()
+        .map(|e| e.path.matches('/').count())
+        .max()
+        .unwrap_or(0);
+
+    let mut groups = BTreeMap::new();
+    for depth in (1..=deepest.max(1)).rev() {
+        groups = group_at_depth(entries, depth);
+        if groups.len() <= MAX_DIRECTORY_LINES {
+            break;
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(directory, counts)| {
+            let counts: Vec<String> = counts
+                .iter()
+                .map(|(status, n)| format!("{} {}", n, status_label(status)))
+                .collect();
+            (directory, counts.join(", "))
+        })
+        .collect()
+}
+
+/// Status counts keyed by the first `depth` directories of each path
+fn group_at_depth(entries: &[StatusEntry], depth: usize) -> BTreeMap<String, BTreeMap<u8, usize>> {
+    let mut groups: BTreeMap<String, BTreeMap<u8, usize>> = BTreeMap::new();
+    for entry in entries {
+        let directories: Vec<&str> = entry.path.split('/').collect();
+        let directories = &directories[..directories.len() - 1];
+        let key = if directories.is_empty() {
+            "(top level)".to_string()
+        } else {
+            directories[..depth.min(directories.len())].join("/")
+        };
+        *groups
+            .entry(key)
+            .or_default()
+            .entry(status_order(&entry.status))
+            .or_default() += 1;
+    }
+    groups
+}
+
+fn status_order(status: &FileStatus) -> u8 {
+    match status {
+        FileStatus::Conflicted => 0,
+        FileStatus::Modified => 1,
+        FileStatus::Added => 2,
+        FileStatus::Deleted => 3,
+        FileStatus::Untracked => 4,
+        FileStatus::Unknown => 5,
+    }
+}
+
+fn status_label(order: &u8) -> &'static str {
+    match order {
+        0 => "conflicted",
+        1 => "modified",
+        2 => "added",
+        3 => "deleted",
+        4 => "untracked",
+        _ => "changed",
+    }
+}
+
+/// "rs 280, md 20, (none) 12", most common first
+fn extension_histogram(entries: &[StatusEntry]) -> String {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in entries {
+        let name = entry.path.rsplit('/').next().unwrap_or(&entry.path);
+        let extension = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => format!(".{}", extension),
+            _ => "(none)".to_string(),
+        };
+        *counts.entry(extension).or_default() += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let others: usize = counts.iter().skip(MAX_EXTENSIONS).map(|(_, n)| n).sum();
+    let mut parts: Vec<String> = counts
+        .iter()
+        .take(MAX_EXTENSIONS)
+        .map(|(extension, n)| format!("{} {}", extension, n))
+        .collect();
+    if others > 0 {
+        parts.push(format!("other {}", others));
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, status: FileStatus) -> StatusEntry {
+        StatusEntry {
+            status,
+            path: path.to_string(),
+            staged: false,
+            unstaged: true,
+        }
+    }
+
+    fn big_change_set() -> Vec<StatusEntry> {
+        let mut entries = Vec::new();
+        for i in 0..40 {
+            entries.push(entry(&format!("src/ui/widget{}.rs", i), FileStatus::Modified));
+        }
+        for i in 0..20 {
+            entries.push(entry(&format!("docs/page{}.md", i), FileStatus::Added));
+        }
+        entries.push(entry("src/ui/input.rs", FileStatus::Deleted));
+        entries.push(entry("Makefile", FileStatus::Modified));
+        entries
+    }
+
+    #[test]
+    fn test_small_sections_are_listed() {
+        let entries = vec![entry("src/main.rs", FileStatus::Modified)];
+        assert_eq!(
+            summarize_files("Unstaged files", &entries, None),
+            "\nUnstaged files:\n  src/main.rs\n"
+        );
+        assert_eq!(summarize_files("Unstaged files", &[], None), "");
+    }
+
+    #[test]
+    fn test_large_sections_are_summarized() {
+        let summary = summarize_files("Unstaged files", &big_change_set(), None);
+        assert!(summary.starts_with("\nUnstaged files: 62 in total, summarized\n"));
+        assert!(summary.contains("    src/ui: 40 modified, 1 deleted\n"));
+        assert!(summary.contains("    docs: 20 added\n"));
+        assert!(summary.contains("    (top level): 1 modified\n"));
+        assert!(summary.contains("  By extension: .rs 41, .md 20, (none) 1\n"));
+        assert!(!summary.contains("widget3.rs"));
+        assert!(!summary.contains("Matching the query"));
+    }
+
+    #[test]
+    fn test_query_mentions_are_listed_first() {
+        let summary = summarize_files("Unstaged files", &big_change_set(), Some("restore input.rs and Makefile"));
+        let matching = summary.split("  By directory:").next().unwrap();
+        assert!(matching.contains("    src/ui/input.rs\n"));
+        assert!(matching.contains("    Makefile\n"));
+        assert!(!matching.contains("widget"));
+
+        let by_dir = summarize_files("Unstaged files", &big_change_set(), Some("stage everything in docs"));
+        assert_eq!(by_dir.matches("    docs/page").count(), MAX_MENTIONED_FILES);
+    }
+
+    #[test]
+    fn test_many_directories_are_grouped_shallower() {
+        let entries: Vec<StatusEntry> = (0..60)
+            .map(|i| entry(&format!("pkg/mod{}/lib.rs", i), FileStatus::Modified))
+            .collect();
+        let groups = directory_groups(&entries);
+        assert_eq!(groups, vec![("pkg".to_string(), "60 modified".to_string())]);
+    }
+
+    #[test]
+    fn test_extension_histogram_caps_entries() {
+        let entries: Vec<StatusEntry> = (0..10)
+            .map(|i| entry(&format!("file.ext{}", i), FileStatus::Added))
+            .chain(std::iter::once(entry(".gitignore", FileStatus::Modified)))
+            .collect();
+        let histogram = extension_histogram(&entries);
+        assert!(histogram.starts_with("(none) 1, .ext0 1"));
+        assert!(histogram.ends_with("other 3"));
+    }
+}
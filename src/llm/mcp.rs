@@ -0,0 +1,474 @@
+use crate::llm::context::{ContextBuilder, QueryType};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// MCP protocol revision sent during initialization
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+#[derive(Debug, Error)]
+pub enum McpError {
+    #[error("Failed to start MCP server '{0}': {1}")]
+    SpawnFailed(String, std::io::Error),
+
+    #[error("MCP server '{0}' timed out")]
+    Timeout(String),
+
+    #[error("MCP server '{0}' closed the connection")]
+    Disconnected(String),
+
+    #[error("MCP server '{0}' returned an error: {1}")]
+    ServerError(String, String),
+
+    #[error("Failed to talk to MCP server '{0}': {1}")]
+    Io(String, std::io::Error),
+}
+
+/// `[mcp]` config section: external MCP servers that enrich translation context
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct McpConfig {
+    #[serde(default)]
+    pub servers: Vec<McpServerConfig>,
+    /// Token budget shared by all servers' content
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+    /// Time allowed per server for the whole exchange
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        Self {
+            servers: Vec::new(),
+            max_tokens: default_max_tokens(),
+            timeout_ms: default_timeout_ms(),
+        }
+    }
+}
+
+impl McpConfig {
+    /// Check if no servers are configured
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+}
+
+/// A single MCP server launched over stdio
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct McpServerConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Resource URIs to read for every translation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resources: Vec<String>,
+    /// Tool to call with `{"query_type": ...}` for every translation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+}
+
+fn default_max_tokens() -> usize {
+    1000
+}
+
+fn default_timeout_ms() -> u64 {
+    3000
+}
+
+/// Minimal MCP client speaking JSON-RPC over a server's stdin/stdout
+///
+/// Each client owns one server process for the duration of a context fetch;
+/// the process is killed when the client is dropped.
+pub struct McpClient {
+    name: String,
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<Value>,
+    deadline: Instant,
+    next_id: u64,
+}
+
+impl McpClient {
+    /// Start a server and perform the MCP initialization handshake
+    pub fn connect(server: &McpServerConfig, timeout: Duration) -> Result<Self, McpError> {
+        let mut child = Command::new(&server.command)
+            .args(&server.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| McpError::SpawnFailed(server.name.clone(), e))?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        // Read on a thread so every request can be bounded by the deadline
+        let (tx, responses) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Ok(message) = serde_json::from_str::<Value>(&line)
+                    && tx.send(message).is_err()
+                {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Self {
+            name: server.name.clone(),
+            child,
+            stdin,
+            responses,
+            deadline: Instant::now() + timeout,
+            next_id: 1,
+        };
+
+        client.request(
+            "initialize",
+            json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "gitalky", "version": env!("CARGO_PKG_VERSION") },
+            }),
+        )?;
+        client.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))?;
+
+        Ok(client)
+    }
+
+    /// Read a resource and return its text contents
+    pub fn read_resource(&mut self, uri: &str) -> Result<String, McpError> {
+        let result = self.request("resources/read", json!({ "uri": uri }))?;
+        Ok(collect_text(&result["contents"]))
+    }
+
+    /// Call a tool and return its text output
+    pub fn call_tool(&mut self, name: &str, arguments: Value) -> Result<String, McpError> {
+        let result = self.request("tools/call", json!({ "name": name, "arguments": arguments }))?;
+        if result["isError"].as_bool() == Some(true) {
+            return Err(McpError::ServerError(self.name.clone(), collect_text(&result["content"])));
+        }
+        Ok(collect_text(&result["content"]))
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, McpError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            let message = match self.responses.recv_timeout(remaining) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => return Err(McpError::Timeout(self.name.clone())),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(McpError::Disconnected(self.name.clone()));
+                }
+            };
+
+            // Skip notifications and responses to other requests
+            if message["id"].as_u64() != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let text = error["message"].as_str().unwrap_or("unknown error").to_string();
+                return Err(McpError::ServerError(self.name.clone(), text));
+            }
+            return Ok(message["result"].clone());
+        }
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), McpError> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| McpError::Io(self.name.clone(), e))
+    }
+}
+
+impl Drop for McpClient {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Join the `text` fields of MCP content/resource entries
+fn collect_text(entries: &Value) -> String {
+    entries
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .unwrap_or_default()
+}
+
+/// Gathers context from all configured MCP servers within a token budget
+#[derive(Debug, Clone)]
+pub struct McpContextSource {
+    config: McpConfig,
+}
+
+impl McpContextSource {
+    pub fn new(config: McpConfig) -> Self {
+        Self { config }
+    }
+
+    /// Query every server and merge their content into one context section
+    ///
+    /// Servers are queried at the same time, so the wait is one `timeout_ms`
+    /// however many there are. Servers that fail are skipped so a broken
+    /// integration never blocks translation. At most `max_tokens` (and never
+    /// more than `available_tokens`) of content is returned.
+    pub fn gather(&self, query_type: &QueryType, available_tokens: usize) -> Option<String> {
+        let timeout = Duration::from_millis(self.config.timeout_ms);
+        let sections: Vec<(String, String)> = std::thread::scope(|scope| {
+            let fetches: Vec<_> = self
+                .config
+                .servers
+                .iter()
+                .map(|server| scope.spawn(move || Self::fetch(server, query_type, timeout)))
+                .collect();
+            fetches
+                .into_iter()
+                .zip(&self.config.servers)
+                .filter_map(|(fetch, server)| {
+                    fetch
+                        .join()
+                        .ok()?
+                        .ok()
+                        .filter(|text| !text.trim().is_empty())
+                        .map(|text| (server.name.clone(), text))
+                })
+                .collect()
+        });
+
+        merge_sections(&sections, self.config.max_tokens.min(available_tokens))
+    }
+
+    fn fetch(
+        server: &McpServerConfig,
+        query_type: &QueryType,
+        timeout: Duration,
+    ) -> Result<String, McpError> {
+        let mut client = McpClient::connect(server, timeout)?;
+        let mut parts = Vec::new();
+
+        for uri in &server.resources {
+            parts.push(client.read_resource(uri)?);
+        }
+        if let Some(ref tool) = server.tool {
+            let query_type = format!("{:?}", query_type).to_lowercase();
+            parts.push(client.call_tool(tool, json!({ "query_type": query_type }))?);
+        }
+
+        Ok(parts.join("\n"))
+    }
+}
+
+/// Merge per-server sections, splitting the token budget evenly
+///
+/// Budget a short section doesn't use is handed on to the ones after it.
+pub fn merge_sections(sections: &[(String, String)], max_tokens: usize) -> Option<String> {
+    if sections.is_empty() || max_tokens == 0 {
+        return None;
+    }
+
+    let mut merged = String::new();
+    let mut remaining = max_tokens;
+
+    for (i, (name, text)) in sections.iter().enumerate() {
+        let header = format!("\n=== Context from {} ===\n", name);
+        let share = remaining / (sections.len() - i);
+        let header_tokens = ContextBuilder::estimate_tokens(&header);
+        if share <= header_tokens {
+            continue;
+        }
+
+        let body = truncate_to_tokens(text.trim(), share - header_tokens);
+        remaining -= header_tokens + ContextBuilder::estimate_tokens(&body);
+        merged.push_str(&header);
+        merged.push_str(&body);
+        merged.push('\n');
+    }
+
+    if merged.is_empty() { None } else { Some(merged) }
+}
+
+/// Cut text to roughly `max_tokens`, respecting UTF-8 boundaries
+fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    const MARKER: &str = "\n... [truncated]";
+
+    if ContextBuilder::estimate_tokens(text) <= max_tokens {
+        return text.to_string();
+    }
+
+    let max_chars = (max_tokens * 4).saturating_sub(MARKER.len());
+    let mut end = max_chars.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &text[..end], MARKER)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(name: &str, text: &str) -> (String, String) {
+        (name.to_string(), text.to_string())
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let toml = r#"
+            max_tokens = 500
+
+            [[servers]]
+            name = "ci"
+            command = "ci-mcp"
+            resources = ["ci://status"]
+        "#;
+        let config: McpConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.max_tokens, 500);
+        assert_eq!(config.timeout_ms, 3000);
+        assert_eq!(config.servers[0].resources, vec!["ci://status".to_string()]);
+        assert!(config.servers[0].tool.is_none());
+    }
+
+    #[test]
+    fn test_merge_sections() {
+        let merged = merge_sections(
+            &[section("ci", "build green"), section("issues", "#42 open")],
+            1000,
+        )
+        .unwrap();
+        assert!(merged.contains("=== Context from ci ===\nbuild green"));
+        assert!(merged.contains("=== Context from issues ===\n#42 open"));
+    }
+
+    #[test]
+    fn test_merge_respects_budget() {
+        let long = "x".repeat(4000);
+        let merged = merge_sections(&[section("a", &long), section("b", &long)], 200).unwrap();
+
+        assert!(ContextBuilder::estimate_tokens(&merged) <= 210);
+        assert!(merged.contains("=== Context from a ==="));
+        assert!(merged.contains("=== Context from b ==="));
+        assert!(merged.contains("[truncated]"));
+    }
+
+    #[test]
+    fn test_unused_budget_carries_over() {
+        let long = "y".repeat(4000);
+        let merged = merge_sections(&[section("short", "ok"), section("long", &long)], 200).unwrap();
+        let long_part = merged.split("=== Context from long ===").nth(1).unwrap();
+        // The long section gets most of the budget the short one didn't need
+        assert!(long_part.len() > 500);
+    }
+
+    #[test]
+    fn test_merge_empty() {
+        assert!(merge_sections(&[], 1000).is_none());
+        assert!(merge_sections(&[section("a", "text")], 0).is_none());
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        let text = "é".repeat(100);
+        let truncated = truncate_to_tokens(&text, 10);
+        assert!(truncated.ends_with("[truncated]"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fetch_from_stdio_server() {
+        // Canned server: answers initialize, ignores the notification, answers one read
+        let script = r#"
+            read l; echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{}}}'
+            read l
+            read l; echo '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+            echo '{"jsonrpc":"2.0","id":2,"result":{"contents":[{"uri":"ci://status","text":"CI: main is green"}]}}'
+            read l
+        "#;
+        let server = McpServerConfig {
+            name: "ci".to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            resources: vec!["ci://status".to_string()],
+            tool: None,
+        };
+
+        let source = McpContextSource::new(McpConfig {
+            servers: vec![server],
+            ..McpConfig::default()
+        });
+        let context = source.gather(&QueryType::General, 5000).unwrap();
+        assert!(context.contains("=== Context from ci ==="));
+        assert!(context.contains("CI: main is green"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_servers_queried_concurrently() {
+        // Each server takes a second to answer its read
+        let script = |text: &str| {
+            format!(
+                r#"
+                read l; echo '{{"jsonrpc":"2.0","id":1,"result":{{}}}}'
+                read l
+                read l; sleep 1; echo '{{"jsonrpc":"2.0","id":2,"result":{{"contents":[{{"text":"{}"}}]}}}}'
+                read l
+                "#,
+                text
+            )
+        };
+        let server = |name: &str| McpServerConfig {
+            name: name.to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script(&format!("from {}", name))],
+            resources: vec!["x://status".to_string()],
+            tool: None,
+        };
+        let source = McpContextSource::new(McpConfig {
+            servers: vec![server("first"), server("second"), server("third")],
+            ..McpConfig::default()
+        });
+
+        let start = Instant::now();
+        let context = source.gather(&QueryType::General, 5000).unwrap();
+        assert!(start.elapsed() < Duration::from_millis(2500), "{:?}", start.elapsed());
+        // Sections keep the configured order
+        let positions: Vec<usize> =
+            ["first", "second", "third"].iter().map(|name| context.find(&format!("from {}", name)).unwrap()).collect();
+        assert!(positions.is_sorted(), "{}", context);
+    }
+
+    #[test]
+    fn test_failing_server_is_skipped() {
+        let server = McpServerConfig {
+            name: "broken".to_string(),
+            command: "/nonexistent/mcp-server".to_string(),
+            args: Vec::new(),
+            resources: vec!["x://y".to_string()],
+            tool: None,
+        };
+        let source = McpContextSource::new(McpConfig {
+            servers: vec![server],
+            ..McpConfig::default()
+        });
+        assert!(source.gather(&QueryType::General, 5000).is_none());
+    }
+}
@@ -1,9 +1,24 @@
+pub mod alternatives;
 pub mod anthropic;
+pub mod batch;
 pub mod client;
 pub mod context;
+pub mod file_summary;
+pub mod mcp;
+pub mod models;
+pub mod placeholders;
+pub mod suggest;
 pub mod translator;
+pub mod warmup;
 
-pub use anthropic::AnthropicClient;
+pub use alternatives::{MAX_ALTERNATIVES, is_vague_query};
+pub use anthropic::{AnthropicClient, GenerationParams};
+pub use batch::{MAX_BATCH_INTENTS, split_intents};
 pub use client::{GitCommand, LLMClient};
 pub use context::{ContextBuilder, QueryType, RepoContext};
+pub use mcp::{McpConfig, McpContextSource, McpServerConfig};
+pub use models::{KNOWN_MODELS, ModelCheck, check_model, model_warning};
+pub use placeholders::{describe_placeholders, placeholders, with_stand_ins};
+pub use suggest::{LiveSuggester, SUGGEST_COMMAND, is_suggestible};
 pub use translator::Translator;
+pub use warmup::ContextWarmup;
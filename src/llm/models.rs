@@ -0,0 +1,125 @@
+/// Claude models gitalky knows about, newest first, with a short description
+///
+/// Used for the setup wizard's pick-list and to catch typos in `llm.model`.
+/// Models released later can be allowed with `llm.extra_models`.
+pub const KNOWN_MODELS: &[(&str, &str)] = &[
+    ("claude-sonnet-4-5-20250929", "Claude Sonnet 4.5 (recommended)"),
+    ("claude-opus-4-1-20250805", "Claude Opus 4.1 (most capable, slower)"),
+    ("claude-opus-4-20250514", "Claude Opus 4"),
+    ("claude-sonnet-4-20250514", "Claude Sonnet 4"),
+    ("claude-3-7-sonnet-20250219", "Claude Sonnet 3.7"),
+    ("claude-3-5-haiku-20241022", "Claude Haiku 3.5 (fastest)"),
+];
+
+/// Aliases the API resolves to the latest snapshot of a model
+const MODEL_ALIASES: &[&str] = &[
+    "claude-sonnet-4-5",
+    "claude-opus-4-1",
+    "claude-opus-4-0",
+    "claude-sonnet-4-0",
+    "claude-3-7-sonnet-latest",
+    "claude-3-5-haiku-latest",
+];
+
+/// Outcome of checking a configured model name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelCheck {
+    Known,
+    /// Not a model we know; `suggestion` is the closest known one
+    Unknown { suggestion: Option<&'static str> },
+}
+
+/// Check `model` against the known models, aliases and `extra` overrides
+pub fn check_model(model: &str, extra: &[String]) -> ModelCheck {
+    let model = model.trim();
+    let known = KNOWN_MODELS.iter().any(|(id, _)| *id == model)
+        || MODEL_ALIASES.contains(&model)
+        || extra.iter().any(|m| m == model);
+    if known {
+        return ModelCheck::Known;
+    }
+
+    // Only suggest when the name is plausibly a typo of a known model
+    let suggestion = KNOWN_MODELS
+        .iter()
+        .map(|(id, _)| *id)
+        .chain(MODEL_ALIASES.iter().copied())
+        .map(|id| (id, edit_distance(model, id)))
+        .filter(|(_, distance)| *distance <= 4)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(id, _)| id);
+    ModelCheck::Unknown { suggestion }
+}
+
+/// Warning for an unknown model, or `None` when it is known
+pub fn model_warning(model: &str, extra: &[String]) -> Option<String> {
+    match check_model(model, extra) {
+        ModelCheck::Known => None,
+        ModelCheck::Unknown { suggestion: Some(id) } => Some(format!(
+            "Unknown model '{}' in llm.model; did you mean '{}'?",
+            model, id
+        )),
+        ModelCheck::Unknown { suggestion: None } => Some(format!(
+            "Unknown model '{}' in llm.model; add it to llm.extra_models if it is new",
+            model
+        )),
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != *cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_models_and_aliases() {
+        assert_eq!(check_model("claude-sonnet-4-20250514", &[]), ModelCheck::Known);
+        assert_eq!(check_model("claude-3-5-haiku-latest", &[]), ModelCheck::Known);
+        assert_eq!(
+            check_model("claude-sonnet-5-20260101", &["claude-sonnet-5-20260101".to_string()]),
+            ModelCheck::Known
+        );
+    }
+
+    #[test]
+    fn test_typo_gets_suggestion() {
+        assert_eq!(
+            check_model("claude-sonet-4-20250514", &[]),
+            ModelCheck::Unknown {
+                suggestion: Some("claude-sonnet-4-20250514")
+            }
+        );
+        assert_eq!(
+            check_model("claude-something-else", &[]),
+            ModelCheck::Unknown { suggestion: None }
+        );
+    }
+
+    #[test]
+    fn test_model_warning() {
+        assert_eq!(model_warning("claude-opus-4-20250514", &[]), None);
+        assert!(model_warning("claude-opus-4-2025051", &[]).unwrap().contains("did you mean 'claude-opus-4-20250514'"));
+        assert!(model_warning("claude-x", &[]).unwrap().contains("llm.extra_models"));
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+}
@@ -0,0 +1,81 @@
+use std::ops::Range;
+
+/// Word put in place of each placeholder when a command with blanks is checked
+const STAND_IN: &str = "placeholder";
+
+/// Byte ranges of the `<branch-name>`/`<message>` blanks in a command, which
+/// the LLM leaves for information the query didn't give
+///
+/// A placeholder is `<`, a letter, then letters, digits, `-` or `_`, and `>`;
+/// anything else with angle brackets is left to the validator.
+pub fn placeholders(command: &str) -> Vec<Range<usize>> {
+    let bytes = command.as_bytes();
+    let mut found = Vec::new();
+    let mut start = 0;
+    while let Some(offset) = command[start..].find('<') {
+        let open = start + offset;
+        let name_len = bytes[open + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'-' || **b == b'_')
+            .count();
+        let close = open + 1 + name_len;
+        if name_len > 0 && bytes[open + 1].is_ascii_alphabetic() && bytes.get(close) == Some(&b'>') {
+            found.push(open..close + 1);
+            start = close + 1;
+        } else {
+            start = open + 1;
+        }
+    }
+    found
+}
+
+/// The command with a plain word in place of every placeholder, so the rest
+/// of it can be checked before the blanks are filled
+pub fn with_stand_ins(command: &str) -> String {
+    let mut filled = String::with_capacity(command.len());
+    let mut last = 0;
+    for range in placeholders(command) {
+        filled.push_str(&command[last..range.start]);
+        filled.push_str(STAND_IN);
+        last = range.end;
+    }
+    filled.push_str(&command[last..]);
+    filled
+}
+
+/// "<branch-name>, <message>": the blanks still to fill in
+pub fn describe_placeholders(command: &str) -> String {
+    placeholders(command)
+        .into_iter()
+        .map(|range| &command[range])
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finds_placeholders() {
+        let command = "git commit -m <message> && git push origin <branch_name>";
+        let names: Vec<&str> = placeholders(command).into_iter().map(|range| &command[range]).collect();
+        assert_eq!(names, ["<message>", "<branch_name>"]);
+        assert_eq!(describe_placeholders(command), "<message>, <branch_name>");
+
+        // Redirections and stray brackets aren't placeholders
+        assert!(placeholders("git log > out.txt").is_empty());
+        assert!(placeholders("git log <<EOF").is_empty());
+        assert!(placeholders("git log <1abc> <a b>").is_empty());
+        assert_eq!(with_stand_ins("git switch <<name>"), "git switch <placeholder");
+    }
+
+    #[test]
+    fn test_stand_ins() {
+        assert_eq!(
+            with_stand_ins("git switch -c <branch-name> <start>"),
+            "git switch -c placeholder placeholder"
+        );
+        assert_eq!(with_stand_ins("git log > out.txt"), "git log > out.txt");
+    }
+}
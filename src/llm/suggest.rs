@@ -0,0 +1,260 @@
+use crate::llm::client::{GitCommand, LLMClient, LLMError};
+use crate::llm::context::RepoContext;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// Input that turns live suggestions on or off for the session
+pub const SUGGEST_COMMAND: &str = ":suggest";
+
+/// Shortest input worth asking about
+const MIN_QUERY_CHARS: usize = 4;
+
+/// Whether `input` is a natural-language query rather than a command
+pub fn is_suggestible(input: &str) -> bool {
+    let input = input.trim();
+    input.chars().count() >= MIN_QUERY_CHARS
+        && !input.starts_with(':')
+        && !input.starts_with("git ")
+        // Several requests become a plan; one command can't preview that
+        && !input.contains(';')
+}
+
+/// Translations requested while the user pauses typing, shown as a hint
+/// beneath the input
+///
+/// Each distinct input is asked about at most once, only after `delay`
+/// without a keystroke, and never while an earlier request is in flight.
+pub struct LiveSuggester {
+    enabled: bool,
+    delay: Duration,
+    /// Input as of the last keystroke, and when it changed
+    input: String,
+    edited_at: Option<Instant>,
+    /// Last input sent, so pausing again on the same text doesn't resend it
+    requested: Option<String>,
+    pending: Option<(String, oneshot::Receiver<Result<GitCommand, LLMError>>)>,
+    /// The input a suggestion answers, and the suggested command
+    suggestion: Option<(String, String)>,
+    /// Set when the rate limiter refuses a suggestion
+    paused_until: Option<Instant>,
+}
+
+impl LiveSuggester {
+    pub fn new(enabled: bool, delay: Duration) -> Self {
+        Self {
+            enabled,
+            delay,
+            input: String::new(),
+            edited_at: None,
+            requested: None,
+            pending: None,
+            suggestion: None,
+            paused_until: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn suggestions on or off for the rest of the session
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pending = None;
+            self.suggestion = None;
+        }
+    }
+
+    /// Note the current input; an edit restarts the pause
+    pub fn observe_input(&mut self, input: &str, now: Instant) {
+        if input != self.input {
+            self.input = input.to_string();
+            self.edited_at = Some(now);
+        }
+    }
+
+    /// Whether typing has paused long enough to ask about the current input
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.enabled
+            && self.pending.is_none()
+            && self.paused_until.is_none_or(|until| now >= until)
+            && self.edited_at.is_some_and(|at| now.duration_since(at) >= self.delay)
+            && self.requested.as_deref() != Some(self.input.as_str())
+            && is_suggestible(&self.input)
+    }
+
+    /// Ask `client` about the current input in the background
+    pub fn request(&mut self, client: Arc<dyn LLMClient>, context: RepoContext) {
+        let query = self.input.clone();
+        let (sender, receiver) = oneshot::channel();
+        let task_query = query.clone();
+        tokio::spawn(async move {
+            let _ = sender.send(client.suggest(&task_query, &context).await);
+        });
+        self.requested = Some(query.clone());
+        self.pending = Some((query, receiver));
+    }
+
+    /// Collect a finished request, keeping its command when `accepts` allows
+    ///
+    /// Returns true when the visible suggestion changed.
+    pub fn poll(&mut self, now: Instant, accepts: impl Fn(&str) -> bool) -> bool {
+        let Some((query, receiver)) = self.pending.as_mut() else {
+            return false;
+        };
+        let result = match receiver.try_recv() {
+            Ok(result) => result,
+            Err(oneshot::error::TryRecvError::Empty) => return false,
+            Err(oneshot::error::TryRecvError::Closed) => {
+                self.pending = None;
+                return false;
+            }
+        };
+        let query = std::mem::take(query);
+        self.pending = None;
+
+        match result {
+            Ok(command) if accepts(&command.command) => {
+                self.suggestion = Some((query, command.command));
+                return true;
+            }
+            Ok(command) => tracing::debug!(command = %command.command, "live suggestion rejected"),
+            Err(LLMError::RateLimitExceeded(secs)) => {
+                tracing::debug!(secs, "live suggestions paused by rate limit");
+                self.paused_until = Some(now + Duration::from_secs(secs.max(1)));
+            }
+            Err(e) => tracing::debug!(error = %e, "live suggestion failed"),
+        }
+        false
+    }
+
+    /// The suggested command, while the input is still what it answers
+    pub fn suggestion(&self) -> Option<&str> {
+        match self.suggestion {
+            Some((ref query, ref command)) if self.enabled && *query == self.input => Some(command),
+            _ => None,
+        }
+    }
+
+    /// Take the visible suggestion to use as the input
+    pub fn accept(&mut self) -> Option<String> {
+        self.suggestion()?;
+        self.suggestion.take().map(|(_, command)| command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct SuggestClient {
+        reply: fn() -> Result<GitCommand, LLMError>,
+    }
+
+    #[async_trait]
+    impl LLMClient for SuggestClient {
+        async fn translate(&self, _query: &str, _context: &RepoContext) -> Result<GitCommand, LLMError> {
+            unreachable!("live suggestions never translate")
+        }
+
+        async fn suggest(&self, _query: &str, _context: &RepoContext) -> Result<GitCommand, LLMError> {
+            (self.reply)()
+        }
+    }
+
+    fn context() -> RepoContext {
+        RepoContext {
+            default_info: "Branch: main".to_string(),
+            escalated_info: None,
+            estimated_tokens: 3,
+        }
+    }
+
+    fn client(reply: fn() -> Result<GitCommand, LLMError>) -> Arc<dyn LLMClient> {
+        Arc::new(SuggestClient { reply })
+    }
+
+    async fn settle(suggester: &mut LiveSuggester, now: Instant, accepts: fn(&str) -> bool) -> bool {
+        for _ in 0..100 {
+            if suggester.poll(now, accepts) {
+                return true;
+            }
+            if suggester.pending.is_none() {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!("suggestion never finished");
+    }
+
+    #[test]
+    fn test_is_suggestible() {
+        assert!(is_suggestible("show what changed"));
+        assert!(!is_suggestible("sho"));
+        assert!(!is_suggestible("git status"));
+        assert!(!is_suggestible(":stack"));
+        assert!(!is_suggestible("stage all; commit"));
+    }
+
+    #[tokio::test]
+    async fn test_suggests_after_pause_once_per_input() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(800);
+        let mut suggester = LiveSuggester::new(true, delay);
+
+        suggester.observe_input("show what changed", start);
+        assert!(!suggester.is_due(start + delay / 2));
+        assert!(suggester.is_due(start + delay));
+
+        suggester.request(
+            client(|| Ok(GitCommand { command: "git diff".to_string(), explanation: None })),
+            context(),
+        );
+        assert!(!suggester.is_due(start + delay));
+        assert!(settle(&mut suggester, start + delay, |_| true).await);
+        assert_eq!(suggester.suggestion(), Some("git diff"));
+        assert!(!suggester.is_due(start + delay * 2));
+
+        // Typing on hides the suggestion and restarts the pause
+        let later = start + delay * 2;
+        suggester.observe_input("show what changed in src", later);
+        assert_eq!(suggester.suggestion(), None);
+        assert!(!suggester.is_due(later));
+        assert!(suggester.is_due(later + delay));
+
+        // Back to the answered text, Tab takes the command
+        suggester.observe_input("show what changed", later);
+        assert_eq!(suggester.accept().as_deref(), Some("git diff"));
+        assert_eq!(suggester.suggestion(), None);
+
+        suggester.set_enabled(false);
+        assert!(!suggester.is_due(later + delay * 2));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_rate_limited_suggestions() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(800);
+        let mut suggester = LiveSuggester::new(true, delay);
+
+        suggester.observe_input("delete everything", start);
+        suggester.request(
+            client(|| Ok(GitCommand { command: "git clean -fdx".to_string(), explanation: None })),
+            context(),
+        );
+        assert!(!settle(&mut suggester, start + delay, |_| false).await);
+        assert_eq!(suggester.suggestion(), None);
+
+        suggester.observe_input("undo my last commit", start + delay);
+        suggester.request(client(|| Err(LLMError::RateLimitExceeded(20))), context());
+        assert!(!settle(&mut suggester, start + delay, |_| true).await);
+
+        // No new requests until the limiter's window has passed
+        suggester.observe_input("undo the last commit", start + delay);
+        assert!(!suggester.is_due(start + delay * 2));
+        assert!(suggester.is_due(start + delay + Duration::from_secs(20)));
+    }
+}
@@ -1,8 +1,16 @@
 use crate::audit::AuditLogger;
+use crate::config::{Config, Correction, Corrections};
+use crate::git::{Repository, StateCache};
+use crate::llm::anthropic::{AnthropicClient, GenerationParams};
+use crate::llm::mcp::McpContextSource;
 use crate::llm::client::{GitCommand, LLMClient, LLMError};
-use crate::llm::context::ContextBuilder;
-use crate::security::ALLOWED_GIT_SUBCOMMANDS;
+use crate::llm::alternatives::{MAX_ALTERNATIVES, is_vague_query};
+use crate::llm::context::{ContextBuilder, RepoContext};
+use crate::llm::warmup::ContextWarmup;
+use crate::llm::placeholders::with_stand_ins;
+use crate::security::{CommandOrigin, CommandValidator, ValidationError, Workspace, check_llm_output};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -15,12 +23,28 @@ pub enum TranslationError {
 
     #[error("LLM returned invalid output: {0}")]
     InvalidOutput(String),
+
+    #[error("Command rejected by security validator: {0}")]
+    Rejected(#[from] ValidationError),
 }
 
+/// Upper bound on `llm.repair_attempts`
+pub const MAX_REPAIR_ATTEMPTS: u32 = 3;
+
 pub struct Translator {
     client: Box<dyn LLMClient>,
     context_builder: ContextBuilder,
     audit_logger: Option<Arc<AuditLogger>>,
+    /// Recent edits the user made to translations in this repository
+    corrections: Vec<Correction>,
+    /// Checks proposed commands the way the executor will
+    validator: Option<CommandValidator>,
+    /// Repair round-trips allowed after a rejected command
+    repair_attempts: u32,
+    /// Cheaper client for live suggestions, when enabled
+    suggest_client: Option<Arc<dyn LLMClient>>,
+    /// Offer a choice of commands for vague queries
+    alternatives: bool,
 }
 
 impl Translator {
@@ -29,9 +53,63 @@ impl Translator {
             client,
             context_builder,
             audit_logger: None,
+            corrections: Vec::new(),
+            validator: None,
+            repair_attempts: 0,
+            suggest_client: None,
+            alternatives: false,
         }
     }
 
+    /// Create a Translator from config, or None when no API key is available
+    pub fn from_config(repo: &Repository, config: &Config) -> Option<Self> {
+        let api_key = config.get_api_key()?;
+        // The network settings were checked when the config was loaded
+        let client = AnthropicClient::with_model(api_key, config.llm.model.clone())
+            .with_generation(GenerationParams {
+                max_tokens: config.llm.max_tokens,
+                temperature: config.llm.temperature,
+                top_p: config.llm.top_p,
+                system_prompt: config.llm.system_prompt.clone(),
+            })
+            .with_network(
+                Duration::from_secs(config.llm.connect_timeout_secs.max(1)),
+                Duration::from_secs(config.llm.request_timeout_secs.max(1)),
+                &config.network,
+            )
+            .ok()?;
+        let suggest_client = config
+            .llm
+            .live_suggest
+            .then(|| Arc::new(client.suggestion_client(config.llm.live_suggest_model.clone())) as Arc<dyn LLMClient>);
+        let client = Box::new(client);
+        let repo = repo.clone().with_second_remote(config.git.second_remote.clone());
+        let mut context_builder = ContextBuilder::new(repo.clone()).with_rename_threshold(config.git.rename_threshold);
+        if !config.mcp.is_empty() {
+            context_builder = context_builder.with_mcp(McpContextSource::new(config.mcp.clone()));
+        }
+        if config.llm.warmup_context {
+            context_builder = context_builder.with_warmup();
+        }
+        let validator = CommandValidator::new()
+            .with_workspace(Workspace::new(repo.path(), config.git.projects_dirs.clone()))
+            .with_allowed_hosts(config.git.allowed_hosts.clone());
+        let mut translator = Self::new(client, context_builder)
+            .with_validator(validator)
+            .with_repair_attempts(config.llm.repair_attempts)
+            .with_alternatives(config.llm.alternatives);
+        translator.suggest_client = suggest_client;
+        if config.behavior.log_commands {
+            translator.audit_logger = AuditLogger::for_config(config).ok().map(Arc::new);
+        }
+        if config.llm.learn_from_edits {
+            // A broken corrections file only costs the examples
+            let corrections = Corrections::load().unwrap_or_default();
+            translator = translator.with_corrections(corrections.recent(repo.path()));
+        }
+        Some(translator)
+    }
+
     /// Create a new Translator with audit logging enabled
     pub fn with_audit_logger(
         client: Box<dyn LLMClient>,
@@ -42,106 +120,209 @@ impl Translator {
             client,
             context_builder,
             audit_logger: Some(audit_logger),
+            corrections: Vec::new(),
+            validator: None,
+            repair_attempts: 0,
+            suggest_client: None,
+            alternatives: false,
         }
     }
 
-    pub async fn translate(&self, query: &str) -> Result<GitCommand, TranslationError> {
-        // Classify the query to determine context needs
-        let query_type = ContextBuilder::classify_query(query);
+    /// Build context from repository states published by the UI when recent
+    pub fn with_state_cache(mut self, state_cache: StateCache) -> Self {
+        self.context_builder = self.context_builder.with_state_cache(state_cache);
+        self
+    }
 
-        // Build appropriate context
-        let context = self.context_builder.build_escalated_context(query_type)?;
+    /// Show these corrections to the LLM as examples with every translation
+    pub fn with_corrections(mut self, corrections: Vec<Correction>) -> Self {
+        self.corrections = corrections;
+        self
+    }
 
-        // Translate using LLM
-        let command = self.client.translate(query, &context).await?;
+    pub fn set_corrections(&mut self, corrections: Vec<Correction>) {
+        self.corrections = corrections;
+    }
 
-        // Validate LLM output before returning
-        if let Err(e) = Self::validate_llm_output(&command.command) {
-            // Log validation failure if audit logger is available
-            if let Some(logger) = &self.audit_logger {
-                let repo_path = self.context_builder.repo_path();
-                let _ = logger.log_validation_failure(
-                    query,
-                    &command.command,
-                    &e.to_string(),
-                    repo_path,
-                );
+    /// Also reject proposals this validator would refuse to run
+    pub fn with_validator(mut self, validator: CommandValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Send rejected commands back to the LLM with the reason, up to
+    /// [`MAX_REPAIR_ATTEMPTS`] times
+    pub fn with_repair_attempts(mut self, attempts: u32) -> Self {
+        self.repair_attempts = attempts.min(MAX_REPAIR_ATTEMPTS);
+        self
+    }
+
+    /// Ask for alternatives when a query is vague
+    pub fn with_alternatives(mut self, enabled: bool) -> Self {
+        self.alternatives = enabled;
+        self
+    }
+
+    /// Offer live suggestions through `client`
+    pub fn with_suggest_client(mut self, client: Arc<dyn LLMClient>) -> Self {
+        self.suggest_client = Some(client);
+        self
+    }
+
+    /// Client and default context for a live suggestion, when enabled
+    ///
+    /// The context is built here so the request itself can run off the UI thread.
+    pub fn suggestion_request(&self) -> Option<(Arc<dyn LLMClient>, RepoContext)> {
+        let client = self.suggest_client.clone()?;
+        match self.context_builder.build_default_context() {
+            Ok(context) => Some((client, context)),
+            Err(e) => {
+                tracing::debug!(error = %e, "no context for live suggestion");
+                None
             }
-            return Err(e);
         }
+    }
 
-        Ok(command)
+    /// Handle prebuilding the translation context, when `llm.warmup_context` is on
+    pub fn context_warmup(&self) -> Option<ContextWarmup> {
+        self.context_builder.warmup()
     }
 
-    /// Validate that LLM output looks like a git command
-    fn validate_llm_output(output: &str) -> Result<(), TranslationError> {
-        let trimmed = output.trim();
+    /// Whether a suggested command would pass the checks a translation gets
+    pub fn accepts_suggestion(&self, command: &str) -> bool {
+        self.check_output(command).is_ok()
+    }
 
-        // Check for empty output
-        if trimmed.is_empty() {
-            return Err(TranslationError::InvalidOutput(
-                "LLM returned empty command".to_string(),
-            ));
+    /// Context escalated for the query's type, with the user's corrections
+    ///
+    /// Built on the blocking pool: it runs git and waits on MCP servers.
+    async fn query_context(&self, query: &str) -> Result<RepoContext, TranslationError> {
+        let builder = self.context_builder.clone();
+        let query = query.to_string();
+        let mut context = tokio::task::spawn_blocking(move || builder.build_query_context(&query))
+            .await
+            .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))?;
+        if !self.corrections.is_empty() {
+            context.default_info.push_str(&corrections_section(&self.corrections));
+            context.estimated_tokens = ContextBuilder::estimate_tokens(&context.get_full_context());
         }
+        Ok(context)
+    }
 
-        // Check for excessively long output (likely hallucination/explanation)
-        if trimmed.len() > 500 {
-            return Err(TranslationError::InvalidOutput(
-                format!("LLM output too long ({} chars), expected git command", trimmed.len()),
-            ));
+    /// Translate a query; a vague one, with alternatives on, gives up to
+    /// [`MAX_ALTERNATIVES`] differing commands that pass validation to pick from
+    ///
+    /// Falls back to a single translation when fewer than two are usable.
+    pub async fn translate_options(&self, query: &str) -> Result<Vec<GitCommand>, TranslationError> {
+        if self.alternatives && is_vague_query(query) {
+            let context = self.query_context(query).await?;
+            match self.client.alternatives(query, &context).await {
+                Ok(options) => {
+                    let mut accepted: Vec<GitCommand> = Vec::new();
+                    for option in options {
+                        if let Err(e) = self.check_output(&option.command) {
+                            if let Some(logger) = &self.audit_logger {
+                                let repo_path = self.context_builder.repo_path();
+                                let _ = logger.log_validation_failure(query, &option.command, &e.to_string(), repo_path);
+                            }
+                        } else if !accepted.iter().any(|a| a.command == option.command) {
+                            accepted.push(option);
+                        }
+                    }
+                    accepted.truncate(MAX_ALTERNATIVES);
+                    if accepted.len() > 1 {
+                        return Ok(accepted);
+                    }
+                }
+                Err(e) => tracing::debug!(error = %e, "no alternatives, translating once"),
+            }
         }
+        Ok(vec![self.translate(query).await?])
+    }
 
-        // Check if it contains newlines (likely explanation, not a command)
-        if trimmed.contains('\n') {
-            return Err(TranslationError::InvalidOutput(
-                "LLM output contains newlines, expected single git command".to_string(),
-            ));
-        }
+    #[tracing::instrument(name = "translation", skip_all, fields(query = %query))]
+    pub async fn translate(&self, query: &str) -> Result<GitCommand, TranslationError> {
+        // Build context escalated for the query's type
+        let context = self.query_context(query).await?;
 
-        // Check for shell metacharacters (command injection attempts)
-        let shell_metacharacters = [";", "|", "&", "$", "`", ">", "<"];
-        for meta in &shell_metacharacters {
-            if trimmed.contains(meta) {
-                return Err(TranslationError::InvalidOutput(
-                    format!("LLM output contains shell metacharacter '{}': '{}'", meta, trimmed),
-                ));
+        // Translate using LLM
+        let mut command = self.client.translate(query, &context).await?;
+
+        // Validate LLM output before returning, asking for a fix when rejected
+        let mut attempt = 0;
+        while let Err(e) = self.check_output(&command.command) {
+            let reason = e.to_string();
+            let repo_path = self.context_builder.repo_path();
+            if let Some(logger) = &self.audit_logger {
+                let _ = logger.log_validation_failure(query, &command.command, &reason, repo_path);
+            }
+            if attempt >= self.repair_attempts {
+                tracing::debug!(error = %e, "translation rejected");
+                return Err(e);
+            }
+
+            attempt += 1;
+            if let Some(logger) = &self.audit_logger {
+                let _ = logger.log_repair_attempt(query, attempt, &reason, repo_path);
             }
+            command = self
+                .client
+                .repair(query, &context, &command.command, &reason)
+                .await?;
         }
 
-        // Check if it starts with "git " or looks like a git subcommand
-        let starts_with_git = trimmed.starts_with("git ");
-        let first_word = trimmed.split_whitespace().next().unwrap_or("");
+        tracing::debug!(command = %command.command, repairs = attempt, "translated");
+        Ok(command)
+    }
 
-        // Use shared allowlist from security module (same as validator)
-        let looks_like_git = starts_with_git || ALLOWED_GIT_SUBCOMMANDS.contains(&first_word);
+    /// Run the output checks and, when configured, the execution validator
+    ///
+    /// Placeholders are checked as plain words; the preview has them filled
+    /// in, and validated again, before anything runs.
+    fn check_output(&self, output: &str) -> Result<(), TranslationError> {
+        let output = with_stand_ins(output);
+        Self::validate_llm_output(&output)?;
+        if let Some(ref validator) = self.validator {
+            validator.validate_with_origin(&output, CommandOrigin::Llm)?;
+        }
+        Ok(())
+    }
 
-        if !looks_like_git {
+    /// Ask the LLM to explain a git command the user typed directly
+    pub async fn explain(&self, command: &str) -> Result<String, TranslationError> {
+        let explanation = self.client.explain(command).await?;
+        if explanation.trim().is_empty() {
             return Err(TranslationError::InvalidOutput(
-                format!("LLM output doesn't look like a git command: '{}'", trimmed),
+                "LLM returned an empty explanation".to_string(),
             ));
         }
+        Ok(explanation)
+    }
 
-        // Check for suspicious content that might indicate hallucination
-        let suspicious_patterns = [
-            "I think", "I would", "You should", "Please", "Here's",
-            "Let me", "To do this", "First,", "Then,", "Finally,",
-        ];
-
-        for pattern in &suspicious_patterns {
-            if trimmed.contains(pattern) {
-                return Err(TranslationError::InvalidOutput(
-                    format!("LLM output contains explanation text: '{}'", trimmed),
-                ));
-            }
-        }
+    /// Validate that LLM output looks like a git command
+    pub fn validate_llm_output(output: &str) -> Result<(), TranslationError> {
+        check_llm_output(output).map_err(|e| TranslationError::InvalidOutput(e.0))
+    }
+}
 
-        Ok(())
+/// Context section listing the user's recent edits as corrective examples
+fn corrections_section(corrections: &[Correction]) -> String {
+    let mut section = String::from(
+        "\n=== Corrections from this user ===\nFor similar requests, prefer what the user ran over what was proposed.\n",
+    );
+    for correction in corrections {
+        section.push_str(&format!(
+            "  Query: {}\n    proposed: {}\n    user ran: {}\n",
+            correction.query, correction.proposed, correction.executed
+        ));
     }
+    section
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::init_repo;
     use crate::llm::client::LLMError;
     use crate::llm::context::RepoContext;
     use async_trait::async_trait;
@@ -160,6 +341,139 @@ mod tests {
         }
     }
 
+    /// Answers with each response in turn, repeating the last one
+    struct ScriptedLLMClient {
+        responses: Vec<&'static str>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl ScriptedLLMClient {
+        fn new(responses: Vec<&'static str>) -> Self {
+            Self {
+                responses,
+                calls: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl LLMClient for ScriptedLLMClient {
+        async fn translate(&self, _query: &str, _context: &RepoContext) -> Result<GitCommand, LLMError> {
+            let call = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let response = self.responses[call.min(self.responses.len() - 1)];
+            Ok(GitCommand {
+                command: response.to_string(),
+                explanation: None,
+            })
+        }
+    }
+
+    fn scripted_translator(
+        temp: &tempfile::TempDir,
+        responses: Vec<&'static str>,
+        attempts: u32,
+    ) -> Translator {
+        use crate::git::Repository;
+        init_repo(temp.path());
+        let logger = AuditLogger::with_path(temp.path().join("audit.log")).unwrap();
+        Translator::with_audit_logger(
+            Box::new(ScriptedLLMClient::new(responses)),
+            ContextBuilder::new(Repository::new(temp.path())),
+            Arc::new(logger),
+        )
+        .with_validator(CommandValidator::new())
+        .with_repair_attempts(attempts)
+    }
+
+    #[tokio::test]
+    async fn test_rejected_output_is_repaired() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let translator = scripted_translator(&temp, vec!["git log | head", "git log -5"], 1);
+
+        let command = translator.translate("last five commits").await.unwrap();
+        assert_eq!(command.command, "git log -5");
+
+        let log = std::fs::read_to_string(temp.path().join("audit.log")).unwrap();
+        assert!(log.contains("[VALIDATION-REJECTED] query=\"last five commits\" llm_output=\"git log | head\""));
+        assert!(log.contains("[REPAIR-ATTEMPT 1] query=\"last five commits\""));
+    }
+
+    #[tokio::test]
+    async fn test_repairs_are_bounded() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let translator = scripted_translator(&temp, vec!["git frobnicate"], 2);
+
+        let result = translator.translate("do the thing").await;
+        assert!(matches!(result, Err(TranslationError::Rejected(_))));
+
+        let log = std::fs::read_to_string(temp.path().join("audit.log")).unwrap();
+        assert_eq!(log.matches("[VALIDATION-REJECTED]").count(), 3);
+        assert_eq!(log.matches("[REPAIR-ATTEMPT").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_repair_when_disabled() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let translator = scripted_translator(&temp, vec!["git log | head", "git log -5"], 0);
+        assert!(translator.translate("last five commits").await.is_err());
+        assert_eq!(translator.with_repair_attempts(10).repair_attempts, MAX_REPAIR_ATTEMPTS);
+    }
+
+    /// Offers fixed alternatives; a plain translation gives `git status`
+    struct AlternativesClient {
+        options: Vec<&'static str>,
+    }
+
+    #[async_trait]
+    impl LLMClient for AlternativesClient {
+        async fn translate(&self, _query: &str, _context: &RepoContext) -> Result<GitCommand, LLMError> {
+            Ok(GitCommand {
+                command: "git status".to_string(),
+                explanation: None,
+            })
+        }
+
+        async fn alternatives(&self, _query: &str, _context: &RepoContext) -> Result<Vec<GitCommand>, LLMError> {
+            Ok(self
+                .options
+                .iter()
+                .map(|command| GitCommand {
+                    command: command.to_string(),
+                    explanation: Some("why".to_string()),
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_vague_query_gets_validated_alternatives() {
+        use crate::git::Repository;
+        let temp = tempfile::TempDir::new().unwrap();
+        init_repo(temp.path());
+        let translator = |options: Vec<&'static str>, enabled: bool| {
+            Translator::new(
+                Box::new(AlternativesClient { options }),
+                ContextBuilder::new(Repository::new(temp.path())),
+            )
+            .with_validator(CommandValidator::new())
+            .with_alternatives(enabled)
+        };
+
+        let options = translator(vec!["git clean -n", "git log | head", "git restore .", "git clean -n"], true)
+            .translate_options("clean this up")
+            .await
+            .unwrap();
+        let commands: Vec<&str> = options.iter().map(|o| o.command.as_str()).collect();
+        assert_eq!(commands, ["git clean -n", "git restore ."]);
+
+        // Specific queries, a disabled option or a single usable candidate translate once
+        let once = |options: Vec<GitCommand>| options.len() == 1 && options[0].command == "git status";
+        let both = vec!["git clean -n", "git restore ."];
+        assert!(once(translator(both.clone(), true).translate_options("show the status").await.unwrap()));
+        assert!(once(translator(both, false).translate_options("clean this up").await.unwrap()));
+        assert!(once(translator(vec!["git clean -n", "git log | head"], true).translate_options("tidy").await.unwrap()));
+    }
+
     #[tokio::test]
     async fn test_translator_basic() {
         use crate::git::Repository;
@@ -181,6 +495,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_corrections_section() {
+        let section = corrections_section(&[Correction {
+            repo: std::path::PathBuf::from("/repo"),
+            query: "show history".to_string(),
+            proposed: "git log".to_string(),
+            executed: "git log --oneline --graph".to_string(),
+        }]);
+        assert!(section.contains("=== Corrections from this user ==="));
+        assert!(section.contains("  Query: show history\n    proposed: git log\n    user ran: git log --oneline --graph\n"));
+    }
+
     // LLM output validation tests
     #[test]
     fn test_validate_llm_output_valid_with_git_prefix() {
@@ -277,7 +603,8 @@ mod tests {
             "status", "log", "show", "diff", "branch", "tag", "remote", "reflog",
             "blame", "describe", "add", "commit", "checkout", "switch", "restore",
             "reset", "revert", "merge", "rebase", "cherry-pick", "stash", "clean",
-            "push", "pull", "fetch", "clone", "config", "filter-branch",
+            "push", "pull", "fetch", "clone", "config", "filter-branch", "gc",
+            "maintenance", "notes", "ls-files", "update-index",
         ];
 
         for cmd in &subcommands {
@@ -294,6 +621,21 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_explain_unsupported_by_default() {
+        use crate::git::Repository;
+
+        if let Ok(repo) = Repository::discover() {
+            let mock_client = Box::new(MockLLMClient {
+                response: "git status".to_string(),
+            });
+            let translator = Translator::new(mock_client, ContextBuilder::new(repo));
+
+            let result = translator.explain("git status").await;
+            assert!(matches!(result, Err(TranslationError::LLMError(_))));
+        }
+    }
+
     #[tokio::test]
     async fn test_translator_rejects_invalid_llm_output() {
         use crate::git::Repository;
@@ -0,0 +1,134 @@
+use crate::git::{Repository, StateCache};
+use crate::llm::context::{ContextBuilder, RENAME_TARGETS, RepoContext, renames_section};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Context prebuilt from one published state
+#[derive(Debug, Default)]
+pub(crate) struct WarmContext {
+    /// State cache generation it was built from
+    pub(crate) generation: u64,
+    pub(crate) default: Option<RepoContext>,
+    /// Renames section per `git diff` target
+    pub(crate) renames: HashMap<&'static str, String>,
+    /// A rebuild is under way on a background thread
+    running: bool,
+}
+
+/// Builds translation context in the background from each state the UI publishes
+///
+/// The state cache's refresh cycle keeps it current, so a translation only
+/// waits for the API instead of for git as well. Clones share the same slot.
+#[derive(Debug, Clone)]
+pub struct ContextWarmup {
+    repo: Repository,
+    state_cache: StateCache,
+    rename_threshold: u8,
+    warm: Arc<Mutex<WarmContext>>,
+}
+
+impl ContextWarmup {
+    pub(crate) fn new(
+        repo: Repository,
+        state_cache: StateCache,
+        rename_threshold: u8,
+        warm: Arc<Mutex<WarmContext>>,
+    ) -> Self {
+        Self {
+            repo,
+            state_cache,
+            rename_threshold,
+            warm,
+        }
+    }
+
+    /// Whether a state was published (or dropped) since the context was built
+    pub fn is_stale(&self) -> bool {
+        self.warm.lock().unwrap().generation != self.state_cache.generation()
+    }
+
+    /// Rebuild on a background thread when stale and not already rebuilding
+    pub fn spawn(&self) {
+        {
+            let mut warm = self.warm.lock().unwrap();
+            if warm.running || warm.generation == self.state_cache.generation() {
+                return;
+            }
+            warm.running = true;
+        }
+        let warmup = self.clone();
+        thread::spawn(move || {
+            warmup.run();
+            warmup.warm.lock().unwrap().running = false;
+        });
+    }
+
+    /// Build the context for the latest published state, however old
+    ///
+    /// Never loads a state itself: that is the UI's refresh, which would
+    /// otherwise run the same git commands twice.
+    pub fn run(&self) {
+        let generation = self.state_cache.generation();
+        let mut warm = WarmContext {
+            generation,
+            ..WarmContext::default()
+        };
+        if let Some(snapshot) = self.state_cache.fresh(self.repo.path(), Duration::MAX) {
+            warm.generation = snapshot.generation;
+            warm.default = Some(ContextBuilder::default_context(&snapshot.state, None));
+            for target in RENAME_TARGETS {
+                warm.renames.insert(target, renames_section(&self.repo, target, self.rename_threshold));
+            }
+        }
+        let mut slot = self.warm.lock().unwrap();
+        warm.running = slot.running;
+        *slot = warm;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_warm_context_follows_published_states() {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        std::fs::write(temp.path().join("config.rs"), "fn main() {}\n".repeat(5)).unwrap();
+        git(temp.path(), &["add", "config.rs"]);
+        git(temp.path(), &["commit", "-q", "-m", "config"]);
+        git(temp.path(), &["mv", "config.rs", "settings.rs"]);
+
+        let repo = Repository::new(temp.path());
+        let cache = StateCache::new();
+        let mut state = repo.state().unwrap();
+        state.current_branch = Some("warmed".to_string());
+        cache.publish(repo.path(), state);
+
+        let builder = ContextBuilder::new(repo.clone()).with_state_cache(cache.clone()).with_warmup();
+        let warmup = builder.warmup().unwrap();
+        assert!(warmup.is_stale());
+        warmup.run();
+        assert!(!warmup.is_stale());
+
+        // git sees no rename any more, the warm context still does: it was built first
+        git(temp.path(), &["mv", "settings.rs", "config.rs"]);
+        let full = builder.build_query_context("which files did I rename").unwrap().get_full_context();
+        assert!(full.contains("Current branch: warmed"));
+        assert!(full.contains("renamed: config.rs → settings.rs"), "{}", full);
+        assert!(builder.build_default_context().unwrap().default_info.contains("warmed"));
+
+        // A new state makes it stale until rebuilt
+        cache.publish(repo.path(), repo.state().unwrap());
+        assert!(warmup.is_stale());
+        warmup.run();
+        let full = builder.build_query_context("which files did I rename").unwrap().get_full_context();
+        assert!(!full.contains("=== Renames ==="), "{}", full);
+
+        assert!(ContextBuilder::new(repo).warmup().is_none());
+    }
+}
@@ -1,16 +1,62 @@
 use crossterm::{
     execute,
+    event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use gitalky::config::{Config, FirstRunWizard};
-use gitalky::{GitVersion, Repository};
+use clap::Parser;
+use gitalky::audit::history::{render_json, render_table};
+use gitalky::audit::{AuditLogger, HistoryFormat, SqliteSink};
+use gitalky::cli::{
+    Cli, CliCommand, CliRunner, HistoryCommand, RunCommand, RunError, exit_codes, interactive, write_completions,
+    write_man_page,
+};
+use gitalky::config::first_run::SetupError;
+use gitalky::config::{Config, FirstRunWizard, RecentRepos, StartupWizard, TrustLevel, TrustStore};
+use gitalky::git::{
+    DoctorCheck, LockOwner, LockStatus, SessionLock, check_git, git_binary, render_checks, set_git_binary,
+    set_parse_locale,
+};
+use gitalky::{GitError, GitVersion, Repository};
+use gitalky::server::Server;
+use gitalky::telemetry;
 use gitalky::ui::App;
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, Write};
 use std::panic;
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+
+    // Generated from the definitions above for packagers; touch nothing else
+    match cli.command {
+        Some(CliCommand::Completions { shell }) => return write_completions(shell, &mut io::stdout()),
+        Some(CliCommand::Man) => return write_man_page(&mut io::stdout()),
+        _ => {}
+    }
+
+    if let Err(e) = telemetry::init(cli.verbose) {
+        eprintln!("Warning: Diagnostics log unavailable: {}", e);
+    }
+
+    // Query the audit log; needs neither git nor a repository
+    if let Some(CliCommand::History(history)) = cli.command {
+        run_history(history);
+    }
+
+    // git.binary_path picks the git everything below runs
+    let early_config = Config::load();
+    set_git_binary(early_config.as_ref().ok().and_then(|config| config.git.binary_path.clone()));
+
+    let command = match cli.command {
+        Some(CliCommand::Doctor) => run_doctor(&early_config),
+        // One-shot, non-interactive; prints nothing beyond what scripts ask for
+        Some(CliCommand::Run(run)) => {
+            run_once(run, Config::or_default_when_missing(early_config)).await
+        }
+        command => command,
+    };
+
     // Validate git version
     match GitVersion::validate() {
         Ok(version) => {
@@ -18,11 +64,32 @@ async fn main() -> io::Result<()> {
         }
         Err(e) => {
             eprintln!("Error: {}", e);
+            eprintln!("Run `gitalky doctor` for details.");
             std::process::exit(1);
         }
     }
 
-    // Load or create configuration
+    // Headless daemon for editors: JSON-RPC / MCP over stdio
+    if matches!(command, Some(CliCommand::Serve)) {
+        // Never run the interactive wizard here; stdout belongs to the protocol
+        let config = Config::or_default_when_missing(Config::load()).unwrap_or_else(|e| {
+            eprintln!("Error: ~/.config/gitalky/config.toml: {}", e);
+            std::process::exit(1);
+        });
+        set_parse_locale(config.git.parse_locale());
+        eprintln!("gitalky serve: listening for JSON-RPC on stdin");
+        return Server::new(config).serve_stdio().await;
+    }
+
+    // A TUI in a CI job or a pipe would wait for keys nobody presses
+    if !interactive() {
+        eprintln!("Error: gitalky needs a terminal on stdin and stdout.");
+        eprintln!("In scripts and CI use `gitalky run <query>`; it never runs dangerous operations there.");
+        std::process::exit(1);
+    }
+
+    // Load or create configuration; a fresh setup is followed by the tutorial
+    let mut first_run = false;
     let config = match Config::load() {
         Ok(config) => {
             eprintln!("Loaded configuration from ~/.config/gitalky/config.toml");
@@ -45,6 +112,7 @@ async fn main() -> io::Result<()> {
                             if let Err(e) = config.save() {
                                 eprintln!("Warning: Failed to save config: {}", e);
                             }
+                            first_run = true;
                             config
                         }
                         Err(e) => {
@@ -57,45 +125,231 @@ async fn main() -> io::Result<()> {
         }
     };
 
-    // Discover repository
+    set_parse_locale(config.git.parse_locale());
+
+    // Discover repository, or offer recent repos / clone / init outside one
+    let mut recent = RecentRepos::load().unwrap_or_default();
     let repo = match Repository::discover() {
         Ok(repo) => repo,
+        Err(GitError::NotARepository) => {
+            let cwd = std::env::current_dir()?;
+            match StartupWizard::new(cwd, &config).run(&recent) {
+                Ok(repo) => repo,
+                Err(SetupError::Cancelled) => std::process::exit(0),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
         Err(e) => {
             eprintln!("Error: {}", e);
             std::process::exit(1);
         }
     };
+    recent.record(repo.path());
+    if let Err(e) = recent.save() {
+        eprintln!("Warning: Failed to save recent repositories: {}", e);
+    }
+
+    // Take the per-repo session lock so two instances don't interleave commands
+    let (session_lock, lock_owner) = match SessionLock::acquire(&repo) {
+        Ok(LockStatus::Acquired(lock)) => (Some(lock), None),
+        Ok(LockStatus::HeldBy(owner)) => {
+            if !prompt_read_only(&owner)? {
+                std::process::exit(1);
+            }
+            (None, Some(owner))
+        }
+        Err(e) => {
+            eprintln!("Warning: Failed to acquire session lock: {}", e);
+            (None, None)
+        }
+    };
+
+    // Ask once per repository whether it may run commands that modify it
+    let trusted = !config.behavior.repo_trust || repository_trusted(&repo)?;
 
     // Set up panic hook to restore terminal
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), DisableFocusChange, DisableBracketedPaste, LeaveAlternateScreen);
         original_hook(panic_info);
     }));
 
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create and run app
-    let result = match App::new(repo, config) {
-        Ok(mut app) => app.run(&mut terminal).await,
-        Err(e) => {
-            // Restore terminal before showing error
-            disable_raw_mode()?;
-            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-            eprintln!("Error creating app: {}", e);
-            std::process::exit(1);
-        }
+    // Create and run app; the repository state loads behind the first frame
+    let app = App::new_lazy(repo, config);
+    let app = match (session_lock, lock_owner) {
+        (Some(lock), _) => app.with_session_lock(lock),
+        (None, Some(owner)) => app.with_read_only(&owner),
+        (None, None) => app,
     };
+    let app = if trusted { app } else { app.with_untrusted() };
+    let mut app = if first_run { app.with_tutorial() } else { app };
+    let result = app.run(&mut terminal).await;
 
     // Restore terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableFocusChange, DisableBracketedPaste, LeaveAlternateScreen)?;
 
     result
 }
+
+/// Warn that another instance owns the repository and ask whether to continue read-only
+fn prompt_read_only(owner: &LockOwner) -> io::Result<bool> {
+    eprintln!(
+        "\n⚠️  Another gitalky instance (PID {}, started {}) is running in this repository.",
+        owner.pid, owner.started_at
+    );
+    eprintln!("Running both could interleave commands and garble the audit log.");
+    eprint!("\nContinue in read-only mode? [y/N]: ");
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// The saved trust decision for `repo`, asking for one the first time
+///
+/// A trust file that can't be read leaves the repository untrusted; saving an
+/// empty store over it would forget every earlier decision.
+fn repository_trusted(repo: &Repository) -> io::Result<bool> {
+    let mut store = match TrustStore::load() {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("Warning: Can't read ~/.config/gitalky/trust.toml ({}); this repository stays untrusted", e);
+            return Ok(false);
+        }
+    };
+    if let Some(level) = store.level(repo.path()) {
+        return Ok(level == TrustLevel::Trusted);
+    }
+
+    eprintln!("\n🔒 First time in {}.", repo.path().display());
+    eprintln!("Untrusted repositories only run commands that don't modify them (:trust lifts this later).");
+    eprint!("\nDo you trust this repository? [y/N]: ");
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let trusted = matches!(input.trim().to_lowercase().as_str(), "y" | "yes");
+    store.set(repo.path(), if trusted { TrustLevel::Trusted } else { TrustLevel::Untrusted });
+    if let Err(e) = store.save() {
+        eprintln!("Warning: Failed to save repository trust: {}", e);
+    }
+    Ok(trusted)
+}
+
+/// `gitalky doctor`: check the setup and say how to fix what's wrong
+fn run_doctor(config: &Result<Config, gitalky::config::settings::ConfigError>) -> ! {
+    let mut checks = vec![match config {
+        Ok(_) => DoctorCheck::pass("config", "~/.config/gitalky/config.toml loaded"),
+        Err(e) => DoctorCheck::fail(
+            "config",
+            e.to_string(),
+            "Fix ~/.config/gitalky/config.toml, or run gitalky without it for first-run setup",
+        ),
+    }];
+    let configured = config.as_ref().is_ok_and(|config| config.git.binary_path.is_some());
+    checks.extend(check_git(git_binary(), configured));
+
+    print!("{}", render_checks(&checks));
+    std::process::exit(if checks.iter().all(|check| check.ok) { 0 } else { 1 });
+}
+
+/// `gitalky run [options] <query>`: translate, validate and run one command,
+/// exiting with one of [`exit_codes`]
+async fn run_once(args: RunCommand, config: Result<Config, gitalky::config::settings::ConfigError>) -> ! {
+    let quiet = args.quiet;
+    let fail = |error: RunError| -> ! {
+        match error {
+            RunError::Git { ref stdout, .. } => {
+                print!("{}", stdout);
+                eprintln!("{}{}", if quiet { "" } else { "Error: " }, error);
+            }
+            _ => eprintln!("{}{}", if quiet { "" } else { "Error: " }, error),
+        }
+        std::process::exit(error.exit_code());
+    };
+
+    let args = args.into_run_args().unwrap_or_else(|e| fail(e));
+    // A config that doesn't load would drop approval, rate limits and allowed hosts
+    let config = config.unwrap_or_else(|e| fail(RunError::Config(e.to_string())));
+    if let Err(e) = GitVersion::validate() {
+        fail(RunError::git(e));
+    }
+    set_parse_locale(config.git.parse_locale());
+    let repo = Repository::discover().unwrap_or_else(|e| fail(RunError::git(e)));
+
+    // Nobody at a terminal to have meant --yes: dangerous operations are refused
+    let runner = if interactive() { CliRunner::new(config) } else { CliRunner::new(config).with_safe_mode() };
+    let outcome = runner.run(&repo, &args).await.unwrap_or_else(|e| fail(e));
+    let Some(output) = outcome.output else {
+        println!("{}", outcome.command);
+        std::process::exit(exit_codes::SUCCESS);
+    };
+    if quiet {
+        eprintln!("{}", outcome.command);
+    } else {
+        eprintln!("$ {}", outcome.command);
+        if let Some(explanation) = outcome.explanation {
+            eprintln!("  {}", explanation);
+        }
+        eprint!("{}", output.stderr);
+    }
+    match output.raw_stdout {
+        Some(bytes) => {
+            let _ = io::stdout().write_all(&bytes);
+        }
+        None => print!("{}", output.stdout),
+    }
+    let _ = io::stdout().flush();
+    if let Some(warning) = outcome.warning {
+        eprintln!("Warning: {}", warning);
+    }
+    std::process::exit(exit_codes::SUCCESS);
+}
+
+/// `gitalky history [filters]`: print matching audit log entries and exit
+fn run_history(args: HistoryCommand) -> ! {
+    let query = match args.into_query(chrono::Utc::now()) {
+        Ok(query) => query,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(2);
+        }
+    };
+    // A sqlite audit sink answers from its indexes instead of the whole log
+    let config = Config::load().unwrap_or_else(|_| Config::default_config());
+    let entries = AuditLogger::new().and_then(|logger| {
+        match config.audit_sinks.iter().find_map(|sink| sink.database(logger.log_path())) {
+            Some(database) => SqliteSink::open(&database)
+                .and_then(|sink| sink.query(&query))
+                .map_err(io::Error::other),
+            None => query.run(logger.log_path()),
+        }
+    });
+    match entries {
+        Ok(entries) => {
+            match query.format {
+                HistoryFormat::Table if entries.is_empty() => eprintln!("No matching commands"),
+                HistoryFormat::Table => print!("{}", render_table(&entries)),
+                HistoryFormat::Json => println!("{}", render_json(&entries)),
+            }
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error: Failed to read the audit log: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::git::{RepositoryState, split_command};
+use std::io;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Subcommands that talk to a remote and may take a while
+pub const LONG_RUNNING_SUBCOMMANDS: &[&str] = &["push", "fetch", "pull", "clone"];
+
+/// Desktop notification with a title and body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+}
+
+impl Notification {
+    /// Build a completion notification from the repository state before and after
+    pub fn for_command(
+        command: &str,
+        success: bool,
+        before: &RepositoryState,
+        after: &RepositoryState,
+    ) -> Self {
+        let subcommand = remote_subcommand(command).unwrap_or("git");
+        let title = if success {
+            format!("gitalky: {} finished", subcommand)
+        } else {
+            format!("gitalky: {} failed", subcommand)
+        };
+
+        let mut body = summarize_state_change(before, after);
+        if body.is_empty() {
+            body = if success {
+                "No changes to the repository".to_string()
+            } else {
+                command.to_string()
+            };
+        }
+
+        Self { title, body }
+    }
+
+    /// Show the notification using the platform's notifier
+    ///
+    /// Uses `notify-send` on Linux/BSD and `osascript` on macOS. The notifier
+    /// runs detached; failures to spawn it are returned.
+    pub fn send(&self) -> io::Result<()> {
+        let mut command = if cfg!(target_os = "macos") {
+            let script = format!(
+                "display notification {} with title {}",
+                applescript_string(&self.body),
+                applescript_string(&self.title)
+            );
+            let mut command = Command::new("osascript");
+            command.args(["-e", &script]);
+            command
+        } else {
+            let mut command = Command::new("notify-send");
+            command.args(["--app-name=gitalky", &self.title, &self.body]);
+            command
+        };
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        // Reap in the background so the UI never waits on the notifier
+        std::thread::spawn(move || child.wait());
+        Ok(())
+    }
+}
+
+/// Check whether a finished command deserves a desktop notification
+pub fn should_notify(command: &str, elapsed: Duration, threshold: Duration, focused: bool) -> bool {
+    !focused && elapsed >= threshold && remote_subcommand(command).is_some()
+}
+
+/// The long-running remote subcommand a command invokes, if any
+fn remote_subcommand(command: &str) -> Option<&'static str> {
+    let words = split_command(command).ok()?;
+    let subcommand = words
+        .iter()
+        .map(String::as_str)
+        .skip_while(|w| *w == "git")
+        .find(|w| !w.starts_with('-'))?;
+    LONG_RUNNING_SUBCOMMANDS.iter().copied().find(|s| *s == subcommand)
+}
+
+/// Describe what changed between two repository states in one line
+pub fn summarize_state_change(before: &RepositoryState, after: &RepositoryState) -> String {
+    let mut parts = Vec::new();
+
+    if before.current_branch != after.current_branch {
+        parts.push(format!(
+            "on {}",
+            after.current_branch.as_deref().unwrap_or("detached HEAD")
+        ));
+    }
+
+    match (&before.upstream, &after.upstream) {
+        (None, Some(upstream)) => parts.push(format!("now tracking {}", upstream.remote_branch)),
+        (Some(old), Some(new)) => {
+            if new.ahead < old.ahead {
+                parts.push(format!("pushed {} commit(s)", old.ahead - new.ahead));
+            }
+            if new.behind > old.behind {
+                parts.push(format!("{} new commit(s) on {}", new.behind - old.behind, new.remote_branch));
+            }
+            if new.behind < old.behind && new.ahead >= old.ahead {
+                parts.push(format!("pulled {} commit(s)", old.behind - new.behind));
+            }
+        }
+        _ => {}
+    }
+
+    let new_commits = after
+        .recent_commits
+        .iter()
+        .take_while(|c| before.recent_commits.first().map(|b| b.hash != c.hash).unwrap_or(true))
+        .count();
+    if new_commits > 0 && before.current_branch == after.current_branch {
+        let has_pulled = parts.iter().any(|p| p.starts_with("pulled"));
+        if !has_pulled {
+            parts.push(format!("{} new local commit(s)", new_commits));
+        }
+    }
+
+    if after.in_merge && !before.in_merge {
+        parts.push("merge in progress (conflicts?)".to_string());
+    }
+
+    parts.join(", ")
+}
+
+/// Quote a string as an AppleScript literal
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{CommitEntry, UpstreamInfo};
+
+    fn state(ahead: usize, behind: usize, commits: &[&str]) -> RepositoryState {
+        RepositoryState {
+            current_branch: Some("main".to_string()),
+            upstream: Some(UpstreamInfo {
+                remote_branch: "origin/main".to_string(),
+                ahead,
+                behind,
+            }),
+            staged_files: Vec::new(),
+            unstaged_files: Vec::new(),
+            untracked_files: Vec::new(),
+            recent_commits: commits
+                .iter()
+                .map(|h| CommitEntry {
+                    hash: h.to_string(),
+                    message: "msg".to_string(),
+                    has_note: false,
+                    signature: None,
+                })
+                .collect(),
+            stashes: Vec::new(),
+            in_merge: false,
+            in_rebase: false,
+            rebase: None,
+            second_upstream: None,
+            user_name: None,
+            user_email: None,
+            shallow_depth: None,
+            hidden_files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_should_notify() {
+        let threshold = Duration::from_secs(10);
+        let long = Duration::from_secs(12);
+        let short = Duration::from_secs(2);
+
+        assert!(should_notify("git push origin main", long, threshold, false));
+        assert!(should_notify("fetch --all", long, threshold, false));
+        assert!(!should_notify("git push", long, threshold, true));
+        assert!(!should_notify("git push", short, threshold, false));
+        assert!(!should_notify("git status", long, threshold, false));
+    }
+
+    #[test]
+    fn test_summarize_push() {
+        let before = state(3, 0, &["a"]);
+        let after = state(0, 0, &["a"]);
+        assert_eq!(summarize_state_change(&before, &after), "pushed 3 commit(s)");
+    }
+
+    #[test]
+    fn test_summarize_fetch() {
+        let before = state(0, 0, &["a"]);
+        let after = state(0, 4, &["a"]);
+        assert_eq!(
+            summarize_state_change(&before, &after),
+            "4 new commit(s) on origin/main"
+        );
+    }
+
+    #[test]
+    fn test_summarize_pull() {
+        let before = state(0, 2, &["a"]);
+        let after = state(0, 0, &["c", "b", "a"]);
+        assert_eq!(summarize_state_change(&before, &after), "pulled 2 commit(s)");
+    }
+
+    #[test]
+    fn test_notification_text() {
+        let before = state(1, 0, &["a"]);
+        let after = state(0, 0, &["a"]);
+
+        let notification = Notification::for_command("git push", true, &before, &after);
+        assert_eq!(notification.title, "gitalky: push finished");
+        assert_eq!(notification.body, "pushed 1 commit(s)");
+
+        let notification = Notification::for_command("git push", false, &before, &before);
+        assert_eq!(notification.title, "gitalky: push failed");
+        assert_eq!(notification.body, "git push");
+    }
+
+    #[test]
+    fn test_applescript_string() {
+        assert_eq!(applescript_string(r#"say "hi""#), r#""say \"hi\"""#);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod desktop;
+pub mod terminal;
+
+pub use desktop::{Notification, should_notify, summarize_state_change};
+pub use terminal::{TerminalInfo, TerminalIntegration, TerminalNotifications, window_title};
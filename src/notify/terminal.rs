@@ -0,0 +1,220 @@
+use crate::notify::desktop::Notification;
+use serde::{Deserialize, Serialize};
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+/// Whether finished commands are announced with terminal escape sequences
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalNotifications {
+    /// Use the desktop notifier only
+    #[default]
+    Off,
+    /// Pick OSC 9 or OSC 777 from the terminal's environment variables
+    Auto,
+    Osc9,
+    Osc777,
+}
+
+/// Escape sequence a terminal understands for notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OscProtocol {
+    /// `ESC ] 9 ; body BEL`: iTerm2, WezTerm, Windows Terminal
+    Osc9,
+    /// `ESC ] 777 ; notify ; title ; body BEL`: urxvt, foot, Ghostty
+    Osc777,
+}
+
+/// What the terminal identifies itself as, from `TERM`, `TERM_PROGRAM` and `TMUX`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalInfo {
+    pub term: String,
+    pub term_program: String,
+    pub in_tmux: bool,
+}
+
+impl TerminalInfo {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        Self {
+            term: var("TERM"),
+            term_program: var("TERM_PROGRAM"),
+            in_tmux: std::env::var_os("TMUX").is_some(),
+        }
+    }
+
+    /// Dumb terminals and the Linux console print titles as garbage
+    pub fn supports_title(&self) -> bool {
+        !matches!(self.term.as_str(), "" | "dumb" | "linux")
+    }
+
+    /// Notification protocol to use under `setting`, if the terminal has one
+    pub fn notification_protocol(&self, setting: TerminalNotifications) -> Option<OscProtocol> {
+        match setting {
+            TerminalNotifications::Off => None,
+            TerminalNotifications::Osc9 => Some(OscProtocol::Osc9),
+            TerminalNotifications::Osc777 => Some(OscProtocol::Osc777),
+            TerminalNotifications::Auto => match self.term_program.as_str() {
+                "iTerm.app" | "WezTerm" => Some(OscProtocol::Osc9),
+                "ghostty" => Some(OscProtocol::Osc777),
+                _ if self.term.starts_with("rxvt") || self.term.starts_with("foot") => Some(OscProtocol::Osc777),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Terminal title and OSC notifications, skipped where unsupported
+pub struct TerminalIntegration {
+    title_enabled: bool,
+    protocol: Option<OscProtocol>,
+    in_tmux: bool,
+    /// Last title written, so unchanged titles aren't rewritten every frame
+    title: Option<String>,
+}
+
+impl TerminalIntegration {
+    pub fn new(info: &TerminalInfo, title: bool, notifications: TerminalNotifications) -> Self {
+        // Nothing to talk to when output is redirected
+        let tty = io::stdout().is_terminal();
+        Self {
+            title_enabled: title && tty && info.supports_title(),
+            protocol: info.notification_protocol(notifications).filter(|_| tty),
+            in_tmux: info.in_tmux,
+            title: None,
+        }
+    }
+
+    /// Save the current title on the terminal's title stack (xterm `CSI 22 t`)
+    pub fn save_title(&self) -> io::Result<()> {
+        if !self.title_enabled {
+            return Ok(());
+        }
+        write_sequence("\x1b[22;0t")
+    }
+
+    /// Set the title when it changed since the last call
+    pub fn set_title(&mut self, title: &str) -> io::Result<()> {
+        if !self.title_enabled || self.title.as_deref() == Some(title) {
+            return Ok(());
+        }
+        self.title = Some(title.to_string());
+        write_sequence(&title_sequence(title))
+    }
+
+    /// Put back the title saved by [`Self::save_title`]
+    pub fn restore_title(&mut self) -> io::Result<()> {
+        if !self.title_enabled || self.title.take().is_none() {
+            return Ok(());
+        }
+        write_sequence("\x1b[23;0t")
+    }
+
+    /// Whether notifications go through the terminal
+    pub fn can_notify(&self) -> bool {
+        self.protocol.is_some()
+    }
+
+    /// Emit `notification` as an OSC sequence; false when the terminal has none
+    pub fn notify(&self, notification: &Notification) -> io::Result<bool> {
+        let Some(protocol) = self.protocol else {
+            return Ok(false);
+        };
+        let sequence = notification_sequence(protocol, &notification.title, &notification.body);
+        write_sequence(&if self.in_tmux { tmux_passthrough(&sequence) } else { sequence })?;
+        Ok(true)
+    }
+}
+
+/// "gitalky — <repo> (<branch>)"
+pub fn window_title(repo: &Path, branch: Option<&str>) -> String {
+    let name = repo
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| repo.display().to_string());
+    format!("gitalky — {} ({})", name, branch.unwrap_or("detached HEAD"))
+}
+
+/// OSC 2 sets the window title
+pub fn title_sequence(title: &str) -> String {
+    format!("\x1b]2;{}\x07", sanitize(title))
+}
+
+pub fn notification_sequence(protocol: OscProtocol, title: &str, body: &str) -> String {
+    match protocol {
+        OscProtocol::Osc9 => format!("\x1b]9;{}: {}\x07", sanitize(title), sanitize(body)),
+        // Fields are `;`-separated
+        OscProtocol::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            sanitize(title).replace(';', ","),
+            sanitize(body).replace(';', ",")
+        ),
+    }
+}
+
+/// Wrap a sequence so tmux forwards it to the outer terminal (needs
+/// `allow-passthrough`; tmux drops it otherwise)
+fn tmux_passthrough(sequence: &str) -> String {
+    format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+}
+
+/// Control characters would end the sequence early
+fn sanitize(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+fn write_sequence(sequence: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(term: &str, term_program: &str) -> TerminalInfo {
+        TerminalInfo {
+            term: term.to_string(),
+            term_program: term_program.to_string(),
+            in_tmux: false,
+        }
+    }
+
+    #[test]
+    fn test_window_title() {
+        assert_eq!(window_title(Path::new("/home/me/src/gitalky"), Some("main")), "gitalky — gitalky (main)");
+        assert_eq!(window_title(Path::new("/srv/app"), None), "gitalky — app (detached HEAD)");
+    }
+
+    #[test]
+    fn test_sequences() {
+        assert_eq!(title_sequence("a\x07b"), "\x1b]2;a b\x07");
+        assert_eq!(
+            notification_sequence(OscProtocol::Osc9, "gitalky: push finished", "2 commits pushed"),
+            "\x1b]9;gitalky: push finished: 2 commits pushed\x07"
+        );
+        assert_eq!(
+            notification_sequence(OscProtocol::Osc777, "done", "a; b\nc"),
+            "\x1b]777;notify;done;a, b c\x07"
+        );
+        assert_eq!(tmux_passthrough("\x1b]9;x\x07"), "\x1bPtmux;\x1b\x1b]9;x\x07\x1b\\");
+    }
+
+    #[test]
+    fn test_detection() {
+        assert!(info("xterm-256color", "").supports_title());
+        assert!(!info("dumb", "").supports_title());
+        assert!(!info("", "").supports_title());
+
+        let auto = TerminalNotifications::Auto;
+        assert_eq!(info("xterm-256color", "iTerm.app").notification_protocol(auto), Some(OscProtocol::Osc9));
+        assert_eq!(info("rxvt-unicode-256color", "").notification_protocol(auto), Some(OscProtocol::Osc777));
+        assert_eq!(info("xterm-256color", "Apple_Terminal").notification_protocol(auto), None);
+        assert_eq!(info("xterm", "").notification_protocol(TerminalNotifications::Off), None);
+        assert_eq!(
+            info("xterm", "").notification_protocol(TerminalNotifications::Osc777),
+            Some(OscProtocol::Osc777)
+        );
+    }
+}
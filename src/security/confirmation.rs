@@ -0,0 +1,288 @@
+use crate::git::executor::split_command;
+use crate::security::DangerousOp;
+use crate::security::paths::positional_args;
+use crate::security::remote_url::PUSH_OPTIONS_WITH_VALUE;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How much friction a dangerous operation gets before it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    /// A single `y`
+    Low,
+    /// Enter on the highlighted warning
+    Medium,
+    /// Typing the branch name or the number of files affected
+    High,
+}
+
+/// What the confirmation prompt asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Confirmation {
+    KeyPress,
+    Acknowledge,
+    /// The exact text to type
+    TypePhrase(String),
+}
+
+impl Confirmation {
+    /// Whether the text typed so far could still become the phrase
+    pub fn is_on_track(&self, typed: &str) -> bool {
+        match self {
+            Confirmation::TypePhrase(phrase) => phrase.starts_with(typed),
+            _ => true,
+        }
+    }
+}
+
+/// `[confirmation]`: risk level of each dangerous operation
+///
+/// ```toml
+/// [confirmation]
+/// protected_branches = ["main", "release"]
+///
+/// [confirmation.levels]
+/// rebase = "medium"
+/// hard_reset = "high"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConfirmationPolicy {
+    /// Overrides of the built-in level, keyed by operation
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub levels: BTreeMap<DangerousOp, RiskLevel>,
+    /// Force pushes to these branches are always high risk
+    #[serde(default = "default_protected_branches")]
+    pub protected_branches: Vec<String>,
+}
+
+fn default_protected_branches() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string()]
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            levels: BTreeMap::new(),
+            protected_branches: default_protected_branches(),
+        }
+    }
+}
+
+impl ConfirmationPolicy {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Risk of running `command`, classified as `op`
+    ///
+    /// Force pushes to a protected branch and `clean -x` (which also deletes
+    /// ignored files) are high risk whatever `levels` says.
+    pub fn risk(&self, op: &DangerousOp, command: &str, current_branch: Option<&str>) -> RiskLevel {
+        let level = self.levels.get(op).copied().unwrap_or_else(|| default_risk(op));
+        let escalated = match op {
+            DangerousOp::ForcePush => push_targets(command, current_branch)
+                .protected(&self.protected_branches)
+                .is_some(),
+            DangerousOp::Clean => removes_ignored(command),
+            _ => false,
+        };
+        if escalated { RiskLevel::High } else { level }
+    }
+}
+
+/// Built-in level: history rewrites of local work are low, losing
+/// uncommitted or shared work is medium, irreversible cleanup is high
+pub fn default_risk(op: &DangerousOp) -> RiskLevel {
+    match op {
+        DangerousOp::Rebase | DangerousOp::StashDrop | DangerousOp::Gc => RiskLevel::Low,
+        DangerousOp::ForcePush
+        | DangerousOp::HardReset
+        | DangerousOp::Clean
+        | DangerousOp::ForceCheckout
+        | DangerousOp::ForceSwitch
+        | DangerousOp::RestoreWorktree
+        | DangerousOp::DeleteBranch
+        | DangerousOp::StashClear
+        | DangerousOp::ReflogExpire
+        | DangerousOp::RemoveIndexLock => RiskLevel::Medium,
+        DangerousOp::FilterBranch | DangerousOp::GcPruneNow => RiskLevel::High,
+    }
+}
+
+/// Branches a `git push` updates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushTargets {
+    /// `--all`, `--branches` or `--mirror`: every local branch
+    AllBranches,
+    /// Each refspec's destination; the current branch when none is given
+    Branches(Vec<String>),
+}
+
+impl PushTargets {
+    /// The first of `protected` the push updates, if any
+    pub fn protected(&self, protected: &[String]) -> Option<String> {
+        match self {
+            PushTargets::AllBranches => protected.first().cloned(),
+            PushTargets::Branches(branches) => branches.iter().find(|branch| protected.contains(branch)).cloned(),
+        }
+    }
+}
+
+/// What a `git push` updates
+pub fn push_targets(command: &str, current_branch: Option<&str>) -> PushTargets {
+    let words = split_command(command).unwrap_or_default();
+    let args: Vec<String> = words.into_iter().skip_while(|w| w != "push").skip(1).collect();
+    if args.iter().any(|a| matches!(a.as_str(), "--all" | "--branches" | "--mirror")) {
+        return PushTargets::AllBranches;
+    }
+
+    // With `--repo` every positional argument is a refspec
+    let positional = positional_args(&args, PUSH_OPTIONS_WITH_VALUE);
+    let names_repo = args.iter().any(|a| a == "--repo" || a.starts_with("--repo="));
+    let refspecs = &positional[positional.len().min(if names_repo { 0 } else { 1 })..];
+    if refspecs.is_empty() {
+        return PushTargets::Branches(current_branch.map(str::to_string).into_iter().collect());
+    }
+    PushTargets::Branches(
+        refspecs
+            .iter()
+            .filter_map(|refspec| {
+                let destination = refspec.rsplit(':').next().unwrap_or(refspec).trim_start_matches('+');
+                match destination {
+                    "" => None,
+                    "HEAD" => current_branch.map(str::to_string),
+                    _ => Some(destination.strip_prefix("refs/heads/").unwrap_or(destination).to_string()),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Whether a `git clean` also removes ignored files (`-x`/`-X`)
+fn removes_ignored(command: &str) -> bool {
+    split_command(command).unwrap_or_default().iter().any(|w| {
+        w.starts_with('-') && !w.starts_with("--") && w.contains(['x', 'X'])
+    })
+}
+
+/// Executor arguments for the `git clean` with `-n` instead of `-f`,
+/// listing what it would delete
+pub fn clean_dry_run(command: &str) -> Option<String> {
+    let words = split_command(command).ok()?;
+    let words: Vec<&String> = words.iter().skip_while(|w| *w != "clean").collect();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut dry_run = Vec::new();
+    for word in words {
+        match word.as_str() {
+            "-f" | "--force" => {}
+            short if short.starts_with('-') && !short.starts_with("--") => {
+                let flags: String = short[1..].chars().filter(|c| *c != 'f').collect();
+                if !flags.is_empty() {
+                    dry_run.push(format!("-{}", flags));
+                }
+            }
+            other => dry_run.push(other.to_string()),
+        }
+        if word == "clean" {
+            dry_run.push("-n".to_string());
+        }
+    }
+    Some(dry_run.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_levels() {
+        let policy = ConfirmationPolicy::default();
+        assert_eq!(policy.risk(&DangerousOp::Rebase, "git rebase main", Some("feature")), RiskLevel::Low);
+        assert_eq!(policy.risk(&DangerousOp::HardReset, "git reset --hard", Some("main")), RiskLevel::Medium);
+        assert_eq!(
+            policy.risk(&DangerousOp::FilterBranch, "git filter-branch HEAD", None),
+            RiskLevel::High
+        );
+    }
+
+    #[test]
+    fn test_force_push_to_protected_branch_is_high() {
+        let policy = ConfirmationPolicy::default();
+        let op = DangerousOp::ForcePush;
+        assert_eq!(policy.risk(&op, "git push --force", Some("feature")), RiskLevel::Medium);
+        assert_eq!(policy.risk(&op, "git push --force", Some("main")), RiskLevel::High);
+        assert_eq!(policy.risk(&op, "git push -f origin HEAD:master", Some("feature")), RiskLevel::High);
+
+        // Any protected destination escalates, however it is named
+        for command in [
+            "git push -f origin topic main",
+            "git push -f origin feature main:main",
+            "git push --force --all origin",
+            "git push --mirror origin",
+            "git push -f --repo=origin main",
+            "git push -f -o ci.skip origin main",
+            "git push -f origin HEAD",
+        ] {
+            assert_eq!(policy.risk(&op, command, Some("main")), RiskLevel::High, "{}", command);
+        }
+        assert_eq!(policy.risk(&op, "git push -f origin topic", Some("main")), RiskLevel::Medium);
+    }
+
+    #[test]
+    fn test_clean_ignored_files_is_high() {
+        let policy = ConfirmationPolicy::default();
+        assert_eq!(policy.risk(&DangerousOp::Clean, "git clean -fd", None), RiskLevel::Medium);
+        assert_eq!(policy.risk(&DangerousOp::Clean, "git clean -fdx", None), RiskLevel::High);
+        assert_eq!(policy.risk(&DangerousOp::Clean, r"git clean -fd\x", None), RiskLevel::High);
+        assert_eq!(policy.risk(&DangerousOp::Clean, "git clean -fd '-x'", None), RiskLevel::High);
+    }
+
+    #[test]
+    fn test_levels_from_config() {
+        let policy: ConfirmationPolicy = toml::from_str(
+            r#"
+            protected_branches = ["release"]
+
+            [levels]
+            rebase = "medium"
+            force_push = "low"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(policy.risk(&DangerousOp::Rebase, "git rebase main", None), RiskLevel::Medium);
+        assert_eq!(policy.risk(&DangerousOp::ForcePush, "git push -f", Some("main")), RiskLevel::Low);
+        assert_eq!(policy.risk(&DangerousOp::ForcePush, "git push -f", Some("release")), RiskLevel::High);
+        assert!(!policy.is_default());
+    }
+
+    #[test]
+    fn test_push_targets() {
+        let branches = |names: &[&str]| PushTargets::Branches(names.iter().map(|n| n.to_string()).collect());
+        assert_eq!(push_targets("git push", Some("dev")), branches(&["dev"]));
+        assert_eq!(push_targets("git push --force origin", None), branches(&[]));
+        assert_eq!(push_targets("git push -f origin topic", Some("dev")), branches(&["topic"]));
+        assert_eq!(push_targets("git push origin +HEAD:refs/heads/main", Some("dev")), branches(&["main"]));
+        assert_eq!(push_targets("git push origin a b:c :gone", None), branches(&["a", "c", "gone"]));
+        assert_eq!(push_targets("git push --repo origin topic", None), branches(&["topic"]));
+        assert_eq!(push_targets("git push --mirror", None), PushTargets::AllBranches);
+    }
+
+    #[test]
+    fn test_clean_dry_run() {
+        assert_eq!(clean_dry_run("git clean -fdx").as_deref(), Some("clean -n -dx"));
+        assert_eq!(clean_dry_run("clean --force -d").as_deref(), Some("clean -n -d"));
+        assert_eq!(clean_dry_run("git reset --hard"), None);
+    }
+
+    #[test]
+    fn test_phrase_tracking() {
+        let confirmation = Confirmation::TypePhrase("main".to_string());
+        assert!(confirmation.is_on_track("ma"));
+        assert!(!confirmation.is_on_track("mx"));
+        assert!(Confirmation::KeyPress.is_on_track("anything"));
+    }
+}
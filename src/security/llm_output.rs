@@ -0,0 +1,92 @@
+use crate::security::ALLOWED_GIT_SUBCOMMANDS;
+use thiserror::Error;
+
+/// Why text doesn't look like the single git command a translation should be
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct LlmOutputError(pub String);
+
+/// Check that LLM output looks like a git command
+///
+/// Used on translations before they are shown, and again by the validator
+/// when the user edits one.
+pub fn check_llm_output(output: &str) -> Result<(), LlmOutputError> {
+    let trimmed = output.trim();
+
+    // Check for empty output
+    if trimmed.is_empty() {
+        return Err(LlmOutputError("LLM returned empty command".to_string()));
+    }
+
+    // Check for excessively long output (likely hallucination/explanation)
+    if trimmed.len() > 500 {
+        return Err(LlmOutputError(format!(
+            "LLM output too long ({} chars), expected git command",
+            trimmed.len()
+        )));
+    }
+
+    // Check if it contains newlines (likely explanation, not a command)
+    if trimmed.contains('\n') {
+        return Err(LlmOutputError(
+            "LLM output contains newlines, expected single git command".to_string(),
+        ));
+    }
+
+    // Check for shell metacharacters (command injection attempts)
+    let shell_metacharacters = [";", "|", "&", "$", "`", ">", "<"];
+    for meta in &shell_metacharacters {
+        if trimmed.contains(meta) {
+            return Err(LlmOutputError(format!(
+                "LLM output contains shell metacharacter '{}': '{}'",
+                meta, trimmed
+            )));
+        }
+    }
+
+    // Check if it starts with "git " or looks like a git subcommand
+    let starts_with_git = trimmed.starts_with("git ");
+    let first_word = trimmed.split_whitespace().next().unwrap_or("");
+
+    // Use shared allowlist (same as validator)
+    let looks_like_git = starts_with_git || ALLOWED_GIT_SUBCOMMANDS.contains(&first_word);
+
+    if !looks_like_git {
+        return Err(LlmOutputError(format!(
+            "LLM output doesn't look like a git command: '{}'",
+            trimmed
+        )));
+    }
+
+    // Check for suspicious content that might indicate hallucination
+    let suspicious_patterns = [
+        "I think", "I would", "You should", "Please", "Here's",
+        "Let me", "To do this", "First,", "Then,", "Finally,",
+    ];
+
+    for pattern in &suspicious_patterns {
+        if trimmed.contains(pattern) {
+            return Err(LlmOutputError(format!(
+                "LLM output contains explanation text: '{}'",
+                trimmed
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_llm_output() {
+        assert!(check_llm_output("git log --oneline -5").is_ok());
+        assert!(check_llm_output("status").is_ok());
+
+        let error = check_llm_output("git status && rm -rf ~").unwrap_err();
+        assert_eq!(error.to_string(), "LLM output contains shell metacharacter '&': 'git status && rm -rf ~'");
+        assert!(check_llm_output("Let me check: git status").is_err());
+    }
+}
@@ -1,11 +1,26 @@
+pub mod confirmation;
+pub mod llm_output;
+pub mod origin;
+pub mod paths;
+pub mod remote_url;
+pub mod secrets;
 pub mod validator;
 
-pub use validator::{CommandValidator, DangerousOp, ValidatedCommand, ValidationError};
+pub use confirmation::{Confirmation, ConfirmationPolicy, RiskLevel};
+pub use llm_output::{LlmOutputError, check_llm_output};
+pub use origin::CommandOrigin;
+pub use paths::Workspace;
+pub use remote_url::{UrlWarning, check_remote_urls, host_allowed, redact_url, remote_urls, url_host, url_warnings};
+pub use secrets::{CommitScope, SecretFinding, SecretKind, commit_scope, scan_diff};
+pub use validator::{
+    CommandValidator, DangerousOp, ValidatedCommand, ValidationError, is_instant_command,
+    is_read_only_command,
+};
 
 /// Allowlist of permitted git subcommands
 ///
 /// This list is used by both the CommandValidator (for command validation)
-/// and the LLM Translator (for LLM output validation) to ensure consistency.
+/// and the LLM output checks to ensure consistency.
 ///
 /// Adding a new subcommand requires careful security review.
 pub const ALLOWED_GIT_SUBCOMMANDS: &[&str] = &[
@@ -20,6 +35,8 @@ pub const ALLOWED_GIT_SUBCOMMANDS: &[&str] = &[
     "reflog",
     "blame",
     "describe",
+    "ls-files",
+    "verify-commit",
     // Write operations
     "add",
     "commit",
@@ -33,13 +50,61 @@ pub const ALLOWED_GIT_SUBCOMMANDS: &[&str] = &[
     "cherry-pick",
     "stash",
     "clean",
+    // Index bits only (flags are validated)
+    "update-index",
     // Remote operations
     "push",
     "pull",
     "fetch",
+    // Repository creation (destination path is validated)
     "clone",
+    "init",
     // Configuration (repo-level only)
     "config",
     // Dangerous operations (require confirmation)
     "filter-branch",
+    "gc",
+    // Background maintenance (actions and options are validated)
+    "maintenance",
+    // Commit annotations (actions and options are validated)
+    "notes",
+    // Bundle files (actions and the written path are validated)
+    "bundle",
+    // The user's configured diff.tool/merge.tool (run with --no-prompt; --extcmd refused)
+    "difftool",
+    "mergetool",
 ];
+
+/// `git maintenance` actions; `--config-file` and unknown options are refused
+pub const MAINTENANCE_ACTIONS: &[&str] = &["run", "start", "stop", "register", "unregister"];
+pub const MAINTENANCE_SCHEDULES: &[&str] = &["hourly", "daily", "weekly"];
+pub const MAINTENANCE_SCHEDULERS: &[&str] = &["auto", "crontab", "systemd-timer", "launchctl", "schtasks"];
+pub const MAINTENANCE_TASKS: &[&str] = &[
+    "gc", "commit-graph", "prefetch", "loose-objects", "incremental-repack", "pack-refs",
+];
+
+/// `git bundle` actions; `unbundle` writes objects without updating refs,
+/// so imports go through `git fetch <file>` instead
+pub const BUNDLE_ACTIONS: &[&str] = &["create", "verify", "list-heads"];
+
+/// `git update-index` options: toggling the bits that hide a file's changes
+pub const UPDATE_INDEX_FLAGS: &[&str] = &[
+    "--assume-unchanged", "--no-assume-unchanged", "--skip-worktree", "--no-skip-worktree", "-q", "--",
+];
+
+/// `git notes` actions; `edit`, `merge` and editor-opening forms are refused
+pub const NOTES_ACTIONS: &[&str] = &["list", "show", "add", "append", "copy", "remove", "prune", "get-ref"];
+
+/// `git notes` actions that only read notes
+pub const READ_ONLY_NOTES_ACTIONS: &[&str] = &["list", "show", "get-ref"];
+
+/// Subcommands that never modify the repository
+///
+/// Used to decide what may run while another gitalky instance holds the
+/// session lock and this instance is in read-only mode.
+pub const READ_ONLY_GIT_SUBCOMMANDS: &[&str] = &[
+    "status", "log", "show", "diff", "reflog", "blame", "describe", "ls-files", "verify-commit",
+];
+
+/// Subcommands that only list things when given no positional arguments
+pub const LISTING_GIT_SUBCOMMANDS: &[&str] = &["branch", "tag", "remote"];
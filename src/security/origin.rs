@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Where a command came from before it reached the validator
+///
+/// Provenance decides which checks apply: LLM output that the user edited is
+/// re-checked with the LLM-output rules, while user-typed commands only go
+/// through `CommandValidator`. Every origin is recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandOrigin {
+    /// Proposed by the LLM and run unchanged
+    Llm,
+    /// Proposed by the LLM, then modified in the preview
+    LlmEdited,
+    /// Typed directly by the user
+    #[default]
+    User,
+    /// Recalled from command history
+    History,
+    /// Emitted by a multi-step workflow
+    Workflow,
+    /// Suggested fix for a failed command (error rules)
+    Suggestion,
+}
+
+impl CommandOrigin {
+    /// Origin after the user edits the command text
+    pub fn edited(self) -> Self {
+        match self {
+            CommandOrigin::Llm => CommandOrigin::LlmEdited,
+            // Anything else the user edits is effectively user-typed
+            CommandOrigin::LlmEdited => CommandOrigin::LlmEdited,
+            _ => CommandOrigin::User,
+        }
+    }
+
+    /// Whether the LLM-output checks must run again before execution
+    pub fn requires_llm_output_check(self) -> bool {
+        self == CommandOrigin::LlmEdited
+    }
+
+    /// Short tag used in the audit log
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommandOrigin::Llm => "llm",
+            CommandOrigin::LlmEdited => "llm-edited",
+            CommandOrigin::User => "user",
+            CommandOrigin::History => "history",
+            CommandOrigin::Workflow => "workflow",
+            CommandOrigin::Suggestion => "suggestion",
+        }
+    }
+}
+
+impl fmt::Display for CommandOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edited_origin() {
+        assert_eq!(CommandOrigin::Llm.edited(), CommandOrigin::LlmEdited);
+        assert_eq!(CommandOrigin::LlmEdited.edited(), CommandOrigin::LlmEdited);
+        assert_eq!(CommandOrigin::History.edited(), CommandOrigin::User);
+        assert_eq!(CommandOrigin::Suggestion.edited(), CommandOrigin::User);
+    }
+
+    #[test]
+    fn test_only_edited_llm_needs_llm_check() {
+        assert!(CommandOrigin::LlmEdited.requires_llm_output_check());
+        assert!(!CommandOrigin::Llm.requires_llm_output_check());
+        assert!(!CommandOrigin::User.requires_llm_output_check());
+    }
+
+    #[test]
+    fn test_serde_names_match_audit_tags() {
+        for origin in [
+            CommandOrigin::Llm,
+            CommandOrigin::LlmEdited,
+            CommandOrigin::User,
+            CommandOrigin::History,
+            CommandOrigin::Workflow,
+            CommandOrigin::Suggestion,
+        ] {
+            let json = serde_json::to_string(&origin).unwrap();
+            assert_eq!(json, format!("\"{}\"", origin.as_str()));
+        }
+    }
+}
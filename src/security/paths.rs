@@ -0,0 +1,345 @@
+use crate::security::BUNDLE_ACTIONS;
+use crate::security::validator::{ValidationError, abbreviated_option};
+use std::path::{Component, Path, PathBuf};
+
+/// Long options that make `clone`/`init` run code or write outside the destination
+const UNSAFE_OPTIONS: &[&str] = &["--upload-pack", "--config", "--template", "--separate-git-dir"];
+
+/// Their short forms: `-u` (upload pack) and `-c` (config)
+const UNSAFE_SHORT_OPTIONS: &[char] = &['u', 'c'];
+
+/// Short options taking a value; in a group like `-bmain` the rest is the value
+const SHORT_OPTIONS_WITH_VALUE: &[char] = &['b', 'o', 'j'];
+
+/// Whether `arg` is an unsafe option however git would accept it spelled:
+/// abbreviated (`--upload-pac=`), with an attached value (`-u'cmd'`) or in a
+/// group of short flags (`-nu cmd`)
+fn is_unsafe_option(arg: &str) -> bool {
+    if let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.starts_with('-')) {
+        for flag in flags.chars() {
+            if UNSAFE_SHORT_OPTIONS.contains(&flag) {
+                return true;
+            }
+            if SHORT_OPTIONS_WITH_VALUE.contains(&flag) {
+                return false;
+            }
+        }
+        return false;
+    }
+    abbreviated_option(arg, UNSAFE_OPTIONS).is_some()
+}
+
+/// `clone` options that consume the following argument
+pub(crate) const CLONE_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-b", "--branch", "-o", "--origin", "--depth", "--reference", "--shallow-since",
+    "--shallow-exclude", "-j", "--jobs", "--filter",
+];
+
+/// `init` options that consume the following argument
+const INIT_OPTIONS_WITH_VALUE: &[&str] = &["-b", "--initial-branch", "--object-format"];
+
+/// Where `clone` and `init` may create repositories and `bundle create` may
+/// write bundle files
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Directory git runs in; relative destinations resolve against it
+    pub base_dir: PathBuf,
+    /// Extra directories new repositories may be created in
+    pub projects_dirs: Vec<PathBuf>,
+}
+
+impl Workspace {
+    pub fn new(base_dir: impl Into<PathBuf>, projects_dirs: Vec<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+            projects_dirs,
+        }
+    }
+
+    /// Validate the destination of a `clone`, `init` or `bundle` invocation
+    ///
+    /// `args` are the words after the subcommand. The destination must resolve
+    /// inside the base directory, its parent, or a configured projects dir.
+    /// `clone` also refuses non-empty destinations; `init` never overwrites
+    /// files (re-running it on a repository is harmless), so only its location
+    /// is checked. `bundle create` may not overwrite a file or write to stdout;
+    /// the bundles `verify` and `list-heads` read may be anywhere.
+    pub fn check(&self, subcommand: &str, args: &[String]) -> Result<(), ValidationError> {
+        if let Some(option) = args.iter().find(|a| is_unsafe_option(a)) {
+            return Err(ValidationError::DangerousFlags(option.clone()));
+        }
+
+        match subcommand {
+            "clone" => {
+                let positional = positional_args(args, CLONE_OPTIONS_WITH_VALUE);
+                let Some(source) = positional.first() else {
+                    return Ok(()); // git itself reports the missing repository
+                };
+                let dest = match positional.get(1) {
+                    Some(dest) => dest.to_string(),
+                    None => default_clone_dir(source),
+                };
+                let target = self.resolve(&dest)?;
+                if is_non_empty(&target) {
+                    return Err(ValidationError::UnsafePath(format!(
+                        "clone destination '{}' already exists and is not empty",
+                        dest
+                    )));
+                }
+                Ok(())
+            }
+            "init" => {
+                let positional = positional_args(args, INIT_OPTIONS_WITH_VALUE);
+                let dest = positional.first().map(|d| d.as_str()).unwrap_or(".");
+                self.resolve(dest).map(|_| ())
+            }
+            "bundle" => {
+                let mut words = args.iter().filter(|a| a.as_str() == "-" || !a.starts_with('-'));
+                let Some(action) = words.next() else {
+                    return Ok(()); // git prints its usage
+                };
+                if !BUNDLE_ACTIONS.contains(&action.as_str()) {
+                    return Err(ValidationError::DisallowedSubcommand(format!("bundle {}", action)));
+                }
+                let Some(file) = words.next().filter(|_| action == "create") else {
+                    return Ok(());
+                };
+                if file == "-" {
+                    return Err(ValidationError::UnsafePath("bundle create to stdout".to_string()));
+                }
+                if self.resolve(file)?.exists() {
+                    return Err(ValidationError::UnsafePath(format!("bundle file '{}' already exists", file)));
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resolve a destination and check it is inside an allowed root
+    fn resolve(&self, dest: &str) -> Result<PathBuf, ValidationError> {
+        let base = resolve_existing(&self.base_dir);
+        let target = resolve_existing(&base.join(dest));
+
+        let mut roots = vec![base.clone()];
+        if let Some(parent) = base.parent() {
+            roots.push(parent.to_path_buf());
+        }
+        roots.extend(self.projects_dirs.iter().map(|d| resolve_existing(d)));
+
+        if roots.iter().any(|root| target.starts_with(root)) {
+            Ok(target)
+        } else {
+            Err(ValidationError::UnsafePath(format!(
+                "'{}' is outside the repository, its parent directory and configured projects dirs",
+                dest
+            )))
+        }
+    }
+}
+
+/// Positional arguments, skipping options and their values
+pub(crate) fn positional_args<'a>(args: &'a [String], options_with_value: &[&str]) -> Vec<&'a String> {
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            positional.extend(iter.by_ref());
+            break;
+        }
+        if arg.starts_with('-') {
+            if options_with_value.contains(&arg.as_str()) {
+                iter.next();
+            }
+            continue;
+        }
+        positional.push(arg);
+    }
+    positional
+}
+
+/// Directory name git derives from a clone URL ("host:org/app.git" -> "app")
+pub fn default_clone_dir(source: &str) -> String {
+    let trimmed = source.trim_end_matches('/').trim_end_matches("/.git");
+    let name = trimmed.rsplit(['/', ':']).next().unwrap_or(trimmed);
+    name.strip_suffix(".git").unwrap_or(name).to_string()
+}
+
+/// Normalize `..`/`.` lexically and resolve symlinks in the existing prefix
+fn resolve_existing(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+
+    // Canonicalize the deepest ancestor that exists, then re-append the rest
+    let mut existing = normalized.clone();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match existing.file_name() {
+            Some(name) => {
+                rest.push(name.to_os_string());
+                existing.pop();
+            }
+            None => return normalized,
+        }
+    }
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    for name in rest.into_iter().rev() {
+        resolved.push(name);
+    }
+    resolved
+}
+
+fn is_non_empty(path: &Path) -> bool {
+    if path.is_dir() {
+        path.read_dir().map(|mut d| d.next().is_some()).unwrap_or(true)
+    } else {
+        path.exists()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    /// projects/repo is the base; projects/ is its parent
+    fn workspace() -> (TempDir, Workspace) {
+        let temp = TempDir::new().unwrap();
+        let base = temp.path().join("projects").join("repo");
+        fs::create_dir_all(&base).unwrap();
+        (temp, Workspace::new(base, Vec::new()))
+    }
+
+    #[test]
+    fn test_default_clone_dir() {
+        assert_eq!(default_clone_dir("https://github.com/org/app.git"), "app");
+        assert_eq!(default_clone_dir("git@github.com:org/app.git"), "app");
+        assert_eq!(default_clone_dir("../local/repo/.git"), "repo");
+        assert_eq!(default_clone_dir("host:app"), "app");
+    }
+
+    #[test]
+    fn test_clone_into_workspace_allowed() {
+        let (_temp, ws) = workspace();
+        assert!(ws.check("clone", &args("https://example.com/app.git")).is_ok());
+        assert!(ws.check("clone", &args("https://example.com/app.git ../sibling")).is_ok());
+        assert!(ws.check("clone", &args("-b main --depth 1 url vendor/lib")).is_ok());
+    }
+
+    #[test]
+    fn test_clone_outside_workspace_rejected() {
+        let (_temp, ws) = workspace();
+        let result = ws.check("clone", &args("https://example.com/x.git /tmp/evil"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        let result = ws.check("clone", &args("url ../../../escape"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+    }
+
+    #[test]
+    fn test_clone_into_projects_dir_allowed() {
+        let (temp, mut ws) = workspace();
+        let projects = temp.path().join("elsewhere");
+        fs::create_dir_all(&projects).unwrap();
+        ws.projects_dirs.push(projects.clone());
+
+        let dest = projects.join("app").display().to_string();
+        assert!(ws.check("clone", &args(&format!("url {}", dest))).is_ok());
+    }
+
+    #[test]
+    fn test_clone_over_non_empty_dir_rejected() {
+        let (_temp, ws) = workspace();
+        let existing = ws.base_dir.join("src");
+        fs::create_dir_all(&existing).unwrap();
+        fs::write(existing.join("main.rs"), "fn main() {}").unwrap();
+
+        let result = ws.check("clone", &args("url src"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        fs::create_dir_all(ws.base_dir.join("empty")).unwrap();
+        assert!(ws.check("clone", &args("url empty")).is_ok());
+    }
+
+    #[test]
+    fn test_unsafe_options_rejected() {
+        let (_temp, ws) = workspace();
+        for cmd in [
+            "--upload-pack=touch /tmp/pwned url",
+            "-u 'sh -c id' url",
+            "--template=/tmp/hooks url",
+            "--separate-git-dir=/tmp/gitdir url",
+            "-u'touch /tmp/pwn' url",
+            "-nqu touch url",
+            "--upload-pac=touch url",
+            "--upl touch url",
+            "--templ=/tmp/hooks url",
+            "--sep=/tmp/gitdir url",
+            "--config core.fsmonitor=touch url",
+            "-nc core.fsmonitor=touch url",
+        ] {
+            let result = ws.check("clone", &args(cmd));
+            assert!(
+                matches!(result, Err(ValidationError::DangerousFlags(_))),
+                "should reject: {}",
+                cmd
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_options_not_mistaken_for_unsafe() {
+        let (_temp, ws) = workspace();
+        assert!(ws.check("clone", &args("-bmain-u url")).is_ok());
+        assert!(ws.check("clone", &args("-q --no-checkout url app")).is_ok());
+        assert!(ws.check("init", &args("-b trunk --quiet")).is_ok());
+    }
+
+    #[test]
+    fn test_init_rules() {
+        let (_temp, ws) = workspace();
+        assert!(ws.check("init", &args("new-project")).is_ok());
+        assert!(ws.check("init", &args("-b main ../another")).is_ok());
+
+        assert!(ws.check("init", &args("")).is_ok());
+
+        let result = ws.check("init", &args("/tmp/elsewhere"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        let result = ws.check("init", &args("--template=/tmp/hooks"));
+        assert!(matches!(result, Err(ValidationError::DangerousFlags(_))));
+    }
+
+    #[test]
+    fn test_bundle_rules() {
+        let (temp, ws) = workspace();
+        assert!(ws.check("bundle", &args("create ../repo.bundle --all")).is_ok());
+        assert!(ws.check("bundle", &args("create -q backup.bundle main")).is_ok());
+        assert!(ws.check("bundle", &args("verify /media/usb/app.bundle")).is_ok());
+        assert!(ws.check("bundle", &args("list-heads ../../app.bundle")).is_ok());
+
+        let result = ws.check("bundle", &args("create /tmp/elsewhere.bundle --all"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+        let result = ws.check("bundle", &args("create - --all"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+        fs::write(temp.path().join("projects").join("old.bundle"), "").unwrap();
+        let result = ws.check("bundle", &args("create ../old.bundle --all"));
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        let result = ws.check("bundle", &args("unbundle app.bundle"));
+        assert!(matches!(result, Err(ValidationError::DisallowedSubcommand(_))));
+    }
+}
@@ -1,6 +1,15 @@
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use thiserror::Error;
-use crate::security::ALLOWED_GIT_SUBCOMMANDS;
+use crate::git::{split_command, tool_args_problem};
+use crate::security::CommandOrigin;
+use crate::security::llm_output::check_llm_output;
+use crate::security::paths::Workspace;
+use crate::security::remote_url::check_remote_urls;
+use crate::security::{
+    ALLOWED_GIT_SUBCOMMANDS, LISTING_GIT_SUBCOMMANDS, MAINTENANCE_ACTIONS, MAINTENANCE_SCHEDULERS, NOTES_ACTIONS, READ_ONLY_NOTES_ACTIONS,
+    MAINTENANCE_SCHEDULES, MAINTENANCE_TASKS, READ_ONLY_GIT_SUBCOMMANDS, UPDATE_INDEX_FLAGS,
+};
 
 #[derive(Debug, Error)]
 pub enum ValidationError {
@@ -18,17 +27,36 @@ pub enum ValidationError {
 
     #[error("Empty command")]
     EmptyCommand,
+
+    #[error("Edited command failed LLM output checks: {0}")]
+    InvalidLlmOutput(String),
+
+    #[error("Unsafe destination path: {0}")]
+    UnsafePath(String),
+
+    #[error("Remote URL refused: {0}")]
+    UnsafeUrl(String),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DangerousOp {
     ForcePush,
     HardReset,
     Clean,
     FilterBranch,
     ForceCheckout,
+    ForceSwitch,
+    RestoreWorktree,
     DeleteBranch,
     Rebase,
+    StashDrop,
+    StashClear,
+    ReflogExpire,
+    Gc,
+    GcPruneNow,
+    /// Deleting a stale `index.lock`; not a git command
+    RemoveIndexLock,
 }
 
 #[derive(Debug, Clone)]
@@ -36,11 +64,83 @@ pub struct ValidatedCommand {
     pub command: String,
     pub is_dangerous: bool,
     pub danger_type: Option<DangerousOp>,
+    pub origin: CommandOrigin,
 }
 
 pub struct CommandValidator {
     allowed_subcommands: HashSet<&'static str>,
     dangerous_flags: HashSet<&'static str>,
+    workspace: Option<Workspace>,
+    /// Hosts remote URLs may point at; empty allows any
+    allowed_hosts: Vec<String>,
+}
+
+/// The command with `-c` spelled `--create` where it follows `git switch`
+///
+/// There it creates a branch; the config override `-c` only goes before the
+/// subcommand, so a `switch` part starting with it is left alone.
+fn without_switch_create(command: &str) -> String {
+    command
+        .split("&&")
+        .map(|part| {
+            let words: Vec<&str> = part.split_whitespace().collect();
+            if words.iter().find(|w| **w != "git") != Some(&"switch") {
+                return part.to_string();
+            }
+            let words: Vec<&str> = words.into_iter().map(|w| if w == "-c" { "--create" } else { w }).collect();
+            format!(" {} ", words.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join("&&")
+}
+
+/// Long options that make git run a command of the caller's choosing
+const COMMAND_OPTIONS: &[&str] = &["--exec", "--upload-pack", "--receive-pack"];
+
+/// `rebase -x`, the short spelling of `--exec`
+const REBASE_EXEC_SHORT_OPTION: char = 'x';
+
+/// Short `rebase` options that take the rest of a flag group as their value
+const REBASE_SHORT_OPTIONS_WITH_VALUE: &[char] = &['s', 'X', 'S', 'C'];
+
+/// Whether a `rebase` in `words` passes `-x`: alone (`-x cmd`), with the
+/// command attached (`-xcmd`) or grouped with other flags (`-ix cmd`)
+fn runs_rebase_exec(words: &[String]) -> bool {
+    let mut rest = words.iter().skip_while(|w| *w == "git");
+    if rest.find(|w| !w.starts_with('-')).is_none_or(|sub| sub != "rebase") {
+        return false;
+    }
+    rest.filter_map(|word| word.strip_prefix('-').filter(|flags| !flags.starts_with('-')))
+        .any(|flags| {
+            flags
+                .chars()
+                .take_while(|flag| !REBASE_SHORT_OPTIONS_WITH_VALUE.contains(flag))
+                .any(|flag| flag == REBASE_EXEC_SHORT_OPTION)
+        })
+}
+
+/// Which of `options` the long option `word` spells out or abbreviates, as
+/// git accepts any prefix of a long option's name
+pub(crate) fn abbreviated_option<'a>(word: &str, options: &[&'a str]) -> Option<&'a str> {
+    let name = word.split_once('=').map_or(word, |(name, _)| name);
+    if !name.starts_with("--") || name.len() <= 2 {
+        return None;
+    }
+    options.iter().copied().find(|option| option.starts_with(name))
+}
+
+/// The arguments the executor splits each `&&` part of the command into
+fn executed_parts(command: &str) -> Result<Vec<Vec<String>>, ValidationError> {
+    command
+        .split("&&")
+        .map(split_command)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ValidationError::InvalidFormat)
+}
+
+/// The command rebuilt from its executed parts
+fn as_executed(parts: &[Vec<String>]) -> String {
+    parts.iter().map(|words| words.join(" ")).collect::<Vec<_>>().join(" && ")
 }
 
 impl CommandValidator {
@@ -51,50 +151,109 @@ impl CommandValidator {
             .copied()
             .collect();
 
-        let dangerous_flags = ["--exec", "core.sshCommand"]
+        let dangerous_flags = COMMAND_OPTIONS
             .iter()
             .copied()
+            .chain(["core.sshCommand"])
             .collect();
 
         Self {
             allowed_subcommands,
             dangerous_flags,
+            workspace: None,
+            allowed_hosts: Vec::new(),
         }
     }
 
-    /// Validate a git command
+    /// Restrict where `clone` and `init` may create repositories
+    ///
+    /// Without a workspace, destinations are checked against the current directory.
+    pub fn with_workspace(mut self, workspace: Workspace) -> Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    /// Refuse remote URLs to hosts not in `hosts`; an empty list allows any
+    pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.allowed_hosts = hosts;
+        self
+    }
+
+    /// Validate a user-typed git command
     pub fn validate(&self, command: &str) -> Result<ValidatedCommand, ValidationError> {
+        self.validate_with_origin(command, CommandOrigin::User)
+    }
+
+    /// Validate a git command, applying the extra checks its origin calls for
+    #[tracing::instrument(name = "validation", level = "debug", skip(self), fields(%origin), err(Display, level = "debug"))]
+    pub fn validate_with_origin(
+        &self,
+        command: &str,
+        origin: CommandOrigin,
+    ) -> Result<ValidatedCommand, ValidationError> {
         let command = command.trim();
 
         if command.is_empty() {
             return Err(ValidationError::EmptyCommand);
         }
 
+        // LLM output the user edited gets the LLM-output checks again
+        if origin.requires_llm_output_check() {
+            check_llm_output(command).map_err(|e| ValidationError::InvalidLlmOutput(e.to_string()))?;
+        }
+
         // Check for command injection attempts
         self.check_for_injection(command)?;
 
+        // The rest is checked on the words git will get, so quotes and
+        // backslashes (`--ha\rd`, `'clear'`) can't hide a flag or action
+        let parts = executed_parts(command)?;
+        let executed = as_executed(&parts);
+
         // Check for dangerous flags BEFORE extracting subcommand
         // (since flags might interfere with subcommand extraction)
-        self.check_dangerous_flags(command)?;
-
-        // Extract subcommand
-        let subcommand = self.extract_subcommand(command)?;
+        self.check_dangerous_flags(&executed)?;
+        if parts.iter().any(|words| runs_rebase_exec(words)) {
+            return Err(ValidationError::DangerousFlags("-x".to_string()));
+        }
 
-        // Check against allowlist
-        if !self.check_subcommand(subcommand) {
-            return Err(ValidationError::DisallowedSubcommand(
-                subcommand.to_string(),
-            ));
+        // Check every chained command's subcommand against the allowlist
+        for part in executed.split("&&") {
+            let subcommand = self.extract_subcommand(part.trim())?;
+            if !self.check_subcommand(subcommand) {
+                return Err(ValidationError::DisallowedSubcommand(
+                    subcommand.to_string(),
+                ));
+            }
         }
 
+        // Check where clone/init would create a repository
+        self.check_destinations(command)?;
+
+        // No command-running transports, and only allowed hosts
+        check_remote_urls(command, &self.allowed_hosts)?;
+
+        // Keep `git maintenance` to scheduling this repository
+        self.check_maintenance(command)?;
+
+        // `git notes` may not open an editor or read notes from files
+        self.check_notes(command)?;
+
+        // `git update-index` may only toggle the assume-unchanged/skip-worktree bits
+        self.check_update_index(command)?;
+
+        // `git difftool`/`mergetool` open the configured tool, without prompts
+        self.check_external_tools(command)?;
+
         // Detect dangerous operations
-        let danger_type = self.detect_dangerous_ops(command);
+        let danger_type = self.detect_dangerous_ops(&parts);
         let is_dangerous = danger_type.is_some();
 
         Ok(ValidatedCommand {
             command: command.to_string(),
             is_dangerous,
             danger_type,
+            origin,
         })
     }
 
@@ -113,6 +272,171 @@ impl CommandValidator {
         Err(ValidationError::InvalidFormat)
     }
 
+    /// Validate destination paths of every `clone`/`init`/`bundle` in the command
+    fn check_destinations(&self, command: &str) -> Result<(), ValidationError> {
+        for part in command.split("&&") {
+            let words = split_command(part).map_err(|_| ValidationError::InvalidFormat)?;
+            let mut rest = words.iter().skip_while(|w| *w == "git");
+            let Some(subcommand) = rest.find(|w| !w.starts_with('-')) else {
+                continue;
+            };
+            if !matches!(subcommand.as_str(), "clone" | "init" | "bundle") {
+                continue;
+            }
+
+            let args: Vec<String> = rest.cloned().collect();
+            match self.workspace {
+                Some(ref workspace) => workspace.check(subcommand, &args)?,
+                None => {
+                    let cwd = std::env::current_dir().map_err(|e| {
+                        ValidationError::UnsafePath(format!("cannot determine current directory: {}", e))
+                    })?;
+                    Workspace::new(cwd, Vec::new()).check(subcommand, &args)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the action and options of every `maintenance` in the command
+    ///
+    /// Only the known actions and option values are let through, and
+    /// `--config-file`, which makes register/unregister write to any file, is
+    /// refused.
+    fn check_maintenance(&self, command: &str) -> Result<(), ValidationError> {
+        for part in command.split("&&") {
+            let words = split_command(part).map_err(|_| ValidationError::InvalidFormat)?;
+            let mut rest = words.iter().skip_while(|w| *w == "git");
+            if rest.find(|w| !w.starts_with('-')).is_none_or(|sub| sub != "maintenance") {
+                continue;
+            }
+
+            // Without an action git only prints its usage
+            let args: Vec<&String> = rest.collect();
+            let Some(action) = args.first() else {
+                continue;
+            };
+            if !MAINTENANCE_ACTIONS.contains(&action.as_str()) {
+                return Err(ValidationError::DisallowedSubcommand(format!("maintenance {}", action)));
+            }
+            for arg in &args[1..] {
+                let (flag, value) = arg.split_once('=').unwrap_or((arg.as_str(), ""));
+                let allowed = match flag {
+                    "--auto" | "--quiet" | "--no-quiet" => value.is_empty(),
+                    "--schedule" => MAINTENANCE_SCHEDULES.contains(&value),
+                    "--scheduler" => MAINTENANCE_SCHEDULERS.contains(&value),
+                    "--task" => MAINTENANCE_TASKS.contains(&value),
+                    _ => false,
+                };
+                if !allowed {
+                    return Err(ValidationError::DangerousFlags(arg.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the action and options of every `notes` in the command
+    ///
+    /// The TUI can't host the editor `edit` and message-less `add`/`append`
+    /// open, `-F`/`--file` and `--stdin` read note text from elsewhere, and
+    /// `--ref` must name a notes ref.
+    fn check_notes(&self, command: &str) -> Result<(), ValidationError> {
+        for part in command.split("&&") {
+            let words = split_command(part).map_err(|_| ValidationError::InvalidFormat)?;
+            let mut rest = words.iter().skip_while(|w| *w == "git");
+            if rest.find(|w| !w.starts_with('-')).is_none_or(|sub| sub != "notes") {
+                continue;
+            }
+
+            let args: Vec<&str> = rest.map(String::as_str).collect();
+            let mut words = args.iter().copied();
+            let mut action = None;
+            while let Some(word) = words.next() {
+                let (flag, value) = word.split_once('=').unwrap_or((word, ""));
+                match flag {
+                    "--ref" => {
+                        let name = if value.is_empty() { words.next().unwrap_or("") } else { value };
+                        if name.is_empty() || (name.starts_with("refs/") && !name.starts_with("refs/notes/")) {
+                            return Err(ValidationError::DangerousFlags(format!("--ref {}", name)));
+                        }
+                    }
+                    "-F" | "--file" | "--stdin" => return Err(ValidationError::DangerousFlags(flag.to_string())),
+                    _ if flag.starts_with("-F") => return Err(ValidationError::DangerousFlags("-F".to_string())),
+                    _ if action.is_none() && !word.starts_with('-') => action = Some(word),
+                    _ => {}
+                }
+            }
+
+            match action {
+                None => {}
+                Some(action) if !NOTES_ACTIONS.contains(&action) => {
+                    return Err(ValidationError::DisallowedSubcommand(format!("notes {}", action)));
+                }
+                Some(action @ ("add" | "append")) => {
+                    let has_message = args.iter().any(|w| {
+                        *w == "-m" || *w == "--message" || w.starts_with("--message=") || (w.starts_with("-m") && w.len() > 2)
+                    });
+                    if !has_message {
+                        return Err(ValidationError::DisallowedSubcommand(format!(
+                            "notes {} without -m (it would open an editor)",
+                            action
+                        )));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the options of every `update-index` in the command
+    ///
+    /// Everything else it can do (`--cacheinfo`, `--index-info`, `--remove`,
+    /// `--chmod`, ...) writes the index directly, bypassing `git add`.
+    fn check_update_index(&self, command: &str) -> Result<(), ValidationError> {
+        for part in command.split("&&") {
+            let words = split_command(part).map_err(|_| ValidationError::InvalidFormat)?;
+            let mut rest = words.iter().skip_while(|w| *w == "git");
+            if rest.find(|w| !w.starts_with('-')).is_none_or(|sub| sub != "update-index") {
+                continue;
+            }
+            let mut paths_only = false;
+            for arg in rest {
+                if paths_only || !arg.starts_with('-') {
+                    continue;
+                }
+                if !UPDATE_INDEX_FLAGS.contains(&arg.as_str()) {
+                    return Err(ValidationError::DangerousFlags(arg.to_string()));
+                }
+                paths_only = arg == "--";
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate every `difftool` and `mergetool` in the command
+    ///
+    /// They must pass `--no-prompt`, and may not swap the configured tool
+    /// for an `--extcmd` of their own.
+    fn check_external_tools(&self, command: &str) -> Result<(), ValidationError> {
+        for part in command.split("&&") {
+            let words = split_command(part).map_err(|_| ValidationError::InvalidFormat)?;
+            let mut rest = words.iter().skip_while(|w| *w == "git");
+            let Some(subcommand) = rest.find(|w| !w.starts_with('-')) else {
+                continue;
+            };
+            if !matches!(subcommand.as_str(), "difftool" | "mergetool") {
+                continue;
+            }
+            let args: Vec<String> = rest.cloned().collect();
+            if let Some(problem) = tool_args_problem(subcommand, &args) {
+                return Err(ValidationError::DangerousFlags(problem));
+            }
+        }
+        Ok(())
+    }
+
     /// Check if subcommand is in allowlist
     fn check_subcommand(&self, subcommand: &str) -> bool {
         self.allowed_subcommands.contains(subcommand)
@@ -120,15 +444,21 @@ impl CommandValidator {
 
     /// Check for command injection attempts
     fn check_for_injection(&self, command: &str) -> Result<(), ValidationError> {
-        // Check for suspicious operators (excluding &&)
-        let suspicious_operators = [";", "||", "|", ">", "<", "$(", "`"];
+        // Check for suspicious operators (excluding &&); a newline ends a
+        // shell command just like `;`
+        let suspicious_operators = [";", "||", "|", ">", "<", "$(", "`", "\n", "\r"];
 
         for op in &suspicious_operators {
             if command.contains(op) {
-                return Err(ValidationError::SuspiciousOperators(op.to_string()));
+                return Err(ValidationError::SuspiciousOperators(op.escape_default().to_string()));
             }
         }
 
+        // A lone & backgrounds the command before it and starts another
+        if command.replace("&&", "").contains('&') {
+            return Err(ValidationError::SuspiciousOperators("&".to_string()));
+        }
+
         // Check for && - only allowed between git commands
         if command.contains("&&") {
             // Verify both sides are git commands
@@ -159,7 +489,8 @@ impl CommandValidator {
     fn check_dangerous_flags(&self, command: &str) -> Result<(), ValidationError> {
         // Check for -c flag which can set arbitrary git config
         // Must check both " -c " and "-c " (at start) and "-c=" and "-cVALUE"
-        if command.contains(" -c") || command.starts_with("-c") {
+        let config_checked = without_switch_create(command);
+        if config_checked.contains(" -c") || config_checked.starts_with("-c") {
             return Err(ValidationError::DangerousFlags("-c".to_string()));
         }
 
@@ -175,18 +506,26 @@ impl CommandValidator {
                 return Err(ValidationError::DangerousFlags(flag.to_string()));
             }
         }
+        if let Some(option) = command.split_whitespace().find_map(|w| abbreviated_option(w, COMMAND_OPTIONS)) {
+            return Err(ValidationError::DangerousFlags(option.to_string()));
+        }
         Ok(())
     }
 
-    /// Detect dangerous operations
-    fn detect_dangerous_ops(&self, command: &str) -> Option<DangerousOp> {
-        let cmd_lower = command.to_lowercase();
+    /// Detect dangerous operations in the executed parts of a command
+    fn detect_dangerous_ops(&self, parts: &[Vec<String>]) -> Option<DangerousOp> {
+        let cmd_lower = as_executed(parts).to_lowercase();
 
-        // Force push (must check before other -f flags)
-        if cmd_lower.contains("push") && (cmd_lower.contains("--force") || cmd_lower.contains("-f"))
+        // Force push (must check before other -f flags); `--mirror` and
+        // `+` refspecs overwrite the remote's refs the same way
+        if cmd_lower.contains("push")
+            && (cmd_lower.contains("--force") || cmd_lower.contains("-f") || cmd_lower.contains("--mirror"))
         {
             return Some(DangerousOp::ForcePush);
         }
+        if parts.iter().any(|words| Self::is_plus_refspec_push(words)) {
+            return Some(DangerousOp::ForcePush);
+        }
 
         // Hard reset
         if cmd_lower.contains("reset") && cmd_lower.contains("--hard") {
@@ -205,23 +544,186 @@ impl CommandValidator {
             return Some(DangerousOp::FilterBranch);
         }
 
+        // Dropped stashes, expired reflog entries and pruned objects are
+        // what `git fsck`/`git reflog` recovery relies on
+        for words in parts {
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            if Self::has_subcommand(&words, "stash", &["drop"]) {
+                return Some(DangerousOp::StashDrop);
+            }
+            if Self::has_subcommand(&words, "stash", &["clear"]) {
+                return Some(DangerousOp::StashClear);
+            }
+            if Self::has_subcommand(&words, "reflog", &["expire", "delete"]) {
+                return Some(DangerousOp::ReflogExpire);
+            }
+            // A plain `maintenance run` runs the gc task
+            if Self::has_subcommand(&words, "maintenance", &["run"])
+                && (words.contains(&"--task=gc")
+                    || !words.iter().any(|w| w.starts_with("--task") || w.starts_with("--schedule")))
+            {
+                return Some(DangerousOp::Gc);
+            }
+            if words.iter().find(|w| **w != "git") == Some(&"gc") {
+                let prunes_now = words
+                    .iter()
+                    .any(|w| matches!(*w, "--prune=now" | "--prune=all"));
+                return Some(if prunes_now { DangerousOp::GcPruneNow } else { DangerousOp::Gc });
+            }
+        }
+
         // Force checkout
         if cmd_lower.contains("checkout") && (cmd_lower.contains("--force") || cmd_lower.contains("-f")) {
             return Some(DangerousOp::ForceCheckout);
         }
 
+        // Force switch (must check before delete branch: "--discard-changes" contains "-d")
+        if parts.iter().any(|words| Self::is_force_switch(words)) {
+            return Some(DangerousOp::ForceSwitch);
+        }
+
+        // Restore of both index and working tree
+        if parts.iter().any(|words| Self::is_worktree_restore(words)) {
+            return Some(DangerousOp::RestoreWorktree);
+        }
+
         // Delete branch (-D flag)
         if cmd_lower.contains("branch") && cmd_lower.contains("-d") {
             return Some(DangerousOp::DeleteBranch);
         }
 
-        // Rebase (interactive or not)
-        if cmd_lower.contains("rebase") {
+        // Rebase (interactive or not); continuing one or editing its todo rewrites nothing new
+        let resumes_rebase = parts.iter().any(|words| {
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            Self::has_subcommand(&words, "rebase", &["--continue", "--edit-todo", "--show-current-patch"])
+        });
+        if cmd_lower.contains("rebase") && !resumes_rebase {
             return Some(DangerousOp::Rebase);
         }
 
         None
     }
+
+    /// Check for `subcommand` immediately followed by one of `actions`
+    fn has_subcommand(words: &[&str], subcommand: &str, actions: &[&str]) -> bool {
+        words
+            .windows(2)
+            .any(|pair| pair[0] == subcommand && actions.contains(&pair[1]))
+    }
+
+    /// Check for a `git push` with a `+<src>:<dst>` refspec, which forces that one ref
+    fn is_plus_refspec_push(words: &[String]) -> bool {
+        let mut words = words.iter().skip_while(|w| *w == "git");
+        words.find(|w| !w.starts_with('-')).is_some_and(|sub| sub == "push") && words.any(|w| w.starts_with('+'))
+    }
+
+    /// Check for `git switch` with flags that throw away local changes
+    fn is_force_switch(words: &[String]) -> bool {
+        let mut words = words.iter().map(String::as_str);
+        if !words.any(|w| w == "switch") {
+            return false;
+        }
+        words.any(|w| matches!(w, "--discard-changes" | "--force" | "-f"))
+    }
+
+    /// Check for `git restore` touching both the index and the working tree
+    ///
+    /// `--staged` alone only unstages; combined with `--worktree` the local
+    /// edits are overwritten too and can't be recovered.
+    fn is_worktree_restore(words: &[String]) -> bool {
+        let mut words = words.iter().map(String::as_str);
+        if !words.any(|w| w == "restore") {
+            return false;
+        }
+
+        let mut staged = false;
+        let mut worktree = false;
+        for word in words {
+            match word {
+                "--" => break,
+                "--staged" => staged = true,
+                "--worktree" => worktree = true,
+                _ if word.starts_with('-') && !word.starts_with("--") => {
+                    // Combined short flags like -SW
+                    staged |= word.contains('S');
+                    worktree |= word.contains('W');
+                }
+                _ => {}
+            }
+        }
+        staged && worktree
+    }
+}
+
+/// Whether `word` makes a reading command write a file (`--output`) or run a
+/// program (`--ext-diff`, `--textconv`), spelled out or abbreviated
+fn writes_or_runs(word: &str) -> bool {
+    const OPTIONS: &[&str] = &["--output", "--ext-diff", "--textconv"];
+    // `--text` is an option of its own, not short for `--textconv`
+    word != "--text" && abbreviated_option(word, OPTIONS).is_some()
+}
+
+/// Check whether a command (or `&&` chain) only reads from the repository
+///
+/// Listing forms of `branch`, `tag` and `remote` count as read-only when they
+/// carry only display flags; anything that can create, delete or rename refs
+/// does not, and neither do options that write files or run programs.
+pub fn is_read_only_command(command: &str) -> bool {
+    const LISTING_FLAGS: &[&str] = &[
+        "-a", "--all", "-r", "--remotes", "-v", "-vv", "--verbose", "-l", "--list",
+    ];
+
+    command.split("&&").all(|part| {
+        let Ok(words) = split_command(part) else {
+            return false;
+        };
+        if words.iter().any(|w| writes_or_runs(w)) {
+            return false;
+        }
+        let mut words = words.iter().map(String::as_str).peekable();
+        if words.peek() == Some(&"git") {
+            words.next();
+        }
+
+        let Some(subcommand) = words.next() else {
+            return false;
+        };
+        // Bare `reflog` lists entries; `expire` and `delete` remove them
+        if subcommand == "reflog" {
+            return !matches!(words.next(), Some("expire") | Some("delete"));
+        }
+        if READ_ONLY_GIT_SUBCOMMANDS.contains(&subcommand) {
+            return true;
+        }
+        if subcommand == "stash" {
+            return matches!(words.next(), Some("list") | Some("show"));
+        }
+        // Bare `notes` lists them
+        if subcommand == "notes" {
+            return words
+                .find(|w| !w.starts_with('-'))
+                .is_none_or(|action| READ_ONLY_NOTES_ACTIONS.contains(&action));
+        }
+        LISTING_GIT_SUBCOMMANDS.contains(&subcommand) && words.all(|w| LISTING_FLAGS.contains(&w))
+    })
+}
+
+/// Whether a translated command may run without a preview
+///
+/// Its subcommand must be in `allowlist` and the command as a whole
+/// read-only; chains always get a preview.
+pub fn is_instant_command(command: &str, allowlist: &[String]) -> bool {
+    if command.contains("&&") || !is_read_only_command(command) {
+        return false;
+    }
+    let Ok(words) = split_command(command) else {
+        return false;
+    };
+    let mut words = words.iter().map(String::as_str).skip_while(|w| *w == "git");
+    let Some(subcommand) = words.next() else {
+        return false;
+    };
+    allowlist.iter().any(|allowed| allowed == subcommand)
 }
 
 impl Default for CommandValidator {
@@ -246,6 +748,34 @@ mod tests {
         assert!(validated.danger_type.is_none());
     }
 
+    #[test]
+    fn test_origin_carried_through() {
+        let validator = CommandValidator::new();
+        assert_eq!(validator.validate("git status").unwrap().origin, CommandOrigin::User);
+
+        let validated = validator
+            .validate_with_origin("git status", CommandOrigin::Llm)
+            .unwrap();
+        assert_eq!(validated.origin, CommandOrigin::Llm);
+    }
+
+    #[test]
+    fn test_edited_llm_command_rechecked() {
+        let validator = CommandValidator::new();
+
+        // && chains are fine when typed by the user...
+        let chain = "git add -A && git commit -m 'test'";
+        assert!(validator.validate_with_origin(chain, CommandOrigin::User).is_ok());
+
+        // ...but an edited LLM proposal must still pass the LLM-output checks
+        let result = validator.validate_with_origin(chain, CommandOrigin::LlmEdited);
+        assert!(matches!(result, Err(ValidationError::InvalidLlmOutput(_))));
+
+        assert!(validator
+            .validate_with_origin("git status -s", CommandOrigin::LlmEdited)
+            .is_ok());
+    }
+
     #[test]
     fn test_validate_without_git_prefix() {
         let validator = CommandValidator::new();
@@ -303,6 +833,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_newline_and_background_injection() {
+        let validator = CommandValidator::new();
+        assert!(matches!(
+            validator.validate("git status\nrm -rf /"),
+            Err(ValidationError::SuspiciousOperators(_))
+        ));
+        assert!(matches!(
+            validator.validate("git status & rm -rf /"),
+            Err(ValidationError::SuspiciousOperators(_))
+        ));
+        assert!(matches!(
+            validator.validate("git fetch &&& rm -rf /"),
+            Err(ValidationError::SuspiciousOperators(_))
+        ));
+    }
+
+    #[test]
+    fn test_every_chained_subcommand_is_allowlisted() {
+        let validator = CommandValidator::new();
+        assert!(matches!(
+            validator.validate("git status && git rm --cached secrets.env"),
+            Err(ValidationError::DisallowedSubcommand(sub)) if sub == "rm"
+        ));
+        assert!(validator.validate("git status &&").is_err());
+    }
+
     #[test]
     fn test_valid_and_operator() {
         let validator = CommandValidator::new();
@@ -328,6 +885,45 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_command_running_transports_rejected() {
+        let validator = CommandValidator::new();
+        for command in [
+            "git fetch --upload-pack=touch origin",
+            "git fetch --upload-pac=touch origin",
+            "git pull --upload-pack touch origin main",
+            "git push --receive-pack=touch origin main",
+            "git push --rece=touch origin main",
+            "git push --exec=touch origin main",
+            "git rebase --exe=touch main",
+            "git rebase -x 'rm -rf ~' main",
+            "git rebase -ix cmd main",
+            "git rebase -xcmd main",
+            "git rebase -i -x'touch x' main",
+        ] {
+            assert!(
+                matches!(validator.validate(command), Err(ValidationError::DangerousFlags(_))),
+                "should reject: {}",
+                command
+            );
+        }
+        assert!(validator.validate("git push --recurse-submodules=check origin main").is_ok());
+        assert!(validator.validate("git diff --exit-code").is_ok());
+        assert!(validator.validate("git rebase -Xtheirs main").is_ok());
+    }
+
+    #[test]
+    fn test_switch_create_allowed() {
+        let validator = CommandValidator::new();
+        assert!(validator.validate("git switch -c feat/login main").is_ok());
+        assert!(validator.validate("git status && git switch -c fix/x").is_ok());
+        // Still the config override before the subcommand
+        let result = validator.validate("git -c core.hooksPath=/tmp switch -c x");
+        assert!(matches!(result, Err(ValidationError::DangerousFlags(_))));
+        let result = validator.validate("git switch -c x && git -c a=b log");
+        assert!(matches!(result, Err(ValidationError::DangerousFlags(_))));
+    }
+
     #[test]
     fn test_dangerous_flag_c_directory() {
         let validator = CommandValidator::new();
@@ -378,6 +974,16 @@ mod tests {
         assert!(validated.is_dangerous);
     }
 
+    #[test]
+    fn test_mirror_and_plus_refspec_push_detection() {
+        let validator = CommandValidator::new();
+        for command in ["git push --mirror origin", "git push origin +main", "git push origin +HEAD:refs/heads/main"] {
+            let validated = validator.validate(command).unwrap();
+            assert_eq!(validated.danger_type, Some(DangerousOp::ForcePush), "{}", command);
+        }
+        assert!(!validator.validate("git push origin main").unwrap().is_dangerous);
+    }
+
     #[test]
     fn test_hard_reset_detection() {
         let validator = CommandValidator::new();
@@ -400,6 +1006,26 @@ mod tests {
         assert_eq!(validated.danger_type, Some(DangerousOp::Clean));
     }
 
+    #[test]
+    fn test_escaped_flags_detected() {
+        let validator = CommandValidator::new();
+        for (command, expected) in [
+            (r"git reset --ha\rd HEAD~3", DangerousOp::HardReset),
+            (r"git clean -\fdx", DangerousOp::Clean),
+            (r"git branch -\D foo", DangerousOp::DeleteBranch),
+            (r"git push --for\ce", DangerousOp::ForcePush),
+            (r#"git push "--force" origin main"#, DangerousOp::ForcePush),
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert_eq!(validated.danger_type, Some(expected), "{}", command);
+        }
+
+        // Flags are checked after unquoting too
+        assert!(validator.validate(r"git rebase --ex\ec=sh").is_err());
+        assert!(validator.validate(r"git status \-C /tmp").is_err());
+        assert!(matches!(validator.validate(r"git status \"), Err(ValidationError::InvalidFormat)));
+    }
+
     #[test]
     fn test_filter_branch_detection() {
         let validator = CommandValidator::new();
@@ -482,6 +1108,384 @@ mod tests {
         assert_eq!(validated.danger_type, Some(DangerousOp::ForceCheckout));
     }
 
+    #[test]
+    fn test_force_switch_detection() {
+        let validator = CommandValidator::new();
+        let result = validator.validate("git switch --discard-changes main");
+        assert!(result.is_ok());
+
+        let validated = result.unwrap();
+        assert!(validated.is_dangerous);
+        assert_eq!(validated.danger_type, Some(DangerousOp::ForceSwitch));
+    }
+
+    #[test]
+    fn test_force_switch_short_flag() {
+        let validator = CommandValidator::new();
+        let result = validator.validate("git switch -f main");
+        assert!(result.is_ok());
+
+        let validated = result.unwrap();
+        assert!(validated.is_dangerous);
+        assert_eq!(validated.danger_type, Some(DangerousOp::ForceSwitch));
+
+        let validated = validator.validate("git switch --force feature-branch").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::ForceSwitch));
+    }
+
+    #[test]
+    fn test_plain_switch_not_dangerous() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git switch feature-branch").unwrap();
+        assert!(!validated.is_dangerous);
+
+        let validated = validator.validate("git switch --detach HEAD~1").unwrap();
+        assert!(!validated.is_dangerous);
+    }
+
+    #[test]
+    fn test_restore_worktree_detection() {
+        let validator = CommandValidator::new();
+        let result = validator.validate("git restore --staged --worktree file.txt");
+        assert!(result.is_ok());
+
+        let validated = result.unwrap();
+        assert!(validated.is_dangerous);
+        assert_eq!(validated.danger_type, Some(DangerousOp::RestoreWorktree));
+    }
+
+    #[test]
+    fn test_restore_worktree_short_flags() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git restore -S -W src/main.rs").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::RestoreWorktree));
+
+        let validated = validator.validate("git restore -SW .").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::RestoreWorktree));
+    }
+
+    #[test]
+    fn test_escaped_switch_and_restore_flags_detected() {
+        let validator = CommandValidator::new();
+        for (command, expected) in [
+            (r"git switch --disc\ard-changes main", DangerousOp::ForceSwitch),
+            (r"git switch 'main' '-f'", DangerousOp::ForceSwitch),
+            (r"git restore --staged --work\tree .", DangerousOp::RestoreWorktree),
+            (r#"git restore "-SW" src/lib.rs"#, DangerousOp::RestoreWorktree),
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert_eq!(validated.danger_type, Some(expected), "{}", command);
+        }
+
+        // The flags count only in the part that switches or restores
+        let validated = validator.validate("git restore --staged a.txt && git restore --worktree b.txt").unwrap();
+        assert_ne!(validated.danger_type, Some(DangerousOp::RestoreWorktree));
+    }
+
+    #[test]
+    fn test_restore_staged_only_not_dangerous() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git restore --staged file.txt").unwrap();
+        assert!(!validated.is_dangerous);
+
+        // Flags after "--" are paths, not options
+        let validated = validator.validate("git restore --staged -- -W").unwrap();
+        assert!(!validated.is_dangerous);
+    }
+
+    #[test]
+    fn test_detect_stash_drop_and_clear() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git stash drop stash@{1}").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::StashDrop));
+        let validated = validator.validate("git stash clear").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::StashClear));
+
+        // Pop keeps the stash when applying it fails
+        let validated = validator.validate("git stash pop").unwrap();
+        assert!(!validated.is_dangerous);
+    }
+
+    #[test]
+    fn test_quoted_actions_detected() {
+        let validator = CommandValidator::new();
+        for (command, expected) in [
+            ("git stash 'clear'", DangerousOp::StashClear),
+            (r"git stash \drop", DangerousOp::StashDrop),
+            ("git reflog 'expire' --all", DangerousOp::ReflogExpire),
+            ("git gc --prune='now'", DangerousOp::GcPruneNow),
+            (r#"git gc "--prune=all""#, DangerousOp::GcPruneNow),
+            ("git status && git gc", DangerousOp::Gc),
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert_eq!(validated.danger_type, Some(expected), "{}", command);
+        }
+    }
+
+    #[test]
+    fn test_detect_reflog_expire() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git reflog expire --expire=now --all").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::ReflogExpire));
+        let validated = validator.validate("git reflog delete HEAD@{2}").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::ReflogExpire));
+
+        let validated = validator.validate("git reflog show main").unwrap();
+        assert!(!validated.is_dangerous);
+    }
+
+    #[test]
+    fn test_gc_requires_confirmation() {
+        let validator = CommandValidator::new();
+        let validated = validator.validate("git gc").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::Gc));
+        let validated = validator.validate("git gc --prune=now").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::GcPruneNow));
+        let validated = validator.validate("git gc --aggressive --prune=all").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::GcPruneNow));
+
+        // Only as the subcommand
+        let validated = validator.validate("git commit -m gc").unwrap();
+        assert!(!validated.is_dangerous);
+    }
+
+    #[test]
+    fn test_notes_safeguards() {
+        let validator = CommandValidator::new();
+        for command in [
+            "git notes",
+            "git notes list",
+            "git notes show HEAD~2",
+            "git notes add -m 'Reviewed by Ana' HEAD",
+            "git notes add -f --message='Deployed to staging' abc1234",
+            "git notes --ref=review append -m 'LGTM'",
+            "git notes --ref refs/notes/review remove HEAD",
+            "git notes copy HEAD~1 HEAD",
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert!(!validated.is_dangerous, "{}", command);
+        }
+
+        for command in [
+            "git notes edit HEAD",
+            "git notes merge other",
+            "git notes add HEAD",
+            "git notes append",
+        ] {
+            assert!(
+                matches!(validator.validate(command), Err(ValidationError::DisallowedSubcommand(_))),
+                "{}",
+                command
+            );
+        }
+        for command in [
+            "git notes add -F /etc/passwd HEAD",
+            "git notes add --file=secrets.txt HEAD",
+            "git notes copy --stdin",
+            "git notes --ref=refs/heads/main add -m x",
+        ] {
+            assert!(
+                matches!(validator.validate(command), Err(ValidationError::DangerousFlags(_))),
+                "{}",
+                command
+            );
+        }
+        assert!(!is_read_only_command("git notes add -m x HEAD"));
+        assert!(!is_read_only_command("git notes remove HEAD"));
+    }
+
+    #[test]
+    fn test_update_index_safeguards() {
+        let validator = CommandValidator::new();
+        for command in [
+            "git update-index --assume-unchanged config/local.toml",
+            "git update-index --no-skip-worktree --no-assume-unchanged -- .env 'my notes.md'",
+            "git update-index --skip-worktree -- --odd-name",
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert!(!validated.is_dangerous, "{}", command);
+        }
+        for command in [
+            "git update-index --cacheinfo 100644,abc123,a.txt",
+            "git update-index --index-info",
+            "git update-index --chmod=+x run.sh",
+            "git update-index --remove a.txt",
+        ] {
+            assert!(
+                matches!(validator.validate(command), Err(ValidationError::DangerousFlags(_))),
+                "{}",
+                command
+            );
+        }
+        assert!(!is_read_only_command("git update-index --assume-unchanged a.txt"));
+        assert!(is_read_only_command("git ls-files -v"));
+    }
+
+    #[test]
+    fn test_external_tool_safeguards() {
+        let validator = CommandValidator::new();
+        assert!(validator.validate("git difftool --no-prompt --cached -- src/a.rs").is_ok());
+        assert!(validator.validate("git mergetool -y --tool=meld").is_ok());
+        // --no-prompt is added when they run
+        assert!(validator.validate("git difftool HEAD~1").is_ok());
+        for command in [
+            "git mergetool --prompt",
+            "git difftool -y -x 'sh -c id'",
+            "git status && git difftool --no-prompt --extcmd=nc",
+        ] {
+            assert!(
+                matches!(validator.validate(command), Err(ValidationError::DangerousFlags(_))),
+                "{}",
+                command
+            );
+        }
+    }
+
+    #[test]
+    fn test_maintenance_safeguards() {
+        let validator = CommandValidator::new();
+        for command in [
+            "git maintenance start",
+            "git maintenance start --scheduler=crontab",
+            "git maintenance run --schedule=daily",
+            "git maintenance run --task=commit-graph --quiet",
+            "git maintenance register",
+            "git maintenance stop",
+        ] {
+            let validated = validator.validate(command).unwrap();
+            assert!(!validated.is_dangerous, "{}", command);
+        }
+
+        // A plain run includes gc
+        let validated = validator.validate("git maintenance run").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::Gc));
+        let validated = validator.validate("git maintenance run --task=gc").unwrap();
+        assert_eq!(validated.danger_type, Some(DangerousOp::Gc));
+
+        assert!(matches!(
+            validator.validate("git maintenance register --config-file=/etc/gitconfig"),
+            Err(ValidationError::DangerousFlags(_))
+        ));
+        assert!(matches!(
+            validator.validate("git maintenance start --scheduler=evil"),
+            Err(ValidationError::DangerousFlags(_))
+        ));
+        assert!(matches!(
+            validator.validate("git maintenance run --schedule=minutely"),
+            Err(ValidationError::DangerousFlags(_))
+        ));
+        assert!(matches!(
+            validator.validate("git maintenance frobnicate"),
+            Err(ValidationError::DisallowedSubcommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_only_commands() {
+        for cmd in [
+            "git status",
+            "git log --oneline -5",
+            "diff --cached",
+            "git branch",
+            "git branch -a",
+            "git remote -v",
+            "git stash list",
+            "git reflog",
+            "git reflog show main",
+            "git status && git log",
+            "git notes",
+            "git notes show HEAD",
+            "git notes --ref=review list",
+            "git diff --text --no-ext-diff --no-textconv",
+            "git log -p --output-indicator-new=+",
+        ] {
+            assert!(is_read_only_command(cmd), "should be read-only: {}", cmd);
+        }
+    }
+
+    #[test]
+    fn test_mutating_commands_not_read_only() {
+        for cmd in [
+            "git commit -m 'x'",
+            "git branch feature",
+            "git branch -D feature",
+            "git tag v1.0",
+            "git remote add origin url",
+            "git stash",
+            "git stash pop",
+            "git reflog expire --expire=now --all",
+            "git reflog delete HEAD@{1}",
+            "git gc",
+            "git fetch",
+            "git status && git commit -m 'x'",
+            "git diff --output=/tmp/changes.patch",
+            "git log -p --output changes.txt",
+            "git show --outp=HEAD.patch",
+            "git diff --ext-diff",
+            "git log -p '--ext-diff'",
+            "git show --textconv HEAD:README.md",
+            r"git diff --text\conv",
+            "",
+        ] {
+            assert!(!is_read_only_command(cmd), "should not be read-only: {}", cmd);
+        }
+    }
+
+    #[test]
+    fn test_instant_commands() {
+
+        let allowlist = ["status".to_string(), "log".to_string(), "diff".to_string(), "branch".to_string()];
+        for cmd in ["git status", "git log --oneline -5", "diff --cached", "git branch -a"] {
+            assert!(is_instant_command(cmd, &allowlist), "should be instant: {}", cmd);
+        }
+        for cmd in [
+            "git show HEAD",
+            "git branch -D feature",
+            "git status && git log",
+            "git diff --output=changes.patch",
+            "git log --output changes.txt",
+            "git commit -m 'status'",
+            "",
+        ] {
+            assert!(!is_instant_command(cmd, &allowlist), "should get a preview: {}", cmd);
+        }
+    }
+
+    #[test]
+    fn test_clone_destination_checked() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let base = temp.path().join("work").join("repo");
+        std::fs::create_dir_all(&base).unwrap();
+        let validator = CommandValidator::new().with_workspace(Workspace::new(&base, Vec::new()));
+
+        assert!(validator.validate("git clone https://example.com/app.git").is_ok());
+        assert!(validator.validate("git init ../new-project").is_ok());
+        assert!(validator.validate("git bundle create ../repo.bundle --all").is_ok());
+        assert!(validator.validate("git fetch ../repo.bundle '+refs/heads/*:refs/remotes/bundle/*'").is_ok());
+
+        let result = validator.validate("git clone https://example.com/x.git /tmp/evil");
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        let result = validator.validate("git status && git clone url /etc/evil");
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+
+        let result = validator.validate("git bundle create /etc/evil.bundle --all");
+        assert!(matches!(result, Err(ValidationError::UnsafePath(_))));
+    }
+
+    #[test]
+    fn test_remote_urls_checked() {
+        let validator = CommandValidator::new();
+        let result = validator.validate("git fetch fd::7 main");
+        assert!(matches!(result, Err(ValidationError::UnsafeUrl(_))));
+        assert!(validator.validate("git remote add mirror https://mirror.example/a.git").is_ok());
+
+        let validator = CommandValidator::new().with_allowed_hosts(vec!["git.corp.example".to_string()]);
+        assert!(validator.validate("git remote add corp git@git.corp.example:team/a.git").is_ok());
+        let result = validator.validate("git remote add mirror https://mirror.example/a.git");
+        assert!(matches!(result, Err(ValidationError::UnsafeUrl(_))));
+    }
+
     #[test]
     fn test_delete_branch_detection() {
         let validator = CommandValidator::new();
@@ -525,4 +1529,14 @@ mod tests {
         assert!(validated.is_dangerous);
         assert_eq!(validated.danger_type, Some(DangerousOp::Rebase));
     }
+
+    #[test]
+    fn test_rebase_progress_actions() {
+        let validator = CommandValidator::new();
+        assert!(!validator.validate("git rebase --continue").unwrap().is_dangerous);
+        assert!(!validator.validate("git rebase --edit-todo").unwrap().is_dangerous);
+        // Skipping drops the commit; aborting throws away resolutions
+        assert_eq!(validator.validate("git rebase --skip").unwrap().danger_type, Some(DangerousOp::Rebase));
+        assert_eq!(validator.validate("git rebase --abort").unwrap().danger_type, Some(DangerousOp::Rebase));
+    }
 }
@@ -0,0 +1,576 @@
+use crate::audit::AuditLogger;
+use crate::config::{Config, DangerLedger, TrustStore};
+use crate::error::GitError;
+use crate::git::Repository;
+use crate::hooks::{ApprovalClient, ApprovalRequest, HookEvent, HookPayload, HookRunner, HookValidation};
+use crate::llm::Translator;
+use crate::security::{CommandOrigin, CommandValidator, Workspace, is_read_only_command};
+use crate::server::protocol::{
+    ExecuteParams, ExecuteResult, RpcError, RpcRequest, RpcResponse, StateParams, TranslateParams,
+    ValidateParams, ValidationResult, codes, tool_definitions,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::io;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Headless JSON-RPC / MCP server exposing gitalky's pipeline to editors
+///
+/// Every request names its own repository, and every command goes through
+/// the same `CommandValidator`, hooks and audit log as the TUI.
+pub struct Server {
+    config: Config,
+    audit_logger: Option<AuditLogger>,
+    hooks: HookRunner,
+    /// `danger_runs.toml`, shared with the TUI and `gitalky run`; `None` without a config dir
+    danger_ledger: Option<PathBuf>,
+    /// Saved trust decisions; a repository the user never trusted only reads
+    trust_file: Option<PathBuf>,
+}
+
+impl Server {
+    pub fn new(config: Config) -> Self {
+        let audit_logger = if config.behavior.log_commands {
+            AuditLogger::for_config(&config).ok()
+        } else {
+            None
+        };
+        let hooks = HookRunner::new(config.hooks.clone());
+
+        Self {
+            config,
+            audit_logger,
+            hooks,
+            danger_ledger: DangerLedger::file_path().ok(),
+            trust_file: TrustStore::file_path().ok(),
+        }
+    }
+
+    /// Serve newline-delimited JSON-RPC on stdin/stdout until stdin closes
+    pub async fn serve_stdio(&self) -> io::Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            if let Some(response) = self.handle_line(&line).await {
+                stdout.write_all(response.as_bytes()).await?;
+                stdout.write_all(b"\n").await?;
+                stdout.flush().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one line of input, returning the serialized response if any
+    pub async fn handle_line(&self, line: &str) -> Option<String> {
+        if line.trim().is_empty() {
+            return None;
+        }
+
+        let response = match serde_json::from_str::<RpcRequest>(line) {
+            Ok(request) => self.handle(request).await?,
+            Err(e) => RpcResponse::failure(
+                Value::Null,
+                RpcError::new(codes::PARSE_ERROR, format!("Invalid JSON-RPC request: {}", e)),
+            ),
+        };
+        serde_json::to_string(&response).ok()
+    }
+
+    /// Dispatch a request; notifications (no id) get no response
+    pub async fn handle(&self, request: RpcRequest) -> Option<RpcResponse> {
+        let id = request.id.clone()?;
+
+        if request.jsonrpc != "2.0" {
+            return Some(RpcResponse::failure(
+                id,
+                RpcError::new(codes::INVALID_REQUEST, "Expected jsonrpc \"2.0\""),
+            ));
+        }
+
+        let result = match request.method.as_str() {
+            // MCP lifecycle and tool discovery
+            "initialize" => Ok(json!({
+                "protocolVersion": crate::llm::mcp::MCP_PROTOCOL_VERSION,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "gitalky", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => Ok(self.call_tool(request.params).await),
+            method => self.call_method(method, request.params).await,
+        };
+
+        Some(match result {
+            Ok(value) => RpcResponse::success(id, value),
+            Err(error) => RpcResponse::failure(id, error),
+        })
+    }
+
+    /// Run a method by name (plain JSON-RPC entry point)
+    async fn call_method(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "translate" => self.translate(parse_params(params)?).await,
+            "validate" => self.validate(parse_params(params)?),
+            "execute" => self.execute(parse_params(params)?).await,
+            "state" => self.state(parse_params(params)?),
+            other => Err(RpcError::new(
+                codes::METHOD_NOT_FOUND,
+                format!("Unknown method: {}", other),
+            )),
+        }
+    }
+
+    /// MCP `tools/call`: errors are reported in-band with `isError`
+    async fn call_tool(&self, params: Value) -> Value {
+        let name = params["name"].as_str().unwrap_or_default().to_string();
+        let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        let (text, is_error) = match self.call_method(&name, arguments).await {
+            Ok(value) => (value.to_string(), false),
+            Err(error) => (error.message, true),
+        };
+        json!({ "content": [{ "type": "text", "text": text }], "isError": is_error })
+    }
+
+    async fn translate(&self, params: TranslateParams) -> Result<Value, RpcError> {
+        let repo = open_repo(&params.repo)?;
+        let translator = Translator::from_config(&repo, &self.config).ok_or_else(|| {
+            RpcError::new(codes::TRANSLATION_FAILED, "No LLM API key configured")
+        })?;
+
+        let command = translator
+            .translate(&params.query)
+            .await
+            .map_err(|e| RpcError::new(codes::TRANSLATION_FAILED, e.to_string()))?;
+        let validation = self.validate_as(&command.command, CommandOrigin::Llm, Some(&repo))?;
+
+        Ok(json!({
+            "command": command.command,
+            "explanation": command.explanation,
+            "validation": validation,
+        }))
+    }
+
+    fn validate(&self, params: ValidateParams) -> Result<Value, RpcError> {
+        let repo = params.repo.as_deref().map(open_repo).transpose()?;
+        self.validate_as(&params.command, CommandOrigin::User, repo.as_ref())
+    }
+
+    /// The validator `execute` runs commands past, so `validate` and
+    /// `translate` answer the same: allowed hosts, and `repo` as the workspace
+    fn validator(&self, repo: Option<&Repository>) -> CommandValidator {
+        let validator = CommandValidator::new().with_allowed_hosts(self.config.git.allowed_hosts.clone());
+        match repo {
+            Some(repo) => validator.with_workspace(Workspace::new(repo.path(), self.config.git.projects_dirs.clone())),
+            None => validator,
+        }
+    }
+
+    fn validate_as(&self, command: &str, origin: CommandOrigin, repo: Option<&Repository>) -> Result<Value, RpcError> {
+        let validated = self
+            .validator(repo)
+            .validate_with_origin(command, origin)
+            .map_err(|e| RpcError::new(codes::VALIDATION_FAILED, e.to_string()))?;
+
+        Ok(json!(ValidationResult {
+            command: validated.command,
+            is_dangerous: validated.is_dangerous,
+            danger_type: validated.danger_type.map(|op| format!("{:?}", op)),
+            origin: validated.origin,
+        }))
+    }
+
+    async fn execute(&self, params: ExecuteParams) -> Result<Value, RpcError> {
+        let repo = open_repo(&params.repo)?;
+        let command = params.command.trim().to_string();
+
+        let origin = params.origin;
+        let validated = match self.validator(Some(&repo)).validate_with_origin(&command, origin) {
+            Ok(validated) => validated,
+            Err(e) => {
+                self.log(&command, &repo, 1, origin);
+                return Err(RpcError::new(codes::VALIDATION_FAILED, e.to_string()));
+            }
+        };
+
+        let trusted = !self.config.behavior.repo_trust
+            || self.trust_file.as_deref().is_some_and(|file| TrustStore::trusts_saved(file, repo.path()));
+        if !trusted && !is_read_only_command(&command) {
+            self.log(&command, &repo, 1, origin);
+            return Err(RpcError::new(
+                codes::UNTRUSTED,
+                "Untrusted repository: only commands that don't modify it can run; \
+                 open it in gitalky and type :trust to trust it",
+            ));
+        }
+
+        let danger_type = validated.danger_type.as_ref().map(|op| format!("{:?}", op));
+        if validated.is_dangerous && !params.confirm_dangerous {
+            return Err(RpcError::new(
+                codes::CONFIRMATION_REQUIRED,
+                format!(
+                    "Dangerous operation ({}) requires confirm_dangerous: true",
+                    danger_type.as_deref().unwrap_or("unknown")
+                ),
+            ));
+        }
+
+        let ledger = self.danger_ledger.as_deref();
+        if let Some(ref op) = validated.danger_type {
+            match DangerLedger::check_saved(ledger, op, &self.config.rate_limits) {
+                Ok(Some(hit)) => return Err(RpcError::new(codes::RATE_LIMITED, hit.describe(chrono::Utc::now()))),
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(RpcError::new(
+                        codes::RATE_LIMITED,
+                        format!("Can't check the rate limit in {}: {}", DangerLedger::display_path(ledger), e),
+                    ));
+                }
+            }
+        }
+
+        let payload = HookPayload::new(HookEvent::PreExecute, repo.path())
+            .with_command(&command)
+            .with_validation(HookValidation {
+                is_dangerous: validated.is_dangerous,
+                danger_type,
+            });
+        if let Some(reason) = self.hooks.run(&payload).await.veto {
+            self.log(&command, &repo, 1, origin);
+            return Err(RpcError::new(
+                codes::HOOK_VETOED,
+                format!("Command blocked by hook: {}", reason),
+            ));
+        }
+
+        if let (Some(approval), Some(op)) = (&self.config.approval, &validated.danger_type) {
+            let branch = repo.current_branch().unwrap_or(None);
+            let risk = self.config.confirmation.risk(op, &command, branch.as_deref());
+            if approval.applies_to(risk) {
+                let request = ApprovalRequest::new(&command, repo.path(), format!("{:?}", op), risk, origin)
+                    .with_branch(branch.as_deref());
+                let decision = ApprovalClient::ask(approval, &self.config.network, &request).await;
+                if let Some(ref logger) = self.audit_logger {
+                    let _ = logger.log_approval(&command, repo.path(), &decision);
+                }
+                if !decision.allowed {
+                    return Err(RpcError::new(
+                        codes::NOT_APPROVED,
+                        format!("Command not approved: {}", decision.describe()),
+                    ));
+                }
+            }
+        }
+
+        // Executor adds the "git " prefix itself
+        let command_for_executor = command.strip_prefix("git ").unwrap_or(&command);
+        let mut result = match repo.executor().execute_for_user(command_for_executor) {
+            Ok(output) => ExecuteResult {
+                command: command.clone(),
+                stdout: output.stdout,
+                stderr: output.stderr,
+                exit_code: output.exit_code,
+                warning: None,
+            },
+            // Git ran and failed: that's a result, not a protocol error
+            Err(GitError::CommandExited {
+                stdout,
+                stderr,
+                exit_code,
+                ..
+            }) => ExecuteResult {
+                command: command.clone(),
+                stdout,
+                stderr,
+                exit_code,
+                warning: None,
+            },
+            Err(e) => {
+                self.log(&command, &repo, 1, origin);
+                return Err(RpcError::new(codes::REPOSITORY_ERROR, e.to_string()));
+            }
+        };
+
+        self.log(&command, &repo, result.exit_code, origin);
+        if let Some(ref op) = validated.danger_type
+            && let Err(e) = DangerLedger::record_saved(ledger, op, &self.config.rate_limits)
+        {
+            result.warning = Some(format!(
+                "Run not counted against [rate_limits] in {}: {}",
+                DangerLedger::display_path(ledger),
+                e
+            ));
+        }
+        let payload = HookPayload::new(HookEvent::PostExecute, repo.path())
+            .with_command(&command)
+            .with_exit_code(result.exit_code);
+        self.hooks.run(&payload).await;
+
+        Ok(json!(result))
+    }
+
+    fn state(&self, params: StateParams) -> Result<Value, RpcError> {
+        let repo = open_repo(&params.repo)?;
+        let state = repo
+            .state()
+            .map_err(|e| RpcError::new(codes::REPOSITORY_ERROR, e.to_string()))?;
+        Ok(json!(state))
+    }
+
+    fn log(&self, command: &str, repo: &Repository, exit_code: i32, origin: CommandOrigin) {
+        if let Some(ref logger) = self.audit_logger {
+            let _ = logger.log_command_with_origin(command, repo.path(), exit_code, origin);
+        }
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(codes::INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+fn open_repo(path: &str) -> Result<Repository, RpcError> {
+    Repository::discover_from(path).map_err(|e| {
+        RpcError::new(codes::REPOSITORY_ERROR, format!("{}: {}", path, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::init_repo;
+    use tempfile::TempDir;
+
+    fn test_server() -> Server {
+        let mut config = Config::default_config();
+        config.behavior.log_commands = false;
+        config.behavior.repo_trust = false;
+        config.llm.api_key_env = "NONEXISTENT_API_KEY".to_string();
+        config.llm.api_key = None;
+        Server::new(config)
+    }
+
+    fn test_repo() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        init_repo(temp.path());
+        temp
+    }
+
+    async fn call(server: &Server, method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = server.handle_line(&line.to_string()).await.unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        let server = test_server();
+        let response = call(&server, "validate", json!({ "command": "git reset --hard" })).await;
+        assert_eq!(response["result"]["is_dangerous"], true);
+        assert_eq!(response["result"]["danger_type"], "HardReset");
+
+        let response = call(&server, "validate", json!({ "command": "git status; ls" })).await;
+        assert_eq!(response["error"]["code"], codes::VALIDATION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn test_validate_matches_execute() {
+        let mut server = test_server();
+        server.config.git.allowed_hosts = vec!["github.com".to_string()];
+        let repo = test_repo();
+        let path = repo.path().display().to_string();
+
+        let command = "git fetch https://evil.example/x.git";
+        let response = call(&server, "validate", json!({ "command": command })).await;
+        assert_eq!(response["error"]["code"], codes::VALIDATION_FAILED);
+        let response = call(&server, "execute", json!({ "repo": path, "command": command })).await;
+        assert_eq!(response["error"]["code"], codes::VALIDATION_FAILED);
+
+        let response = call(&server, "validate", json!({ "command": "git clone https://github.com/a.git /", "repo": path })).await;
+        assert_eq!(response["error"]["code"], codes::VALIDATION_FAILED);
+        let response = call(&server, "validate", json!({ "command": "git status", "repo": path })).await;
+        assert_eq!(response["result"]["is_dangerous"], false);
+    }
+
+    #[tokio::test]
+    async fn test_execute_and_state() {
+        let server = test_server();
+        let repo = test_repo();
+        let path = repo.path().display().to_string();
+
+        std::fs::write(repo.path().join("a.txt"), "hello").unwrap();
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git add a.txt" })).await;
+        assert_eq!(response["result"]["exit_code"], 0);
+
+        let response = call(&server, "state", json!({ "repo": path })).await;
+        assert_eq!(response["result"]["staged_files"][0]["path"], "a.txt");
+        assert_eq!(response["result"]["staged_files"][0]["status"], "added");
+    }
+
+    #[tokio::test]
+    async fn test_execute_requires_confirmation_for_dangerous() {
+        let server = test_server();
+        let repo = test_repo();
+        let path = repo.path().display().to_string();
+
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git clean -fd" })).await;
+        assert_eq!(response["error"]["code"], codes::CONFIRMATION_REQUIRED);
+
+        let response = call(
+            &server,
+            "execute",
+            json!({ "repo": path, "command": "git clean -fd", "confirm_dangerous": true }),
+        )
+        .await;
+        assert_eq!(response["result"]["exit_code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rate_limited() {
+        let mut server = test_server();
+        let repo = test_repo();
+        server.danger_ledger = Some(repo.path().join(".git").join("danger_runs.toml"));
+        let limit = crate::config::RateLimit { max: 1, window_minutes: 10 };
+        server.config.rate_limits.insert(crate::security::DangerousOp::Clean, limit);
+        let path = repo.path().display().to_string();
+
+        let params = json!({ "repo": path, "command": "git clean -fd", "confirm_dangerous": true });
+        assert_eq!(call(&server, "execute", params.clone()).await["result"]["exit_code"], 0);
+        assert_eq!(call(&server, "execute", params.clone()).await["error"]["code"], codes::RATE_LIMITED);
+
+        std::fs::write(server.danger_ledger.as_ref().unwrap(), "runs = [").unwrap();
+        let response = call(&server, "execute", params.clone()).await;
+        assert_eq!(response["error"]["code"], codes::RATE_LIMITED);
+        assert!(response["error"]["message"].as_str().unwrap().contains("Can't check"), "{}", response);
+
+        server.danger_ledger = None;
+        assert_eq!(call(&server, "execute", params).await["error"]["code"], codes::RATE_LIMITED);
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_untrusted_repository() {
+        let mut server = test_server();
+        let repo = test_repo();
+        server.config.behavior.repo_trust = true;
+        server.trust_file = Some(repo.path().join(".git").join("trust.toml"));
+        std::fs::write(repo.path().join("a.txt"), "hello").unwrap();
+        let path = repo.path().display().to_string();
+
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git status" })).await;
+        assert_eq!(response["result"]["exit_code"], 0);
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git add a.txt" })).await;
+        assert_eq!(response["error"]["code"], codes::UNTRUSTED);
+
+        let mut store = TrustStore::default();
+        store.set(Repository::discover_from(&path).unwrap().path(), crate::config::TrustLevel::Trusted);
+        store.save_to(server.trust_file.as_ref().unwrap()).unwrap();
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git add a.txt" })).await;
+        assert_eq!(response["result"]["exit_code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_execute_asks_approval_endpoint() {
+        let mut server = test_server();
+        server.config.approval = Some(crate::hooks::ApprovalConfig {
+            url: "http://127.0.0.1:1".to_string(),
+            min_risk: crate::security::RiskLevel::Low,
+            timeout_secs: 5,
+            poll_interval_secs: 1,
+            token_env: None,
+        });
+        let repo = test_repo();
+        let path = repo.path().display().to_string();
+
+        let response = call(
+            &server,
+            "execute",
+            json!({ "repo": path, "command": "git clean -fd", "confirm_dangerous": true }),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], codes::NOT_APPROVED);
+
+        // Safe commands never ask
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git status" })).await;
+        assert_eq!(response["result"]["exit_code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_git_failure_is_a_result() {
+        let server = test_server();
+        let repo = test_repo();
+        let path = repo.path().display().to_string();
+
+        let response = call(&server, "execute", json!({ "repo": path, "command": "git log" })).await;
+        assert_ne!(response["result"]["exit_code"], 0);
+        assert!(!response["result"]["stderr"].as_str().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bad_requests() {
+        let server = test_server();
+
+        let response = call(&server, "nope", json!({})).await;
+        assert_eq!(response["error"]["code"], codes::METHOD_NOT_FOUND);
+
+        let response = call(&server, "execute", json!({ "command": "git status" })).await;
+        assert_eq!(response["error"]["code"], codes::INVALID_PARAMS);
+
+        let response = call(&server, "state", json!({ "repo": "/nonexistent/path" })).await;
+        assert_eq!(response["error"]["code"], codes::REPOSITORY_ERROR);
+
+        let response: Value = serde_json::from_str(&server.handle_line("{not json").await.unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], codes::PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_translate_without_api_key() {
+        let server = test_server();
+        let repo = test_repo();
+        let response = call(
+            &server,
+            "translate",
+            json!({ "repo": repo.path().display().to_string(), "query": "show status" }),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], codes::TRANSLATION_FAILED);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_get_no_response() {
+        let server = test_server();
+        let line = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(server.handle_line(&line.to_string()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tools() {
+        let server = test_server();
+
+        let response = call(&server, "initialize", json!({})).await;
+        assert_eq!(response["result"]["serverInfo"]["name"], "gitalky");
+
+        let response = call(&server, "tools/list", json!({})).await;
+        let tools = response["result"]["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 4);
+
+        let response = call(
+            &server,
+            "tools/call",
+            json!({ "name": "validate", "arguments": { "command": "git status" } }),
+        )
+        .await;
+        assert_eq!(response["result"]["isError"], false);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("\"is_dangerous\":false"));
+
+        let response = call(
+            &server,
+            "tools/call",
+            json!({ "name": "validate", "arguments": { "command": "git rm -rf /" } }),
+        )
+        .await;
+        assert_eq!(response["result"]["isError"], true);
+    }
+}
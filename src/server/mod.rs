@@ -0,0 +1,5 @@
+pub mod daemon;
+pub mod protocol;
+
+pub use daemon::Server;
+pub use protocol::{RpcError, RpcRequest, RpcResponse};
@@ -0,0 +1,208 @@
+use crate::security::CommandOrigin;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// JSON-RPC error codes used by the daemon
+pub mod codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    /// The repository path is missing or not a git repository
+    pub const REPOSITORY_ERROR: i64 = -32001;
+    /// `CommandValidator` rejected the command
+    pub const VALIDATION_FAILED: i64 = -32002;
+    /// Dangerous command sent without `confirm_dangerous`
+    pub const CONFIRMATION_REQUIRED: i64 = -32003;
+    /// A pre-execute hook vetoed the command
+    pub const HOOK_VETOED: i64 = -32004;
+    /// No LLM is configured or the translation failed
+    pub const TRANSLATION_FAILED: i64 = -32005;
+    /// The `[approval]` endpoint denied the command, or couldn't be asked
+    pub const NOT_APPROVED: i64 = -32006;
+    /// The operation used up its `[rate_limits]` entry, or the ledger counting
+    /// runs against it can't be read
+    pub const RATE_LIMITED: i64 = -32007;
+    /// The repository isn't trusted and the command would modify it
+    pub const UNTRUSTED: i64 = -32008;
+}
+
+/// Incoming JSON-RPC 2.0 request or notification
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub jsonrpc: String,
+    /// Absent for notifications, which get no response
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// JSON-RPC error object
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Outgoing JSON-RPC 2.0 response
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RpcResponse {
+    pub jsonrpc: &'static str,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Value, error: RpcError) -> Self {
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Params for `translate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranslateParams {
+    pub repo: String,
+    pub query: String,
+}
+
+/// Params for `validate`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValidateParams {
+    pub command: String,
+    /// Repository `clone`/`init` destinations are checked against; the
+    /// daemon's directory without it
+    #[serde(default)]
+    pub repo: Option<String>,
+}
+
+/// Params for `execute`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExecuteParams {
+    pub repo: String,
+    pub command: String,
+    /// Must be true to run commands the validator marks dangerous
+    #[serde(default)]
+    pub confirm_dangerous: bool,
+    /// Where the editor got the command from (defaults to user-typed)
+    ///
+    /// Advisory only: it is the client's own claim, recorded in the audit
+    /// log. `llm-edited` adds the LLM-output checks, but no origin skips any
+    /// validation, confirmation or trust check.
+    #[serde(default)]
+    pub origin: CommandOrigin,
+}
+
+/// Params for `state`
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateParams {
+    pub repo: String,
+}
+
+/// Result of `validate`, also embedded in `translate` results
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationResult {
+    pub command: String,
+    pub is_dangerous: bool,
+    pub danger_type: Option<String>,
+    pub origin: CommandOrigin,
+}
+
+/// Result of `execute`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecuteResult {
+    pub command: String,
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    /// Set when the run couldn't be counted against `[rate_limits]`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+/// MCP tool descriptions with JSON Schemas for each method's params
+pub fn tool_definitions() -> Value {
+    serde_json::json!([
+        {
+            "name": "translate",
+            "description": "Translate a natural language request into a validated git command",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo": { "type": "string", "description": "Path inside the git repository" },
+                    "query": { "type": "string", "description": "What you want git to do" }
+                },
+                "required": ["repo", "query"]
+            }
+        },
+        {
+            "name": "validate",
+            "description": "Check a git command against gitalky's security validator",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "command": { "type": "string" },
+                    "repo": { "type": "string", "description": "Path inside the repository" }
+                },
+                "required": ["command"]
+            }
+        },
+        {
+            "name": "execute",
+            "description": "Validate and run a git command; dangerous commands need confirm_dangerous",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo": { "type": "string" },
+                    "command": { "type": "string" },
+                    "confirm_dangerous": { "type": "boolean", "default": false },
+                    "origin": {
+                        "type": "string",
+                        "enum": ["llm", "llm-edited", "user", "history", "workflow", "suggestion"],
+                        "default": "user"
+                    }
+                },
+                "required": ["repo", "command"]
+            }
+        },
+        {
+            "name": "state",
+            "description": "Current branch, file status, recent commits and stashes",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "repo": { "type": "string" }
+                },
+                "required": ["repo"]
+            }
+        }
+    ])
+}
@@ -0,0 +1,187 @@
+use crate::config::Config;
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt};
+
+/// Overrides the level chosen by `--verbose`, e.g. `GITALKY_LOG=gitalky::llm=trace`
+pub const LOG_ENV: &str = "GITALKY_LOG";
+
+/// The diagnostics log starts over once it grows past this
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Events kept in memory for the `:logs` screen
+const RECENT_EVENTS: usize = 500;
+
+/// A diagnostics event as kept for the in-app log viewer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEvent {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// Module the event came from, e.g. `gitalky::llm::anthropic`
+    pub target: String,
+    /// Message followed by the event's other fields as `key=value`
+    pub message: String,
+}
+
+/// Ring buffer of the latest events, filled by a tracing layer
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    events: Arc<Mutex<VecDeque<LogEvent>>>,
+}
+
+impl LogBuffer {
+    /// The buffer `init` installs
+    pub fn global() -> &'static LogBuffer {
+        static BUFFER: OnceLock<LogBuffer> = OnceLock::new();
+        BUFFER.get_or_init(LogBuffer::default)
+    }
+
+    pub fn push(&self, event: LogEvent) {
+        let Ok(mut events) = self.events.lock() else {
+            return;
+        };
+        if events.len() == RECENT_EVENTS {
+            events.pop_front();
+        }
+        events.push_back(event);
+    }
+
+    /// Events oldest first
+    pub fn snapshot(&self) -> Vec<LogEvent> {
+        self.events
+            .lock()
+            .map(|events| events.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogBuffer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.push(LogEvent {
+            time: Local::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message + &visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+/// Filter directive for gitalky's own spans and events
+pub fn filter_directive(verbosity: u8) -> String {
+    let level = match verbosity {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+    format!("warn,gitalky={}", level)
+}
+
+/// `~/.config/gitalky/gitalky.log`
+pub fn log_path() -> io::Result<PathBuf> {
+    Config::config_dir()
+        .map(|dir| dir.join("gitalky.log"))
+        .map_err(|e| io::Error::new(io::ErrorKind::NotFound, e.to_string()))
+}
+
+/// Send tracing output to the diagnostics log instead of the terminal,
+/// where it would corrupt the TUI, and keep recent events for `:logs`
+pub fn init(verbosity: u8) -> io::Result<PathBuf> {
+    let path = log_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(&path)?;
+    if file.metadata()?.len() > MAX_LOG_SIZE {
+        file.set_len(0)?;
+    }
+
+    let filter = EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(filter_directive(verbosity)));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(Mutex::new(file)).with_ansi(false))
+        .with(LogBuffer::global().clone())
+        .try_init()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_captures_events() {
+        let buffer = LogBuffer::default();
+        let subscriber = tracing_subscriber::registry().with(buffer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(wait_ms = 2000, "rate limited, retrying");
+            tracing::debug!("refreshed");
+        });
+
+        let events = buffer.snapshot();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].level, Level::WARN);
+        assert_eq!(events[0].message, "rate limited, retrying wait_ms=2000");
+        assert_eq!(events[0].target, module_path!());
+        assert_eq!(events[1].message, "refreshed");
+    }
+
+    #[test]
+    fn test_buffer_keeps_latest() {
+        let buffer = LogBuffer::default();
+        for i in 0..RECENT_EVENTS + 3 {
+            buffer.push(LogEvent {
+                time: Local::now(),
+                level: Level::INFO,
+                target: "gitalky".to_string(),
+                message: i.to_string(),
+            });
+        }
+        let events = buffer.snapshot();
+        assert_eq!(events.len(), RECENT_EVENTS);
+        assert_eq!(events[0].message, "3");
+    }
+
+    #[test]
+    fn test_filter_directive() {
+        assert_eq!(filter_directive(0), "warn,gitalky=info");
+        assert_eq!(filter_directive(1), "warn,gitalky=debug");
+        assert_eq!(filter_directive(5), "warn,gitalky=trace");
+    }
+}
@@ -0,0 +1,30 @@
+//! Git fixtures shared by the unit tests; integration tests use `tests/helpers`
+
+use crate::git::git_command;
+use std::path::Path;
+
+/// Run git in `dir`, failing the test when it fails; returns stdout, trimmed
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = git_command().args(args).current_dir(dir).output().unwrap();
+    assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Run git in `dir` for a command that may fail, such as a conflicting merge
+pub fn try_git(dir: &Path, args: &[&str]) -> bool {
+    git_command().args(args).current_dir(dir).output().unwrap().status.success()
+}
+
+/// Make `dir` an empty repository on `main` with a committer identity
+pub fn init_repo(dir: &Path) {
+    git(dir, &["init", "-q", "-b", "main"]);
+    git(dir, &["config", "user.name", "Test User"]);
+    git(dir, &["config", "user.email", "test@example.com"]);
+}
+
+/// Write `file`, with its own name as content, and commit it
+pub fn commit_file(dir: &Path, file: &str) {
+    std::fs::write(dir.join(file), file).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", file]);
+}
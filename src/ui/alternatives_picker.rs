@@ -0,0 +1,180 @@
+use crate::git::StateFingerprint;
+use crate::llm::GitCommand;
+use crate::security::CommandValidator;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// What the user did with the alternatives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlternativesAction {
+    Cancel,
+    /// Preview the selected command
+    Pick,
+}
+
+/// Candidate commands for a vague query, side by side, to preview one of
+pub struct AlternativesPicker {
+    query: String,
+    options: Vec<GitCommand>,
+    /// Whether each option needs confirming before it runs
+    dangerous: Vec<bool>,
+    selected: usize,
+    /// Repository state at translation, handed on to the preview
+    fingerprint: Option<StateFingerprint>,
+}
+
+impl AlternativesPicker {
+    pub fn new(
+        query: &str,
+        options: Vec<GitCommand>,
+        fingerprint: Option<StateFingerprint>,
+        validator: &CommandValidator,
+    ) -> Self {
+        let dangerous = options
+            .iter()
+            .map(|option| validator.validate(&option.command).is_ok_and(|validated| validated.is_dangerous))
+            .collect();
+        AlternativesPicker {
+            query: query.to_string(),
+            options,
+            dangerous,
+            selected: 0,
+            fingerprint,
+        }
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// The selected command, the query and the state it was translated in
+    pub fn into_choice(mut self) -> (String, GitCommand, Option<StateFingerprint>) {
+        let option = self.options.swap_remove(self.selected);
+        (self.query, option, self.fingerprint)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<AlternativesAction> {
+        match key.code {
+            KeyCode::Esc => Some(AlternativesAction::Cancel),
+            KeyCode::Enter => Some(AlternativesAction::Pick),
+            KeyCode::Left => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Right | KeyCode::Tab => {
+                self.selected = (self.selected + 1) % self.options.len();
+                None
+            }
+            KeyCode::Char(c) => {
+                let index = c.to_digit(10)?.checked_sub(1)? as usize;
+                (index < self.options.len()).then(|| {
+                    self.selected = index;
+                    AlternativesAction::Pick
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn option_lines(&self, index: usize) -> Vec<Line<'static>> {
+        let option = &self.options[index];
+        let mut lines = vec![
+            Line::styled(option.command.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Line::from(""),
+            Line::from(option.explanation.clone().unwrap_or_else(|| "No explanation given".to_string())),
+        ];
+        if self.dangerous[index] {
+            lines.push(Line::from(""));
+            lines.push(Line::styled("⚠ Destructive: asks for confirmation", Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Which did you mean? ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " ←→: choose  Enter or 1-9: preview  Esc: back to the query ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(" Query: ", Style::default().fg(Color::DarkGray)),
+                Span::raw(self.query.clone()),
+            ])),
+            rows[0],
+        );
+
+        let count = self.options.len() as u32;
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints((0..count).map(|_| Constraint::Ratio(1, count)).collect::<Vec<_>>())
+            .split(rows[1]);
+        for (index, column) in columns.iter().enumerate() {
+            let border = if index == self.selected { Color::Yellow } else { Color::DarkGray };
+            let option = Paragraph::new(self.option_lines(index)).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .title(format!(" {} ", index + 1))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(border)),
+            );
+            frame.render_widget(option, *column);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn picker() -> AlternativesPicker {
+        let option = |command: &str| GitCommand {
+            command: command.to_string(),
+            explanation: None,
+        };
+        AlternativesPicker::new(
+            "clean this up",
+            vec![option("git clean -n"), option("git reset --hard")],
+            None,
+            &CommandValidator::new(),
+        )
+    }
+
+    #[test]
+    fn test_choose_by_arrow_or_number() {
+        let mut by_arrow = picker();
+        assert_eq!(by_arrow.dangerous, [false, true]);
+        assert_eq!(by_arrow.handle_key(key(KeyCode::Right)), None);
+        assert_eq!(by_arrow.handle_key(key(KeyCode::Enter)), Some(AlternativesAction::Pick));
+        let (query, option, _) = by_arrow.into_choice();
+        assert_eq!((query.as_str(), option.command.as_str()), ("clean this up", "git reset --hard"));
+
+        let mut by_number = picker();
+        assert_eq!(by_number.handle_key(key(KeyCode::Char('3'))), None);
+        assert_eq!(by_number.handle_key(key(KeyCode::Char('1'))), Some(AlternativesAction::Pick));
+        assert_eq!(by_number.into_choice().1.command, "git clean -n");
+        assert_eq!(picker().handle_key(key(KeyCode::Esc)), Some(AlternativesAction::Cancel));
+    }
+}
@@ -0,0 +1,253 @@
+use crate::git::{BranchPolicy, create_branch_command};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Base branches listed at once; the list scrolls past them
+const VISIBLE_BASES: usize = 10;
+
+/// What the user asked for in the branch dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BranchDialogAction {
+    Cancel,
+    /// Preview this `git switch -c` command
+    Create(String),
+}
+
+/// Full-screen dialog naming a new branch by the naming policy and picking
+/// the branch it starts from
+pub struct BranchDialog {
+    pub visible: bool,
+    /// The name, or a description to suggest one from
+    text: String,
+    policy: BranchPolicy,
+    /// Local branches, the current one first
+    bases: Vec<String>,
+    current: Option<String>,
+    selected: usize,
+}
+
+impl BranchDialog {
+    pub fn new() -> Self {
+        BranchDialog {
+            visible: false,
+            text: String::new(),
+            policy: BranchPolicy::default(),
+            bases: Vec::new(),
+            current: None,
+            selected: 0,
+        }
+    }
+
+    /// Open with `text` typed in and `current` (if any) as the base
+    pub fn show(&mut self, text: &str, policy: BranchPolicy, mut bases: Vec<String>, current: Option<String>) {
+        if let Some(ref current) = current {
+            bases.retain(|branch| branch != current);
+            bases.insert(0, current.clone());
+        }
+        self.text = text.to_string();
+        self.policy = policy;
+        self.bases = bases;
+        self.current = current;
+        self.selected = 0;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// The text as typed when it passes the policy, else the name suggested from it
+    pub fn name(&self) -> String {
+        let typed = self.text.trim();
+        if self.policy.check(typed).is_ok() {
+            typed.to_string()
+        } else {
+            self.policy.suggest(typed)
+        }
+    }
+
+    /// The selected base, `None` when it's the current branch
+    pub fn base(&self) -> Option<&str> {
+        self.bases
+            .get(self.selected)
+            .map(String::as_str)
+            .filter(|base| Some(*base) != self.current.as_deref())
+    }
+
+    /// The command creating the branch, once the name passes the policy
+    pub fn command(&self) -> Result<String, String> {
+        let name = self.name();
+        self.policy.check(&name)?;
+        if self.bases.contains(&name) {
+            return Err(format!("{} already exists", name));
+        }
+        Ok(create_branch_command(&name, self.base()))
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<BranchDialogAction> {
+        match key.code {
+            KeyCode::Esc => Some(BranchDialogAction::Cancel),
+            KeyCode::Enter => self.command().ok().map(BranchDialogAction::Create),
+            // Tab takes the suggestion, to edit it further
+            KeyCode::Tab => {
+                self.text = self.name();
+                None
+            }
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.bases.len().saturating_sub(1));
+                None
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+                None
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.text.clear();
+                None
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.text.push(c);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let label = Style::default().fg(Color::DarkGray);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Name or description: ", label),
+                Span::styled(format!("{}█", self.text), Style::default().fg(Color::White)),
+            ]),
+            Line::from(""),
+        ];
+
+        let name = self.name();
+        let mut branch = vec![
+            Span::styled("Branch: ", label),
+            Span::styled(name.clone(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        ];
+        if name != self.text.trim() && !name.is_empty() {
+            branch.push(Span::styled("  (suggested; Tab to edit)", label));
+        }
+        lines.push(Line::from(branch));
+        lines.push(match self.command() {
+            Ok(command) => Line::styled(format!("✓ {}", command), Style::default().fg(Color::Green)),
+            Err(problem) => Line::styled(format!("✗ {}", problem), Style::default().fg(Color::Red)),
+        });
+        if let Some(pattern) = self.policy.pattern() {
+            lines.push(Line::styled(format!("Naming policy: {}", pattern), label));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled("Start from:", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+        let first = self.selected.saturating_sub(VISIBLE_BASES - 1);
+        for (index, base) in self.bases.iter().enumerate().skip(first).take(VISIBLE_BASES) {
+            let marker = if index == self.selected { "▶ " } else { "  " };
+            let mut style = Style::default().fg(Color::White);
+            if index == self.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            let mut spans = vec![Span::raw(marker), Span::styled(base.clone(), style)];
+            if Some(base) == self.current.as_ref() {
+                spans.push(Span::styled("  (current)", label));
+            }
+            lines.push(Line::from(spans));
+        }
+        if self.bases.is_empty() {
+            lines.push(Line::styled("  HEAD (no branches yet)", label));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" New Branch ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " type a name or what it's for  Tab: take suggestion  ↑↓: base  Enter: preview  Esc: cancel ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let inner = Rect {
+            x: inner.x + 1,
+            width: inner.width.saturating_sub(2),
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(self.lines()).wrap(Wrap { trim: false }), inner);
+    }
+}
+
+impl Default for BranchDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn dialog(text: &str) -> BranchDialog {
+        let policy = BranchPolicy::new(Some("^(feat|fix|chore)/[a-z0-9-]+$")).unwrap();
+        let mut dialog = BranchDialog::new();
+        let bases = vec!["develop".to_string(), "main".to_string(), "fix/old".to_string()];
+        dialog.show(text, policy, bases, Some("main".to_string()));
+        dialog
+    }
+
+    #[test]
+    fn test_suggests_compliant_name_from_description() {
+        let mut dialog = dialog("Fix the login timeout");
+        assert_eq!(dialog.name(), "fix/login-timeout");
+        assert_eq!(
+            dialog.handle_key(key(KeyCode::Enter)),
+            Some(BranchDialogAction::Create("git switch -c fix/login-timeout".to_string()))
+        );
+
+        // The current branch is listed first; another base is named in the command
+        dialog.handle_key(key(KeyCode::Down));
+        assert_eq!(dialog.base(), Some("develop"));
+        assert_eq!(dialog.command().unwrap(), "git switch -c fix/login-timeout develop");
+    }
+
+    #[test]
+    fn test_typed_name_kept_when_compliant() {
+        let mut dialog = dialog("feat/x");
+        assert_eq!(dialog.name(), "feat/x");
+        dialog.handle_key(key(KeyCode::Char(' ')));
+        dialog.handle_key(key(KeyCode::Char('y')));
+        // "feat/x y" breaks the policy: the suggestion takes over
+        assert_eq!(dialog.name(), "feat/x-y");
+        dialog.handle_key(key(KeyCode::Tab));
+        assert_eq!(dialog.text, "feat/x-y");
+    }
+
+    #[test]
+    fn test_existing_or_empty_name_refused() {
+        let mut existing = dialog("fix/old");
+        assert!(existing.command().unwrap_err().contains("already exists"));
+        assert_eq!(existing.handle_key(key(KeyCode::Enter)), None);
+        assert_eq!(existing.handle_key(key(KeyCode::Esc)), Some(BranchDialogAction::Cancel));
+        assert!(dialog("").command().is_err());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::git::{Checkpoint, CheckpointHead};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Input prefix for creating and listing checkpoints from the prompt
+pub const CHECKPOINT_COMMAND: &str = ":checkpoint";
+
+const CHECKPOINT_USAGE: &str = "Usage: :checkpoint create \"name\" | :checkpoint list";
+
+/// A parsed `:checkpoint` input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointCommand {
+    Create(String),
+    List,
+}
+
+/// Parse `:checkpoint create "name"`, `:checkpoint list` or a bare `:checkpoint`
+///
+/// `None` when the input isn't a checkpoint command at all, `Some(Err)` with
+/// the usage when it is but can't be understood.
+pub fn parse_checkpoint_command(query: &str) -> Option<Result<CheckpointCommand, String>> {
+    let rest = query.strip_prefix(CHECKPOINT_COMMAND)?;
+    let rest = rest.strip_prefix('s').unwrap_or(rest);
+    if !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    let rest = rest.trim();
+    if rest.is_empty() || rest == "list" {
+        return Some(Ok(CheckpointCommand::List));
+    }
+    if let Some(name) = rest.strip_prefix("create")
+        && (name.is_empty() || name.starts_with(' '))
+    {
+        let name = name.trim();
+        let name = ['"', '\'']
+            .iter()
+            .find_map(|quote| name.strip_prefix(*quote).and_then(|n| n.strip_suffix(*quote)))
+            .unwrap_or(name)
+            .trim();
+        if !name.is_empty() {
+            return Some(Ok(CheckpointCommand::Create(name.to_string())));
+        }
+    }
+    Some(Err(CHECKPOINT_USAGE.to_string()))
+}
+
+/// What the user picked in the checkpoint panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckpointAction {
+    Close,
+    Restore(Checkpoint),
+    Delete(Checkpoint),
+}
+
+/// Full-screen list of the repository's checkpoints
+pub struct CheckpointPanel {
+    pub visible: bool,
+    checkpoints: Vec<Checkpoint>,
+    selected: usize,
+    /// Waiting for `y` to delete the selected checkpoint
+    confirming_delete: bool,
+}
+
+impl CheckpointPanel {
+    pub fn new() -> Self {
+        CheckpointPanel {
+            visible: false,
+            checkpoints: Vec::new(),
+            selected: 0,
+            confirming_delete: false,
+        }
+    }
+
+    /// Show `checkpoints` (newest first), keeping the selection in range
+    pub fn show(&mut self, checkpoints: Vec<Checkpoint>) {
+        self.selected = self.selected.min(checkpoints.len().saturating_sub(1));
+        self.checkpoints = checkpoints;
+        self.confirming_delete = false;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<CheckpointAction> {
+        if self.confirming_delete {
+            self.confirming_delete = false;
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    self.checkpoints.get(self.selected).cloned().map(CheckpointAction::Delete)
+                }
+                _ => None,
+            };
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.checkpoints.len().saturating_sub(1));
+                None
+            }
+            KeyCode::Enter => self.checkpoints.get(self.selected).cloned().map(CheckpointAction::Restore),
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                self.confirming_delete = !self.checkpoints.is_empty();
+                None
+            }
+            KeyCode::Esc | KeyCode::Char('q') => Some(CheckpointAction::Close),
+            _ => None,
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.checkpoints.is_empty() {
+            return vec![
+                Line::from("No checkpoints in this repository yet."),
+                Line::from(""),
+                Line::from("Save one before risky work with :checkpoint create \"before rebase\""),
+            ];
+        }
+
+        let mut lines = Vec::new();
+        for (i, checkpoint) in self.checkpoints.iter().enumerate() {
+            let selected = i == self.selected;
+            let marker = if selected { "› " } else { "  " };
+            let name_style = if selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::styled(marker, Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    checkpoint.created.format("%Y-%m-%d %H:%M  ").to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(checkpoint.name.clone(), name_style),
+                Span::styled(format!("  {}", head_label(checkpoint)), Style::default().fg(Color::Cyan)),
+            ];
+            if checkpoint.stash.is_some() {
+                spans.push(Span::styled("  + changes", Style::default().fg(Color::Magenta)));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        // Branch positions of the selected checkpoint
+        if let Some(checkpoint) = self.checkpoints.get(self.selected) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Branches at this checkpoint:",
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )));
+            for (branch, commit) in &checkpoint.snapshot.branches {
+                lines.push(Line::from(format!("  {} {}", short(commit), branch)));
+            }
+        }
+
+        if self.confirming_delete {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Delete this checkpoint? y: delete, any other key: keep",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Checkpoints ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " ↑↓: select  Enter: restore  d: delete  Esc: close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+impl Default for CheckpointPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `main @ abc1234` or `detached @ abc1234`
+fn head_label(checkpoint: &Checkpoint) -> String {
+    let commit = checkpoint.snapshot.head_commit.as_deref().map(short).unwrap_or_default();
+    match checkpoint.snapshot.head {
+        CheckpointHead::Branch(ref branch) => format!("{} @ {}", branch, commit),
+        CheckpointHead::Detached => format!("detached @ {}", commit),
+    }
+}
+
+fn short(commit: &str) -> String {
+    commit.chars().take(7).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::RepoSnapshot;
+    use chrono::{Local, TimeZone};
+    use crossterm::event::KeyModifiers;
+    use std::collections::BTreeMap;
+
+    fn checkpoint(name: &str) -> Checkpoint {
+        Checkpoint {
+            ref_name: format!("refs/gitalky/checkpoints/1-{}", name),
+            name: name.to_string(),
+            created: Local.timestamp_opt(1_700_000_000, 0).unwrap(),
+            snapshot: RepoSnapshot {
+                head: CheckpointHead::Branch("main".to_string()),
+                head_commit: Some("0123456789abcdef".to_string()),
+                branches: BTreeMap::from([("main".to_string(), "0123456789abcdef".to_string())]),
+            },
+            stash: None,
+        }
+    }
+
+    fn press(panel: &mut CheckpointPanel, code: KeyCode) -> Option<CheckpointAction> {
+        panel.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_parse_checkpoint_command() {
+        assert_eq!(
+            parse_checkpoint_command(":checkpoint create \"before rebase\""),
+            Some(Ok(CheckpointCommand::Create("before rebase".to_string())))
+        );
+        assert_eq!(
+            parse_checkpoint_command(":checkpoint create wip"),
+            Some(Ok(CheckpointCommand::Create("wip".to_string())))
+        );
+        assert_eq!(parse_checkpoint_command(":checkpoint"), Some(Ok(CheckpointCommand::List)));
+        assert_eq!(parse_checkpoint_command(":checkpoints"), Some(Ok(CheckpointCommand::List)));
+        assert_eq!(parse_checkpoint_command(":checkpoint list"), Some(Ok(CheckpointCommand::List)));
+        assert!(matches!(parse_checkpoint_command(":checkpoint create \"\""), Some(Err(_))));
+        assert!(matches!(parse_checkpoint_command(":checkpoint restore x"), Some(Err(_))));
+        assert_eq!(parse_checkpoint_command(":checkpointy"), None);
+        assert_eq!(parse_checkpoint_command("checkpoint my work"), None);
+    }
+
+    #[test]
+    fn test_select_and_restore() {
+        let mut panel = CheckpointPanel::new();
+        panel.show(vec![checkpoint("newer"), checkpoint("older")]);
+
+        assert_eq!(press(&mut panel, KeyCode::Down), None);
+        assert_eq!(press(&mut panel, KeyCode::Down), None);
+        assert_eq!(press(&mut panel, KeyCode::Enter), Some(CheckpointAction::Restore(checkpoint("older"))));
+        assert_eq!(press(&mut panel, KeyCode::Esc), Some(CheckpointAction::Close));
+    }
+
+    #[test]
+    fn test_delete_needs_confirmation() {
+        let mut panel = CheckpointPanel::new();
+        panel.show(vec![checkpoint("wip")]);
+
+        assert_eq!(press(&mut panel, KeyCode::Char('d')), None);
+        assert_eq!(press(&mut panel, KeyCode::Char('n')), None);
+        assert_eq!(press(&mut panel, KeyCode::Char('y')), None);
+
+        press(&mut panel, KeyCode::Char('d'));
+        assert!(panel.lines().last().unwrap().to_string().starts_with("Delete this checkpoint?"));
+        assert_eq!(press(&mut panel, KeyCode::Char('y')), Some(CheckpointAction::Delete(checkpoint("wip"))));
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut panel = CheckpointPanel::new();
+        let mut with_changes = checkpoint("before rebase");
+        with_changes.stash = Some("fedcba9876543210".to_string());
+        panel.show(vec![with_changes]);
+
+        let text: Vec<String> = panel.lines().iter().map(|line| line.to_string()).collect();
+        assert!(text[0].ends_with("before rebase  main @ 0123456  + changes"));
+        assert_eq!(text.last().unwrap(), "  0123456 main");
+    }
+}
@@ -1,3 +1,6 @@
+use crate::git::{CommitWarning, MergePreview, Rename, ResolvedRef, StateFingerprint};
+use crate::llm::{describe_placeholders, placeholders};
+use crate::security::{CommandOrigin, CommandValidator, DangerousOp, SecretFinding, url_warnings};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
@@ -6,35 +9,193 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget, Wrap},
 };
+use std::ops::Range;
+
+/// Result of validating the command while it is being edited
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditFeedback {
+    Valid,
+    Dangerous(DangerousOp),
+    Rejected(String),
+    /// Placeholders like `<branch-name>` still to fill in
+    Unfilled(String),
+}
+
+/// Most secret findings listed in the preview
+const MAX_SECRET_LINES: usize = 5;
+
+/// Most commit check warnings listed in the preview
+const MAX_WARNING_LINES: usize = 5;
+
+/// Most detected renames and copies listed in the preview
+const MAX_RENAME_LINES: usize = 5;
 
 /// Command preview widget for reviewing and editing proposed commands
 pub struct CommandPreview {
     command: String,
+    /// The command as first proposed, before any edits
+    proposed: String,
     explanation: Option<String>,
     edit_mode: bool,
     cursor_position: usize,
+    /// Placeholder jumped to with Tab; typing replaces it
+    selected: Option<Range<usize>>,
+    origin: CommandOrigin,
+    feedback: Option<EditFeedback>,
+    /// Result of the pre-commit secrets scan; `None` until scanned
+    secrets: Option<Vec<SecretFinding>>,
+    secrets_acknowledged: bool,
+    /// Whitespace, conflict-marker and large-file warnings for a commit
+    commit_warnings: Vec<CommitWarning>,
+    /// Renames and copies in what a commit or diff covers
+    renames: Vec<Rename>,
+    commit_checked: bool,
+    /// Commits the refs in the command point at
+    resolved_refs: Vec<ResolvedRef>,
+    refs_resolved: bool,
+    /// What a `git merge` would bring in
+    merge: Option<MergePreview>,
+    merge_previewed: bool,
+    /// Query the command was translated from and the state it was translated in
+    translation: Option<(String, StateFingerprint)>,
+    /// What changed in the repository since the translation
+    state_changes: Vec<String>,
 }
 
 impl CommandPreview {
     pub fn new(command: String, explanation: Option<String>) -> Self {
         let cursor_position = command.len();
         Self {
+            proposed: command.clone(),
             command,
             explanation,
             edit_mode: false,
             cursor_position,
+            selected: None,
+            origin: CommandOrigin::User,
+            feedback: None,
+            secrets: None,
+            secrets_acknowledged: false,
+            commit_warnings: Vec::new(),
+            renames: Vec::new(),
+            commit_checked: false,
+            resolved_refs: Vec::new(),
+            refs_resolved: false,
+            merge: None,
+            merge_previewed: false,
+            translation: None,
+            state_changes: Vec::new(),
+        }
+    }
+
+    /// Remember the query and the repository state the LLM translated in
+    pub fn with_translation(mut self, query: String, fingerprint: StateFingerprint) -> Self {
+        self.translation = Some((query, fingerprint));
+        self
+    }
+
+    /// The query the command was translated from, if it came from the LLM
+    pub fn query(&self) -> Option<&str> {
+        self.translation.as_ref().map(|(query, _)| query.as_str())
+    }
+
+    pub fn translated_state(&self) -> Option<&StateFingerprint> {
+        self.translation.as_ref().map(|(_, fingerprint)| fingerprint)
+    }
+
+    /// Record what changed since the translation; `now` becomes the state
+    /// later changes are compared with
+    pub fn set_state_changes(&mut self, changes: Vec<String>, now: StateFingerprint) {
+        if let Some((_, ref mut fingerprint)) = self.translation {
+            *fingerprint = now;
         }
+        self.state_changes = changes;
     }
 
-    /// Enter edit mode for modifying the command
+    pub fn state_changes(&self) -> &[String] {
+        &self.state_changes
+    }
+
+    /// Record where the proposed command came from
+    pub fn with_origin(mut self, origin: CommandOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Where the command came from, accounting for edits made in the preview
+    pub fn origin(&self) -> CommandOrigin {
+        self.origin
+    }
+
+    /// Enter edit mode for modifying the command, at its first placeholder if it has any
     pub fn enter_edit_mode(&mut self) {
         self.edit_mode = true;
         self.cursor_position = self.command.len();
+        self.selected = None;
+        self.jump_to_placeholder(true);
     }
 
     /// Exit edit mode
     pub fn exit_edit_mode(&mut self) {
         self.edit_mode = false;
+        self.selected = None;
+        self.feedback = None;
+    }
+
+    /// Whether the command still has `<placeholder>` blanks to fill in
+    pub fn has_placeholders(&self) -> bool {
+        !placeholders(&self.command).is_empty()
+    }
+
+    /// Select the next (or previous) placeholder from the cursor, wrapping around
+    fn jump_to_placeholder(&mut self, forward: bool) {
+        let blanks = placeholders(&self.command);
+        let target = if forward {
+            // From the start of a selected one, the next is further on
+            let from = self.selected.as_ref().map_or(self.cursor_position, |range| range.end);
+            blanks.iter().find(|range| range.start >= from).or(blanks.first())
+        } else {
+            blanks.iter().rev().find(|range| range.start < self.cursor_position).or(blanks.last())
+        };
+        if let Some(range) = target.cloned() {
+            self.cursor_position = range.start;
+            self.selected = Some(range);
+        }
+    }
+
+    /// Remove the selected placeholder, leaving the cursor where it was
+    fn take_selected(&mut self) -> bool {
+        let Some(range) = self.selected.take() else {
+            return false;
+        };
+        self.cursor_position = range.start;
+        self.command.replace_range(range, "");
+        true
+    }
+
+    /// Re-run the validator on the current text, as the executor would
+    pub fn revalidate(&mut self, validator: &CommandValidator) {
+        if self.has_placeholders() {
+            self.feedback = Some(EditFeedback::Unfilled(describe_placeholders(&self.command)));
+            return;
+        }
+        self.feedback = Some(match validator.validate_with_origin(&self.command, self.origin) {
+            Ok(validated) => match validated.danger_type {
+                Some(op) => EditFeedback::Dangerous(op),
+                None => EditFeedback::Valid,
+            },
+            Err(e) => EditFeedback::Rejected(e.to_string()),
+        });
+    }
+
+    /// Latest validation result for the edited command
+    pub fn feedback(&self) -> Option<&EditFeedback> {
+        self.feedback.as_ref()
+    }
+
+    /// Whether the edited command may be submitted
+    pub fn can_submit(&self) -> bool {
+        !matches!(self.feedback, Some(EditFeedback::Rejected(_) | EditFeedback::Unfilled(_)))
     }
 
     /// Check if in edit mode
@@ -47,12 +208,177 @@ impl CommandPreview {
         &self.command
     }
 
+    /// The command as first proposed, before any edits
+    pub fn proposed_command(&self) -> &str {
+        &self.proposed
+    }
+
+    /// Replace the explanation shown under the command
+    pub fn set_explanation(&mut self, explanation: Option<String>) {
+        self.explanation = explanation;
+    }
+
+    pub fn explanation(&self) -> Option<&str> {
+        self.explanation.as_deref()
+    }
+
+    /// Record the findings of the secrets scan for the current command
+    pub fn set_secret_findings(&mut self, findings: Vec<SecretFinding>) {
+        self.secrets = Some(findings);
+        self.secrets_acknowledged = false;
+    }
+
+    /// Findings of the secrets scan, if it has run for the current command
+    pub fn secret_findings(&self) -> Option<&[SecretFinding]> {
+        self.secrets.as_deref()
+    }
+
+    /// Whether possible secrets were found and the user hasn't accepted them yet
+    pub fn needs_secret_confirmation(&self) -> bool {
+        !self.secrets_acknowledged && self.secrets.as_ref().is_some_and(|f| !f.is_empty())
+    }
+
+    /// The user explicitly chose to commit despite the findings
+    pub fn acknowledge_secrets(&mut self) {
+        self.secrets_acknowledged = true;
+    }
+
+    /// Record the results of the pre-commit checks for the current command
+    pub fn set_commit_warnings(&mut self, warnings: Vec<CommitWarning>) {
+        self.commit_warnings = warnings;
+        self.commit_checked = true;
+    }
+
+    pub fn commit_warnings(&self) -> &[CommitWarning] {
+        &self.commit_warnings
+    }
+
+    /// Record the renames and copies git detects in what the command covers
+    pub fn set_renames(&mut self, renames: Vec<Rename>) {
+        self.renames = renames;
+    }
+
+    pub fn renames(&self) -> &[Rename] {
+        &self.renames
+    }
+
+    /// Identity profile a commit warning suggests switching to
+    pub fn identity_switch(&self) -> Option<&str> {
+        self.commit_warnings.iter().find_map(|warning| match warning {
+            CommitWarning::WrongIdentity { profile, .. } => profile.as_deref(),
+            _ => None,
+        })
+    }
+
+    /// Whether the pre-commit checks still have to run for the current command
+    pub fn needs_commit_checks(&self) -> bool {
+        !self.commit_checked
+    }
+
+    /// Record the commits the refs in the current command resolve to
+    pub fn set_resolved_refs(&mut self, refs: Vec<ResolvedRef>) {
+        self.resolved_refs = refs;
+        self.refs_resolved = true;
+    }
+
+    pub fn resolved_refs(&self) -> &[ResolvedRef] {
+        &self.resolved_refs
+    }
+
+    /// Whether the refs in the current command still have to be resolved
+    pub fn needs_ref_resolution(&self) -> bool {
+        !self.refs_resolved
+    }
+
+    /// Record what the merge in the current command would bring in
+    pub fn set_merge_preview(&mut self, merge: Option<MergePreview>) {
+        self.merge = merge;
+        self.merge_previewed = true;
+    }
+
+    pub fn merge_preview(&self) -> Option<&MergePreview> {
+        self.merge.as_ref()
+    }
+
+    /// Whether the merge preview still has to be worked out for the current command
+    pub fn needs_merge_preview(&self) -> bool {
+        !self.merge_previewed
+    }
+
+    /// Edits invalidate origin-independent results computed for the old text
+    fn command_changed(&mut self) {
+        self.origin = self.origin.edited();
+        self.secrets = None;
+        self.secrets_acknowledged = false;
+        self.commit_warnings.clear();
+        self.renames.clear();
+        self.commit_checked = false;
+        self.resolved_refs.clear();
+        self.refs_resolved = false;
+        self.merge = None;
+        self.merge_previewed = false;
+    }
+
+    /// Height needed to show the command and its explanation, including borders
+    pub fn desired_height(&self) -> u16 {
+        let explanation_lines = self
+            .explanation
+            .as_ref()
+            .map(|e| e.lines().count() + 1)
+            .unwrap_or(0);
+        let feedback_lines = if self.feedback.is_some() { 1 } else { 0 };
+        let ref_lines = self.resolved_refs.len();
+        let secret_lines = match self.secrets.as_deref() {
+            Some(findings) if !findings.is_empty() => {
+                // Blank + heading + findings (+ "more") + prompt
+                let more = if findings.len() > MAX_SECRET_LINES { 1 } else { 0 };
+                3 + findings.len().min(MAX_SECRET_LINES) + more
+            }
+            _ => 0,
+        };
+        let warning_lines = match self.commit_warnings.len() {
+            0 => 0,
+            // Blank + heading + warnings (+ "more")
+            n => 2 + n.min(MAX_WARNING_LINES) + usize::from(n > MAX_WARNING_LINES),
+        };
+        // Blank + one per warning
+        let url_lines = match url_warnings(&self.command).len() {
+            0 => 0,
+            n => 1 + n,
+        };
+        let rename_lines = match self.renames.len() {
+            0 => 0,
+            n => 2 + n.min(MAX_RENAME_LINES) + usize::from(n > MAX_RENAME_LINES),
+        };
+        // Blank + heading + impact
+        let impact_lines = self.merge.as_ref().map_or(0, |merge| 2 + merge.impact_lines().len());
+        // Blank + heading + changes + prompt
+        let change_lines = match self.state_changes.len() {
+            0 => 0,
+            n => 3 + n,
+        };
+        // Borders + title + blank + command + refs + feedback + explanation
+        (5 + ref_lines
+            + feedback_lines
+            + change_lines
+            + secret_lines
+            + warning_lines
+            + url_lines
+            + rename_lines
+            + impact_lines
+            + explanation_lines)
+            .clamp(8, 16) as u16
+    }
+
     /// Handle keyboard input in edit mode
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
         if !self.edit_mode {
             return false;
         }
 
+        if matches!(key.code, KeyCode::Left | KeyCode::Right | KeyCode::Home | KeyCode::End) {
+            self.selected = None;
+        }
         match key.code {
             KeyCode::Char(c) => {
                 // Check for Ctrl+C (don't insert)
@@ -60,20 +386,36 @@ impl CommandPreview {
                     return false;
                 }
 
+                self.take_selected();
                 self.command.insert(self.cursor_position, c);
-                self.cursor_position += 1;
+                self.cursor_position += c.len_utf8();
+                self.command_changed();
+                true
+            }
+            KeyCode::Tab => {
+                self.jump_to_placeholder(true);
+                true
+            }
+            KeyCode::BackTab => {
+                self.jump_to_placeholder(false);
+                true
+            }
+            KeyCode::Backspace | KeyCode::Delete if self.take_selected() => {
+                self.command_changed();
                 true
             }
             KeyCode::Backspace => {
                 if self.cursor_position > 0 {
                     self.cursor_position -= 1;
                     self.command.remove(self.cursor_position);
+                    self.command_changed();
                 }
                 true
             }
             KeyCode::Delete => {
                 if self.cursor_position < self.command.len() {
                     self.command.remove(self.cursor_position);
+                    self.command_changed();
                 }
                 true
             }
@@ -102,6 +444,48 @@ impl CommandPreview {
     }
 }
 
+impl CommandPreview {
+    /// The command with its placeholders highlighted, and the cursor while editing
+    fn command_spans(&self) -> Vec<Span<'_>> {
+        let plain = Style::default().fg(Color::Green);
+        let mut segments = Vec::new();
+        let mut last = 0;
+        for range in placeholders(&self.command) {
+            if range.start > last {
+                segments.push((last..range.start, plain));
+            }
+            let style = if self.selected.as_ref() == Some(&range) {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+            };
+            last = range.end;
+            segments.push((range, style));
+        }
+        if last < self.command.len() {
+            segments.push((last..self.command.len(), plain));
+        }
+
+        let cursor = Span::styled("▊", Style::default().fg(Color::Yellow));
+        let mut spans = vec![Span::raw("  ")];
+        let mut cursor_drawn = !self.edit_mode;
+        for (range, style) in segments {
+            if !cursor_drawn && range.contains(&self.cursor_position) {
+                spans.push(Span::styled(&self.command[range.start..self.cursor_position], style));
+                spans.push(cursor.clone());
+                spans.push(Span::styled(&self.command[self.cursor_position..range.end], style));
+                cursor_drawn = true;
+            } else {
+                spans.push(Span::styled(&self.command[range], style));
+            }
+        }
+        if !cursor_drawn {
+            spans.push(cursor);
+        }
+        spans
+    }
+}
+
 impl Widget for &CommandPreview {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let mut lines = Vec::new();
@@ -120,29 +504,170 @@ impl Widget for &CommandPreview {
         lines.push(Line::from(""));
 
         // Command line with cursor if in edit mode
-        if self.edit_mode {
-            let before = &self.command[..self.cursor_position];
-            let after = &self.command[self.cursor_position..];
-            lines.push(Line::from(vec![
-                Span::styled("  ", Style::default()),
-                Span::styled(before, Style::default().fg(Color::Green)),
-                Span::styled("▊", Style::default().fg(Color::Yellow)),
-                Span::styled(after, Style::default().fg(Color::Green)),
-            ]));
-        } else {
+        lines.push(Line::from(self.command_spans()));
+
+        // Where the refs in the command point
+        for resolved in &self.resolved_refs {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", resolved),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+
+        // Live validation feedback while editing
+        if self.edit_mode
+            && let Some(ref feedback) = self.feedback
+        {
+            let (text, color) = match feedback {
+                EditFeedback::Valid => ("✓ Passes validation".to_string(), Color::Green),
+                EditFeedback::Dangerous(op) => (
+                    format!("⚠ Dangerous ({:?}): you'll be asked to confirm", op),
+                    Color::Yellow,
+                ),
+                EditFeedback::Rejected(reason) => (format!("✗ {}", reason), Color::Red),
+                EditFeedback::Unfilled(blanks) => (
+                    format!("✎ Fill in {} (Tab jumps between them)", blanks),
+                    Color::Yellow,
+                ),
+            };
             lines.push(Line::from(vec![
                 Span::styled("  ", Style::default()),
-                Span::styled(&self.command, Style::default().fg(Color::Green)),
+                Span::styled(text, Style::default().fg(color)),
             ]));
         }
 
+        // The repository moved on after the LLM saw it
+        if !self.state_changes.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "⚠ The repository changed since this was translated:",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            for change in &self.state_changes {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", change),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(Span::styled(
+                "  Press r to refresh and re-translate, Enter to run it anyway",
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        // Possible secrets in what the commit will contain
+        if let Some(findings) = self.secrets.as_deref().filter(|f| !f.is_empty()) {
+            let warning = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("⚠ Possible secrets in this commit ({}):", findings.len()),
+                warning,
+            )));
+            for finding in findings.iter().take(MAX_SECRET_LINES) {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", finding),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+            if findings.len() > MAX_SECRET_LINES {
+                lines.push(Line::from(Span::styled(
+                    format!("  ... and {} more", findings.len() - MAX_SECRET_LINES),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            let prompt = if self.secrets_acknowledged {
+                "  Confirmed: committing anyway"
+            } else {
+                "  Press Y to commit anyway, Esc to cancel"
+            };
+            lines.push(Line::from(Span::styled(prompt, Style::default().fg(Color::Yellow))));
+        }
+
+        // Plaintext transports and credentials in remote URLs
+        let url_warnings = url_warnings(&self.command);
+        if !url_warnings.is_empty() {
+            lines.push(Line::from(""));
+            for warning in &url_warnings {
+                lines.push(Line::from(Span::styled(
+                    format!("⚠ {}", warning),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+        }
+
+        // Whitespace errors, conflict markers and large files in the commit
+        if !self.commit_warnings.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Commit checks ({}):", self.commit_warnings.len()),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            for warning in self.commit_warnings.iter().take(MAX_WARNING_LINES) {
+                let color = match warning {
+                    CommitWarning::ConflictMarker { .. } | CommitWarning::WrongIdentity { .. } => Color::Red,
+                    _ => Color::Yellow,
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", warning),
+                    Style::default().fg(color),
+                )));
+            }
+            if self.commit_warnings.len() > MAX_WARNING_LINES {
+                lines.push(Line::from(Span::styled(
+                    format!("  ... and {} more", self.commit_warnings.len() - MAX_WARNING_LINES),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+
+        // Files git matched across paths
+        if !self.renames.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Renames ({}):", self.renames.len()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )));
+            for rename in self.renames.iter().take(MAX_RENAME_LINES) {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", rename),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            if self.renames.len() > MAX_RENAME_LINES {
+                lines.push(Line::from(Span::styled(
+                    format!("  ... and {} more", self.renames.len() - MAX_RENAME_LINES),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+
+        // What a merge brings in
+        if let Some(ref merge) = self.merge {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Impact:",
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )));
+            for (i, text) in merge.impact_lines().into_iter().enumerate() {
+                let color = match i {
+                    0 if !merge.fast_forward && merge.commit_count > 0 => Color::Yellow,
+                    0 => Color::Cyan,
+                    _ => Color::Gray,
+                };
+                lines.push(Line::from(Span::styled(format!("  {}", text), Style::default().fg(color))));
+            }
+        }
+
         // Explanation if present
         if let Some(ref explanation) = self.explanation {
             lines.push(Line::from(""));
-            lines.push(Line::from(vec![
-                Span::styled("Explanation: ", Style::default().fg(Color::DarkGray)),
-                Span::styled(explanation, Style::default().fg(Color::Gray)),
-            ]));
+            for (i, text) in explanation.lines().enumerate() {
+                let prefix = if i == 0 { "Explanation: " } else { "  " };
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, Style::default().fg(Color::DarkGray)),
+                    Span::styled(text, Style::default().fg(Color::Gray)),
+                ]));
+            }
         }
 
         let block = Block::default()
@@ -165,6 +690,7 @@ impl Widget for &CommandPreview {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::RenameKind;
 
     #[test]
     fn test_command_preview_creation() {
@@ -183,6 +709,20 @@ mod tests {
         assert!(preview.explanation.is_some());
     }
 
+    #[test]
+    fn test_desired_height_grows_with_explanation() {
+        let mut preview = CommandPreview::new("git clean -fd".to_string(), None);
+        assert_eq!(preview.desired_height(), 8);
+
+        let long = (0..6).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        preview.set_explanation(Some(long));
+        assert_eq!(preview.desired_height(), 12);
+
+        let huge = (0..40).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        preview.set_explanation(Some(huge));
+        assert_eq!(preview.desired_height(), 16);
+    }
+
     #[test]
     fn test_edit_mode() {
         let mut preview = CommandPreview::new("git status".to_string(), None);
@@ -211,6 +751,50 @@ mod tests {
         assert!(preview.handle_key(key));
 
         assert_eq!(preview.get_command(), "git status -s");
+        assert_eq!(preview.proposed_command(), "git status");
+    }
+
+    #[test]
+    fn test_fill_in_placeholders() {
+        let validator = CommandValidator::new();
+        let mut preview = CommandPreview::new("git checkout -b <branch-name> <start>".to_string(), None);
+        assert!(preview.has_placeholders());
+        preview.enter_edit_mode();
+        preview.revalidate(&validator);
+        assert_eq!(preview.feedback(), Some(&EditFeedback::Unfilled("<branch-name>, <start>".to_string())));
+        assert!(!preview.can_submit());
+
+        // Typing replaces the selected placeholder
+        for c in "fix".chars() {
+            preview.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(preview.get_command(), "git checkout -b fix <start>");
+        preview.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        preview.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        for c in "main".chars() {
+            preview.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(preview.get_command(), "git checkout -b fix main");
+        preview.revalidate(&validator);
+        assert_eq!(preview.feedback(), Some(&EditFeedback::Valid));
+        assert!(preview.can_submit());
+    }
+
+    #[test]
+    fn test_tab_wraps_between_placeholders() {
+        let mut preview = CommandPreview::new("git commit -m <message> --author <author>".to_string(), None);
+        preview.enter_edit_mode();
+        assert_eq!(preview.cursor_position, 14);
+        preview.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(preview.cursor_position, 33);
+        preview.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert_eq!(preview.cursor_position, 14);
+        preview.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::NONE));
+        assert_eq!(preview.cursor_position, 33);
+        // Moving the cursor lets go of the placeholder
+        preview.handle_key(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        preview.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(preview.get_command(), "git commit -m <message> --author <author>x");
     }
 
     #[test]
@@ -225,6 +809,53 @@ mod tests {
         assert_eq!(preview.get_command(), "git statu");
     }
 
+    #[test]
+    fn test_editing_changes_origin() {
+        let mut preview =
+            CommandPreview::new("git status".to_string(), None).with_origin(CommandOrigin::Llm);
+        preview.enter_edit_mode();
+
+        // Moving the cursor isn't an edit
+        preview.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        assert_eq!(preview.origin(), CommandOrigin::Llm);
+
+        preview.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert_eq!(preview.origin(), CommandOrigin::LlmEdited);
+    }
+
+    #[test]
+    fn test_live_validation_feedback() {
+        let validator = CommandValidator::new();
+        let mut preview = CommandPreview::new("git status".to_string(), None);
+        preview.enter_edit_mode();
+        preview.revalidate(&validator);
+        assert_eq!(preview.feedback(), Some(&EditFeedback::Valid));
+        assert!(preview.can_submit());
+
+        for c in "; ls".chars() {
+            preview.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        preview.revalidate(&validator);
+        assert!(matches!(preview.feedback(), Some(EditFeedback::Rejected(_))));
+        assert!(!preview.can_submit());
+
+        preview.exit_edit_mode();
+        assert!(preview.feedback().is_none());
+    }
+
+    #[test]
+    fn test_live_validation_dangerous() {
+        let validator = CommandValidator::new();
+        let mut preview = CommandPreview::new("git reset --hard".to_string(), None);
+        preview.enter_edit_mode();
+        preview.revalidate(&validator);
+        assert_eq!(
+            preview.feedback(),
+            Some(&EditFeedback::Dangerous(DangerousOp::HardReset))
+        );
+        assert!(preview.can_submit());
+    }
+
     #[test]
     fn test_no_edit_when_not_in_edit_mode() {
         let mut preview = CommandPreview::new("git status".to_string(), None);
@@ -234,4 +865,110 @@ mod tests {
 
         assert_eq!(preview.get_command(), "git status");
     }
+
+    #[test]
+    fn test_editing_resets_secret_scan() {
+        use crate::security::{SecretFinding, SecretKind};
+
+        let mut preview = CommandPreview::new("git commit -m x".to_string(), None);
+        preview.set_secret_findings(vec![SecretFinding {
+            file: "a.env".to_string(),
+            line: 3,
+            kind: SecretKind::AwsAccessKey,
+        }]);
+        assert!(preview.needs_secret_confirmation());
+        assert!(preview.desired_height() > 8);
+
+        preview.enter_edit_mode();
+        preview.handle_key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(preview.secret_findings().is_none());
+        assert!(!preview.needs_secret_confirmation());
+    }
+
+    #[test]
+    fn test_resolved_refs_reset_on_edit() {
+        let mut preview = CommandPreview::new("git reset --hard HEAD~3".to_string(), None);
+        assert!(preview.needs_ref_resolution());
+
+        preview.set_resolved_refs(vec![ResolvedRef {
+            name: "HEAD~3".to_string(),
+            short_sha: "abc1234".to_string(),
+            subject: "Fix login".to_string(),
+        }]);
+        assert!(!preview.needs_ref_resolution());
+        assert_eq!(preview.resolved_refs()[0].to_string(), "HEAD~3 = abc1234 'Fix login'");
+
+        preview.enter_edit_mode();
+        preview.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert!(preview.needs_ref_resolution());
+        assert!(preview.resolved_refs().is_empty());
+    }
+
+    #[test]
+    fn test_renames_reset_on_edit() {
+        let mut preview = CommandPreview::new("git diff --cached".to_string(), Some("Show staged changes".to_string()));
+        let height = preview.desired_height();
+        preview.set_renames(vec![Rename {
+            kind: RenameKind::Renamed,
+            from: "old.rs".to_string(),
+            to: "new.rs".to_string(),
+            similarity: 92,
+        }]);
+        assert!(preview.desired_height() > height);
+
+        preview.enter_edit_mode();
+        preview.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert!(preview.renames().is_empty());
+    }
+
+    #[test]
+    fn test_url_warnings_take_room() {
+        let explanation = Some("Copy the project\ninto ./a".to_string());
+        let height = |command: &str| CommandPreview::new(command.to_string(), explanation.clone()).desired_height();
+        let plain = height("git clone https://example.com/a.git");
+        // Blank, credentials and plaintext lines
+        assert_eq!(height("git clone http://me:pw@example.com/a.git"), plain + 3);
+    }
+
+    #[test]
+    fn test_commit_warnings_reset_on_edit() {
+        let mut preview = CommandPreview::new("git commit -m x".to_string(), None);
+        assert!(preview.needs_commit_checks());
+
+        let height = preview.desired_height();
+        preview.set_commit_warnings(vec![CommitWarning::LargeFile {
+            file: "video.mp4".to_string(),
+            bytes: 50 * 1024 * 1024,
+        }]);
+        assert!(!preview.needs_commit_checks());
+        assert!(preview.desired_height() >= height);
+
+        preview.enter_edit_mode();
+        preview.handle_key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(preview.needs_commit_checks());
+        assert!(preview.commit_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_merge_preview_reset_on_edit() {
+        let mut preview = CommandPreview::new("git merge feature".to_string(), None);
+        assert!(preview.needs_merge_preview());
+
+        let height = preview.desired_height();
+        preview.set_merge_preview(Some(MergePreview {
+            branch: "feature".to_string(),
+            commit_count: 2,
+            commits: vec!["abc1234 Add a.txt".to_string(), "def5678 Add b.txt".to_string()],
+            stat: Some("2 files changed, 2 insertions(+)".to_string()),
+            fast_forward: true,
+            ff_mode: crate::git::FastForwardMode::Allow,
+        }));
+        assert!(!preview.needs_merge_preview());
+        assert!(preview.desired_height() > height);
+
+        preview.enter_edit_mode();
+        preview.handle_key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(preview.needs_merge_preview());
+        assert!(preview.merge_preview().is_none());
+    }
 }
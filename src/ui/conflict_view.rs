@@ -0,0 +1,264 @@
+use crate::git::{ConflictFile, ConflictHunk, HunkChoice};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// What the user asked for in the conflict view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictAction {
+    Close,
+    /// Open the file in the editor at this line
+    Edit(String, usize),
+    /// Write the chosen sides back and stage the file
+    Write,
+    /// Resolve the file in the configured merge tool instead
+    MergeTool(String),
+}
+
+/// Full-screen ours / base / theirs view of one conflicted file, taking a
+/// side per hunk
+pub struct ConflictView {
+    pub visible: bool,
+    file: Option<ConflictFile>,
+    selected: usize,
+    /// Why writing was refused
+    note: Option<String>,
+}
+
+impl ConflictView {
+    pub fn new() -> Self {
+        ConflictView {
+            visible: false,
+            file: None,
+            selected: 0,
+            note: None,
+        }
+    }
+
+    pub fn show(&mut self, file: ConflictFile) {
+        self.selected = 0;
+        self.note = None;
+        self.file = Some(file);
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.file = None;
+    }
+
+    pub fn file(&self) -> Option<&ConflictFile> {
+        self.file.as_ref()
+    }
+
+    /// Show `note` under the hunks until the next key
+    pub fn set_note(&mut self, note: String) {
+        self.note = Some(note);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<ConflictAction> {
+        let file = self.file.as_mut()?;
+        self.note = None;
+        let last = file.hunk_count().saturating_sub(1);
+        let choice = match key.code {
+            KeyCode::Char('o') | KeyCode::Char('O') => Some(HunkChoice::Ours),
+            KeyCode::Char('t') | KeyCode::Char('T') => Some(HunkChoice::Theirs),
+            KeyCode::Char('a') | KeyCode::Char('A') => Some(HunkChoice::Both),
+            _ => None,
+        };
+        if let Some(choice) = choice {
+            file.choose(self.selected, Some(choice));
+            // On to the next hunk, as in a merge tool
+            self.selected = (self.selected + 1).min(last);
+            return None;
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::Char('p') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Char('n') => {
+                self.selected = (self.selected + 1).min(last);
+                None
+            }
+            KeyCode::Char('u') | KeyCode::Char('U') => {
+                file.choose(self.selected, None);
+                None
+            }
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                let line = file.hunks().nth(self.selected).map_or(1, |hunk| hunk.line);
+                Some(ConflictAction::Edit(file.path.clone(), line))
+            }
+            KeyCode::Char('m') | KeyCode::Char('M') => Some(ConflictAction::MergeTool(file.path.clone())),
+            KeyCode::Char('w') | KeyCode::Char('W') | KeyCode::Enter => match file.undecided() {
+                0 => Some(ConflictAction::Write),
+                undecided => {
+                    self.note = Some(format!("{} hunk(s) still undecided: o, t or a picks a side", undecided));
+                    None
+                }
+            },
+            KeyCode::Esc | KeyCode::Char('q') => Some(ConflictAction::Close),
+            _ => None,
+        }
+    }
+
+    /// "Hunk 2/3 (1 undecided)" and a line per hunk with its choice
+    fn summary_lines(&self, file: &ConflictFile) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from(vec![
+            Span::styled(file.path.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+            Span::styled(
+                format!(
+                    "  hunk {}/{}, {} undecided",
+                    self.selected + 1,
+                    file.hunk_count(),
+                    file.undecided()
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ])];
+        let mut spans = vec![Span::raw("")];
+        for index in 0..file.hunk_count() {
+            let (text, color) = match file.choice(index) {
+                Some(HunkChoice::Ours) => ("ours", Color::Green),
+                Some(HunkChoice::Theirs) => ("theirs", Color::Blue),
+                Some(HunkChoice::Both) => ("both", Color::Magenta),
+                None => ("?", Color::Red),
+            };
+            let mut style = Style::default().fg(color);
+            if index == self.selected {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(format!(" {}:{} ", index + 1, text), style));
+        }
+        lines.push(Line::from(spans));
+        if let Some(ref note) = self.note {
+            lines.push(Line::styled(note.clone(), Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+
+    fn pane(title: String, lines: Option<&[String]>, chosen: bool, color: Color) -> Paragraph<'static> {
+        let body: Vec<Line> = match lines {
+            Some([]) => vec![Line::styled("(nothing)", Style::default().fg(Color::DarkGray))],
+            Some(lines) => lines.iter().map(|line| Line::from(line.clone())).collect(),
+            None => vec![Line::styled(
+                "(unknown: not in the markers or the index)",
+                Style::default().fg(Color::DarkGray),
+            )],
+        };
+        let mut border = Style::default().fg(color);
+        if chosen {
+            border = border.add_modifier(Modifier::BOLD);
+        }
+        let title = if chosen { format!(" {} ✓ ", title) } else { format!(" {} ", title) };
+        Paragraph::new(body)
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title(title).borders(Borders::ALL).border_style(border))
+    }
+
+    fn render_hunk(&self, frame: &mut Frame, area: Rect, hunk: &ConflictHunk, choice: Option<HunkChoice>) {
+        let panes = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
+            .split(area);
+        let keeps = |side: HunkChoice| choice == Some(side) || choice == Some(HunkChoice::Both);
+        let label = |name: &str, label: &str| {
+            if label.is_empty() { name.to_string() } else { format!("{} ({})", name, label) }
+        };
+        frame.render_widget(
+            Self::pane(label("o: ours", &hunk.ours_label), Some(&hunk.ours), keeps(HunkChoice::Ours), Color::Green),
+            panes[0],
+        );
+        frame.render_widget(Self::pane("base".to_string(), hunk.base.as_deref(), false, Color::DarkGray), panes[1]);
+        frame.render_widget(
+            Self::pane(
+                label("t: theirs", &hunk.theirs_label),
+                Some(&hunk.theirs),
+                keeps(HunkChoice::Theirs),
+                Color::Blue,
+            ),
+            panes[2],
+        );
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Resolve Conflicts ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " ↑↓: hunk  o/t: take ours/theirs  a: both  u: undo  e: editor  m: merge tool  w: write and stage  Esc: close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let Some(ref file) = self.file else {
+            return;
+        };
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(3)])
+            .split(Rect {
+                x: inner.x + 1,
+                y: inner.y,
+                width: inner.width.saturating_sub(2),
+                height: inner.height,
+            });
+        frame.render_widget(Paragraph::new(self.summary_lines(file)), rows[0]);
+        match file.hunks().nth(self.selected) {
+            Some(hunk) => self.render_hunk(frame, rows[1], hunk, file.choice(self.selected)),
+            None => frame.render_widget(
+                Paragraph::new("No conflict markers left in this file; w stages it."),
+                rows[1],
+            ),
+        }
+    }
+}
+
+impl Default for ConflictView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_choosing_moves_to_the_next_hunk() {
+        let text = "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> x\n<<<<<<< HEAD\nc\n=======\nd\n>>>>>>> x\n";
+        let mut view = ConflictView::new();
+        view.show(ConflictFile::parse("f.txt", text));
+
+        assert_eq!(view.handle_key(key(KeyCode::Char('t'))), None);
+        assert_eq!(view.selected, 1);
+        assert_eq!(view.handle_key(key(KeyCode::Char('e'))), Some(ConflictAction::Edit("f.txt".to_string(), 6)));
+        view.handle_key(key(KeyCode::Char('a')));
+        // The last hunk stays selected
+        assert_eq!(view.selected, 1);
+        assert_eq!(view.file().unwrap().resolved_text().unwrap(), "b\nc\nd\n");
+        assert_eq!(view.handle_key(key(KeyCode::Char('w'))), Some(ConflictAction::Write));
+        view.handle_key(key(KeyCode::Char('u')));
+        assert_eq!(view.file().unwrap().undecided(), 1);
+        assert_eq!(view.handle_key(key(KeyCode::Char('w'))), None);
+        assert!(view.note.is_some());
+        assert_eq!(view.handle_key(key(KeyCode::Char('m'))), Some(ConflictAction::MergeTool("f.txt".to_string())));
+        assert_eq!(view.handle_key(key(KeyCode::Esc)), Some(ConflictAction::Close));
+    }
+}
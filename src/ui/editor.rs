@@ -0,0 +1,216 @@
+use crate::security::secrets::hunk_start;
+use crate::ui::shell::with_suspended_terminal;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+
+/// Most files opened by a single "open in editor" action
+pub const MAX_EDITOR_FILES: usize = 20;
+
+/// A file to open, optionally at a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorTarget {
+    /// Path relative to the repository root
+    pub path: String,
+    pub line: Option<usize>,
+}
+
+impl EditorTarget {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            line: None,
+        }
+    }
+
+    pub fn with_line(mut self, line: Option<usize>) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+/// The editor to launch: `ui.editor`, then `$VISUAL`, then `$EDITOR`
+pub fn editor_program(configured: Option<&str>) -> Option<String> {
+    configured
+        .map(str::to_string)
+        .into_iter()
+        .chain(std::env::var("VISUAL").ok())
+        .chain(std::env::var("EDITOR").ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// How an editor expects to be told the line to jump to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineSyntax {
+    /// `+12 file` (vi, nano, emacs, kakoune, ...)
+    Plus,
+    /// `file:12` (sublime, zed, helix, micro)
+    Colon,
+    /// `--goto file:12` (VS Code and forks)
+    Goto,
+}
+
+fn line_syntax(program: &str) -> LineSyntax {
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    match name {
+        "code" | "code-insiders" | "codium" | "cursor" => LineSyntax::Goto,
+        "subl" | "zed" | "hx" | "helix" | "micro" => LineSyntax::Colon,
+        _ => LineSyntax::Plus,
+    }
+}
+
+/// Full argv opening `targets` in `editor`
+///
+/// `editor` may carry its own arguments (`code --wait`), split on whitespace
+/// like git does for `core.editor`.
+pub fn editor_args(editor: &str, targets: &[EditorTarget]) -> Vec<String> {
+    let mut args: Vec<String> = editor.split_whitespace().map(str::to_string).collect();
+    let syntax = args.first().map(|p| line_syntax(p)).unwrap_or(LineSyntax::Plus);
+
+    if syntax == LineSyntax::Goto && targets.iter().any(|t| t.line.is_some()) {
+        args.push("--goto".to_string());
+    }
+    for target in targets {
+        let path = as_file_arg(&target.path);
+        match (syntax, target.line) {
+            (LineSyntax::Plus, Some(line)) => {
+                args.push(format!("+{}", line));
+                args.push(path);
+            }
+            (_, Some(line)) => args.push(format!("{}:{}", path, line)),
+            (_, None) => args.push(path),
+        }
+    }
+    args
+}
+
+/// `path` spelled so no editor takes it for an option or a `+command`:
+/// `./-x` and `./+!sh` rather than `-x` and `+!sh`
+///
+/// `--` would do for vi, but `+<line>` arguments after it count as files too.
+fn as_file_arg(path: &str) -> String {
+    if path.starts_with(['-', '+']) {
+        format!("./{}", path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// Line of the first `<<<<<<<` conflict marker in a file
+pub fn first_conflict_line(path: &Path) -> Option<usize> {
+    let bytes = std::fs::read(path).ok()?;
+    String::from_utf8_lossy(&bytes)
+        .lines()
+        .position(|line| line.starts_with("<<<<<<<"))
+        .map(|index| index + 1)
+}
+
+/// Files in `git diff`/`git show` output, each at the start of its first hunk
+pub fn diff_targets(diff: &str) -> Vec<EditorTarget> {
+    let mut targets: Vec<EditorTarget> = Vec::new();
+    // Whether hunks belong to the last target (deleted files have nothing to open)
+    let mut in_target = false;
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ ") {
+            in_target = path.starts_with("b/");
+            if let Some(path) = path.strip_prefix("b/") {
+                targets.push(EditorTarget::new(path));
+            }
+        } else if in_target
+            && let Some(header) = line.strip_prefix("@@ ")
+            && let Some(target) = targets.last_mut()
+            && target.line.is_none()
+        {
+            target.line = hunk_start(header).map(|line| line.max(1));
+        }
+    }
+    targets.dedup_by(|a, b| a.path == b.path);
+    targets
+}
+
+/// Leave the TUI, edit `targets` in `dir`, then restore the TUI
+///
+/// Returns the editor's exit code; the caller should clear and redraw the
+/// screen and refresh repository state afterwards.
+pub fn run_suspended_editor(dir: &Path, editor: &str, targets: &[EditorTarget]) -> io::Result<i32> {
+    let args = editor_args(editor, targets);
+    let Some((program, rest)) = args.split_first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no editor configured"));
+    };
+
+    let status = with_suspended_terminal(|| Command::new(program).args(rest).current_dir(dir).status())??;
+    Ok(status.code().unwrap_or(-1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets() -> Vec<EditorTarget> {
+        vec![
+            EditorTarget::new("src/lib.rs").with_line(Some(12)),
+            EditorTarget::new("README.md"),
+        ]
+    }
+
+    #[test]
+    fn test_editor_program_prefers_config() {
+        assert_eq!(editor_program(Some("hx")), Some("hx".to_string()));
+    }
+
+    #[test]
+    fn test_editor_args_per_editor() {
+        assert_eq!(
+            editor_args("nvim", &targets()),
+            vec!["nvim", "+12", "src/lib.rs", "README.md"]
+        );
+        assert_eq!(
+            editor_args("/usr/bin/code --wait", &targets()),
+            vec!["/usr/bin/code", "--wait", "--goto", "src/lib.rs:12", "README.md"]
+        );
+        assert_eq!(
+            editor_args("subl -w", &targets()),
+            vec!["subl", "-w", "src/lib.rs:12", "README.md"]
+        );
+        assert_eq!(
+            editor_args("code", &[EditorTarget::new("a.rs")]),
+            vec!["code", "a.rs"]
+        );
+    }
+
+    #[test]
+    fn test_paths_never_read_as_options() {
+        let targets = [EditorTarget::new("+!touch x").with_line(Some(3)), EditorTarget::new("-R")];
+        assert_eq!(editor_args("vim", &targets), vec!["vim", "+3", "./+!touch x", "./-R"]);
+        assert_eq!(editor_args("code", &targets), vec!["code", "--goto", "./+!touch x:3", "./-R"]);
+    }
+
+    #[test]
+    fn test_diff_targets() {
+        let diff = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -3,2 +4,3 @@ fn x()\n \
+                    ctx\n@@ -20 +21 @@\n-x\n+y\n\
+                    diff --git a/gone.rs b/gone.rs\n--- a/gone.rs\n+++ /dev/null\n@@ -1 +0,0 @@\n-z\n\
+                    diff --git a/new.rs b/new.rs\n--- /dev/null\n+++ b/new.rs\n@@ -0,0 +1 @@\n+n\n";
+        assert_eq!(
+            diff_targets(diff),
+            vec![
+                EditorTarget::new("a.rs").with_line(Some(4)),
+                EditorTarget::new("new.rs").with_line(Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_first_conflict_line() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let file = temp.path().join("f.txt");
+        std::fs::write(&file, "a\nb\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> topic\n").unwrap();
+        assert_eq!(first_conflict_line(&file), Some(3));
+
+        std::fs::write(&file, "resolved\n").unwrap();
+        assert_eq!(first_conflict_line(&file), None);
+    }
+}
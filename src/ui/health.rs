@@ -0,0 +1,255 @@
+use crate::git::commit_checks::format_size;
+use crate::git::health::{RepoHealth, STALE_BRANCH_DAYS};
+use crate::git::maintenance::MaintenanceStatus;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Input that opens the repository health screen from the prompt
+pub const HEALTH_COMMAND: &str = ":health";
+
+/// Stale branches listed before "... and N more"
+const MAX_BRANCH_LINES: usize = 8;
+
+/// Full-screen maintenance overview of the repository
+pub struct HealthScreen {
+    pub visible: bool,
+    /// The summary, or why it couldn't be collected
+    content: Result<RepoHealth, String>,
+    suggestions: Vec<String>,
+}
+
+impl HealthScreen {
+    pub fn new() -> Self {
+        HealthScreen {
+            visible: false,
+            content: Ok(RepoHealth::default()),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Show `health`, or a message explaining why it is missing
+    pub fn show(&mut self, content: Result<RepoHealth, String>) {
+        self.suggestions = content.as_ref().map(RepoHealth::suggestions).unwrap_or_default();
+        self.content = content;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Suggested maintenance command by its 1-based number
+    pub fn suggestion(&self, number: usize) -> Option<&str> {
+        let index = number.checked_sub(1)?;
+        self.suggestions.get(index).map(String::as_str)
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let health = match &self.content {
+            Ok(health) => health,
+            Err(message) => return vec![Line::from(message.clone())],
+        };
+        let objects = &health.objects;
+        let mut lines = Vec::new();
+
+        lines.push(heading("Object store"));
+        lines.push(Line::from(format!("  Size: {}", format_size(health.size_bytes()))));
+        lines.push(Line::from(format!(
+            "  Loose objects: {} ({})",
+            objects.loose_objects,
+            format_size(objects.loose_bytes)
+        )));
+        lines.push(Line::from(format!(
+            "  Packs: {} ({})",
+            objects.packs,
+            format_size(objects.pack_bytes)
+        )));
+        if objects.garbage > 0 {
+            lines.push(warning(format!("  Garbage files in the object store: {}", objects.garbage)));
+        }
+        lines.push(Line::from(match health.days_since_repack {
+            Some(0) => "  Last repacked: today".to_string(),
+            Some(days) => format!("  Last repacked: {} days ago", days),
+            None => "  Last repacked: never (no packs yet)".to_string(),
+        }));
+        lines.push(Line::from(""));
+
+        lines.push(heading(&format!("Stale branches (no commits for {}+ days)", STALE_BRANCH_DAYS)));
+        if health.stale_branches.is_empty() {
+            lines.push(Line::from("  None"));
+        }
+        for branch in health.stale_branches.iter().take(MAX_BRANCH_LINES) {
+            lines.push(Line::from(format!("  {} ({} days)", branch.name, branch.days)));
+        }
+        if health.stale_branches.len() > MAX_BRANCH_LINES {
+            lines.push(Line::from(Span::styled(
+                format!("  ... and {} more", health.stale_branches.len() - MAX_BRANCH_LINES),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+
+        lines.push(heading("Largest files in history"));
+        if health.large_blobs.is_empty() {
+            lines.push(Line::from("  None"));
+        }
+        for blob in &health.large_blobs {
+            lines.push(Line::from(format!("  {:>9}  {}", format_size(blob.bytes), blob.path)));
+        }
+        lines.push(Line::from(""));
+
+        lines.push(heading("Dangling commits"));
+        lines.push(Line::from(match health.dangling_commits {
+            0 => "  None".to_string(),
+            n => format!("  {} unreachable commits, kept until gc prunes them", n),
+        }));
+        lines.push(Line::from(""));
+
+        lines.push(heading("Background maintenance"));
+        lines.extend(maintenance_lines(&health.maintenance));
+        lines.push(Line::from(""));
+
+        lines.push(heading("Suggested maintenance"));
+        if self.suggestions.is_empty() {
+            lines.push(Line::from("  Nothing to do"));
+        }
+        for (i, command) in self.suggestions.iter().enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  [{}] ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::styled(command.clone(), Style::default().fg(Color::Green)),
+            ]));
+        }
+
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Repository Health ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " 1-9: review a suggestion in the preview | Esc to close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+impl Default for HealthScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn maintenance_lines(status: &MaintenanceStatus) -> Vec<Line<'static>> {
+    if !status.supported {
+        return vec![Line::from("  Not available (needs git 2.30 or newer)")];
+    }
+    if !status.registered {
+        return vec![warning("  Not scheduled; `git maintenance start` runs hourly, daily and weekly tasks".to_string())];
+    }
+
+    let strategy = status.strategy.as_deref().unwrap_or("default");
+    let mut lines = vec![Line::from(format!("  Scheduled (strategy: {})", strategy))];
+    lines.push(Line::from(match (status.running, status.hours_since_run) {
+        (true, _) => "  Last run: running now".to_string(),
+        (false, Some(0)) => "  Last run: within the hour".to_string(),
+        (false, Some(hours)) if hours < 48 => format!("  Last run: {} hours ago", hours),
+        (false, Some(hours)) => format!("  Last run: {} days ago", hours / 24),
+        (false, None) => "  Last run: no task output yet".to_string(),
+    }));
+    lines
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))
+}
+
+fn warning(text: String) -> Line<'static> {
+    Line::from(Span::styled(text, Style::default().fg(Color::Red)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::health::{LargeBlob, ObjectCounts, StaleBranch};
+
+    fn text(screen: &HealthScreen) -> Vec<String> {
+        screen.lines().iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_shows_message_on_error() {
+        let mut screen = HealthScreen::new();
+        screen.show(Err("git failed".to_string()));
+        assert!(screen.visible);
+        assert_eq!(text(&screen), vec!["git failed"]);
+        assert_eq!(screen.suggestion(1), None);
+    }
+
+    #[test]
+    fn test_summarizes_health() {
+        let mut screen = HealthScreen::new();
+        screen.show(Ok(RepoHealth {
+            objects: ObjectCounts {
+                loose_objects: 5000,
+                loose_bytes: 2048,
+                ..Default::default()
+            },
+            stale_branches: vec![StaleBranch { name: "old".to_string(), days: 120 }],
+            large_blobs: vec![LargeBlob { path: "video.mp4".to_string(), bytes: 3 * 1024 * 1024 }],
+            dangling_commits: 2,
+            days_since_repack: None,
+            maintenance: MaintenanceStatus { supported: true, ..Default::default() },
+        }));
+
+        let lines = text(&screen);
+        assert!(lines.contains(&"  Loose objects: 5000 (2 KB)".to_string()));
+        assert!(lines.contains(&"  old (120 days)".to_string()));
+        assert!(lines.contains(&"     3.0 MB  video.mp4".to_string()));
+        assert!(lines.contains(&"  [1] git gc".to_string()));
+        assert_eq!(screen.suggestion(2), Some("git maintenance start"));
+        assert_eq!(screen.suggestion(3), Some("git branch -d old"));
+        assert_eq!(screen.suggestion(0), None);
+    }
+
+    #[test]
+    fn test_maintenance_status() {
+        let status = MaintenanceStatus {
+            supported: true,
+            registered: true,
+            strategy: Some("incremental".to_string()),
+            hours_since_run: Some(72),
+            running: false,
+        };
+        let lines: Vec<String> = maintenance_lines(&status).iter().map(|l| l.to_string()).collect();
+        assert_eq!(lines, vec!["  Scheduled (strategy: incremental)", "  Last run: 3 days ago"]);
+
+        let lines = maintenance_lines(&MaintenanceStatus::default());
+        assert_eq!(lines[0].to_string(), "  Not available (needs git 2.30 or newer)");
+    }
+}
@@ -38,7 +38,7 @@ impl HelpScreen {
             .direction(Direction::Vertical)
             .margin(2)
             .constraints([
-                Constraint::Length(12), // Keyboard shortcuts
+                Constraint::Length(17), // Keyboard shortcuts
                 Constraint::Length(1),  // Separator
                 Constraint::Length(8),  // Example queries
                 Constraint::Length(1),  // Separator
@@ -68,10 +68,34 @@ impl HelpScreen {
                 Span::styled("  Enter      ", Style::default().fg(Color::Cyan)),
                 Span::raw("Submit query / Execute command"),
             ]),
+            Line::from(vec![
+                Span::styled("  a; b; c    ", Style::default().fg(Color::Cyan)),
+                Span::raw("Several requests at once: review the plan, Enter runs each step"),
+            ]),
             Line::from(vec![
                 Span::styled("  e          ", Style::default().fg(Color::Cyan)),
                 Span::raw("Edit proposed command"),
             ]),
+            Line::from(vec![
+                Span::styled("  Tab        ", Style::default().fg(Color::Cyan)),
+                Span::raw("While editing: jump to the next <placeholder> (Shift+Tab: previous); typing replaces it"),
+            ]),
+            Line::from(vec![
+                Span::styled("  x          ", Style::default().fg(Color::Cyan)),
+                Span::raw("Explain proposed command and its flags"),
+            ]),
+            Line::from(vec![
+                Span::styled("  d          ", Style::default().fg(Color::Cyan)),
+                Span::raw("Dry-run proposed command on a sandbox copy"),
+            ]),
+            Line::from(vec![
+                Span::styled("  r          ", Style::default().fg(Color::Cyan)),
+                Span::raw("Re-translate when the repo changed since (in preview)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  u          ", Style::default().fg(Color::Cyan)),
+                Span::raw("Fetch full history first (shallow clone, when offered)"),
+            ]),
             Line::from(vec![
                 Span::styled("  t          ", Style::default().fg(Color::Cyan)),
                 Span::raw("Toggle raw/simplified error display"),
@@ -80,6 +104,82 @@ impl HelpScreen {
                 Span::styled("  r          ", Style::default().fg(Color::Cyan)),
                 Span::raw("Retry LLM connection (when offline)"),
             ]),
+            Line::from(vec![
+                Span::styled("  Tab        ", Style::default().fg(Color::Cyan)),
+                Span::raw("Accept live suggestion, else focus repository panel (/ filter, s stage, d diff, t diff.tool/merge.tool, i intent to add, h hide/unhide, e edit, c resolve conflicts, n note, v verify signature, ↑↓ scroll, Enter expand or preview a next action)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  ↑/↓        ", Style::default().fg(Color::Cyan)),
+                Span::raw("Recall earlier queries matching what is typed (behavior.query_history)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  !          ", Style::default().fg(Color::Cyan)),
+                Span::raw("Suspend to a shell in the repo (exit to return)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Ctrl+U     ", Style::default().fg(Color::Cyan)),
+                Span::raw("Publish current branch (git push -u)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Ctrl+F     ", Style::default().fg(Color::Cyan)),
+                Span::raw("Safe force push after a rebase (--force-with-lease)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  ←/→ 1/2    ", Style::default().fg(Color::Cyan)),
+                Span::raw("Choose between alternatives for a vague query (llm.alternatives)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  Ctrl+B     ", Style::default().fg(Color::Cyan)),
+                Span::raw("New branch: name it by git.branch_pattern, pick the base"),
+            ]),
+            Line::from(vec![
+                Span::styled("  h          ", Style::default().fg(Color::Cyan)),
+                Span::raw("After an unstaged git diff: discard single hunks (checkpoint saved first)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :tutorial  ", Style::default().fg(Color::Cyan)),
+                Span::raw("Guided tour in a throwaway demo repository"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :stats     ", Style::default().fg(Color::Cyan)),
+                Span::raw("Local usage stats (opt-in, behavior.local_stats)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :health    ", Style::default().fg(Color::Cyan)),
+                Span::raw("Repository size, stale branches and maintenance tips"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :logs      ", Style::default().fg(Color::Cyan)),
+                Span::raw("Recent diagnostics (refresh failures, rate limits)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :checkpoint ", Style::default().fg(Color::Cyan)),
+                Span::raw("Save (create \"name\") or list and restore checkpoints"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :ref       ", Style::default().fg(Color::Cyan)),
+                Span::raw("Offline git reference (:ref <search>)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :stack     ", Style::default().fg(Color::Cyan)),
+                Span::raw("Stacked branches and a plan to restack them"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :suggest   ", Style::default().fg(Color::Cyan)),
+                Span::raw("Live suggestions on/off (llm.live_suggest)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :trust     ", Style::default().fg(Color::Cyan)),
+                Span::raw("Trust this repository to run commands that modify it"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :identity  ", Style::default().fg(Color::Cyan)),
+                Span::raw("Switch author identity (:identity <label>)"),
+            ]),
+            Line::from(vec![
+                Span::styled("  :forget-corrections ", Style::default().fg(Color::Cyan)),
+                Span::raw("Wipe edits remembered for the LLM"),
+            ]),
         ];
 
         let shortcuts_widget = Paragraph::new(shortcuts)
@@ -114,6 +214,10 @@ impl HelpScreen {
                 Span::styled("  • ", Style::default().fg(Color::Green)),
                 Span::raw("\"show me the last 10 commits\""),
             ]),
+            Line::from(vec![
+                Span::styled("  • ", Style::default().fg(Color::Green)),
+                Span::raw("\"split my last commit\" (guided, with undo)"),
+            ]),
         ];
 
         let examples_widget = Paragraph::new(examples)
@@ -142,7 +246,7 @@ impl HelpScreen {
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Press '?' or Esc to close this help screen",
+                Span::styled("Press '/' to search the offline git reference (:ref), '?' or Esc to close",
                     Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
             ]),
         ];
@@ -0,0 +1,225 @@
+use crate::git::DiffHunk;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Hunks listed at once; the list scrolls with the selection
+const VISIBLE_HUNKS: usize = 8;
+
+/// What the user asked for in the hunk view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkAction {
+    Close,
+    /// Discard this hunk, confirmed
+    Discard(DiffHunk),
+}
+
+/// Full-screen list of unstaged hunks, to throw individual ones away
+pub struct HunkView {
+    pub visible: bool,
+    /// Paths the diff was limited to, to reload the same hunks
+    paths: Vec<String>,
+    hunks: Vec<DiffHunk>,
+    selected: usize,
+    /// Waiting for `y` to discard the selected hunk
+    confirming: bool,
+    note: Option<String>,
+}
+
+impl HunkView {
+    pub fn new() -> Self {
+        HunkView {
+            visible: false,
+            paths: Vec::new(),
+            hunks: Vec::new(),
+            selected: 0,
+            confirming: false,
+            note: None,
+        }
+    }
+
+    /// Open on the `hunks` of `paths`, keeping the selection where it was when reloading
+    pub fn show(&mut self, paths: Vec<String>, hunks: Vec<DiffHunk>) {
+        self.selected = self.selected.min(hunks.len().saturating_sub(1));
+        self.paths = paths;
+        self.hunks = hunks;
+        self.confirming = false;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.hunks.clear();
+        self.selected = 0;
+        self.note = None;
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Show `note` above the hunk until the next key
+    pub fn set_note(&mut self, note: String) {
+        self.note = Some(note);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<HunkAction> {
+        self.note = None;
+        if self.confirming {
+            self.confirming = false;
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => self.hunks.get(self.selected).cloned().map(HunkAction::Discard),
+                _ => None,
+            };
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.hunks.len().saturating_sub(1));
+                None
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') if !self.hunks.is_empty() => {
+                self.confirming = true;
+                None
+            }
+            KeyCode::Esc | KeyCode::Char('q') => Some(HunkAction::Close),
+            _ => None,
+        }
+    }
+
+    fn list_lines(&self) -> Vec<Line<'static>> {
+        let first = self.selected.saturating_sub(VISIBLE_HUNKS - 1);
+        self.hunks
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(VISIBLE_HUNKS)
+            .map(|(index, hunk)| {
+                let (added, removed) = hunk.counts();
+                let mut style = Style::default().fg(Color::White);
+                if index == self.selected {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                Line::from(vec![
+                    Span::raw(if index == self.selected { "▶ " } else { "  " }),
+                    Span::styled(format!("{} {}", hunk.path, hunk.range()), style),
+                    Span::styled(format!("  +{}", added), Style::default().fg(Color::Green)),
+                    Span::styled(format!(" -{}", removed), Style::default().fg(Color::Red)),
+                ])
+            })
+            .collect()
+    }
+
+    fn hunk_lines(&self, hunk: &DiffHunk) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+        if self.confirming {
+            let warning = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+            lines.push(Line::styled(
+                format!("⚠ Discard this hunk of {}? The change is deleted from the working tree.", hunk.path),
+                warning,
+            ));
+            lines.push(Line::styled(
+                "  A checkpoint is saved first (:checkpoint restores it).  y: discard  any key: keep",
+                warning,
+            ));
+            lines.push(Line::from(""));
+        } else if let Some(ref note) = self.note {
+            lines.push(Line::styled(note.clone(), Style::default().fg(Color::Yellow)));
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::styled(hunk.range().to_string(), Style::default().fg(Color::Cyan)));
+        lines.extend(hunk.lines().map(|line| {
+            let color = match line.chars().next() {
+                Some('+') => Color::Green,
+                Some('-') => Color::Red,
+                _ => Color::White,
+            };
+            Line::styled(line.to_string(), Style::default().fg(color))
+        }));
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Discard Unstaged Hunks ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " ↑↓: hunk  d: discard (asks first)  Esc: close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red))
+            .style(Style::default().bg(Color::Black));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        let inner = Rect {
+            x: inner.x + 1,
+            width: inner.width.saturating_sub(2),
+            ..inner
+        };
+
+        let Some(hunk) = self.hunks.get(self.selected) else {
+            let text = self.note.clone().unwrap_or_else(|| "No unstaged hunks left.".to_string());
+            frame.render_widget(Paragraph::new(text), inner);
+            return;
+        };
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(VISIBLE_HUNKS.min(self.hunks.len()) as u16 + 1), Constraint::Min(3)])
+            .split(inner);
+        frame.render_widget(Paragraph::new(self.list_lines()), rows[0]);
+        frame.render_widget(
+            Paragraph::new(self.hunk_lines(hunk))
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::TOP).border_style(Style::default().fg(Color::DarkGray))),
+            rows[1],
+        );
+    }
+}
+
+impl Default for HunkView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parse_hunks;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_discard_needs_confirming() {
+        let hunks = parse_hunks(
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-a\n+b\n@@ -5 +5 @@\n-c\n+d\n",
+        );
+        let mut view = HunkView::new();
+        view.show(Vec::new(), hunks.clone());
+
+        view.handle_key(key(KeyCode::Down));
+        assert_eq!(view.handle_key(key(KeyCode::Char('d'))), None);
+        // Any other key keeps the hunk
+        assert_eq!(view.handle_key(key(KeyCode::Char('n'))), None);
+        assert_eq!(view.handle_key(key(KeyCode::Char('y'))), None);
+        view.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(view.handle_key(key(KeyCode::Char('y'))), Some(HunkAction::Discard(hunks[1].clone())));
+
+        // Reloading with fewer hunks keeps the selection in range
+        view.show(Vec::new(), hunks[..1].to_vec());
+        assert_eq!(view.selected, 0);
+        assert_eq!(view.handle_key(key(KeyCode::Esc)), Some(HunkAction::Close));
+    }
+}
@@ -2,7 +2,8 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
@@ -19,6 +20,12 @@ pub struct InputWidget {
     cursor_position: usize,
     mode: InputMode,
     active: bool,
+    /// Live suggestion shown greyed-out beneath the input
+    suggestion: Option<String>,
+    /// Asks for something other than a query, replacing the mode's prompt
+    prompt: Option<String>,
+    /// Validation of a typed `git ...` command: the violation when refused
+    check: Option<Result<(), String>>,
 }
 
 impl InputWidget {
@@ -28,9 +35,33 @@ impl InputWidget {
             cursor_position: 0,
             mode,
             active: false,
+            suggestion: None,
+            prompt: None,
+            check: None,
         }
     }
 
+    /// Color the border by whether the typed command passes validation, and
+    /// show the violation when it doesn't; `None` for anything but a git command
+    pub fn set_check(&mut self, check: Option<Result<(), String>>) {
+        self.check = check;
+    }
+
+    /// Ask for something other than a query; `None` restores the mode's prompt
+    pub fn set_prompt(&mut self, prompt: Option<&str>) {
+        self.prompt = prompt.map(str::to_string);
+    }
+
+    /// Show (or hide) a suggested command beneath the input
+    pub fn set_suggestion(&mut self, suggestion: Option<&str>) {
+        self.suggestion = suggestion.map(str::to_string);
+    }
+
+    /// Rows needed, including the suggestion and violation lines when there are any
+    pub fn desired_height(&self) -> u16 {
+        3 + u16::from(self.suggestion.is_some()) + u16::from(matches!(self.check, Some(Err(_))))
+    }
+
     /// Set whether the input widget is active (focused)
     pub fn set_active(&mut self, active: bool) {
         self.active = active;
@@ -104,6 +135,26 @@ impl InputWidget {
         &self.input
     }
 
+    /// Replace the input, with the cursor at the end
+    pub fn set_input(&mut self, text: &str) {
+        self.input = text.to_string();
+        self.cursor_position = self.input.len();
+    }
+
+    /// Insert pasted text at the cursor
+    ///
+    /// Line breaks become `; ` so a pasted checklist stays one multi-step query.
+    pub fn paste(&mut self, text: &str) {
+        let text = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.input.insert_str(self.cursor_position, &text);
+        self.cursor_position += text.len();
+    }
+
     /// Clear the input
     pub fn clear(&mut self) {
         self.input.clear();
@@ -112,6 +163,9 @@ impl InputWidget {
 
     /// Get prompt text based on mode
     fn get_prompt(&self) -> &str {
+        if let Some(ref prompt) = self.prompt {
+            return prompt;
+        }
         match self.mode {
             InputMode::Online => "Natural language or git command:",
             InputMode::Offline => "Enter git command:",
@@ -139,15 +193,31 @@ impl Widget for &InputWidget {
             Style::default().fg(Color::DarkGray)
         };
 
+        let border = match self.check {
+            _ if !self.active => Color::DarkGray,
+            Some(Ok(())) => Color::Green,
+            Some(Err(_)) => Color::Red,
+            None => Color::Yellow,
+        };
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(if self.active {
-                Style::default().fg(Color::Yellow)
-            } else {
-                Style::default().fg(Color::DarkGray)
-            });
+            .border_style(Style::default().fg(border));
+
+        let mut lines = vec![Line::from(display_text)];
+        if let Some(ref suggestion) = self.suggestion {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  ↳ {}", suggestion),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                ),
+                Span::styled("  Tab: accept", Style::default().fg(Color::DarkGray)),
+            ]));
+        }
+        if let Some(Err(ref violation)) = self.check {
+            lines.push(Line::styled(format!("  ✗ {}", violation), Style::default().fg(Color::Red)));
+        }
 
-        let paragraph = Paragraph::new(display_text)
+        let paragraph = Paragraph::new(lines)
             .style(style)
             .block(block);
 
@@ -223,12 +293,54 @@ mod tests {
         assert_eq!(widget.cursor_position, 0);
     }
 
+    #[test]
+    fn test_set_input_moves_cursor_to_end() {
+        let mut widget = InputWidget::new(InputMode::Online);
+        widget.set_input("show log");
+        assert_eq!(widget.get_input(), "show log");
+        assert_eq!(widget.cursor_position, 8);
+    }
+
+    #[test]
+    fn test_paste_joins_lines() {
+        let mut widget = InputWidget::new(InputMode::Online);
+        widget.paste("- stage everything\n\n- commit as wip\r\n- push\n");
+        assert_eq!(widget.get_input(), "- stage everything; - commit as wip; - push");
+        assert_eq!(widget.cursor_position, widget.get_input().len());
+    }
+
+    #[test]
+    fn test_violation_line_and_border() {
+        let mut widget = InputWidget::new(InputMode::Online);
+        widget.set_active(true);
+        widget.set_input("git frobnicate");
+        widget.set_check(Some(Err("Git subcommand not allowed: frobnicate".to_string())));
+        assert_eq!(widget.desired_height(), 4);
+
+        let area = Rect::new(0, 0, 60, 4);
+        let mut buf = Buffer::empty(area);
+        (&widget).render(area, &mut buf);
+        let violation: String = (0..60).map(|x| buf[(x, 2)].symbol().to_string()).collect();
+        assert!(violation.contains("✗ Git subcommand not allowed: frobnicate"));
+        assert_eq!(buf[(0, 0)].fg, Color::Red);
+
+        widget.set_check(Some(Ok(())));
+        assert_eq!(widget.desired_height(), 3);
+        (&widget).render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].fg, Color::Green);
+    }
+
     #[test]
     fn test_prompt_changes_with_mode() {
         let online = InputWidget::new(InputMode::Online);
         assert_eq!(online.get_prompt(), "Natural language or git command:");
 
-        let offline = InputWidget::new(InputMode::Offline);
+        let mut offline = InputWidget::new(InputMode::Offline);
+        assert_eq!(offline.get_prompt(), "Enter git command:");
+
+        offline.set_prompt(Some("URL:"));
+        assert_eq!(offline.get_prompt(), "URL:");
+        offline.set_prompt(None);
         assert_eq!(offline.get_prompt(), "Enter git command:");
     }
 }
@@ -0,0 +1,148 @@
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+/// Smallest terminal the main screen is drawn in; below it a warning is shown
+pub const MIN_WIDTH: u16 = 50;
+pub const MIN_HEIGHT: u16 = 14;
+
+/// Below this height the title bar drops its border to a single line
+const COMPACT_HEIGHT: u16 = 24;
+
+/// The repository panel keeps at least this many rows...
+const MIN_PANEL_ROWS: u16 = 5;
+/// ...and at least this share of the rows between title and status bar
+const MIN_PANEL_PERCENT: u16 = 35;
+
+/// Input box height, the least the bottom panel gets
+const MIN_BOTTOM_ROWS: u16 = 3;
+
+/// Areas of the main screen: title, repository panel, bottom panel
+/// (input, preview, output, ...) and status bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MainLayout {
+    pub title: Rect,
+    pub panel: Rect,
+    pub bottom: Rect,
+    pub status: Rect,
+    /// The title is a single unbordered line
+    pub compact: bool,
+}
+
+impl MainLayout {
+    /// Split `area`, giving the bottom panel up to `bottom_desired` rows while
+    /// the repository panel keeps its minimum share
+    pub fn new(area: Rect, bottom_desired: u16) -> Self {
+        let compact = area.height < COMPACT_HEIGHT;
+        let title_rows = if compact { 1 } else { 3 };
+        let rows = area.height.saturating_sub(title_rows + 1);
+
+        let panel_min = MIN_PANEL_ROWS.max(rows * MIN_PANEL_PERCENT / 100);
+        let bottom_rows = bottom_desired
+            .min(rows.saturating_sub(panel_min))
+            .max(MIN_BOTTOM_ROWS)
+            .min(rows);
+        let panel_rows = rows - bottom_rows;
+
+        let row = |y: u16, height: u16| Rect { x: area.x, y, width: area.width, height };
+        let title = row(area.y, title_rows.min(area.height));
+        let panel = row(title.bottom(), panel_rows);
+        let bottom = row(panel.bottom(), bottom_rows);
+        let status = row(bottom.bottom(), area.bottom().saturating_sub(bottom.bottom()));
+
+        Self { title, panel, bottom, status, compact }
+    }
+}
+
+/// Whether `area` is too small to draw the main screen
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+/// Ask for a bigger terminal instead of drawing overlapping panels
+pub fn render_too_small(frame: &mut Frame, area: Rect) {
+    let lines = vec![
+        Line::styled("Terminal too small", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        Line::from(format!("{}×{}, need at least {}×{}", area.width, area.height, MIN_WIDTH, MIN_HEIGHT)),
+        Line::styled("Resize the window to continue", Style::default().fg(Color::DarkGray)),
+    ];
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    let centered = Rect {
+        y: area.y + top,
+        height: area.height - top,
+        ..area
+    };
+    frame.render_widget(
+        Paragraph::new(lines).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        centered,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(width: u16, height: u16) -> Rect {
+        Rect::new(0, 0, width, height)
+    }
+
+    fn assert_tiles(layout: &MainLayout, area: Rect) {
+        assert_eq!(layout.title.y, area.y);
+        assert_eq!(layout.panel.y, layout.title.bottom());
+        assert_eq!(layout.bottom.y, layout.panel.bottom());
+        assert_eq!(layout.status.y, layout.bottom.bottom());
+        assert_eq!(layout.status.bottom(), area.bottom());
+    }
+
+    #[test]
+    fn test_standard_terminal() {
+        let layout = MainLayout::new(area(80, 30), 3);
+        assert!(!layout.compact);
+        assert_eq!(layout.title.height, 3);
+        assert_eq!(layout.bottom.height, 3);
+        assert_eq!(layout.panel.height, 23);
+        assert_tiles(&layout, area(80, 30));
+    }
+
+    #[test]
+    fn test_bottom_capped_to_keep_panel() {
+        // 20 rows between title and status; the panel keeps 7
+        let layout = MainLayout::new(area(80, 24), 100);
+        assert_eq!(layout.panel.height, 7);
+        assert_eq!(layout.bottom.height, 13);
+        assert_tiles(&layout, area(80, 24));
+    }
+
+    #[test]
+    fn test_tall_terminal_grows_output() {
+        let layout = MainLayout::new(area(120, 80), 40);
+        assert_eq!(layout.bottom.height, 40);
+        assert_eq!(layout.panel.height, 36);
+
+        // Short output leaves the rest to the panel
+        let layout = MainLayout::new(area(120, 80), 8);
+        assert_eq!(layout.bottom.height, 8);
+        assert_eq!(layout.panel.height, 68);
+    }
+
+    #[test]
+    fn test_compact_terminal() {
+        let layout = MainLayout::new(area(60, MIN_HEIGHT), 15);
+        assert!(layout.compact);
+        assert_eq!(layout.title.height, 1);
+        assert_eq!(layout.panel.height, MIN_PANEL_ROWS);
+        assert_eq!(layout.bottom.height, 7);
+        assert_tiles(&layout, area(60, MIN_HEIGHT));
+    }
+
+    #[test]
+    fn test_is_too_small() {
+        assert!(is_too_small(area(40, 30)));
+        assert!(is_too_small(area(80, 10)));
+        assert!(!is_too_small(area(MIN_WIDTH, MIN_HEIGHT)));
+    }
+}
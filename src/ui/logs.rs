@@ -0,0 +1,217 @@
+use crate::telemetry::LogEvent;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use tracing::Level;
+
+/// Input that opens the diagnostics screen from the prompt
+pub const LOGS_COMMAND: &str = ":logs";
+
+/// Full-screen view of gitalky's own recent diagnostics events
+pub struct LogScreen {
+    pub visible: bool,
+    events: Vec<LogEvent>,
+    /// Most verbose level shown
+    min_level: Level,
+    /// Lines scrolled up from the newest event
+    scroll: usize,
+}
+
+impl LogScreen {
+    pub fn new() -> Self {
+        LogScreen {
+            visible: false,
+            events: Vec::new(),
+            min_level: Level::INFO,
+            scroll: 0,
+        }
+    }
+
+    /// Show `events` (oldest first), scrolled to the newest
+    pub fn show(&mut self, events: Vec<LogEvent>) {
+        self.events = events;
+        self.scroll = 0;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Level filter and scroll keys; false when the key should close the screen
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let level = match key.code {
+            KeyCode::Char('e') => Level::ERROR,
+            KeyCode::Char('w') => Level::WARN,
+            KeyCode::Char('i') => Level::INFO,
+            KeyCode::Char('d') => Level::DEBUG,
+            KeyCode::Char('t') => Level::TRACE,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.scroll = (self.scroll + 1).min(self.shown().len().saturating_sub(1));
+                return true;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll = self.scroll.saturating_sub(1);
+                return true;
+            }
+            _ => return false,
+        };
+        self.min_level = level;
+        self.scroll = 0;
+        true
+    }
+
+    pub fn min_level(&self) -> Level {
+        self.min_level
+    }
+
+    /// Events at `min_level` or more severe, oldest first
+    fn shown(&self) -> Vec<&LogEvent> {
+        // More verbose levels compare greater
+        self.events.iter().filter(|event| event.level <= self.min_level).collect()
+    }
+
+    fn lines(&self, height: usize) -> Vec<Line<'static>> {
+        let shown = self.shown();
+        if shown.is_empty() {
+            return vec![Line::from(format!(
+                "No {} events or worse this session. Press d or t for more detail.",
+                self.min_level.as_str().to_lowercase()
+            ))];
+        }
+
+        let end = shown.len() - self.scroll.min(shown.len() - 1);
+        let start = end.saturating_sub(height);
+        shown[start..end]
+            .iter()
+            .map(|event| {
+                Line::from(vec![
+                    Span::styled(event.time.format("%H:%M:%S ").to_string(), Style::default().fg(Color::DarkGray)),
+                    Span::styled(format!("{:<5} ", event.level.as_str()), level_style(event.level)),
+                    Span::styled(
+                        format!("{} ", event.target.trim_start_matches("gitalky::")),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(event.message.clone()),
+                ])
+            })
+            .collect()
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(format!(" Diagnostics ({} and worse) ", self.min_level.as_str().to_lowercase()))
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " e/w/i/d/t: level  ↑↓: scroll  Esc: close  Full log: ~/.config/gitalky/gitalky.log ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 1,
+            y: inner.y,
+            width: inner.width.saturating_sub(2),
+            height: inner.height,
+        };
+        let paragraph = Paragraph::new(self.lines(margin.height as usize)).style(Style::default().fg(Color::White));
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+impl Default for LogScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn level_style(level: Level) -> Style {
+    Style::default().fg(match level {
+        Level::ERROR => Color::Red,
+        Level::WARN => Color::Yellow,
+        Level::INFO => Color::Green,
+        _ => Color::DarkGray,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use crossterm::event::KeyModifiers;
+
+    fn event(level: Level, message: &str) -> LogEvent {
+        LogEvent {
+            time: Local::now(),
+            level,
+            target: "gitalky::ui::app".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    fn messages(screen: &LogScreen, height: usize) -> Vec<String> {
+        screen
+            .lines(height)
+            .iter()
+            .map(|line| line.spans.last().unwrap().content.to_string())
+            .collect()
+    }
+
+    fn press(screen: &mut LogScreen, code: KeyCode) -> bool {
+        screen.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_level_filter() {
+        let mut screen = LogScreen::new();
+        screen.show(vec![
+            event(Level::DEBUG, "git finished"),
+            event(Level::WARN, "failed to refresh repository state"),
+            event(Level::ERROR, "boom"),
+        ]);
+        assert_eq!(messages(&screen, 10), vec!["failed to refresh repository state", "boom"]);
+
+        assert!(press(&mut screen, KeyCode::Char('e')));
+        assert_eq!(messages(&screen, 10), vec!["boom"]);
+        assert!(press(&mut screen, KeyCode::Char('d')));
+        assert_eq!(messages(&screen, 10).len(), 3);
+
+        assert!(!press(&mut screen, KeyCode::Esc));
+    }
+
+    #[test]
+    fn test_scroll_from_newest() {
+        let mut screen = LogScreen::new();
+        screen.show((0..5).map(|i| event(Level::WARN, &i.to_string())).collect());
+        assert_eq!(messages(&screen, 2), vec!["3", "4"]);
+
+        press(&mut screen, KeyCode::Up);
+        assert_eq!(messages(&screen, 2), vec!["2", "3"]);
+        for _ in 0..10 {
+            press(&mut screen, KeyCode::Up);
+        }
+        assert_eq!(messages(&screen, 2), vec!["0"]);
+        press(&mut screen, KeyCode::Down);
+        assert_eq!(messages(&screen, 2), vec!["0", "1"]);
+    }
+
+    #[test]
+    fn test_empty_message() {
+        let mut screen = LogScreen::new();
+        screen.show(vec![event(Level::DEBUG, "git finished")]);
+        assert_eq!(
+            screen.lines(5)[0].to_string(),
+            "No info events or worse this session. Press d or t for more detail."
+        );
+    }
+}
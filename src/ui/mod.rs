@@ -1,13 +1,55 @@
+pub mod alternatives_picker;
 pub mod app;
+pub mod branch_dialog;
+pub mod checkpoints;
 pub mod command_preview;
+pub mod conflict_view;
+pub mod editor;
+pub mod health;
 pub mod help;
+pub mod hunk_view;
 pub mod input;
+pub mod layout;
+pub mod logs;
+pub mod note_editor;
 pub mod output;
+pub mod plan_preview;
+pub mod pull_chooser;
+pub mod reference;
+pub mod repo_gone;
 pub mod repo_panel;
+pub mod sandbox_view;
+pub mod shell;
+pub mod split_panel;
+pub mod stack_view;
+pub mod stats;
+pub mod translation_wait;
+pub mod tutorial;
 
+pub use alternatives_picker::{AlternativesAction, AlternativesPicker};
 pub use app::App;
-pub use command_preview::CommandPreview;
+pub use branch_dialog::{BranchDialog, BranchDialogAction};
+pub use checkpoints::{CHECKPOINT_COMMAND, CheckpointAction, CheckpointCommand, CheckpointPanel};
+pub use command_preview::{CommandPreview, EditFeedback};
+pub use conflict_view::{ConflictAction, ConflictView};
+pub use editor::{EditorTarget, run_suspended_editor};
+pub use health::{HEALTH_COMMAND, HealthScreen};
 pub use help::HelpScreen;
+pub use hunk_view::{HunkAction, HunkView};
 pub use input::{InputMode, InputWidget};
+pub use layout::MainLayout;
+pub use logs::{LOGS_COMMAND, LogScreen};
+pub use note_editor::{NoteAction, NoteEditor};
 pub use output::{CommandOutput, OutputDisplay};
-pub use repo_panel::RepositoryPanel;
+pub use plan_preview::{PlanAction, PlanPreview, PlanStep, StepCheck};
+pub use pull_chooser::{PullChoice, PullChooser};
+pub use reference::{REFERENCE_COMMAND, ReferenceBrowser, parse_reference_command};
+pub use repo_gone::{RepoGoneAction, RepoGoneScreen};
+pub use repo_panel::{PanelCache, RepositoryPanel};
+pub use sandbox_view::SandboxView;
+pub use shell::{ShellSession, run_suspended_shell, with_suspended_terminal};
+pub use split_panel::{SplitAction, SplitFile, SplitPanel};
+pub use stack_view::{STACK_COMMAND, StackView};
+pub use stats::{STATS_COMMAND, StatsScreen};
+pub use translation_wait::{TranslationWait, WaitChoice};
+pub use tutorial::{TUTORIAL_COMMAND, Tutorial, TutorialStep};
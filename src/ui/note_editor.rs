@@ -0,0 +1,180 @@
+use crate::git::{CommitEntry, quote_arg};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// What the user asked for in the note editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteAction {
+    /// Review this `git notes` command
+    Save(String),
+    /// Closed without changes
+    Cancel,
+}
+
+/// Editor for the note attached to one commit
+pub struct NoteEditor {
+    commit: String,
+    subject: String,
+    /// The note when the editor opened
+    original: Option<String>,
+    text: String,
+}
+
+impl NoteEditor {
+    pub fn new(commit: &CommitEntry, note: Option<String>) -> Self {
+        Self {
+            commit: commit.hash.clone(),
+            subject: commit.message.clone(),
+            text: note.clone().unwrap_or_default(),
+            original: note,
+        }
+    }
+
+    pub fn short_hash(&self) -> &str {
+        &self.commit[..self.commit.len().min(7)]
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.text.push_str(&text.replace("\r\n", "\n"));
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<NoteAction> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('s') if ctrl => Some(self.command().map_or(NoteAction::Cancel, NoteAction::Save)),
+            KeyCode::Char(c) if !ctrl => {
+                self.text.push(c);
+                None
+            }
+            KeyCode::Enter => {
+                self.text.push('\n');
+                None
+            }
+            KeyCode::Backspace => {
+                self.text.pop();
+                None
+            }
+            KeyCode::Esc => Some(NoteAction::Cancel),
+            _ => None,
+        }
+    }
+
+    /// The command that stores the edited note; `None` when nothing changed
+    ///
+    /// Paragraphs (separated by blank lines) become one `-m` each, with
+    /// their lines joined by spaces. Clearing the note removes it.
+    pub fn command(&self) -> Option<String> {
+        let edited = paragraphs(&self.text);
+        if self.original.as_deref().map(paragraphs).as_ref() == Some(&edited) {
+            return None;
+        }
+        if edited.is_empty() {
+            return self.original.as_ref().map(|_| format!("git notes remove {}", self.commit));
+        }
+        let messages: Vec<String> = edited.iter().map(|p| format!("-m {}", quote_arg(p))).collect();
+        Some(format!("git notes add -f {} {}", messages.join(" "), self.commit))
+    }
+
+    /// Height for the note text plus borders and the heading
+    pub fn desired_height(&self) -> u16 {
+        (self.text.split('\n').count() + 4).clamp(6, 14) as u16
+    }
+}
+
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|paragraph| paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+impl Widget for &NoteEditor {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(self.short_hash().to_string(), Style::default().fg(Color::Yellow)),
+                Span::raw(" "),
+                Span::raw(self.subject.clone()),
+            ]),
+            Line::from(""),
+        ];
+        let mut text_lines: Vec<Line> = self.text.split('\n').map(|line| Line::from(line.to_string())).collect();
+        if let Some(last) = text_lines.last_mut() {
+            last.spans.push(Span::styled("▊", Style::default().fg(Color::Yellow)));
+        }
+        lines.extend(text_lines);
+
+        let title = if self.original.is_some() { "Edit note" } else { "Add note" };
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Magenta)),
+            )
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit() -> CommitEntry {
+        CommitEntry {
+            hash: "0123456789abcdef".to_string(),
+            message: "Fix login".to_string(),
+            has_note: false,
+            signature: None,
+        }
+    }
+
+    fn type_text(editor: &mut NoteEditor, text: &str) {
+        for c in text.chars() {
+            let code = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+            editor.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    fn save(editor: &mut NoteEditor) -> Option<NoteAction> {
+        editor.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL))
+    }
+
+    #[test]
+    fn test_add_note() {
+        let mut editor = NoteEditor::new(&commit(), None);
+        assert_eq!(save(&mut editor), Some(NoteAction::Cancel));
+
+        type_text(&mut editor, "Deployed to\nstaging\n\nReviewed by Ana");
+        assert_eq!(
+            save(&mut editor),
+            Some(NoteAction::Save(
+                "git notes add -f -m 'Deployed to staging' -m 'Reviewed by Ana' 0123456789abcdef".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_edit_and_remove_note() {
+        let mut editor = NoteEditor::new(&commit(), Some("Deployed".to_string()));
+        assert_eq!(editor.command(), None);
+        // Reflowed lines of a note written elsewhere don't count as an edit
+        assert_eq!(NoteEditor::new(&commit(), Some("a\nb\n".to_string())).command(), None);
+
+        type_text(&mut editor, "!");
+        assert_eq!(editor.command().as_deref(), Some("git notes add -f -m 'Deployed!' 0123456789abcdef"));
+
+        for _ in 0.."Deployed!".len() {
+            editor.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        }
+        assert_eq!(editor.command().as_deref(), Some("git notes remove 0123456789abcdef"));
+        assert_eq!(editor.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)), Some(NoteAction::Cancel));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::git::executor::{is_binary, split_command};
+use crate::ui::editor::{EditorTarget, diff_targets};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
@@ -5,6 +7,9 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget, Wrap},
 };
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 /// Command execution result
 #[derive(Debug, Clone)]
@@ -13,6 +18,12 @@ pub struct CommandOutput {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
+    /// User-friendly explanation shown separately from raw stderr
+    pub advice: Option<String>,
+    /// Suggested fix commands the user can load into the preview
+    pub recovery_commands: Vec<String>,
+    /// Raw stdout when it wasn't valid UTF-8 text
+    pub raw_stdout: Option<Vec<u8>>,
 }
 
 impl CommandOutput {
@@ -22,130 +33,373 @@ impl CommandOutput {
             stdout,
             stderr,
             exit_code,
+            advice: None,
+            recovery_commands: Vec::new(),
+            raw_stdout: None,
         }
     }
 
+    /// Keep stdout's bytes when the text form is lossy or binary
+    pub fn with_raw_stdout(mut self, raw_stdout: Option<Vec<u8>>) -> Self {
+        self.raw_stdout = raw_stdout;
+        self
+    }
+
+    /// Raw stdout when it is binary data rather than text
+    pub fn binary_stdout(&self) -> Option<&[u8]> {
+        self.raw_stdout.as_deref().filter(|bytes| is_binary(bytes))
+    }
+
+    /// Attach translated advice to display below the raw output
+    pub fn with_advice(mut self, advice: String) -> Self {
+        self.advice = Some(advice);
+        self
+    }
+
+    /// Attach suggested fix commands (at most 9 are selectable)
+    pub fn with_recovery_commands(mut self, commands: Vec<String>) -> Self {
+        self.recovery_commands = commands;
+        self.recovery_commands.truncate(9);
+        self
+    }
+
     pub fn is_success(&self) -> bool {
         self.exit_code == 0
     }
 }
 
-/// Some comment
+/// What "save output" writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveContent {
+    /// Stdout exactly as the command printed it
+    Stdout,
+    /// Command, stdout, stderr and exit code
+    Transcript,
+}
+
+/// Path prompt for writing the displayed output to a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavePrompt {
+    /// Relative paths are taken from the repository root
+    pub path: String,
+    pub content: SaveContent,
+}
+
+impl SavePrompt {
+    pub fn toggle_content(&mut self) {
+        self.content = match self.content {
+            SaveContent::Stdout => SaveContent::Transcript,
+            SaveContent::Transcript => SaveContent::Stdout,
+        };
+    }
+}
+
+/// `gitalky-<subcommand>-<timestamp>.txt` (`.bin` for binary stdout)
+pub fn default_save_name(output: &CommandOutput) -> String {
+    let subcommand = split_command(&output.command)
+        .ok()
+        .and_then(|words| words.into_iter().find(|w| w != "git" && !w.starts_with('-')))
+        .filter(|sub| sub.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or_else(|| "output".to_string());
+    let extension = if output.binary_stdout().is_some() { "bin" } else { "txt" };
+    format!(
+        "gitalky-{}-{}.{}",
+        subcommand,
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        extension
+    )
+}
+
+/// Bytes written for `content`
+pub fn save_bytes(output: &CommandOutput, content: SaveContent) -> Vec<u8> {
+    match content {
+        SaveContent::Stdout => output
+            .raw_stdout
+            .clone()
+            .unwrap_or_else(|| output.stdout.clone().into_bytes()),
+        SaveContent::Transcript => {
+            let mut text = format!("$ {}\n", output.command);
+            text.push_str(&output.stdout);
+            if !output.stderr.is_empty() {
+                text.push_str(&output.stderr);
+            }
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&format!("[exit code {}]\n", output.exit_code));
+            text.into_bytes()
+        }
+    }
+}
+
 /// Output display widget for showing command execution results
+///
+/// Display lines are built once when the output is set rather than on every
+/// frame, since command output can be large and rarely changes.
 pub struct OutputDisplay {
     output: Option<CommandOutput>,
+    lines: Vec<Line<'static>>,
+    /// Files shown by diff output, for "open in editor"
+    editor_targets: Vec<EditorTarget>,
     scroll: usize,
+    /// Open while asking where to save the output
+    save_prompt: Option<SavePrompt>,
 }
 
 impl OutputDisplay {
     pub fn new() -> Self {
         Self {
             output: None,
+            lines: Vec::new(),
+            editor_targets: Vec::new(),
             scroll: 0,
+            save_prompt: None,
         }
     }
 
     /// Set the output to display
     pub fn set_output(&mut self, output: CommandOutput) {
+        self.lines = Self::build_lines(&output);
+        self.editor_targets = diff_targets(&output.stdout);
         self.output = Some(output);
         self.scroll = 0;
+        self.save_prompt = None;
     }
 
     /// Clear the output
     pub fn clear(&mut self) {
         self.output = None;
+        self.lines.clear();
+        self.editor_targets.clear();
         self.scroll = 0;
+        self.save_prompt = None;
     }
 
-    /// Scroll up
-    pub fn scroll_up(&mut self) {
-        if self.scroll > 0 {
-            self.scroll -= 1;
+    /// Ask where to save the output, defaulting to a file in the repository root
+    pub fn start_save(&mut self) {
+        if let Some(ref output) = self.output {
+            self.save_prompt = Some(SavePrompt {
+                path: default_save_name(output),
+                content: SaveContent::Stdout,
+            });
         }
     }
 
-    /// Scroll down
-    pub fn scroll_down(&mut self) {
-        self.scroll += 1;
+    pub fn save_prompt(&self) -> Option<&SavePrompt> {
+        self.save_prompt.as_ref()
     }
-}
 
-impl Default for OutputDisplay {
-    fn default() -> Self {
-        Self::new()
+    pub fn save_prompt_mut(&mut self) -> Option<&mut SavePrompt> {
+        self.save_prompt.as_mut()
     }
-}
 
-impl Widget for &OutputDisplay {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        if let Some(ref output) = self.output {
-            let mut lines = Vec::new();
+    pub fn cancel_save(&mut self) {
+        self.save_prompt = None;
+    }
 
-            // Header with status
-            let status_style = if output.is_success() {
+    /// Write the output as the prompt says, never replacing an existing file
+    ///
+    /// The prompt stays open when writing fails so the path can be fixed.
+    pub fn save(&mut self, repo_root: &Path) -> io::Result<PathBuf> {
+        let (Some(output), Some(prompt)) = (self.output.as_ref(), self.save_prompt.as_ref()) else {
+            return Err(io::Error::new(io::ErrorKind::NotFound, "no output to save"));
+        };
+        let path = repo_root.join(prompt.path.trim());
+        let mut file = fs::OpenOptions::new().write(true).create_new(true).open(&path)?;
+        file.write_all(&save_bytes(output, prompt.content))?;
+        self.save_prompt = None;
+        Ok(path)
+    }
+
+    /// The displayed command
+    pub fn command(&self) -> Option<&str> {
+        self.output.as_ref().map(|output| output.command.as_str())
+    }
+
+    /// Whether the displayed command succeeded
+    pub fn is_success(&self) -> bool {
+        self.output.as_ref().is_some_and(|output| output.is_success())
+    }
+
+    /// Files in the displayed diff, each at its first changed line
+    pub fn editor_targets(&self) -> &[EditorTarget] {
+        &self.editor_targets
+    }
+
+    /// Get the suggested fix command for a 1-based menu number
+    pub fn recovery_command(&self, number: usize) -> Option<&str> {
+        let output = self.output.as_ref()?;
+        let index = number.checked_sub(1)?;
+        output.recovery_commands.get(index).map(|c| c.as_str())
+    }
+
+    /// Raw stdout of the displayed command when it is binary
+    pub fn binary_stdout(&self) -> Option<&[u8]> {
+        self.output.as_ref()?.binary_stdout()
+    }
+
+    /// Check if the current output offers suggested fixes
+    pub fn has_recovery_commands(&self) -> bool {
+        self.output
+            .as_ref()
+            .is_some_and(|output| !output.recovery_commands.is_empty())
+    }
+
+    /// Build the display lines for a command output
+    fn build_lines(output: &CommandOutput) -> Vec<Line<'static>> {
+        let mut lines = Vec::new();
+
+        // Header with status
+        let status_style = if output.is_success() {
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        };
+
+        let status_text = if output.is_success() {
+            "✓ Success".to_string()
+        } else {
+            format!("✗ Failed (exit code: {})", output.exit_code)
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled("Command: ", Style::default().fg(Color::Cyan)),
+            Span::styled(output.command.clone(), Style::default().fg(Color::White)),
+        ]));
+
+        lines.push(Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Cyan)),
+            Span::styled(status_text, status_style),
+        ]));
+
+        lines.push(Line::from(""));
+
+        // Binary stdout would only dump noise into the terminal
+        if let Some(bytes) = output.binary_stdout() {
+            lines.push(Line::from(vec![Span::styled(
+                format!("Binary output ({} bytes) — press s to save to file", bytes.len()),
+                Style::default().fg(Color::Yellow),
+            )]));
+            lines.push(Line::from(""));
+        } else if !output.stdout.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "Output:",
                 Style::default()
                     .fg(Color::Green)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-            };
-
-            let status_text = if output.is_success() {
-                "✓ Success".to_string()
-            } else {
-                format!("✗ Failed (exit code: {})", output.exit_code)
-            };
-
-            lines.push(Line::from(vec![
-                Span::styled("Command: ", Style::default().fg(Color::Cyan)),
-                Span::styled(&output.command, Style::default().fg(Color::White)),
-            ]));
-
-            lines.push(Line::from(vec![
-                Span::styled("Status: ", Style::default().fg(Color::Cyan)),
-                Span::styled(status_text, status_style),
-            ]));
+                    .add_modifier(Modifier::BOLD),
+            )]));
+            if output.raw_stdout.is_some() {
+                lines.push(Line::from(vec![Span::styled(
+                    "(not valid UTF-8; undecodable bytes are shown as �)",
+                    Style::default().fg(Color::DarkGray),
+                )]));
+            }
+
+            // `Notes:` / `Notes (ref):` blocks of show and log output stand out
+            let mut in_notes = false;
+            for line in output.stdout.lines() {
+                let is_notes_header = line.starts_with("Notes:") || line.starts_with("Notes (");
+                in_notes = is_notes_header || (in_notes && line.starts_with("    "));
+                let style = match (is_notes_header, in_notes) {
+                    (true, _) => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                    (false, true) => Style::default().fg(Color::Magenta),
+                    _ => Style::default().fg(Color::White),
+                };
+                lines.push(Line::from(vec![Span::styled(line.to_string(), style)]));
+            }
 
             lines.push(Line::from(""));
+        }
 
-            // Stdout
-            if !output.stdout.is_empty() {
+        // Stderr
+        if !output.stderr.is_empty() {
+            lines.push(Line::from(vec![Span::styled(
+                "Errors:",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )]));
+
+            for line in output.stderr.lines() {
                 lines.push(Line::from(vec![Span::styled(
-                    "Output:",
-                    Style::default()
-                        .fg(Color::Green)
-                        .add_modifier(Modifier::BOLD),
+                    line.to_string(),
+                    Style::default().fg(Color::Red),
                 )]));
-
-                for line in output.stdout.lines() {
-                    lines.push(Line::from(vec![Span::styled(
-                        line,
-                        Style::default().fg(Color::White),
-                    )]));
-                }
-
-                lines.push(Line::from(""));
             }
+        }
 
-            // Stderr
-            if !output.stderr.is_empty() {
+        // Translated advice
+        if let Some(ref advice) = output.advice {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                "What this means:",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )]));
+
+            for line in advice.lines() {
                 lines.push(Line::from(vec![Span::styled(
-                    "Errors:",
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    line.to_string(),
+                    Style::default().fg(Color::Yellow),
                 )]));
+            }
+        }
 
-                for line in output.stderr.lines() {
-                    lines.push(Line::from(vec![Span::styled(
-                        line,
-                        Style::default().fg(Color::Red),
-                    )]));
-                }
+        // Suggested fixes
+        if !output.recovery_commands.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![Span::styled(
+                "Suggested fixes (press number to review):",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )]));
+
+            for (i, command) in output.recovery_commands.iter().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  [{}] ", i + 1), Style::default().fg(Color::Cyan)),
+                    Span::styled(command.clone(), Style::default().fg(Color::Green)),
+                ]));
             }
+        }
+
+        lines
+    }
 
+    /// Height that shows all the output, including borders
+    ///
+    /// The layout caps it so the repository panel stays visible.
+    pub fn desired_height(&self) -> u16 {
+        (self.lines.len() + 2).clamp(5, u16::MAX as usize) as u16
+    }
+
+    /// Scroll up
+    pub fn scroll_up(&mut self) {
+        if self.scroll > 0 {
+            self.scroll -= 1;
+        }
+    }
+
+    /// Scroll down
+    pub fn scroll_down(&mut self) {
+        self.scroll += 1;
+    }
+}
+
+impl Default for OutputDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Widget for &OutputDisplay {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if let Some(ref output) = self.output {
             // Apply scrolling by skipping lines
-            let visible_lines: Vec<_> = lines.into_iter().skip(self.scroll).collect();
+            let visible_lines: Vec<_> = self.lines.iter().skip(self.scroll).cloned().collect();
 
-            let block = Block::default()
+            let mut block = Block::default()
                 .borders(Borders::ALL)
                 .border_style(if output.is_success() {
                     Style::default().fg(Color::Green)
@@ -153,6 +407,17 @@ impl Widget for &OutputDisplay {
                     Style::default().fg(Color::Red)
                 })
                 .title("Command Output");
+            if let Some(ref prompt) = self.save_prompt {
+                let what = match prompt.content {
+                    SaveContent::Stdout => "output",
+                    SaveContent::Transcript => "transcript",
+                };
+                block = block.title_bottom(Line::from(vec![
+                    Span::styled(format!(" Save {} to: ", what), Style::default().fg(Color::Yellow)),
+                    Span::raw(prompt.path.clone()),
+                    Span::styled("▊ ", Style::default().fg(Color::Yellow)),
+                ]));
+            }
 
             let paragraph = Paragraph::new(visible_lines)
                 .block(block)
@@ -229,9 +494,184 @@ mod tests {
 
         display.clear();
         assert!(display.output.is_none());
+        assert!(display.lines.is_empty());
         assert_eq!(display.scroll, 0);
     }
 
+    #[test]
+    fn test_binary_output_replaced_by_notice() {
+        let mut display = OutputDisplay::new();
+        let output = CommandOutput::new("git show :a.png".to_string(), "\u{fffd}PNG\0".to_string(), String::new(), 0)
+            .with_raw_stdout(Some(vec![0x89, b'P', b'N', b'G', 0]));
+        display.set_output(output);
+
+        let text: Vec<String> = display.lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.contains(&"Binary output (5 bytes) — press s to save to file".to_string()));
+        assert!(!text.iter().any(|line| line.contains("PNG")));
+        assert_eq!(display.binary_stdout().map(<[u8]>::len), Some(5));
+
+        let lossy = CommandOutput::new("git show :a.txt".to_string(), "caf\u{fffd}".to_string(), String::new(), 0)
+            .with_raw_stdout(Some(b"caf\xe9".to_vec()));
+        display.set_output(lossy);
+        let text: Vec<String> = display.lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.contains(&"caf\u{fffd}".to_string()));
+        assert_eq!(display.binary_stdout(), None);
+    }
+
+    #[test]
+    fn test_save_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut display = OutputDisplay::new();
+        display.set_output(CommandOutput::new(
+            "git log --oneline".to_string(),
+            "abc123 Fix\n".to_string(),
+            "warning: shallow\n".to_string(),
+            0,
+        ));
+
+        display.start_save();
+        let prompt = display.save_prompt_mut().unwrap();
+        assert!(prompt.path.starts_with("gitalky-log-") && prompt.path.ends_with(".txt"));
+        prompt.path = "log.txt".to_string();
+        let path = display.save(dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abc123 Fix\n");
+        assert!(display.save_prompt().is_none());
+
+        // Existing files are never replaced; the prompt stays open
+        display.start_save();
+        let prompt = display.save_prompt_mut().unwrap();
+        prompt.path = "log.txt".to_string();
+        prompt.toggle_content();
+        let err = display.save(dir.path()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(display.save_prompt().is_some());
+
+        display.save_prompt_mut().unwrap().path = "transcript.txt".to_string();
+        let path = display.save(dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "$ git log --oneline\nabc123 Fix\nwarning: shallow\n[exit code 0]\n"
+        );
+    }
+
+    #[test]
+    fn test_save_binary_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut display = OutputDisplay::new();
+        let bytes = vec![0x89, b'P', b'N', b'G', 0];
+        display.set_output(
+            CommandOutput::new("git show HEAD:logo.png".to_string(), String::new(), String::new(), 0)
+                .with_raw_stdout(Some(bytes.clone())),
+        );
+        display.start_save();
+        assert!(display.save_prompt().unwrap().path.starts_with("gitalky-show-"));
+        assert!(display.save_prompt().unwrap().path.ends_with(".bin"));
+        let path = display.save(dir.path()).unwrap();
+        assert_eq!(fs::read(path).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_desired_height_follows_content() {
+        let mut display = OutputDisplay::new();
+        assert_eq!(display.desired_height(), 5);
+
+        let stdout: Vec<String> = (0..40).map(|i| format!("line {}", i)).collect();
+        display.set_output(CommandOutput::new("git log".to_string(), stdout.join("\n"), String::new(), 0));
+        // Command, status, blank, heading, 40 lines, blank, borders
+        assert_eq!(display.desired_height(), 47);
+    }
+
+    #[test]
+    fn test_lines_built_on_set_output() {
+        let mut display = OutputDisplay::new();
+        display.set_output(CommandOutput::new(
+            "git log".to_string(),
+            "first\nsecond".to_string(),
+            "warning".to_string(),
+            0,
+        ));
+
+        let text: Vec<String> = display.lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.iter().any(|line| line == "first"));
+        assert!(text.iter().any(|line| line == "second"));
+        assert!(text.iter().any(|line| line == "warning"));
+    }
+
+    #[test]
+    fn test_notes_highlighted() {
+        let mut display = OutputDisplay::new();
+        display.set_output(CommandOutput::new(
+            "git show".to_string(),
+            "commit abc\n\n    Fix login\n\nNotes:\n    Deployed to staging\n\ndiff --git a/a b/a\n".to_string(),
+            String::new(),
+            0,
+        ));
+        let color = |text: &str| {
+            display.lines.iter().find(|line| line.to_string() == text).unwrap().spans[0].style.fg
+        };
+        assert_eq!(color("Notes:"), Some(Color::Magenta));
+        assert_eq!(color("    Deployed to staging"), Some(Color::Magenta));
+        assert_eq!(color("    Fix login"), Some(Color::White));
+        assert_eq!(color("diff --git a/a b/a"), Some(Color::White));
+    }
+
+    #[test]
+    fn test_diff_output_offers_editor_targets() {
+        let mut display = OutputDisplay::new();
+        display.set_output(CommandOutput::new(
+            "git diff".to_string(),
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +7,2 @@\n+x\n".to_string(),
+            String::new(),
+            0,
+        ));
+        assert_eq!(display.editor_targets(), [EditorTarget::new("a.rs").with_line(Some(7))]);
+
+        display.clear();
+        assert!(display.editor_targets().is_empty());
+    }
+
+    #[test]
+    fn test_recovery_commands_are_numbered() {
+        let mut display = OutputDisplay::new();
+        assert!(!display.has_recovery_commands());
+
+        display.set_output(
+            CommandOutput::new("git rebase".to_string(), String::new(), "error".to_string(), 1)
+                .with_recovery_commands(vec![
+                    "git rebase --continue".to_string(),
+                    "git rebase --abort".to_string(),
+                ]),
+        );
+
+        assert!(display.has_recovery_commands());
+        assert_eq!(display.recovery_command(1), Some("git rebase --continue"));
+        assert_eq!(display.recovery_command(2), Some("git rebase --abort"));
+        assert_eq!(display.recovery_command(0), None);
+        assert_eq!(display.recovery_command(3), None);
+
+        let text: Vec<String> = display.lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.iter().any(|line| line == "  [2] git rebase --abort"));
+    }
+
+    #[test]
+    fn test_advice_shown_separately() {
+        let mut display = OutputDisplay::new();
+        display.set_output(
+            CommandOutput::new(
+                "git push".to_string(),
+                String::new(),
+                "fatal: no upstream".to_string(),
+                128,
+            )
+            .with_advice("No remote branch is configured for tracking.".to_string()),
+        );
+
+        let text: Vec<String> = display.lines.iter().map(|line| line.to_string()).collect();
+        assert!(text.iter().any(|line| line == "fatal: no upstream"));
+        assert!(text.iter().any(|line| line == "What this means:"));
+        assert!(text.iter().any(|line| line == "No remote branch is configured for tracking."));
+    }
+
     #[test]
     fn test_scroll() {
         let mut display = OutputDisplay::new();
@@ -0,0 +1,362 @@
+use crate::llm::{describe_placeholders, placeholders};
+use crate::security::{CommandOrigin, CommandValidator, DangerousOp};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// Most steps shown at once; the list scrolls with the selection
+const VISIBLE_STEPS: usize = 8;
+
+/// What the user asked for in the plan preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanAction {
+    None,
+    Run,
+    Cancel,
+}
+
+/// Validation result for one step of a plan
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepCheck {
+    Valid,
+    Dangerous(DangerousOp),
+    /// Refused by the validator or a hook
+    Rejected(String),
+    /// The LLM gave no usable command
+    Untranslated(String),
+}
+
+/// One intent of a multi-step query and the command proposed for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanStep {
+    pub intent: String,
+    pub command: Option<String>,
+    pub check: StepCheck,
+}
+
+impl PlanStep {
+    pub fn translated(intent: String, command: String) -> Self {
+        Self {
+            intent,
+            command: Some(command),
+            check: StepCheck::Valid,
+        }
+    }
+
+    pub fn failed(intent: String, reason: String) -> Self {
+        Self {
+            intent,
+            command: None,
+            check: StepCheck::Untranslated(reason),
+        }
+    }
+
+    pub fn is_runnable(&self) -> bool {
+        matches!(self.check, StepCheck::Valid | StepCheck::Dangerous(_))
+    }
+}
+
+/// Combined preview of the commands for a pasted checklist
+///
+/// Every step is validated up front. Running executes the steps in order,
+/// each through the usual preview checks, and stops at the first failure.
+pub struct PlanPreview {
+    steps: Vec<PlanStep>,
+    selected: usize,
+    /// Steps handed out for execution so far
+    started: usize,
+    origin: CommandOrigin,
+    /// Shown above the steps, e.g. what can go wrong halfway
+    warning: Option<String>,
+}
+
+impl PlanPreview {
+    pub fn new(steps: Vec<PlanStep>) -> Self {
+        Self {
+            steps,
+            selected: 0,
+            started: 0,
+            origin: CommandOrigin::Llm,
+            warning: None,
+        }
+    }
+
+    /// Record where the steps came from (translated ones by default)
+    pub fn with_origin(mut self, origin: CommandOrigin) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warning = Some(warning.into());
+        self
+    }
+
+    pub fn origin(&self) -> CommandOrigin {
+        self.origin
+    }
+
+    pub fn steps(&self) -> &[PlanStep] {
+        &self.steps
+    }
+
+    /// Check every translated step as the executor will
+    pub fn validate(&mut self, validator: &CommandValidator) {
+        let origin = self.origin;
+        for step in &mut self.steps {
+            let Some(ref command) = step.command else {
+                continue;
+            };
+            if matches!(step.check, StepCheck::Rejected(_)) {
+                continue;
+            }
+            if !placeholders(command).is_empty() {
+                step.check = StepCheck::Rejected(format!(
+                    "Leaves {} to fill in; ask for this step on its own to fill them",
+                    describe_placeholders(command)
+                ));
+                continue;
+            }
+            step.check = match validator.validate_with_origin(command, origin) {
+                Ok(validated) => match validated.danger_type {
+                    Some(op) => StepCheck::Dangerous(op),
+                    None => StepCheck::Valid,
+                },
+                Err(e) => StepCheck::Rejected(e.to_string()),
+            };
+        }
+    }
+
+    /// Why the plan can't run yet, if it can't
+    pub fn blocker(&self) -> Option<String> {
+        if self.steps.is_empty() {
+            return Some("The plan has no steps left".to_string());
+        }
+        self.steps
+            .iter()
+            .position(|step| !step.is_runnable())
+            .map(|i| format!("Step {} can't run; remove it with D or cancel with Esc", i + 1))
+    }
+
+    /// Whether steps are being executed
+    pub fn is_running(&self) -> bool {
+        self.started > 0
+    }
+
+    /// Hand out the next command to execute with its 1-based step number
+    pub fn next_step(&mut self) -> Option<(usize, &PlanStep)> {
+        let index = self.started;
+        let step = self.steps.get(index)?;
+        self.started += 1;
+        Some((index + 1, step))
+    }
+
+    /// Number of the step handed out last, 0 before running
+    pub fn current_step(&self) -> usize {
+        self.started
+    }
+
+    /// Whether steps remain after the ones handed out
+    pub fn has_next(&self) -> bool {
+        self.started < self.steps.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Height the preview wants in the layout
+    pub fn desired_height(&self) -> u16 {
+        // Borders, header, blank line and one line per step (two when it has a problem)
+        let warning_lines = self.warning.as_ref().map_or(0, |w| w.lines().count() + 1);
+        let step_lines: usize = self
+            .steps
+            .iter()
+            .take(VISIBLE_STEPS)
+            .map(|step| if step.is_runnable() { 1 } else { 2 })
+            .sum();
+        (step_lines + warning_lines + 4).clamp(6, 20) as u16
+    }
+
+    /// Translate a key press into an action
+    pub fn handle_key(&mut self, key: KeyEvent) -> PlanAction {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.steps.len() => {
+                self.selected += 1;
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Delete if !self.steps.is_empty() => {
+                self.steps.remove(self.selected);
+                self.selected = self.selected.min(self.steps.len().saturating_sub(1));
+            }
+            KeyCode::Enter => return PlanAction::Run,
+            KeyCode::Esc => return PlanAction::Cancel,
+            _ => {}
+        }
+        PlanAction::None
+    }
+}
+
+impl Widget for &PlanPreview {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("Proposed Plan ({} steps)", self.steps.len()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        if let Some(ref warning) = self.warning {
+            for text in warning.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("⚠ {}", text),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+
+        // Keep the selection visible
+        let start = self.selected.saturating_sub(VISIBLE_STEPS - 1);
+        for (i, step) in self.steps.iter().enumerate().skip(start).take(VISIBLE_STEPS) {
+            let marker = if i == self.selected { "> " } else { "  " };
+            let (symbol, color) = match step.check {
+                StepCheck::Valid => ("✓", Color::Green),
+                StepCheck::Dangerous(_) => ("⚠", Color::Yellow),
+                StepCheck::Rejected(_) | StepCheck::Untranslated(_) => ("✗", Color::Red),
+            };
+            let command = step.command.as_deref().unwrap_or("(no command)");
+            let confirm = match step.check {
+                StepCheck::Dangerous(ref op) => format!(" [{:?}: asks for confirmation]", op),
+                _ => String::new(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}. {} ", i + 1, symbol), Style::default().fg(color)),
+                Span::styled(command, Style::default().fg(color)),
+                Span::styled(confirm, Style::default().fg(Color::Yellow)),
+                Span::styled(format!("  ← {}", step.intent), Style::default().fg(Color::DarkGray)),
+            ]));
+
+            if let StepCheck::Rejected(ref reason) | StepCheck::Untranslated(ref reason) = step.check {
+                lines.push(Line::from(Span::styled(
+                    format!("       {}", reason),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("Command Plan")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn plan() -> PlanPreview {
+        PlanPreview::new(vec![
+            PlanStep::translated("stage everything".to_string(), "git add -A".to_string()),
+            PlanStep::translated("commit as wip".to_string(), "git commit -m wip".to_string()),
+            PlanStep::translated("push".to_string(), "git push".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_validate_marks_each_step() {
+        let mut plan = PlanPreview::new(vec![
+            PlanStep::translated("stage".to_string(), "git add -A".to_string()),
+            PlanStep::translated("undo".to_string(), "git reset --hard".to_string()),
+            PlanStep::translated("list".to_string(), "git status | head".to_string()),
+            PlanStep::failed("???".to_string(), "LLM returned invalid output".to_string()),
+        ]);
+        plan.validate(&CommandValidator::new());
+
+        assert_eq!(plan.steps()[0].check, StepCheck::Valid);
+        assert_eq!(plan.steps()[1].check, StepCheck::Dangerous(DangerousOp::HardReset));
+        assert!(matches!(plan.steps()[2].check, StepCheck::Rejected(_)));
+        assert!(matches!(plan.steps()[3].check, StepCheck::Untranslated(_)));
+        assert!(plan.blocker().unwrap().starts_with("Step 3"));
+    }
+
+    #[test]
+    fn test_remove_blocking_step() {
+        let mut plan = PlanPreview::new(vec![
+            PlanStep::translated("stage".to_string(), "git add -A".to_string()),
+            PlanStep::failed("???".to_string(), "no command".to_string()),
+        ]);
+        assert!(plan.blocker().is_some());
+
+        assert_eq!(plan.handle_key(key(KeyCode::Down)), PlanAction::None);
+        assert_eq!(plan.handle_key(key(KeyCode::Char('d'))), PlanAction::None);
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan.blocker(), None);
+
+        plan.handle_key(key(KeyCode::Char('d')));
+        assert!(plan.is_empty());
+        assert!(plan.blocker().is_some());
+    }
+
+    #[test]
+    fn test_steps_are_handed_out_in_order() {
+        let mut plan = plan();
+        assert!(!plan.is_running());
+        assert_eq!(plan.handle_key(key(KeyCode::Enter)), PlanAction::Run);
+
+        let (number, step) = plan.next_step().unwrap();
+        assert_eq!((number, step.command.as_deref()), (1, Some("git add -A")));
+        assert!(plan.is_running());
+        assert_eq!(plan.next_step().unwrap().0, 2);
+        assert!(plan.has_next());
+        assert_eq!(plan.next_step().unwrap().0, 3);
+        assert!(!plan.has_next());
+        assert!(plan.next_step().is_none());
+    }
+
+    #[test]
+    fn test_origin_and_warning() {
+        let mut plan = PlanPreview::new(vec![PlanStep::translated(
+            "stash".to_string(),
+            "git stash push --include-untracked".to_string(),
+        )])
+        .with_origin(CommandOrigin::Suggestion)
+        .with_warning("stash pop can conflict");
+        plan.validate(&CommandValidator::new());
+        assert_eq!(plan.origin(), CommandOrigin::Suggestion);
+        assert_eq!(plan.steps()[0].check, StepCheck::Valid);
+        assert_eq!(plan.desired_height(), 7);
+    }
+
+    #[test]
+    fn test_desired_height() {
+        let mut plan = plan();
+        assert_eq!(plan.desired_height(), 7);
+        plan.steps[0].check = StepCheck::Rejected("pipes are not allowed".to_string());
+        assert_eq!(plan.desired_height(), 8);
+        assert_eq!(plan.handle_key(key(KeyCode::Esc)), PlanAction::Cancel);
+    }
+}
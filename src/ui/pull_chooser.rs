@@ -0,0 +1,161 @@
+use crate::git::{PullStrategy, UpstreamInfo};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// What the user picked in the chooser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullChoice {
+    /// Pull with this strategy, saving it to the repository's git config if `persist`
+    Strategy { strategy: PullStrategy, persist: bool },
+    Cancel,
+}
+
+/// Asks how to pull when the branch and its upstream have both moved on
+///
+/// Shown instead of running a `git pull` that names no strategy, since git
+/// would otherwise stop with "You have divergent branches".
+pub struct PullChooser {
+    command: String,
+    branch: String,
+    upstream: UpstreamInfo,
+    /// Also write the choice to `.git/config`
+    persist: bool,
+}
+
+impl PullChooser {
+    pub fn new(command: &str, branch: &str, upstream: &UpstreamInfo) -> Self {
+        PullChooser {
+            command: command.to_string(),
+            branch: branch.to_string(),
+            upstream: upstream.clone(),
+            persist: false,
+        }
+    }
+
+    /// The pull command waiting for a strategy
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<PullChoice> {
+        match key.code {
+            KeyCode::Esc => Some(PullChoice::Cancel),
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                self.persist = !self.persist;
+                None
+            }
+            KeyCode::Char(c) => {
+                let index = c.to_digit(10)?.checked_sub(1)? as usize;
+                let strategy = *PullStrategy::ALL.get(index)?;
+                Some(PullChoice::Strategy { strategy, persist: self.persist })
+            }
+            _ => None,
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let key = |key: String| Span::styled(format!("  {:<3}", key), Style::default().fg(Color::Cyan));
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!(
+                    "'{}' and {} have diverged: {} local and {} upstream commit(s).",
+                    self.branch, self.upstream.remote_branch, self.upstream.ahead, self.upstream.behind
+                ),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(format!("How should `{}` combine them?", self.command)),
+            Line::from(""),
+        ];
+        for (i, strategy) in PullStrategy::ALL.iter().enumerate() {
+            lines.push(Line::from(vec![
+                key((i + 1).to_string()),
+                Span::styled(strategy.name(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::styled(format!("  {}", strategy.flag()), Style::default().fg(Color::DarkGray)),
+            ]));
+            lines.push(Line::from(format!("     {}", strategy.effect())));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            key("p".to_string()),
+            Span::raw(format!(
+                "[{}] Save the choice for this repository (git config pull.rebase / pull.ff)",
+                if self.persist { "x" } else { " " }
+            )),
+        ]));
+        lines.push(Line::from(vec![key("Esc".to_string()), Span::raw("Back to the preview")]));
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Pull Strategy ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn chooser() -> PullChooser {
+        let upstream = UpstreamInfo {
+            remote_branch: "origin/main".to_string(),
+            ahead: 2,
+            behind: 3,
+        };
+        PullChooser::new("git pull", "main", &upstream)
+    }
+
+    fn press(chooser: &mut PullChooser, code: KeyCode) -> Option<PullChoice> {
+        chooser.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_keys() {
+        let mut chooser = chooser();
+        assert_eq!(
+            press(&mut chooser, KeyCode::Char('2')),
+            Some(PullChoice::Strategy { strategy: PullStrategy::Rebase, persist: false })
+        );
+        assert_eq!(press(&mut chooser, KeyCode::Char('p')), None);
+        assert_eq!(
+            press(&mut chooser, KeyCode::Char('3')),
+            Some(PullChoice::Strategy { strategy: PullStrategy::FastForwardOnly, persist: true })
+        );
+        assert_eq!(press(&mut chooser, KeyCode::Char('4')), None);
+        assert_eq!(press(&mut chooser, KeyCode::Enter), None);
+        assert_eq!(press(&mut chooser, KeyCode::Esc), Some(PullChoice::Cancel));
+    }
+
+    #[test]
+    fn test_lines() {
+        let text: Vec<String> = chooser().lines().iter().map(|line| line.to_string()).collect();
+        assert_eq!(text[0], "'main' and origin/main have diverged: 2 local and 3 upstream commit(s).");
+        assert!(text.contains(&"  1  Merge  --no-rebase".to_string()));
+        assert!(text.iter().any(|line| line.contains("[ ] Save the choice")));
+    }
+}
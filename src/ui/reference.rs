@@ -0,0 +1,238 @@
+use crate::git::{ReferenceEntry, search_reference};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Input that opens the git reference from the prompt, optionally with a search
+pub const REFERENCE_COMMAND: &str = ":ref";
+
+/// The search of a `:ref` input; `None` for other input
+pub fn parse_reference_command(query: &str) -> Option<&str> {
+    let rest = query.strip_prefix(REFERENCE_COMMAND)?;
+    (rest.is_empty() || rest.starts_with(' ')).then(|| rest.trim())
+}
+
+/// Full-screen, searchable reference for the subcommands Gitalky allows
+pub struct ReferenceBrowser {
+    pub visible: bool,
+    query: String,
+    selected: usize,
+}
+
+impl ReferenceBrowser {
+    pub fn new() -> Self {
+        ReferenceBrowser {
+            visible: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Open with `query` as the search
+    pub fn show(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.selected = 0;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Typing searches, arrows select; returns false when the browser should close
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc if self.query.is_empty() => return false,
+            KeyCode::Esc => {
+                self.query.clear();
+                self.selected = 0;
+            }
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.entries().len().saturating_sub(1));
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn entries(&self) -> Vec<&'static ReferenceEntry> {
+        search_reference(&self.query)
+    }
+
+    /// The page shown on the right
+    pub fn selected_entry(&self) -> Option<&'static ReferenceEntry> {
+        self.entries().get(self.selected).copied()
+    }
+
+    fn list_lines(&self, entries: &[&ReferenceEntry]) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("/ ", Style::default().fg(Color::Yellow)),
+                Span::raw(self.query.clone()),
+                Span::styled("▊", Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from(""),
+        ];
+        if entries.is_empty() {
+            lines.push(Line::from(Span::styled("No matches", Style::default().fg(Color::DarkGray))));
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let style = if i == self.selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let marker = if i == self.selected { "› " } else { "  " };
+            lines.push(Line::from(Span::styled(format!("{}{}", marker, entry.name), style)));
+        }
+        lines
+    }
+
+    fn page_lines(entry: &ReferenceEntry) -> Vec<Line<'static>> {
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ))
+        };
+        let mut lines = vec![
+            Line::from(Span::styled(
+                format!("git {}", entry.name),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(entry.summary()),
+            Line::from(""),
+            Line::from(Span::styled(entry.synopsis, Style::default().fg(Color::Green))),
+        ];
+
+        let flags = entry.flags();
+        if !flags.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(heading("Common flags"));
+            for (flag, description) in flags {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  {:<22}", flag), Style::default().fg(Color::Cyan)),
+                    Span::raw(description),
+                ]));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(heading("Examples"));
+        for (example, description) in entry.examples {
+            lines.push(Line::from(Span::styled(format!("  {}", example), Style::default().fg(Color::Green))));
+            lines.push(Line::from(Span::styled(
+                format!("    {}", description),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        if let Some(caution) = entry.caution {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(format!("⚠ {}", caution), Style::default().fg(Color::Red))));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Git Reference (offline) ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " type to search | ↑↓: select | Esc: clear search, then close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .margin(1)
+            .constraints([Constraint::Length(20), Constraint::Min(0)])
+            .split(inner);
+
+        let entries = self.entries();
+        // Keep the selection visible in long lists
+        let skip = (self.selected + 3).saturating_sub(columns[0].height as usize);
+        let mut list = self.list_lines(&entries);
+        if skip > 0 {
+            list.drain(2..2 + skip.min(list.len() - 2));
+        }
+        frame.render_widget(Paragraph::new(list), columns[0]);
+
+        if let Some(entry) = entries.get(self.selected) {
+            let page = Paragraph::new(Self::page_lines(entry))
+                .style(Style::default().fg(Color::White))
+                .wrap(Wrap { trim: false });
+            frame.render_widget(page, columns[1]);
+        }
+    }
+}
+
+impl Default for ReferenceBrowser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(browser: &mut ReferenceBrowser, code: KeyCode) -> bool {
+        browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_parse_reference_command() {
+        assert_eq!(parse_reference_command(":ref"), Some(""));
+        assert_eq!(parse_reference_command(":ref  force push "), Some("force push"));
+        assert_eq!(parse_reference_command(":refs"), None);
+        assert_eq!(parse_reference_command("ref"), None);
+    }
+
+    #[test]
+    fn test_search_and_close() {
+        let mut browser = ReferenceBrowser::new();
+        browser.show("");
+        assert_eq!(browser.selected_entry().unwrap().name, "status");
+
+        for c in "rebas".chars() {
+            assert!(press(&mut browser, KeyCode::Char(c)));
+        }
+        assert_eq!(browser.selected_entry().unwrap().name, "rebase");
+        assert!(press(&mut browser, KeyCode::Down));
+
+        // Esc clears the search first, then closes
+        assert!(press(&mut browser, KeyCode::Esc));
+        assert_eq!(browser.selected_entry().unwrap().name, "status");
+        assert!(!press(&mut browser, KeyCode::Esc));
+    }
+
+    #[test]
+    fn test_page_lines() {
+        let entry = crate::git::REFERENCE.iter().find(|entry| entry.name == "push").unwrap();
+        let text: Vec<String> = ReferenceBrowser::page_lines(entry).iter().map(|line| line.to_string()).collect();
+        assert_eq!(text[0], "git push");
+        assert!(text.iter().any(|line| line.starts_with("  --force-with-lease")));
+        assert!(text.last().unwrap().starts_with("⚠ --force"));
+    }
+}
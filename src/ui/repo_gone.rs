@@ -0,0 +1,150 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use std::path::{Path, PathBuf};
+
+/// Recent repositories offered as number keys
+const MAX_CHOICES: usize = 9;
+
+/// What the user picked on the recovery screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoGoneAction {
+    /// Look for the repository again, e.g. after moving it back
+    Retry,
+    /// Continue in another repository
+    Open(PathBuf),
+    Quit,
+}
+
+/// Shown instead of the main screen once the repository directory was
+/// deleted, moved, or stopped being a git repository
+pub struct RepoGoneScreen {
+    path: PathBuf,
+    /// Recent repositories that still exist, other than the missing one
+    choices: Vec<PathBuf>,
+    /// Result of the last retry
+    note: Option<String>,
+}
+
+impl RepoGoneScreen {
+    pub fn new(path: &Path, recent: &[&Path]) -> Self {
+        RepoGoneScreen {
+            path: path.to_path_buf(),
+            choices: recent
+                .iter()
+                .filter(|candidate| **candidate != path)
+                .take(MAX_CHOICES)
+                .map(|candidate| candidate.to_path_buf())
+                .collect(),
+            note: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn set_note(&mut self, note: impl Into<String>) {
+        self.note = Some(note.into());
+    }
+
+    pub fn handle_key(&self, key: KeyEvent) -> Option<RepoGoneAction> {
+        match key.code {
+            KeyCode::Char('r') | KeyCode::Char('R') => Some(RepoGoneAction::Retry),
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => Some(RepoGoneAction::Quit),
+            KeyCode::Char(c) => {
+                let index = c.to_digit(10)?.checked_sub(1)? as usize;
+                self.choices.get(index).cloned().map(RepoGoneAction::Open)
+            }
+            _ => None,
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let key = |key: String| Span::styled(format!("  {:<3}", key), Style::default().fg(Color::Cyan));
+        let mut lines = vec![
+            Line::from(Span::styled(
+                self.path.display().to_string(),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from("was deleted or moved, or is no longer a git repository."),
+            Line::from("Gitalky has stopped running git there."),
+            Line::from(""),
+            Line::from(vec![key("r".to_string()), Span::raw("Check again (after moving it back)")]),
+        ];
+        for (i, choice) in self.choices.iter().enumerate() {
+            lines.push(Line::from(vec![
+                key((i + 1).to_string()),
+                Span::raw(format!("Open {}", choice.display())),
+            ]));
+        }
+        lines.push(Line::from(vec![key("q".to_string()), Span::raw("Quit")]));
+        if let Some(ref note) = self.note {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(note.clone(), Style::default().fg(Color::Red)));
+        }
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Repository Unavailable ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Red))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn press(screen: &RepoGoneScreen, code: KeyCode) -> Option<RepoGoneAction> {
+        screen.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_choices_skip_missing_repo() {
+        let gone = Path::new("/src/app");
+        let screen = RepoGoneScreen::new(gone, &[gone, Path::new("/src/lib"), Path::new("/src/web")]);
+
+        assert_eq!(press(&screen, KeyCode::Char('1')), Some(RepoGoneAction::Open(PathBuf::from("/src/lib"))));
+        assert_eq!(press(&screen, KeyCode::Char('2')), Some(RepoGoneAction::Open(PathBuf::from("/src/web"))));
+        assert_eq!(press(&screen, KeyCode::Char('3')), None);
+        assert_eq!(press(&screen, KeyCode::Char('0')), None);
+        assert_eq!(press(&screen, KeyCode::Char('r')), Some(RepoGoneAction::Retry));
+        assert_eq!(press(&screen, KeyCode::Esc), Some(RepoGoneAction::Quit));
+        assert_eq!(press(&screen, KeyCode::Enter), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut screen = RepoGoneScreen::new(Path::new("/src/app"), &[Path::new("/src/lib")]);
+        screen.set_note("/src/app is still missing");
+        let text: Vec<String> = screen.lines().iter().map(|line| line.to_string()).collect();
+        assert_eq!(text[0], "/src/app");
+        assert!(text.contains(&"  1  Open /src/lib".to_string()));
+        assert_eq!(text.last().unwrap(), "/src/app is still missing");
+    }
+}
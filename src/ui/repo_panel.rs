@@ -1,23 +1,330 @@
-use crate::git::{FileStatus, RepositoryState};
+use crate::config::identity::{IdentityProfile, active_profile};
+use crate::git::{
+    CommitEntry, FileStatus, HiddenFile, NextAction, RebaseProgress, RebaseStop, RepositoryState, SignatureStatus,
+    StateSection, StatusEntry, UpstreamInfo, next_actions,
+};
 use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
+/// Match `pattern` against `text` as a case-insensitive fuzzy substring
+///
+/// The pattern's characters must appear in order, not necessarily adjacent
+/// ("rpnl" matches "repo_panel.rs"). Returns the char indices of the matched
+/// characters for highlighting.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    let mut pattern_chars = pattern.chars().flat_map(char::to_lowercase).peekable();
+
+    for (i, ch) in text.chars().enumerate() {
+        let Some(&wanted) = pattern_chars.peek() else {
+            break;
+        };
+        if ch.to_lowercase().eq(std::iter::once(wanted)) {
+            positions.push(i);
+            pattern_chars.next();
+        }
+    }
+
+    pattern_chars.peek().is_none().then_some(positions)
+}
+
+/// "↑2 ↓5", or `None` when level with the remote branch
+fn tracking_text(upstream: &UpstreamInfo) -> Option<String> {
+    let mut parts = Vec::new();
+    if upstream.ahead > 0 {
+        parts.push(format!("↑{}", upstream.ahead));
+    }
+    if upstream.behind > 0 {
+        parts.push(format!("↓{}", upstream.behind));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Green for a trusted good signature, red when it can't be trusted at all
+fn signature_color(status: SignatureStatus) -> Color {
+    match status {
+        SignatureStatus::Good => Color::Green,
+        SignatureStatus::Untrusted | SignatureStatus::Expired | SignatureStatus::Unverifiable => Color::Yellow,
+        SignatureStatus::Revoked | SignatureStatus::Bad => Color::Red,
+        SignatureStatus::Unsigned => Color::DarkGray,
+    }
+}
+
+/// Filter narrowing the file lists shown in the repository panel
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelFilter {
+    pub query: String,
+    /// The filter prompt is open and receiving keys
+    pub editing: bool,
+}
+
+impl PanelFilter {
+    /// Whether a non-empty filter is applied
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Check whether a path passes the filter
+    pub fn matches(&self, path: &str) -> bool {
+        fuzzy_match(&self.query, path).is_some()
+    }
+
+    /// Entries whose path passes the filter
+    pub fn apply<'s>(&self, entries: &'s [StatusEntry]) -> Vec<&'s StatusEntry> {
+        entries.iter().filter(|e| self.matches(&e.path)).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.editing = false;
+    }
+}
+
+/// Lists in the repository panel that can be expanded past their limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelSection {
+    Untracked,
+    Unstaged,
+    Staged,
+    Hidden,
+    Stashes,
+    Commits,
+}
+
+impl PanelSection {
+    /// Rows shown before the list collapses into "... and N more"
+    fn limit(self) -> usize {
+        match self {
+            PanelSection::Untracked | PanelSection::Unstaged | PanelSection::Staged | PanelSection::Hidden => 10,
+            PanelSection::Stashes | PanelSection::Commits => 5,
+        }
+    }
+}
+
+/// Scroll position, cursor and expanded sections of the repository panel
+///
+/// Owned by the app so it survives repository refreshes; call
+/// [`clamp`](Self::clamp) whenever the content may have changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelView {
+    /// First content line shown
+    pub scroll: usize,
+    /// Highlighted content line while the panel is focused
+    pub cursor: usize,
+    expanded: Vec<PanelSection>,
+}
+
+impl PanelView {
+    pub fn is_expanded(&self, section: PanelSection) -> bool {
+        self.expanded.contains(&section)
+    }
+
+    /// Expand a truncated section, or collapse an expanded one
+    pub fn toggle(&mut self, section: PanelSection) {
+        if let Some(index) = self.expanded.iter().position(|s| *s == section) {
+            self.expanded.remove(index);
+        } else {
+            self.expanded.push(section);
+        }
+    }
+
+    /// Move the cursor by `delta` lines within `line_count` lines
+    pub fn move_cursor(&mut self, delta: isize, line_count: usize) {
+        let last = line_count.saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Keep the cursor within the content and the viewport of `height` lines
+    pub fn clamp(&mut self, line_count: usize, height: usize) {
+        self.cursor = self.cursor.min(line_count.saturating_sub(1));
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if height > 0 && self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+        self.scroll = self.scroll.min(line_count.saturating_sub(height));
+    }
+}
+
+/// Content lines of the panel and where the selectable rows are
+struct PanelRows<'a> {
+    lines: Vec<Line<'a>>,
+    /// "... and N more" / "show less" lines and their sections
+    toggles: Vec<(usize, PanelSection)>,
+    /// First suggested next action row
+    actions_start: usize,
+    /// Rows of files hidden from git status
+    hidden_rows: std::ops::Range<usize>,
+    /// First commit row
+    commits_start: usize,
+}
+
 /// Repository state display panel
 pub struct RepositoryPanel<'a> {
     state: &'a RepositoryState,
+    filter: Option<&'a PanelFilter>,
+    view: Option<&'a PanelView>,
+    focused: bool,
+    identities: &'a [IdentityProfile],
+    /// Sections still loading at startup, drawn as placeholders
+    loading: &'a [StateSection],
 }
 
 impl<'a> RepositoryPanel<'a> {
     pub fn new(state: &'a RepositoryState) -> Self {
-        Self { state }
+        Self {
+            state,
+            filter: None,
+            view: None,
+            focused: false,
+            identities: &[],
+            loading: &[],
+        }
+    }
+
+    /// Show placeholders for sections that haven't loaded yet
+    pub fn with_loading(mut self, loading: &'a [StateSection]) -> Self {
+        self.loading = loading;
+        self
+    }
+
+    fn is_loading(&self, section: StateSection) -> bool {
+        self.loading.contains(&section)
+    }
+
+    /// Narrow the file lists with a filter
+    pub fn with_filter(mut self, filter: &'a PanelFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Scroll, cursor and expansion state to draw with
+    pub fn with_view(mut self, view: &'a PanelView) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// Draw the panel as the focused widget
+    pub fn with_focus(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Show which configured identity commits would be authored as
+    pub fn with_identities(mut self, identities: &'a [IdentityProfile]) -> Self {
+        self.identities = identities;
+        self
+    }
+
+    fn active_filter(&self) -> Option<&'a PanelFilter> {
+        self.filter.filter(|f| f.is_active())
+    }
+
+    /// Entries that pass the filter (all of them when no filter is active)
+    fn visible(&self, entries: &'a [StatusEntry]) -> Vec<&'a StatusEntry> {
+        match self.active_filter() {
+            Some(filter) => filter.apply(entries),
+            None => entries.iter().collect(),
+        }
+    }
+
+    /// Hidden files that pass the filter
+    fn visible_hidden(&self) -> Vec<&'a HiddenFile> {
+        let filter = self.active_filter();
+        self.state
+            .hidden_files
+            .iter()
+            .filter(|file| filter.is_none_or(|f| f.matches(&file.path)))
+            .collect()
+    }
+
+    /// Section header with a "shown/total" badge while filtering
+    fn section_title(&self, name: &str, shown: usize, total: usize) -> String {
+        if self.active_filter().is_some() {
+            format!("{} ({}/{})", name, shown, total)
+        } else {
+            format!("{} ({})", name, total)
+        }
+    }
+
+    /// Path spans with the filter's matched characters highlighted
+    fn path_spans(&self, path: &'a str) -> Vec<Span<'a>> {
+        let Some(positions) = self.active_filter().and_then(|f| fuzzy_match(&f.query, path)) else {
+            return vec![Span::raw(path)];
+        };
+
+        let highlight = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        path.chars()
+            .enumerate()
+            .map(|(i, ch)| {
+                if positions.contains(&i) {
+                    Span::styled(ch.to_string(), highlight)
+                } else {
+                    Span::raw(ch.to_string())
+                }
+            })
+            .collect()
     }
 
     /// Build the content lines for the repository panel
     fn build_content(&self) -> Vec<Line<'a>> {
+        self.build_rows().lines
+    }
+
+    /// Number of content lines, for scrolling
+    pub fn line_count(&self) -> usize {
+        self.build_rows().lines.len()
+    }
+
+    /// Section that Enter on content line `index` expands or collapses
+    pub fn toggle_at(&self, index: usize) -> Option<PanelSection> {
+        self.build_rows()
+            .toggles
+            .into_iter()
+            .find(|(line, _)| *line == index)
+            .map(|(_, section)| section)
+    }
+
+    /// Commit shown on content line `index`
+    pub fn commit_at(&self, index: usize) -> Option<&'a CommitEntry> {
+        let rows = self.build_rows();
+        // Commits are the last section; only its toggle line follows them
+        if index < rows.commits_start || rows.toggles.iter().any(|(line, _)| *line == index) {
+            return None;
+        }
+        self.state.recent_commits.get(index - rows.commits_start)
+    }
+
+    /// Assume-unchanged or skip-worktree file shown on content line `index`
+    pub fn hidden_at(&self, index: usize) -> Option<&'a HiddenFile> {
+        let rows = self.build_rows().hidden_rows;
+        rows.contains(&index).then(|| self.visible_hidden()[index - rows.start])
+    }
+
+    /// Suggested next action shown on content line `index`
+    pub fn action_at(&self, index: usize) -> Option<NextAction> {
+        let offset = index.checked_sub(self.build_rows().actions_start)?;
+        self.next_actions().into_iter().nth(offset)
+    }
+
+    /// Next actions to suggest, once the state they depend on has loaded
+    fn next_actions(&self) -> Vec<NextAction> {
+        if self.is_loading(StateSection::Head) || self.is_loading(StateSection::Files) {
+            return Vec::new();
+        }
+        next_actions(self.state)
+    }
+
+    fn build_rows(&self) -> PanelRows<'a> {
         let mut lines = Vec::new();
+        let mut toggles = Vec::new();
 
         // Repository State header
         lines.push(Line::from(Span::styled(
@@ -27,40 +334,118 @@ impl<'a> RepositoryPanel<'a> {
                 .add_modifier(Modifier::BOLD),
         )));
         lines.push(Line::from("─".repeat(60)));
+
+        // Filter prompt
+        if let Some(filter) = self.filter.filter(|f| f.editing || f.is_active()) {
+            let mut spans = vec![
+                Span::styled("Filter: /", Style::default().fg(Color::Yellow)),
+                Span::raw(filter.query.clone()),
+            ];
+            if filter.editing {
+                spans.push(Span::styled("▊", Style::default().fg(Color::Yellow)));
+            }
+            lines.push(Line::from(spans));
+        }
         lines.push(Line::from(""));
 
+        let placeholder = |text: &'static str| Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)));
+        if self.is_loading(StateSection::Head) {
+            lines.push(placeholder("Loading repository state…"));
+            let end = lines.len() + 1;
+            return PanelRows { lines, toggles, actions_start: end, hidden_rows: 0..0, commits_start: end };
+        }
+
         // Head section
         self.add_head_section(&mut lines);
         lines.push(Line::from(""));
 
+        // Suggested next actions
+        let actions = self.next_actions();
+        let actions_start = lines.len() + 1;
+        if !actions.is_empty() {
+            self.add_next_actions_section(&mut lines, actions);
+            lines.push(Line::from(""));
+        }
+
+        if self.is_loading(StateSection::Files) {
+            lines.push(placeholder("Loading working tree status…"));
+            lines.push(Line::from(""));
+        }
+
         // Untracked files
         if !self.state.untracked_files.is_empty() {
-            self.add_untracked_section(&mut lines);
+            self.add_untracked_section(&mut lines, &mut toggles);
             lines.push(Line::from(""));
         }
 
         // Unstaged changes
         if !self.state.unstaged_files.is_empty() {
-            self.add_unstaged_section(&mut lines);
+            self.add_unstaged_section(&mut lines, &mut toggles);
             lines.push(Line::from(""));
         }
 
         // Staged changes
         if !self.state.staged_files.is_empty() {
-            self.add_staged_section(&mut lines);
+            self.add_staged_section(&mut lines, &mut toggles);
+            lines.push(Line::from(""));
+        }
+
+        // Files git status keeps quiet about
+        let mut hidden_rows = 0..0;
+        if !self.state.hidden_files.is_empty() {
+            let start = lines.len() + 1;
+            self.add_hidden_section(&mut lines, &mut toggles);
+            let end = match toggles.last() {
+                Some(&(line, PanelSection::Hidden)) => line,
+                _ => lines.len(),
+            };
+            hidden_rows = start..end;
             lines.push(Line::from(""));
         }
 
         // Stashes (only show if stashes exist)
         if !self.state.stashes.is_empty() {
-            self.add_stash_section(&mut lines);
+            self.add_stash_section(&mut lines, &mut toggles);
             lines.push(Line::from(""));
         }
 
+        if self.is_loading(StateSection::History) {
+            lines.push(placeholder("Loading commits and stashes…"));
+            let commits_start = lines.len() + 1;
+            return PanelRows { lines, toggles, actions_start, hidden_rows, commits_start };
+        }
+
         // Recent commits
-        self.add_commits_section(&mut lines);
+        let commits_start = lines.len() + 1;
+        self.add_commits_section(&mut lines, &mut toggles);
 
-        lines
+        PanelRows { lines, toggles, actions_start, hidden_rows, commits_start }
+    }
+
+    /// Push a section's rows, truncated unless the section is expanded
+    fn add_rows(
+        &self,
+        lines: &mut Vec<Line<'a>>,
+        toggles: &mut Vec<(usize, PanelSection)>,
+        section: PanelSection,
+        rows: Vec<Line<'a>>,
+    ) {
+        let total = rows.len();
+        let expanded = self.view.is_some_and(|v| v.is_expanded(section));
+        let shown = if expanded { total } else { section.limit() };
+        lines.extend(rows.into_iter().take(shown));
+
+        let hint = Style::default().fg(Color::DarkGray);
+        if total > shown {
+            toggles.push((lines.len(), section));
+            lines.push(Line::from(Span::styled(
+                format!("  ... and {} more", total - shown),
+                hint,
+            )));
+        } else if expanded && total > section.limit() {
+            toggles.push((lines.len(), section));
+            lines.push(Line::from(Span::styled("  (show less)", hint)));
+        }
     }
 
     fn add_head_section(&self, lines: &mut Vec<Line<'a>>) {
@@ -74,21 +459,9 @@ impl<'a> RepositoryPanel<'a> {
 
             // Add upstream tracking info if available
             if let Some(ref upstream) = self.state.upstream {
-                let mut tracking_parts = vec![];
-
-                if upstream.ahead > 0 {
-                    tracking_parts.push(format!("↑{}", upstream.ahead));
-                }
-                if upstream.behind > 0 {
-                    tracking_parts.push(format!("↓{}", upstream.behind));
-                }
-
-                if !tracking_parts.is_empty() {
+                if let Some(tracking) = tracking_text(upstream) {
                     head_spans.push(Span::raw(" "));
-                    head_spans.push(Span::styled(
-                        tracking_parts.join(" "),
-                        Style::default().fg(Color::Cyan),
-                    ));
+                    head_spans.push(Span::styled(tracking, Style::default().fg(Color::Cyan)));
                 }
 
                 head_spans.push(Span::raw("  "));
@@ -96,6 +469,13 @@ impl<'a> RepositoryPanel<'a> {
                     format!("({})", upstream.remote_branch),
                     Style::default().fg(Color::DarkGray),
                 ));
+            } else {
+                // Never pushed: the next plain `git push` would fail
+                head_spans.push(Span::raw("  "));
+                head_spans.push(Span::styled(
+                    "[no upstream · Ctrl+U to publish]".to_string(),
+                    Style::default().fg(Color::Magenta),
+                ));
             }
         } else {
             head_spans.push(Span::styled(
@@ -104,99 +484,176 @@ impl<'a> RepositoryPanel<'a> {
             ));
         }
 
+        // The project a fork was made from, say
+        if let Some(ref second) = self.state.second_upstream {
+            head_spans.push(Span::raw("  "));
+            head_spans.push(Span::styled(
+                format!("{} ", second.remote_branch),
+                Style::default().fg(Color::DarkGray),
+            ));
+            head_spans.push(Span::styled(
+                tracking_text(second).unwrap_or_else(|| "=".to_string()),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+
+        // log, describe and bisect stop at the clone's depth
+        if let Some(depth) = self.state.shallow_depth {
+            head_spans.push(Span::raw("  "));
+            head_spans.push(Span::styled(
+                format!("[shallow (depth≈{})]", depth),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+
         lines.push(Line::from(head_spans));
+
+        if let Some(ref rebase) = self.state.rebase {
+            lines.push(self.rebase_line(rebase));
+        }
+
+        if !self.identities.is_empty() {
+            lines.push(self.identity_line());
+        }
     }
 
-    fn add_untracked_section(&self, lines: &mut Vec<Line<'a>>) {
-        let count = self.state.untracked_files.len();
+    fn add_next_actions_section(&self, lines: &mut Vec<Line<'a>>, actions: Vec<NextAction>) {
         lines.push(Line::from(Span::styled(
-            format!("Untracked files ({})", count),
-            Style::default()
-                .fg(Color::Red)
-                .add_modifier(Modifier::BOLD),
+            "Next (Tab, Enter to preview):",
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
         )));
-
-        for file in self.state.untracked_files.iter().take(10) {
+        for action in actions {
             lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled("untracked:  ", Style::default().fg(Color::Red)),
-                Span::raw(&file.path),
+                Span::styled("  ▸ ", Style::default().fg(Color::Green)),
+                Span::raw(action.reason),
+                Span::styled(" → ", Style::default().fg(Color::DarkGray)),
+                Span::styled(action.label, Style::default().fg(Color::Green)),
             ]));
         }
+    }
 
-        if count > 10 {
-            lines.push(Line::from(Span::styled(
-                format!("  ... and {} more", count - 10),
-                Style::default().fg(Color::DarkGray),
-            )));
-        }
+    /// "Rebase:   applying 3/7: Fix the parser  [1 conflicted file]"
+    fn rebase_line(&self, rebase: &RebaseProgress) -> Line<'a> {
+        let conflicts = self
+            .state
+            .unstaged_files
+            .iter()
+            .filter(|file| file.status == FileStatus::Conflicted)
+            .count();
+        let stop = rebase.stop(conflicts);
+        let color = if matches!(stop, RebaseStop::Conflicts(_)) { Color::Red } else { Color::Magenta };
+        Line::from(vec![
+            Span::styled(format!("Rebase:   {}", rebase.summary()), Style::default().fg(Color::Magenta)),
+            Span::raw("  "),
+            Span::styled(format!("[{}]", stop.describe()), Style::default().fg(color)),
+        ])
     }
 
-    fn add_unstaged_section(&self, lines: &mut Vec<Line<'a>>) {
-        let count = self.state.unstaged_files.len();
+    /// Identity badge; red when commits wouldn't match any configured profile
+    fn identity_line(&self) -> Line<'a> {
+        let email = self.state.user_email.as_deref();
+        let (text, color) = match (active_profile(self.identities, email), email) {
+            (Some(profile), _) => (format!("Identity: {} <{}>", profile.label, profile.email), Color::Green),
+            (None, Some(email)) => (format!("Identity: {} (no profile · :identity <label>)", email), Color::Red),
+            (None, None) => ("Identity: not set (:identity <label>)".to_string(), Color::Red),
+        };
+        Line::from(Span::styled(text, Style::default().fg(color)))
+    }
+
+    fn add_untracked_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
+        let files = self.visible(&self.state.untracked_files);
         lines.push(Line::from(Span::styled(
-            format!("Unstaged changes ({})", count),
+            self.section_title("Untracked files", files.len(), self.state.untracked_files.len()),
             Style::default()
                 .fg(Color::Red)
                 .add_modifier(Modifier::BOLD),
         )));
 
-        for file in self.state.unstaged_files.iter().take(10) {
-            let (status_text, color) = match file.status {
-                FileStatus::Modified => ("modified:  ", Color::Yellow),
-                FileStatus::Deleted => ("deleted:   ", Color::Red),
-                FileStatus::Added => ("new file:  ", Color::Green),
-                _ => ("unknown:   ", Color::White),
-            };
+        let rows = files
+            .iter()
+            .map(|file| {
+                let mut spans = vec![
+                    Span::raw("  "),
+                    Span::styled("untracked:  ", Style::default().fg(Color::Red)),
+                ];
+                spans.extend(self.path_spans(&file.path));
+                Line::from(spans)
+            })
+            .collect();
+        self.add_rows(lines, toggles, PanelSection::Untracked, rows);
+    }
 
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(status_text, Style::default().fg(color)),
-                Span::raw(&file.path),
-            ]));
-        }
+    fn add_unstaged_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
+        let files = self.visible(&self.state.unstaged_files);
+        lines.push(Line::from(Span::styled(
+            self.section_title("Unstaged changes", files.len(), self.state.unstaged_files.len()),
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        )));
 
-        if count > 10 {
-            lines.push(Line::from(Span::styled(
-                format!("  ... and {} more", count - 10),
-                Style::default().fg(Color::DarkGray),
-            )));
-        }
+        let rows = files.iter().map(|file| self.file_row(file)).collect();
+        self.add_rows(lines, toggles, PanelSection::Unstaged, rows);
     }
 
-    fn add_staged_section(&self, lines: &mut Vec<Line<'a>>) {
-        let count = self.state.staged_files.len();
+    fn add_staged_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
+        let files = self.visible(&self.state.staged_files);
         lines.push(Line::from(Span::styled(
-            format!("Staged changes ({})", count),
+            self.section_title("Staged changes", files.len(), self.state.staged_files.len()),
             Style::default()
                 .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
         )));
 
-        for file in self.state.staged_files.iter().take(10) {
-            let (status_text, color) = match file.status {
-                FileStatus::Modified => ("modified:  ", Color::Yellow),
-                FileStatus::Deleted => ("deleted:   ", Color::Red),
-                FileStatus::Added => ("new file:  ", Color::Green),
-                _ => ("unknown:   ", Color::White),
-            };
+        let rows = files.iter().map(|file| self.file_row(file)).collect();
+        self.add_rows(lines, toggles, PanelSection::Staged, rows);
+    }
 
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(status_text, Style::default().fg(color)),
-                Span::raw(&file.path),
-            ]));
-        }
+    fn add_hidden_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
+        let files = self.visible_hidden();
+        lines.push(Line::from(Span::styled(
+            self.section_title("Hidden from git status", files.len(), self.state.hidden_files.len()),
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+        )));
 
-        if count > 10 {
-            lines.push(Line::from(Span::styled(
-                format!("  ... and {} more", count - 10),
-                Style::default().fg(Color::DarkGray),
-            )));
+        let rows = files
+            .iter()
+            .map(|file| {
+                let mut spans = vec![
+                    Span::raw("  "),
+                    Span::styled(format!("{}: ", file.label()), Style::default().fg(Color::Blue)),
+                ];
+                spans.extend(self.path_spans(&file.path));
+                Line::from(spans)
+            })
+            .collect();
+        self.add_rows(lines, toggles, PanelSection::Hidden, rows);
+    }
+
+    /// Row for a tracked file: status label and highlighted path
+    fn file_row(&self, file: &'a StatusEntry) -> Line<'a> {
+        let (status_text, color) = match file.status {
+            FileStatus::Modified => ("modified:  ", Color::Yellow),
+            FileStatus::Deleted => ("deleted:   ", Color::Red),
+            FileStatus::Added => ("new file:  ", Color::Green),
+            FileStatus::Conflicted => ("conflict:  ", Color::LightRed),
+            _ => ("unknown:   ", Color::White),
+        };
+
+        let mut spans = vec![
+            Span::raw("  "),
+            Span::styled(status_text, Style::default().fg(color)),
+        ];
+        spans.extend(self.path_spans(&file.path));
+        if file.is_intent_to_add() {
+            spans.push(Span::styled(" (intent to add)", Style::default().fg(Color::DarkGray)));
         }
+        Line::from(spans)
     }
 
-    fn add_stash_section(&self, lines: &mut Vec<Line<'a>>) {
+    fn add_stash_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
         let count = self.state.stashes.len();
         lines.push(Line::from(Span::styled(
             format!("Stashes ({})", count),
@@ -205,67 +662,150 @@ impl<'a> RepositoryPanel<'a> {
                 .add_modifier(Modifier::BOLD),
         )));
 
-        // Show first 5 stashes
-        for stash in self.state.stashes.iter().take(5) {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(&stash.index, Style::default().fg(Color::Cyan)),
-                Span::raw(": "),
-                Span::raw(&stash.message),
-            ]));
-        }
-
-        if count > 5 {
-            lines.push(Line::from(Span::styled(
-                format!("  ... and {} more", count - 5),
-                Style::default().fg(Color::DarkGray),
-            )));
-        }
+        let rows = self
+            .state
+            .stashes
+            .iter()
+            .map(|stash| {
+                Line::from(vec![
+                    Span::raw("  "),
+                    Span::styled(&stash.index, Style::default().fg(Color::Cyan)),
+                    Span::raw(": "),
+                    Span::raw(&stash.message),
+                ])
+            })
+            .collect();
+        self.add_rows(lines, toggles, PanelSection::Stashes, rows);
     }
 
-    fn add_commits_section(&self, lines: &mut Vec<Line<'a>>) {
+    fn add_commits_section(&self, lines: &mut Vec<Line<'a>>, toggles: &mut Vec<(usize, PanelSection)>) {
         let count = self.state.recent_commits.len();
-        let display_count = if count > 0 { count } else { 0 };
 
         lines.push(Line::from(Span::styled(
-            format!("Recent commits ({})", display_count),
+            format!("Recent commits ({})", count),
             Style::default()
                 .fg(Color::Blue)
                 .add_modifier(Modifier::BOLD),
         )));
 
-        for commit in self.state.recent_commits.iter().take(5) {
-            let short_hash = if commit.hash.len() >= 7 {
-                &commit.hash[..7]
-            } else {
-                &commit.hash
-            };
-
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(short_hash, Style::default().fg(Color::Yellow)),
-                Span::raw(" "),
-                Span::raw(&commit.message),
-            ]));
-        }
+        let rows = self
+            .state
+            .recent_commits
+            .iter()
+            .map(|commit| {
+                let short_hash = if commit.hash.len() >= 7 {
+                    &commit.hash[..7]
+                } else {
+                    &commit.hash
+                };
 
-        if count > 5 {
-            lines.push(Line::from(Span::styled(
-                format!("  ... and {} more", count - 5),
-                Style::default().fg(Color::DarkGray),
-            )));
-        }
+                let note = if commit.has_note { "✎ " } else { "" };
+                let mut spans = vec![
+                    Span::raw("  "),
+                    Span::styled(short_hash, Style::default().fg(Color::Yellow)),
+                    Span::raw(" "),
+                ];
+                if let Some(signature) = commit.signature {
+                    spans.push(Span::styled(
+                        format!("{} ", signature.badge()),
+                        Style::default().fg(signature_color(signature)),
+                    ));
+                }
+                spans.push(Span::styled(note, Style::default().fg(Color::Magenta)));
+                spans.push(Span::raw(&commit.message));
+                Line::from(spans)
+            })
+            .collect();
+        self.add_rows(lines, toggles, PanelSection::Commits, rows);
     }
 }
 
 impl<'a> Widget for RepositoryPanel<'a> {
-    fn render(self, area: ratatui::layout::Rect, buf: &mut ratatui::buffer::Buffer) {
-        let content = self.build_content();
-        let paragraph = Paragraph::new(content).block(Block::default().borders(Borders::ALL));
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut content = self.build_content();
+        let block = if self.focused {
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan))
+                .title("/ filter · s stage · d diff · i intent to add · h hide/unhide · e edit · n note · v verify · Enter expand/preview · Tab back")
+        } else {
+            Block::default().borders(Borders::ALL)
+        };
+
+        let scroll = self.view.map(|v| v.scroll).unwrap_or(0);
+        if self.focused
+            && let Some(line) = self.view.and_then(|v| content.get_mut(v.cursor))
+        {
+            line.style = Style::default().bg(Color::DarkGray);
+        }
+
+        // Hidden lines above/below the viewport
+        let height = area.height.saturating_sub(2) as usize;
+        let below = content.len().saturating_sub(scroll + height);
+        let block = if scroll > 0 || below > 0 {
+            block.title_bottom(
+                Line::from(format!(" ↑{} ↓{} ", scroll, below)).right_aligned(),
+            )
+        } else {
+            block
+        };
+
+        let paragraph = Paragraph::new(content)
+            .block(block)
+            .scroll((scroll.min(u16::MAX as usize) as u16, 0));
         paragraph.render(area, buf);
     }
 }
 
+/// Caches the rendered repository panel between frames
+///
+/// The panel is only rebuilt when it has been invalidated (the repository
+/// state changed) or the area it is drawn into changed size. Otherwise the
+/// previously rendered cells are merged into the frame as-is.
+#[derive(Debug, Default)]
+pub struct PanelCache {
+    buffer: Option<Buffer>,
+    dirty: bool,
+}
+
+impl PanelCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark the cached panel as stale so the next render rebuilds it
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Check whether the next render for `area` will rebuild the panel
+    pub fn is_stale(&self, area: Rect) -> bool {
+        self.dirty || self.buffer.as_ref().is_none_or(|cached| cached.area != area)
+    }
+
+    /// Render the panel for `state` into `buf`, rebuilding only when stale
+    pub fn render(&mut self, state: &RepositoryState, area: Rect, buf: &mut Buffer) {
+        self.render_panel(RepositoryPanel::new(state), area, buf);
+    }
+
+    /// Render a configured panel (filter, view, focus) into `buf`, rebuilding only when stale
+    ///
+    /// Callers must [`invalidate`](Self::invalidate) the cache when the filter,
+    /// view or focus changes.
+    pub fn render_panel(&mut self, panel: RepositoryPanel<'_>, area: Rect, buf: &mut Buffer) {
+        if self.is_stale(area) {
+            let mut cached = Buffer::empty(area);
+            panel.render(area, &mut cached);
+            self.buffer = Some(cached);
+            self.dirty = false;
+        }
+
+        if let Some(ref cached) = self.buffer {
+            buf.merge(cached);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,10 +837,14 @@ mod tests {
                 CommitEntry {
                     hash: "abc123def456".to_string(),
                     message: "Initial commit".to_string(),
+                    has_note: false,
+                    signature: None,
                 },
                 CommitEntry {
                     hash: "def456abc123".to_string(),
                     message: "Second commit".to_string(),
+                    has_note: true,
+                    signature: None,
                 },
             ],
             stashes: vec![
@@ -315,6 +859,12 @@ mod tests {
             ],
             in_merge: false,
             in_rebase: false,
+            rebase: None,
+            second_upstream: None,
+            user_name: None,
+            user_email: None,
+            shallow_depth: None,
+            hidden_files: Vec::new(),
         }
     }
 
@@ -431,6 +981,24 @@ mod tests {
         assert!(has_remote);
     }
 
+    #[test]
+    fn test_second_remote_display() {
+        let mut state = create_test_state();
+        state.second_upstream = Some(crate::git::UpstreamInfo {
+            remote_branch: "upstream/main".to_string(),
+            ahead: 0,
+            behind: 5,
+        });
+        let head = |state: &RepositoryState| {
+            let line = RepositoryPanel::new(state).build_content()[3].clone();
+            line.spans.iter().map(|span| span.content.to_string()).collect::<String>()
+        };
+        assert!(head(&state).ends_with("  upstream/main ↓5"));
+
+        state.second_upstream.as_mut().unwrap().behind = 0;
+        assert!(head(&state).ends_with("  upstream/main ="));
+    }
+
     #[test]
     fn test_no_upstream_display() {
         let state = create_test_state(); // upstream is None
@@ -445,5 +1013,330 @@ mod tests {
                 .any(|span| span.content.contains("↑") || span.content.contains("↓"))
         });
         assert!(!has_tracking);
+
+        let has_badge = content.iter().any(|line| {
+            line.spans
+                .iter()
+                .any(|span| span.content.contains("no upstream"))
+        });
+        assert!(has_badge);
+    }
+
+    #[test]
+    fn test_no_upstream_badge_hidden_when_detached() {
+        let mut state = create_test_state();
+        state.current_branch = None;
+
+        let panel = RepositoryPanel::new(&state);
+        let content = panel.build_content();
+
+        let has_badge = content.iter().any(|line| {
+            line.spans
+                .iter()
+                .any(|span| span.content.contains("no upstream"))
+        });
+        assert!(!has_badge);
+    }
+
+    #[test]
+    fn test_shallow_badge() {
+        let mut state = create_test_state();
+        let has_badge = |state: &RepositoryState| {
+            RepositoryPanel::new(state)
+                .build_content()
+                .iter()
+                .any(|line| line.spans.iter().any(|span| span.content == "[shallow (depth≈1)]"))
+        };
+        assert!(!has_badge(&state));
+
+        state.shallow_depth = Some(1);
+        assert!(has_badge(&state));
+    }
+
+    #[test]
+    fn test_identity_badge() {
+        let mut state = create_test_state();
+        let identities = vec![IdentityProfile {
+            label: "work".to_string(),
+            name: "Jane Doe".to_string(),
+            email: "jane@corp.example".to_string(),
+            signing_key: None,
+        }];
+        let identity_line = |state: &RepositoryState, identities: &[IdentityProfile]| {
+            text(&RepositoryPanel::new(state).with_identities(identities).build_content())
+                .into_iter()
+                .find(|line| line.starts_with("Identity:"))
+        };
+
+        // Only shown once identities are configured
+        assert_eq!(identity_line(&state, &[]), None);
+        assert_eq!(identity_line(&state, &identities).as_deref(), Some("Identity: not set (:identity <label>)"));
+
+        state.user_email = Some("jane@corp.example".to_string());
+        assert_eq!(identity_line(&state, &identities).as_deref(), Some("Identity: work <jane@corp.example>"));
+
+        state.user_email = Some("jane@home.example".to_string());
+        assert_eq!(
+            identity_line(&state, &identities).as_deref(),
+            Some("Identity: jane@home.example (no profile · :identity <label>)")
+        );
+    }
+
+    #[test]
+    fn test_panel_cache_rebuilds_only_when_stale() {
+        let state = create_test_state();
+        let area = Rect::new(0, 0, 60, 30);
+        let mut cache = PanelCache::new();
+        let mut buf = Buffer::empty(area);
+
+        assert!(cache.is_stale(area));
+        cache.render(&state, area, &mut buf);
+        assert!(!cache.is_stale(area));
+
+        // A different area forces a rebuild
+        assert!(cache.is_stale(Rect::new(0, 0, 80, 30)));
+
+        cache.invalidate();
+        assert!(cache.is_stale(area));
+    }
+
+    #[test]
+    fn test_panel_cache_matches_direct_render() {
+        let state = create_test_state();
+        let area = Rect::new(0, 0, 60, 30);
+
+        let mut direct = Buffer::empty(area);
+        RepositoryPanel::new(&state).render(area, &mut direct);
+
+        let mut cache = PanelCache::new();
+        let mut cached = Buffer::empty(area);
+        cache.render(&state, area, &mut cached);
+        // Second render reuses the cached cells
+        let mut reused = Buffer::empty(area);
+        cache.render(&state, area, &mut reused);
+
+        assert_eq!(direct, cached);
+        assert_eq!(direct, reused);
+    }
+
+    #[test]
+    fn test_loading_placeholders() {
+        let state = create_test_state();
+        let text = |loading: &[StateSection]| -> Vec<String> {
+            RepositoryPanel::new(&state)
+                .with_loading(loading)
+                .build_content()
+                .iter()
+                .map(|line| line.to_string())
+                .collect()
+        };
+
+        let all = text(&StateSection::ALL);
+        assert!(all.iter().any(|l| l == "Loading repository state…"));
+        assert!(!all.iter().any(|l| l.starts_with("Head:")));
+
+        let files = text(&[StateSection::Files, StateSection::History]);
+        assert!(files.iter().any(|l| l.starts_with("Head:")));
+        assert!(files.iter().any(|l| l == "Loading working tree status…"));
+        assert!(files.iter().any(|l| l == "Loading commits and stashes…"));
+        assert!(!files.iter().any(|l| l.starts_with("Recent commits")));
+
+        let done = text(&[]);
+        assert!(!done.iter().any(|l| l.starts_with("Loading")));
+        assert!(done.iter().any(|l| l.starts_with("Recent commits")));
+    }
+
+    #[test]
+    fn test_next_action_rows() {
+        let state = create_test_state();
+        let panel = RepositoryPanel::new(&state);
+        let lines: Vec<String> = panel.build_content().iter().map(|line| line.to_string()).collect();
+
+        let row = lines.iter().position(|l| l == "  ▸ 1 staged file → commit").unwrap();
+        assert_eq!(panel.action_at(row).unwrap().command, "git commit -m \"Update new_file.rs\"");
+        assert_eq!(panel.action_at(row - 1), None);
+        assert_eq!(panel.action_at(row + 1), None);
+
+        // Nothing to suggest until the working tree status is in
+        let loading = [StateSection::Files];
+        let panel = RepositoryPanel::new(&state).with_loading(&loading);
+        assert!(!panel.build_content().iter().any(|l| l.to_string().starts_with("Next")));
+    }
+
+    #[test]
+    fn test_hidden_section_rows() {
+        let mut state = create_test_state();
+        state.hidden_files = crate::git::parse_hidden_files("h local.toml\0S .env\0");
+        let panel = RepositoryPanel::new(&state);
+        let lines: Vec<String> = panel.build_content().iter().map(|line| line.to_string()).collect();
+
+        let title = lines.iter().position(|l| l == "Hidden from git status (2)").unwrap();
+        assert_eq!(lines[title + 1], "  assume-unchanged: local.toml");
+        assert_eq!(panel.hidden_at(title), None);
+        assert_eq!(panel.hidden_at(title + 2).unwrap().path, ".env");
+        assert_eq!(panel.hidden_at(title + 3), None);
+
+        let filter = PanelFilter { query: "env".to_string(), editing: false };
+        let panel = RepositoryPanel::new(&state).with_filter(&filter);
+        let lines: Vec<String> = panel.build_content().iter().map(|line| line.to_string()).collect();
+        let title = lines.iter().position(|l| l == "Hidden from git status (1/2)").unwrap();
+        assert_eq!(panel.hidden_at(title + 1).unwrap().path, ".env");
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("pnl", "repo_panel.rs"), Some(vec![2, 7, 9]));
+        assert_eq!(fuzzy_match("README", "readme.md"), Some(vec![0, 1, 2, 3, 4, 5]));
+        assert_eq!(fuzzy_match("", "anything"), Some(Vec::new()));
+        assert_eq!(fuzzy_match("zz", "repo_panel.rs"), None);
+        assert_eq!(fuzzy_match("lp", "panel"), None); // order matters
+    }
+
+    #[test]
+    fn test_filter_narrows_sections_with_badges() {
+        let mut state = create_test_state();
+        state.unstaged_files.push(StatusEntry {
+            status: FileStatus::Modified,
+            path: "src/main.rs".to_string(),
+            staged: false,
+            unstaged: true,
+        });
+        let filter = PanelFilter {
+            query: "main".to_string(),
+            editing: false,
+        };
+
+        let panel = RepositoryPanel::new(&state).with_filter(&filter);
+        let text: Vec<String> = panel
+            .build_content()
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+
+        assert!(text.iter().any(|l| l == "Unstaged changes (1/2)"));
+        assert!(text.iter().any(|l| l.contains("src/main.rs")));
+        assert!(!text.iter().any(|l| l.contains("existing.rs")));
+        assert!(text.iter().any(|l| l == "Staged changes (0/1)"));
+        assert!(text.iter().any(|l| l == "Filter: /main"));
+    }
+
+    #[test]
+    fn test_filter_highlights_matches() {
+        let state = create_test_state();
+        let filter = PanelFilter {
+            query: "xst".to_string(),
+            editing: true,
+        };
+
+        let panel = RepositoryPanel::new(&state).with_filter(&filter);
+        let content = panel.build_content();
+        let highlighted: Vec<&str> = content
+            .iter()
+            .flat_map(|line| line.spans.iter())
+            .filter(|span| span.style.add_modifier.contains(Modifier::UNDERLINED))
+            .map(|span| span.content.as_ref())
+            .collect();
+
+        assert_eq!(highlighted, vec!["x", "s", "t"]);
+    }
+
+    fn state_with_untracked(count: usize) -> RepositoryState {
+        let mut state = create_test_state();
+        state.untracked_files = (0..count)
+            .map(|i| StatusEntry {
+                status: FileStatus::Untracked,
+                path: format!("file{:02}.txt", i),
+                staged: false,
+                unstaged: false,
+            })
+            .collect();
+        state
+    }
+
+    fn text(content: &[Line]) -> Vec<String> {
+        content.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_commit_rows_and_notes() {
+        let state = create_test_state();
+        let panel = RepositoryPanel::new(&state);
+        let lines = text(&panel.build_content());
+        let first = lines.iter().position(|l| l == "  abc123d Initial commit").unwrap();
+        assert_eq!(lines[first + 1], "  def456a ✎ Second commit");
+
+        assert_eq!(panel.commit_at(first).unwrap().hash, "abc123def456");
+        assert!(panel.commit_at(first + 1).unwrap().has_note);
+        assert_eq!(panel.commit_at(first - 1), None);
+        assert_eq!(panel.commit_at(first + 2), None);
+
+        // Badges only once signatures are checked
+        let mut state = create_test_state();
+        state.recent_commits[0].signature = Some(SignatureStatus::Good);
+        state.recent_commits[1].signature = Some(SignatureStatus::Bad);
+        let lines = text(&RepositoryPanel::new(&state).build_content());
+        assert_eq!(lines[first], "  abc123d ✓ Initial commit");
+        assert_eq!(lines[first + 1], "  def456a ✗ ✎ Second commit");
+    }
+
+    #[test]
+    fn test_expand_and_collapse_section() {
+        let state = state_with_untracked(14);
+        let mut view = PanelView::default();
+
+        let panel = RepositoryPanel::new(&state).with_view(&view);
+        let lines = text(&panel.build_content());
+        let more = lines.iter().position(|l| l == "  ... and 4 more").unwrap();
+        assert_eq!(panel.toggle_at(more), Some(PanelSection::Untracked));
+        assert_eq!(panel.toggle_at(more - 1), None);
+
+        view.toggle(PanelSection::Untracked);
+        let panel = RepositoryPanel::new(&state).with_view(&view);
+        let lines = text(&panel.build_content());
+        assert!(lines.iter().any(|l| l.ends_with("file13.txt")));
+        let less = lines.iter().position(|l| l == "  (show less)").unwrap();
+        assert_eq!(less, more + 4);
+        assert_eq!(panel.toggle_at(less), Some(PanelSection::Untracked));
+
+        view.toggle(PanelSection::Untracked);
+        assert!(!view.is_expanded(PanelSection::Untracked));
+    }
+
+    #[test]
+    fn test_view_clamps_cursor_and_scroll() {
+        let mut view = PanelView::default();
+        view.move_cursor(25, 30);
+        assert_eq!(view.cursor, 25);
+
+        // Scroll follows the cursor into a 10-line viewport
+        view.clamp(30, 10);
+        assert_eq!(view.scroll, 16);
+
+        // Content shrank after a refresh: both stay in range
+        view.clamp(12, 10);
+        assert_eq!(view.cursor, 11);
+        assert_eq!(view.scroll, 2);
+
+        view.move_cursor(-100, 12);
+        view.clamp(12, 10);
+        assert_eq!((view.cursor, view.scroll), (0, 0));
+    }
+
+    #[test]
+    fn test_render_scrolls_content() {
+        let state = state_with_untracked(3);
+        let view = PanelView {
+            scroll: 2,
+            ..PanelView::default()
+        };
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        RepositoryPanel::new(&state).with_view(&view).render(area, &mut buf);
+
+        // Line 2 of the content (blank after the header rule) is now the first visible row
+        let first_row: String = (1..39).map(|x| buf[(x, 1)].symbol().to_string()).collect();
+        assert!(first_row.trim().is_empty());
+        let second_row: String = (1..39).map(|x| buf[(x, 2)].symbol().to_string()).collect();
+        assert!(second_row.contains("Head:"));
     }
 }
@@ -0,0 +1,190 @@
+use crate::git::SandboxReport;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// Most lines shown of the command's own output and of each change list
+const MAX_SECTION_LINES: usize = 8;
+
+/// What a dry run in the sandbox copy changed, shown before running for real
+pub struct SandboxView {
+    report: SandboxReport,
+}
+
+impl SandboxView {
+    pub fn new(report: SandboxReport) -> Self {
+        SandboxView { report }
+    }
+
+    pub fn report(&self) -> &SandboxReport {
+        &self.report
+    }
+
+    pub fn desired_height(&self) -> u16 {
+        (self.lines().len() + 2).clamp(5, u16::MAX as usize) as u16
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let report = &self.report;
+        let heading = |text: &str| {
+            Line::from(Span::styled(
+                text.to_string(),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ))
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::styled("Tried on a copy: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(report.command.clone(), Style::default().fg(Color::Green)),
+        ])];
+
+        if !report.succeeded() {
+            lines.push(Line::from(Span::styled(
+                format!("✗ Failed in the sandbox (exit code {})", report.exit_code),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            push_limited(&mut lines, report.output.lines(), Color::Red);
+        }
+        if report.is_unchanged() {
+            lines.push(Line::from(Span::styled(
+                "No branches, tags or files would change",
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        if report.head_before != report.head_after {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("HEAD: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(format!("{} → {}", report.head_before, report.head_after)),
+            ]));
+        }
+
+        if !report.ref_changes.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(heading("Branches and tags:"));
+            let changes = report.ref_changes.iter().map(|change| {
+                let position = |commit: &Option<String>| commit.clone().unwrap_or_else(|| "(none)".to_string());
+                match (&change.before, &change.after) {
+                    (Some(_), None) => format!("{}: deleted (was {})", change.name, position(&change.before)),
+                    (None, Some(_)) => format!("{}: created at {}", change.name, position(&change.after)),
+                    _ => format!("{}: {} → {}", change.name, position(&change.before), position(&change.after)),
+                }
+            });
+            push_limited(&mut lines, changes, Color::Magenta);
+        }
+
+        if !report.commit_diff.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(heading("Files changed between the old and new HEAD:"));
+            push_limited(&mut lines, report.commit_diff.iter(), Color::White);
+        }
+
+        if !report.status_removed.is_empty() || !report.status_added.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(heading("Working tree:"));
+            let removed = report.status_removed.iter().map(|line| format!("- {}", line));
+            let added = report.status_added.iter().map(|line| format!("+ {}", line));
+            push_limited(&mut lines, removed.chain(added), Color::Yellow);
+        }
+        lines
+    }
+}
+
+/// Indented lines in `color`, with an "... and N more" line past the limit
+fn push_limited<T: ToString>(lines: &mut Vec<Line<'static>>, items: impl Iterator<Item = T>, color: Color) {
+    let items: Vec<String> = items.map(|item| item.to_string()).collect();
+    for item in items.iter().take(MAX_SECTION_LINES) {
+        lines.push(Line::from(Span::styled(format!("  {}", item), Style::default().fg(color))));
+    }
+    if items.len() > MAX_SECTION_LINES {
+        lines.push(Line::from(Span::styled(
+            format!("  ... and {} more", items.len() - MAX_SECTION_LINES),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+}
+
+impl Widget for &SandboxView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Magenta))
+            .title("Sandbox Dry Run");
+
+        Paragraph::new(self.lines())
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::RefChange;
+
+    fn report() -> SandboxReport {
+        SandboxReport {
+            command: "git reset --hard HEAD~1".to_string(),
+            exit_code: 0,
+            output: "HEAD is now at abc1234 a.txt\n".to_string(),
+            head_before: "main".to_string(),
+            head_after: "main".to_string(),
+            ref_changes: vec![
+                RefChange {
+                    name: "main".to_string(),
+                    before: Some("def5678".to_string()),
+                    after: Some("abc1234".to_string()),
+                },
+                RefChange {
+                    name: "old".to_string(),
+                    before: Some("abc1234".to_string()),
+                    after: None,
+                },
+            ],
+            commit_diff: vec![" b.txt | 1 -".to_string()],
+            status_removed: vec![" M a.txt".to_string()],
+            status_added: Vec::new(),
+        }
+    }
+
+    fn text(view: &SandboxView) -> Vec<String> {
+        view.lines().iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_lines() {
+        let lines = text(&SandboxView::new(report()));
+        assert!(lines.contains(&"  main: def5678 → abc1234".to_string()));
+        assert!(lines.contains(&"  old: deleted (was abc1234)".to_string()));
+        assert!(lines.contains(&"   b.txt | 1 -".to_string()));
+        assert!(lines.contains(&"  -  M a.txt".to_string()));
+        assert!(!lines.iter().any(|line| line.starts_with("HEAD:")));
+    }
+
+    #[test]
+    fn test_failed_and_unchanged() {
+        let mut report = report();
+        report.exit_code = 1;
+        report.output = "error: branch 'main' not found\n".to_string();
+        report.ref_changes.clear();
+        report.commit_diff.clear();
+        report.status_removed.clear();
+        let lines = text(&SandboxView::new(report));
+        assert_eq!(lines[1], "✗ Failed in the sandbox (exit code 1)");
+        assert_eq!(lines[2], "  error: branch 'main' not found");
+        assert_eq!(lines[3], "No branches, tags or files would change");
+    }
+
+    #[test]
+    fn test_long_sections_are_cut() {
+        let mut report = report();
+        report.status_added = (0..20).map(|i| format!("?? file{}.txt", i)).collect();
+        let lines = text(&SandboxView::new(report));
+        assert!(lines.contains(&"  ... and 13 more".to_string()));
+    }
+}
@@ -0,0 +1,80 @@
+use crossterm::{
+    event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Environment variable set inside the suspended shell so prompts can show it
+pub const SHELL_MARKER_ENV: &str = "GITALKY_SHELL";
+
+/// An interactive shell session run while the TUI was suspended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellSession {
+    pub shell: String,
+    pub exit_code: i32,
+    pub duration: Duration,
+}
+
+/// The user's shell: `$SHELL`, falling back to `/bin/sh`
+pub fn shell_program() -> String {
+    std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string())
+}
+
+/// Leave the TUI for the duration of `run`, then restore it
+///
+/// The terminal is restored whatever `run` returns; the caller should clear
+/// and redraw the screen afterwards.
+pub fn with_suspended_terminal<T>(run: impl FnOnce() -> T) -> io::Result<T> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), DisableFocusChange, DisableBracketedPaste, LeaveAlternateScreen)?;
+
+    let result = run();
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableFocusChange, EnableBracketedPaste)?;
+    Ok(result)
+}
+
+/// Leave the TUI, run an interactive shell in `dir`, then restore the TUI
+///
+/// The terminal is restored even when the shell fails to start; the caller
+/// should clear and redraw the screen afterwards.
+pub fn run_suspended_shell(dir: &Path) -> io::Result<ShellSession> {
+    let shell = shell_program();
+
+    let (status, duration) = with_suspended_terminal(|| {
+        println!("gitalky: starting {} in {}", shell, dir.display());
+        println!("gitalky: type 'exit' to return");
+
+        let started = Instant::now();
+        let status = Command::new(&shell)
+            .current_dir(dir)
+            .env(SHELL_MARKER_ENV, "1")
+            .status();
+        (status, started.elapsed())
+    })?;
+
+    let status = status?;
+    Ok(ShellSession {
+        shell,
+        exit_code: status.code().unwrap_or(-1),
+        duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_program_is_never_empty() {
+        assert!(!shell_program().trim().is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+use crate::git::RepositoryState;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+/// Most files shown at once; the list scrolls with the selection
+const VISIBLE_FILES: usize = 8;
+
+/// What the user asked for in the split panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SplitAction {
+    None,
+    Stage(String),
+    Unstage(String),
+    StageAll,
+    Commit(String),
+    Finish,
+    Abort,
+}
+
+/// A file left over from the commit being split
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitFile {
+    pub path: String,
+    /// Fully staged for the next commit
+    pub staged: bool,
+    /// Staged with further unstaged changes
+    pub partial: bool,
+}
+
+/// Staging panel for the commit-splitting flow
+pub struct SplitPanel {
+    subject: String,
+    commits_made: usize,
+    files: Vec<SplitFile>,
+    selected: usize,
+    /// Commit message being typed, if composing
+    message: Option<String>,
+    confirm_abort: bool,
+}
+
+impl SplitPanel {
+    pub fn new(subject: String) -> Self {
+        Self {
+            subject,
+            commits_made: 0,
+            files: Vec::new(),
+            selected: 0,
+            message: None,
+            confirm_abort: false,
+        }
+    }
+
+    /// Rebuild the file list from the repository state
+    pub fn sync(&mut self, state: &RepositoryState, commits_made: usize) {
+        self.commits_made = commits_made;
+
+        let mut files: Vec<SplitFile> = Vec::new();
+        for entry in &state.staged_files {
+            files.push(SplitFile {
+                path: entry.path.clone(),
+                staged: true,
+                partial: entry.unstaged,
+            });
+        }
+        for entry in state.unstaged_files.iter().chain(&state.untracked_files) {
+            if !files.iter().any(|f| f.path == entry.path) {
+                files.push(SplitFile {
+                    path: entry.path.clone(),
+                    staged: false,
+                    partial: false,
+                });
+            }
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        self.files = files;
+        self.selected = self.selected.min(self.files.len().saturating_sub(1));
+    }
+
+    pub fn files(&self) -> &[SplitFile] {
+        &self.files
+    }
+
+    pub fn staged_count(&self) -> usize {
+        self.files.iter().filter(|f| f.staged).count()
+    }
+
+    /// Whether a commit message is being typed
+    pub fn is_composing(&self) -> bool {
+        self.message.is_some()
+    }
+
+    pub fn is_confirming_abort(&self) -> bool {
+        self.confirm_abort
+    }
+
+    /// Height the panel wants in the layout
+    pub fn desired_height(&self) -> u16 {
+        // Borders, header, blank line, hint/prompt line and spacing
+        (self.files.len().clamp(1, VISIBLE_FILES) + 6) as u16
+    }
+
+    /// Translate a key press into an action
+    pub fn handle_key(&mut self, key: KeyEvent) -> SplitAction {
+        if self.confirm_abort {
+            self.confirm_abort = false;
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => SplitAction::Abort,
+                _ => SplitAction::None,
+            };
+        }
+
+        if let Some(ref mut message) = self.message {
+            match key.code {
+                KeyCode::Enter => {
+                    let message = message.clone();
+                    self.message = None;
+                    return SplitAction::Commit(message);
+                }
+                KeyCode::Esc => self.message = None,
+                KeyCode::Backspace => {
+                    message.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    message.push(c);
+                }
+                _ => {}
+            }
+            return SplitAction::None;
+        }
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < self.files.len() => {
+                self.selected += 1;
+            }
+            KeyCode::Char(' ') => {
+                if let Some(file) = self.files.get(self.selected) {
+                    return if file.staged {
+                        SplitAction::Unstage(file.path.clone())
+                    } else {
+                        SplitAction::Stage(file.path.clone())
+                    };
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => return SplitAction::StageAll,
+            KeyCode::Char('c') | KeyCode::Char('C') if self.staged_count() > 0 => {
+                self.message = Some(String::new());
+            }
+            KeyCode::Char('f') | KeyCode::Char('F') => return SplitAction::Finish,
+            KeyCode::Esc => self.confirm_abort = true,
+            _ => {}
+        }
+        SplitAction::None
+    }
+}
+
+impl Widget for &SplitPanel {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Splitting: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw(self.subject.as_str()),
+                Span::styled(
+                    format!("  ({} commit(s) made)", self.commits_made),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]),
+            Line::from(""),
+        ];
+
+        if self.files.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  All changes committed — press F to finish",
+                Style::default().fg(Color::Green),
+            )));
+        }
+
+        // Keep the selection visible
+        let start = self.selected.saturating_sub(VISIBLE_FILES - 1);
+        for (i, file) in self.files.iter().enumerate().skip(start).take(VISIBLE_FILES) {
+            let marker = if i == self.selected { "> " } else { "  " };
+            let (check, color) = match (file.staged, file.partial) {
+                (true, true) => ("[~] ", Color::Yellow),
+                (true, false) => ("[x] ", Color::Green),
+                _ => ("[ ] ", Color::Gray),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(marker, Style::default().fg(Color::Yellow)),
+                Span::styled(check, Style::default().fg(color)),
+                Span::styled(file.path.as_str(), Style::default().fg(color)),
+            ]));
+        }
+
+        lines.push(Line::from(""));
+        if self.confirm_abort {
+            lines.push(Line::from(Span::styled(
+                "Restore the original commit and discard the split? (y/N)",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+        } else if let Some(ref message) = self.message {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("Message for commit {}: ", self.commits_made + 1),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw(message.as_str()),
+                Span::styled("▊", Style::default().fg(Color::Yellow)),
+            ]));
+        } else {
+            lines.push(Line::from(Span::styled(
+                format!("{} of {} file(s) staged for the next commit", self.staged_count(), self.files.len()),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .block(Block::default().title("Split commit").borders(Borders::ALL))
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{FileStatus, StatusEntry};
+
+    fn entry(path: &str, staged: bool, unstaged: bool) -> StatusEntry {
+        StatusEntry {
+            status: FileStatus::Modified,
+            path: path.to_string(),
+            staged,
+            unstaged,
+        }
+    }
+
+    fn state() -> RepositoryState {
+        RepositoryState {
+            current_branch: Some("main".to_string()),
+            upstream: None,
+            staged_files: vec![entry("b.rs", true, true)],
+            unstaged_files: vec![entry("b.rs", true, true), entry("c.rs", false, true)],
+            untracked_files: vec![entry("a.rs", false, false)],
+            recent_commits: Vec::new(),
+            stashes: Vec::new(),
+            in_merge: false,
+            in_rebase: false,
+            rebase: None,
+            second_upstream: None,
+            user_name: None,
+            user_email: None,
+            shallow_depth: None,
+            hidden_files: Vec::new(),
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_sync_merges_file_lists() {
+        let mut panel = SplitPanel::new("Add things".to_string());
+        panel.sync(&state(), 1);
+
+        let paths: Vec<_> = panel.files().iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.rs", "b.rs", "c.rs"]);
+        assert!(panel.files()[1].staged && panel.files()[1].partial);
+        assert_eq!(panel.staged_count(), 1);
+    }
+
+    #[test]
+    fn test_space_toggles_selected_file() {
+        let mut panel = SplitPanel::new("Add things".to_string());
+        panel.sync(&state(), 0);
+
+        assert_eq!(panel.handle_key(key(KeyCode::Char(' '))), SplitAction::Stage("a.rs".to_string()));
+        panel.handle_key(key(KeyCode::Down));
+        assert_eq!(panel.handle_key(key(KeyCode::Char(' '))), SplitAction::Unstage("b.rs".to_string()));
+    }
+
+    #[test]
+    fn test_commit_message_flow() {
+        let mut panel = SplitPanel::new("Add things".to_string());
+        panel.sync(&state(), 0);
+
+        assert_eq!(panel.handle_key(key(KeyCode::Char('c'))), SplitAction::None);
+        assert!(panel.is_composing());
+        for c in "Fix b".chars() {
+            panel.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(panel.handle_key(key(KeyCode::Enter)), SplitAction::Commit("Fix b".to_string()));
+        assert!(!panel.is_composing());
+    }
+
+    #[test]
+    fn test_commit_needs_staged_files() {
+        let mut panel = SplitPanel::new("Add things".to_string());
+        let mut nothing_staged = state();
+        nothing_staged.staged_files.clear();
+        panel.sync(&nothing_staged, 0);
+
+        panel.handle_key(key(KeyCode::Char('c')));
+        assert!(!panel.is_composing());
+    }
+
+    #[test]
+    fn test_abort_requires_confirmation() {
+        let mut panel = SplitPanel::new("Add things".to_string());
+        panel.sync(&state(), 0);
+
+        panel.handle_key(key(KeyCode::Esc));
+        assert!(panel.is_confirming_abort());
+        assert_eq!(panel.handle_key(key(KeyCode::Char('n'))), SplitAction::None);
+        assert!(!panel.is_confirming_abort());
+
+        panel.handle_key(key(KeyCode::Esc));
+        assert_eq!(panel.handle_key(key(KeyCode::Char('y'))), SplitAction::Abort);
+    }
+}
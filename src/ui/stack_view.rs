@@ -0,0 +1,211 @@
+use crate::git::{BranchStack, StackBranch};
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Input that opens the branch stack view from the prompt
+pub const STACK_COMMAND: &str = ":stack";
+
+/// Full-screen tree of stacked local branches
+pub struct StackView {
+    pub visible: bool,
+    /// The stack, or why it couldn't be read
+    content: Result<BranchStack, String>,
+    /// Branch checked out when the view opened
+    current: Option<String>,
+}
+
+impl StackView {
+    pub fn new() -> Self {
+        StackView {
+            visible: false,
+            content: Err(String::new()),
+            current: None,
+        }
+    }
+
+    /// Show `stack`, or a message explaining why it is missing
+    pub fn show(&mut self, content: Result<BranchStack, String>, current: Option<String>) {
+        self.content = content;
+        self.current = current;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Rebases that restack the shown branches, returning to the current branch
+    pub fn restack_steps(&self) -> Vec<(String, String)> {
+        match &self.content {
+            Ok(stack) => stack.restack_steps(self.current.as_deref()),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let stack = match &self.content {
+            Ok(stack) => stack,
+            Err(message) => return vec![Line::from(message.clone())],
+        };
+        if stack.branches.is_empty() {
+            return vec![Line::from("No local branches besides the trunk.")];
+        }
+
+        let mut lines = Vec::new();
+        if let Some(ref trunk) = stack.trunk {
+            lines.push(Line::from(Span::styled(
+                trunk.clone(),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )));
+        }
+        for branch in &stack.branches {
+            lines.push(self.branch_line(branch, stack.trunk.is_some()));
+        }
+
+        lines.push(Line::from(""));
+        let restacks = stack.branches.iter().filter(|branch| branch.needs_restack()).count();
+        lines.push(if restacks == 0 {
+            Line::from(Span::styled(
+                "Every branch sits on top of its parent.",
+                Style::default().fg(Color::Green),
+            ))
+        } else {
+            Line::from(Span::styled(
+                format!("{} branch(es) fell behind their parent; press r to review a restack plan.", restacks),
+                Style::default().fg(Color::Yellow),
+            ))
+        });
+        lines.push(Line::from(Span::styled(
+            "Parents are guessed from history; `git branch -u <parent> <branch>` sets one explicitly.",
+            Style::default().fg(Color::DarkGray),
+        )));
+        lines
+    }
+
+    fn branch_line(&self, branch: &StackBranch, under_trunk: bool) -> Line<'static> {
+        let indent = if under_trunk || branch.depth > 0 {
+            format!("{}└─ ", "   ".repeat(branch.depth))
+        } else {
+            String::new()
+        };
+        let current = self.current.as_deref() == Some(branch.name.as_str());
+        let mut name_style = Style::default().fg(Color::White);
+        if current {
+            name_style = name_style.fg(Color::Green).add_modifier(Modifier::BOLD);
+        }
+
+        let mut spans = vec![
+            Span::styled(indent, Style::default().fg(Color::DarkGray)),
+            Span::styled(branch.name.clone(), name_style),
+        ];
+        if current {
+            spans.push(Span::styled(" (current)", Style::default().fg(Color::Green)));
+        }
+        let detail = match (&branch.parent, branch.ahead) {
+            (None, _) => "  no history shared with the other branches".to_string(),
+            (Some(_), 0) => "  no commits of its own".to_string(),
+            (Some(_), 1) => "  1 commit".to_string(),
+            (Some(_), ahead) => format!("  {} commits", ahead),
+        };
+        spans.push(Span::styled(detail, Style::default().fg(Color::DarkGray)));
+        if branch.follows_trunk && branch.parent_moved > 0 {
+            spans.push(Span::styled(
+                format!("  {} behind, pull to update", branch.parent_moved),
+                Style::default().fg(Color::DarkGray),
+            ));
+        } else if branch.needs_restack()
+            && let Some(ref parent) = branch.parent
+        {
+            spans.push(Span::styled(
+                format!("  ⚠ {} moved {} ahead, needs restack", parent, branch.parent_moved),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let hint = if self.restack_steps().is_empty() {
+            " Any key to close "
+        } else {
+            " r: review restack plan | any other key to close "
+        };
+        let block = Block::default()
+            .title(" Branch Stack ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines())
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+impl Default for StackView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn branch(name: &str, parent: &str, depth: usize, parent_moved: usize) -> StackBranch {
+        StackBranch {
+            name: name.to_string(),
+            parent: Some(parent.to_string()),
+            depth,
+            base: Some("0123456789abcdef".to_string()),
+            ahead: 2,
+            parent_moved,
+            follows_trunk: false,
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut view = StackView::new();
+        let stack = BranchStack {
+            trunk: Some("origin/main".to_string()),
+            branches: vec![branch("feature-a", "origin/main", 0, 3), branch("feature-b", "feature-a", 1, 0)],
+        };
+        view.show(Ok(stack), Some("feature-b".to_string()));
+
+        let text: Vec<String> = view.lines().iter().map(|line| line.to_string()).collect();
+        assert_eq!(text[0], "origin/main");
+        assert_eq!(text[1], "└─ feature-a  2 commits  ⚠ origin/main moved 3 ahead, needs restack");
+        assert_eq!(text[2], "   └─ feature-b (current)  2 commits");
+        assert!(text[4].starts_with("1 branch(es) fell behind"));
+
+        let steps = view.restack_steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].1, "git checkout feature-b");
+    }
+
+    #[test]
+    fn test_error_message() {
+        let mut view = StackView::new();
+        view.show(Err("Could not read branches".to_string()), None);
+        assert_eq!(view.lines()[0].to_string(), "Could not read branches");
+        assert!(view.restack_steps().is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::audit::UsageStats;
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Input that opens the local stats screen from the prompt
+pub const STATS_COMMAND: &str = ":stats";
+
+/// Rows shown per chart
+const MAX_BARS: usize = 8;
+
+/// Widest a bar label gets before it is cut
+const MAX_LABEL_WIDTH: usize = 28;
+
+/// Full-screen summary of the user's own usage, read from the local audit log
+pub struct StatsScreen {
+    pub visible: bool,
+    /// Stats to chart, or why there are none
+    content: Result<UsageStats, String>,
+}
+
+impl StatsScreen {
+    pub fn new() -> Self {
+        StatsScreen {
+            visible: false,
+            content: Ok(UsageStats::default()),
+        }
+    }
+
+    /// Show `stats`, or a message explaining why there are none
+    pub fn show(&mut self, content: Result<UsageStats, String>) {
+        self.content = content;
+        self.visible = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn lines(&self, width: usize) -> Vec<Line<'static>> {
+        let stats = match &self.content {
+            Ok(stats) if !stats.is_empty() => stats,
+            Ok(_) => return vec![Line::from("Nothing logged yet. Run a few commands and come back.")],
+            Err(message) => return vec![Line::from(message.clone())],
+        };
+
+        let mut lines = Vec::new();
+
+        lines.push(heading("Most used commands"));
+        lines.extend(bars(&stats.commands, width, Color::Green));
+        lines.push(Line::from(""));
+
+        lines.push(heading("Translations"));
+        match stats.acceptance_rate() {
+            Some(rate) => {
+                let total = stats.translations_executed + stats.translations_cancelled;
+                lines.push(Line::from(format!(
+                    "  {} of {} proposed commands run ({:.0}%)",
+                    stats.translations_executed,
+                    total,
+                    rate * 100.0
+                )));
+                lines.extend(bars(
+                    &[
+                        ("run".to_string(), stats.translations_executed),
+                        ("cancelled".to_string(), stats.translations_cancelled),
+                    ],
+                    width,
+                    Color::Cyan,
+                ));
+            }
+            None => lines.push(Line::from("  No translated commands yet")),
+        }
+        if stats.translations_rejected > 0 {
+            lines.push(Line::from(format!(
+                "  {} refused by the validator",
+                stats.translations_rejected
+            )));
+        }
+        if let Some(average) = stats.average_latency_ms() {
+            lines.push(Line::from(format!(
+                "  Average LLM latency: {} ms over {} translations",
+                average,
+                stats.latencies_ms.len()
+            )));
+        }
+        lines.push(Line::from(""));
+
+        if !stats.top_queries.is_empty() {
+            lines.push(heading("Most repeated queries"));
+            lines.extend(bars(&stats.top_queries, width, Color::Magenta));
+            lines.push(Line::from(""));
+        }
+
+        lines.push(heading("Errors by type"));
+        if stats.errors.is_empty() {
+            lines.push(Line::from("  No errors logged"));
+        } else {
+            lines.extend(bars(&stats.errors, width, Color::Red));
+        }
+
+        lines
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(" Local Usage Stats ")
+            .title_alignment(Alignment::Center)
+            .title_bottom(Line::from(Span::styled(
+                " Computed from your audit log and query history; nothing leaves this machine. Esc to close ",
+                Style::default().fg(Color::DarkGray),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let margin = Rect {
+            x: inner.x + 2,
+            y: inner.y + 1,
+            width: inner.width.saturating_sub(4),
+            height: inner.height.saturating_sub(2),
+        };
+        let paragraph = Paragraph::new(self.lines(margin.width as usize))
+            .style(Style::default().fg(Color::White))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, margin);
+    }
+}
+
+impl Default for StatsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn heading(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        text.to_string(),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// Horizontal bar chart, one row per entry, scaled to the largest count
+fn bars(entries: &[(String, usize)], width: usize, color: Color) -> Vec<Line<'static>> {
+    let shown = &entries[..entries.len().min(MAX_BARS)];
+    let max = shown.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+    let label_width = shown
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_LABEL_WIDTH);
+    let count_width = max.to_string().len();
+    // "  label  bar count"
+    let bar_width = width.saturating_sub(label_width + count_width + 5).max(1);
+
+    shown
+        .iter()
+        .map(|(label, count)| {
+            let label: String = label.chars().take(label_width).collect();
+            let filled = (count * bar_width).div_ceil(max);
+            Line::from(vec![
+                Span::raw(format!("  {:<label_width$}  ", label)),
+                Span::styled("█".repeat(filled), Style::default().fg(color)),
+                Span::raw(format!(" {}", count)),
+            ])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(screen: &StatsScreen) -> Vec<String> {
+        screen.lines(40).iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_bars_scale_to_largest() {
+        let entries = vec![("status".to_string(), 10), ("log".to_string(), 5)];
+        let lines: Vec<String> = bars(&entries, 30, Color::Green)
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        // 30 - (6 label + 2 count + 5) = 17 columns for the longest bar
+        assert_eq!(lines[0], format!("  status  {} 10", "█".repeat(17)));
+        assert_eq!(lines[1], format!("  log     {} 5", "█".repeat(9)));
+    }
+
+    #[test]
+    fn test_shows_message_instead_of_charts() {
+        let mut screen = StatsScreen::new();
+        screen.show(Err("Local stats are off".to_string()));
+        assert!(screen.visible);
+        assert_eq!(text(&screen), vec!["Local stats are off"]);
+
+        screen.hide();
+        assert!(!screen.visible);
+    }
+
+    #[test]
+    fn test_summarizes_stats() {
+        let mut screen = StatsScreen::new();
+        screen.show(Ok(UsageStats {
+            commands: vec![("status".to_string(), 3)],
+            translations_executed: 3,
+            translations_cancelled: 1,
+            translations_rejected: 2,
+            top_queries: vec![("sync with main".to_string(), 2)],
+            errors: Vec::new(),
+            latencies_ms: vec![500, 700],
+        }));
+
+        let text = text(&screen);
+        assert!(text.contains(&"  3 of 4 proposed commands run (75%)".to_string()));
+        assert!(text.contains(&"  2 refused by the validator".to_string()));
+        assert!(text.contains(&"Most repeated queries".to_string()));
+        assert!(text.contains(&"  Average LLM latency: 600 ms over 2 translations".to_string()));
+        assert!(text.contains(&"  No errors logged".to_string()));
+    }
+}
@@ -0,0 +1,132 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use std::time::{Duration, Instant};
+
+/// What the user decided while a translation was running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitChoice {
+    /// Hide the choices until another budget has passed
+    KeepWaiting,
+    /// Drop the request and go back to the prompt
+    Cancel,
+    /// Drop the request and type git commands directly
+    GoOffline,
+}
+
+/// Elapsed time and soft latency budget of an in-flight LLM call
+///
+/// Once the budget is spent the user is offered to keep waiting, cancel or
+/// switch to offline input; keeping waiting grants another budget.
+#[derive(Debug, Clone)]
+pub struct TranslationWait {
+    started: Instant,
+    budget: Duration,
+    /// Elapsed time at which the choices are shown
+    offer_at: Duration,
+}
+
+impl TranslationWait {
+    pub fn new(budget: Duration) -> Self {
+        Self::started_at(Instant::now(), budget)
+    }
+
+    pub fn started_at(started: Instant, budget: Duration) -> Self {
+        Self {
+            started,
+            budget,
+            offer_at: budget,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Whether the budget is spent and the choices are on screen
+    pub fn offering_choices(&self) -> bool {
+        self.elapsed() >= self.offer_at
+    }
+
+    /// Esc cancels at any time; the other choices only once offered
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<WaitChoice> {
+        match key.code {
+            KeyCode::Esc => Some(WaitChoice::Cancel),
+            _ if !self.offering_choices() => None,
+            KeyCode::Char('c') | KeyCode::Char('C') => Some(WaitChoice::Cancel),
+            KeyCode::Char('o') | KeyCode::Char('O') => Some(WaitChoice::GoOffline),
+            KeyCode::Char('w') | KeyCode::Char('W') | KeyCode::Enter => {
+                self.offer_at = self.elapsed() + self.budget;
+                Some(WaitChoice::KeepWaiting)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Widget for &TranslationWait {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let seconds = self.elapsed().as_secs_f32();
+        let line = if self.offering_choices() {
+            Line::from(vec![
+                Span::styled(
+                    format!("⏳ Still waiting for Claude ({:.0}s)  ", seconds),
+                    Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    "W: keep waiting | C: cancel | O: switch to offline input",
+                    Style::default().fg(Color::White),
+                ),
+            ])
+        } else {
+            Line::from(Span::styled(
+                format!("⏳ Translating with Claude... {:.1}s", seconds),
+                Style::default().fg(Color::Yellow),
+            ))
+        };
+
+        Paragraph::new(line)
+            .block(Block::default().borders(Borders::ALL))
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn started_ago(ago: Duration, budget: Duration) -> TranslationWait {
+        TranslationWait::started_at(Instant::now() - ago, budget)
+    }
+
+    #[test]
+    fn test_choices_only_after_budget() {
+        let mut wait = started_ago(Duration::from_secs(2), Duration::from_secs(8));
+        assert!(!wait.offering_choices());
+        assert_eq!(wait.handle_key(key(KeyCode::Char('o'))), None);
+        assert_eq!(wait.handle_key(key(KeyCode::Esc)), Some(WaitChoice::Cancel));
+
+        let mut wait = started_ago(Duration::from_secs(9), Duration::from_secs(8));
+        assert!(wait.offering_choices());
+        assert_eq!(wait.handle_key(key(KeyCode::Char('o'))), Some(WaitChoice::GoOffline));
+        assert_eq!(wait.handle_key(key(KeyCode::Char('c'))), Some(WaitChoice::Cancel));
+    }
+
+    #[test]
+    fn test_keep_waiting_grants_another_budget() {
+        let mut wait = started_ago(Duration::from_secs(9), Duration::from_secs(8));
+        assert_eq!(wait.handle_key(key(KeyCode::Char('w'))), Some(WaitChoice::KeepWaiting));
+        assert!(!wait.offering_choices());
+        assert_eq!(wait.handle_key(key(KeyCode::Char('o'))), None);
+    }
+}
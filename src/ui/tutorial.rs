@@ -0,0 +1,298 @@
+use crate::error::GitResult;
+use crate::git::{DemoRepo, Repository};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+/// Input that starts the tutorial from the prompt
+pub const TUTORIAL_COMMAND: &str = ":tutorial";
+
+/// Where the user is in the scripted tutorial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    Welcome,
+    /// A file was changed for the user; ask gitalky to stage it
+    StageChange,
+    /// A command is (or was just) in the preview
+    ReviewPreview,
+    Commit,
+    /// Run `reset --hard HEAD~1` through the confirmation prompt
+    Dangerous,
+    /// Bring the reset commit back
+    Undo,
+    Done,
+}
+
+impl TutorialStep {
+    const ALL: [TutorialStep; 7] = [
+        TutorialStep::Welcome,
+        TutorialStep::StageChange,
+        TutorialStep::ReviewPreview,
+        TutorialStep::Commit,
+        TutorialStep::Dangerous,
+        TutorialStep::Undo,
+        TutorialStep::Done,
+    ];
+
+    fn number(self) -> usize {
+        Self::ALL.iter().position(|s| *s == self).unwrap_or(0) + 1
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            TutorialStep::Welcome => "Welcome",
+            TutorialStep::StageChange => "Ask for something",
+            TutorialStep::ReviewPreview => "Review before running",
+            TutorialStep::Commit => "Commit",
+            TutorialStep::Dangerous => "Dangerous commands",
+            TutorialStep::Undo => "Undo",
+            TutorialStep::Done => "Done",
+        }
+    }
+
+    fn instructions(self) -> Vec<String> {
+        let lines: &[&str] = match self {
+            TutorialStep::Welcome => &[
+                "This tutorial runs in a throwaway demo repository, so nothing you do here touches your projects.",
+                "Press Enter to start, Esc to leave the tutorial at any time.",
+            ],
+            TutorialStep::StageChange => &[
+                "We edited notes.txt for you: it is listed under Unstaged changes above.",
+                "Ask gitalky to stage it: type \"stage notes.txt\" and press Enter.",
+                "(Offline? Type git add notes.txt instead.)",
+            ],
+            TutorialStep::ReviewPreview => &[
+                "Nothing has run yet: the preview shows the exact git command first.",
+                "Enter runs it, E edits it, X explains it, Esc cancels.",
+            ],
+            TutorialStep::Commit => &[
+                "notes.txt is staged. Now commit it:",
+                "type \"commit with message Update notes\" and run the command.",
+            ],
+            TutorialStep::Dangerous => &[
+                "Now a destructive command. Type: git reset --hard HEAD~1",
+                "Gitalky stops and asks you to confirm before it runs. Go ahead, it's a demo.",
+            ],
+            TutorialStep::Undo => &[
+                "Your commit is gone from the branch, but git still remembers it.",
+                "Undo the reset: type git reset --hard ORIG_HEAD (or ask \"undo the last reset\").",
+            ],
+            TutorialStep::Done => &[
+                "You asked for a change, reviewed and ran it, survived a destructive command and undid it.",
+                "Press Enter to return to your repository. Type :tutorial to run this again.",
+            ],
+        };
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+}
+
+/// Guided walk-through in a demo repository
+///
+/// Steps advance by watching the demo repository (what is staged, where
+/// HEAD is) rather than the exact commands typed, so any command that gets
+/// the job done counts.
+#[derive(Debug)]
+pub struct Tutorial {
+    demo: DemoRepo,
+    step: TutorialStep,
+    /// HEAD of the demo history before the user's commit
+    base_head: String,
+    /// The user's commit, once made
+    committed_head: Option<String>,
+}
+
+impl Tutorial {
+    /// Create a demo repository and start at the welcome step
+    pub fn start() -> GitResult<Self> {
+        Self::with_demo(DemoRepo::create()?)
+    }
+
+    pub fn with_demo(demo: DemoRepo) -> GitResult<Self> {
+        let base_head = head(&demo.repository())?;
+        Ok(Self {
+            demo,
+            step: TutorialStep::Welcome,
+            base_head,
+            committed_head: None,
+        })
+    }
+
+    pub fn step(&self) -> TutorialStep {
+        self.step
+    }
+
+    pub fn repository(&self) -> Repository {
+        self.demo.repository()
+    }
+
+    /// Whether the current step is waiting for Enter rather than a command
+    pub fn waits_for_enter(&self) -> bool {
+        matches!(self.step, TutorialStep::Welcome | TutorialStep::Done)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.step == TutorialStep::Done
+    }
+
+    /// Enter on the welcome step: make the scripted change and move on
+    pub fn proceed(&mut self) -> GitResult<()> {
+        if self.step == TutorialStep::Welcome {
+            self.demo.make_change()?;
+            self.step = TutorialStep::StageChange;
+        }
+        Ok(())
+    }
+
+    /// Check the demo repository after the app changed state
+    ///
+    /// `previewing` is true while a command waits in the preview or the
+    /// confirmation prompt. Returns true when the step advanced.
+    pub fn observe(&mut self, previewing: bool) -> bool {
+        let repo = self.demo.repository();
+        let Ok(head) = head(&repo) else {
+            return false;
+        };
+        let staged = repo.state().map(|s| !s.staged_files.is_empty()).unwrap_or(false);
+
+        let next = match self.step {
+            TutorialStep::StageChange | TutorialStep::ReviewPreview | TutorialStep::Commit
+                if head != self.base_head =>
+            {
+                self.committed_head = Some(head);
+                TutorialStep::Dangerous
+            }
+            TutorialStep::StageChange | TutorialStep::ReviewPreview if staged => TutorialStep::Commit,
+            TutorialStep::StageChange if previewing => TutorialStep::ReviewPreview,
+            // Cancelled, or ran something that didn't stage the file
+            TutorialStep::ReviewPreview if !previewing => TutorialStep::StageChange,
+            TutorialStep::Dangerous if head == self.base_head => TutorialStep::Undo,
+            TutorialStep::Undo if self.committed_head.as_deref() == Some(head.as_str()) => {
+                TutorialStep::Done
+            }
+            step => step,
+        };
+
+        let advanced = next != self.step;
+        self.step = next;
+        advanced
+    }
+
+    /// Height of the overlay box, including borders
+    pub fn desired_height(&self, width: u16) -> u16 {
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let lines: usize = self
+            .step
+            .instructions()
+            .iter()
+            .map(|line| line.chars().count().div_ceil(inner).max(1))
+            .sum();
+        (lines + 2) as u16
+    }
+}
+
+fn head(repo: &Repository) -> GitResult<String> {
+    Ok(repo.executor().execute("rev-parse HEAD")?.stdout.trim().to_string())
+}
+
+impl Widget for &Tutorial {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let text = Style::default().fg(Color::White);
+        let lines: Vec<Line> = self
+            .step
+            .instructions()
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line, text)))
+            .collect();
+
+        let title = format!(
+            " Tutorial {}/{}: {} ",
+            self.step.number(),
+            TutorialStep::ALL.len(),
+            self.step.title()
+        );
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow))
+            .title(Span::styled(
+                title,
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ));
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::DEMO_CHANGED_FILE;
+    use tempfile::TempDir;
+
+    fn tutorial(temp: &TempDir) -> Tutorial {
+        Tutorial::with_demo(DemoRepo::create_in(temp.path()).unwrap()).unwrap()
+    }
+
+    fn git(tutorial: &Tutorial, command: &str) {
+        tutorial.repository().executor().execute(command).unwrap();
+    }
+
+    #[test]
+    fn test_full_walkthrough() {
+        let temp = TempDir::new().unwrap();
+        let mut tutorial = tutorial(&temp);
+        assert!(tutorial.waits_for_enter());
+
+        tutorial.proceed().unwrap();
+        assert_eq!(tutorial.step(), TutorialStep::StageChange);
+        let state = tutorial.repository().state().unwrap();
+        assert_eq!(state.unstaged_files[0].path, DEMO_CHANGED_FILE);
+
+        assert!(tutorial.observe(true));
+        assert_eq!(tutorial.step(), TutorialStep::ReviewPreview);
+
+        git(&tutorial, "add notes.txt");
+        tutorial.observe(false);
+        assert_eq!(tutorial.step(), TutorialStep::Commit);
+
+        git(&tutorial, "commit -q -m 'Update notes'");
+        tutorial.observe(false);
+        assert_eq!(tutorial.step(), TutorialStep::Dangerous);
+
+        git(&tutorial, "reset -q --hard HEAD~1");
+        tutorial.observe(false);
+        assert_eq!(tutorial.step(), TutorialStep::Undo);
+
+        git(&tutorial, "reset -q --hard ORIG_HEAD");
+        tutorial.observe(false);
+        assert!(tutorial.is_finished());
+        assert!(tutorial.waits_for_enter());
+    }
+
+    #[test]
+    fn test_cancelled_preview_goes_back() {
+        let temp = TempDir::new().unwrap();
+        let mut tutorial = tutorial(&temp);
+        tutorial.proceed().unwrap();
+
+        tutorial.observe(true);
+        assert_eq!(tutorial.step(), TutorialStep::ReviewPreview);
+        assert!(tutorial.observe(false));
+        assert_eq!(tutorial.step(), TutorialStep::StageChange);
+    }
+
+    #[test]
+    fn test_overlay_height_grows_when_narrow() {
+        let temp = TempDir::new().unwrap();
+        let tutorial = tutorial(&temp);
+        assert!(tutorial.desired_height(40) > tutorial.desired_height(200));
+        assert_eq!(tutorial.desired_height(400), 4);
+    }
+}
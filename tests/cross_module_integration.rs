@@ -114,7 +114,7 @@ fn test_token_estimation_realistic() {
     // Test with realistic context strings
     let small_context = "Current branch: main\n";
     let tokens = ContextBuilder::estimate_tokens(small_context);
-    assert!(tokens >= 4 && tokens <= 10); // ~6 words
+    assert!((4..=10).contains(&tokens)); // ~6 words
 
     let medium_context = r#"
 Current branch: main
@@ -127,7 +127,7 @@ Staged files:
   src/lib.rs
 "#;
     let tokens = ContextBuilder::estimate_tokens(medium_context);
-    assert!(tokens >= 20 && tokens <= 60); // Reasonable range
+    assert!((20..=60).contains(&tokens)); // Reasonable range
 }
 
 /// Test error translation with multiple error types
@@ -1,6 +1,6 @@
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
+use gitalky::git::git_command;
 use tempfile::TempDir;
 
 /// Helper to create a test git repository
@@ -9,20 +9,20 @@ pub fn create_test_repo() -> (TempDir, PathBuf) {
     let repo_path = temp_dir.path().to_path_buf();
 
     // Initialize git repo
-    Command::new("git")
+    git_command()
         .args(["init"])
         .current_dir(&repo_path)
         .output()
         .expect("Failed to init git repo");
 
     // Configure git
-    Command::new("git")
+    git_command()
         .args(["config", "user.name", "Test User"])
         .current_dir(&repo_path)
         .output()
         .expect("Failed to set git user.name");
 
-    Command::new("git")
+    git_command()
         .args(["config", "user.email", "test@example.com"])
         .current_dir(&repo_path)
         .output()
@@ -36,13 +36,13 @@ pub fn create_commit(repo_path: &PathBuf, file: &str, content: &str, message: &s
     let file_path = repo_path.join(file);
     fs::write(&file_path, content).expect("Failed to write file");
 
-    Command::new("git")
+    git_command()
         .args(["add", file])
         .current_dir(repo_path)
         .output()
         .expect("Failed to add file");
 
-    Command::new("git")
+    git_command()
         .args(["commit", "-m", message])
         .current_dir(repo_path)
         .output()
@@ -0,0 +1,175 @@
+// Property tests for the parsers and the command validator
+// Malformed git output and adversarial command strings must never panic,
+// and nothing the validator accepts may reach a shell as more than one command
+
+use gitalky::git::parser::{parse_log, parse_status_porcelain_v2};
+use gitalky::git::{quote_arg, split_command};
+use gitalky::security::{CommandOrigin, CommandValidator, Workspace, ALLOWED_GIT_SUBCOMMANDS};
+use proptest::prelude::*;
+
+/// Shell syntax that would chain, redirect or substitute commands
+const SHELL_METACHARACTERS: &[&str] = &[";", "|", ">", "<", "`", "$(", "\n", "\r"];
+
+fn validator() -> CommandValidator {
+    CommandValidator::new().with_workspace(Workspace::new("/home/me/src/project", Vec::new()))
+}
+
+/// Whether `command` has a lone `&` (backgrounding), as opposed to the `&&`
+/// chains the validator checks part by part
+fn has_lone_ampersand(command: &str) -> bool {
+    command.replace("&&", "").contains('&')
+}
+
+/// Lines shaped like `git status --porcelain=v2` records, with arbitrary fields
+fn porcelain_line() -> impl Strategy<Value = String> {
+    let field = "[^\n]{0,12}";
+    prop_oneof![
+        ("[12u?#]", prop::collection::vec(field, 0..12)).prop_map(|(kind, fields)| {
+            std::iter::once(kind).chain(fields).collect::<Vec<_>>().join(" ")
+        }),
+        "[^\n]{0,80}",
+    ]
+}
+
+/// Command strings built from git-ish words and shell syntax, mostly
+/// starting like a real command so they get past the subcommand check
+fn command_like() -> impl Strategy<Value = String> {
+    let word = prop_oneof![
+        Just("git".to_string()),
+        prop::sample::select(ALLOWED_GIT_SUBCOMMANDS).prop_map(str::to_string),
+        Just("--force".to_string()),
+        Just("-c".to_string()),
+        Just("rm".to_string()),
+        prop::sample::select(vec![";", "|", "||", "&", "&&", ">", "<", "`", "$(", ")", "\n", "'", "\""])
+            .prop_map(str::to_string),
+        "[a-zA-Z0-9_./=-]{1,10}",
+    ];
+    let start = prop_oneof![
+        3 => prop::sample::select(ALLOWED_GIT_SUBCOMMANDS).prop_map(|sub| format!("git {}", sub)),
+        1 => Just(String::new()),
+    ];
+    (start, prop::collection::vec(word, 1..10))
+        .prop_map(|(start, words)| std::iter::once(start).chain(words).collect::<Vec<_>>().join(" "))
+}
+
+/// Words that decide whether and how a command is dangerous
+const DANGER_WORDS: &[&str] = &[
+    "push", "reset", "clean", "branch", "stash", "reflog", "gc", "switch", "restore", "checkout",
+    "rebase", "maintenance", "run", "--force", "-f", "--hard", "-fdx", "-D", "-d", "drop", "clear",
+    "expire", "--prune=now", "--discard-changes", "--staged", "--worktree", "-SW", "--mirror",
+    "--continue", "--task=gc", "origin", "main", "+main", "HEAD~1", ".",
+];
+
+/// A word spelled the way a shell would still read it: as-is, quoted, or
+/// with a backslash before one of its characters
+fn spelled(word: &'static str) -> impl Strategy<Value = String> {
+    (0..4u8, 0..word.len()).prop_map(move |(how, at)| match how {
+        0 => word.to_string(),
+        1 => format!("'{}'", word),
+        2 => format!("\"{}\"", word),
+        _ => format!("{}\\{}", &word[..at], &word[at..]),
+    })
+}
+
+/// `git` commands of danger words, each spelled some way
+fn spelled_command() -> impl Strategy<Value = String> {
+    let word = prop::sample::select(DANGER_WORDS).prop_flat_map(spelled);
+    prop::collection::vec(word, 1..6).prop_map(|words| format!("git {}", words.join(" ")))
+}
+
+/// Each `&&` part split the way the executor splits it, then quoted back
+fn requoted(command: &str) -> Option<String> {
+    let parts: Option<Vec<String>> = command
+        .split("&&")
+        .map(|part| {
+            let words = split_command(part).ok()?;
+            Some(words.iter().map(|word| quote_arg(word)).collect::<Vec<_>>().join(" "))
+        })
+        .collect();
+    parts.map(|parts| parts.join(" && "))
+}
+
+proptest! {
+    #[test]
+    fn status_parser_never_panics(output in "\\PC*") {
+        let _ = parse_status_porcelain_v2(&output);
+    }
+
+    #[test]
+    fn status_parser_handles_malformed_records(lines in prop::collection::vec(porcelain_line(), 0..20)) {
+        let entries = parse_status_porcelain_v2(&lines.join("\n")).unwrap();
+        prop_assert!(entries.len() <= lines.len());
+    }
+
+    #[test]
+    fn log_parser_never_panics(output in "\\PC*") {
+        let commits = parse_log(&output).unwrap();
+        prop_assert!(commits.len() <= output.lines().count());
+    }
+
+    #[test]
+    fn split_command_never_panics(command in "\\PC*") {
+        let _ = split_command(&command);
+    }
+
+    #[test]
+    fn quoted_arguments_split_back_unchanged(args in prop::collection::vec("\\PC*", 1..6)) {
+        let command = args.iter().map(|arg| quote_arg(arg)).collect::<Vec<_>>().join(" ");
+        prop_assert_eq!(split_command(&command).unwrap(), args);
+    }
+
+    #[test]
+    fn validator_never_panics(command in "\\PC*") {
+        let validator = validator();
+        let _ = validator.validate(&command);
+        let _ = validator.validate_with_origin(&command, CommandOrigin::Llm);
+    }
+
+    #[test]
+    fn validated_commands_contain_no_shell_metacharacters(
+        command in prop_oneof![command_like(), "git [^\\x00]{0,40}"]
+    ) {
+        if let Ok(validated) = validator().validate(&command) {
+            for metacharacter in SHELL_METACHARACTERS {
+                prop_assert!(
+                    !validated.command.contains(metacharacter),
+                    "accepted {:?} containing {:?}", validated.command, metacharacter
+                );
+            }
+            prop_assert!(!has_lone_ampersand(&validated.command), "accepted {:?}", validated.command);
+        }
+    }
+
+    #[test]
+    fn danger_is_classified_on_the_executed_arguments(
+        command in prop_oneof![spelled_command(), command_like()]
+    ) {
+        let Some(plain) = requoted(&command) else {
+            return Ok(());
+        };
+        let validator = validator();
+        let classify = |command: &str| validator.validate(command).map(|validated| validated.danger_type).ok();
+        // The raw text may still be refused outright (a newline, say); what's accepted is
+        // classified the same as its plain spelling
+        if let Some(danger) = classify(&command) {
+            prop_assert_eq!(Some(danger), classify(&plain), "{:?} vs {:?}", command, plain);
+        }
+    }
+
+    #[test]
+    fn validated_chains_run_only_allowed_subcommands(command in command_like()) {
+        if let Ok(validated) = validator().validate(&command) {
+            for part in validated.command.split("&&") {
+                let words = split_command(part).unwrap();
+                let subcommand = words
+                    .iter()
+                    .skip_while(|word| *word == "git")
+                    .find(|word| !word.starts_with('-'));
+                prop_assert!(
+                    subcommand.is_some_and(|sub| ALLOWED_GIT_SUBCOMMANDS.contains(&sub.as_str())),
+                    "accepted {:?}", validated.command
+                );
+            }
+        }
+    }
+}
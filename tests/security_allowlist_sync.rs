@@ -79,19 +79,19 @@ async fn test_llm_validation_uses_shared_allowlist() {
 
     Command::new("git")
         .args(["init"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
     Command::new("git")
         .args(["config", "user.name", "Test"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
     Command::new("git")
         .args(["config", "user.email", "test@example.com"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
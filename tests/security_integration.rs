@@ -18,20 +18,20 @@ fn create_test_repo() -> (TempDir, Repository) {
     // Initialize git repo
     Command::new("git")
         .args(["init"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
     // Configure git
     Command::new("git")
         .args(["config", "user.name", "Test User"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
     Command::new("git")
         .args(["config", "user.email", "test@example.com"])
-        .current_dir(&repo_path)
+        .current_dir(repo_path)
         .output()
         .unwrap();
 
@@ -266,7 +266,7 @@ fn test_allowlist_blocks_disallowed_subcommands() {
     // These should be rejected (not in allowlist)
     let disallowed = vec![
         "git rm -rf /",
-        "git gc --prune=now",
+        "git prune --expire=now",
         "git daemon",
         "git update-server-info",
     ];
@@ -275,6 +275,10 @@ fn test_allowlist_blocks_disallowed_subcommands() {
         let result = validator.validate(cmd);
         assert!(result.is_err(), "Should reject disallowed command: '{}'", cmd);
     }
+
+    // gc is allowed, but only behind the dangerous-op confirmation
+    let validated = validator.validate("git gc --prune=now").unwrap();
+    assert!(validated.is_dangerous);
 }
 
 #[test]
@@ -310,6 +314,8 @@ fn test_dangerous_operations_detected_across_layers() {
         ("git reset --hard HEAD~1", "HardReset"),
         ("git clean -fd", "Clean"),
         ("git checkout --force main", "ForceCheckout"),
+        ("git switch --discard-changes main", "ForceSwitch"),
+        ("git restore --staged --worktree .", "RestoreWorktree"),
         ("git branch -D old-feature", "DeleteBranch"),
         ("git rebase main", "Rebase"),
     ];